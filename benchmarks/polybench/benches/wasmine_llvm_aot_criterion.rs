@@ -38,11 +38,17 @@ pub fn wasmine_llvm_aot_criterion(c: &mut Criterion) {
 
                         let module = Parser::parse_from_buf(wasm_bytes).unwrap();
                         llvm_gen::Translator::translate_module_meta(&module).unwrap();
-                        llvm_gen::FunctionLoader
+                        llvm_gen::FunctionLoader::default()
                             .parse_all_functions(&module)
                             .unwrap();
                         let module = Rc::new(module);
-                        llvm_gen::aot::store_aot_module(module, &compiled_file_path).unwrap()
+                        llvm_gen::aot::store_aot_module(
+                            module,
+                            &compiled_file_path,
+                            &llvm_gen::OptLevel::default(),
+                            &llvm_gen::CompileTarget::host(),
+                        )
+                        .unwrap()
                     },
                     BatchSize::SmallInput,
                 )
@@ -53,7 +53,7 @@ fn teardown_for_compile(path: PathBuf) {
 pub fn wasmine_llvm_aot_compile(wasm_bytes: Vec<u8>) -> PathBuf {
     let module = Parser::parse_from_buf(wasm_bytes).unwrap();
     llvm_gen::Translator::translate_module_meta(&module).unwrap();
-    llvm_gen::FunctionLoader
+    llvm_gen::FunctionLoader::default()
         .parse_all_functions(&module)
         .unwrap();
     let module = Rc::new(module);
@@ -61,7 +61,13 @@ pub fn wasmine_llvm_aot_compile(wasm_bytes: Vec<u8>) -> PathBuf {
         .unwrap()
         .into_temp_path()
         .with_extension("cwasm");
-    llvm_gen::aot::store_aot_module(module, &compiled_file_path).unwrap();
+    llvm_gen::aot::store_aot_module(
+        module,
+        &compiled_file_path,
+        &llvm_gen::OptLevel::default(),
+        &llvm_gen::CompileTarget::host(),
+    )
+    .unwrap();
 
     compiled_file_path
 }
@@ -73,7 +79,7 @@ fn setup_for_execute(bm: &str) -> PathBuf {
 
     let module = Parser::parse_from_buf(wasm_bytes).unwrap();
     llvm_gen::Translator::translate_module_meta(&module).unwrap();
-    llvm_gen::FunctionLoader
+    llvm_gen::FunctionLoader::default()
         .parse_all_functions(&module)
         .unwrap();
     let module = Rc::new(module);
@@ -81,7 +87,13 @@ fn setup_for_execute(bm: &str) -> PathBuf {
         .unwrap()
         .into_temp_path()
         .with_extension("cwasm");
-    llvm_gen::aot::store_aot_module(module, &compiled_file_path).unwrap();
+    llvm_gen::aot::store_aot_module(
+        module,
+        &compiled_file_path,
+        &llvm_gen::OptLevel::default(),
+        &llvm_gen::CompileTarget::host(),
+    )
+    .unwrap();
     compiled_file_path
 }
 
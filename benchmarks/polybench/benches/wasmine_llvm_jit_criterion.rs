@@ -30,7 +30,7 @@ pub fn wasmine_llvm_jit_criterion(c: &mut Criterion) {
 
                         let module = Parser::parse_from_buf(wasm_bytes).unwrap();
                         llvm_gen::Translator::translate_module_meta(&module).unwrap();
-                        llvm_gen::FunctionLoader
+                        llvm_gen::FunctionLoader::default()
                             .parse_all_functions(&module)
                             .unwrap();
                         let module = Rc::new(module);
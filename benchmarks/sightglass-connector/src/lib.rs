@@ -289,7 +289,9 @@ impl<'a> BenchState<'a> {
         (self.compilation_start)(self.compilation_timer);
         let module = Parser::parse_from_buf(bytes.to_vec()).unwrap();
         Translator::translate_module_meta(&module).unwrap();
-        module.load_all_functions(llvm_gen::FunctionLoader).unwrap();
+        module
+            .load_all_functions(llvm_gen::FunctionLoader::default())
+            .unwrap();
         (self.compilation_end)(self.compilation_timer);
 
         self.module = Some(Rc::new(module));
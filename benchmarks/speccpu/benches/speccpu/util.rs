@@ -95,7 +95,9 @@ mod wasmine {
                 |wasm_bytes| {
                     let module = Parser::parse_from_buf(wasm_bytes).unwrap();
                     let module = Rc::new(module);
-                    module.load_all_functions(FunctionLoader).unwrap();
+                    module
+                        .load_all_functions(FunctionLoader::default())
+                        .unwrap();
                     module
                 },
                 BatchSize::SmallInput,
@@ -121,7 +123,7 @@ mod wasmine {
                     let module = Parser::parse_from_buf(wasm_bytes).unwrap();
                     let module = Rc::new(module);
                     llvm_gen::Translator::translate_module_meta(&module).unwrap();
-                    llvm_gen::FunctionLoader
+                    llvm_gen::FunctionLoader::default()
                         .parse_all_functions(&module)
                         .unwrap();
 
@@ -169,7 +171,7 @@ mod wasmine {
         let module = Parser::parse_from_buf(wasm_bytes).unwrap();
         let module = Rc::new(module);
         llvm_gen::Translator::translate_module_meta(&module).unwrap();
-        llvm_gen::FunctionLoader
+        llvm_gen::FunctionLoader::default()
             .parse_all_functions(&module)
             .unwrap();
 
@@ -230,11 +232,17 @@ mod wasmine {
 
                         let module = Parser::parse_from_buf(wasm_bytes).unwrap();
                         llvm_gen::Translator::translate_module_meta(&module).unwrap();
-                        llvm_gen::FunctionLoader
+                        llvm_gen::FunctionLoader::default()
                             .parse_all_functions(&module)
                             .unwrap();
                         let module = Rc::new(module);
-                        llvm_gen::aot::store_aot_module(module, &compiled_file_path).unwrap()
+                        llvm_gen::aot::store_aot_module(
+                            module,
+                            &compiled_file_path,
+                            &llvm_gen::OptLevel::default(),
+                            &llvm_gen::CompileTarget::host(),
+                        )
+                        .unwrap()
                     },
                     BatchSize::SmallInput,
                 )
@@ -300,7 +308,9 @@ mod wasmine {
         let wasm_bytes = std::fs::read(wasm_file_path).unwrap();
         let module = Parser::parse_from_buf(wasm_bytes).unwrap();
         let module = Rc::new(module);
-        module.load_all_functions(FunctionLoader).unwrap();
+        module
+            .load_all_functions(FunctionLoader::default())
+            .unwrap();
         module
     }
 }
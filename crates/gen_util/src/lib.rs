@@ -20,19 +20,31 @@ impl Parse for GenMacroInput {
     }
 }
 
+/// Generates a test case for every `.wast` file of the spec tests, including the SIMD tests in their `simd`
+/// subdirectory, and of the repository's own wast tests in `tests/fixtures/wast`, which cover proposals the pinned
+/// spec tests don't.
 #[proc_macro]
 pub fn generate_spec_test_cases(input: TokenStream) -> TokenStream {
-    let test_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap())
-        .join("thirdparty/spec/test/core");
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let test_dir = manifest_dir.join("thirdparty/spec/test/core");
     if !test_dir.is_dir() {
         panic!("Spec test directory {} not found. Try running `git submodule update --init --recursive` to fetch the spec tests.", test_dir.display());
     }
+    let fixture_dir = manifest_dir.join("tests/fixtures/wast");
 
     let mut test_cases = quote! {};
     let test_function = syn::parse_macro_input!(input as GenMacroInput);
 
-    // Iterate over files in the test directory
-    if let Ok(entries) = fs::read_dir(test_dir) {
+    let simd_test_dir = test_dir.join("simd");
+    for (dir, prefix) in [
+        (test_dir, "spec_test"),
+        (simd_test_dir, "spec_simd_test"),
+        (fixture_dir, "fixture_test"),
+    ] {
+        // Iterate over files in the test directory
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
         for entry in entries {
             match entry {
                 Ok(entry)
@@ -48,8 +60,12 @@ pub fn generate_spec_test_cases(input: TokenStream) -> TokenStream {
                             .clone()
                             .into_token_stream()
                             .to_string();
-                        let test_name =
-                            format_ident!("spec_test_{}_{}", s, file_name.replace(['.', '-'], "_"));
+                        let test_name = format_ident!(
+                            "{}_{}_{}",
+                            prefix,
+                            s,
+                            file_name.replace(['.', '-'], "_")
+                        );
                         let file_path = entry.path().to_str().unwrap().to_string();
                         let test_function_name = &test_function.test_function_name;
                         test_cases = quote! {
@@ -0,0 +1,20 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayCopyInstruction;
+
+impl Executable for ArrayCopyInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let dst = stack_frame.vars.get(self.dst).as_u64();
+        let dst_offset = stack_frame.vars.get(self.dst_offset).as_u32();
+        let src = stack_frame.vars.get(self.src).as_u64();
+        let src_offset = stack_frame.vars.get(self.src_offset).as_u32();
+        let len = stack_frame.vars.get(self.len).as_u32();
+
+        unsafe {
+            runtime_interface::gc_array_copy(ctx.exec_ctx, dst, dst_offset, src, src_offset, len)
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayFillInstruction;
+
+impl Executable for ArrayFillInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let array = stack_frame.vars.get(self.array).as_u64();
+        let offset = stack_frame.vars.get(self.offset).as_u32();
+        let value = stack_frame.vars.get(self.value).as_u64();
+        let len = stack_frame.vars.get(self.len).as_u32();
+
+        unsafe { runtime_interface::gc_array_fill(ctx.exec_ctx, array, offset, value, len) };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayGetInstruction;
+
+impl Executable for ArrayGetInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let array = stack_frame.vars.get(self.array).as_u64();
+        let idx = stack_frame.vars.get(self.idx).as_u32();
+
+        let res = unsafe {
+            runtime_interface::gc_array_get(ctx.exec_ctx, array, idx, self.signed as u32)
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::{ArrayInitSegmentInstruction, ArraySegment};
+
+impl Executable for ArrayInitSegmentInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let array = stack_frame.vars.get(self.array).as_u64();
+        let dst_offset = stack_frame.vars.get(self.dst_offset).as_u32();
+        let src_offset = stack_frame.vars.get(self.src_offset).as_u32();
+        let len = stack_frame.vars.get(self.len).as_u32();
+
+        unsafe {
+            match self.segment {
+                ArraySegment::Data(data_idx) => runtime_interface::gc_array_init_data(
+                    ctx.exec_ctx,
+                    array,
+                    data_idx,
+                    dst_offset,
+                    src_offset,
+                    len,
+                ),
+                ArraySegment::Elem(elem_idx) => runtime_interface::gc_array_init_elem(
+                    ctx.exec_ctx,
+                    array,
+                    elem_idx,
+                    dst_offset,
+                    src_offset,
+                    len,
+                ),
+            }
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,17 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayLenInstruction;
+
+impl Executable for ArrayLenInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let array = stack_frame.vars.get(self.in1).as_u64();
+
+        let res = unsafe { runtime_interface::gc_array_len(ctx.exec_ctx, array) };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
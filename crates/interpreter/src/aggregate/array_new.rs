@@ -0,0 +1,25 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayNewInstruction;
+
+impl Executable for ArrayNewInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let len = stack_frame.vars.get(self.len).as_u32();
+        let init = self.init.map(|init| stack_frame.vars.get(init).as_u64());
+
+        let res = unsafe {
+            match init {
+                Some(init) => {
+                    runtime_interface::gc_array_new(ctx.exec_ctx, self.type_idx, init, len)
+                }
+                None => runtime_interface::gc_array_new_default(ctx.exec_ctx, self.type_idx, len),
+            }
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArrayNewFixedInstruction;
+
+impl Executable for ArrayNewFixedInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let values = self
+            .values
+            .iter()
+            .map(|value| stack_frame.vars.get(*value).as_u64())
+            .collect::<Vec<_>>();
+
+        let res = unsafe {
+            runtime_interface::gc_array_new_fixed(
+                ctx.exec_ctx,
+                self.type_idx,
+                values.as_ptr(),
+                values.len() as u32,
+            )
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
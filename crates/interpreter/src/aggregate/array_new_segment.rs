@@ -0,0 +1,35 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::{ArrayNewSegmentInstruction, ArraySegment};
+
+impl Executable for ArrayNewSegmentInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let offset = stack_frame.vars.get(self.offset).as_u32();
+        let len = stack_frame.vars.get(self.len).as_u32();
+
+        let res = unsafe {
+            match self.segment {
+                ArraySegment::Data(data_idx) => runtime_interface::gc_array_new_data(
+                    ctx.exec_ctx,
+                    self.type_idx,
+                    data_idx,
+                    offset,
+                    len,
+                ),
+                ArraySegment::Elem(elem_idx) => runtime_interface::gc_array_new_elem(
+                    ctx.exec_ctx,
+                    self.type_idx,
+                    elem_idx,
+                    offset,
+                    len,
+                ),
+            }
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
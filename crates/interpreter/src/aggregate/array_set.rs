@@ -0,0 +1,16 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ArraySetInstruction;
+
+impl Executable for ArraySetInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let array = stack_frame.vars.get(self.array).as_u64();
+        let idx = stack_frame.vars.get(self.idx).as_u32();
+        let value = stack_frame.vars.get(self.value).as_u64();
+
+        unsafe { runtime_interface::gc_array_set(ctx.exec_ctx, array, idx, value) };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::{
+    ArrayCopyInstruction, ArrayFillInstruction, ArrayGetInstruction, ArrayInitSegmentInstruction,
+    ArrayLenInstruction, ArrayNewFixedInstruction, ArrayNewInstruction, ArrayNewSegmentInstruction,
+    ArraySetInstruction, Instruction, StructGetInstruction, StructNewInstruction,
+    StructSetInstruction,
+};
+use wasm_types::{AggregateInstructionCategory, InstructionType};
+
+mod array_copy;
+mod array_fill;
+mod array_get;
+mod array_init;
+mod array_len;
+mod array_new;
+mod array_new_fixed;
+mod array_new_segment;
+mod array_set;
+mod struct_get;
+mod struct_new;
+mod struct_set;
+
+pub(crate) fn execute_aggregate_instruction(
+    ctx: &mut InterpreterContext,
+    instruction_category: AggregateInstructionCategory,
+    t: InstructionType,
+) -> Result<(), InterpreterError> {
+    let i = &mut ctx.stack.last_mut().unwrap().decoder;
+
+    match instruction_category {
+        AggregateInstructionCategory::StructNew => {
+            StructNewInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::StructGet => {
+            StructGetInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::StructSet => {
+            StructSetInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayNew => {
+            ArrayNewInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayNewFixed => {
+            ArrayNewFixedInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayNewData | AggregateInstructionCategory::ArrayNewElem => {
+            ArrayNewSegmentInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayGet => {
+            ArrayGetInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArraySet => {
+            ArraySetInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayLen => {
+            ArrayLenInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayFill => {
+            ArrayFillInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayCopy => {
+            ArrayCopyInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        AggregateInstructionCategory::ArrayInitData
+        | AggregateInstructionCategory::ArrayInitElem => {
+            ArrayInitSegmentInstruction::deserialize(i, t)?.execute(ctx)
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::StructGetInstruction;
+
+impl Executable for StructGetInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let obj = stack_frame.vars.get(self.in1).as_u64();
+
+        let res = unsafe {
+            runtime_interface::gc_struct_get(ctx.exec_ctx, obj, self.field_idx, self.signed as u32)
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
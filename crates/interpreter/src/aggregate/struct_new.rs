@@ -0,0 +1,28 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::StructNewInstruction;
+use std::ptr;
+
+impl Executable for StructNewInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let fields = self
+            .fields
+            .iter()
+            .map(|field| stack_frame.vars.get(*field).as_u64())
+            .collect::<Vec<_>>();
+        // struct.new_default carries no field operands
+        let fields_ptr = match self.fields.is_empty() {
+            true => ptr::null(),
+            false => fields.as_ptr(),
+        };
+
+        let res =
+            unsafe { runtime_interface::gc_struct_new(ctx.exec_ctx, self.type_idx, fields_ptr) };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, res.into());
+
+        Ok(())
+    }
+}
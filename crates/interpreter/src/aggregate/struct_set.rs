@@ -0,0 +1,15 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::StructSetInstruction;
+
+impl Executable for StructSetInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let obj = stack_frame.vars.get(self.in1).as_u64();
+        let value = stack_frame.vars.get(self.in2).as_u64();
+
+        unsafe { runtime_interface::gc_struct_set(ctx.exec_ctx, obj, self.field_idx, value) };
+
+        Ok(())
+    }
+}
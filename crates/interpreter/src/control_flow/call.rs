@@ -10,13 +10,15 @@ pub(super) fn handle_call(
     return_bb: BasicBlockID,
     call_params: &[VariableID],
     return_vars: &[VariableID],
+    unwind_bb: Option<BasicBlockID>,
 ) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
     log::trace!("Handling Call");
-    Ok(call_util(
+    call_util(
         ctx,
         func_idx,
         call_params,
         return_bb,
         return_vars,
-    ))
+        unwind_bb,
+    )
 }
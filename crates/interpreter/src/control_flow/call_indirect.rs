@@ -1,7 +1,7 @@
 use crate::{control_flow::call::handle_call, InterpreterContext, InterpreterError};
 use log;
 use module::{basic_block::BasicBlockID, instructions::VariableID, objects::value::ValueRaw};
-use wasm_types::{TableIdx, TypeIdx};
+use wasm_types::{FuncIdx, TableIdx, TypeIdx};
 
 pub(super) fn handle_call_indirect(
     ctx: &mut InterpreterContext,
@@ -11,8 +11,20 @@ pub(super) fn handle_call_indirect(
     return_bb: BasicBlockID,
     call_params: &[VariableID],
     return_vars: &[VariableID],
+    unwind_bb: Option<BasicBlockID>,
 ) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
     log::trace!("Handling call indirect");
+    let fn_idx = resolve_indirect_callee(ctx, type_idx, selector_var, table_idx);
+    handle_call(ctx, fn_idx, return_bb, call_params, return_vars, unwind_bb)
+}
+
+/// Looks up the function selected by `selector_var` in the table, trapping on a type mismatch.
+pub(super) fn resolve_indirect_callee(
+    ctx: &mut InterpreterContext,
+    type_idx: TypeIdx,
+    selector_var: VariableID,
+    table_idx: TableIdx,
+) -> FuncIdx {
     let selector = ctx.stack.last().unwrap().vars.get(selector_var).as_u32();
 
     let fn_ptr =
@@ -26,5 +38,5 @@ pub(super) fn handle_call_indirect(
 
     log::trace!("Indirect call to function idx: {}", fn_idx);
 
-    handle_call(ctx, fn_idx, return_bb, call_params, return_vars)
+    fn_idx
 }
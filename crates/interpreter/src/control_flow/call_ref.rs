@@ -0,0 +1,47 @@
+use crate::{
+    control_flow::{call::handle_call, return_call::handle_return_call},
+    InterpreterContext, InterpreterError,
+};
+use log;
+use module::{
+    basic_block::BasicBlockID,
+    instructions::VariableID,
+    objects::value::{Reference, Value, ValueRaw},
+};
+use wasm_types::FuncIdx;
+
+pub(super) fn handle_call_ref(
+    ctx: &mut InterpreterContext,
+    selector_var: VariableID,
+    return_bb: BasicBlockID,
+    call_params: &[VariableID],
+    return_vars: &[VariableID],
+    unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling call ref");
+    let fn_idx = resolve_ref_callee(ctx, selector_var)?;
+    handle_call(ctx, fn_idx, return_bb, call_params, return_vars, unwind_bb)
+}
+
+pub(super) fn handle_return_call_ref(
+    ctx: &mut InterpreterContext,
+    selector_var: VariableID,
+    call_params: &[VariableID],
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling return call ref");
+    let fn_idx = resolve_ref_callee(ctx, selector_var)?;
+    handle_return_call(ctx, fn_idx, call_params)
+}
+
+/// Function references are function indices of the current module, trapping on null.
+fn resolve_ref_callee(
+    ctx: &mut InterpreterContext,
+    selector_var: VariableID,
+) -> Result<FuncIdx, InterpreterError> {
+    let func_ref = ctx.stack.last().unwrap().vars.get(selector_var);
+    if func_ref.as_u32() == ValueRaw::from(Value::Reference(Reference::Null)).as_u32() {
+        return Err(InterpreterError::NullReference);
+    }
+    log::trace!("Call ref to function idx: {}", func_ref.as_funcref());
+    Ok(func_ref.as_funcref())
+}
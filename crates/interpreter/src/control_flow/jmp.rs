@@ -5,6 +5,6 @@ pub(super) fn handle_jmp(
     ctx: &mut InterpreterContext,
     target: BasicBlockID,
 ) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
-    break_util(ctx, target);
+    break_util(ctx, target)?;
     Ok(None)
 }
@@ -14,6 +14,6 @@ pub(super) fn handle_jmp_cond(
     } else {
         target_if_false
     };
-    break_util(ctx, target);
+    break_util(ctx, target)?;
     Ok(None)
 }
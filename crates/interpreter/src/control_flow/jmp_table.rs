@@ -14,6 +14,6 @@ pub(super) fn handle_jmp_table(
     } else {
         default_target
     };
-    break_util(ctx, target);
+    break_util(ctx, target)?;
     Ok(None)
 }
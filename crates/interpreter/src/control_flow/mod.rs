@@ -1,5 +1,6 @@
 use call::handle_call;
 use call_indirect::handle_call_indirect;
+use call_ref::{handle_call_ref, handle_return_call_ref};
 use jmp::handle_jmp;
 use jmp_cond::handle_jmp_cond;
 use jmp_table::handle_jmp_table;
@@ -12,15 +13,20 @@ use module::{
     },
 };
 use r#return::handle_return;
+use return_call::{handle_return_call, handle_return_call_indirect};
+use throw::{handle_catch_dispatch, handle_throw, handle_throw_ref};
 
 use crate::{InterpreterContext, InterpreterError, InterpreterFunc};
 
 mod call;
 mod call_indirect;
+mod call_ref;
 mod jmp;
 mod jmp_cond;
 mod jmp_table;
 mod r#return;
+mod return_call;
+mod throw;
 pub(super) mod util;
 
 pub(super) trait GlueHandler {
@@ -55,7 +61,15 @@ impl GlueHandler for BasicBlockGlue {
                 return_bb,
                 call_params,
                 return_vars,
-            } => handle_call(ctx, *func_idx, *return_bb, call_params, return_vars),
+                unwind_bb,
+            } => handle_call(
+                ctx,
+                *func_idx,
+                *return_bb,
+                call_params,
+                return_vars,
+                *unwind_bb,
+            ),
             BasicBlockGlue::CallIndirect {
                 type_idx,
                 selector_var,
@@ -63,6 +77,7 @@ impl GlueHandler for BasicBlockGlue {
                 return_bb,
                 call_params,
                 return_vars,
+                unwind_bb,
             } => handle_call_indirect(
                 ctx,
                 *type_idx,
@@ -71,8 +86,53 @@ impl GlueHandler for BasicBlockGlue {
                 *return_bb,
                 call_params,
                 return_vars,
+                *unwind_bb,
+            ),
+            BasicBlockGlue::CallRef {
+                selector_var,
+                return_bb,
+                call_params,
+                return_vars,
+                unwind_bb,
+                ..
+            } => handle_call_ref(
+                ctx,
+                *selector_var,
+                *return_bb,
+                call_params,
+                return_vars,
+                *unwind_bb,
             ),
             BasicBlockGlue::Return { return_vars } => return handle_return(ctx, return_vars),
+            BasicBlockGlue::ReturnCall {
+                func_idx,
+                call_params,
+            } => handle_return_call(ctx, *func_idx, call_params),
+            BasicBlockGlue::ReturnCallIndirect {
+                type_idx,
+                selector_var,
+                table_idx,
+                call_params,
+            } => {
+                handle_return_call_indirect(ctx, *type_idx, *selector_var, *table_idx, call_params)
+            }
+            BasicBlockGlue::ReturnCallRef {
+                selector_var,
+                call_params,
+                ..
+            } => handle_return_call_ref(ctx, *selector_var, call_params),
+            BasicBlockGlue::Throw {
+                tag_idx,
+                values,
+                unwind_bb,
+            } => handle_throw(ctx, *tag_idx, values, *unwind_bb),
+            BasicBlockGlue::ThrowRef { exn_var, unwind_bb } => {
+                handle_throw_ref(ctx, *exn_var, *unwind_bb)
+            }
+            BasicBlockGlue::CatchDispatch {
+                handlers,
+                unwind_bb,
+            } => handle_catch_dispatch(ctx, handlers, *unwind_bb),
             BasicBlockGlue::ElseMarker { .. } => Ok(None), // no-op
             BasicBlockGlue::Unreachable => Err(InterpreterError::Unreachable),
         };
@@ -88,8 +148,6 @@ impl GlueHandler for BasicBlockGlue {
         // Here we have just started a new basic block. Resolve PhiNodes in its inputs.
 
         let func: Result<_, InterpreterError> = {
-            let ir: &Vec<FunctionIR> = &ctx.ir;
-
             let fn_meta = match (&ctx).module.meta.functions.get(fn_idx as usize) {
                 Some(meta) => meta,
                 None => return Err(InterpreterError::FunctionNotFound(fn_idx)),
@@ -99,10 +157,10 @@ impl GlueHandler for BasicBlockGlue {
                 FunctionSource::Import(FunctionImport { import_idx }) => {
                     Ok(InterpreterFunc::Import(*import_idx))
                 }
-                FunctionSource::Wasm(_) => Ok(InterpreterFunc::IR(&ir[fn_idx as usize])),
+                FunctionSource::Wasm(_) => Ok(InterpreterFunc::IR(ctx.ir.executing(fn_idx)?)),
             }
         };
-        let fn_ir = match func.unwrap() {
+        let fn_ir = match func? {
             InterpreterFunc::IR(function_ir) => function_ir,
             InterpreterFunc::Import(_) => unreachable!(),
         };
@@ -116,20 +174,27 @@ impl GlueHandler for BasicBlockGlue {
             .unwrap_or_else(|| panic!("Basic block with ID {} not found", stack_frame.bb_id));
 
         log::trace!("Resolving PhiNodes in inputs: {:?}", bb.inputs);
-        for phi_node in &bb.inputs {
-            log::trace!("Resolving PhiNode: {:?}", phi_node);
-            let (_, var_idx) = *phi_node
-                .inputs
-                .iter()
-                .find(|(bb, _)| stack_frame.last_bb_id == *bb)
-                .unwrap_or_else(|| {
-                    panic!(
-                        "PhiNode {:?} has no input for bb {}",
-                        phi_node, stack_frame.last_bb_id
-                    )
-                });
-
-            let value = stack_frame.vars.get(var_idx);
+        // all inputs are read before any output is written, as inputs may be outputs of other phis of
+        // the block (e.g. values swapped on a loop's back edge)
+        let values = bb
+            .inputs
+            .iter()
+            .map(|phi_node| {
+                log::trace!("Resolving PhiNode: {:?}", phi_node);
+                let (_, var_idx) = *phi_node
+                    .inputs
+                    .iter()
+                    .find(|(bb, _)| stack_frame.last_bb_id == *bb)
+                    .unwrap_or_else(|| {
+                        panic!(
+                            "PhiNode {:?} has no input for bb {}",
+                            phi_node, stack_frame.last_bb_id
+                        )
+                    });
+                stack_frame.vars.get(var_idx)
+            })
+            .collect::<Vec<_>>();
+        for (phi_node, value) in bb.inputs.iter().zip(values) {
             stack_frame.vars.set(phi_node.out, value);
         }
 
@@ -13,20 +13,30 @@ pub(super) fn handle_return(
     ctx: &mut InterpreterContext,
     return_vars: &[VariableID],
 ) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
-    let old_stack_frame = ctx.stack.pop().unwrap();
-    ctx.exec_ctx.recursion_size.saturating_sub(1);
-
-    log::trace!("old stack frame: {:#?}", &old_stack_frame);
-    log::trace!("current stack: {:#?}", &ctx.stack);
+    let stack_frame = ctx.stack.last().unwrap();
 
     let mut return_values = Vec::new();
     let mut sorted_ret_ids = return_vars.to_vec();
     sorted_ret_ids.sort();
 
     for var_id in sorted_ret_ids {
-        return_values.push(old_stack_frame.vars.get(var_id));
+        return_values.push(stack_frame.vars.get(var_id));
     }
 
+    return_util(ctx, return_values)
+}
+
+/// Pops the current stack frame and hands `return_values` over to the caller.
+pub(super) fn return_util(
+    ctx: &mut InterpreterContext,
+    return_values: Vec<ValueRaw>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    let old_stack_frame = ctx.stack.pop().unwrap();
+    ctx.exec_ctx.recursion_size.saturating_sub(1);
+
+    log::trace!("old stack frame: {:#?}", &old_stack_frame);
+    log::trace!("current stack: {:#?}", &ctx.stack);
+
     // create a new decoder with the return_bb
     let fn_idx = match ctx.stack.last_mut() {
         Some(sf) => sf,
@@ -35,8 +45,6 @@ pub(super) fn handle_return(
     .fn_idx;
 
     let func = {
-        let ir: &Vec<FunctionIR> = &ctx.ir;
-
         let fn_meta = match (&ctx).module.meta.functions.get(fn_idx as usize) {
             Some(meta) => meta,
             None => return Err(InterpreterError::FunctionNotFound(fn_idx)),
@@ -46,7 +54,7 @@ pub(super) fn handle_return(
             FunctionSource::Import(FunctionImport { import_idx }) => {
                 InterpreterFunc::Import(*import_idx)
             }
-            FunctionSource::Wasm(_) => InterpreterFunc::IR(&ir[fn_idx as usize]),
+            FunctionSource::Wasm(_) => InterpreterFunc::IR(ctx.ir.executing(fn_idx)?),
         }
     };
 
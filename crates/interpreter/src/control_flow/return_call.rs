@@ -0,0 +1,72 @@
+use super::{
+    call_indirect::resolve_indirect_callee,
+    r#return::return_util,
+    util::{call_import_helper, unwind_util},
+};
+use crate::{InterpreterContext, InterpreterError, VariableStore};
+use module::{
+    instructions::VariableID,
+    objects::{
+        function::{FunctionImport, FunctionSource},
+        value::ValueRaw,
+    },
+    InstructionDecoder,
+};
+use wasm_types::{FuncIdx, TableIdx, TypeIdx};
+
+pub(super) fn handle_return_call(
+    ctx: &mut InterpreterContext,
+    func_idx: FuncIdx,
+    call_params: &[VariableID],
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling return call");
+    let stack_frame = ctx.stack.last().unwrap();
+    let params: Vec<ValueRaw> = call_params
+        .iter()
+        .map(|&param| stack_frame.vars.get(param))
+        .collect();
+
+    let import_idx = match ctx.module.meta.functions.get(func_idx as usize) {
+        Some(meta) => match &meta.source {
+            FunctionSource::Import(FunctionImport { import_idx }) => Some(*import_idx),
+            FunctionSource::Wasm(_) => None,
+        },
+        None => return Err(InterpreterError::FunctionNotFound(func_idx)),
+    };
+
+    if let Some(import_idx) = import_idx {
+        // imports run on the native stack, so the current function returns their results right away
+        let return_values = unsafe { call_import_helper(ctx, import_idx as usize, &params) };
+        if unsafe { runtime_interface::exception_pending(ctx.exec_ctx) } != 0 {
+            return unwind_util(ctx, None);
+        }
+        return return_util(ctx, return_values);
+    }
+
+    // replace the current stack frame by the callee's. The return variables are kept, so the callee
+    // returns directly to our caller and the interpreter stack doesn't grow.
+    let ir = ctx.ir.clone();
+    let callee = ir.get(&ctx.module, func_idx)?;
+    let entry_bb = &callee.bbs[0];
+    let stack_frame = ctx.stack.last_mut().unwrap();
+    stack_frame.fn_idx = func_idx;
+    stack_frame.fn_local_vars = VariableStore::with_locals(params, &callee.locals);
+    stack_frame.vars = VariableStore::new(Vec::new());
+    stack_frame.bb_id = entry_bb.id;
+    stack_frame.last_bb_id = 0;
+    stack_frame.decoder = InstructionDecoder::new(entry_bb.instructions.clone());
+
+    Ok(None)
+}
+
+pub(super) fn handle_return_call_indirect(
+    ctx: &mut InterpreterContext,
+    type_idx: TypeIdx,
+    selector_var: VariableID,
+    table_idx: TableIdx,
+    call_params: &[VariableID],
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling return call indirect");
+    let fn_idx = resolve_indirect_callee(ctx, type_idx, selector_var, table_idx);
+    handle_return_call(ctx, fn_idx, call_params)
+}
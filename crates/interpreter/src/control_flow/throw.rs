@@ -0,0 +1,73 @@
+use super::util::{break_util, unwind_util};
+use crate::{InterpreterContext, InterpreterError};
+use module::{
+    basic_block::{BasicBlockID, CatchHandler},
+    instructions::VariableID,
+    objects::value::ValueRaw,
+};
+use wasm_types::TagIdx;
+
+pub(super) fn handle_throw(
+    ctx: &mut InterpreterContext,
+    tag_idx: TagIdx,
+    values: &[VariableID],
+    unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling throw");
+    let stack_frame = ctx.stack.last().unwrap();
+    let payload: Vec<ValueRaw> = values
+        .iter()
+        .map(|&var| stack_frame.vars.get(var))
+        .collect();
+    unsafe { runtime_interface::exception_throw(ctx.exec_ctx, tag_idx, payload.as_ptr()) };
+    unwind_util(ctx, unwind_bb)
+}
+
+pub(super) fn handle_throw_ref(
+    ctx: &mut InterpreterContext,
+    exn_var: VariableID,
+    unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling throw_ref");
+    let exn = ctx.stack.last().unwrap().vars.get(exn_var).as_u64();
+    // traps on a null reference
+    unsafe { runtime_interface::exception_throw_ref(ctx.exec_ctx, exn) };
+    unwind_util(ctx, unwind_bb)
+}
+
+/// Catches the pending exception with the first matching handler, or keeps unwinding if none matches.
+pub(super) fn handle_catch_dispatch(
+    ctx: &mut InterpreterContext,
+    handlers: &[CatchHandler],
+    unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    log::trace!("Handling catch dispatch");
+    let handler = handlers.iter().find(|handler| match handler.tag_idx {
+        Some(tag_idx) => unsafe {
+            runtime_interface::exception_matches(ctx.exec_ctx, tag_idx) != 0
+        },
+        None => true,
+    });
+    let Some(handler) = handler else {
+        return unwind_util(ctx, unwind_bb);
+    };
+
+    let mut payload = vec![ValueRaw::u64(0); handler.payload_vars.len()];
+    // catch_all handlers don't receive the payload
+    let payload_out = match handler.tag_idx {
+        Some(_) => payload.as_mut_ptr(),
+        None => std::ptr::null_mut(),
+    };
+    let exn = unsafe { runtime_interface::exception_catch(ctx.exec_ctx, payload_out) };
+
+    let stack_frame = ctx.stack.last_mut().unwrap();
+    for (&var, &value) in handler.payload_vars.iter().zip(payload.iter()) {
+        stack_frame.vars.set(var, value);
+    }
+    if let Some(exn_var) = handler.exn_var {
+        stack_frame.vars.set(exn_var, ValueRaw::u64(exn));
+    }
+
+    break_util(ctx, handler.target)?;
+    Ok(None)
+}
@@ -12,11 +12,12 @@ use module::{
 use runtime_interface::ExecutionContext;
 use wasm_types::{FuncIdx, ImportDesc};
 
-pub(super) fn break_util(ctx: &mut InterpreterContext, target: BasicBlockID) {
+pub(super) fn break_util(
+    ctx: &mut InterpreterContext,
+    target: BasicBlockID,
+) -> Result<(), InterpreterError> {
     let function_idx = ctx.stack.last_mut().unwrap().fn_idx;
     let current_func = {
-        let ir: &Vec<FunctionIR> = &ctx.ir;
-
         let fn_meta = match (&ctx).module.meta.functions.get(function_idx as usize) {
             Some(meta) => meta,
             None => unreachable!(),
@@ -26,7 +27,7 @@ pub(super) fn break_util(ctx: &mut InterpreterContext, target: BasicBlockID) {
             FunctionSource::Import(FunctionImport { import_idx }) => {
                 InterpreterFunc::Import(*import_idx)
             }
-            FunctionSource::Wasm(_) => InterpreterFunc::IR(&ir[function_idx as usize]),
+            FunctionSource::Wasm(_) => InterpreterFunc::IR(ctx.ir.executing(function_idx)?),
         }
     };
     let stack_frame = ctx.stack.last_mut().unwrap();
@@ -49,6 +50,26 @@ pub(super) fn break_util(ctx: &mut InterpreterContext, target: BasicBlockID) {
     let instrs = basic_block.instructions.clone(); //slow
 
     stack_frame.decoder = InstructionDecoder::new(instrs);
+    Ok(())
+}
+
+/// Continues a pending exception at `unwind_bb`. Without one, frames are popped until a caller has an unwind
+/// block, or the interpreter stack is empty and the exception is left pending for the caller of the interpreter.
+pub(super) fn unwind_util(
+    ctx: &mut InterpreterContext,
+    mut unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
+    loop {
+        if let Some(target) = unwind_bb {
+            break_util(ctx, target)?;
+            return Ok(None);
+        }
+        ctx.stack.pop();
+        unwind_bb = match ctx.stack.last() {
+            Some(stack_frame) => stack_frame.unwind_bb,
+            None => return Ok(Some(Vec::new())),
+        };
+    }
 }
 
 pub(crate) fn call_util(
@@ -57,15 +78,14 @@ pub(crate) fn call_util(
     call_params: &[VariableID],
     return_bb: BasicBlockID,
     return_vars: &[VariableID],
-) -> Option<Vec<ValueRaw>> {
+    unwind_bb: Option<BasicBlockID>,
+) -> Result<Option<Vec<ValueRaw>>, InterpreterError> {
     // not the best design but only imported functions can and must return values here.
     let func = &ctx.module.meta.functions[func_idx as usize];
 
     log::trace!("Calling function: {:#?}", func);
 
     let func = {
-        let ir: &Vec<FunctionIR> = &ctx.ir;
-
         let fn_meta = match (&ctx).module.meta.functions.get(func_idx as usize) {
             Some(meta) => meta,
             None => unreachable!(),
@@ -75,7 +95,7 @@ pub(crate) fn call_util(
             FunctionSource::Import(FunctionImport { import_idx }) => {
                 InterpreterFunc::Import(*import_idx)
             }
-            FunctionSource::Wasm(_) => InterpreterFunc::IR(&ir[func_idx as usize]),
+            FunctionSource::Wasm(_) => InterpreterFunc::IR(ctx.ir.get(&ctx.module, func_idx)?),
         }
     };
 
@@ -83,38 +103,42 @@ pub(crate) fn call_util(
         InterpreterFunc::Import(import_idx) => {
             let mut ret =
                 unsafe { call_import_util(ctx, import_idx as usize, call_params, return_vars) };
+            if unsafe { runtime_interface::exception_pending(ctx.exec_ctx) } != 0 {
+                return unwind_util(ctx, unwind_bb);
+            }
 
             let mut stack_frame = ctx.stack.last_mut().unwrap();
             stack_frame.was_imported_terminator = true;
 
-            Some(ret)
+            Ok(Some(ret))
         }
         InterpreterFunc::IR(f_int) => {
             ctx.exec_ctx.recursion_size += 1;
             let stack_frame = ctx.stack.last_mut().unwrap();
             let bbs = &f_int.bbs;
 
-            let mut new_stack_frame = StackFrame {
+            let params = call_params
+                .iter()
+                .map(|&param| stack_frame.vars.get(param))
+                .collect();
+            let new_stack_frame = StackFrame {
                 fn_idx: func_idx,
-                fn_local_vars: VariableStore::new(Vec::new()),
+                fn_local_vars: VariableStore::with_locals(params, &f_int.locals),
                 bb_id: bbs[0].id,
                 last_bb_id: 0,
                 return_vars: Vec::from(return_vars),
                 decoder: InstructionDecoder::new(bbs[0].instructions.clone()),
                 vars: VariableStore::new(Vec::new()),
                 was_imported_terminator: false,
+                unwind_bb: None,
             };
 
-            for (idx, &param) in call_params.iter().enumerate() {
-                let var = stack_frame.vars.get(param);
-                new_stack_frame.fn_local_vars.set(idx, var);
-            }
-
             stack_frame.last_bb_id = stack_frame.bb_id;
             stack_frame.bb_id = return_bb;
+            stack_frame.unwind_bb = unwind_bb;
 
             ctx.stack.push(new_stack_frame);
-            None
+            Ok(None)
         }
     }
 }
@@ -0,0 +1,74 @@
+use crate::InterpreterError;
+use module::{instructions::FunctionIR, objects::module::Module, passes::PassManager};
+use parser::FunctionLoader;
+use std::cell::OnceCell;
+use wasm_types::FuncIdx;
+
+/// IR of the module's functions, either parsed ahead of time or when a function is first executed.
+pub struct FunctionIRStore {
+    functions: Vec<OnceCell<FunctionIR>>,
+    // parser and optimizations for functions parsed on first use
+    loader: FunctionLoader,
+    passes: Option<PassManager>,
+}
+
+impl FunctionIRStore {
+    /// Store of functions that were all parsed before execution, indexed by function index.
+    pub fn eager(functions: Vec<FunctionIR>) -> Self {
+        Self {
+            functions: functions.into_iter().map(OnceCell::from).collect(),
+            loader: FunctionLoader::default(),
+            passes: None,
+        }
+    }
+
+    /// Store parsing the body of each function of `module` on first use. Invalid functions are only
+    /// reported once they are executed.
+    pub fn lazy(module: &Module) -> Self {
+        Self {
+            functions: (0..module.meta.functions.len())
+                .map(|_| OnceCell::new())
+                .collect(),
+            loader: FunctionLoader::default(),
+            passes: None,
+        }
+    }
+
+    /// Parses functions with `loader`, e.g. to verify their IR.
+    pub fn with_loader(mut self, loader: FunctionLoader) -> Self {
+        self.loader = loader;
+        self
+    }
+
+    /// Optimizes functions with `passes` once they are parsed.
+    pub fn with_passes(mut self, passes: PassManager) -> Self {
+        self.passes = Some(passes);
+        self
+    }
+
+    pub fn get(&self, module: &Module, func_idx: FuncIdx) -> Result<&FunctionIR, InterpreterError> {
+        let function = self
+            .functions
+            .get(func_idx as usize)
+            .ok_or(InterpreterError::FunctionNotFound(func_idx))?;
+        if let Some(function_ir) = function.get() {
+            return Ok(function_ir);
+        }
+        log::debug!("Parsing function {func_idx} on its first call");
+        let mut function_ir = self.loader.parse_function_ir(module, func_idx)?;
+        if let Some(passes) = &self.passes {
+            passes
+                .run(&mut function_ir)
+                .map_err(|e| e.in_function(func_idx))?;
+        }
+        Ok(function.get_or_init(|| function_ir))
+    }
+
+    /// IR of a function on the interpreter stack, which has been parsed when it was called.
+    pub(crate) fn executing(&self, func_idx: FuncIdx) -> Result<&FunctionIR, InterpreterError> {
+        self.functions
+            .get(func_idx as usize)
+            .and_then(OnceCell::get)
+            .ok_or(InterpreterError::FunctionNotParsed(func_idx))
+    }
+}
@@ -3,15 +3,15 @@
 use control_flow::GlueHandler;
 use core::ffi;
 use module::{
-    basic_block::BasicBlockGlue,
+    basic_block::{BasicBlockGlue, BasicBlockID},
     instructions::FunctionIR,
     objects::{
         function::{Function, FunctionImport, FunctionSource},
-        value::{Number, ValueRaw},
+        value::{Number, Reference, ValueRaw},
     },
     utils::numeric_transmutes::{Bit32, Bit64},
 };
-use runtime_interface::{ExecutionContext, GlobalInstance, RawPointer};
+use runtime_interface::{ExecutionContext, GcRootScanner, GlobalInstance, RawPointer};
 use std::{collections::HashMap, fmt::Display, rc::Rc};
 use table::execute_table_instruction;
 use thiserror::Error;
@@ -19,17 +19,22 @@ use wasm_types::{FuncIdx, GlobalIdx, InstructionType, NumType, ValType};
 use {
     module::instructions::VariableID,
     module::objects::{module::Module, value::Value},
-    module::{DecodingError, InstructionDecoder},
+    module::{DecodingError, InstructionDecoder, VerifierError},
     parser::error::ParserError,
 };
 
+mod aggregate;
 mod control_flow;
+mod ir_store;
 mod memory;
 mod numeric;
 mod parametric;
 mod reference;
 mod table;
 mod variable;
+mod vector;
+
+pub use ir_store::FunctionIRStore;
 
 use log;
 
@@ -43,12 +48,18 @@ pub enum InterpreterError {
     InvalidType,
     #[error("Decoding error: {0}")]
     DecodingError(#[from] DecodingError),
+    #[error("Invalid IR: {0}")]
+    InvalidIR(#[from] VerifierError),
     #[error("Parser error: {0}")]
     ParserError(#[from] ParserError),
     #[error("Unreachable instruction reached")]
     Unreachable,
     #[error("Index out of bounds")]
     IdxBounds,
+    #[error("unaligned atomic")]
+    UnalignedAtomic,
+    #[error("Null reference")]
+    NullReference,
     #[error("Division by zero")]
     DivZero,
     #[error("Error converting float to integer")]
@@ -61,6 +72,26 @@ pub enum InterpreterError {
     NoIR,
     #[error{"Function at index {0} not found"}]
     FunctionNotFound(FuncIdx),
+    #[error{"Function at index {0} is executed but wasn't parsed"}]
+    FunctionNotParsed(FuncIdx),
+    #[error("{source} (in function {function})")]
+    InFunction {
+        function: String,
+        source: Box<InterpreterError>,
+    },
+}
+
+impl InterpreterError {
+    /// Attach the name of the function the error occurred in, unless it already has one.
+    fn in_function(self, ctx: &InterpreterContext) -> Self {
+        match (self, ctx.stack.last()) {
+            (e @ (Self::InFunction { .. } | Self::StackExhausted), _) | (e, None) => e,
+            (e, Some(frame)) => Self::InFunction {
+                function: Function::debug_function_name(frame.fn_idx, &ctx.module.meta),
+                source: Box::new(e),
+            },
+        }
+    }
 }
 
 pub(crate) trait Executable {
@@ -77,6 +108,17 @@ impl VariableStore {
         Self { vars: init }
     }
 
+    /// The locals of a function called with `params`, all other `locals` start out with the default value of their
+    /// type.
+    pub(crate) fn with_locals(mut params: Vec<ValueRaw>, locals: &[ValType]) -> Self {
+        let defaults = locals.iter().skip(params.len()).map(|t| match t {
+            ValType::Reference(_) => ValueRaw::from(Value::Reference(Reference::Null)),
+            ValType::Number(_) | ValType::VecType => ValueRaw::u64(0),
+        });
+        params.extend(defaults);
+        Self { vars: params }
+    }
+
     pub(crate) fn get(&self, idx: VariableID) -> ValueRaw {
         log::trace!("getting from idx: {}. Value: ", idx);
         let value_raw = self
@@ -168,6 +210,31 @@ struct StackFrame {
     decoder: InstructionDecoder,
     vars: VariableStore,
     was_imported_terminator: bool,
+    /// basic block at which exceptions raised by the current callee continue, if any
+    unwind_bb: Option<BasicBlockID>,
+}
+
+/// Unregisters the roots of an [`InterpreterContext`] when [`Interpreter::run`] returns.
+struct GcRootsGuard;
+
+impl Drop for GcRootsGuard {
+    fn drop(&mut self) {
+        runtime_interface::pop_gc_roots();
+    }
+}
+
+unsafe fn scan_gc_roots(data: *const ffi::c_void, visit: &mut dyn FnMut(u64)) {
+    let ctx = unsafe { &*(data as *const InterpreterContext) };
+    for frame in &ctx.stack {
+        for value in frame
+            .fn_local_vars
+            .vars
+            .iter()
+            .chain(frame.vars.vars.iter())
+        {
+            visit(value.as_u64());
+        }
+    }
 }
 
 type ImportMap = HashMap<String, RawPointer>;
@@ -178,7 +245,7 @@ pub struct InterpreterContext<'a> {
     stack: Vec<StackFrame>,
     exec_ctx: &'a mut ExecutionContext,
     imported_symbols: ImportMap,
-    ir: Rc<Vec<FunctionIR>>,
+    ir: Rc<FunctionIRStore>,
 }
 
 pub enum InterpreterFunc<'a> {
@@ -191,7 +258,7 @@ impl<'a> InterpreterContext<'a> {
         module_rc: Rc<Module>,
         exec_ctx: &'a mut ExecutionContext,
         imported_symbols: ImportMap,
-        ir: Rc<Vec<FunctionIR>>,
+        ir: Rc<FunctionIRStore>,
     ) -> Self {
         Self {
             module: module_rc,
@@ -206,7 +273,7 @@ impl<'a> InterpreterContext<'a> {
 #[derive(Clone)]
 pub struct Interpreter {
     module: Option<Rc<Module>>,
-    ir: Option<Rc<Vec<FunctionIR>>>,
+    ir: Option<Rc<FunctionIRStore>>,
     pub imported_functions: ImportMap,
     global_addresses: GlobalMap,
 }
@@ -225,7 +292,7 @@ impl Interpreter {
         self.module = Some(module);
     }
 
-    pub fn set_ir(&mut self, ir: Rc<Vec<FunctionIR>>) {
+    pub fn set_ir(&mut self, ir: Rc<FunctionIRStore>) {
         self.ir = Some(ir);
     }
 
@@ -282,6 +349,13 @@ impl Interpreter {
 
         ctx.stack = Vec::new();
 
+        // the variables of all interpreter frames may hold references into the GC heap
+        runtime_interface::push_gc_roots(GcRootScanner {
+            data: &ctx as *const InterpreterContext as *const ffi::c_void,
+            scan: scan_gc_roots,
+        });
+        let _gc_roots = GcRootsGuard;
+
         let entry_fn_meta = ctx
             .module
             .meta
@@ -300,8 +374,6 @@ impl Interpreter {
         ctx.exec_ctx.recursion_size += 1;
 
         let entry_fn_res: Result<_, InterpreterError> = {
-            let ir: &Vec<FunctionIR> = &ctx.ir;
-
             let fn_meta = match (&ctx).module.meta.functions.get(function_idx as usize) {
                 Some(meta) => meta,
                 None => return Err(InterpreterError::FunctionNotFound(function_idx)),
@@ -311,7 +383,9 @@ impl Interpreter {
                 FunctionSource::Import(FunctionImport { import_idx }) => {
                     Ok(InterpreterFunc::Import(*import_idx))
                 }
-                FunctionSource::Wasm(_) => Ok(InterpreterFunc::IR(&ir[function_idx as usize])),
+                FunctionSource::Wasm(_) => {
+                    Ok(InterpreterFunc::IR(ctx.ir.get(&ctx.module, function_idx)?))
+                }
             }
         };
 
@@ -333,13 +407,14 @@ impl Interpreter {
 
                 ctx.stack.push(StackFrame {
                     fn_idx: function_idx,
-                    fn_local_vars: VariableStore::new(raw_parameters),
+                    fn_local_vars: VariableStore::with_locals(raw_parameters, &entry_fn.locals),
                     bb_id: basic_block.id,
                     last_bb_id: 0,
                     return_vars: Vec::new(),
                     decoder,
                     vars: VariableStore::new(Vec::new()),
                     was_imported_terminator: false,
+                    unwind_bb: None,
                 });
 
                 loop {
@@ -362,14 +437,12 @@ impl Interpreter {
                     log::debug!("Instruction type: {:?}", instruction_type);
 
                     match instruction_type {
-                        Ok(instruction_type) => {
-                            self.execute_instruction(instruction_type, &mut ctx)?
-                        }
+                        Ok(instruction_type) => self
+                            .execute_instruction(instruction_type, &mut ctx)
+                            .map_err(|e| e.in_function(&ctx))?,
                         Err(DecodingError::InstructionStorageExhausted) => {
                             let current_fn_idx = ctx.stack.last_mut().unwrap().fn_idx;
                             let interpreter_func_res: Result<_, InterpreterError> = {
-                                let ir: &Vec<FunctionIR> = &ctx.ir;
-
                                 let fn_meta =
                                     match (&ctx).module.meta.functions.get(current_fn_idx as usize)
                                     {
@@ -386,11 +459,11 @@ impl Interpreter {
                                         Ok(InterpreterFunc::Import(*import_idx))
                                     }
                                     FunctionSource::Wasm(_) => {
-                                        Ok(InterpreterFunc::IR(&ir[current_fn_idx as usize]))
+                                        Ok(InterpreterFunc::IR(ctx.ir.executing(current_fn_idx)?))
                                     }
                                 }
                             };
-                            let func = match interpreter_func_res.unwrap() {
+                            let func = match interpreter_func_res? {
                                 InterpreterFunc::IR(function_ir) => function_ir,
                                 InterpreterFunc::Import(_) => unreachable!(),
                             };
@@ -408,7 +481,11 @@ impl Interpreter {
                             log::debug!("handling terminator {:?}", bb.terminator);
                             log::trace!("from basic block: {:#?}", bb);
 
-                            let ret_vals = bb.terminator.to_owned().handle(&mut ctx)?;
+                            let ret_vals = bb
+                                .terminator
+                                .to_owned()
+                                .handle(&mut ctx)
+                                .map_err(|e| e.in_function(&ctx))?;
 
                             let should_pop = ctx
                                 .stack
@@ -421,8 +498,9 @@ impl Interpreter {
                             log::debug!("stack len: {}", &ctx.stack.len());
 
                             if ctx.stack.len() == 0 {
+                                // an uncaught exception leaves the function without results, it stays pending for our caller
                                 let mut ret_vals = ret_vals.unwrap_or_default();
-                                ret_vals.truncate(fn_type.num_results());
+                                ret_vals.resize(fn_type.num_results(), ValueRaw::u64(0));
 
                                 break ret_vals;
                             }
@@ -484,10 +562,15 @@ impl Interpreter {
                 reference::execute_reference_instruction(ctx, c, instruction_type)
             }
             InstructionType::Table(c) => execute_table_instruction(ctx, c, instruction_type),
+            InstructionType::Aggregate(c) => {
+                aggregate::execute_aggregate_instruction(ctx, c, instruction_type)
+            }
             InstructionType::Control(_) => unreachable!(
                 "Control instructions are not serialized and can therefore not be deserialized."
             ),
-            InstructionType::Vector => todo!(),
+            InstructionType::Vector(c) => {
+                vector::execute_vector_instruction(ctx, c, instruction_type)
+            }
         }
     }
 }
@@ -0,0 +1,195 @@
+use super::{effective_address, memory_slice};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{
+    instructions::{
+        AtomicCmpxchgInstruction, AtomicFenceInstruction, AtomicLoadInstruction,
+        AtomicNotifyInstruction, AtomicRmwInstruction, AtomicStoreInstruction,
+        AtomicWaitInstruction, VariableID,
+    },
+    objects::{memory::MemArg, value::ValueRaw},
+};
+use std::sync::atomic::{fence, AtomicU16, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use wasm_types::{AtomicRmwOp, NumType};
+
+/// Runs `$body` on the atomic integer of `$width` bytes at `$ptr`, converting the result to `u64`.
+macro_rules! with_atomic {
+    ($ptr:expr, $width:expr, $a:ident => $body:expr) => {
+        match $width {
+            1 => {
+                let $a = unsafe { AtomicU8::from_ptr($ptr) };
+                ($body) as u64
+            }
+            2 => {
+                let $a = unsafe { AtomicU16::from_ptr($ptr as *mut u16) };
+                ($body) as u64
+            }
+            4 => {
+                let $a = unsafe { AtomicU32::from_ptr($ptr as *mut u32) };
+                ($body) as u64
+            }
+            _ => {
+                let $a = unsafe { AtomicU64::from_ptr($ptr as *mut u64) };
+                $body
+            }
+        }
+    };
+}
+
+fn read_int(ctx: &mut InterpreterContext, var: VariableID, t: NumType) -> u64 {
+    let value = ctx.stack.last_mut().unwrap().vars.get(var);
+    match t {
+        NumType::I32 | NumType::F32 => value.as_u32() as u64,
+        NumType::I64 | NumType::F64 => value.as_u64(),
+    }
+}
+
+/// Host pointer of an atomic access, which must be in bounds and naturally aligned.
+fn atomic_access_ptr(
+    ctx: &mut InterpreterContext,
+    addr: VariableID,
+    memarg: &MemArg,
+    width: u8,
+) -> Result<*mut u8, InterpreterError> {
+    let effective_address = effective_address(ctx, addr, memarg)?;
+    let ptr = memory_slice(ctx, memarg.memory, effective_address, width as usize)?.as_mut_ptr();
+    if effective_address % width as u64 != 0 {
+        return Err(InterpreterError::UnalignedAtomic);
+    }
+    Ok(ptr)
+}
+
+impl Executable for AtomicLoadInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let ptr = atomic_access_ptr(ctx, self.addr, &self.memarg, self.width)?;
+        let res = with_atomic!(ptr, self.width, a => a.load(Ordering::SeqCst));
+        ctx.stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .set(self.out1, ValueRaw::u64(res));
+        Ok(())
+    }
+}
+
+impl Executable for AtomicStoreInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let ptr = atomic_access_ptr(ctx, self.addr_in, &self.memarg, self.width)?;
+        let value = read_int(ctx, self.value_in, self.in_type);
+        unsafe {
+            match self.width {
+                1 => AtomicU8::from_ptr(ptr).store(value as u8, Ordering::SeqCst),
+                2 => AtomicU16::from_ptr(ptr as *mut u16).store(value as u16, Ordering::SeqCst),
+                4 => AtomicU32::from_ptr(ptr as *mut u32).store(value as u32, Ordering::SeqCst),
+                _ => AtomicU64::from_ptr(ptr as *mut u64).store(value, Ordering::SeqCst),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Executable for AtomicRmwInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let ptr = atomic_access_ptr(ctx, self.addr, &self.memarg, self.width)?;
+        let value = read_int(ctx, self.value, self.r#type);
+        let old = with_atomic!(ptr, self.width, a => match self.op {
+            AtomicRmwOp::Add => a.fetch_add(value as _, Ordering::SeqCst),
+            AtomicRmwOp::Sub => a.fetch_sub(value as _, Ordering::SeqCst),
+            AtomicRmwOp::And => a.fetch_and(value as _, Ordering::SeqCst),
+            AtomicRmwOp::Or => a.fetch_or(value as _, Ordering::SeqCst),
+            AtomicRmwOp::Xor => a.fetch_xor(value as _, Ordering::SeqCst),
+            AtomicRmwOp::Xchg => a.swap(value as _, Ordering::SeqCst),
+        });
+        ctx.stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .set(self.out1, ValueRaw::u64(old));
+        Ok(())
+    }
+}
+
+impl Executable for AtomicCmpxchgInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let ptr = atomic_access_ptr(ctx, self.addr, &self.memarg, self.width)?;
+        // narrow variants compare against the wrapped expected value
+        let expected = read_int(ctx, self.expected, self.r#type);
+        let replacement = read_int(ctx, self.replacement, self.r#type);
+        let old = with_atomic!(ptr, self.width, a => {
+            match a.compare_exchange(
+                expected as _,
+                replacement as _,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            ) {
+                Ok(old) | Err(old) => old,
+            }
+        });
+        ctx.stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .set(self.out1, ValueRaw::u64(old));
+        Ok(())
+    }
+}
+
+impl Executable for AtomicWaitInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        // alignment, bounds and shared-ness are checked by the runtime
+        let address = effective_address(ctx, self.addr, &self.memarg)?;
+        let expected = read_int(ctx, self.expected, self.r#type);
+        let timeout = read_int(ctx, self.timeout, NumType::I64) as i64;
+        let res = unsafe {
+            match self.r#type {
+                NumType::I64 | NumType::F64 => runtime_interface::memory_atomic_wait64(
+                    ctx.exec_ctx,
+                    self.memarg.memory,
+                    address,
+                    expected,
+                    timeout,
+                ),
+                NumType::I32 | NumType::F32 => runtime_interface::memory_atomic_wait32(
+                    ctx.exec_ctx,
+                    self.memarg.memory,
+                    address,
+                    expected as u32,
+                    timeout,
+                ),
+            }
+        };
+        ctx.stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .set(self.out1, ValueRaw::u32(res));
+        Ok(())
+    }
+}
+
+impl Executable for AtomicNotifyInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let address = effective_address(ctx, self.addr, &self.memarg)?;
+        let count = read_int(ctx, self.count, NumType::I32) as u32;
+        let res = unsafe {
+            runtime_interface::memory_atomic_notify(
+                ctx.exec_ctx,
+                self.memarg.memory,
+                address,
+                count,
+            )
+        };
+        ctx.stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .set(self.out1, ValueRaw::u32(res));
+        Ok(())
+    }
+}
+
+impl Executable for AtomicFenceInstruction {
+    fn execute(&mut self, _: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        fence(Ordering::SeqCst);
+        Ok(())
+    }
+}
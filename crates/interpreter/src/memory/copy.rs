@@ -1,14 +1,24 @@
+use super::{index_type, read_index_as};
 use crate::{Executable, InterpreterContext, InterpreterError};
 use module::instructions::MemoryCopyInstruction;
 
 impl Executable for MemoryCopyInstruction {
     fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
-        let stack_frame = ctx.stack.last_mut().unwrap();
-        let size = stack_frame.vars.get(self.n).into();
-        let src_offset = stack_frame.vars.get(self.s).into();
-        let dst_offset = stack_frame.vars.get(self.d).into();
+        let dst_index_type = index_type(ctx, self.memory_idx_x);
+        let src_index_type = index_type(ctx, self.memory_idx_y);
+        // copies between a 32-bit and a 64-bit memory take a 32-bit length
+        let size = read_index_as(ctx, self.n, dst_index_type.min(src_index_type));
+        let src_offset = read_index_as(ctx, self.s, src_index_type);
+        let dst_offset = read_index_as(ctx, self.d, dst_index_type);
         unsafe {
-            runtime_interface::memory_copy(ctx.exec_ctx, 0, src_offset, dst_offset, size);
+            runtime_interface::memory_copy(
+                ctx.exec_ctx,
+                self.memory_idx_x,
+                self.memory_idx_y,
+                src_offset,
+                dst_offset,
+                size,
+            );
         };
         Ok(())
     }
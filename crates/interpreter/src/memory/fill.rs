@@ -1,14 +1,14 @@
+use super::read_index;
 use crate::{Executable, InterpreterContext, InterpreterError};
 use module::instructions::MemoryFillInstruction;
 
 impl Executable for MemoryFillInstruction {
     fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
-        let stack_frame = ctx.stack.last_mut().unwrap();
-        let offset = stack_frame.vars.get(self.d).into();
-        let size = stack_frame.vars.get(self.n).into();
-        let value = stack_frame.vars.get(self.val).as_u32() as u8;
+        let offset = read_index(ctx, self.memory_idx, self.d);
+        let size = read_index(ctx, self.memory_idx, self.n);
+        let value = ctx.stack.last_mut().unwrap().vars.get(self.val).as_u32() as u8;
         unsafe {
-            runtime_interface::memory_fill(ctx.exec_ctx, 0, offset, size, value);
+            runtime_interface::memory_fill(ctx.exec_ctx, self.memory_idx, offset, size, value);
         };
         Ok(())
     }
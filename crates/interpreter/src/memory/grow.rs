@@ -1,11 +1,14 @@
+use super::read_index;
 use crate::{Executable, InterpreterContext, InterpreterError};
 use module::instructions::MemoryGrowInstruction;
 
 impl Executable for MemoryGrowInstruction {
     fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let grow_by = read_index(ctx, self.memory_idx, self.in1);
+        let res = unsafe {
+            runtime_interface::memory_grow(ctx.exec_ctx, self.memory_idx as usize, grow_by)
+        };
         let stack_frame = ctx.stack.last_mut().unwrap();
-        let grow_by = stack_frame.vars.get(self.in1).into();
-        let res = unsafe { runtime_interface::memory_grow(ctx.exec_ctx, 0, grow_by) };
         stack_frame.vars.set(self.out1, res.into());
 
         Ok(())
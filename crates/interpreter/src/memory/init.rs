@@ -1,5 +1,6 @@
 use module::instructions::MemoryInitInstruction;
 
+use super::read_index;
 use crate::{Executable, InterpreterContext, InterpreterError};
 
 impl Executable for MemoryInitInstruction {
@@ -7,9 +8,18 @@ impl Executable for MemoryInitInstruction {
         let stack_frame = ctx.stack.last_mut().unwrap();
         let n = stack_frame.vars.get(self.n).as_u32();
         let s = stack_frame.vars.get(self.s).as_u32();
-        let d = stack_frame.vars.get(self.d).as_u32();
+        let d = read_index(ctx, self.memory_idx, self.d);
 
-        unsafe { runtime_interface::memory_init(&mut ctx.exec_ctx, 0, self.data_idx, s, d, n) };
+        unsafe {
+            runtime_interface::memory_init(
+                &mut ctx.exec_ctx,
+                self.memory_idx,
+                self.data_idx,
+                s,
+                d,
+                n,
+            )
+        };
 
         Ok(())
     }
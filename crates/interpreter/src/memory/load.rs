@@ -2,8 +2,9 @@ use log::trace;
 use module::{
     instructions::LoadInstruction, objects::value::ValueRaw, utils::numeric_transmutes::Bit64,
 };
-use wasm_types::{LoadOp, NumType};
+use wasm_types::{LoadOp, MemIdx, NumType};
 
+use super::{effective_address, memory_slice};
 use crate::{Executable, InterpreterContext, InterpreterError};
 
 enum LoadSize {
@@ -15,58 +16,67 @@ enum LoadSize {
 
 impl Executable for LoadInstruction {
     fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
-        let stack_frame = ctx.stack.last_mut().unwrap();
-        let dyn_addr = stack_frame.vars.get(self.addr).as_i32() as usize;
-        let effective_address = dyn_addr as usize + self.memarg.offset as usize;
+        let effective_address = effective_address(ctx, self.addr, &self.memarg)?;
 
         log::trace!("Memory Load: {:#?}", self);
 
         let res = match self.operation {
-            LoadOp::INNLoad | LoadOp::FNNLoad => {
-                handle_load(ctx, effective_address, self.out1_type, LoadSize::Full, None)
-            }
+            LoadOp::INNLoad | LoadOp::FNNLoad => handle_load(
+                ctx,
+                self.memarg.memory,
+                effective_address,
+                self.out1_type,
+                LoadSize::Full,
+                None,
+            )?,
             LoadOp::INNLoad8U => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::Byte,
                 Some(false),
-            ),
+            )?,
             LoadOp::INNLoad8S => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::Byte,
                 Some(true),
-            ),
+            )?,
             LoadOp::INNLoad16U => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::Word,
                 Some(false),
-            ),
+            )?,
             LoadOp::INNLoad16S => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::Word,
                 Some(true),
-            ),
+            )?,
             LoadOp::INNLoad32U => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::DoubleWord,
                 Some(false),
-            ),
+            )?,
             LoadOp::INNLoad32S => handle_load(
                 ctx,
+                self.memarg.memory,
                 effective_address,
                 self.out1_type,
                 LoadSize::DoubleWord,
                 Some(true),
-            ),
+            )?,
         };
 
         let stack_frame = ctx.stack.last_mut().unwrap();
@@ -78,15 +88,12 @@ impl Executable for LoadInstruction {
 
 fn handle_load(
     ctx: &mut InterpreterContext,
-    addr: usize,
+    memory_idx: MemIdx,
+    addr: u64,
     out_type: NumType,
     size: LoadSize,
     signed: Option<bool>,
-) -> ValueRaw {
-    let memory_inst_ptr = ctx.exec_ctx.memories_ptr;
-    let memory_data_ptr = unsafe { (*memory_inst_ptr).data };
-    let src_val_ptr = unsafe { memory_data_ptr.add(addr) };
-
+) -> Result<ValueRaw, InterpreterError> {
     let num_bytes_to_load = match (&size, out_type) {
         (LoadSize::Byte, _) => 1,
         (LoadSize::Word, _) => 2,
@@ -99,10 +106,7 @@ fn handle_load(
 
     trace!("Loading {} bytes from address {}", num_bytes_to_load, addr);
 
-    let bytes;
-    unsafe {
-        bytes = core::slice::from_raw_parts(src_val_ptr, num_bytes_to_load);
-    }
+    let bytes = memory_slice(ctx, memory_idx, addr, num_bytes_to_load)?;
     let mut padded: [u8; 8] = [0; 8];
     padded[..num_bytes_to_load].copy_from_slice(bytes);
 
@@ -121,5 +125,5 @@ fn handle_load(
 
     trace!("Loaded value: {}", res.trans_f32());
 
-    ValueRaw::u64(res)
+    Ok(ValueRaw::u64(res))
 }
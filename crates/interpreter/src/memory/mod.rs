@@ -1,12 +1,18 @@
 use module::instructions::{
+    AtomicCmpxchgInstruction, AtomicFenceInstruction, AtomicLoadInstruction,
+    AtomicNotifyInstruction, AtomicRmwInstruction, AtomicStoreInstruction, AtomicWaitInstruction,
     DataDropInstruction, Instruction, LoadInstruction, MemoryCopyInstruction,
     MemoryFillInstruction, MemoryGrowInstruction, MemoryInitInstruction, MemorySizeInstruction,
-    StoreInstruction,
+    StoreInstruction, VariableID,
+};
+use module::objects::memory::MemArg;
+use wasm_types::{
+    AtomicOp, IndexType, InstructionType, MemIdx, MemoryInstructionCategory, MemoryOp,
 };
-use wasm_types::{InstructionType, MemoryInstructionCategory, MemoryOp};
 
 use crate::{Executable, InterpreterContext, InterpreterError};
 
+mod atomic;
 mod copy;
 mod drop;
 mod fill;
@@ -46,5 +52,83 @@ pub(crate) fn execute_memory_instruction(
         MemoryInstructionCategory::Memory(MemoryOp::Drop) => {
             DataDropInstruction::deserialize(i, t)?.execute(ctx)
         }
+        MemoryInstructionCategory::Atomic(AtomicOp::Load) => {
+            AtomicLoadInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Store) => {
+            AtomicStoreInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Rmw(_)) => {
+            AtomicRmwInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Cmpxchg) => {
+            AtomicCmpxchgInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Wait) => {
+            AtomicWaitInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Notify) => {
+            AtomicNotifyInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        MemoryInstructionCategory::Atomic(AtomicOp::Fence) => {
+            AtomicFenceInstruction::deserialize(i, t)?.execute(ctx)
+        }
+    }
+}
+
+/// Index type of memory `memory_idx`.
+pub(crate) fn index_type(ctx: &InterpreterContext, memory_idx: MemIdx) -> IndexType {
+    ctx.module.meta.memories[memory_idx as usize]
+        .r#type
+        .index_type
+}
+
+/// Reads an address, size or page count operand whose width depends on `index_type`.
+pub(crate) fn read_index_as(
+    ctx: &mut InterpreterContext,
+    var: VariableID,
+    index_type: IndexType,
+) -> u64 {
+    let value = ctx.stack.last_mut().unwrap().vars.get(var);
+    match index_type {
+        IndexType::I32 => value.as_u32() as u64,
+        IndexType::I64 => value.as_u64(),
+    }
+}
+
+/// Reads an address, size or page count operand of memory `memory_idx`.
+pub(crate) fn read_index(ctx: &mut InterpreterContext, memory_idx: MemIdx, var: VariableID) -> u64 {
+    let index_type = index_type(ctx, memory_idx);
+    read_index_as(ctx, var, index_type)
+}
+
+/// Effective address of a memory access. Addresses overflowing the 64-bit address space are out of bounds.
+pub(crate) fn effective_address(
+    ctx: &mut InterpreterContext,
+    addr: VariableID,
+    memarg: &MemArg,
+) -> Result<u64, InterpreterError> {
+    read_index(ctx, memarg.memory, addr)
+        .checked_add(memarg.offset)
+        .ok_or(InterpreterError::IdxBounds)
+}
+
+/// The `len` bytes of memory `memory_idx` starting at `addr`. Accesses are bounds checked explicitly, as the guard
+/// region behind the memory only covers 32-bit address computations.
+pub(crate) fn memory_slice<'a>(
+    ctx: &'a mut InterpreterContext,
+    memory_idx: MemIdx,
+    addr: u64,
+    len: usize,
+) -> Result<&'a mut [u8], InterpreterError> {
+    let memory_inst_ptr = unsafe { ctx.exec_ctx.memories_ptr.add(memory_idx as usize) };
+    let memory_size = unsafe { (*memory_inst_ptr).size } * 65536;
+    if !addr
+        .checked_add(len as u64)
+        .is_some_and(|end| end <= memory_size)
+    {
+        return Err(InterpreterError::IdxBounds);
     }
+    let memory_data_ptr = unsafe { (*memory_inst_ptr).data };
+    Ok(unsafe { core::slice::from_raw_parts_mut(memory_data_ptr.add(addr as usize), len) })
 }
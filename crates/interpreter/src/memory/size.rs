@@ -3,7 +3,9 @@ use module::instructions::MemorySizeInstruction;
 
 impl Executable for MemorySizeInstruction {
     fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
-        let res = unsafe { runtime_interface::memory_grow(&mut ctx.exec_ctx, 0, 0) };
+        let res = unsafe {
+            runtime_interface::memory_grow(&mut ctx.exec_ctx, self.memory_idx as usize, 0)
+        };
 
         let stack_frame = ctx.stack.last_mut().unwrap();
         stack_frame.vars.set(self.out1, res.into());
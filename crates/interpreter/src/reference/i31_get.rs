@@ -0,0 +1,19 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::I31GetInstruction, objects::value::gc};
+
+impl Executable for I31GetInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let in1 = stack_frame.vars.get(self.in1).as_u64();
+        if in1 == gc::NULL {
+            return Err(InterpreterError::NullReference);
+        }
+
+        stack_frame
+            .vars
+            .set(self.out1, gc::i31_get(in1, self.signed).into());
+
+        Ok(())
+    }
+}
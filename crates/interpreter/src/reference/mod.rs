@@ -1,13 +1,21 @@
 use module::instructions::{
-    Instruction, ReferenceFunctionInstruction, ReferenceIsNullInstruction, ReferenceNullInstruction,
+    I31GetInstruction, Instruction, ReferenceAsNonNullInstruction, ReferenceCastInstruction,
+    ReferenceEqInstruction, ReferenceFunctionInstruction, ReferenceI31Instruction,
+    ReferenceIsNullInstruction, ReferenceNullInstruction, ReferenceTestInstruction,
 };
 use wasm_types::{InstructionType, ReferenceInstructionType};
 
 use crate::{Executable, InterpreterContext, InterpreterError};
 
+mod i31_get;
+mod ref_as_non_null;
+mod ref_cast;
+mod ref_eq;
 mod ref_func;
+mod ref_i31;
 mod ref_is_null;
 mod ref_null;
+mod ref_test;
 
 pub(crate) fn execute_reference_instruction(
     ctx: &mut InterpreterContext,
@@ -26,6 +34,22 @@ pub(crate) fn execute_reference_instruction(
         ReferenceInstructionType::RefFunc => {
             ReferenceFunctionInstruction::deserialize(i, t)?.execute(ctx)?
         }
+        ReferenceInstructionType::RefAsNonNull => {
+            ReferenceAsNonNullInstruction::deserialize(i, t)?.execute(ctx)?
+        }
+        ReferenceInstructionType::RefEq => {
+            ReferenceEqInstruction::deserialize(i, t)?.execute(ctx)?
+        }
+        ReferenceInstructionType::RefTest => {
+            ReferenceTestInstruction::deserialize(i, t)?.execute(ctx)?
+        }
+        ReferenceInstructionType::RefCast => {
+            ReferenceCastInstruction::deserialize(i, t)?.execute(ctx)?
+        }
+        ReferenceInstructionType::RefI31 => {
+            ReferenceI31Instruction::deserialize(i, t)?.execute(ctx)?
+        }
+        ReferenceInstructionType::I31Get => I31GetInstruction::deserialize(i, t)?.execute(ctx)?,
     }
 
     Ok(())
@@ -0,0 +1,20 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{
+    instructions::ReferenceAsNonNullInstruction,
+    objects::value::{Reference, Value, ValueRaw},
+};
+
+impl Executable for ReferenceAsNonNullInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let in1 = stack_frame.vars.get(self.in1);
+        if in1.as_u32() == ValueRaw::from(Value::Reference(Reference::Null)).as_u32() {
+            return Err(InterpreterError::NullReference);
+        }
+
+        stack_frame.vars.set(self.out1, in1);
+
+        Ok(())
+    }
+}
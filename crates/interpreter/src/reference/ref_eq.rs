@@ -0,0 +1,18 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ReferenceEqInstruction;
+
+impl Executable for ReferenceEqInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        // eqref values are identical iff their raw representations are
+        let in1 = stack_frame.vars.get(self.in1).as_u64();
+        let in2 = stack_frame.vars.get(self.in2).as_u64();
+
+        stack_frame
+            .vars
+            .set(self.out1, ((in1 == in2) as u64).into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::ReferenceI31Instruction, objects::value::gc};
+
+impl Executable for ReferenceI31Instruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let in1 = stack_frame.vars.get(self.in1).as_u32();
+
+        stack_frame.vars.set(self.out1, gc::i31(in1).into());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::instructions::ReferenceTestInstruction;
+
+impl Executable for ReferenceTestInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+
+        let in1 = stack_frame.vars.get(self.in1).as_u64();
+
+        let res = unsafe {
+            runtime_interface::gc_ref_test(
+                ctx.exec_ctx,
+                in1,
+                self.ref_type.heap_type().to_s33(),
+                self.ref_type.is_nullable() as u32,
+            )
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, (res as u64).into());
+
+        Ok(())
+    }
+}
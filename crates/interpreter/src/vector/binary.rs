@@ -0,0 +1,238 @@
+use super::{float_shape, int_shape, pack, unpack, zip, Lane};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::VBinaryInstruction, objects::value::ValueRaw};
+use wasm_types::{VBinaryOp, VecShape};
+
+macro_rules! wasm_float_min_max {
+    ($min:ident, $max:ident, $t:ty) => {
+        fn $min(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                // -0.0 is smaller than +0.0
+                if a.is_sign_negative() {
+                    a
+                } else {
+                    b
+                }
+            } else {
+                a.min(b)
+            }
+        }
+
+        fn $max(a: $t, b: $t) -> $t {
+            if a.is_nan() || b.is_nan() {
+                <$t>::NAN
+            } else if a == b {
+                if a.is_sign_positive() {
+                    a
+                } else {
+                    b
+                }
+            } else {
+                a.max(b)
+            }
+        }
+    };
+}
+wasm_float_min_max!(min_f32, max_f32, f32);
+wasm_float_min_max!(min_f64, max_f64, f64);
+
+/// Multiplies `N` lanes starting at lane `offset` of `a` and `b` after widening them to `D`.
+fn extmul<S: Lane, D: Lane, const SN: usize, const N: usize>(
+    a: [u8; 16],
+    b: [u8; 16],
+    offset: usize,
+    mul: impl Fn(S, S) -> D,
+) -> [u8; 16] {
+    let a = unpack::<S, SN>(a);
+    let b = unpack::<S, SN>(b);
+    pack::<D, N>(std::array::from_fn(|i| mul(a[offset + i], b[offset + i])))
+}
+
+fn narrow<S: Lane, D: Lane, const SN: usize>(
+    a: [u8; 16],
+    b: [u8; 16],
+    saturate: impl Fn(S) -> D,
+) -> [u8; 16] {
+    let a = unpack::<S, SN>(a);
+    let b = unpack::<S, SN>(b);
+    let mut out = [0u8; 16];
+    for (i, lane) in a.iter().chain(b.iter()).enumerate() {
+        saturate(*lane).write(&mut out[i * D::BYTES..(i + 1) * D::BYTES]);
+    }
+    out
+}
+
+impl Executable for VBinaryInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let a = stack_frame.vars.get(self.lhs).as_v128();
+        let b = stack_frame.vars.get(self.rhs).as_v128();
+        let (a128, b128) = (u128::from_le_bytes(a), u128::from_le_bytes(b));
+        let shape = self.shape;
+
+        let res = match &self.op {
+            VBinaryOp::And => (a128 & b128).to_le_bytes(),
+            VBinaryOp::AndNot => (a128 & !b128).to_le_bytes(),
+            VBinaryOp::Or => (a128 | b128).to_le_bytes(),
+            VBinaryOp::Xor => (a128 ^ b128).to_le_bytes(),
+            VBinaryOp::Swizzle => {
+                std::array::from_fn(|i| a.get(b[i] as usize).copied().unwrap_or(0))
+            }
+
+            VBinaryOp::Add if shape.is_float() => {
+                float_shape!(shape, F, U, N, zip::<F, N>(a, b, |x, y| x + y))
+            }
+            VBinaryOp::Sub if shape.is_float() => {
+                float_shape!(shape, F, U, N, zip::<F, N>(a, b, |x, y| x - y))
+            }
+            VBinaryOp::Mul if shape.is_float() => {
+                float_shape!(shape, F, U, N, zip::<F, N>(a, b, |x, y| x * y))
+            }
+            VBinaryOp::Div => float_shape!(shape, F, U, N, zip::<F, N>(a, b, |x, y| x / y)),
+            VBinaryOp::Min => match shape {
+                VecShape::F32x4 => zip::<f32, 4>(a, b, min_f32),
+                _ => zip::<f64, 2>(a, b, min_f64),
+            },
+            VBinaryOp::Max => match shape {
+                VecShape::F32x4 => zip::<f32, 4>(a, b, max_f32),
+                _ => zip::<f64, 2>(a, b, max_f64),
+            },
+            VBinaryOp::PMin => {
+                float_shape!(
+                    shape,
+                    F,
+                    U,
+                    N,
+                    zip::<F, N>(a, b, |x, y| if y < x { y } else { x })
+                )
+            }
+            VBinaryOp::PMax => {
+                float_shape!(
+                    shape,
+                    F,
+                    U,
+                    N,
+                    zip::<F, N>(a, b, |x, y| if x < y { y } else { x })
+                )
+            }
+
+            VBinaryOp::Add => {
+                int_shape!(shape, S, U, N, zip::<U, N>(a, b, |x, y| x.wrapping_add(y)))
+            }
+            VBinaryOp::Sub => {
+                int_shape!(shape, S, U, N, zip::<U, N>(a, b, |x, y| x.wrapping_sub(y)))
+            }
+            VBinaryOp::Mul => {
+                int_shape!(shape, S, U, N, zip::<U, N>(a, b, |x, y| x.wrapping_mul(y)))
+            }
+            VBinaryOp::AddSatS => {
+                int_shape!(
+                    shape,
+                    S,
+                    U,
+                    N,
+                    zip::<S, N>(a, b, |x, y| x.saturating_add(y))
+                )
+            }
+            VBinaryOp::AddSatU => {
+                int_shape!(
+                    shape,
+                    S,
+                    U,
+                    N,
+                    zip::<U, N>(a, b, |x, y| x.saturating_add(y))
+                )
+            }
+            VBinaryOp::SubSatS => {
+                int_shape!(
+                    shape,
+                    S,
+                    U,
+                    N,
+                    zip::<S, N>(a, b, |x, y| x.saturating_sub(y))
+                )
+            }
+            VBinaryOp::SubSatU => {
+                int_shape!(
+                    shape,
+                    S,
+                    U,
+                    N,
+                    zip::<U, N>(a, b, |x, y| x.saturating_sub(y))
+                )
+            }
+            VBinaryOp::MinS => int_shape!(shape, S, U, N, zip::<S, N>(a, b, |x, y| x.min(y))),
+            VBinaryOp::MinU => int_shape!(shape, S, U, N, zip::<U, N>(a, b, |x, y| x.min(y))),
+            VBinaryOp::MaxS => int_shape!(shape, S, U, N, zip::<S, N>(a, b, |x, y| x.max(y))),
+            VBinaryOp::MaxU => int_shape!(shape, S, U, N, zip::<U, N>(a, b, |x, y| x.max(y))),
+            VBinaryOp::AvgrU => int_shape!(
+                shape,
+                S,
+                U,
+                N,
+                zip::<U, N>(a, b, |x, y| ((x as u128 + y as u128 + 1) / 2) as U)
+            ),
+            VBinaryOp::Q15MulrSatS => zip::<i16, 8>(a, b, |x, y| {
+                ((x as i32 * y as i32 + 0x4000) >> 15).clamp(i16::MIN as i32, i16::MAX as i32)
+                    as i16
+            }),
+            VBinaryOp::DotS => {
+                let a = unpack::<i16, 8>(a);
+                let b = unpack::<i16, 8>(b);
+                pack::<i32, 4>(std::array::from_fn(|i| {
+                    (a[2 * i] as i32 * b[2 * i] as i32)
+                        .wrapping_add(a[2 * i + 1] as i32 * b[2 * i + 1] as i32)
+                }))
+            }
+
+            VBinaryOp::ExtMulLowS | VBinaryOp::ExtMulHighS => {
+                let high = matches!(self.op, VBinaryOp::ExtMulHighS);
+                match shape {
+                    VecShape::I16x8 => extmul::<i8, i16, 16, 8>(a, b, high as usize * 8, |x, y| {
+                        x as i16 * y as i16
+                    }),
+                    VecShape::I32x4 => extmul::<i16, i32, 8, 4>(a, b, high as usize * 4, |x, y| {
+                        x as i32 * y as i32
+                    }),
+                    VecShape::I64x2 => extmul::<i32, i64, 4, 2>(a, b, high as usize * 2, |x, y| {
+                        x as i64 * y as i64
+                    }),
+                    _ => return Err(InterpreterError::InvalidType),
+                }
+            }
+            VBinaryOp::ExtMulLowU | VBinaryOp::ExtMulHighU => {
+                let high = matches!(self.op, VBinaryOp::ExtMulHighU);
+                match shape {
+                    VecShape::I16x8 => extmul::<u8, u16, 16, 8>(a, b, high as usize * 8, |x, y| {
+                        x as u16 * y as u16
+                    }),
+                    VecShape::I32x4 => extmul::<u16, u32, 8, 4>(a, b, high as usize * 4, |x, y| {
+                        x as u32 * y as u32
+                    }),
+                    VecShape::I64x2 => extmul::<u32, u64, 4, 2>(a, b, high as usize * 2, |x, y| {
+                        x as u64 * y as u64
+                    }),
+                    _ => return Err(InterpreterError::InvalidType),
+                }
+            }
+
+            VBinaryOp::NarrowS => match shape {
+                VecShape::I8x16 => {
+                    narrow::<i16, i8, 8>(a, b, |x| x.clamp(i8::MIN as i16, i8::MAX as i16) as i8)
+                }
+                _ => narrow::<i32, i16, 4>(a, b, |x| {
+                    x.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+                }),
+            },
+            VBinaryOp::NarrowU => match shape {
+                VecShape::I8x16 => narrow::<i16, u8, 8>(a, b, |x| x.clamp(0, u8::MAX as i16) as u8),
+                _ => narrow::<i32, u16, 4>(a, b, |x| x.clamp(0, u16::MAX as i32) as u16),
+            },
+        };
+
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
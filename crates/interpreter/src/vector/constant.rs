@@ -0,0 +1,27 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{
+    instructions::{VBitselectInstruction, VConstInstruction},
+    objects::value::ValueRaw,
+};
+
+impl Executable for VConstInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, ValueRaw::v128(self.imm));
+        Ok(())
+    }
+}
+
+impl Executable for VBitselectInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let in1 = u128::from_le_bytes(stack_frame.vars.get(self.in1).as_v128());
+        let in2 = u128::from_le_bytes(stack_frame.vars.get(self.in2).as_v128());
+        let mask = u128::from_le_bytes(stack_frame.vars.get(self.mask).as_v128());
+        let res = (in1 & mask) | (in2 & !mask);
+        stack_frame
+            .vars
+            .set(self.out1, ValueRaw::v128(res.to_le_bytes()));
+        Ok(())
+    }
+}
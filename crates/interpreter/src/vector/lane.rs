@@ -0,0 +1,72 @@
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{
+    instructions::{
+        VExtractLaneInstruction, VReplaceLaneInstruction, VShuffleInstruction, VSplatInstruction,
+    },
+    objects::value::ValueRaw,
+};
+
+impl Executable for VShuffleInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let lhs = stack_frame.vars.get(self.lhs).as_v128();
+        let rhs = stack_frame.vars.get(self.rhs).as_v128();
+        let res = self.lanes.map(|lane| match lane {
+            0..=15 => lhs[lane as usize],
+            _ => rhs[lane as usize - 16],
+        });
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
+
+// scalars are stored little endian in the low bytes of a ValueRaw, so lanes can be copied bytewise
+impl Executable for VSplatInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let scalar = stack_frame.vars.get(self.in1).as_v128();
+        let lane_bytes = self.shape.lane_bytes();
+        let mut res = [0u8; 16];
+        for lane in res.chunks_exact_mut(lane_bytes) {
+            lane.copy_from_slice(&scalar[..lane_bytes]);
+        }
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
+
+impl Executable for VExtractLaneInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let vector = stack_frame.vars.get(self.in1).as_v128();
+        let lane_bytes = self.shape.lane_bytes();
+        let lane = &vector[self.lane as usize * lane_bytes..(self.lane as usize + 1) * lane_bytes];
+
+        let res = match (self.signed, lane_bytes) {
+            (Some(true), 1) => ValueRaw::i32(lane[0] as i8 as i32),
+            (Some(false), 1) => ValueRaw::u32(lane[0] as u32),
+            (Some(true), 2) => ValueRaw::i32(i16::from_le_bytes([lane[0], lane[1]]) as i32),
+            (Some(false), 2) => ValueRaw::u32(u16::from_le_bytes([lane[0], lane[1]]) as u32),
+            _ => {
+                let mut scalar = [0u8; 16];
+                scalar[..lane_bytes].copy_from_slice(lane);
+                ValueRaw::v128(scalar)
+            }
+        };
+        stack_frame.vars.set(self.out1, res);
+        Ok(())
+    }
+}
+
+impl Executable for VReplaceLaneInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let mut res = stack_frame.vars.get(self.in1).as_v128();
+        let scalar = stack_frame.vars.get(self.value_in).as_v128();
+        let lane_bytes = self.shape.lane_bytes();
+        let lane_start = self.lane as usize * lane_bytes;
+        res[lane_start..lane_start + lane_bytes].copy_from_slice(&scalar[..lane_bytes]);
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use super::{pack, widen, Lane};
+use crate::memory::{effective_address, memory_slice};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{
+    instructions::{
+        VLoadInstruction, VLoadLaneInstruction, VStoreInstruction, VStoreLaneInstruction,
+    },
+    objects::value::ValueRaw,
+};
+use wasm_types::VecLoadOp;
+
+fn load_splat<T: Lane, const N: usize>(bytes: &[u8]) -> [u8; 16] {
+    pack([T::read(bytes); N])
+}
+
+impl Executable for VLoadInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let addr = effective_address(ctx, self.addr, &self.memarg)?;
+        log::trace!("Vector Load: {:#?}", self);
+
+        let num_bytes_to_load = match self.operation {
+            VecLoadOp::Load128 => 16,
+            VecLoadOp::Load8Splat => 1,
+            VecLoadOp::Load16Splat => 2,
+            VecLoadOp::Load32Splat | VecLoadOp::Load32Zero => 4,
+            _ => 8,
+        };
+        let bytes = memory_slice(ctx, self.memarg.memory, addr, num_bytes_to_load)?;
+        let mut padded = [0u8; 16];
+        padded[..num_bytes_to_load].copy_from_slice(bytes);
+
+        let res = match self.operation {
+            VecLoadOp::Load128 | VecLoadOp::Load32Zero | VecLoadOp::Load64Zero => padded,
+            VecLoadOp::Load8x8S => widen::<i8, i16, 16, 8>(padded, 0, |x| x as i16),
+            VecLoadOp::Load8x8U => widen::<u8, u16, 16, 8>(padded, 0, |x| x as u16),
+            VecLoadOp::Load16x4S => widen::<i16, i32, 8, 4>(padded, 0, |x| x as i32),
+            VecLoadOp::Load16x4U => widen::<u16, u32, 8, 4>(padded, 0, |x| x as u32),
+            VecLoadOp::Load32x2S => widen::<i32, i64, 4, 2>(padded, 0, |x| x as i64),
+            VecLoadOp::Load32x2U => widen::<u32, u64, 4, 2>(padded, 0, |x| x as u64),
+            VecLoadOp::Load8Splat => load_splat::<u8, 16>(&padded[..1]),
+            VecLoadOp::Load16Splat => load_splat::<u16, 8>(&padded[..2]),
+            VecLoadOp::Load32Splat => load_splat::<u32, 4>(&padded[..4]),
+            VecLoadOp::Load64Splat => load_splat::<u64, 2>(&padded[..8]),
+        };
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
+
+impl Executable for VStoreInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let addr = effective_address(ctx, self.addr_in, &self.memarg)?;
+        log::trace!("Vector Store: {:#?}", self);
+        let value = ctx.stack.last_mut().unwrap().vars.get(self.value_in);
+        memory_slice(ctx, self.memarg.memory, addr, 16)?.copy_from_slice(&value.as_v128());
+        Ok(())
+    }
+}
+
+impl Executable for VLoadLaneInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let addr = effective_address(ctx, self.addr, &self.memarg)?;
+        let lane_bytes = self.shape.lane_bytes();
+        let lane_start = self.lane as usize * lane_bytes;
+
+        let mut res = ctx.stack.last_mut().unwrap().vars.get(self.in1).as_v128();
+        res[lane_start..lane_start + lane_bytes].copy_from_slice(memory_slice(
+            ctx,
+            self.memarg.memory,
+            addr,
+            lane_bytes,
+        )?);
+
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
+
+impl Executable for VStoreLaneInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let addr = effective_address(ctx, self.addr_in, &self.memarg)?;
+        let lane_bytes = self.shape.lane_bytes();
+        let lane_start = self.lane as usize * lane_bytes;
+
+        let value = ctx
+            .stack
+            .last_mut()
+            .unwrap()
+            .vars
+            .get(self.value_in)
+            .as_v128();
+        memory_slice(ctx, self.memarg.memory, addr, lane_bytes)?
+            .copy_from_slice(&value[lane_start..lane_start + lane_bytes]);
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+use module::instructions::{
+    Instruction, VBinaryInstruction, VBitselectInstruction, VConstInstruction,
+    VExtractLaneInstruction, VLoadInstruction, VLoadLaneInstruction, VRelationalInstruction,
+    VReplaceLaneInstruction, VShiftInstruction, VShuffleInstruction, VSplatInstruction,
+    VStoreInstruction, VStoreLaneInstruction, VTestInstruction, VUnaryInstruction,
+};
+use wasm_types::{InstructionType, VectorInstructionCategory};
+
+use crate::{Executable, InterpreterContext, InterpreterError};
+
+mod binary;
+mod constant;
+mod lane;
+mod memory;
+mod relational;
+mod shift;
+mod test;
+mod unary;
+
+pub(crate) fn execute_vector_instruction(
+    ctx: &mut InterpreterContext,
+    instruction_category: VectorInstructionCategory,
+    t: InstructionType,
+) -> Result<(), InterpreterError> {
+    let i = &mut ctx.stack.last_mut().unwrap().decoder;
+
+    log::trace!("Vector instruction: {:#?}", instruction_category);
+
+    match instruction_category {
+        VectorInstructionCategory::Constant => VConstInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::Load(_) => VLoadInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::Store => VStoreInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::LoadLane(_) => {
+            VLoadLaneInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::StoreLane(_) => {
+            VStoreLaneInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::Shuffle => VShuffleInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::Splat(_) => VSplatInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::ExtractLane(_)
+        | VectorInstructionCategory::ExtractLaneS(_)
+        | VectorInstructionCategory::ExtractLaneU(_) => {
+            VExtractLaneInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::ReplaceLane(_) => {
+            VReplaceLaneInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::Unary(..) => VUnaryInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::Binary(..) => {
+            VBinaryInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::Bitselect => {
+            VBitselectInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::Relational(..) => {
+            VRelationalInstruction::deserialize(i, t)?.execute(ctx)
+        }
+        VectorInstructionCategory::Shift(..) => VShiftInstruction::deserialize(i, t)?.execute(ctx),
+        VectorInstructionCategory::Test(..) => VTestInstruction::deserialize(i, t)?.execute(ctx),
+    }
+}
+
+/// A scalar that can be packed into / unpacked from a 128 bit vector lane.
+pub(super) trait Lane: Copy + Default {
+    const BYTES: usize;
+    fn read(bytes: &[u8]) -> Self;
+    fn write(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_lane {
+    ($($t:ty),*) => {
+        $(
+            impl Lane for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+                fn read(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().unwrap())
+                }
+                fn write(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes())
+                }
+            }
+        )*
+    };
+}
+impl_lane!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+pub(super) fn unpack<T: Lane, const N: usize>(v: [u8; 16]) -> [T; N] {
+    let mut lanes = [T::default(); N];
+    for (idx, lane) in lanes.iter_mut().enumerate() {
+        *lane = T::read(&v[idx * T::BYTES..(idx + 1) * T::BYTES]);
+    }
+    lanes
+}
+
+pub(super) fn pack<T: Lane, const N: usize>(lanes: [T; N]) -> [u8; 16] {
+    let mut v = [0; 16];
+    for (idx, lane) in lanes.iter().enumerate() {
+        lane.write(&mut v[idx * T::BYTES..(idx + 1) * T::BYTES]);
+    }
+    v
+}
+
+pub(super) fn map<T: Lane, const N: usize>(v: [u8; 16], f: impl Fn(T) -> T) -> [u8; 16] {
+    pack::<T, N>(unpack::<T, N>(v).map(f))
+}
+
+pub(super) fn zip<T: Lane, const N: usize>(
+    a: [u8; 16],
+    b: [u8; 16],
+    f: impl Fn(T, T) -> T,
+) -> [u8; 16] {
+    let a = unpack::<T, N>(a);
+    let b = unpack::<T, N>(b);
+    let mut out = [T::default(); N];
+    for idx in 0..N {
+        out[idx] = f(a[idx], b[idx]);
+    }
+    pack(out)
+}
+
+/// Converts `N` lanes starting at lane `offset` of `v` to the (wider) lane type `D`.
+pub(super) fn widen<S: Lane, D: Lane, const SN: usize, const N: usize>(
+    v: [u8; 16],
+    offset: usize,
+    f: impl Fn(S) -> D,
+) -> [u8; 16] {
+    let src = unpack::<S, SN>(v);
+    let mut out = [D::default(); N];
+    for idx in 0..N {
+        out[idx] = f(src[offset + idx]);
+    }
+    pack(out)
+}
+
+/// Dispatches over the integer shapes. `$s` / `$u` are bound to the signed / unsigned lane type
+/// and `$n` to the number of lanes.
+macro_rules! int_shape {
+    ($shape:expr, $s:ident, $u:ident, $n:ident, $body:expr) => {
+        match $shape {
+            wasm_types::VecShape::I8x16 => {
+                #[allow(dead_code)]
+                type $s = i8;
+                #[allow(dead_code)]
+                type $u = u8;
+                const $n: usize = 16;
+                $body
+            }
+            wasm_types::VecShape::I16x8 => {
+                #[allow(dead_code)]
+                type $s = i16;
+                #[allow(dead_code)]
+                type $u = u16;
+                const $n: usize = 8;
+                $body
+            }
+            wasm_types::VecShape::I32x4 => {
+                #[allow(dead_code)]
+                type $s = i32;
+                #[allow(dead_code)]
+                type $u = u32;
+                const $n: usize = 4;
+                $body
+            }
+            wasm_types::VecShape::I64x2 => {
+                #[allow(dead_code)]
+                type $s = i64;
+                #[allow(dead_code)]
+                type $u = u64;
+                const $n: usize = 2;
+                $body
+            }
+            _ => return Err(crate::InterpreterError::InvalidType),
+        }
+    };
+}
+pub(crate) use int_shape;
+
+/// Dispatches over the float shapes. `$f` is bound to the float lane type, `$u` to the unsigned
+/// integer type of the same width and `$n` to the number of lanes.
+macro_rules! float_shape {
+    ($shape:expr, $f:ident, $u:ident, $n:ident, $body:expr) => {
+        match $shape {
+            wasm_types::VecShape::F32x4 => {
+                #[allow(dead_code)]
+                type $f = f32;
+                #[allow(dead_code)]
+                type $u = u32;
+                const $n: usize = 4;
+                $body
+            }
+            wasm_types::VecShape::F64x2 => {
+                #[allow(dead_code)]
+                type $f = f64;
+                #[allow(dead_code)]
+                type $u = u64;
+                const $n: usize = 2;
+                $body
+            }
+            _ => return Err(crate::InterpreterError::InvalidType),
+        }
+    };
+}
+pub(crate) use float_shape;
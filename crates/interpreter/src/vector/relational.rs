@@ -0,0 +1,65 @@
+use super::{float_shape, int_shape, pack, unpack, Lane};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::VRelationalInstruction, objects::value::ValueRaw};
+use wasm_types::VRelationalOp;
+
+/// Compares the lanes of `a` and `b`, producing an all-ones mask lane (`ones`) for true results.
+fn compare<T: Lane, M: Lane, const N: usize>(
+    a: [u8; 16],
+    b: [u8; 16],
+    ones: M,
+    cmp: impl Fn(T, T) -> bool,
+) -> [u8; 16] {
+    let a = unpack::<T, N>(a);
+    let b = unpack::<T, N>(b);
+    pack::<M, N>(std::array::from_fn(|i| {
+        if cmp(a[i], b[i]) {
+            ones
+        } else {
+            M::default()
+        }
+    }))
+}
+
+impl Executable for VRelationalInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let a = stack_frame.vars.get(self.lhs).as_v128();
+        let b = stack_frame.vars.get(self.rhs).as_v128();
+        let shape = self.shape;
+
+        let res = if shape.is_float() {
+            float_shape!(shape, F, U, N, {
+                let cmp: fn(F, F) -> bool = match self.op {
+                    VRelationalOp::Eq => |x, y| x == y,
+                    VRelationalOp::Ne => |x, y| x != y,
+                    VRelationalOp::Lt => |x, y| x < y,
+                    VRelationalOp::Gt => |x, y| x > y,
+                    VRelationalOp::Le => |x, y| x <= y,
+                    VRelationalOp::Ge => |x, y| x >= y,
+                    _ => return Err(InterpreterError::InvalidType),
+                };
+                compare::<F, U, N>(a, b, U::MAX, cmp)
+            })
+        } else {
+            int_shape!(shape, S, U, N, {
+                match self.op {
+                    VRelationalOp::Eq => compare::<U, U, N>(a, b, U::MAX, |x, y| x == y),
+                    VRelationalOp::Ne => compare::<U, U, N>(a, b, U::MAX, |x, y| x != y),
+                    VRelationalOp::LtS => compare::<S, U, N>(a, b, U::MAX, |x, y| x < y),
+                    VRelationalOp::LtU => compare::<U, U, N>(a, b, U::MAX, |x, y| x < y),
+                    VRelationalOp::GtS => compare::<S, U, N>(a, b, U::MAX, |x, y| x > y),
+                    VRelationalOp::GtU => compare::<U, U, N>(a, b, U::MAX, |x, y| x > y),
+                    VRelationalOp::LeS => compare::<S, U, N>(a, b, U::MAX, |x, y| x <= y),
+                    VRelationalOp::LeU => compare::<U, U, N>(a, b, U::MAX, |x, y| x <= y),
+                    VRelationalOp::GeS => compare::<S, U, N>(a, b, U::MAX, |x, y| x >= y),
+                    VRelationalOp::GeU => compare::<U, U, N>(a, b, U::MAX, |x, y| x >= y),
+                    _ => return Err(InterpreterError::InvalidType),
+                }
+            })
+        };
+
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
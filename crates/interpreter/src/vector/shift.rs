@@ -0,0 +1,24 @@
+use super::{int_shape, map};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::VShiftInstruction, objects::value::ValueRaw};
+use wasm_types::VShiftOp;
+
+impl Executable for VShiftInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let v = stack_frame.vars.get(self.in1).as_v128();
+        // wrapping shifts mask the amount with the lane width, as required by the spec
+        let amount = stack_frame.vars.get(self.amount).as_u32();
+
+        let res = int_shape!(self.shape, S, U, N, {
+            match self.op {
+                VShiftOp::Shl => map::<U, N>(v, |x| x.wrapping_shl(amount)),
+                VShiftOp::ShrS => map::<S, N>(v, |x| x.wrapping_shr(amount)),
+                VShiftOp::ShrU => map::<U, N>(v, |x| x.wrapping_shr(amount)),
+            }
+        });
+
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
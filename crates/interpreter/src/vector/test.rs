@@ -0,0 +1,33 @@
+use super::{int_shape, unpack};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::VTestInstruction, objects::value::ValueRaw};
+use wasm_types::VTestOp;
+
+impl Executable for VTestInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let v = stack_frame.vars.get(self.in1).as_v128();
+
+        let res = match self.op {
+            VTestOp::AnyTrue => (u128::from_le_bytes(v) != 0) as u32,
+            VTestOp::AllTrue => {
+                int_shape!(
+                    self.shape,
+                    S,
+                    U,
+                    N,
+                    unpack::<U, N>(v).iter().all(|l| *l != 0) as u32
+                )
+            }
+            VTestOp::Bitmask => int_shape!(self.shape, S, U, N, {
+                unpack::<S, N>(v)
+                    .iter()
+                    .enumerate()
+                    .fold(0u32, |mask, (i, l)| mask | (((*l < 0) as u32) << i))
+            }),
+        };
+
+        stack_frame.vars.set(self.out1, ValueRaw::u32(res));
+        Ok(())
+    }
+}
@@ -0,0 +1,92 @@
+use super::{float_shape, int_shape, map, pack, unpack, widen};
+use crate::{Executable, InterpreterContext, InterpreterError};
+use module::{instructions::VUnaryInstruction, objects::value::ValueRaw};
+use wasm_types::{VUnaryOp, VecShape};
+
+impl Executable for VUnaryInstruction {
+    fn execute(&mut self, ctx: &mut InterpreterContext) -> Result<(), InterpreterError> {
+        let stack_frame = ctx.stack.last_mut().unwrap();
+        let v = stack_frame.vars.get(self.in1).as_v128();
+
+        let res = match (&self.op, self.shape) {
+            (VUnaryOp::Not, _) => (!u128::from_le_bytes(v)).to_le_bytes(),
+
+            (VUnaryOp::Abs, shape) if !shape.is_float() => {
+                int_shape!(shape, S, U, N, map::<S, N>(v, |a| a.wrapping_abs()))
+            }
+            (VUnaryOp::Neg, shape) if !shape.is_float() => {
+                int_shape!(shape, S, U, N, map::<S, N>(v, |a| a.wrapping_neg()))
+            }
+            (VUnaryOp::Popcnt, _) => map::<u8, 16>(v, |a| a.count_ones() as u8),
+
+            (VUnaryOp::Abs, shape) => float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.abs())),
+            (VUnaryOp::Neg, shape) => float_shape!(shape, F, U, N, map::<F, N>(v, |a| -a)),
+            (VUnaryOp::Sqrt, shape) => float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.sqrt())),
+            (VUnaryOp::Ceil, shape) => float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.ceil())),
+            (VUnaryOp::Floor, shape) => {
+                float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.floor()))
+            }
+            (VUnaryOp::Trunc, shape) => {
+                float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.trunc()))
+            }
+            (VUnaryOp::Nearest, shape) => {
+                float_shape!(shape, F, U, N, map::<F, N>(v, |a| a.round_ties_even()))
+            }
+
+            (VUnaryOp::ExtendLowS, VecShape::I16x8) => widen::<i8, i16, 16, 8>(v, 0, |a| a as i16),
+            (VUnaryOp::ExtendHighS, VecShape::I16x8) => widen::<i8, i16, 16, 8>(v, 8, |a| a as i16),
+            (VUnaryOp::ExtendLowU, VecShape::I16x8) => widen::<u8, u16, 16, 8>(v, 0, |a| a as u16),
+            (VUnaryOp::ExtendHighU, VecShape::I16x8) => widen::<u8, u16, 16, 8>(v, 8, |a| a as u16),
+            (VUnaryOp::ExtendLowS, VecShape::I32x4) => widen::<i16, i32, 8, 4>(v, 0, |a| a as i32),
+            (VUnaryOp::ExtendHighS, VecShape::I32x4) => widen::<i16, i32, 8, 4>(v, 4, |a| a as i32),
+            (VUnaryOp::ExtendLowU, VecShape::I32x4) => widen::<u16, u32, 8, 4>(v, 0, |a| a as u32),
+            (VUnaryOp::ExtendHighU, VecShape::I32x4) => widen::<u16, u32, 8, 4>(v, 4, |a| a as u32),
+            (VUnaryOp::ExtendLowS, VecShape::I64x2) => widen::<i32, i64, 4, 2>(v, 0, |a| a as i64),
+            (VUnaryOp::ExtendHighS, VecShape::I64x2) => widen::<i32, i64, 4, 2>(v, 2, |a| a as i64),
+            (VUnaryOp::ExtendLowU, VecShape::I64x2) => widen::<u32, u64, 4, 2>(v, 0, |a| a as u64),
+            (VUnaryOp::ExtendHighU, VecShape::I64x2) => widen::<u32, u64, 4, 2>(v, 2, |a| a as u64),
+
+            (VUnaryOp::ExtAddPairwiseS, VecShape::I16x8) => {
+                let src = unpack::<i8, 16>(v);
+                pack::<i16, 8>(std::array::from_fn(|i| {
+                    src[2 * i] as i16 + src[2 * i + 1] as i16
+                }))
+            }
+            (VUnaryOp::ExtAddPairwiseU, VecShape::I16x8) => {
+                let src = unpack::<u8, 16>(v);
+                pack::<u16, 8>(std::array::from_fn(|i| {
+                    src[2 * i] as u16 + src[2 * i + 1] as u16
+                }))
+            }
+            (VUnaryOp::ExtAddPairwiseS, VecShape::I32x4) => {
+                let src = unpack::<i16, 8>(v);
+                pack::<i32, 4>(std::array::from_fn(|i| {
+                    src[2 * i] as i32 + src[2 * i + 1] as i32
+                }))
+            }
+            (VUnaryOp::ExtAddPairwiseU, VecShape::I32x4) => {
+                let src = unpack::<u16, 8>(v);
+                pack::<u32, 4>(std::array::from_fn(|i| {
+                    src[2 * i] as u32 + src[2 * i + 1] as u32
+                }))
+            }
+
+            // `as` casts from float to int saturate and map NaN to 0, exactly like trunc_sat
+            (VUnaryOp::TruncSatS, _) => widen::<f32, i32, 4, 4>(v, 0, |a| a as i32),
+            (VUnaryOp::TruncSatU, _) => widen::<f32, u32, 4, 4>(v, 0, |a| a as u32),
+            (VUnaryOp::TruncSatZeroS, _) => widen::<f64, i32, 2, 2>(v, 0, |a| a as i32),
+            (VUnaryOp::TruncSatZeroU, _) => widen::<f64, u32, 2, 2>(v, 0, |a| a as u32),
+            (VUnaryOp::ConvertS, _) => widen::<i32, f32, 4, 4>(v, 0, |a| a as f32),
+            (VUnaryOp::ConvertU, _) => widen::<u32, f32, 4, 4>(v, 0, |a| a as f32),
+            (VUnaryOp::ConvertLowS, _) => widen::<i32, f64, 4, 2>(v, 0, |a| a as f64),
+            (VUnaryOp::ConvertLowU, _) => widen::<u32, f64, 4, 2>(v, 0, |a| a as f64),
+            (VUnaryOp::DemoteZero, _) => widen::<f64, f32, 2, 2>(v, 0, |a| a as f32),
+            (VUnaryOp::PromoteLow, _) => widen::<f32, f64, 4, 2>(v, 0, |a| a as f64),
+
+            _ => return Err(InterpreterError::InvalidType),
+        };
+
+        stack_frame.vars.set(self.out1, ValueRaw::v128(res));
+        Ok(())
+    }
+}
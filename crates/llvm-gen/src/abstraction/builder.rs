@@ -1,27 +1,37 @@
-use super::{context::Context, function::Function, module::Module};
+use super::{
+    context::Context,
+    function::Function,
+    module::{Module, INTERNAL_CALL_CONV},
+};
 use crate::util::c_str;
 use llvm_sys::{
     core::{
         LLVMAddIncoming, LLVMAppendBasicBlockInContext, LLVMArrayType2, LLVMBuildAdd,
-        LLVMBuildAggregateRet, LLVMBuildAlloca, LLVMBuildBitCast, LLVMBuildBr, LLVMBuildCall2,
-        LLVMBuildCondBr, LLVMBuildFCmp, LLVMBuildFNeg, LLVMBuildFPCast, LLVMBuildFPToSI,
-        LLVMBuildFPToUI, LLVMBuildGEP2, LLVMBuildICmp, LLVMBuildIntCast2, LLVMBuildLoad2,
-        LLVMBuildMul, LLVMBuildPhi, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildSIToFP,
-        LLVMBuildSelect, LLVMBuildStore, LLVMBuildSub, LLVMBuildSwitch, LLVMBuildUIToFP,
-        LLVMBuildUnreachable, LLVMConstBitCast, LLVMConstInt, LLVMConstNull, LLVMConstReal,
-        LLVMCreateBuilderInContext, LLVMDisposeBuilder, LLVMDoubleTypeInContext,
-        LLVMFloatTypeInContext, LLVMInt16TypeInContext, LLVMInt1Type, LLVMInt32TypeInContext,
-        LLVMInt64TypeInContext, LLVMInt8TypeInContext, LLVMIntTypeInContext,
-        LLVMMDStringInContext2, LLVMMetadataAsValue, LLVMMetadataTypeInContext, LLVMPointerType,
-        LLVMPointerTypeInContext, LLVMPositionBuilderAtEnd, LLVMStructTypeInContext, LLVMTypeOf,
+        LLVMBuildAggregateRet, LLVMBuildAlloca, LLVMBuildAtomicCmpXchg, LLVMBuildAtomicRMW,
+        LLVMBuildBinOp, LLVMBuildBitCast, LLVMBuildBr, LLVMBuildCall2, LLVMBuildCondBr,
+        LLVMBuildExtractElement, LLVMBuildExtractValue, LLVMBuildFCmp, LLVMBuildFNeg,
+        LLVMBuildFPCast, LLVMBuildFPToSI, LLVMBuildFPToUI, LLVMBuildFence, LLVMBuildGEP2,
+        LLVMBuildICmp, LLVMBuildInsertElement, LLVMBuildIntCast2, LLVMBuildLoad2, LLVMBuildMul,
+        LLVMBuildPhi, LLVMBuildRet, LLVMBuildRetVoid, LLVMBuildSIToFP, LLVMBuildSelect,
+        LLVMBuildShuffleVector, LLVMBuildStore, LLVMBuildSub, LLVMBuildSwitch, LLVMBuildUIToFP,
+        LLVMBuildUnreachable, LLVMConstBitCast, LLVMConstInt, LLVMConstIntOfArbitraryPrecision,
+        LLVMConstNull, LLVMConstReal, LLVMConstVector, LLVMCreateBuilderInContext,
+        LLVMDisposeBuilder, LLVMDoubleTypeInContext, LLVMFloatTypeInContext, LLVMGetPoison,
+        LLVMGetReturnType, LLVMGetVectorSize, LLVMInt16TypeInContext, LLVMInt1Type,
+        LLVMInt32TypeInContext, LLVMInt64TypeInContext, LLVMInt8TypeInContext,
+        LLVMIntTypeInContext, LLVMMDStringInContext2, LLVMMetadataAsValue,
+        LLVMMetadataTypeInContext, LLVMPointerType, LLVMPointerTypeInContext,
+        LLVMPositionBuilderAtEnd, LLVMSetAlignment, LLVMSetInstructionCallConv, LLVMSetOrdering,
+        LLVMSetTailCallKind, LLVMStructTypeInContext, LLVMTypeOf, LLVMVectorType,
         LLVMVoidTypeInContext,
     },
     prelude::{LLVMBasicBlockRef, LLVMBuilderRef, LLVMContextRef, LLVMTypeRef, LLVMValueRef},
-    LLVMIntPredicate, LLVMRealPredicate,
+    LLVMAtomicOrdering, LLVMAtomicRMWBinOp, LLVMIntPredicate, LLVMOpcode, LLVMRealPredicate,
+    LLVMTailCallKind,
 };
 use module::objects::value::ValueRaw;
 use std::{rc::Rc, sync::OnceLock};
-use wasm_types::{NumType, ValType};
+use wasm_types::{NumType, ValType, VecShape};
 
 struct LLVMTypeWrapper(LLVMTypeRef);
 unsafe impl Send for LLVMTypeWrapper {}
@@ -141,12 +151,30 @@ impl Builder {
             .0
     }
 
+    /// v128 values are kept as plain 128-bit integers and only bitcast to lane vectors on use.
+    /// Loads / stores of integers never require 16-byte alignment, unlike `<N x T>` vectors.
     pub(crate) fn vec(&self) -> LLVMTypeRef {
         self.VEC
-            .get_or_init(|| unsafe { LLVMTypeWrapper(LLVMPointerTypeInContext(self.context, 0)) })
+            .get_or_init(|| unsafe { LLVMTypeWrapper(LLVMIntTypeInContext(self.context, 128)) })
             .0
     }
 
+    pub(crate) fn lane_vec(&self, shape: VecShape) -> LLVMTypeRef {
+        let lane_ty = match shape {
+            VecShape::I8x16 => self.i8(),
+            VecShape::I16x8 => self.i16(),
+            VecShape::I32x4 => self.i32(),
+            VecShape::I64x2 => self.i64(),
+            VecShape::F32x4 => self.f32(),
+            VecShape::F64x2 => self.f64(),
+        };
+        self.vector(lane_ty, shape.lane_count() as u32)
+    }
+
+    pub(crate) fn vector(&self, elem_ty: LLVMTypeRef, len: u32) -> LLVMTypeRef {
+        unsafe { LLVMVectorType(elem_ty, len) }
+    }
+
     pub(crate) fn value_raw_ty(&self) -> LLVMTypeRef {
         unsafe { LLVMIntTypeInContext(self.context, (std::mem::size_of::<ValueRaw>() * 8) as u32) }
     }
@@ -194,6 +222,10 @@ impl Builder {
         }
     }
 
+    pub(crate) fn const_null_ptr(&self) -> LLVMValueRef {
+        unsafe { LLVMConstNull(self.ptr()) }
+    }
+
     pub(crate) fn const_i32(&self, i: u32) -> LLVMValueRef {
         unsafe { LLVMConstInt(self.i32(), i as u64, false.into()) }
     }
@@ -214,6 +246,16 @@ impl Builder {
     pub(crate) fn const_i1(&self, i: bool) -> LLVMValueRef {
         unsafe { LLVMConstInt(self.i1(), i as u64, false.into()) }
     }
+    pub(crate) fn const_v128(&self, bytes: [u8; 16]) -> LLVMValueRef {
+        let v = u128::from_le_bytes(bytes);
+        let words = [v as u64, (v >> 64) as u64];
+        unsafe { LLVMConstIntOfArbitraryPrecision(self.vec(), 2, words.as_ptr()) }
+    }
+    /// Constant `<N x i32>` vector, as required for shufflevector masks.
+    pub(crate) fn const_i32_vec(&self, elems: &[u32]) -> LLVMValueRef {
+        let mut elems: Vec<_> = elems.iter().map(|e| self.const_i32(*e)).collect();
+        unsafe { LLVMConstVector(elems.as_mut_ptr(), elems.len() as u32) }
+    }
 
     pub(crate) fn build_gep(
         &self,
@@ -295,9 +337,151 @@ impl Builder {
         unsafe { LLVMBuildBitCast(self.get(), val, to_ty, c_str(name).as_ptr()) }
     }
 
-    pub(crate) fn build_store(&self, val: LLVMValueRef, ptr: LLVMValueRef) {
+    pub(crate) fn build_extract_element(
+        &self,
+        vec: LLVMValueRef,
+        idx: LLVMValueRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        unsafe { LLVMBuildExtractElement(self.get(), vec, idx, c_str(name).as_ptr()) }
+    }
+
+    pub(crate) fn build_insert_element(
+        &self,
+        vec: LLVMValueRef,
+        elem: LLVMValueRef,
+        idx: LLVMValueRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        unsafe { LLVMBuildInsertElement(self.get(), vec, elem, idx, c_str(name).as_ptr()) }
+    }
+
+    pub(crate) fn build_shuffle_vector(
+        &self,
+        v1: LLVMValueRef,
+        v2: LLVMValueRef,
+        mask: &[u32],
+        name: &str,
+    ) -> LLVMValueRef {
+        unsafe {
+            LLVMBuildShuffleVector(
+                self.get(),
+                v1,
+                v2,
+                self.const_i32_vec(mask),
+                c_str(name).as_ptr(),
+            )
+        }
+    }
+
+    /// Broadcasts a scalar into every lane of a vector of type `vec_ty`.
+    pub(crate) fn build_splat(
+        &self,
+        val: LLVMValueRef,
+        vec_ty: LLVMTypeRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        let len = unsafe { LLVMGetVectorSize(vec_ty) };
+        let vec = unsafe { LLVMGetPoison(vec_ty) };
+        let vec = self.build_insert_element(vec, val, self.const_i32(0), name);
+        self.build_shuffle_vector(vec, vec, &vec![0; len as usize], name)
+    }
+
+    pub(crate) fn build_binop(
+        &self,
+        op: LLVMOpcode,
+        lhs: LLVMValueRef,
+        rhs: LLVMValueRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        unsafe { LLVMBuildBinOp(self.get(), op, lhs, rhs, c_str(name).as_ptr()) }
+    }
+
+    pub(crate) fn build_store(&self, val: LLVMValueRef, ptr: LLVMValueRef) -> LLVMValueRef {
+        unsafe { LLVMBuildStore(self.get(), val, ptr) }
+    }
+
+    /// Sequentially consistent load of `ty`; `ptr` must be aligned to `align` bytes.
+    pub(crate) fn build_atomic_load(
+        &self,
+        ty: LLVMTypeRef,
+        ptr: LLVMValueRef,
+        align: u32,
+        name: &str,
+    ) -> LLVMValueRef {
+        let load = self.build_load(ty, ptr, name);
+        unsafe {
+            LLVMSetOrdering(
+                load,
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+            );
+            LLVMSetAlignment(load, align);
+        }
+        load
+    }
+
+    /// Sequentially consistent store; `ptr` must be aligned to `align` bytes.
+    pub(crate) fn build_atomic_store(&self, val: LLVMValueRef, ptr: LLVMValueRef, align: u32) {
+        let store = self.build_store(val, ptr);
         unsafe {
-            LLVMBuildStore(self.get(), val, ptr);
+            LLVMSetOrdering(
+                store,
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+            );
+            LLVMSetAlignment(store, align);
+        }
+    }
+
+    /// Sequentially consistent read-modify-write, returns the old value.
+    pub(crate) fn build_atomic_rmw(
+        &self,
+        op: LLVMAtomicRMWBinOp,
+        ptr: LLVMValueRef,
+        val: LLVMValueRef,
+    ) -> LLVMValueRef {
+        unsafe {
+            LLVMBuildAtomicRMW(
+                self.get(),
+                op,
+                ptr,
+                val,
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+                0,
+            )
+        }
+    }
+
+    /// Sequentially consistent compare-exchange, returns the old value.
+    pub(crate) fn build_atomic_cmpxchg(
+        &self,
+        ptr: LLVMValueRef,
+        expected: LLVMValueRef,
+        replacement: LLVMValueRef,
+        name: &str,
+    ) -> LLVMValueRef {
+        unsafe {
+            let res = LLVMBuildAtomicCmpXchg(
+                self.get(),
+                ptr,
+                expected,
+                replacement,
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+                0,
+            );
+            // { old value, success flag }
+            LLVMBuildExtractValue(self.get(), res, 0, c_str(name).as_ptr())
+        }
+    }
+
+    pub(crate) fn build_fence(&self) {
+        unsafe {
+            LLVMBuildFence(
+                self.get(),
+                LLVMAtomicOrdering::LLVMAtomicOrderingSequentiallyConsistent,
+                0,
+                c_str("").as_ptr(),
+            );
         }
     }
 
@@ -380,6 +564,42 @@ impl Builder {
         }
     }
 
+    /// Call a wasm function, which uses the internal calling convention.
+    pub(crate) fn build_internal_call(
+        &self,
+        func: &Function,
+        args: &mut [LLVMValueRef],
+        name: &str,
+    ) -> LLVMValueRef {
+        let call = self.build_call(func, args, name);
+        unsafe { LLVMSetInstructionCallConv(call, INTERNAL_CALL_CONV) };
+        call
+    }
+
+    /// Tail call a wasm function and return its results. The call is marked `musttail`, so it always reuses
+    /// the current stack frame.
+    pub(crate) fn build_tail_call(&self, func: &Function, args: &mut [LLVMValueRef]) {
+        let returns_void = unsafe { LLVMGetReturnType(func.r#type()) } == self.void();
+        let call =
+            self.build_internal_call(func, args, if returns_void { "" } else { "tail_call_res" });
+        unsafe { LLVMSetTailCallKind(call, LLVMTailCallKind::LLVMTailCallKindMustTail) };
+        if returns_void {
+            self.build_ret_void();
+        } else {
+            self.build_ret(call);
+        }
+    }
+
+    /// Return from `func` without meaningful results, e.g. while an exception unwinds the stack.
+    pub(crate) fn build_ret_poison(&self, func: &Function) {
+        let return_ty = unsafe { LLVMGetReturnType(func.r#type()) };
+        if return_ty == self.void() {
+            self.build_ret_void();
+        } else {
+            self.build_ret(unsafe { LLVMGetPoison(return_ty) });
+        }
+    }
+
     pub(crate) fn build_unreachable(&self) {
         let intrinsic_function = self
             .module
@@ -632,6 +852,20 @@ impl Builder {
         )
     }
 
+    pub(crate) fn call_intrinsic_raw(
+        &self,
+        param_tys: &mut [LLVMTypeRef],
+        params: &mut [LLVMValueRef],
+        ret_ty: LLVMTypeRef,
+        intrinsic_name: &str,
+    ) -> LLVMValueRef {
+        let intrinsic_function = self
+            .module
+            .get_intrinsic_func(intrinsic_name, param_tys, ret_ty)
+            .unwrap();
+        self.build_call(&intrinsic_function, params, "call_intrinsic")
+    }
+
     pub(crate) fn call_binary_intrinsic_raw(
         &self,
         mut param_tys: [LLVMTypeRef; 2],
@@ -1,10 +1,8 @@
 use super::module::Module;
 use crate::abstraction::target_machine::TargetMachine;
 use crate::util::c_str;
-use crate::ExecutionError;
-use llvm_sys::core::{
-    LLVMCreateMemoryBufferWithMemoryRangeCopy, LLVMGetBufferSize, LLVMGetBufferStart,
-};
+use crate::{ExecutionError, OptLevel, TargetInfo};
+use llvm_sys::core::LLVMCreateMemoryBufferWithMemoryRangeCopy;
 use llvm_sys::error::{LLVMCreateStringError, LLVMErrorRef};
 use llvm_sys::execution_engine::LLVMLinkInMCJIT;
 use llvm_sys::orc2::ee::LLVMOrcCreateRTDyldObjectLinkingLayerWithSectionMemoryManager;
@@ -27,8 +25,7 @@ use llvm_sys::orc2::{
     LLVMOrcSymbolStringPoolEntryStr,
 };
 use llvm_sys::target_machine::{
-    LLVMCodeGenFileType, LLVMGetDefaultTargetTriple, LLVMGetTargetFromTriple, LLVMTargetHasJIT,
-    LLVMTargetMachineEmitToMemoryBuffer, LLVMTargetRef,
+    LLVMGetDefaultTargetTriple, LLVMGetTargetFromTriple, LLVMTargetHasJIT, LLVMTargetRef,
 };
 use module::objects::value::ValueRaw;
 use runtime_interface::RawPointer;
@@ -53,6 +50,9 @@ pub(crate) struct JITExecutionEngine {
     // keep modules alive until the engine is dropped
     modules: Vec<Rc<Module>>,
 
+    // code generation settings of the JIT, reused when emitting object files
+    opt_level: OptLevel,
+
     // keep symbol resolution context for cleanup on destruction
     _symbol_gen_ctxt: *mut DynamicSymbolResolverContext,
 }
@@ -76,8 +76,7 @@ impl JITExecutionEngine {
 
     /// Add object file to the JIT compiler
     ///
-    /// # Warning
-    /// Does not take ownership of the object file buffer. Buffer must be kept alive until the JIT compiler is dropped.
+    /// Note: The object file is copied, so the buffer doesn't have to outlive the JIT compiler.
     pub(crate) fn add_object_file(&mut self, obj_file: &[u8]) -> Result<(), ExecutionError> {
         let main_dylib = unsafe { LLVMOrcLLJITGetMainJITDylib(self.jit) };
         let resource_tracker = unsafe { LLVMOrcJITDylibCreateResourceTracker(main_dylib) };
@@ -96,35 +95,6 @@ impl JITExecutionEngine {
         Ok(())
     }
 
-    pub(crate) fn get_module_as_object_buffer(
-        &self,
-        module_idx: usize,
-    ) -> Result<&[u8], ExecutionError> {
-        let module = self
-            .modules
-            .get(module_idx)
-            .ok_or(ExecutionError::ModuleNotFound(module_idx))?;
-
-        let mut memory_buf = MaybeUninit::uninit();
-        let mut error = MaybeUninit::uninit();
-        if 0 != unsafe {
-            LLVMTargetMachineEmitToMemoryBuffer(
-                TargetMachine::create_default()?.into_raw(),
-                module.get(),
-                LLVMCodeGenFileType::LLVMObjectFile,
-                error.as_mut_ptr(),
-                memory_buf.as_mut_ptr(),
-            )
-        } {
-            return Err(ExecutionError::from(unsafe { error.assume_init() }));
-        }
-
-        let buffer = unsafe { memory_buf.assume_init() };
-        Ok(unsafe {
-            std::slice::from_raw_parts(LLVMGetBufferStart(buffer) as _, LLVMGetBufferSize(buffer))
-        })
-    }
-
     extern "C" fn obj_linking_layer_creator(
         _: *mut ::libc::c_void,
         execution_session: LLVMOrcExecutionSessionRef,
@@ -213,7 +183,7 @@ impl JITExecutionEngine {
             .insert(name.into(), addr.as_ptr());
     }
 
-    pub(crate) fn init() -> Result<Self, ExecutionError> {
+    pub(crate) fn init(opt_level: &OptLevel) -> Result<Self, ExecutionError> {
         unsafe {
             // this is a noop-function forcing linkage of the JIT compiler
             // DON'T REMOVE OR YOU'LL ENCOUNTER THE ERROR "JIT has not been linked in."
@@ -222,7 +192,7 @@ impl JITExecutionEngine {
         let lljit_builder = unsafe { LLVMOrcCreateLLJITBuilder() };
 
         // set JIT target machine as host machine
-        let target_machine = TargetMachine::create_default()?;
+        let target_machine = TargetMachine::create(opt_level, &TargetInfo::host())?;
         unsafe {
             let jit_target_machine_builder =
                 LLVMOrcJITTargetMachineBuilderCreateFromTargetMachine(target_machine.into_raw());
@@ -276,6 +246,7 @@ impl JITExecutionEngine {
             jit: lljit,
             external_syms,
             modules: Vec::new(),
+            opt_level: opt_level.clone(),
             _symbol_gen_ctxt: symbol_gen_ctxt as *mut DynamicSymbolResolverContext,
         })
     }
@@ -284,6 +255,10 @@ impl JITExecutionEngine {
         self.jit
     }
 
+    pub(crate) fn opt_level(&self) -> &OptLevel {
+        &self.opt_level
+    }
+
     pub fn get_symbol_addr(&self, fn_name: &str) -> Result<RawPointer, ExecutionError> {
         let mut address = MaybeUninit::uninit();
         let error = unsafe {
@@ -308,10 +283,11 @@ impl JITExecutionEngine {
         use llvm_sys::target_machine::{LLVMCodeGenFileType, LLVMTargetMachineEmitToFile};
 
         let file_name = c_str("debug.o");
+        let target_machine = TargetMachine::create(&self.opt_level, &TargetInfo::host())?;
         let mut error_msg = MaybeUninit::uninit();
         if 0 != unsafe {
             LLVMTargetMachineEmitToFile(
-                TargetMachine::create_default()?.into_raw(),
+                target_machine.get(),
                 self.modules.last().unwrap().get(),
                 std::mem::transmute::<*const i8, *mut i8>(file_name.as_ptr()),
                 LLVMCodeGenFileType::LLVMObjectFile,
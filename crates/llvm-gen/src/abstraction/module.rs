@@ -3,12 +3,17 @@ use crate::{util::c_str, TranslationError};
 use llvm_sys::{
     core::{
         LLVMAddFunction, LLVMAddGlobal, LLVMFunctionType, LLVMGetNamedFunction, LLVMGetNamedGlobal,
-        LLVMModuleCreateWithNameInContext, LLVMSetFunctionCallConv, LLVMSetLinkage,
+        LLVMModuleCreateWithNameInContext, LLVMSetFunctionCallConv, LLVMSetInitializer,
+        LLVMSetLinkage,
     },
     prelude::{LLVMModuleRef, LLVMTypeRef, LLVMValueRef},
     LLVMCallConv, LLVMLinkage,
 };
 
+/// LLVM's `tailcc`, which llvm-sys doesn't expose. It is used for all calls between wasm functions,
+/// because only it guarantees that `musttail` calls reuse the caller's stack frame.
+pub(crate) const INTERNAL_CALL_CONV: u32 = 18;
+
 pub struct Module {
     inner: LLVMModuleRef,
 }
@@ -36,10 +41,30 @@ impl Module {
         ty: LLVMTypeRef,
         linkage: LLVMLinkage,
         call_conv: LLVMCallConv,
+    ) -> Function {
+        self.add_function_with_raw_call_conv(name, ty, linkage, call_conv as u32)
+    }
+
+    /// Add a function which is called from wasm functions, see [`INTERNAL_CALL_CONV`].
+    pub(crate) fn add_internal_function(
+        &self,
+        name: &str,
+        ty: LLVMTypeRef,
+        linkage: LLVMLinkage,
+    ) -> Function {
+        self.add_function_with_raw_call_conv(name, ty, linkage, INTERNAL_CALL_CONV)
+    }
+
+    fn add_function_with_raw_call_conv(
+        &self,
+        name: &str,
+        ty: LLVMTypeRef,
+        linkage: LLVMLinkage,
+        call_conv: u32,
     ) -> Function {
         let fn_val = unsafe { LLVMAddFunction(self.get(), c_str(name).as_ptr(), ty) };
         unsafe { LLVMSetLinkage(fn_val, linkage) }
-        unsafe { LLVMSetFunctionCallConv(fn_val, call_conv as u32) };
+        unsafe { LLVMSetFunctionCallConv(fn_val, call_conv) };
         Function::new(fn_val, ty).unwrap()
     }
 
@@ -123,6 +148,19 @@ impl Module {
     pub(crate) fn add_global(&self, name: &str, ty: LLVMTypeRef) {
         unsafe { LLVMAddGlobal(self.get(), ty, c_str(name).as_ptr()) };
     }
+
+    /// Add a global only visible inside this module, which starts out as `init`.
+    pub(crate) fn add_private_global(
+        &self,
+        name: &str,
+        ty: LLVMTypeRef,
+        init: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let global = unsafe { LLVMAddGlobal(self.get(), ty, c_str(name).as_ptr()) };
+        unsafe { LLVMSetInitializer(global, init) };
+        unsafe { LLVMSetLinkage(global, LLVMLinkage::LLVMInternalLinkage) };
+        global
+    }
 }
 
 impl Drop for Module {
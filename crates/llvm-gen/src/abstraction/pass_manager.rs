@@ -1,22 +1,30 @@
 use super::module::Module;
-use crate::{abstraction::target_machine::TargetMachine, util::c_str, ExecutionError};
-use llvm_sys::transforms::pass_builder::{LLVMCreatePassBuilderOptions, LLVMRunPasses};
+use crate::{abstraction::target_machine::TargetMachine, util::c_str, ExecutionError, OptLevel};
+use llvm_sys::transforms::pass_builder::{
+    LLVMCreatePassBuilderOptions, LLVMDisposePassBuilderOptions, LLVMRunPasses,
+};
 
 pub(crate) struct PassManager;
 
 impl PassManager {
-    pub(crate) fn optimize_module(module: &Module) -> Result<(), ExecutionError> {
+    pub(crate) fn optimize_module(
+        module: &Module,
+        opt_level: &OptLevel,
+        target_machine: &TargetMachine,
+    ) -> Result<(), ExecutionError> {
         let options = unsafe { LLVMCreatePassBuilderOptions() };
-        let optimization_passes = "mem2reg,gvn,reassociate,adce,simplifycfg";
+        opt_level.configure_pass_builder(options);
+        let optimization_passes = opt_level.pass_pipeline();
         log::debug!("running passes '{optimization_passes}' on translated module");
         let err = unsafe {
             LLVMRunPasses(
                 module.get(),
                 c_str(optimization_passes).as_ptr(),
-                TargetMachine::create_default()?.into_raw(),
+                target_machine.get(),
                 options,
             )
         };
+        unsafe { LLVMDisposePassBuilderOptions(options) };
         if !err.is_null() {
             return Err(err.into());
         }
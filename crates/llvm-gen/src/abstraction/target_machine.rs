@@ -1,11 +1,20 @@
-use crate::ExecutionError;
-use llvm_sys::target_machine::{
-    LLVMCodeGenOptLevel, LLVMCodeModel, LLVMCreateTargetMachine, LLVMGetDefaultTargetTriple,
-    LLVMGetFirstTarget, LLVMGetHostCPUFeatures, LLVMGetHostCPUName, LLVMRelocMode,
-    LLVMSetTargetMachineFastISel, LLVMTargetMachineRef,
+use super::module::Module;
+use crate::{util::c_str, ExecutionError, OptLevel, TargetInfo};
+use llvm_sys::{
+    core::{
+        LLVMDisposeMemoryBuffer, LLVMDisposeMessage, LLVMGetBufferSize, LLVMGetBufferStart,
+        LLVMSetTarget,
+    },
+    target::{LLVMDisposeTargetData, LLVMSetModuleDataLayout},
+    target_machine::{
+        LLVMCodeGenFileType, LLVMCodeModel, LLVMCreateTargetDataLayout, LLVMCreateTargetMachine,
+        LLVMDisposeTargetMachine, LLVMGetDefaultTargetTriple, LLVMGetHostCPUFeatures,
+        LLVMGetHostCPUName, LLVMGetTargetFromTriple, LLVMGetTargetMachineTriple, LLVMRelocMode,
+        LLVMSetTargetMachineFastISel, LLVMTargetMachineEmitToMemoryBuffer, LLVMTargetMachineRef,
+    },
 };
 use once_cell::sync::Lazy;
-use std::ffi::CString;
+use std::{ffi::CString, mem::MaybeUninit};
 
 pub(crate) static TARGET_TRIPLE: Lazy<CString> = Lazy::new(|| unsafe {
     let target_triple = LLVMGetDefaultTargetTriple();
@@ -13,13 +22,13 @@ pub(crate) static TARGET_TRIPLE: Lazy<CString> = Lazy::new(|| unsafe {
     log::debug!("using LLVM target triple: {:?}", target_triple);
     target_triple
 });
-static CPU: Lazy<CString> = Lazy::new(|| unsafe {
+pub(crate) static HOST_CPU: Lazy<CString> = Lazy::new(|| unsafe {
     let cpu = LLVMGetHostCPUName();
     let cpu = CString::from_raw(cpu);
     log::debug!("LLVM detected CPU: {:?}", cpu);
     cpu
 });
-static CPU_FEATURES: Lazy<CString> = Lazy::new(|| unsafe {
+pub(crate) static HOST_CPU_FEATURES: Lazy<CString> = Lazy::new(|| unsafe {
     let features = LLVMGetHostCPUFeatures();
     CString::from_raw(features)
 });
@@ -37,29 +46,103 @@ static LLVM_TARGET_INIT: Lazy<Result<(), ExecutionError>> = Lazy::new(|| {
     }
     Ok(())
 });
+// other targets are only needed for cross compilation
+static LLVM_ALL_TARGETS_INIT: Lazy<()> = Lazy::new(|| unsafe {
+    llvm_sys::target::LLVM_InitializeAllTargetInfos();
+    llvm_sys::target::LLVM_InitializeAllTargets();
+    llvm_sys::target::LLVM_InitializeAllTargetMCs();
+    llvm_sys::target::LLVM_InitializeAllAsmPrinters();
+});
 
 pub(crate) struct TargetMachine(LLVMTargetMachineRef);
 
 impl TargetMachine {
-    pub(crate) fn create_default() -> Result<Self, ExecutionError> {
+    pub(crate) fn create(
+        opt_level: &OptLevel,
+        target: &TargetInfo,
+    ) -> Result<Self, ExecutionError> {
         LLVM_TARGET_INIT.clone()?;
-        let target = unsafe { LLVMGetFirstTarget() };
+        if !target.is_host_triple() {
+            Lazy::force(&LLVM_ALL_TARGETS_INIT);
+        }
+        let triple = c_str(&target.triple);
+        let mut llvm_target = MaybeUninit::uninit();
+        let mut error = MaybeUninit::uninit();
+        if 0 != unsafe {
+            LLVMGetTargetFromTriple(
+                triple.as_ptr(),
+                llvm_target.as_mut_ptr(),
+                error.as_mut_ptr(),
+            )
+        } {
+            return Err(ExecutionError::from(unsafe { error.assume_init() }));
+        }
         let target_machine = unsafe {
             LLVMCreateTargetMachine(
-                target,
-                TARGET_TRIPLE.as_ptr(),
-                CPU.as_ptr(),
-                CPU_FEATURES.as_ptr(),
-                LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault,
+                llvm_target.assume_init(),
+                triple.as_ptr(),
+                c_str(&target.cpu).as_ptr(),
+                c_str(&target.features).as_ptr(),
+                opt_level.codegen_level(),
                 LLVMRelocMode::LLVMRelocDefault,
                 LLVMCodeModel::LLVMCodeModelJITDefault,
             )
         };
-        unsafe { LLVMSetTargetMachineFastISel(target_machine, true.into()) };
+        unsafe { LLVMSetTargetMachineFastISel(target_machine, opt_level.fast_isel().into()) };
         Ok(Self(target_machine))
     }
 
-    pub(crate) fn into_raw(self) -> LLVMTargetMachineRef {
+    pub(crate) fn get(&self) -> LLVMTargetMachineRef {
         self.0
     }
+
+    /// Hands the target machine over to an LLVM API that takes ownership of it
+    pub(crate) fn into_raw(self) -> LLVMTargetMachineRef {
+        std::mem::ManuallyDrop::new(self).0
+    }
+
+    /// Sets the triple and data layout of `module`, which the optimization passes take into account
+    pub(crate) fn configure_module(&self, module: &Module) {
+        unsafe {
+            let triple = LLVMGetTargetMachineTriple(self.0);
+            LLVMSetTarget(module.get(), triple);
+            LLVMDisposeMessage(triple);
+            let data_layout = LLVMCreateTargetDataLayout(self.0);
+            LLVMSetModuleDataLayout(module.get(), data_layout);
+            LLVMDisposeTargetData(data_layout);
+        }
+    }
+
+    pub(crate) fn emit_object(&self, module: &Module) -> Result<Vec<u8>, ExecutionError> {
+        let mut memory_buf = MaybeUninit::uninit();
+        let mut error = MaybeUninit::uninit();
+        if 0 != unsafe {
+            LLVMTargetMachineEmitToMemoryBuffer(
+                self.0,
+                module.get(),
+                LLVMCodeGenFileType::LLVMObjectFile,
+                error.as_mut_ptr(),
+                memory_buf.as_mut_ptr(),
+            )
+        } {
+            return Err(ExecutionError::from(unsafe { error.assume_init() }));
+        }
+
+        let buffer = unsafe { memory_buf.assume_init() };
+        let object = unsafe {
+            std::slice::from_raw_parts(
+                LLVMGetBufferStart(buffer) as *const u8,
+                LLVMGetBufferSize(buffer),
+            )
+            .to_vec()
+        };
+        unsafe { LLVMDisposeMemoryBuffer(buffer) };
+        Ok(object)
+    }
+}
+
+impl Drop for TargetMachine {
+    fn drop(&mut self) {
+        unsafe { LLVMDisposeTargetMachine(self.0) }
+    }
 }
@@ -0,0 +1,415 @@
+use super::{
+    encode_aot_module, parse_aot_meta_with_key, AOTError, CWASM_FORMAT_VERSION, RUNTIME_VERSION,
+};
+use crate::{OptLevel, TargetInfo};
+use module::{passes::PassManager as IRPassManager, Module as WasmModule};
+use resource_buffer::{ResourceBuffer, ResourceBufferError};
+use sha2::{Digest, Sha256};
+use std::{
+    ffi::OsStr,
+    fmt::Debug,
+    fs::{self, File},
+    io::{self, ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+const ENTRY_EXTENSION: &str = "cwasm";
+const TEMP_EXTENSION: &str = "tmp";
+/// Temporary files older than this were left behind by crashed processes
+const STALE_TEMP_FILE_AGE: Duration = Duration::from_secs(60 * 60);
+
+/// Directory of compiled modules, which lets the JIT reuse the object code of a module that was compiled with the
+/// same settings before instead of translating and optimizing it again.
+///
+/// Entries are cwasm files as written by [`super::store_aot_module`], named after a hash of the wasm binary, the
+/// compiler settings and the runtime version. Several processes may share a directory: entries are written to a
+/// temporary file and atomically renamed into place, so readers only ever see complete entries. Once the entries
+/// exceed the size limit, the least recently used ones are evicted.
+#[derive(Clone)]
+pub struct CompilationCache {
+    dir: PathBuf,
+    max_size: u64,
+    key: Option<Vec<u8>>,
+}
+
+impl CompilationCache {
+    pub const DEFAULT_MAX_SIZE: u64 = 1 << 30;
+
+    /// A cache in `dir`, which is created once the first entry is stored.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_size: Self::DEFAULT_MAX_SIZE,
+            key: None,
+        }
+    }
+
+    /// Limits the total size of the entries in bytes.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Authenticates entries with an HMAC-SHA256 key, entries stored without it are recompiled. Protects against
+    /// tampered entries if others can write to the directory.
+    pub fn with_key(mut self, key: Vec<u8>) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    /// The entry of a wasm binary compiled for `target` with `opt_level`, after optimizing its IR with `ir_passes`
+    /// if there are any.
+    pub(crate) fn entry(
+        &self,
+        wasm: &[u8],
+        opt_level: &OptLevel,
+        target: &TargetInfo,
+        ir_passes: Option<&IRPassManager>,
+    ) -> CacheEntry<'_> {
+        // everything the object code depends on besides the binary and the runtime version
+        let pass_names: Option<Vec<_>> = ir_passes.map(|passes| passes.pass_names().collect());
+        let settings = format!("{opt_level:?} {target:?} {pass_names:?}");
+        let mut hasher = Sha256::new();
+        for part in [
+            RUNTIME_VERSION.as_bytes(),
+            &CWASM_FORMAT_VERSION.to_be_bytes(),
+            settings.as_bytes(),
+            wasm,
+        ] {
+            hasher.update((part.len() as u64).to_be_bytes());
+            hasher.update(part);
+        }
+        let name: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect();
+        CacheEntry {
+            cache: self,
+            path: self.dir.join(name).with_extension(ENTRY_EXTENSION),
+        }
+    }
+
+    /// Removes the least recently used entries until the cache fits into its size limit, along with temporary
+    /// files of crashed processes. Other processes may evict concurrently, so files can vanish at any time. Files
+    /// that can't be inspected or removed are skipped.
+    fn evict(&self) -> io::Result<()> {
+        let now = SystemTime::now();
+        let mut entries = Vec::new();
+        let mut size = 0;
+        for dir_entry in fs::read_dir(&self.dir)? {
+            let Ok(path) = dir_entry.map(|dir_entry| dir_entry.path()) else {
+                continue;
+            };
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            match path.extension().and_then(OsStr::to_str) {
+                Some(ENTRY_EXTENSION) => {
+                    size += metadata.len();
+                    entries.push((modified, metadata.len(), path));
+                }
+                Some(TEMP_EXTENSION)
+                    if now
+                        .duration_since(modified)
+                        .is_ok_and(|age| age > STALE_TEMP_FILE_AGE) =>
+                {
+                    if let Err(e) = remove_file(&path) {
+                        log::debug!("Could not remove stale temporary file {path:?}: {e}");
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // oldest first, lookups refresh the modification time
+        entries.sort_unstable();
+        for (_, len, path) in entries {
+            if size <= self.max_size {
+                break;
+            }
+            log::debug!("Evicting cache entry {path:?}");
+            // e.g. read-only entries of a shared cache, the younger ones are evicted instead
+            if let Err(e) = remove_file(&path) {
+                log::debug!("Could not evict cache entry {path:?}: {e}");
+                continue;
+            }
+            size -= len;
+        }
+        Ok(())
+    }
+}
+
+impl Debug for CompilationCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CompilationCache")
+            .field("dir", &self.dir)
+            .field("max_size", &self.max_size)
+            // the key is a secret, so it is kept out of logs
+            .field("key", &self.key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+pub(crate) struct CacheEntry<'a> {
+    cache: &'a CompilationCache,
+    path: PathBuf,
+}
+
+impl CacheEntry<'_> {
+    /// The precompiled module of the entry, if it exists and is valid. It is loaded like any other cwasm file, so
+    /// its metadata is validated and it has to be authenticated with the key of the cache, if it has one.
+    pub(crate) fn load(&self) -> Option<WasmModule> {
+        let buffer = match ResourceBuffer::from_file(&self.path) {
+            Ok(buffer) => buffer,
+            Err(ResourceBufferError::IoError(e)) if e.kind() == ErrorKind::NotFound => return None,
+            Err(e) => {
+                log::warn!("Could not read cache entry {:?}: {e}", self.path);
+                return None;
+            }
+        };
+        match parse_aot_meta_with_key(buffer, self.cache.key.as_deref()) {
+            Ok(module) => {
+                log::info!("Reusing cached object code {:?}", self.path);
+                // marks the entry as recently used, read-only entries of a shared cache are evicted earlier
+                if let Err(e) = File::options()
+                    .append(true)
+                    .open(&self.path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                {
+                    log::debug!("Could not refresh cache entry {:?}: {e}", self.path);
+                }
+                Some(module)
+            }
+            // overwritten once the module is compiled again
+            Err(e) => {
+                log::warn!("Ignoring invalid cache entry {:?}: {e}", self.path);
+                None
+            }
+        }
+    }
+
+    /// Stores the module compiled to `object` as the entry, replacing an existing one.
+    pub(crate) fn store(
+        &self,
+        module: &WasmModule,
+        object: &[u8],
+        opt_level: &OptLevel,
+        target: TargetInfo,
+    ) -> Result<(), AOTError> {
+        let file = encode_aot_module(module, object, opt_level, target, self.cache.key.as_deref())?;
+        fs::create_dir_all(&self.cache.dir)?;
+
+        // unique among all processes sharing the directory, even across pid namespaces
+        static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let temp_path = self.path.with_extension(format!(
+            "{}.{}.{}.{TEMP_EXTENSION}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .subsec_nanos(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = File::create_new(&temp_path)
+            .and_then(|mut temp_file| {
+                temp_file.write_all(&file)?;
+                temp_file.sync_all()
+            })
+            .and_then(|()| fs::rename(&temp_path, &self.path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        log::debug!("Stored cache entry {:?}", self.path);
+
+        if let Err(e) = self.cache.evict() {
+            log::warn!("Could not evict entries of cache {:?}: {e}", self.cache.dir);
+        }
+        Ok(())
+    }
+}
+
+/// Removes a file unless another process already did.
+fn remove_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aot::CwasmHeader;
+
+    const OBJECT: &[u8] = b"object code";
+
+    /// An empty module with a custom section, to tell binaries apart
+    fn wasm(name: &str) -> Vec<u8> {
+        let mut wasm = b"\0asm\x01\0\0\0\0".to_vec();
+        wasm.push(name.len() as u8 + 1);
+        wasm.push(name.len() as u8);
+        wasm.extend(name.as_bytes());
+        wasm
+    }
+
+    fn module(wasm: Vec<u8>) -> WasmModule {
+        parser::Parser::parse_from_buf(wasm).unwrap()
+    }
+
+    fn target(cpu: &str) -> TargetInfo {
+        TargetInfo {
+            triple: "x86_64-unknown-linux-gnu".to_owned(),
+            cpu: cpu.to_owned(),
+            features: String::new(),
+        }
+    }
+
+    fn files(dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<_> = fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        files
+    }
+
+    fn set_age(path: &Path, age: Duration) {
+        File::options()
+            .append(true)
+            .open(path)
+            .and_then(|file| file.set_modified(SystemTime::now() - age))
+            .unwrap();
+    }
+
+    #[test]
+    fn stored_entries_are_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CompilationCache::new(dir.path().join("cache")).with_key(b"key".to_vec());
+        let wasm = wasm("a");
+        let entry = cache.entry(&wasm, &OptLevel::O2, &TargetInfo::host(), None);
+        assert!(entry.load().is_none());
+
+        entry
+            .store(
+                &module(wasm.clone()),
+                OBJECT,
+                &OptLevel::O2,
+                TargetInfo::host(),
+            )
+            .unwrap();
+        let precompiled = cache
+            .entry(&wasm, &OptLevel::O2, &TargetInfo::host(), None)
+            .load()
+            .expect("cache hit");
+        let header = CwasmHeader::parse(precompiled.source.get()).unwrap();
+        assert_eq!(&precompiled.source.get()[header.object], OBJECT);
+        assert_eq!(precompiled.custom_sections().count(), 1);
+
+        // entries of another key are compiled again
+        let cache = CompilationCache::new(cache.dir()).with_key(b"other key".to_vec());
+        let entry = cache.entry(&wasm, &OptLevel::O2, &TargetInfo::host(), None);
+        assert!(entry.load().is_none());
+    }
+
+    #[test]
+    fn entries_depend_on_the_binary_and_settings() {
+        let cache = CompilationCache::new("cache");
+        let passes = IRPassManager::default();
+        let path = |name: &str,
+                    opt_level: &OptLevel,
+                    target: &TargetInfo,
+                    passes: Option<&IRPassManager>| {
+            cache.entry(&wasm(name), opt_level, target, passes).path
+        };
+        let host = target("x86-64");
+        let original = path("a", &OptLevel::O2, &host, None);
+        assert_eq!(path("a", &OptLevel::O2, &host, None), original);
+        for other in [
+            path("b", &OptLevel::O2, &host, None),
+            path("a", &OptLevel::O3, &host, None),
+            path("a", &OptLevel::Custom("gvn".to_owned()), &host, None),
+            path("a", &OptLevel::O2, &target("generic"), None),
+            path("a", &OptLevel::O2, &host, Some(&passes)),
+        ] {
+            assert_ne!(other, original);
+        }
+    }
+
+    #[test]
+    fn least_recently_used_entries_are_evicted() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CompilationCache::new(dir.path());
+        let store = |cache: &CompilationCache, name| {
+            let wasm = wasm(name);
+            let entry = cache.entry(&wasm, &OptLevel::O2, &target("x86-64"), None);
+            entry
+                .store(
+                    &module(wasm.clone()),
+                    OBJECT,
+                    &OptLevel::O2,
+                    target("x86-64"),
+                )
+                .unwrap();
+            entry.path
+        };
+        let oldest = store(&cache, "a");
+        let entry_size = fs::metadata(&oldest).unwrap().len();
+        let older = store(&cache, "b");
+        set_age(&oldest, Duration::from_secs(20));
+        set_age(&older, Duration::from_secs(10));
+        let stale_temp_file = dir.path().join("stale.tmp");
+        let temp_file = dir.path().join("in_progress.tmp");
+        for path in [&stale_temp_file, &temp_file] {
+            fs::write(path, b"").unwrap();
+        }
+        set_age(&stale_temp_file, STALE_TEMP_FILE_AGE * 2);
+
+        let cache = cache.with_max_size(2 * entry_size);
+        let newest = store(&cache, "c");
+        let mut remaining = vec![older, newest, temp_file];
+        remaining.sort();
+        assert_eq!(files(dir.path()), remaining);
+    }
+
+    #[test]
+    fn concurrent_stores_leave_a_valid_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = CompilationCache::new(dir.path());
+        let wasm = wasm("a");
+        let entry = cache.entry(&wasm, &OptLevel::O2, &target("x86-64"), None);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    let module = module(wasm.clone());
+                    for _ in 0..4 {
+                        entry
+                            .store(&module, OBJECT, &OptLevel::O2, target("x86-64"))
+                            .unwrap();
+                    }
+                });
+            }
+        });
+
+        // no temporary files are left behind
+        assert_eq!(files(dir.path()), vec![entry.path.clone()]);
+        let file = fs::read(&entry.path).unwrap();
+        let header = CwasmHeader::parse(&file).unwrap();
+        header.verify_checksum(&file).unwrap();
+        assert_eq!(&file[header.object], OBJECT);
+    }
+}
@@ -0,0 +1,512 @@
+use super::AOTError;
+use crate::TargetInfo;
+use sha2::{Digest, Sha256};
+use std::ops::Range;
+
+/// First bytes of every cwasm file
+pub const CWASM_MAGIC: [u8; 8] = *b"\0WASMINE";
+/// Version of the layout of cwasm files, bumped whenever the header or the encoding of the payload changes
+pub const CWASM_FORMAT_VERSION: u32 = 2;
+/// The rkyv encoding of the module metadata depends on the runtime that wrote it
+pub const RUNTIME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const DIGEST_SIZE: usize = 32;
+const SHA256_BLOCK_SIZE: usize = 64;
+
+/// Header of a cwasm file describing how and for which machine its object code was compiled.
+///
+/// All integers are big endian, strings are prefixed with their length as `u32`:
+///
+/// | field               | encoding            |
+/// |---------------------|---------------------|
+/// | magic               | [`CWASM_MAGIC`]     |
+/// | format version      | `u32`               |
+/// | header size         | `u32`               |
+/// | runtime version     | string              |
+/// | target triple       | string              |
+/// | cpu                 | string              |
+/// | cpu features        | string              |
+/// | optimization level  | string              |
+/// | metadata            | `u64` offset + size |
+/// | custom sections     | `u64` offset + size |
+/// | object code         | `u64` offset + size |
+/// | authenticated       | `u8`, 0 or 1        |
+/// | HMAC                | 32 bytes            |
+/// | checksum            | 32 bytes            |
+///
+/// The HMAC is the HMAC-SHA256 of the whole file without the HMAC and the checksum, the checksum the SHA-256 of
+/// the whole file without the checksum. Files are only authenticated if they were stored with a key, otherwise
+/// the HMAC is zeroed. The rkyv encoded
+/// module metadata, the payloads of the custom sections and the object code follow the header, the metadata is
+/// 16 byte aligned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CwasmHeader {
+    pub format_version: u32,
+    pub runtime_version: String,
+    pub target: TargetInfo,
+    pub opt_level: String,
+    pub metadata: Range<usize>,
+    pub custom_sections: Range<usize>,
+    pub object: Range<usize>,
+    /// HMAC-SHA256 with the key the file was stored with, if any
+    pub mac: Option<[u8; DIGEST_SIZE]>,
+    pub checksum: [u8; DIGEST_SIZE],
+}
+
+impl CwasmHeader {
+    /// Header for the current format and runtime, the sections are filled in by the caller and the digests by
+    /// [`CwasmHeader::encode`].
+    pub(crate) fn new(target: TargetInfo, opt_level: String) -> Self {
+        Self {
+            format_version: CWASM_FORMAT_VERSION,
+            runtime_version: RUNTIME_VERSION.to_owned(),
+            target,
+            opt_level,
+            metadata: 0..0,
+            custom_sections: 0..0,
+            object: 0..0,
+            mac: None,
+            checksum: [0; DIGEST_SIZE],
+        }
+    }
+
+    /// Size of the encoded header, which doesn't depend on the section ranges
+    pub(crate) fn size(&self) -> usize {
+        self.encode_without_digests(false).len() + 2 * DIGEST_SIZE
+    }
+
+    /// Encodes the header and computes its digests over it and `payload`, which starts right after the header.
+    /// The file is authenticated if a `key` is given.
+    pub(crate) fn encode(&mut self, payload: &[u8], key: Option<&[u8]>) -> Vec<u8> {
+        let mut bytes = self.encode_without_digests(key.is_some());
+        self.mac = key.map(|key| hmac(key, &bytes, payload));
+        bytes.extend_from_slice(&self.mac.unwrap_or_default());
+        self.checksum = checksum(&bytes, payload);
+        bytes.extend_from_slice(&self.checksum);
+        bytes
+    }
+
+    fn encode_without_digests(&self, authenticated: bool) -> Vec<u8> {
+        let mut bytes = CWASM_MAGIC.to_vec();
+        bytes.extend(self.format_version.to_be_bytes());
+        // patched below, once the size is known
+        bytes.extend(0u32.to_be_bytes());
+        for string in [
+            &self.runtime_version,
+            &self.target.triple,
+            &self.target.cpu,
+            &self.target.features,
+            &self.opt_level,
+        ] {
+            bytes.extend((string.len() as u32).to_be_bytes());
+            bytes.extend(string.as_bytes());
+        }
+        for range in [&self.metadata, &self.custom_sections, &self.object] {
+            bytes.extend((range.start as u64).to_be_bytes());
+            bytes.extend((range.len() as u64).to_be_bytes());
+        }
+        bytes.push(authenticated.into());
+        let size = (bytes.len() + 2 * DIGEST_SIZE) as u32;
+        bytes[12..16].copy_from_slice(&size.to_be_bytes());
+        bytes
+    }
+
+    /// Decodes the header of a cwasm file and checks that its sections lie within `input`. Whether the file
+    /// can be loaded by this runtime is checked by [`CwasmHeader::validate`].
+    pub fn parse(input: &[u8]) -> Result<Self, AOTError> {
+        let mut reader = Reader { input, offset: 0 };
+        if reader.bytes(CWASM_MAGIC.len())? != CWASM_MAGIC {
+            return Err(AOTError::InvalidMagic);
+        }
+        let format_version = reader.u32()?;
+        if format_version != CWASM_FORMAT_VERSION {
+            return Err(AOTError::UnsupportedFormatVersion {
+                found: format_version,
+                expected: CWASM_FORMAT_VERSION,
+            });
+        }
+        let size = reader.u32()? as usize;
+        reader.input = input.get(..size).ok_or(AOTError::Truncated)?;
+
+        let runtime_version = reader.string()?;
+        let target = TargetInfo {
+            triple: reader.string()?,
+            cpu: reader.string()?,
+            features: reader.string()?,
+        };
+        let opt_level = reader.string()?;
+        let metadata = reader.range()?;
+        let custom_sections = reader.range()?;
+        let object = reader.range()?;
+        let authenticated = match reader.bytes(1)?[0] {
+            0 => false,
+            1 => true,
+            flag => {
+                return Err(AOTError::Corrupted(format!(
+                    "invalid authentication flag {flag}"
+                )))
+            }
+        };
+        let mac: [u8; DIGEST_SIZE] = reader.bytes(DIGEST_SIZE)?.try_into().unwrap();
+        let checksum = reader.bytes(DIGEST_SIZE)?.try_into().unwrap();
+        if reader.offset != size {
+            return Err(AOTError::Corrupted(format!(
+                "header size {size} doesn't match its {} bytes of content",
+                reader.offset
+            )));
+        }
+
+        for (name, range) in [
+            ("metadata", &metadata),
+            ("custom sections", &custom_sections),
+            ("object code", &object),
+        ] {
+            if range.start < size || range.end > input.len() {
+                return Err(AOTError::Corrupted(format!(
+                    "{name} at {range:?} lie outside of the payload {:?}",
+                    size..input.len()
+                )));
+            }
+        }
+        if metadata.start % 16 != 0 {
+            return Err(AOTError::Corrupted(format!(
+                "metadata at offset {} isn't aligned",
+                metadata.start
+            )));
+        }
+
+        Ok(Self {
+            format_version,
+            runtime_version,
+            target,
+            opt_level,
+            metadata,
+            custom_sections,
+            object,
+            mac: authenticated.then_some(mac),
+            checksum,
+        })
+    }
+
+    /// Checks that `input`, the file the header was parsed from, isn't corrupted and can be loaded by this
+    /// runtime on this host. With a `key`, the file also has to be authenticated with it.
+    pub fn validate(&self, input: &[u8], key: Option<&[u8]>) -> Result<(), AOTError> {
+        self.verify_checksum(input)?;
+        if let Some(key) = key {
+            self.verify_mac(input, key)?;
+        }
+        self.check_compatibility()
+    }
+
+    /// The start of the header and the payload, which a digest stored `digests` bytes before the end of the
+    /// header is computed over
+    fn digested_parts<'a>(
+        &self,
+        input: &'a [u8],
+        digests: usize,
+    ) -> Result<(&'a [u8], &'a [u8]), AOTError> {
+        let size = self.size();
+        match (input.get(..size - digests * DIGEST_SIZE), input.get(size..)) {
+            (Some(header), Some(payload)) => Ok((header, payload)),
+            _ => Err(AOTError::Truncated),
+        }
+    }
+
+    pub fn verify_checksum(&self, input: &[u8]) -> Result<(), AOTError> {
+        let (header, payload) = self.digested_parts(input, 1)?;
+        if checksum(header, payload) != self.checksum {
+            return Err(AOTError::ChecksumMismatch);
+        }
+        Ok(())
+    }
+
+    /// Checks that the file was stored with `key`. Unlike the checksum, this protects against deliberate
+    /// tampering, e.g. with files from a shared cache, as long as the key is kept secret.
+    pub fn verify_mac(&self, input: &[u8], key: &[u8]) -> Result<(), AOTError> {
+        let Some(mac) = self.mac else {
+            return Err(AOTError::Unauthenticated);
+        };
+        let (header, payload) = self.digested_parts(input, 2)?;
+        // compared in constant time to not reveal how much of a forged HMAC is correct
+        let difference = hmac(key, header, payload)
+            .iter()
+            .zip(mac)
+            .fold(0, |difference, (a, b)| difference | (a ^ b));
+        if difference != 0 {
+            return Err(AOTError::AuthenticationFailed);
+        }
+        Ok(())
+    }
+
+    pub fn check_compatibility(&self) -> Result<(), AOTError> {
+        if self.runtime_version != RUNTIME_VERSION {
+            return Err(AOTError::RuntimeVersionMismatch {
+                found: self.runtime_version.clone(),
+                expected: RUNTIME_VERSION,
+            });
+        }
+        if !self.target.is_host_triple() {
+            return Err(AOTError::TargetMismatch {
+                found: self.target.triple.clone(),
+                host: TargetInfo::host().triple,
+            });
+        }
+        let missing_features = self.target.missing_host_features();
+        if !missing_features.is_empty() {
+            return Err(AOTError::MissingCpuFeatures(missing_features.join(",")));
+        }
+        Ok(())
+    }
+}
+
+fn checksum(header: &[u8], payload: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut hasher = Sha256::new();
+    hasher.update(header);
+    hasher.update(payload);
+    hasher.finalize().into()
+}
+
+/// HMAC-SHA256 as specified by RFC 2104
+fn hmac(key: &[u8], header: &[u8], payload: &[u8]) -> [u8; DIGEST_SIZE] {
+    let mut block_key = [0; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        block_key[..DIGEST_SIZE].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block_key[..key.len()].copy_from_slice(key);
+    }
+    let mut inner = Sha256::new();
+    inner.update(block_key.map(|b| b ^ 0x36));
+    inner.update(header);
+    inner.update(payload);
+    let mut outer = Sha256::new();
+    outer.update(block_key.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+struct Reader<'a> {
+    input: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], AOTError> {
+        let bytes = self
+            .input
+            .get(self.offset..self.offset.saturating_add(len))
+            .ok_or(AOTError::Truncated)?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, AOTError> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, AOTError> {
+        Ok(u64::from_be_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+
+    fn string(&mut self) -> Result<String, AOTError> {
+        let len = self.u32()? as usize;
+        String::from_utf8(self.bytes(len)?.to_vec())
+            .map_err(|_| AOTError::Corrupted("header contains a string that isn't UTF-8".into()))
+    }
+
+    fn range(&mut self) -> Result<Range<usize>, AOTError> {
+        let start = self.u64()?;
+        let len = self.u64()?;
+        let end = start
+            .checked_add(len)
+            .ok_or_else(|| AOTError::Corrupted(format!("section size {len} overflows")))?;
+        match (usize::try_from(start), usize::try_from(end)) {
+            (Ok(start), Ok(end)) => Ok(start..end),
+            _ => Err(AOTError::Truncated),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OptLevel;
+
+    const OBJECT: &[u8] = b"object code";
+
+    fn target() -> TargetInfo {
+        TargetInfo {
+            triple: "x86_64-unknown-linux-gnu".to_owned(),
+            cpu: "x86-64-v2".to_owned(),
+            features: "+avx2".to_owned(),
+        }
+    }
+
+    /// A cwasm file with 16 bytes of metadata, an empty custom section and some object code
+    fn cwasm_file(key: Option<&[u8]>) -> (CwasmHeader, Vec<u8>) {
+        let mut header = CwasmHeader::new(target(), OptLevel::O3.to_string());
+        let size = header.size();
+        let metadata_offset = size.next_multiple_of(16);
+        header.metadata = metadata_offset..metadata_offset + 16;
+        header.custom_sections = header.metadata.end..header.metadata.end;
+        header.object = header.metadata.end..header.metadata.end + OBJECT.len();
+
+        let mut payload = vec![0; metadata_offset - size];
+        payload.extend([0xaa; 16]);
+        payload.extend(OBJECT);
+        let mut file = header.encode(&payload, key);
+        file.extend(payload);
+        (header, file)
+    }
+
+    #[test]
+    fn header_round_trip() {
+        for key in [None, Some(&b"key"[..])] {
+            let (header, file) = cwasm_file(key);
+            assert_eq!(CwasmHeader::parse(&file).unwrap(), header);
+            assert_eq!(header.mac.is_some(), key.is_some());
+            assert_eq!(&file[header.object.clone()], OBJECT);
+            header.verify_checksum(&file).unwrap();
+        }
+    }
+
+    #[test]
+    fn other_format_versions_are_rejected() {
+        let (_, mut file) = cwasm_file(None);
+        file[8..12].copy_from_slice(&(CWASM_FORMAT_VERSION - 1).to_be_bytes());
+        let err = CwasmHeader::parse(&file).unwrap_err();
+        assert!(
+            matches!(err, AOTError::UnsupportedFormatVersion { found, expected }
+                if found == CWASM_FORMAT_VERSION - 1 && expected == CWASM_FORMAT_VERSION),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn files_without_the_magic_are_rejected() {
+        let (_, mut file) = cwasm_file(None);
+        file[1] = b'w';
+        // a wasm binary is padded to the size of the magic
+        for input in [&file[..], b"\0asm\x01\0\0\0"] {
+            let err = CwasmHeader::parse(input).unwrap_err();
+            assert!(matches!(err, AOTError::InvalidMagic), "{err}");
+        }
+        let err = CwasmHeader::parse(&CWASM_MAGIC[..4]).unwrap_err();
+        assert!(matches!(err, AOTError::Truncated), "{err}");
+    }
+
+    /// Offset of the object code range in the header, right before the authentication flag and the digests
+    fn object_range_offset(header: &CwasmHeader) -> usize {
+        header.size() - 2 * DIGEST_SIZE - 1 - 16
+    }
+
+    #[test]
+    fn tampered_payloads_are_detected() {
+        let key = b"key";
+        let (header, mut file) = cwasm_file(Some(key));
+        header.verify_mac(&file, key).unwrap();
+        file[header.object.start] ^= 1;
+        let err = header.verify_checksum(&file).unwrap_err();
+        assert!(matches!(err, AOTError::ChecksumMismatch), "{err}");
+        // the checksum can be recomputed after tampering with the file, the HMAC can't without the key
+        let err = header.verify_mac(&file, key).unwrap_err();
+        assert!(matches!(err, AOTError::AuthenticationFailed), "{err}");
+    }
+
+    #[test]
+    fn files_stored_with_another_key_are_rejected() {
+        let (header, file) = cwasm_file(Some(b"key"));
+        for key in [&b"other key"[..], b"ke", b""] {
+            let err = header.verify_mac(&file, key).unwrap_err();
+            assert!(matches!(err, AOTError::AuthenticationFailed), "{err}");
+        }
+    }
+
+    #[test]
+    fn unauthenticated_files_are_rejected_with_a_key() {
+        let (header, file) = cwasm_file(None);
+        assert_eq!(header.mac, None);
+        let err = header.verify_mac(&file, b"key").unwrap_err();
+        assert!(matches!(err, AOTError::Unauthenticated), "{err}");
+    }
+
+    #[test]
+    fn sections_outside_of_the_file_are_rejected() {
+        let (header, file) = cwasm_file(None);
+        let err = CwasmHeader::parse(&file[..file.len() - 1]).unwrap_err();
+        assert!(matches!(err, AOTError::Corrupted(_)), "{err}");
+        let err = CwasmHeader::parse(&file[..header.size() - 1]).unwrap_err();
+        assert!(matches!(err, AOTError::Truncated), "{err}");
+
+        let offset = object_range_offset(&header);
+        for (start, len) in [
+            (0, 1),
+            (file.len() as u64, 1),
+            (u64::MAX, 1),
+            (header.object.start as u64, u64::MAX),
+        ] {
+            let mut file = file.clone();
+            file[offset..offset + 8].copy_from_slice(&start.to_be_bytes());
+            file[offset + 8..offset + 16].copy_from_slice(&len.to_be_bytes());
+            assert!(
+                matches!(
+                    CwasmHeader::parse(&file),
+                    Err(AOTError::Corrupted(_) | AOTError::Truncated)
+                ),
+                "object code at {start} with {len} bytes"
+            );
+        }
+    }
+
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    /// Test cases of RFC 4231 for HMAC-SHA256, except for the one with a truncated output
+    #[test]
+    fn hmac_matches_rfc_4231() {
+        let large_key = [0xaa; 131];
+        let cases: [(&[u8], &[u8], &str); 6] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+            ),
+            (
+                &[0xaa; 20],
+                &[0xdd; 50],
+                "773ea91e36800e46854db8ebd09181a72959098b3ef8c122d9635514ced565fe",
+            ),
+            (
+                &from_hex("0102030405060708090a0b0c0d0e0f10111213141516171819"),
+                &[0xcd; 50],
+                "82558a389a443c0ea4cc819899f2083a85f0faa3e578f8077a2e3ff46729665b",
+            ),
+            (
+                &large_key,
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+            ),
+            (
+                &large_key,
+                b"This is a test using a larger than block-size key and a larger than block-size data. \
+                  The key needs to be hashed before being used by the HMAC algorithm.",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+            ),
+        ];
+        for (key, data, expected) in cases {
+            // the split between header and payload doesn't matter
+            for split in [0, data.len() / 2, data.len()] {
+                let (header, payload) = data.split_at(split);
+                assert_eq!(hmac(key, header, payload).to_vec(), from_hex(expected));
+            }
+        }
+    }
+}
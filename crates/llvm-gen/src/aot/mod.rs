@@ -0,0 +1,310 @@
+use crate::{
+    abstraction::{module::Module, pass_manager::PassManager, target_machine::TargetMachine},
+    CompileTarget, ExecutionError, FunctionLoader, LLVMAdditionalResources, OptLevel, TargetInfo,
+    TranslationError, Translator,
+};
+use module::FunctionLoaderInterface;
+use module::{Module as WasmModule, ModuleMetadata};
+use resource_buffer::ResourceBuffer;
+use std::{ops::Range, path::Path, rc::Rc, sync::RwLock};
+
+mod cache;
+mod header;
+
+pub use cache::CompilationCache;
+pub use header::{CwasmHeader, CWASM_FORMAT_VERSION, CWASM_MAGIC, RUNTIME_VERSION};
+
+#[derive(Debug, thiserror::Error)]
+pub enum AOTError {
+    #[error("ResourceBuffer error: {0}")]
+    ResourceBufferError(#[from] resource_buffer::ResourceBufferError),
+
+    #[error("AOT error: {0}")]
+    Msg(String),
+
+    #[error("IO error: {0}")]
+    IOError(#[from] std::io::Error),
+
+    #[error("LLVM execution error: {0}")]
+    ExecutionError(#[from] ExecutionError),
+
+    #[error("LLVM translation error: {0}")]
+    TranslationError(#[from] TranslationError),
+
+    #[error("Not a cwasm file")]
+    InvalidMagic,
+
+    #[error("Unsupported cwasm format version {found}, expected version {expected}")]
+    UnsupportedFormatVersion { found: u32, expected: u32 },
+
+    #[error("Cwasm file was compiled by runtime version {found}, but this is version {expected}")]
+    RuntimeVersionMismatch {
+        found: String,
+        expected: &'static str,
+    },
+
+    #[error("Cwasm file was compiled for {found}, but the host is {host}")]
+    TargetMismatch { found: String, host: String },
+
+    #[error("Cwasm file requires CPU features the host lacks: {0}")]
+    MissingCpuFeatures(String),
+
+    #[error("Cwasm file is truncated")]
+    Truncated,
+
+    #[error("Cwasm file is corrupted: {0}")]
+    Corrupted(String),
+
+    #[error("Cwasm checksum mismatch, the file is corrupted")]
+    ChecksumMismatch,
+
+    #[error("Cwasm file isn't authenticated, but a key was given")]
+    Unauthenticated,
+
+    #[error("Cwasm authentication failed, the file was tampered with or stored with another key")]
+    AuthenticationFailed,
+}
+
+/// Precompiled LLVM function, stored in memory.
+///
+/// Note: This is used for AOT compiled functions.
+///       The stored value is a pointer + size to an LLVM memory buffer of the object file containing the function's symbol.
+#[derive(Debug, Clone)]
+pub struct AOTFunctions {
+    pub offset: usize,
+    pub size: usize,
+}
+
+pub fn parse_aot_module(buffer: ResourceBuffer) -> Result<WasmModule, AOTError> {
+    let wasm_module = parse_aot_meta(buffer)?;
+    parse_aot_functions(&wasm_module)?;
+    Ok(wasm_module)
+}
+
+/// Decodes the module metadata of a cwasm file, after checking that the file is intact and was compiled by this
+/// runtime for this host.
+pub fn parse_aot_meta(buffer: ResourceBuffer) -> Result<WasmModule, AOTError> {
+    parse_aot_meta_with_key(buffer, None)
+}
+
+/// Like [`parse_aot_meta`], but with a `key` the file also has to be authenticated with it, see
+/// [`store_signed_aot_module`].
+///
+/// Note: The metadata and all offsets are validated, so a malformed file is rejected instead of corrupting the
+///       host. The object code can't be validated though, only loading authenticated files protects against files
+///       that were tampered with deliberately.
+pub fn parse_aot_meta_with_key(
+    buffer: ResourceBuffer,
+    key: Option<&[u8]>,
+) -> Result<WasmModule, AOTError> {
+    log::debug!("Loading aot module meta using `llvm-gen`.");
+    let input = buffer.get();
+    let header = CwasmHeader::parse(input)?;
+    header.validate(input, key)?;
+
+    let module_meta = rkyv::from_bytes::<ModuleMetadata>(&input[header.metadata.clone()])
+        .map_err(|e| AOTError::Corrupted(format!("invalid module metadata: {e}")))?;
+    check_custom_sections(&module_meta, &header.custom_sections)?;
+
+    Ok(WasmModule {
+        meta: module_meta,
+        source: buffer,
+        artifact_registry: Default::default(),
+    })
+}
+
+/// Checks that the payloads of all custom sections lie within `custom_sections`, the range the header reserves
+/// for them.
+fn check_custom_sections(
+    module_meta: &ModuleMetadata,
+    custom_sections: &Range<usize>,
+) -> Result<(), AOTError> {
+    for section in &module_meta.custom_sections {
+        let in_bounds = section.offset.checked_add(section.size).is_some_and(|end| {
+            custom_sections.start <= section.offset && end <= custom_sections.end
+        });
+        if !in_bounds {
+            return Err(AOTError::Corrupted(format!(
+                "custom section \"{}\" lies outside of the custom sections {custom_sections:?}",
+                section.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_aot_functions(wasm_module: &WasmModule) -> Result<(), AOTError> {
+    log::debug!("Loading aot llvm functions using `llvm-gen`.");
+    let mut artifacts_ref = wasm_module.artifact_registry.write().unwrap();
+    if artifacts_ref.contains_key("llvm-obj") {
+        log::info!("LLVM aot functions already parsed by `llvm-gen`. Skipping.");
+        return Ok(());
+    }
+
+    // the file has been validated by `parse_aot_meta`
+    let header = CwasmHeader::parse(wasm_module.source.get())?;
+    artifacts_ref.insert(
+        "llvm-obj".to_string(),
+        RwLock::new(Box::new(AOTFunctions {
+            offset: header.object.start,
+            size: header.object.len(),
+        })),
+    );
+    Ok(())
+}
+
+/// Translates and optimizes the module and compiles it to an object file for `target`.
+fn compile_object(
+    module: &WasmModule,
+    opt_level: &OptLevel,
+    target: &TargetInfo,
+) -> Result<Vec<u8>, AOTError> {
+    Translator::translate_module_meta(module)?;
+    FunctionLoader::default()
+        .parse_all_functions(module)
+        .map_err(|e| AOTError::Msg(e.to_string()))?;
+    let llvm_module = {
+        let artifacts_ref = module.artifact_registry.read().unwrap();
+        let llvm_resources = artifacts_ref.get("llvm-module").unwrap().read().unwrap();
+        llvm_resources
+            .downcast_ref::<LLVMAdditionalResources>()
+            .unwrap()
+            .module
+            .clone()
+    };
+
+    Ok(compile_llvm_module(&llvm_module, opt_level, target)?)
+}
+
+/// Optimizes an already translated module and compiles it to an object file for `target`.
+pub(crate) fn compile_llvm_module(
+    llvm_module: &Module,
+    opt_level: &OptLevel,
+    target: &TargetInfo,
+) -> Result<Vec<u8>, ExecutionError> {
+    let target_machine = TargetMachine::create(opt_level, target)?;
+    target_machine.configure_module(llvm_module);
+    PassManager::optimize_module(llvm_module, opt_level, &target_machine)?;
+    target_machine.emit_object(llvm_module)
+}
+
+pub fn store_aot_module(
+    module: Rc<WasmModule>,
+    output_path: impl AsRef<Path>,
+    opt_level: &OptLevel,
+    target: &CompileTarget,
+) -> Result<(), AOTError> {
+    write_aot_module(module, output_path, opt_level, target, None)
+}
+
+/// Like [`store_aot_module`], but authenticates the file with `key`, an HMAC-SHA256 key shared with the hosts
+/// loading the file through [`parse_aot_meta_with_key`].
+pub fn store_signed_aot_module(
+    module: Rc<WasmModule>,
+    output_path: impl AsRef<Path>,
+    opt_level: &OptLevel,
+    target: &CompileTarget,
+    key: &[u8],
+) -> Result<(), AOTError> {
+    write_aot_module(module, output_path, opt_level, target, Some(key))
+}
+
+fn write_aot_module(
+    module: Rc<WasmModule>,
+    output_path: impl AsRef<Path>,
+    opt_level: &OptLevel,
+    target: &CompileTarget,
+    key: Option<&[u8]>,
+) -> Result<(), AOTError> {
+    let target = target.resolve();
+    log::info!("Compiling module for {target}");
+    let object = compile_object(&module, opt_level, &target)?;
+    std::fs::write(
+        output_path,
+        encode_aot_module(&module, &object, opt_level, target, key)?,
+    )?;
+    Ok(())
+}
+
+/// Encodes a cwasm file of the module and its object code, authenticated with `key` if one is given.
+pub(crate) fn encode_aot_module(
+    module: &WasmModule,
+    object: &[u8],
+    opt_level: &OptLevel,
+    target: TargetInfo,
+    key: Option<&[u8]>,
+) -> Result<Vec<u8>, AOTError> {
+    // custom section payloads are stored between the metadata and the object code, the wasm binary
+    // isn't part of the cwasm file, so their offsets have to be relocated
+    let mut header = CwasmHeader::new(target, opt_level.to_string());
+    let mut module_meta = module.meta.clone();
+    let metadata_offset = header.size().next_multiple_of(16);
+    let metadata_size = rkyv::to_bytes::<_, 1024>(&module_meta)
+        .map_err(|e| AOTError::Msg(format!("Failed to encode module metadata: {e}")))?
+        .len();
+    let custom_sections_offset = metadata_offset + metadata_size;
+    let mut custom_sections_size = 0;
+    for section in module_meta.custom_sections.iter_mut() {
+        section.offset = custom_sections_offset + custom_sections_size;
+        custom_sections_size += section.size;
+    }
+    let metadata = rkyv::to_bytes::<_, 1024>(&module_meta)
+        .map_err(|e| AOTError::Msg(format!("Failed to encode module metadata: {e}")))?;
+    debug_assert_eq!(metadata.len(), metadata_size);
+
+    // everything after the header, which is covered by its digests
+    let mut payload = vec![0; metadata_offset - header.size()];
+    payload.extend_from_slice(&metadata);
+    for (name, data) in module.custom_sections() {
+        log::debug!("Storing custom section \"{name}\" ({} bytes)", data.len());
+        payload.extend_from_slice(data);
+    }
+    let object_offset = (custom_sections_offset + custom_sections_size).next_multiple_of(16);
+    payload.resize(object_offset - header.size(), 0);
+    payload.extend_from_slice(object);
+
+    header.metadata = metadata_offset..custom_sections_offset;
+    header.custom_sections = custom_sections_offset..custom_sections_offset + custom_sections_size;
+    header.object = object_offset..object_offset + object.len();
+
+    let mut file = header.encode(&payload, key);
+    file.extend_from_slice(&payload);
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use module::objects::custom_section::CustomSection;
+
+    fn metadata(sections: &[(usize, usize)]) -> ModuleMetadata {
+        ModuleMetadata {
+            custom_sections: sections
+                .iter()
+                .map(|&(offset, size)| CustomSection {
+                    name: "name".to_owned(),
+                    offset,
+                    size,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn custom_sections_lie_within_their_range() {
+        let range = 64..96;
+        check_custom_sections(&metadata(&[(64, 16), (80, 16), (96, 0)]), &range).unwrap();
+        for section in [
+            (48, 16),
+            (60, 8),
+            (90, 8),
+            (96, 1),
+            (usize::MAX, 1),
+            (80, usize::MAX),
+        ] {
+            let err = check_custom_sections(&metadata(&[(64, 8), section]), &range).unwrap_err();
+            assert!(matches!(err, AOTError::Corrupted(_)), "{section:?}: {err}");
+        }
+    }
+}
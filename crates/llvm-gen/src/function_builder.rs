@@ -1,7 +1,7 @@
 use crate::{
     abstraction::{builder::Builder, function::Function, module::Module},
     instructions::instruction_consumer::LLVMInstructionConsumer,
-    util::c_str,
+    util::{build_llvm_local_name, c_str},
     Context, Translator,
 };
 use llvm_sys::{
@@ -9,13 +9,14 @@ use llvm_sys::{
     prelude::{LLVMBasicBlockRef, LLVMTypeRef, LLVMValueRef},
 };
 use module::{
+    basic_block::CatchHandler,
     instructions::{Variable, VariableID},
     BasicBlockID,
 };
 use parser::FunctionBuilderInterface;
 use smallvec::SmallVec;
 use std::{cell::RefCell, collections::HashMap, rc::Rc, u32};
-use wasm_types::{FuncIdx, FuncType, LocalIdx, ValType};
+use wasm_types::{FuncIdx, FuncType, LocalIdx, TagIdx, ValType};
 
 // modelling a state machine
 // TODO: continue integration with functionbuilder
@@ -47,6 +48,7 @@ pub struct LLVMFunctionBuilder<'wasm> {
     vars: Rc<RefCell<Vec<LLVMValueRef>>>,
 
     current_bb_id: BasicBlockID,
+    next_bb_id: BasicBlockID,
     current_instrs: LLVMInstructionConsumer<'wasm>,
     bbs: HashMap<BasicBlockID, CurrentBlockCtxt<'wasm>>,
 
@@ -73,6 +75,7 @@ impl<'wasm> LLVMFunctionBuilder<'wasm> {
             func_idx,
             func_type: FuncType::default(),
             current_bb_id: u32::MAX,
+            next_bb_id: 0,
             bbs: HashMap::new(),
             current_instrs: LLVMInstructionConsumer::new(
                 ctxt.clone(),
@@ -107,6 +110,190 @@ impl<'wasm> LLVMFunctionBuilder<'wasm> {
         self.module.print_to_file();
     }
 
+    /// Looks up the function selected by `selector_var` in the table, trapping on a type mismatch.
+    fn resolve_indirect_callee(
+        &self,
+        type_idx: u32,
+        selector_var: VariableID,
+        table_idx: u32,
+    ) -> Function {
+        let selector_var = self.vars.borrow()[selector_var];
+        let resolved_func_ptr = {
+            let func_type = Module::create_func_type(
+                self.current_instrs.translator.builder.ptr(),
+                &mut [
+                    self.current_instrs.translator.builder.ptr(),
+                    self.current_instrs.translator.builder.i32(),
+                    self.current_instrs.translator.builder.i32(),
+                    self.current_instrs.translator.builder.i32(),
+                ],
+            );
+            let indirect_call_fn = self
+                .module
+                .find_func("__wasmine_runtime.indirect_call", func_type)
+                .unwrap_or_else(|| {
+                    self.module.add_function(
+                        "__wasmine_runtime.indirect_call",
+                        func_type,
+                        llvm_sys::LLVMLinkage::LLVMExternalLinkage,
+                        llvm_sys::LLVMCallConv::LLVMCCallConv,
+                    )
+                });
+
+            self.current_instrs.translator.builder.build_call(
+                &indirect_call_fn,
+                &mut [
+                    Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx as usize]),
+                    self.current_instrs.translator.builder.const_i32(table_idx),
+                    self.current_instrs.translator.builder.const_i32(type_idx),
+                    selector_var,
+                ],
+                "indirect_call_res",
+            )
+        };
+        self.typed_callee(type_idx, resolved_func_ptr)
+    }
+
+    /// Resolves the function referenced by `selector_var`, trapping on null references.
+    fn resolve_ref_callee(&self, type_idx: u32, selector_var: VariableID) -> Function {
+        let selector_var = self.vars.borrow()[selector_var];
+        let resolved_func_ptr = {
+            let func_type = Module::create_func_type(
+                self.current_instrs.translator.builder.ptr(),
+                &mut [
+                    self.current_instrs.translator.builder.ptr(),
+                    self.current_instrs.translator.builder.i64(),
+                ],
+            );
+            let ref_call_fn = self
+                .module
+                .find_func("__wasmine_runtime.ref_call", func_type)
+                .unwrap_or_else(|| {
+                    self.module.add_function(
+                        "__wasmine_runtime.ref_call",
+                        func_type,
+                        llvm_sys::LLVMLinkage::LLVMExternalLinkage,
+                        llvm_sys::LLVMCallConv::LLVMCCallConv,
+                    )
+                });
+
+            self.current_instrs.translator.builder.build_call(
+                &ref_call_fn,
+                &mut [
+                    Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx as usize]),
+                    selector_var,
+                ],
+                "ref_call_res",
+            )
+        };
+        self.typed_callee(type_idx, resolved_func_ptr)
+    }
+
+    /// Wraps a resolved function pointer into a function of type `type_idx`.
+    fn typed_callee(&self, type_idx: u32, resolved_func_ptr: LLVMValueRef) -> Function {
+        let indirect_fn_type = self.wasm_module.function_types[type_idx as usize];
+        let mut param_types = vec![
+            // runtime ptr
+            self.current_instrs.translator.builder.ptr(),
+        ];
+        for valtype in indirect_fn_type.params_iter() {
+            param_types.push(self.current_instrs.translator.builder.valtype2llvm(valtype));
+        }
+
+        let return_type = match indirect_fn_type.num_results() {
+            0 => self.current_instrs.translator.builder.void(),
+            1 => self
+                .current_instrs
+                .translator
+                .builder
+                .valtype2llvm(indirect_fn_type.results_iter().next().unwrap()),
+            _ => self.current_instrs.translator.builder.r#struct(
+                indirect_fn_type
+                    .results_iter()
+                    .map(|valtype| self.current_instrs.translator.builder.valtype2llvm(valtype))
+                    .collect::<Vec<_>>()
+                    .as_mut_slice(),
+            ),
+        };
+        let indirect_llvm_fn_type = Module::create_func_type(return_type, &mut param_types);
+        Function::new(resolved_func_ptr, indirect_llvm_fn_type).unwrap()
+    }
+
+    /// Calls an already resolved function, e.g. of `call_indirect` or `call_ref`.
+    fn build_resolved_call(
+        &self,
+        func: &Function,
+        return_bb: BasicBlockID,
+        call_params: SmallVec<[VariableID; 2]>,
+        return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        let mut parameters =
+            vec![self.llvm_functions.borrow()[self.func_idx as usize].get_param(0)];
+        for var_id in call_params {
+            let llvm_val = self.vars.borrow()[var_id];
+            parameters.push(llvm_val);
+        }
+        let indirect_func_call_res = self.current_instrs.translator.builder.build_internal_call(
+            func,
+            parameters.as_mut_slice(),
+            if return_vars.is_empty() {
+                ""
+            } else {
+                "call_indirect"
+            },
+        );
+        match return_vars.len() {
+            0 => (),
+            1 => {
+                if self.vars.borrow().len() <= return_vars[0] {
+                    self.vars
+                        .borrow_mut()
+                        .resize(return_vars[0] + 1, std::ptr::null_mut());
+                }
+                self.vars.borrow_mut()[return_vars[0]] = indirect_func_call_res
+            }
+            _ => {
+                let max_ret_var_idx = return_vars.iter().max().unwrap();
+                if self.vars.borrow().len() <= *max_ret_var_idx {
+                    self.vars
+                        .borrow_mut()
+                        .resize(max_ret_var_idx + 1, std::ptr::null_mut());
+                }
+                for (i, var) in return_vars.iter().enumerate() {
+                    self.vars.borrow_mut()[*var] = unsafe {
+                        LLVMBuildExtractValue(
+                            self.current_instrs.translator.builder.get(),
+                            indirect_func_call_res,
+                            i as u32,
+                            c_str("extract_res").as_ptr(),
+                        )
+                    };
+                }
+            }
+        };
+        self.build_call_unwind_check(return_bb, unwind_bb);
+    }
+
+    fn llvm_bb(&self, id: BasicBlockID) -> LLVMBasicBlockRef {
+        self.bbs.get(&id).unwrap().bb
+    }
+
+    fn build_call_unwind_check(&self, return_bb: BasicBlockID, unwind_bb: Option<BasicBlockID>) {
+        self.current_instrs.translator.build_call_unwind_check(
+            self.llvm_bb(return_bb),
+            unwind_bb.map(|bb| self.llvm_bb(bb)),
+            &self.llvm_functions.borrow()[self.func_idx as usize],
+        );
+    }
+
+    /// Makes sure `var` can be assigned in `self.vars`.
+    fn reserve_var(&self, var: VariableID) {
+        if self.vars.borrow().len() <= var {
+            self.vars.borrow_mut().resize(var + 1, std::ptr::null_mut());
+        }
+    }
+
     #[inline]
     fn add_phi_input_vals(&self, target: BasicBlockID, output_vars: &[VariableID]) {
         let target_phis = &self.bbs.get(&target).unwrap().phi_inputs;
@@ -153,11 +340,14 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
                 .valtype2llvm(wasm_type);
             let param_val =
                 self.llvm_functions.borrow()[self.func_idx as usize].get_param(param_idx + 1);
-            let local = self
-                .current_instrs
-                .translator
-                .builder
-                .build_alloca(param_llvm_type, &format!("local{param_idx}"));
+            let local = self.current_instrs.translator.builder.build_alloca(
+                param_llvm_type,
+                &build_llvm_local_name(
+                    self.func_idx,
+                    param_idx as LocalIdx,
+                    self.current_instrs.translator.wasm_module_meta,
+                ),
+            );
             self.current_instrs
                 .translator
                 .builder
@@ -176,11 +366,14 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
             .translator
             .builder
             .valtype2llvm(local_ty);
-        let local_llvm_storage = self
-            .current_instrs
-            .translator
-            .builder
-            .build_alloca(local_llvm_type, &format!("local{local_idx}"));
+        let local_llvm_storage = self.current_instrs.translator.builder.build_alloca(
+            local_llvm_type,
+            &build_llvm_local_name(
+                self.func_idx,
+                local_idx,
+                self.current_instrs.translator.wasm_module_meta,
+            ),
+        );
         self.current_instrs.translator.builder.build_store(
             self.current_instrs.translator.builder.const_zero(local_ty),
             local_llvm_storage,
@@ -202,6 +395,11 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
         // noop for now
     }
 
+    fn next_bb_id(&mut self) -> BasicBlockID {
+        self.next_bb_id += 1;
+        self.next_bb_id - 1
+    }
+
     fn reserve_bb_with_id(&mut self, id: BasicBlockID) -> BasicBlockID {
         debug_assert!(!self.bbs.contains_key(&id));
         let new_bb = self.context.append_basic_block(
@@ -373,117 +571,10 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
         return_bb: module::BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<module::BasicBlockID>,
     ) {
-        let selector_var = self.vars.borrow()[selector_var];
-        let resolved_func_ptr = {
-            let func_type = Module::create_func_type(
-                self.current_instrs.translator.builder.ptr(),
-                &mut [
-                    self.current_instrs.translator.builder.ptr(),
-                    self.current_instrs.translator.builder.i32(),
-                    self.current_instrs.translator.builder.i32(),
-                    self.current_instrs.translator.builder.i32(),
-                ],
-            );
-            let indirect_call_fn = self
-                .module
-                .find_func("__wasmine_runtime.indirect_call", func_type)
-                .unwrap_or_else(|| {
-                    self.module.add_function(
-                        "__wasmine_runtime.indirect_call",
-                        func_type,
-                        llvm_sys::LLVMLinkage::LLVMExternalLinkage,
-                        llvm_sys::LLVMCallConv::LLVMCCallConv,
-                    )
-                });
-
-            self.current_instrs.translator.builder.build_call(
-                &indirect_call_fn,
-                &mut [
-                    Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx as usize]),
-                    self.current_instrs.translator.builder.const_i32(table_idx),
-                    self.current_instrs.translator.builder.const_i32(type_idx),
-                    selector_var,
-                ],
-                "indirect_call_res",
-            )
-        };
-
-        let indirect_fn_type = self.wasm_module.function_types[type_idx as usize];
-        let mut param_types = vec![
-            // runtime ptr
-            self.current_instrs.translator.builder.ptr(),
-        ];
-        for valtype in indirect_fn_type.params_iter() {
-            param_types.push(self.current_instrs.translator.builder.valtype2llvm(valtype));
-        }
-
-        let return_type = match indirect_fn_type.num_results() {
-            0 => self.current_instrs.translator.builder.void(),
-            1 => self
-                .current_instrs
-                .translator
-                .builder
-                .valtype2llvm(indirect_fn_type.results_iter().next().unwrap()),
-            _ => self.current_instrs.translator.builder.r#struct(
-                indirect_fn_type
-                    .results_iter()
-                    .map(|valtype| self.current_instrs.translator.builder.valtype2llvm(valtype))
-                    .collect::<Vec<_>>()
-                    .as_mut_slice(),
-            ),
-        };
-        let indirect_llvm_fn_type = Module::create_func_type(return_type, &mut param_types);
-        let func = Function::new(resolved_func_ptr, indirect_llvm_fn_type).unwrap();
-
-        let mut parameters =
-            vec![self.llvm_functions.borrow()[self.func_idx as usize].get_param(0)];
-        for var_id in call_params {
-            let llvm_val = self.vars.borrow()[var_id];
-            parameters.push(llvm_val);
-        }
-        let indirect_func_call_res = self.current_instrs.translator.builder.build_call(
-            &func,
-            parameters.as_mut_slice(),
-            if return_vars.is_empty() {
-                ""
-            } else {
-                "call_indirect"
-            },
-        );
-        match return_vars.len() {
-            0 => (),
-            1 => {
-                if self.vars.borrow().len() <= return_vars[0] {
-                    self.vars
-                        .borrow_mut()
-                        .resize(return_vars[0] + 1, std::ptr::null_mut());
-                }
-                self.vars.borrow_mut()[return_vars[0]] = indirect_func_call_res
-            }
-            _ => {
-                let max_ret_var_idx = return_vars.iter().max().unwrap();
-                if self.vars.borrow().len() <= *max_ret_var_idx {
-                    self.vars
-                        .borrow_mut()
-                        .resize(max_ret_var_idx + 1, std::ptr::null_mut());
-                }
-                for (i, var) in return_vars.iter().enumerate() {
-                    self.vars.borrow_mut()[*var] = unsafe {
-                        LLVMBuildExtractValue(
-                            self.current_instrs.translator.builder.get(),
-                            indirect_func_call_res,
-                            i as u32,
-                            c_str("extract_res").as_ptr(),
-                        )
-                    };
-                }
-            }
-        };
-        self.current_instrs
-            .translator
-            .builder
-            .build_unconditional_branch(self.bbs.get(&return_bb).unwrap().bb);
+        let func = self.resolve_indirect_callee(type_idx, selector_var, table_idx);
+        self.build_resolved_call(&func, return_bb, call_params, return_vars, unwind_bb);
     }
 
     fn terminate_call(
@@ -492,6 +583,7 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
         return_bb: module::BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<module::BasicBlockID>,
     ) {
         let function = &self.llvm_functions.borrow()[func_idx as usize];
         let mut parameters =
@@ -500,7 +592,7 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
             let llvm_val = self.vars.borrow()[var_id];
             parameters.push(llvm_val);
         }
-        let call_res_val = self.current_instrs.translator.builder.build_call(
+        let call_res_val = self.current_instrs.translator.builder.build_internal_call(
             function,
             parameters.as_mut_slice(),
             if return_vars.is_empty() {
@@ -538,10 +630,70 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
                 }
             }
         };
+        self.build_call_unwind_check(return_bb, unwind_bb);
+    }
+
+    fn terminate_return_call(&mut self, func_idx: u32, call_params: SmallVec<[VariableID; 2]>) {
+        let function = &self.llvm_functions.borrow()[func_idx as usize];
+        let mut parameters =
+            vec![self.llvm_functions.borrow()[self.func_idx as usize].get_param(0)];
+        for var_id in call_params {
+            parameters.push(self.vars.borrow()[var_id]);
+        }
+        self.current_instrs
+            .translator
+            .builder
+            .build_tail_call(function, parameters.as_mut_slice());
+    }
+
+    fn terminate_return_call_indirect(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        table_idx: u32,
+        call_params: SmallVec<[VariableID; 2]>,
+    ) {
+        let func = self.resolve_indirect_callee(type_idx, selector_var, table_idx);
+        let mut parameters =
+            vec![self.llvm_functions.borrow()[self.func_idx as usize].get_param(0)];
+        for var_id in call_params {
+            parameters.push(self.vars.borrow()[var_id]);
+        }
+        self.current_instrs
+            .translator
+            .builder
+            .build_tail_call(&func, parameters.as_mut_slice());
+    }
+
+    fn terminate_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        return_bb: module::BasicBlockID,
+        call_params: SmallVec<[VariableID; 2]>,
+        return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<module::BasicBlockID>,
+    ) {
+        let func = self.resolve_ref_callee(type_idx, selector_var);
+        self.build_resolved_call(&func, return_bb, call_params, return_vars, unwind_bb);
+    }
+
+    fn terminate_return_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        call_params: SmallVec<[VariableID; 2]>,
+    ) {
+        let func = self.resolve_ref_callee(type_idx, selector_var);
+        let mut parameters =
+            vec![self.llvm_functions.borrow()[self.func_idx as usize].get_param(0)];
+        for var_id in call_params {
+            parameters.push(self.vars.borrow()[var_id]);
+        }
         self.current_instrs
             .translator
             .builder
-            .build_unconditional_branch(self.bbs.get(&return_bb).unwrap().bb);
+            .build_tail_call(&func, parameters.as_mut_slice());
     }
 
     fn terminate_jmp_cond(
@@ -612,6 +764,59 @@ impl FunctionBuilderInterface for LLVMFunctionBuilder<'_> {
         }
     }
 
+    fn terminate_throw(
+        &mut self,
+        tag_idx: TagIdx,
+        values: SmallVec<[VariableID; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        let values = values
+            .iter()
+            .map(|var| self.vars.borrow()[*var])
+            .collect::<Vec<_>>();
+        self.current_instrs.translator.build_throw(
+            tag_idx,
+            &values,
+            unwind_bb.map(|bb| self.llvm_bb(bb)),
+            &self.llvm_functions.borrow()[self.func_idx as usize],
+        );
+    }
+
+    fn terminate_throw_ref(&mut self, exn_var: VariableID, unwind_bb: Option<BasicBlockID>) {
+        self.current_instrs.translator.build_throw_ref(
+            self.vars.borrow()[exn_var],
+            unwind_bb.map(|bb| self.llvm_bb(bb)),
+            &self.llvm_functions.borrow()[self.func_idx as usize],
+        );
+    }
+
+    fn terminate_catch_dispatch(
+        &mut self,
+        handlers: SmallVec<[CatchHandler; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        let handler_bbs = handlers
+            .iter()
+            .map(|handler| self.llvm_bb(handler.target))
+            .collect::<Vec<_>>();
+        let caught = self.current_instrs.translator.build_catch_dispatch(
+            &handlers,
+            &handler_bbs,
+            unwind_bb.map(|bb| self.llvm_bb(bb)),
+            &self.llvm_functions.borrow()[self.func_idx as usize],
+        );
+        for (handler, caught) in handlers.iter().zip(caught) {
+            for (var, val) in handler.payload_vars.iter().zip(caught.payload) {
+                self.reserve_var(*var);
+                self.vars.borrow_mut()[*var] = val;
+            }
+            if let Some(exn_var) = handler.exn_var {
+                self.reserve_var(exn_var);
+                self.vars.borrow_mut()[exn_var] = caught.exn;
+            }
+        }
+    }
+
     fn eliminate_current_bb(&mut self) {
         self.context
             .delete_basic_block(self.bbs.remove(&self.current_bb_id).unwrap().bb);
@@ -0,0 +1,368 @@
+use crate::{abstraction::function::Function, error::TranslationError, translator::Translator};
+use llvm_sys::prelude::LLVMValueRef;
+use module::{
+    instructions::{
+        ArrayCopyInstruction, ArrayFillInstruction, ArrayGetInstruction,
+        ArrayInitSegmentInstruction, ArrayLenInstruction, ArrayNewFixedInstruction,
+        ArrayNewInstruction, ArrayNewSegmentInstruction, ArraySegment, ArraySetInstruction,
+        StructGetInstruction, StructNewInstruction, StructSetInstruction,
+    },
+    InstructionDecoder,
+};
+use wasm_types::{
+    AggregateInstructionCategory, InstructionType, NumType, StorageType, TypeIdx, ValType,
+};
+
+impl Translator<'_> {
+    pub(crate) fn translate_aggregate(
+        &self,
+        instr_type: AggregateInstructionCategory,
+        instruction: InstructionType,
+        decoder: &mut InstructionDecoder,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        match instr_type {
+            AggregateInstructionCategory::StructNew => {
+                let instr = decoder.read::<StructNewInstruction>(instruction)?;
+                self.compile_struct_new(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::StructGet => {
+                let instr = decoder.read::<StructGetInstruction>(instruction)?;
+                self.compile_struct_get(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::StructSet => {
+                let instr = decoder.read::<StructSetInstruction>(instruction)?;
+                self.compile_struct_set(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayNew => {
+                let instr = decoder.read::<ArrayNewInstruction>(instruction)?;
+                self.compile_array_new(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayNewFixed => {
+                let instr = decoder.read::<ArrayNewFixedInstruction>(instruction)?;
+                self.compile_array_new_fixed(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayNewData
+            | AggregateInstructionCategory::ArrayNewElem => {
+                let instr = decoder.read::<ArrayNewSegmentInstruction>(instruction)?;
+                self.compile_array_new_segment(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayGet => {
+                let instr = decoder.read::<ArrayGetInstruction>(instruction)?;
+                self.compile_array_get(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArraySet => {
+                let instr = decoder.read::<ArraySetInstruction>(instruction)?;
+                self.compile_array_set(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayLen => {
+                let instr = decoder.read::<ArrayLenInstruction>(instruction)?;
+                self.compile_array_len(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayFill => {
+                let instr = decoder.read::<ArrayFillInstruction>(instruction)?;
+                self.compile_array_fill(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayCopy => {
+                let instr = decoder.read::<ArrayCopyInstruction>(instruction)?;
+                self.compile_array_copy(instr, variable_map, llvm_function);
+            }
+            AggregateInstructionCategory::ArrayInitData
+            | AggregateInstructionCategory::ArrayInitElem => {
+                let instr = decoder.read::<ArrayInitSegmentInstruction>(instruction)?;
+                self.compile_array_init_segment(instr, variable_map, llvm_function);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn compile_struct_new(
+        &self,
+        instr: StructNewInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let fields = match instr.fields.is_empty() {
+            // struct.new_default carries no field operands
+            true => self.builder.const_null_ptr(),
+            false => {
+                let values = instr
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(field_idx, field)| {
+                        let storage_type = self.struct_field_type(instr.type_idx, field_idx as u32);
+                        self.field_to_raw(variable_map[*field], storage_type)
+                    })
+                    .collect::<Vec<_>>();
+                self.build_raw_values(&values, "struct_fields")
+            }
+        };
+        variable_map[instr.out1] =
+            self.gc_struct_new(Self::get_rt_ref(llvm_function), instr.type_idx, fields);
+    }
+
+    pub(crate) fn compile_struct_get(
+        &self,
+        instr: StructGetInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let raw = self.gc_struct_get(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.in1],
+            instr.field_idx,
+            instr.signed,
+        );
+        let storage_type = self.struct_field_type(instr.type_idx, instr.field_idx);
+        variable_map[instr.out1] = self.raw_to_field(raw, storage_type);
+    }
+
+    pub(crate) fn compile_struct_set(
+        &self,
+        instr: StructSetInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let storage_type = self.struct_field_type(instr.type_idx, instr.field_idx);
+        let value = self.field_to_raw(variable_map[instr.in2], storage_type);
+        self.gc_struct_set(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.in1],
+            instr.field_idx,
+            value,
+        );
+    }
+
+    pub(crate) fn compile_array_new(
+        &self,
+        instr: ArrayNewInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let ctxt = Self::get_rt_ref(llvm_function);
+        let len = variable_map[instr.len];
+        variable_map[instr.out1] = match instr.init {
+            Some(init) => {
+                let storage_type = self.array_field_type(instr.type_idx);
+                let init = self.field_to_raw(variable_map[init], storage_type);
+                self.gc_array_new(ctxt, instr.type_idx, init, len)
+            }
+            None => self.gc_array_new_default(ctxt, instr.type_idx, len),
+        };
+    }
+
+    pub(crate) fn compile_array_new_fixed(
+        &self,
+        instr: ArrayNewFixedInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let storage_type = self.array_field_type(instr.type_idx);
+        let values = instr
+            .values
+            .iter()
+            .map(|value| self.field_to_raw(variable_map[*value], storage_type))
+            .collect::<Vec<_>>();
+        let values_ptr = self.build_raw_values(&values, "array_values");
+        variable_map[instr.out1] = self.gc_array_new_fixed(
+            Self::get_rt_ref(llvm_function),
+            instr.type_idx,
+            values_ptr,
+            values.len() as u32,
+        );
+    }
+
+    pub(crate) fn compile_array_new_segment(
+        &self,
+        instr: ArrayNewSegmentInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let ctxt = Self::get_rt_ref(llvm_function);
+        let offset = variable_map[instr.offset];
+        let len = variable_map[instr.len];
+        variable_map[instr.out1] = match instr.segment {
+            ArraySegment::Data(data_idx) => {
+                self.gc_array_new_data(ctxt, instr.type_idx, data_idx, offset, len)
+            }
+            ArraySegment::Elem(elem_idx) => {
+                self.gc_array_new_elem(ctxt, instr.type_idx, elem_idx, offset, len)
+            }
+        };
+    }
+
+    pub(crate) fn compile_array_get(
+        &self,
+        instr: ArrayGetInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let raw = self.gc_array_get(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.array],
+            variable_map[instr.idx],
+            instr.signed,
+        );
+        let storage_type = self.array_field_type(instr.type_idx);
+        variable_map[instr.out1] = self.raw_to_field(raw, storage_type);
+    }
+
+    pub(crate) fn compile_array_set(
+        &self,
+        instr: ArraySetInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let storage_type = self.array_field_type(instr.type_idx);
+        let value = self.field_to_raw(variable_map[instr.value], storage_type);
+        self.gc_array_set(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.array],
+            variable_map[instr.idx],
+            value,
+        );
+    }
+
+    pub(crate) fn compile_array_len(
+        &self,
+        instr: ArrayLenInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        variable_map[instr.out1] =
+            self.gc_array_len(Self::get_rt_ref(llvm_function), variable_map[instr.in1]);
+    }
+
+    pub(crate) fn compile_array_fill(
+        &self,
+        instr: ArrayFillInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let storage_type = self.array_field_type(instr.type_idx);
+        let value = self.field_to_raw(variable_map[instr.value], storage_type);
+        self.gc_array_fill(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.array],
+            variable_map[instr.offset],
+            value,
+            variable_map[instr.len],
+        );
+    }
+
+    pub(crate) fn compile_array_copy(
+        &self,
+        instr: ArrayCopyInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        self.gc_array_copy(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.dst],
+            variable_map[instr.dst_offset],
+            variable_map[instr.src],
+            variable_map[instr.src_offset],
+            variable_map[instr.len],
+        );
+    }
+
+    pub(crate) fn compile_array_init_segment(
+        &self,
+        instr: ArrayInitSegmentInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let ctxt = Self::get_rt_ref(llvm_function);
+        let array = variable_map[instr.array];
+        let dst_offset = variable_map[instr.dst_offset];
+        let src_offset = variable_map[instr.src_offset];
+        let len = variable_map[instr.len];
+        match instr.segment {
+            ArraySegment::Data(data_idx) => {
+                self.gc_array_init_data(ctxt, array, data_idx, dst_offset, src_offset, len)
+            }
+            ArraySegment::Elem(elem_idx) => {
+                self.gc_array_init_elem(ctxt, array, elem_idx, dst_offset, src_offset, len)
+            }
+        }
+    }
+
+    /// Types have been validated by the parser, fields of unknown types are treated as `i64`.
+    fn struct_field_type(&self, type_idx: TypeIdx, field_idx: u32) -> StorageType {
+        self.wasm_module_meta
+            .types
+            .get(type_idx as usize)
+            .and_then(|t| t.as_struct())
+            .and_then(|fields| fields.get(field_idx as usize))
+            .map_or(StorageType::Val(ValType::i64()), |field| field.storage_type)
+    }
+
+    fn array_field_type(&self, type_idx: TypeIdx) -> StorageType {
+        self.wasm_module_meta
+            .types
+            .get(type_idx as usize)
+            .and_then(|t| t.as_array())
+            .map_or(StorageType::Val(ValType::i64()), |field| field.storage_type)
+    }
+
+    /// Field values are passed to the runtime as their raw 64-bit representation.
+    fn field_to_raw(&self, value: LLVMValueRef, storage_type: StorageType) -> LLVMValueRef {
+        match storage_type.unpacked() {
+            ValType::Number(NumType::I32) => {
+                self.builder
+                    .build_int_cast(value, self.builder.i64(), false, "field_raw")
+            }
+            ValType::Number(NumType::F32) => {
+                let bits = self
+                    .builder
+                    .build_bitcast(value, self.builder.i32(), "field_bits");
+                self.builder
+                    .build_int_cast(bits, self.builder.i64(), false, "field_raw")
+            }
+            ValType::Number(NumType::F64) => {
+                self.builder
+                    .build_bitcast(value, self.builder.i64(), "field_raw")
+            }
+            _ => value,
+        }
+    }
+
+    fn raw_to_field(&self, raw: LLVMValueRef, storage_type: StorageType) -> LLVMValueRef {
+        match storage_type.unpacked() {
+            ValType::Number(NumType::I32) => {
+                self.builder
+                    .build_int_cast(raw, self.builder.i32(), false, "field")
+            }
+            ValType::Number(NumType::F32) => {
+                let bits =
+                    self.builder
+                        .build_int_cast(raw, self.builder.i32(), false, "field_bits");
+                self.builder
+                    .build_bitcast(bits, self.builder.f32(), "field")
+            }
+            ValType::Number(NumType::F64) => {
+                self.builder.build_bitcast(raw, self.builder.f64(), "field")
+            }
+            _ => raw,
+        }
+    }
+
+    /// Stores `values` in a stack allocated array, the pointer is passed to the runtime.
+    fn build_raw_values(&self, values: &[LLVMValueRef], name: &str) -> LLVMValueRef {
+        let values_ptr = self
+            .builder
+            .build_alloca(self.builder.array(self.builder.i64(), values.len()), name);
+        for (i, value) in values.iter().enumerate() {
+            let value_ptr = self.builder.build_gep(
+                self.builder.i64(),
+                values_ptr,
+                &mut [self.builder.const_i32(i as u32)],
+                &format!("{name}_{i}_ptr"),
+            );
+            self.builder.build_store(*value, value_ptr);
+        }
+        values_ptr
+    }
+}
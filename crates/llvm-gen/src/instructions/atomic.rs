@@ -0,0 +1,256 @@
+use crate::abstraction::function::Function;
+use crate::{error::TranslationError, translator::Translator};
+use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
+use llvm_sys::{LLVMAtomicRMWBinOp, LLVMIntPredicate};
+use module::objects::memory::MemArg;
+use module::{instructions::*, InstructionDecoder};
+use wasm_types::{AtomicOp, AtomicRmwOp, InstructionType, NumType, ValType};
+
+struct AtomicRmwOpConv(AtomicRmwOp);
+impl From<AtomicRmwOpConv> for LLVMAtomicRMWBinOp {
+    fn from(val: AtomicRmwOpConv) -> Self {
+        match val.0 {
+            AtomicRmwOp::Add => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAdd,
+            AtomicRmwOp::Sub => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpSub,
+            AtomicRmwOp::And => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpAnd,
+            AtomicRmwOp::Or => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpOr,
+            AtomicRmwOp::Xor => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXor,
+            AtomicRmwOp::Xchg => LLVMAtomicRMWBinOp::LLVMAtomicRMWBinOpXchg,
+        }
+    }
+}
+
+impl Translator<'_> {
+    pub(crate) fn translate_atomic(
+        &self,
+        op: AtomicOp,
+        instruction: InstructionType,
+        decoder: &mut InstructionDecoder,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        match op {
+            AtomicOp::Load => {
+                let instr = decoder.read::<AtomicLoadInstruction>(instruction)?;
+                self.compile_atomic_load(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Store => {
+                let instr = decoder.read::<AtomicStoreInstruction>(instruction)?;
+                self.compile_atomic_store(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Rmw(_) => {
+                let instr = decoder.read::<AtomicRmwInstruction>(instruction)?;
+                self.compile_atomic_rmw(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Cmpxchg => {
+                let instr = decoder.read::<AtomicCmpxchgInstruction>(instruction)?;
+                self.compile_atomic_cmpxchg(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Wait => {
+                let instr = decoder.read::<AtomicWaitInstruction>(instruction)?;
+                self.compile_atomic_wait(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Notify => {
+                let instr = decoder.read::<AtomicNotifyInstruction>(instruction)?;
+                self.compile_atomic_notify(instr, variable_map, llvm_function)
+            }
+            AtomicOp::Fence => {
+                decoder.read::<AtomicFenceInstruction>(instruction)?;
+                self.builder.build_fence();
+                Ok(())
+            }
+        }
+    }
+
+    /// Integer type of an atomic access of `width` bytes.
+    fn atomic_access_ty(&self, width: u8) -> LLVMTypeRef {
+        match width {
+            1 => self.builder.i8(),
+            2 => self.builder.i16(),
+            4 => self.builder.i32(),
+            _ => self.builder.i64(),
+        }
+    }
+
+    /// Host pointer of an atomic access. Atomic accesses must be naturally aligned, otherwise we trap.
+    fn atomic_access_ptr(
+        &self,
+        addr: LLVMValueRef,
+        memarg: &MemArg,
+        width: u8,
+        llvm_function: &Function,
+    ) -> LLVMValueRef {
+        let effective_addr = self.effective_addr(addr, memarg, width as u64, llvm_function);
+        if width > 1 {
+            let misalignment = self.builder.build_and(
+                effective_addr,
+                self.builder.const_i64(width as u64 - 1),
+                "misalignment",
+            );
+            let is_aligned = self.builder.build_icmp(
+                LLVMIntPredicate::LLVMIntEQ,
+                misalignment,
+                self.builder.const_i64(0),
+                "is_aligned",
+            );
+            self.builder
+                .build_trap_if_is_zero(is_aligned, llvm_function.get());
+        }
+        let memory_ptr =
+            self.ec_get_mem_ptr(Self::get_rt_ref(llvm_function), memarg.memory as usize);
+        self.builder.build_gep(
+            self.builder.i8(),
+            memory_ptr,
+            &mut [effective_addr],
+            "get_storage_pos",
+        )
+    }
+
+    pub(crate) fn compile_atomic_load(
+        &self,
+        instr: AtomicLoadInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let ptr = self.atomic_access_ptr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            instr.width,
+            llvm_function,
+        );
+        let val = self.builder.build_atomic_load(
+            self.atomic_access_ty(instr.width),
+            ptr,
+            instr.width as u32,
+            "atomic_load",
+        );
+        let out_ty = self.builder.valtype2llvm(ValType::Number(instr.out1_type));
+        variable_map[instr.out1] = self.builder.build_int_cast(val, out_ty, false, "upcast");
+        Ok(())
+    }
+
+    pub(crate) fn compile_atomic_store(
+        &self,
+        instr: AtomicStoreInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let ptr = self.atomic_access_ptr(
+            variable_map[instr.addr_in],
+            &instr.memarg,
+            instr.width,
+            llvm_function,
+        );
+        let val = self.builder.build_int_cast(
+            variable_map[instr.value_in],
+            self.atomic_access_ty(instr.width),
+            false,
+            "downcast",
+        );
+        self.builder
+            .build_atomic_store(val, ptr, instr.width as u32);
+        Ok(())
+    }
+
+    pub(crate) fn compile_atomic_rmw(
+        &self,
+        instr: AtomicRmwInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let ptr = self.atomic_access_ptr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            instr.width,
+            llvm_function,
+        );
+        let val = self.builder.build_int_cast(
+            variable_map[instr.value],
+            self.atomic_access_ty(instr.width),
+            false,
+            "downcast",
+        );
+        let old = self
+            .builder
+            .build_atomic_rmw(AtomicRmwOpConv(instr.op).into(), ptr, val);
+        let out_ty = self.builder.valtype2llvm(ValType::Number(instr.r#type));
+        variable_map[instr.out1] = self.builder.build_int_cast(old, out_ty, false, "upcast");
+        Ok(())
+    }
+
+    pub(crate) fn compile_atomic_cmpxchg(
+        &self,
+        instr: AtomicCmpxchgInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let ptr = self.atomic_access_ptr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            instr.width,
+            llvm_function,
+        );
+        let access_ty = self.atomic_access_ty(instr.width);
+        // narrow variants compare against the wrapped expected value
+        let expected =
+            self.builder
+                .build_int_cast(variable_map[instr.expected], access_ty, false, "downcast");
+        let replacement = self.builder.build_int_cast(
+            variable_map[instr.replacement],
+            access_ty,
+            false,
+            "downcast",
+        );
+        let old = self
+            .builder
+            .build_atomic_cmpxchg(ptr, expected, replacement, "cmpxchg_old");
+        let out_ty = self.builder.valtype2llvm(ValType::Number(instr.r#type));
+        variable_map[instr.out1] = self.builder.build_int_cast(old, out_ty, false, "upcast");
+        Ok(())
+    }
+
+    pub(crate) fn compile_atomic_wait(
+        &self,
+        instr: AtomicWaitInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        // alignment, bounds and shared-ness are checked by the runtime
+        let width = match instr.r#type {
+            NumType::I64 | NumType::F64 => 8,
+            NumType::I32 | NumType::F32 => 4,
+        };
+        let address = self.effective_addr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            width,
+            llvm_function,
+        );
+        variable_map[instr.out1] = self.memory_atomic_wait(
+            Self::get_rt_ref(llvm_function),
+            instr.memarg.memory,
+            address,
+            variable_map[instr.expected],
+            variable_map[instr.timeout],
+            instr.r#type == NumType::I64,
+        );
+        Ok(())
+    }
+
+    pub(crate) fn compile_atomic_notify(
+        &self,
+        instr: AtomicNotifyInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let address =
+            self.effective_addr(variable_map[instr.addr], &instr.memarg, 4, llvm_function);
+        variable_map[instr.out1] = self.memory_atomic_notify(
+            Self::get_rt_ref(llvm_function),
+            instr.memarg.memory,
+            address,
+            variable_map[instr.count],
+        );
+        Ok(())
+    }
+}
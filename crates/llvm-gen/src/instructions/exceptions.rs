@@ -0,0 +1,183 @@
+use crate::{abstraction::function::Function, Translator};
+use llvm_sys::{
+    prelude::{LLVMBasicBlockRef, LLVMValueRef},
+    LLVMIntPredicate,
+};
+use module::basic_block::CatchHandler;
+use wasm_types::{ImportDesc, TagIdx};
+
+/// Values defined by a catch handler, only valid in the handler's basic block.
+pub(crate) struct CaughtException {
+    pub(crate) payload: Vec<LLVMValueRef>,
+    pub(crate) exn: LLVMValueRef,
+}
+
+impl Translator<'_> {
+    /// Whether exceptions can reach this module's functions. Without own tags they can only originate from other
+    /// modules, which we can only call through imported functions or tables shared with them.
+    pub(crate) fn may_unwind(&self) -> bool {
+        !self.wasm_module_meta.tags.is_empty()
+            || !self.wasm_module_meta.exports.tables.is_empty()
+            || self
+                .wasm_module_meta
+                .imports
+                .iter()
+                .any(|import| matches!(import.desc, ImportDesc::Func(_) | ImportDesc::Table(_)))
+    }
+
+    /// Continues the pending exception at `unwind_bb`, or leaves the function if there is none.
+    pub(crate) fn build_unwind(
+        &self,
+        unwind_bb: Option<LLVMBasicBlockRef>,
+        llvm_function: &Function,
+    ) {
+        match unwind_bb {
+            Some(unwind_bb) => self.builder.build_unconditional_branch(unwind_bb),
+            None => self.builder.build_ret_poison(llvm_function),
+        }
+    }
+
+    /// Terminates the current block after a call: branches to `unwind_bb` if the callee raised an exception, to
+    /// `return_bb` otherwise.
+    pub(crate) fn build_call_unwind_check(
+        &self,
+        return_bb: LLVMBasicBlockRef,
+        unwind_bb: Option<LLVMBasicBlockRef>,
+        llvm_function: &Function,
+    ) {
+        if !self.may_unwind() {
+            self.builder.build_unconditional_branch(return_bb);
+            return;
+        }
+        let pending = self.exception_pending(Self::get_rt_ref(llvm_function));
+        let is_pending = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntNE,
+            pending,
+            self.builder.const_i32(0),
+            "is_exception_pending",
+        );
+        match unwind_bb {
+            Some(unwind_bb) => self
+                .builder
+                .build_conditional_branch(is_pending, unwind_bb, return_bb),
+            None => {
+                let propagate_bb = self
+                    .context
+                    .append_basic_block(llvm_function.get(), "propagate_exception");
+                self.builder
+                    .build_conditional_branch(is_pending, propagate_bb, return_bb);
+                self.builder.position_at_end(propagate_bb);
+                self.builder.build_ret_poison(llvm_function);
+            }
+        }
+    }
+
+    pub(crate) fn build_throw(
+        &self,
+        tag_idx: TagIdx,
+        values: &[LLVMValueRef],
+        unwind_bb: Option<LLVMBasicBlockRef>,
+        llvm_function: &Function,
+    ) {
+        let payload_ptr = self.builder.build_alloca(
+            self.builder
+                .array(self.builder.value_raw_ty(), values.len()),
+            "exception_payload",
+        );
+        for (i, value) in values.iter().enumerate() {
+            let value_ptr = self.builder.build_gep(
+                self.builder.value_raw_ty(),
+                payload_ptr,
+                &mut [self.builder.const_i32(i as u32)],
+                &format!("exception_payload_{i}_ptr"),
+            );
+            self.builder.build_store(*value, value_ptr);
+        }
+        self.exception_throw(Self::get_rt_ref(llvm_function), tag_idx, payload_ptr);
+        self.build_unwind(unwind_bb, llvm_function);
+    }
+
+    pub(crate) fn build_throw_ref(
+        &self,
+        exn: LLVMValueRef,
+        unwind_bb: Option<LLVMBasicBlockRef>,
+        llvm_function: &Function,
+    ) {
+        // the runtime traps on null references
+        self.exception_throw_ref(Self::get_rt_ref(llvm_function), exn);
+        self.build_unwind(unwind_bb, llvm_function);
+    }
+
+    /// Tests the pending exception against `handlers` in order and branches to the first matching handler block
+    /// after catching the exception. If no handler matches, the exception keeps unwinding.
+    pub(crate) fn build_catch_dispatch(
+        &self,
+        handlers: &[CatchHandler],
+        handler_bbs: &[LLVMBasicBlockRef],
+        unwind_bb: Option<LLVMBasicBlockRef>,
+        llvm_function: &Function,
+    ) -> Vec<CaughtException> {
+        let rt_ref = Self::get_rt_ref(llvm_function);
+        let mut caught = Vec::with_capacity(handlers.len());
+        for (handler, handler_bb) in handlers.iter().zip(handler_bbs.iter()) {
+            let is_match = match handler.tag_idx {
+                Some(tag_idx) => self.builder.build_icmp(
+                    LLVMIntPredicate::LLVMIntNE,
+                    self.exception_matches(rt_ref, tag_idx),
+                    self.builder.const_i32(0),
+                    "is_catch_match",
+                ),
+                None => self.builder.const_i1(true),
+            };
+            let catch_bb = self
+                .context
+                .append_basic_block(llvm_function.get(), "catch");
+            let next_bb = self
+                .context
+                .append_basic_block(llvm_function.get(), "catch_next");
+            self.builder
+                .build_conditional_branch(is_match, catch_bb, next_bb);
+
+            self.builder.position_at_end(catch_bb);
+            // catch_all handlers don't receive the payload
+            let (payload_types, payload_ptr) = match handler.tag_idx {
+                Some(tag_idx) => {
+                    let tag = &self.wasm_module_meta.tags[tag_idx as usize];
+                    let payload_types =
+                        self.wasm_module_meta.function_types[tag.type_idx as usize].params();
+                    let payload_ptr = self.builder.build_alloca(
+                        self.builder
+                            .array(self.builder.value_raw_ty(), payload_types.len()),
+                        "caught_payload",
+                    );
+                    (payload_types, payload_ptr)
+                }
+                None => (Vec::new(), self.builder.const_null_ptr()),
+            };
+            let exn = self.exception_catch(rt_ref, payload_ptr);
+            let payload = payload_types
+                .iter()
+                .enumerate()
+                .map(|(i, ty)| {
+                    let value_ptr = self.builder.build_gep(
+                        self.builder.value_raw_ty(),
+                        payload_ptr,
+                        &mut [self.builder.const_i32(i as u32)],
+                        &format!("caught_payload_{i}_ptr"),
+                    );
+                    self.builder.build_load(
+                        self.builder.valtype2llvm(*ty),
+                        value_ptr,
+                        &format!("caught_payload_{i}"),
+                    )
+                })
+                .collect();
+            self.builder.build_unconditional_branch(*handler_bb);
+            caught.push(CaughtException { payload, exn });
+
+            self.builder.position_at_end(next_bb);
+        }
+        self.build_unwind(unwind_bb, llvm_function);
+        caught
+    }
+}
@@ -171,6 +171,53 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         self.finish(ControlInstruction::Return);
     }
 
+    fn write_return_call(&mut self, i: module::instructions::ReturnCall) {
+        self.finish(ControlInstruction::ReturnCall(i.func_idx));
+    }
+
+    fn write_call_ref(&mut self, i: module::instructions::CallRef) {
+        self.finish(ControlInstruction::CallRef(i.type_idx));
+    }
+
+    fn write_return_call_ref(&mut self, i: module::instructions::ReturnCallRef) {
+        self.finish(ControlInstruction::ReturnCallRef(i.type_idx));
+    }
+
+    fn write_br_on_null(&mut self, i: module::instructions::BrOnNull) {
+        self.finish(ControlInstruction::BrOnNull(i.label_idx));
+    }
+
+    fn write_br_on_non_null(&mut self, i: module::instructions::BrOnNonNull) {
+        self.finish(ControlInstruction::BrOnNonNull(i.label_idx));
+    }
+
+    fn write_br_on_cast(&mut self, i: module::instructions::BrOnCast) {
+        self.finish(ControlInstruction::BrOnCast(i.label_idx, i.from, i.to));
+    }
+
+    fn write_br_on_cast_fail(&mut self, i: module::instructions::BrOnCastFail) {
+        self.finish(ControlInstruction::BrOnCastFail(i.label_idx, i.from, i.to));
+    }
+
+    fn write_return_call_indirect(&mut self, i: module::instructions::ReturnCallIndirect) {
+        self.finish(ControlInstruction::ReturnCallIndirect(
+            i.type_idx,
+            i.table_idx,
+        ));
+    }
+
+    fn write_try_table(&mut self, i: module::instructions::TryTable) {
+        self.finish(ControlInstruction::TryTable(i.block_type, i.catches));
+    }
+
+    fn write_throw(&mut self, i: module::instructions::Throw) {
+        self.finish(ControlInstruction::Throw(i.tag_idx));
+    }
+
+    fn write_throw_ref(&mut self) {
+        self.finish(ControlInstruction::ThrowRef);
+    }
+
     fn write_unreachable(&mut self) {
         self.finish(ControlInstruction::Unreachable);
     }
@@ -217,9 +264,9 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
 
     fn write_memory_size(&mut self, i: module::instructions::MemorySizeInstruction) {
         self.extend_vars(i.out1);
-        self.vars.borrow_mut()[i.out1] = self.translator.ec_get_mem_size(
+        self.vars.borrow_mut()[i.out1] = self.translator.memory_size(
             Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx]),
-            0,
+            i.memory_idx,
         );
     }
 
@@ -228,7 +275,7 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         let mut vars = self.vars.borrow_mut();
         vars[i.out1] = self.translator.memory_grow(
             Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx]),
-            0,
+            i.memory_idx as usize,
             vars[i.in1],
         );
     }
@@ -237,7 +284,8 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         let vars = self.vars.borrow();
         self.translator.memory_copy(
             Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx]),
-            0,
+            i.memory_idx_x,
+            i.memory_idx_y,
             vars[i.s],
             vars[i.d],
             vars[i.n],
@@ -254,7 +302,7 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         );
         self.translator.memory_fill(
             Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx]),
-            0,
+            i.memory_idx,
             vars[i.d],
             vars[i.n],
             value,
@@ -265,7 +313,7 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         let vars = self.vars.borrow();
         self.translator.memory_init(
             Translator::get_rt_ref(&self.llvm_functions.borrow()[self.func_idx]),
-            0,
+            i.memory_idx,
             i.data_idx,
             vars[i.s],
             vars[i.d],
@@ -459,6 +507,27 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         );
     }
 
+    fn write_reference_as_non_null(
+        &mut self,
+        i: module::instructions::ReferenceAsNonNullInstruction,
+    ) {
+        self.extend_vars(i.out1);
+        let val = self.vars.borrow()[i.in1];
+        let is_non_null = self.translator.builder.build_icmp(
+            llvm_sys::LLVMIntPredicate::LLVMIntNE,
+            val,
+            self.translator
+                .builder
+                .const_i64(ValueRaw::from(Value::Reference(Reference::Null)).as_u64()),
+            "ref_is_non_null",
+        );
+        self.translator.builder.build_trap_if_is_zero(
+            is_non_null,
+            self.llvm_functions.borrow()[self.func_idx].get(),
+        );
+        self.vars.borrow_mut()[i.out1] = val;
+    }
+
     fn write_reference_null(&mut self, i: module::instructions::ReferenceNullInstruction) {
         self.extend_vars(i.out1);
         self.vars.borrow_mut()[i.out1] = self
@@ -472,6 +541,148 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         self.vars.borrow_mut()[i.out1] = self.translator.builder.const_i64(i.func_idx as u64);
     }
 
+    fn write_reference_eq(&mut self, i: module::instructions::ReferenceEqInstruction) {
+        self.extend_vars(i.out1);
+        self.translator
+            .compile_reference_eq(i, &mut self.vars.borrow_mut());
+    }
+
+    fn write_reference_test(&mut self, i: module::instructions::ReferenceTestInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_reference_test(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_reference_cast(&mut self, i: module::instructions::ReferenceCastInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_reference_cast(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_reference_i31(&mut self, i: module::instructions::ReferenceI31Instruction) {
+        self.extend_vars(i.out1);
+        self.translator
+            .compile_reference_i31(i, &mut self.vars.borrow_mut());
+    }
+
+    fn write_i31_get(&mut self, i: module::instructions::I31GetInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_i31_get(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_struct_new(&mut self, i: module::instructions::StructNewInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_struct_new(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_struct_get(&mut self, i: module::instructions::StructGetInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_struct_get(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_struct_set(&mut self, i: module::instructions::StructSetInstruction) {
+        self.translator.compile_struct_set(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_new(&mut self, i: module::instructions::ArrayNewInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_array_new(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_new_fixed(&mut self, i: module::instructions::ArrayNewFixedInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_array_new_fixed(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_new_segment(&mut self, i: module::instructions::ArrayNewSegmentInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_array_new_segment(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_get(&mut self, i: module::instructions::ArrayGetInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_array_get(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_set(&mut self, i: module::instructions::ArraySetInstruction) {
+        self.translator.compile_array_set(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_len(&mut self, i: module::instructions::ArrayLenInstruction) {
+        self.extend_vars(i.out1);
+        self.translator.compile_array_len(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_fill(&mut self, i: module::instructions::ArrayFillInstruction) {
+        self.translator.compile_array_fill(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_copy(&mut self, i: module::instructions::ArrayCopyInstruction) {
+        self.translator.compile_array_copy(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
+    fn write_array_init_segment(&mut self, i: module::instructions::ArrayInitSegmentInstruction) {
+        self.translator.compile_array_init_segment(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        );
+    }
+
     fn write_select(&mut self, i: module::instructions::SelectInstruction) {
         self.extend_vars(i.out1);
         let mut vars = self.vars.borrow_mut();
@@ -638,6 +849,222 @@ impl module::InstructionConsumer for LLVMInstructionConsumer<'_> {
         vars[i.out1] = vars[i.in1];
     }
 
+    fn write_v128_const(&mut self, i: module::instructions::VConstInstruction) {
+        self.extend_vars(i.out1);
+        self.vars.borrow_mut()[i.out1] = self.translator.builder.const_v128(i.imm);
+    }
+
+    fn write_v128_load(&mut self, i: module::instructions::VLoadInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_v128_load(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_v128_store(&mut self, i: module::instructions::VStoreInstruction) {
+        if let Err(e) = self.translator.compile_v128_store(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_v128_load_lane(&mut self, i: module::instructions::VLoadLaneInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_v128_load_lane(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_v128_store_lane(&mut self, i: module::instructions::VStoreLaneInstruction) {
+        if let Err(e) = self.translator.compile_v128_store_lane(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_shuffle(&mut self, i: module::instructions::VShuffleInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_shuffle(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_splat(&mut self, i: module::instructions::VSplatInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_splat(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_extract_lane(&mut self, i: module::instructions::VExtractLaneInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_extract_lane(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_replace_lane(&mut self, i: module::instructions::VReplaceLaneInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_replace_lane(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_vunary(&mut self, i: module::instructions::VUnaryInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_vunary(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_vbinary(&mut self, i: module::instructions::VBinaryInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_vbinary(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_bitselect(&mut self, i: module::instructions::VBitselectInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_bitselect(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_vrelational(&mut self, i: module::instructions::VRelationalInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_vrelational(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_vshift(&mut self, i: module::instructions::VShiftInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_vshift(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_vtest(&mut self, i: module::instructions::VTestInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self
+            .translator
+            .compile_vtest(i, &mut self.vars.borrow_mut())
+        {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_load(&mut self, i: module::instructions::AtomicLoadInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_atomic_load(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_store(&mut self, i: module::instructions::AtomicStoreInstruction) {
+        if let Err(e) = self.translator.compile_atomic_store(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_rmw(&mut self, i: module::instructions::AtomicRmwInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_atomic_rmw(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_cmpxchg(&mut self, i: module::instructions::AtomicCmpxchgInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_atomic_cmpxchg(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_wait(&mut self, i: module::instructions::AtomicWaitInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_atomic_wait(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_notify(&mut self, i: module::instructions::AtomicNotifyInstruction) {
+        self.extend_vars(i.out1);
+        if let Err(e) = self.translator.compile_atomic_notify(
+            i,
+            &mut self.vars.borrow_mut(),
+            &self.llvm_functions.borrow()[self.func_idx],
+        ) {
+            self.error = Some(e);
+        }
+    }
+
+    fn write_atomic_fence(&mut self, _: module::instructions::AtomicFenceInstruction) {
+        self.translator.builder.build_fence();
+    }
+
     fn finish(&mut self, terminator: module::objects::instruction::ControlInstruction) {
         self.terminator = Some(terminator);
     }
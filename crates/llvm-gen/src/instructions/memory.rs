@@ -1,7 +1,6 @@
-use crate::util::c_str;
 use crate::{abstraction::function::Function, TranslationError, Translator};
-use llvm_sys::core::LLVMBuildAdd;
 use llvm_sys::prelude::LLVMValueRef;
+use llvm_sys::LLVMIntPredicate;
 use module::instructions::{
     DataDropInstruction, MemoryCopyInstruction, MemoryFillInstruction, MemoryGrowInstruction,
     MemoryInitInstruction, MemorySizeInstruction,
@@ -11,7 +10,10 @@ use module::{
     instructions::{LoadInstruction, StoreInstruction},
     InstructionDecoder,
 };
-use wasm_types::{InstructionType, LoadOp, MemoryInstructionCategory, MemoryOp, StoreOp};
+use wasm_types::{
+    IndexType, InstructionType, LoadOp, MemIdx, MemoryInstructionCategory, MemoryOp, NumType,
+    StoreOp,
+};
 
 impl Translator<'_> {
     pub(crate) fn translate_memory(
@@ -34,14 +36,19 @@ impl Translator<'_> {
             MemoryInstructionCategory::Memory(op) => match op {
                 MemoryOp::Size => {
                     let instr = decoder.read::<MemorySizeInstruction>(instruction)?;
-                    let mem_size = self.ec_get_mem_size(Self::get_rt_ref(llvm_function), 0);
+                    let mem_size =
+                        self.memory_size(Self::get_rt_ref(llvm_function), instr.memory_idx);
                     variable_map[instr.out1] = mem_size;
                     Ok(())
                 }
                 MemoryOp::Grow => {
                     let instr = decoder.read::<MemoryGrowInstruction>(instruction)?;
                     let grow_by = variable_map[instr.in1];
-                    let res = self.memory_grow(Self::get_rt_ref(llvm_function), 0, grow_by);
+                    let res = self.memory_grow(
+                        Self::get_rt_ref(llvm_function),
+                        instr.memory_idx as usize,
+                        grow_by,
+                    );
                     variable_map[instr.out1] = res;
                     Ok(())
                 }
@@ -49,7 +56,7 @@ impl Translator<'_> {
                     let instr = decoder.read::<MemoryInitInstruction>(instruction)?;
                     self.memory_init(
                         Self::get_rt_ref(llvm_function),
-                        0,
+                        instr.memory_idx,
                         instr.data_idx,
                         variable_map[instr.s],
                         variable_map[instr.d],
@@ -67,7 +74,7 @@ impl Translator<'_> {
                     );
                     self.memory_fill(
                         Self::get_rt_ref(llvm_function),
-                        0,
+                        instr.memory_idx,
                         variable_map[instr.d],
                         variable_map[instr.n],
                         value,
@@ -78,7 +85,8 @@ impl Translator<'_> {
                     let instr = decoder.read::<MemoryCopyInstruction>(instruction)?;
                     self.memory_copy(
                         Self::get_rt_ref(llvm_function),
-                        0,
+                        instr.memory_idx_x,
+                        instr.memory_idx_y,
                         variable_map[instr.s],
                         variable_map[instr.d],
                         variable_map[instr.n],
@@ -91,6 +99,9 @@ impl Translator<'_> {
                     Ok(())
                 }
             },
+            MemoryInstructionCategory::Atomic(op) => {
+                self.translate_atomic(op, instruction, decoder, variable_map, llvm_function)
+            }
         }
     }
 
@@ -100,8 +111,18 @@ impl Translator<'_> {
         variable_map: &mut [LLVMValueRef],
         llvm_function: &Function,
     ) -> Result<(), TranslationError> {
-        let memory_ptr = self.ec_get_mem_ptr(Self::get_rt_ref(llvm_function), 0);
-        let addr = self.calc_addr(variable_map[instr.addr], memory_ptr, &instr.memarg);
+        let width = match instr.operation {
+            LoadOp::INNLoad | LoadOp::FNNLoad => full_width(instr.out1_type),
+            LoadOp::INNLoad8S | LoadOp::INNLoad8U => 1,
+            LoadOp::INNLoad16S | LoadOp::INNLoad16U => 2,
+            LoadOp::INNLoad32S | LoadOp::INNLoad32U => 4,
+        };
+        let addr = self.calc_addr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            width,
+            llvm_function,
+        );
         let out_ty = self
             .builder
             .valtype2llvm(wasm_types::ValType::Number(instr.out1_type));
@@ -135,27 +156,96 @@ impl Translator<'_> {
         Ok(())
     }
 
-    fn calc_addr(
+    /// 64-bit effective address of a `width` byte access to memory `memarg.memory`.
+    /// Out of bounds accesses to 32-bit memories hit the guard region reserved behind the memory, 64-bit
+    /// memories can't reserve their whole address space and are bounds checked explicitly.
+    pub(super) fn effective_addr(
         &self,
-        mut base_addr: LLVMValueRef,
-        memory_ptr: LLVMValueRef,
+        base_addr: LLVMValueRef,
         memarg: &MemArg,
+        width: u64,
+        llvm_function: &Function,
     ) -> LLVMValueRef {
-        if memarg.offset != 0 {
-            base_addr = unsafe {
-                LLVMBuildAdd(
-                    self.builder.get(),
-                    base_addr,
-                    self.builder.const_i32(memarg.offset),
-                    c_str("add_memarg_offset").as_ptr(),
-                )
-            };
+        // zero-extending 32-bit addresses keeps the addition of the offset from wrapping around
+        let base_addr =
+            self.builder
+                .build_int_cast(base_addr, self.builder.i64(), false, "extend_addr");
+        if self.wasm_module_meta.memories[memarg.memory as usize]
+            .r#type
+            .index_type
+            == IndexType::I64
+        {
+            self.build_bounds_check(
+                memarg.memory,
+                base_addr,
+                memarg.offset.saturating_add(width),
+                llvm_function,
+            );
+        }
+        if memarg.offset == 0 {
+            return base_addr;
         }
+        self.builder.build_add(
+            base_addr,
+            self.builder.const_i64(memarg.offset),
+            "add_memarg_offset",
+        )
+    }
+
+    /// Traps unless `base_addr + access_end` lies within memory `memory_idx`, without computing the
+    /// possibly overflowing sum.
+    fn build_bounds_check(
+        &self,
+        memory_idx: MemIdx,
+        base_addr: LLVMValueRef,
+        access_end: u64,
+        llvm_function: &Function,
+    ) {
+        let mem_size = self.ec_get_mem_size(Self::get_rt_ref(llvm_function), memory_idx as usize);
+        let mem_size = self.builder.build_shl(
+            mem_size,
+            self.builder.const_i64(WASM_PAGE_SIZE_LOG2),
+            "mem_size_bytes",
+        );
+        let access_end = self.builder.const_i64(access_end);
+        let access_fits = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntULE,
+            access_end,
+            mem_size,
+            "access_fits",
+        );
+        let max_base_addr = self
+            .builder
+            .build_sub(mem_size, access_end, "max_base_addr");
+        let base_in_bounds = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntULE,
+            base_addr,
+            max_base_addr,
+            "base_in_bounds",
+        );
+        let in_bounds = self
+            .builder
+            .build_and(access_fits, base_in_bounds, "in_bounds");
+        self.builder
+            .build_trap_if_is_zero(in_bounds, llvm_function.get());
+    }
+
+    /// Host address of a `width` byte access to memory `memarg.memory`.
+    pub(super) fn calc_addr(
+        &self,
+        base_addr: LLVMValueRef,
+        memarg: &MemArg,
+        width: u64,
+        llvm_function: &Function,
+    ) -> LLVMValueRef {
+        let effective_addr = self.effective_addr(base_addr, memarg, width, llvm_function);
+        let memory_ptr =
+            self.ec_get_mem_ptr(Self::get_rt_ref(llvm_function), memarg.memory as usize);
         // memarg.align may be ignored (see reference)
         self.builder.build_gep(
             self.builder.i8(),
             memory_ptr,
-            &mut [base_addr],
+            &mut [effective_addr],
             "get_storage_pos",
         )
     }
@@ -166,9 +256,19 @@ impl Translator<'_> {
         variable_map: &mut [LLVMValueRef],
         llvm_function: &Function,
     ) -> Result<(), TranslationError> {
-        let memory_ptr = self.ec_get_mem_ptr(Self::get_rt_ref(llvm_function), 0);
+        let width = match instr.operation {
+            StoreOp::INNStore | StoreOp::FNNStore => full_width(instr.in_type),
+            StoreOp::INNStore8 => 1,
+            StoreOp::INNStore16 => 2,
+            StoreOp::INNStore32 => 4,
+        };
         let val = variable_map[instr.value_in];
-        let addr = self.calc_addr(variable_map[instr.addr_in], memory_ptr, &instr.memarg);
+        let addr = self.calc_addr(
+            variable_map[instr.addr_in],
+            &instr.memarg,
+            width,
+            llvm_function,
+        );
 
         let val = match instr.operation {
             StoreOp::INNStore | StoreOp::FNNStore => val,
@@ -189,3 +289,14 @@ impl Translator<'_> {
         Ok(())
     }
 }
+
+// log2 of the wasm page size
+const WASM_PAGE_SIZE_LOG2: u64 = 16;
+
+/// Width in bytes of a full width access of type `t`.
+fn full_width(t: NumType) -> u64 {
+    match t {
+        NumType::I32 | NumType::F32 => 4,
+        NumType::I64 | NumType::F64 => 8,
+    }
+}
@@ -1,3 +1,6 @@
+mod aggregate;
+mod atomic;
+pub(crate) mod exceptions;
 mod memory;
 mod numeric;
 mod parametric;
@@ -5,5 +8,6 @@ mod reference;
 mod table;
 mod terminators;
 mod variable;
+mod vector;
 
 pub(crate) mod instruction_consumer;
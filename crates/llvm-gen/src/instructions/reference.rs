@@ -1,10 +1,12 @@
-use crate::{error::TranslationError, translator::Translator};
+use crate::{abstraction::function::Function, error::TranslationError, translator::Translator};
 use llvm_sys::{prelude::LLVMValueRef, LLVMIntPredicate};
 use module::{
     instructions::{
-        ReferenceFunctionInstruction, ReferenceIsNullInstruction, ReferenceNullInstruction,
+        I31GetInstruction, ReferenceAsNonNullInstruction, ReferenceCastInstruction,
+        ReferenceEqInstruction, ReferenceFunctionInstruction, ReferenceI31Instruction,
+        ReferenceIsNullInstruction, ReferenceNullInstruction, ReferenceTestInstruction,
     },
-    objects::value::{Reference, Value, ValueRaw},
+    objects::value::{gc, Reference, Value, ValueRaw},
     InstructionDecoder,
 };
 use wasm_types::{InstructionType, ReferenceInstructionType};
@@ -16,6 +18,7 @@ impl Translator<'_> {
         instruction: InstructionType,
         decoder: &mut InstructionDecoder,
         variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
     ) -> Result<(), TranslationError> {
         match instr_type {
             ReferenceInstructionType::RefFunc => {
@@ -36,13 +39,143 @@ impl Translator<'_> {
                     self.builder
                         .build_int_cast(val, self.builder.i32(), false, "ref_is_null")
             }
+            ReferenceInstructionType::RefAsNonNull => {
+                let instr = decoder.read::<ReferenceAsNonNullInstruction>(instruction)?;
+                let val = variable_map[instr.in1];
+                let is_non_null = self.builder.build_icmp(
+                    LLVMIntPredicate::LLVMIntNE,
+                    val,
+                    self.builder
+                        .const_i64(ValueRaw::from(Value::Reference(Reference::Null)).as_u64()),
+                    "ref_is_non_null",
+                );
+                self.builder
+                    .build_trap_if_is_zero(is_non_null, llvm_function.get());
+                variable_map[instr.out1] = val;
+            }
             ReferenceInstructionType::RefNull => {
                 let instr = decoder.read::<ReferenceNullInstruction>(instruction)?;
                 variable_map[instr.out1] = self
                     .builder
                     .const_i64(ValueRaw::from(Value::Reference(Reference::Null)).as_u64());
             }
+            ReferenceInstructionType::RefEq => {
+                let instr = decoder.read::<ReferenceEqInstruction>(instruction)?;
+                self.compile_reference_eq(instr, variable_map);
+            }
+            ReferenceInstructionType::RefTest => {
+                let instr = decoder.read::<ReferenceTestInstruction>(instruction)?;
+                self.compile_reference_test(instr, variable_map, llvm_function);
+            }
+            ReferenceInstructionType::RefCast => {
+                let instr = decoder.read::<ReferenceCastInstruction>(instruction)?;
+                self.compile_reference_cast(instr, variable_map, llvm_function);
+            }
+            ReferenceInstructionType::RefI31 => {
+                let instr = decoder.read::<ReferenceI31Instruction>(instruction)?;
+                self.compile_reference_i31(instr, variable_map);
+            }
+            ReferenceInstructionType::I31Get => {
+                let instr = decoder.read::<I31GetInstruction>(instruction)?;
+                self.compile_i31_get(instr, variable_map, llvm_function);
+            }
         }
         Ok(())
     }
+
+    /// eqref values are identical iff their raw representations are.
+    pub(crate) fn compile_reference_eq(
+        &self,
+        instr: ReferenceEqInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) {
+        let eq = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntEQ,
+            variable_map[instr.in1],
+            variable_map[instr.in2],
+            "ref_eq",
+        );
+        variable_map[instr.out1] =
+            self.builder
+                .build_int_cast(eq, self.builder.i32(), false, "ref_eq");
+    }
+
+    pub(crate) fn compile_reference_test(
+        &self,
+        instr: ReferenceTestInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        variable_map[instr.out1] = self.gc_ref_test(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.in1],
+            instr.ref_type,
+        );
+    }
+
+    pub(crate) fn compile_reference_cast(
+        &self,
+        instr: ReferenceCastInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        variable_map[instr.out1] = self.gc_ref_cast(
+            Self::get_rt_ref(llvm_function),
+            variable_map[instr.in1],
+            instr.ref_type,
+        );
+    }
+
+    /// i31 references are tagged values, see [`gc::i31`].
+    pub(crate) fn compile_reference_i31(
+        &self,
+        instr: ReferenceI31Instruction,
+        variable_map: &mut [LLVMValueRef],
+    ) {
+        let value = self.builder.build_and(
+            variable_map[instr.in1],
+            self.builder.const_i32(0x7FFF_FFFF),
+            "i31_value",
+        );
+        let value = self
+            .builder
+            .build_int_cast(value, self.builder.i64(), false, "i31_value");
+        variable_map[instr.out1] =
+            self.builder
+                .build_or(value, self.builder.const_i64(gc::I31_TAG), "ref_i31");
+    }
+
+    pub(crate) fn compile_i31_get(
+        &self,
+        instr: I31GetInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) {
+        let val = variable_map[instr.in1];
+        let is_non_null = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntNE,
+            val,
+            self.builder.const_i64(gc::NULL),
+            "i31_is_non_null",
+        );
+        self.builder
+            .build_trap_if_is_zero(is_non_null, llvm_function.get());
+        let value = self
+            .builder
+            .build_int_cast(val, self.builder.i32(), false, "i31_value");
+        variable_map[instr.out1] = match instr.signed {
+            // sign-extend bit 30
+            true => {
+                let shifted = self
+                    .builder
+                    .build_shl(value, self.builder.const_i32(1), "i31_shl");
+                self.builder
+                    .build_ashr(shifted, self.builder.const_i32(1), "i31_get_s")
+            }
+            false => {
+                self.builder
+                    .build_and(value, self.builder.const_i32(0x7FFF_FFFF), "i31_get_u")
+            }
+        };
+    }
 }
@@ -59,6 +59,7 @@ impl Translator<'_> {
                 return_bb,
                 call_params,
                 return_vars,
+                unwind_bb,
             } => {
                 let function = &self.llvm_functions.borrow()[*func_idx as usize];
                 let mut parameters = vec![llvm_function.get_param(0)];
@@ -66,7 +67,7 @@ impl Translator<'_> {
                     let llvm_val = variable_map[*var_id];
                     parameters.push(llvm_val);
                 }
-                let call_res_val = self.builder.build_call(
+                let call_res_val = self.builder.build_internal_call(
                     function,
                     parameters.as_mut_slice(),
                     if return_vars.is_empty() {
@@ -93,8 +94,11 @@ impl Translator<'_> {
                         }
                     }
                 };
-                self.builder
-                    .build_unconditional_branch(function_bbs[*return_bb as usize]);
+                self.build_call_unwind_check(
+                    function_bbs[*return_bb as usize],
+                    unwind_bb.map(|bb| function_bbs[bb as usize]),
+                    llvm_function,
+                );
             }
             BasicBlockGlue::Unreachable => self.builder.build_unreachable(),
             BasicBlockGlue::JmpTable {
@@ -130,24 +134,21 @@ impl Translator<'_> {
                 return_bb,
                 call_params,
                 return_vars,
+                unwind_bb,
             } => {
-                let selector_var = variable_map[*selector_var];
-                let resolved_func_ptr = self.indirect_call(
-                    Self::get_rt_ref(llvm_function),
-                    *table_idx,
+                let func = self.resolve_indirect_callee(
                     *type_idx,
-                    selector_var,
-                );
-                let indirect_func_llvm_type =
-                    self.llvm_internal_func_type_from_wasm(*type_idx as usize)?;
-                let func = Function::new(resolved_func_ptr, indirect_func_llvm_type).unwrap();
+                    variable_map[*selector_var],
+                    *table_idx,
+                    llvm_function,
+                )?;
 
                 let mut parameters = vec![llvm_function.get_param(0)];
                 for var_id in call_params {
                     let llvm_val = variable_map[*var_id];
                     parameters.push(llvm_val);
                 }
-                let indirect_func_call_res = self.builder.build_call(
+                let indirect_func_call_res = self.builder.build_internal_call(
                     &func,
                     parameters.as_mut_slice(),
                     if return_vars.is_empty() {
@@ -172,8 +173,141 @@ impl Translator<'_> {
                         }
                     }
                 };
+                self.build_call_unwind_check(
+                    function_bbs[*return_bb as usize],
+                    unwind_bb.map(|bb| function_bbs[bb as usize]),
+                    llvm_function,
+                );
+            }
+            BasicBlockGlue::CallRef {
+                type_idx,
+                selector_var,
+                return_bb,
+                call_params,
+                return_vars,
+                unwind_bb,
+            } => {
+                let func =
+                    self.resolve_ref_callee(*type_idx, variable_map[*selector_var], llvm_function)?;
+
+                let mut parameters = vec![llvm_function.get_param(0)];
+                for var_id in call_params {
+                    let llvm_val = variable_map[*var_id];
+                    parameters.push(llvm_val);
+                }
+                let ref_func_call_res = self.builder.build_internal_call(
+                    &func,
+                    parameters.as_mut_slice(),
+                    if return_vars.is_empty() {
+                        ""
+                    } else {
+                        "call_ref"
+                    },
+                );
+                match return_vars.len() {
+                    0 => (),
+                    1 => variable_map[return_vars[0]] = ref_func_call_res,
+                    _ => {
+                        for (i, var) in return_vars.iter().enumerate() {
+                            variable_map[*var] = unsafe {
+                                LLVMBuildExtractValue(
+                                    self.builder.get(),
+                                    ref_func_call_res,
+                                    i as u32,
+                                    c_str("extract_res").as_ptr(),
+                                )
+                            };
+                        }
+                    }
+                };
+                self.build_call_unwind_check(
+                    function_bbs[*return_bb as usize],
+                    unwind_bb.map(|bb| function_bbs[bb as usize]),
+                    llvm_function,
+                );
+            }
+            BasicBlockGlue::ReturnCall {
+                func_idx,
+                call_params,
+            } => {
+                let function = &self.llvm_functions.borrow()[*func_idx as usize];
+                let mut parameters = vec![llvm_function.get_param(0)];
+                parameters.extend(call_params.iter().map(|var_id| variable_map[*var_id]));
+                self.builder
+                    .build_tail_call(function, parameters.as_mut_slice());
+            }
+            BasicBlockGlue::ReturnCallIndirect {
+                type_idx,
+                selector_var,
+                table_idx,
+                call_params,
+            } => {
+                let func = self.resolve_indirect_callee(
+                    *type_idx,
+                    variable_map[*selector_var],
+                    *table_idx,
+                    llvm_function,
+                )?;
+                let mut parameters = vec![llvm_function.get_param(0)];
+                parameters.extend(call_params.iter().map(|var_id| variable_map[*var_id]));
+                self.builder
+                    .build_tail_call(&func, parameters.as_mut_slice());
+            }
+            BasicBlockGlue::ReturnCallRef {
+                type_idx,
+                selector_var,
+                call_params,
+            } => {
+                let func =
+                    self.resolve_ref_callee(*type_idx, variable_map[*selector_var], llvm_function)?;
+                let mut parameters = vec![llvm_function.get_param(0)];
+                parameters.extend(call_params.iter().map(|var_id| variable_map[*var_id]));
                 self.builder
-                    .build_unconditional_branch(function_bbs[*return_bb as usize]);
+                    .build_tail_call(&func, parameters.as_mut_slice());
+            }
+            BasicBlockGlue::Throw {
+                tag_idx,
+                values,
+                unwind_bb,
+            } => {
+                let values = values
+                    .iter()
+                    .map(|var| variable_map[*var])
+                    .collect::<Vec<_>>();
+                self.build_throw(
+                    *tag_idx,
+                    &values,
+                    unwind_bb.map(|bb| function_bbs[bb as usize]),
+                    llvm_function,
+                );
+            }
+            BasicBlockGlue::ThrowRef { exn_var, unwind_bb } => self.build_throw_ref(
+                variable_map[*exn_var],
+                unwind_bb.map(|bb| function_bbs[bb as usize]),
+                llvm_function,
+            ),
+            BasicBlockGlue::CatchDispatch {
+                handlers,
+                unwind_bb,
+            } => {
+                let handler_bbs = handlers
+                    .iter()
+                    .map(|handler| function_bbs[handler.target as usize])
+                    .collect::<Vec<_>>();
+                let caught = self.build_catch_dispatch(
+                    handlers,
+                    &handler_bbs,
+                    unwind_bb.map(|bb| function_bbs[bb as usize]),
+                    llvm_function,
+                );
+                for (handler, caught) in handlers.iter().zip(caught) {
+                    for (var, val) in handler.payload_vars.iter().zip(caught.payload) {
+                        variable_map[*var] = val;
+                    }
+                    if let Some(exn_var) = handler.exn_var {
+                        variable_map[exn_var] = caught.exn;
+                    }
+                }
             }
             BasicBlockGlue::ElseMarker { .. } => {
                 return Err(TranslationError::Unimplemented(
@@ -183,4 +317,34 @@ impl Translator<'_> {
         };
         Ok(())
     }
+
+    /// Looks up the function selected by `selector` in the table, trapping on a type mismatch.
+    fn resolve_indirect_callee(
+        &self,
+        type_idx: u32,
+        selector: LLVMValueRef,
+        table_idx: u32,
+        llvm_function: &Function,
+    ) -> Result<Function, TranslationError> {
+        let resolved_func_ptr = self.indirect_call(
+            Self::get_rt_ref(llvm_function),
+            table_idx,
+            type_idx,
+            selector,
+        );
+        let indirect_func_llvm_type = self.llvm_internal_func_type_from_wasm(type_idx as usize)?;
+        Ok(Function::new(resolved_func_ptr, indirect_func_llvm_type).unwrap())
+    }
+
+    /// Resolves the function referenced by `selector`, trapping on null references.
+    fn resolve_ref_callee(
+        &self,
+        type_idx: u32,
+        selector: LLVMValueRef,
+        llvm_function: &Function,
+    ) -> Result<Function, TranslationError> {
+        let resolved_func_ptr = self.ref_call(Self::get_rt_ref(llvm_function), selector);
+        let ref_func_llvm_type = self.llvm_internal_func_type_from_wasm(type_idx as usize)?;
+        Ok(Function::new(resolved_func_ptr, ref_func_llvm_type).unwrap())
+    }
 }
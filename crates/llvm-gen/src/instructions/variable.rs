@@ -57,7 +57,7 @@ impl Translator<'_> {
                 let instr = decoder.read::<GlobalSetInstruction>(instruction)?;
                 let global_name = format!("__wasmine_global__{}", instr.global_idx);
                 let global = self.module.get_global(&global_name)?;
-                self.builder.build_store(variable_map[instr.in1], global)
+                self.builder.build_store(variable_map[instr.in1], global);
             }
         }
         Ok(())
@@ -0,0 +1,896 @@
+use crate::abstraction::function::Function;
+use crate::{error::TranslationError, translator::Translator};
+use llvm_sys::core::{LLVMConstAllOnes, LLVMConstInt, LLVMConstNull, LLVMSetAlignment};
+use llvm_sys::prelude::{LLVMTypeRef, LLVMValueRef};
+use llvm_sys::{LLVMIntPredicate, LLVMOpcode, LLVMRealPredicate};
+use module::{instructions::*, InstructionDecoder};
+use wasm_types::{
+    InstructionType, VBinaryOp, VRelationalOp, VShiftOp, VTestOp, VUnaryOp, VecLoadOp, VecShape,
+    VectorInstructionCategory,
+};
+
+/// Overload suffix of the lane vector type, as used for mangling intrinsic names.
+fn mangle(shape: VecShape) -> &'static str {
+    match shape {
+        VecShape::I8x16 => "v16i8",
+        VecShape::I16x8 => "v8i16",
+        VecShape::I32x4 => "v4i32",
+        VecShape::I64x2 => "v2i64",
+        VecShape::F32x4 => "v4f32",
+        VecShape::F64x2 => "v2f64",
+    }
+}
+
+/// Shape with half the lane count and lanes of half the width (e.g. i8x16 for i16x8).
+fn narrower(shape: VecShape) -> VecShape {
+    match shape {
+        VecShape::I16x8 => VecShape::I8x16,
+        VecShape::I32x4 => VecShape::I16x8,
+        VecShape::I64x2 => VecShape::I32x4,
+        VecShape::F64x2 => VecShape::F32x4,
+        _ => unreachable!("no narrower shape for {shape}"),
+    }
+}
+
+/// Integer shape with the same lane layout (used for comparison masks of float shapes).
+fn int_shape(shape: VecShape) -> VecShape {
+    match shape {
+        VecShape::F32x4 => VecShape::I32x4,
+        VecShape::F64x2 => VecShape::I64x2,
+        shape => shape,
+    }
+}
+
+struct VRelationalOpConv(VRelationalOp);
+impl From<VRelationalOpConv> for LLVMIntPredicate {
+    fn from(val: VRelationalOpConv) -> Self {
+        match val.0 {
+            VRelationalOp::Eq => LLVMIntPredicate::LLVMIntEQ,
+            VRelationalOp::Ne => LLVMIntPredicate::LLVMIntNE,
+            VRelationalOp::LtS => LLVMIntPredicate::LLVMIntSLT,
+            VRelationalOp::LtU => LLVMIntPredicate::LLVMIntULT,
+            VRelationalOp::LeS => LLVMIntPredicate::LLVMIntSLE,
+            VRelationalOp::LeU => LLVMIntPredicate::LLVMIntULE,
+            VRelationalOp::GtS => LLVMIntPredicate::LLVMIntSGT,
+            VRelationalOp::GtU => LLVMIntPredicate::LLVMIntUGT,
+            VRelationalOp::GeS => LLVMIntPredicate::LLVMIntSGE,
+            VRelationalOp::GeU => LLVMIntPredicate::LLVMIntUGE,
+            op => unreachable!("{op} is not an integer comparison"),
+        }
+    }
+}
+impl From<VRelationalOpConv> for LLVMRealPredicate {
+    fn from(val: VRelationalOpConv) -> Self {
+        match val.0 {
+            VRelationalOp::Eq => LLVMRealPredicate::LLVMRealOEQ,
+            VRelationalOp::Ne => LLVMRealPredicate::LLVMRealUNE,
+            VRelationalOp::Lt => LLVMRealPredicate::LLVMRealOLT,
+            VRelationalOp::Le => LLVMRealPredicate::LLVMRealOLE,
+            VRelationalOp::Gt => LLVMRealPredicate::LLVMRealOGT,
+            VRelationalOp::Ge => LLVMRealPredicate::LLVMRealOGE,
+            op => unreachable!("{op} is not a float comparison"),
+        }
+    }
+}
+
+impl Translator<'_> {
+    pub(crate) fn translate_vector(
+        &self,
+        instr_type: VectorInstructionCategory,
+        instruction: InstructionType,
+        decoder: &mut InstructionDecoder,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        match instr_type {
+            VectorInstructionCategory::Constant => {
+                let instr = decoder.read::<VConstInstruction>(instruction)?;
+                variable_map[instr.out1] = self.builder.const_v128(instr.imm);
+                Ok(())
+            }
+            VectorInstructionCategory::Load(_) => {
+                let instr = decoder.read::<VLoadInstruction>(instruction)?;
+                self.compile_v128_load(instr, variable_map, llvm_function)
+            }
+            VectorInstructionCategory::Store => {
+                let instr = decoder.read::<VStoreInstruction>(instruction)?;
+                self.compile_v128_store(instr, variable_map, llvm_function)
+            }
+            VectorInstructionCategory::LoadLane(_) => {
+                let instr = decoder.read::<VLoadLaneInstruction>(instruction)?;
+                self.compile_v128_load_lane(instr, variable_map, llvm_function)
+            }
+            VectorInstructionCategory::StoreLane(_) => {
+                let instr = decoder.read::<VStoreLaneInstruction>(instruction)?;
+                self.compile_v128_store_lane(instr, variable_map, llvm_function)
+            }
+            VectorInstructionCategory::Shuffle => {
+                let instr = decoder.read::<VShuffleInstruction>(instruction)?;
+                self.compile_shuffle(instr, variable_map)
+            }
+            VectorInstructionCategory::Splat(_) => {
+                let instr = decoder.read::<VSplatInstruction>(instruction)?;
+                self.compile_splat(instr, variable_map)
+            }
+            VectorInstructionCategory::ExtractLane(_)
+            | VectorInstructionCategory::ExtractLaneS(_)
+            | VectorInstructionCategory::ExtractLaneU(_) => {
+                let instr = decoder.read::<VExtractLaneInstruction>(instruction)?;
+                self.compile_extract_lane(instr, variable_map)
+            }
+            VectorInstructionCategory::ReplaceLane(_) => {
+                let instr = decoder.read::<VReplaceLaneInstruction>(instruction)?;
+                self.compile_replace_lane(instr, variable_map)
+            }
+            VectorInstructionCategory::Unary(..) => {
+                let instr = decoder.read::<VUnaryInstruction>(instruction)?;
+                self.compile_vunary(instr, variable_map)
+            }
+            VectorInstructionCategory::Binary(..) => {
+                let instr = decoder.read::<VBinaryInstruction>(instruction)?;
+                self.compile_vbinary(instr, variable_map)
+            }
+            VectorInstructionCategory::Bitselect => {
+                let instr = decoder.read::<VBitselectInstruction>(instruction)?;
+                self.compile_bitselect(instr, variable_map)
+            }
+            VectorInstructionCategory::Relational(..) => {
+                let instr = decoder.read::<VRelationalInstruction>(instruction)?;
+                self.compile_vrelational(instr, variable_map)
+            }
+            VectorInstructionCategory::Shift(..) => {
+                let instr = decoder.read::<VShiftInstruction>(instruction)?;
+                self.compile_vshift(instr, variable_map)
+            }
+            VectorInstructionCategory::Test(..) => {
+                let instr = decoder.read::<VTestInstruction>(instruction)?;
+                self.compile_vtest(instr, variable_map)
+            }
+        }
+    }
+
+    /// Reinterprets a v128 value as a vector of lanes of the given shape.
+    fn as_lanes(&self, val: LLVMValueRef, shape: VecShape) -> LLVMValueRef {
+        self.builder
+            .build_bitcast(val, self.builder.lane_vec(shape), "as_lanes")
+    }
+
+    /// Reinterprets a lane vector as the opaque v128 representation.
+    fn from_lanes(&self, val: LLVMValueRef) -> LLVMValueRef {
+        self.builder
+            .build_bitcast(val, self.builder.vec(), "from_lanes")
+    }
+
+    /// Vector with the same lane count as `shape`, but with lanes of `bits` width.
+    fn resized_lanes(&self, shape: VecShape, bits: u32) -> LLVMTypeRef {
+        self.builder
+            .vector(self.builder.custom_type(bits), shape.lane_count() as u32)
+    }
+
+    fn call_vector_intrinsic(
+        &self,
+        name: &str,
+        shape: VecShape,
+        params: &mut [LLVMValueRef],
+    ) -> LLVMValueRef {
+        let ty = self.builder.lane_vec(shape);
+        self.builder.call_intrinsic_raw(
+            &mut vec![ty; params.len()],
+            params,
+            ty,
+            &format!("{name}.{}", mangle(shape)),
+        )
+    }
+
+    // wasm gives no alignment guarantees, so all vector memory accesses are emitted unaligned
+    fn build_unaligned_load(&self, ty: LLVMTypeRef, addr: LLVMValueRef) -> LLVMValueRef {
+        let val = self.builder.build_load(ty, addr, "vload");
+        unsafe { LLVMSetAlignment(val, 1) };
+        val
+    }
+
+    fn build_unaligned_store(&self, val: LLVMValueRef, addr: LLVMValueRef) {
+        let store = self.builder.build_store(val, addr);
+        unsafe { LLVMSetAlignment(store, 1) };
+    }
+
+    pub(crate) fn compile_v128_load(
+        &self,
+        instr: VLoadInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let width = match instr.operation {
+            VecLoadOp::Load128 => 16,
+            VecLoadOp::Load8Splat => 1,
+            VecLoadOp::Load16Splat => 2,
+            VecLoadOp::Load32Splat | VecLoadOp::Load32Zero => 4,
+            _ => 8,
+        };
+        let addr = self.calc_addr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            width,
+            llvm_function,
+        );
+        let extend = |src_bits: u32, dst: VecShape, signed: bool| {
+            let val = self.build_unaligned_load(self.resized_lanes(dst, src_bits), addr);
+            let val =
+                self.builder
+                    .build_int_cast(val, self.builder.lane_vec(dst), signed, "extend");
+            self.from_lanes(val)
+        };
+        let splat = |shape: VecShape| {
+            let lane_ty = self.builder.custom_type(shape.lane_bytes() as u32 * 8);
+            let val = self.build_unaligned_load(lane_ty, addr);
+            let val = self
+                .builder
+                .build_splat(val, self.builder.lane_vec(shape), "splat");
+            self.from_lanes(val)
+        };
+        let zero_extend = |ty: LLVMTypeRef| {
+            let val = self.build_unaligned_load(ty, addr);
+            self.builder
+                .build_int_cast(val, self.builder.vec(), false, "zero_extend")
+        };
+
+        variable_map[instr.out1] = match instr.operation {
+            VecLoadOp::Load128 => self.build_unaligned_load(self.builder.vec(), addr),
+            VecLoadOp::Load8x8S => extend(8, VecShape::I16x8, true),
+            VecLoadOp::Load8x8U => extend(8, VecShape::I16x8, false),
+            VecLoadOp::Load16x4S => extend(16, VecShape::I32x4, true),
+            VecLoadOp::Load16x4U => extend(16, VecShape::I32x4, false),
+            VecLoadOp::Load32x2S => extend(32, VecShape::I64x2, true),
+            VecLoadOp::Load32x2U => extend(32, VecShape::I64x2, false),
+            VecLoadOp::Load8Splat => splat(VecShape::I8x16),
+            VecLoadOp::Load16Splat => splat(VecShape::I16x8),
+            VecLoadOp::Load32Splat => splat(VecShape::I32x4),
+            VecLoadOp::Load64Splat => splat(VecShape::I64x2),
+            VecLoadOp::Load32Zero => zero_extend(self.builder.i32()),
+            VecLoadOp::Load64Zero => zero_extend(self.builder.i64()),
+        };
+        Ok(())
+    }
+
+    pub(crate) fn compile_v128_store(
+        &self,
+        instr: VStoreInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let addr = self.calc_addr(
+            variable_map[instr.addr_in],
+            &instr.memarg,
+            16,
+            llvm_function,
+        );
+        self.build_unaligned_store(variable_map[instr.value_in], addr);
+        Ok(())
+    }
+
+    pub(crate) fn compile_v128_load_lane(
+        &self,
+        instr: VLoadLaneInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let addr = self.calc_addr(
+            variable_map[instr.addr],
+            &instr.memarg,
+            instr.shape.lane_bytes() as u64,
+            llvm_function,
+        );
+        let lane_ty = self
+            .builder
+            .custom_type(instr.shape.lane_bytes() as u32 * 8);
+        let val = self.build_unaligned_load(lane_ty, addr);
+        let vec = self.as_lanes(variable_map[instr.in1], instr.shape);
+        let vec = self.builder.build_insert_element(
+            vec,
+            val,
+            self.builder.const_i32(instr.lane as u32),
+            "load_lane",
+        );
+        variable_map[instr.out1] = self.from_lanes(vec);
+        Ok(())
+    }
+
+    pub(crate) fn compile_v128_store_lane(
+        &self,
+        instr: VStoreLaneInstruction,
+        variable_map: &mut [LLVMValueRef],
+        llvm_function: &Function,
+    ) -> Result<(), TranslationError> {
+        let addr = self.calc_addr(
+            variable_map[instr.addr_in],
+            &instr.memarg,
+            instr.shape.lane_bytes() as u64,
+            llvm_function,
+        );
+        let vec = self.as_lanes(variable_map[instr.value_in], instr.shape);
+        let val = self.builder.build_extract_element(
+            vec,
+            self.builder.const_i32(instr.lane as u32),
+            "store_lane",
+        );
+        self.build_unaligned_store(val, addr);
+        Ok(())
+    }
+
+    pub(crate) fn compile_shuffle(
+        &self,
+        instr: VShuffleInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let lhs = self.as_lanes(variable_map[instr.lhs], VecShape::I8x16);
+        let rhs = self.as_lanes(variable_map[instr.rhs], VecShape::I8x16);
+        let mask = instr.lanes.map(|l| l as u32);
+        let res = self
+            .builder
+            .build_shuffle_vector(lhs, rhs, &mask, "shuffle");
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    /// Scalars of i8x16 and i16x8 lanes are passed as i32 and have to be truncated first.
+    fn scalar_to_lane(&self, val: LLVMValueRef, shape: VecShape) -> LLVMValueRef {
+        match shape {
+            VecShape::I8x16 => {
+                self.builder
+                    .build_int_cast(val, self.builder.i8(), false, "trunc_lane")
+            }
+            VecShape::I16x8 => {
+                self.builder
+                    .build_int_cast(val, self.builder.i16(), false, "trunc_lane")
+            }
+            _ => val,
+        }
+    }
+
+    pub(crate) fn compile_splat(
+        &self,
+        instr: VSplatInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let val = self.scalar_to_lane(variable_map[instr.in1], instr.shape);
+        let res = self
+            .builder
+            .build_splat(val, self.builder.lane_vec(instr.shape), "splat");
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    pub(crate) fn compile_extract_lane(
+        &self,
+        instr: VExtractLaneInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let vec = self.as_lanes(variable_map[instr.in1], instr.shape);
+        let val = self.builder.build_extract_element(
+            vec,
+            self.builder.const_i32(instr.lane as u32),
+            "extract_lane",
+        );
+        variable_map[instr.out1] = match instr.signed {
+            Some(signed) => {
+                self.builder
+                    .build_int_cast(val, self.builder.i32(), signed, "extend_lane")
+            }
+            None => val,
+        };
+        Ok(())
+    }
+
+    pub(crate) fn compile_replace_lane(
+        &self,
+        instr: VReplaceLaneInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let vec = self.as_lanes(variable_map[instr.in1], instr.shape);
+        let val = self.scalar_to_lane(variable_map[instr.value_in], instr.shape);
+        let res = self.builder.build_insert_element(
+            vec,
+            val,
+            self.builder.const_i32(instr.lane as u32),
+            "replace_lane",
+        );
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    /// Selects the lower (`high == false`) or upper half of the lanes of a vector with `lanes` lanes.
+    fn half(&self, val: LLVMValueRef, lanes: u32, high: bool) -> LLVMValueRef {
+        let offset = if high { lanes / 2 } else { 0 };
+        let mask: Vec<u32> = (offset..offset + lanes / 2).collect();
+        self.builder.build_shuffle_vector(val, val, &mask, "half")
+    }
+
+    /// Selects every other lane of a vector with `lanes` lanes, starting at lane `start`.
+    fn every_other(&self, val: LLVMValueRef, lanes: u32, start: u32) -> LLVMValueRef {
+        let mask: Vec<u32> = (start..lanes).step_by(2).collect();
+        self.builder
+            .build_shuffle_vector(val, val, &mask, "every_other")
+    }
+
+    /// Widens the lanes of a vector to the lanes of `shape`.
+    fn extend_lanes(&self, val: LLVMValueRef, shape: VecShape, signed: bool) -> LLVMValueRef {
+        self.builder
+            .build_int_cast(val, self.builder.lane_vec(shape), signed, "extend_lanes")
+    }
+
+    /// Pads a vector of two lanes with two zero lanes.
+    fn pad_zero(&self, val: LLVMValueRef, ty: LLVMTypeRef) -> LLVMValueRef {
+        let zero = unsafe { LLVMConstNull(ty) };
+        self.builder
+            .build_shuffle_vector(val, zero, &[0, 1, 2, 3], "pad_zero")
+    }
+
+    pub(crate) fn compile_vunary(
+        &self,
+        instr: VUnaryInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let shape = instr.shape;
+        let src = variable_map[instr.in1];
+        let lanes = shape.lane_count() as u32;
+
+        let res = match instr.op {
+            VUnaryOp::Not => {
+                let ones = unsafe { LLVMConstAllOnes(self.builder.vec()) };
+                self.builder.build_xor(src, ones, "not")
+            }
+            VUnaryOp::Abs if !shape.is_float() => {
+                // https://llvm.org/docs/LangRef.html#llvm-abs-intrinsic
+                let ty = self.builder.lane_vec(shape);
+                self.builder.call_intrinsic_raw(
+                    &mut [ty, self.builder.i1()],
+                    &mut [self.as_lanes(src, shape), self.builder.const_i1(false)],
+                    ty,
+                    &format!("llvm.abs.{}", mangle(shape)),
+                )
+            }
+            VUnaryOp::Neg if !shape.is_float() => {
+                let zero = unsafe { LLVMConstNull(self.builder.lane_vec(shape)) };
+                self.builder
+                    .build_sub(zero, self.as_lanes(src, shape), "neg")
+            }
+            VUnaryOp::Popcnt => {
+                self.call_vector_intrinsic("llvm.ctpop", shape, &mut [self.as_lanes(src, shape)])
+            }
+            VUnaryOp::Abs => {
+                self.call_vector_intrinsic("llvm.fabs", shape, &mut [self.as_lanes(src, shape)])
+            }
+            VUnaryOp::Neg => self.builder.build_fneg(self.as_lanes(src, shape), "fneg"),
+            VUnaryOp::Sqrt => {
+                self.call_vector_intrinsic("llvm.sqrt", shape, &mut [self.as_lanes(src, shape)])
+            }
+            VUnaryOp::Ceil => {
+                self.call_vector_intrinsic("llvm.ceil", shape, &mut [self.as_lanes(src, shape)])
+            }
+            VUnaryOp::Floor => {
+                self.call_vector_intrinsic("llvm.floor", shape, &mut [self.as_lanes(src, shape)])
+            }
+            VUnaryOp::Trunc => {
+                self.call_vector_intrinsic("llvm.trunc", shape, &mut [self.as_lanes(src, shape)])
+            }
+            // https://llvm.org/docs/LangRef.html#llvm-roundeven-intrinsic
+            VUnaryOp::Nearest => self.call_vector_intrinsic(
+                "llvm.roundeven",
+                shape,
+                &mut [self.as_lanes(src, shape)],
+            ),
+            VUnaryOp::ExtendLowS
+            | VUnaryOp::ExtendHighS
+            | VUnaryOp::ExtendLowU
+            | VUnaryOp::ExtendHighU => {
+                let high = matches!(instr.op, VUnaryOp::ExtendHighS | VUnaryOp::ExtendHighU);
+                let signed = matches!(instr.op, VUnaryOp::ExtendLowS | VUnaryOp::ExtendHighS);
+                let src = self.as_lanes(src, narrower(shape));
+                let half = self.half(src, lanes * 2, high);
+                self.extend_lanes(half, shape, signed)
+            }
+            VUnaryOp::ExtAddPairwiseS | VUnaryOp::ExtAddPairwiseU => {
+                let signed = matches!(instr.op, VUnaryOp::ExtAddPairwiseS);
+                let src = self.as_lanes(src, narrower(shape));
+                let even = self.extend_lanes(self.every_other(src, lanes * 2, 0), shape, signed);
+                let odd = self.extend_lanes(self.every_other(src, lanes * 2, 1), shape, signed);
+                self.builder.build_add(even, odd, "extadd_pairwise")
+            }
+            VUnaryOp::TruncSatS | VUnaryOp::TruncSatU => {
+                // https://llvm.org/docs/LangRef.html#llvm-fptosi-sat-intrinsic
+                let name = if matches!(instr.op, VUnaryOp::TruncSatS) {
+                    "llvm.fptosi.sat.v4i32.v4f32"
+                } else {
+                    "llvm.fptoui.sat.v4i32.v4f32"
+                };
+                self.builder.call_intrinsic_raw(
+                    &mut [self.builder.lane_vec(VecShape::F32x4)],
+                    &mut [self.as_lanes(src, VecShape::F32x4)],
+                    self.builder.lane_vec(VecShape::I32x4),
+                    name,
+                )
+            }
+            VUnaryOp::TruncSatZeroS | VUnaryOp::TruncSatZeroU => {
+                let name = if matches!(instr.op, VUnaryOp::TruncSatZeroS) {
+                    "llvm.fptosi.sat.v2i32.v2f64"
+                } else {
+                    "llvm.fptoui.sat.v2i32.v2f64"
+                };
+                let half_ty = self.builder.vector(self.builder.i32(), 2);
+                let res = self.builder.call_intrinsic_raw(
+                    &mut [self.builder.lane_vec(VecShape::F64x2)],
+                    &mut [self.as_lanes(src, VecShape::F64x2)],
+                    half_ty,
+                    name,
+                );
+                self.pad_zero(res, half_ty)
+            }
+            VUnaryOp::ConvertS | VUnaryOp::ConvertU => self.builder.build_int2float(
+                self.as_lanes(src, VecShape::I32x4),
+                self.builder.lane_vec(VecShape::F32x4),
+                matches!(instr.op, VUnaryOp::ConvertS),
+                "convert",
+            ),
+            VUnaryOp::ConvertLowS | VUnaryOp::ConvertLowU => {
+                let low = self.half(self.as_lanes(src, VecShape::I32x4), 4, false);
+                self.builder.build_int2float(
+                    low,
+                    self.builder.lane_vec(VecShape::F64x2),
+                    matches!(instr.op, VUnaryOp::ConvertLowS),
+                    "convert_low",
+                )
+            }
+            VUnaryOp::DemoteZero => {
+                let half_ty = self.builder.vector(self.builder.f32(), 2);
+                let res = self.builder.build_float_cast(
+                    self.as_lanes(src, VecShape::F64x2),
+                    half_ty,
+                    "demote",
+                );
+                self.pad_zero(res, half_ty)
+            }
+            VUnaryOp::PromoteLow => {
+                let low = self.half(self.as_lanes(src, VecShape::F32x4), 4, false);
+                self.builder.build_float_cast(
+                    low,
+                    self.builder.lane_vec(VecShape::F64x2),
+                    "promote",
+                )
+            }
+        };
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    /// Clamps every lane of `val` (of type `ty`) into `[min, max]` using signed comparisons.
+    fn clamp_lanes(&self, val: LLVMValueRef, ty: LLVMTypeRef, min: i64, max: i64) -> LLVMValueRef {
+        let elem_ty = unsafe { llvm_sys::core::LLVMGetElementType(ty) };
+        let splat = |c: i64| {
+            let c = unsafe { LLVMConstInt(elem_ty, c as u64, true.into()) };
+            self.builder.build_splat(c, ty, "clamp_bound")
+        };
+        let mangled = |name: &str| {
+            let lanes = unsafe { llvm_sys::core::LLVMGetVectorSize(ty) };
+            let bits = unsafe { llvm_sys::core::LLVMGetIntTypeWidth(elem_ty) };
+            format!("{name}.v{lanes}i{bits}")
+        };
+        let val = self.builder.call_binary_intrinsic_raw(
+            [ty, ty],
+            [val, splat(max)],
+            ty,
+            &mangled("llvm.smin"),
+        );
+        self.builder.call_binary_intrinsic_raw(
+            [ty, ty],
+            [val, splat(min)],
+            ty,
+            &mangled("llvm.smax"),
+        )
+    }
+
+    pub(crate) fn compile_vbinary(
+        &self,
+        instr: VBinaryInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let shape = instr.shape;
+        let (lhs, rhs) = (variable_map[instr.lhs], variable_map[instr.rhs]);
+        let (a, b) = (self.as_lanes(lhs, shape), self.as_lanes(rhs, shape));
+        let lanes = shape.lane_count() as u32;
+        let lane_bits = shape.lane_bytes() as u32 * 8;
+        let intrinsic = |name: &str| self.call_vector_intrinsic(name, shape, &mut [a, b]);
+        let binop = |op: LLVMOpcode| self.builder.build_binop(op, a, b, "vbinary");
+
+        let res = match instr.op {
+            VBinaryOp::And => self.builder.build_and(lhs, rhs, "and"),
+            VBinaryOp::Or => self.builder.build_or(lhs, rhs, "or"),
+            VBinaryOp::Xor => self.builder.build_xor(lhs, rhs, "xor"),
+            VBinaryOp::AndNot => {
+                let ones = unsafe { LLVMConstAllOnes(self.builder.vec()) };
+                let not_rhs = self.builder.build_xor(rhs, ones, "not");
+                self.builder.build_and(lhs, not_rhs, "andnot")
+            }
+            VBinaryOp::Swizzle => {
+                // lanes are selected one by one, indices out of range yield zero lanes
+                let mut res = unsafe { LLVMConstNull(self.builder.lane_vec(shape)) };
+                for i in 0..lanes {
+                    let idx = self.builder.build_extract_element(
+                        b,
+                        self.builder.const_i32(i),
+                        "swizzle_idx",
+                    );
+                    let idx = self.builder.build_and(
+                        idx,
+                        unsafe { LLVMConstInt(self.builder.i8(), 15, false.into()) },
+                        "swizzle_idx_mask",
+                    );
+                    let lane = self.builder.build_extract_element(a, idx, "swizzle_lane");
+                    res = self.builder.build_insert_element(
+                        res,
+                        lane,
+                        self.builder.const_i32(i),
+                        "swizzle",
+                    );
+                }
+                let limit = self.builder.build_splat(
+                    unsafe { LLVMConstInt(self.builder.i8(), 16, false.into()) },
+                    self.builder.lane_vec(shape),
+                    "swizzle_limit",
+                );
+                let in_range =
+                    self.builder
+                        .build_icmp(LLVMIntPredicate::LLVMIntULT, b, limit, "in_range");
+                let zero = unsafe { LLVMConstNull(self.builder.lane_vec(shape)) };
+                self.builder.build_select(in_range, res, zero, "swizzle")
+            }
+
+            VBinaryOp::Add if shape.is_float() => binop(LLVMOpcode::LLVMFAdd),
+            VBinaryOp::Sub if shape.is_float() => binop(LLVMOpcode::LLVMFSub),
+            VBinaryOp::Mul if shape.is_float() => binop(LLVMOpcode::LLVMFMul),
+            VBinaryOp::Div => binop(LLVMOpcode::LLVMFDiv),
+            // https://llvm.org/docs/LangRef.html#llvm-minimum-intrinsic
+            VBinaryOp::Min => intrinsic("llvm.minimum"),
+            // https://llvm.org/docs/LangRef.html#llvm-maximum-intrinsic
+            VBinaryOp::Max => intrinsic("llvm.maximum"),
+            VBinaryOp::PMin => {
+                let cond =
+                    self.builder
+                        .build_fcmp(LLVMRealPredicate::LLVMRealOLT, b, a, "pmin_cmp");
+                self.builder.build_select(cond, b, a, "pmin")
+            }
+            VBinaryOp::PMax => {
+                let cond =
+                    self.builder
+                        .build_fcmp(LLVMRealPredicate::LLVMRealOLT, a, b, "pmax_cmp");
+                self.builder.build_select(cond, b, a, "pmax")
+            }
+
+            VBinaryOp::Add => binop(LLVMOpcode::LLVMAdd),
+            VBinaryOp::Sub => binop(LLVMOpcode::LLVMSub),
+            VBinaryOp::Mul => binop(LLVMOpcode::LLVMMul),
+            VBinaryOp::AddSatS => intrinsic("llvm.sadd.sat"),
+            VBinaryOp::AddSatU => intrinsic("llvm.uadd.sat"),
+            VBinaryOp::SubSatS => intrinsic("llvm.ssub.sat"),
+            VBinaryOp::SubSatU => intrinsic("llvm.usub.sat"),
+            VBinaryOp::MinS => intrinsic("llvm.smin"),
+            VBinaryOp::MinU => intrinsic("llvm.umin"),
+            VBinaryOp::MaxS => intrinsic("llvm.smax"),
+            VBinaryOp::MaxU => intrinsic("llvm.umax"),
+            VBinaryOp::AvgrU => {
+                // (a + b + 1) / 2, computed without overflow in lanes of twice the width
+                let wide_ty = self.resized_lanes(shape, lane_bits * 2);
+                let wide_a = self.builder.build_int_cast(a, wide_ty, false, "widen");
+                let wide_b = self.builder.build_int_cast(b, wide_ty, false, "widen");
+                let one = self.builder.build_splat(
+                    unsafe {
+                        LLVMConstInt(self.builder.custom_type(lane_bits * 2), 1, false.into())
+                    },
+                    wide_ty,
+                    "one",
+                );
+                let sum = self.builder.build_add(wide_a, wide_b, "avgr_sum");
+                let sum = self.builder.build_add(sum, one, "avgr_sum");
+                let avg = self.builder.build_lshr(sum, one, "avgr");
+                self.builder
+                    .build_int_cast(avg, self.builder.lane_vec(shape), false, "narrow")
+            }
+            VBinaryOp::Q15MulrSatS => {
+                let wide_ty = self.resized_lanes(shape, 32);
+                let wide_a = self.builder.build_int_cast(a, wide_ty, true, "widen");
+                let wide_b = self.builder.build_int_cast(b, wide_ty, true, "widen");
+                let splat = |c: u64| {
+                    self.builder.build_splat(
+                        unsafe { LLVMConstInt(self.builder.i32(), c, false.into()) },
+                        wide_ty,
+                        "q15_const",
+                    )
+                };
+                let prod = self.builder.build_mul(wide_a, wide_b, "q15_mul");
+                let prod = self.builder.build_add(prod, splat(0x4000), "q15_round");
+                let prod = self.builder.build_ashr(prod, splat(15), "q15_shift");
+                let prod = self.clamp_lanes(prod, wide_ty, i16::MIN as i64, i16::MAX as i64);
+                self.builder
+                    .build_int_cast(prod, self.builder.lane_vec(shape), true, "narrow")
+            }
+            VBinaryOp::DotS => {
+                let src_shape = narrower(shape);
+                let (a, b) = (self.as_lanes(lhs, src_shape), self.as_lanes(rhs, src_shape));
+                let wide_ty = self.resized_lanes(src_shape, 32);
+                let wide_a = self.builder.build_int_cast(a, wide_ty, true, "widen");
+                let wide_b = self.builder.build_int_cast(b, wide_ty, true, "widen");
+                let prod = self.builder.build_mul(wide_a, wide_b, "dot_mul");
+                let even = self.every_other(prod, lanes * 2, 0);
+                let odd = self.every_other(prod, lanes * 2, 1);
+                self.builder.build_add(even, odd, "dot")
+            }
+            VBinaryOp::ExtMulLowS
+            | VBinaryOp::ExtMulHighS
+            | VBinaryOp::ExtMulLowU
+            | VBinaryOp::ExtMulHighU => {
+                let high = matches!(instr.op, VBinaryOp::ExtMulHighS | VBinaryOp::ExtMulHighU);
+                let signed = matches!(instr.op, VBinaryOp::ExtMulLowS | VBinaryOp::ExtMulHighS);
+                let src_shape = narrower(shape);
+                let (a, b) = (self.as_lanes(lhs, src_shape), self.as_lanes(rhs, src_shape));
+                let a = self.extend_lanes(self.half(a, lanes * 2, high), shape, signed);
+                let b = self.extend_lanes(self.half(b, lanes * 2, high), shape, signed);
+                self.builder.build_mul(a, b, "extmul")
+            }
+            VBinaryOp::NarrowS | VBinaryOp::NarrowU => {
+                // the result shape has twice as many lanes as the operands
+                let src_shape = match shape {
+                    VecShape::I8x16 => VecShape::I16x8,
+                    _ => VecShape::I32x4,
+                };
+                let (a, b) = (self.as_lanes(lhs, src_shape), self.as_lanes(rhs, src_shape));
+                let mask: Vec<u32> = (0..lanes).collect();
+                let joined = self
+                    .builder
+                    .build_shuffle_vector(a, b, &mask, "narrow_join");
+                let joined_ty = self.resized_lanes(shape, lane_bits * 2);
+                let (min, max) = match (instr.op, shape) {
+                    (VBinaryOp::NarrowS, VecShape::I8x16) => (i8::MIN as i64, i8::MAX as i64),
+                    (VBinaryOp::NarrowS, _) => (i16::MIN as i64, i16::MAX as i64),
+                    (_, VecShape::I8x16) => (0, u8::MAX as i64),
+                    _ => (0, u16::MAX as i64),
+                };
+                let clamped = self.clamp_lanes(joined, joined_ty, min, max);
+                self.builder
+                    .build_int_cast(clamped, self.builder.lane_vec(shape), false, "narrow")
+            }
+        };
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    pub(crate) fn compile_bitselect(
+        &self,
+        instr: VBitselectInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let mask = variable_map[instr.mask];
+        let ones = unsafe { LLVMConstAllOnes(self.builder.vec()) };
+        let inv_mask = self.builder.build_xor(mask, ones, "inv_mask");
+        let from_in1 = self
+            .builder
+            .build_and(variable_map[instr.in1], mask, "select_in1");
+        let from_in2 = self
+            .builder
+            .build_and(variable_map[instr.in2], inv_mask, "select_in2");
+        variable_map[instr.out1] = self.builder.build_or(from_in1, from_in2, "bitselect");
+        Ok(())
+    }
+
+    pub(crate) fn compile_vrelational(
+        &self,
+        instr: VRelationalInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let shape = instr.shape;
+        let a = self.as_lanes(variable_map[instr.lhs], shape);
+        let b = self.as_lanes(variable_map[instr.rhs], shape);
+        let cmp = if shape.is_float() {
+            self.builder
+                .build_fcmp(VRelationalOpConv(instr.op).into(), a, b, "vcmp")
+        } else {
+            self.builder
+                .build_icmp(VRelationalOpConv(instr.op).into(), a, b, "vcmp")
+        };
+        // true lanes are all ones
+        let mask = self.builder.build_int_cast(
+            cmp,
+            self.builder.lane_vec(int_shape(shape)),
+            true,
+            "vcmp_mask",
+        );
+        variable_map[instr.out1] = self.from_lanes(mask);
+        Ok(())
+    }
+
+    pub(crate) fn compile_vshift(
+        &self,
+        instr: VShiftInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let shape = instr.shape;
+        let lane_bits = shape.lane_bytes() as u32 * 8;
+        let vec = self.as_lanes(variable_map[instr.in1], shape);
+        // the shift amount is taken modulo the lane width
+        let amount = self.builder.build_and(
+            variable_map[instr.amount],
+            self.builder.const_i32(lane_bits - 1),
+            "shift_amount",
+        );
+        let amount = self.builder.build_int_cast(
+            amount,
+            self.builder.custom_type(lane_bits),
+            false,
+            "shift_amount",
+        );
+        let amount = self
+            .builder
+            .build_splat(amount, self.builder.lane_vec(shape), "shift_amount");
+        let res = match instr.op {
+            VShiftOp::Shl => self.builder.build_shl(vec, amount, "vshl"),
+            VShiftOp::ShrS => self.builder.build_ashr(vec, amount, "vshr_s"),
+            VShiftOp::ShrU => self.builder.build_lshr(vec, amount, "vshr_u"),
+        };
+        variable_map[instr.out1] = self.from_lanes(res);
+        Ok(())
+    }
+
+    pub(crate) fn compile_vtest(
+        &self,
+        instr: VTestInstruction,
+        variable_map: &mut [LLVMValueRef],
+    ) -> Result<(), TranslationError> {
+        let shape = instr.shape;
+        let src = variable_map[instr.in1];
+        let lanes = shape.lane_count() as u32;
+
+        let res = match instr.op {
+            VTestOp::AnyTrue => self.builder.build_icmp(
+                LLVMIntPredicate::LLVMIntNE,
+                src,
+                unsafe { LLVMConstNull(self.builder.vec()) },
+                "any_true",
+            ),
+            VTestOp::AllTrue => {
+                let vec = self.as_lanes(src, shape);
+                let zero = unsafe { LLVMConstNull(self.builder.lane_vec(shape)) };
+                let non_zero = self.builder.build_icmp(
+                    LLVMIntPredicate::LLVMIntNE,
+                    vec,
+                    zero,
+                    "lane_non_zero",
+                );
+                let bits = self.builder.build_bitcast(
+                    non_zero,
+                    self.builder.custom_type(lanes),
+                    "lane_bits",
+                );
+                self.builder.build_icmp(
+                    LLVMIntPredicate::LLVMIntEQ,
+                    bits,
+                    unsafe { LLVMConstAllOnes(self.builder.custom_type(lanes)) },
+                    "all_true",
+                )
+            }
+            VTestOp::Bitmask => {
+                let vec = self.as_lanes(src, shape);
+                let zero = unsafe { LLVMConstNull(self.builder.lane_vec(shape)) };
+                let negative = self.builder.build_icmp(
+                    LLVMIntPredicate::LLVMIntSLT,
+                    vec,
+                    zero,
+                    "lane_negative",
+                );
+                self.builder
+                    .build_bitcast(negative, self.builder.custom_type(lanes), "bitmask")
+            }
+        };
+        variable_map[instr.out1] =
+            self.builder
+                .build_int_cast(res, self.builder.i32(), false, "vtest");
+        Ok(())
+    }
+}
@@ -1,68 +1,261 @@
 use crate::{
     abstraction::{
         context::Context, lljit::JITExecutionEngine, module::Module, pass_manager::PassManager,
+        target_machine::TargetMachine,
     },
-    aot::AOTFunctions,
+    aot::{compile_llvm_module, AOTFunctions, CompilationCache},
     error::ExecutionError,
-    FunctionLoader, LLVMAdditionalResources, Translator,
+    util::build_llvm_lazy_body_name,
+    FunctionLoader, LLVMAdditionalResources, OptLevel, TargetInfo, Translator,
+};
+use module::{
+    instructions::FunctionIR, objects::value::ValueRaw, passes::PassManager as IRPassManager,
+    FunctionLoaderInterface, Module as WasmModule,
 };
-use module::{objects::value::ValueRaw, FunctionLoaderInterface, Module as WasmModule};
 use runtime_interface::RawPointer;
 use std::rc::Rc;
-use wasm_types::GlobalIdx;
+use wasm_types::{FuncIdx, GlobalIdx};
 
 pub struct JITExecutor {
     execution_engine: JITExecutionEngine,
     #[allow(dead_code)] // hold on to the context to prevent it from being dropped
     context: Rc<Context>,
+    /// functions compiled on their first call, if the module is compiled lazily
+    lazy: Option<LazyFunctions>,
+}
+
+struct LazyFunctions {
+    module: Rc<WasmModule>,
+    // parser and optimizations for functions compiled on first use
+    loader: parser::FunctionLoader,
+    passes: Option<IRPassManager>,
+    // code of the compiled functions, indexed by function index
+    compiled: Vec<Option<RawPointer>>,
+    // hold on to the contexts of the functions' modules to prevent them from being dropped
+    contexts: Vec<Rc<Context>>,
 }
 
 impl JITExecutor {
-    pub fn new(module: Rc<WasmModule>) -> Result<Self, ExecutionError> {
+    /// Compiles the module with the given optimization level. Precompiled modules are only loaded, they keep the
+    /// optimizations they were compiled with.
+    pub fn new(module: Rc<WasmModule>, opt_level: &OptLevel) -> Result<Self, ExecutionError> {
+        Self::with_loader(module, parser::FunctionLoader::default(), opt_level)
+    }
+
+    /// Like [`JITExecutor::new`], but decodes and validates the function bodies with `loader`, e.g. on its number
+    /// of worker threads.
+    pub fn with_loader(
+        module: Rc<WasmModule>,
+        loader: parser::FunctionLoader,
+        opt_level: &OptLevel,
+    ) -> Result<Self, ExecutionError> {
         match module.source.kind() {
-            resource_buffer::SourceFormat::Wasm => {
-                Translator::translate_module_meta(&module)?;
-                FunctionLoader::default()
-                    .parse_all_functions(&module)
-                    .map_err(|e| ExecutionError::Msg(e.to_string()))?;
-
-                let (llvm_module, llvm_context) = {
-                    let artifacts_ref = module.artifact_registry.read().unwrap();
-                    let llvm_resources = artifacts_ref.get("llvm-module").unwrap();
-                    let llvm_resources = llvm_resources.read().unwrap();
-                    let llvm_resources = llvm_resources
-                        .downcast_ref::<LLVMAdditionalResources>()
-                        .unwrap();
-                    (
-                        llvm_resources.module.clone(),
-                        llvm_resources.context.clone(),
-                    )
-                };
-                let mut instance = Self {
-                    execution_engine: JITExecutionEngine::init()?,
-                    context: llvm_context,
-                };
-                instance.add_module(llvm_module)?;
-                return Ok(instance);
-            }
-            resource_buffer::SourceFormat::Cwasm => {
-                crate::aot::parse_aot_functions(&module)
-                    .map_err(|e| ExecutionError::Msg(e.to_string()))?;
-
-                let artifact_ref = module.artifact_registry.read().unwrap();
-                let obj_buf = artifact_ref.get("llvm-obj").unwrap();
-                let mut instance = Self {
-                    execution_engine: JITExecutionEngine::init()?,
-                    context: Rc::new(Context::create()),
-                };
-                let obj_buf = obj_buf.read().unwrap();
-                let obj_buf = obj_buf.downcast_ref::<AOTFunctions>().unwrap();
-                instance.add_object_file(
-                    &module.source.get()[obj_buf.offset..obj_buf.offset + obj_buf.size],
-                )?;
-                return Ok(instance);
+            resource_buffer::SourceFormat::Wasm | resource_buffer::SourceFormat::Wat => {
+                Self::translate(&module, loader, None)?;
+                Self::from_translated_module(&module, opt_level)
             }
+            resource_buffer::SourceFormat::Cwasm => Self::from_cwasm(&module, opt_level),
+        }
+    }
+
+    /// Translates the module from its `FunctionIR` after parsing it with `loader` and optimizing it with
+    /// `passes`, instead of emitting LLVM IR while parsing. Precompiled modules are loaded as with
+    /// [`JITExecutor::new`].
+    pub fn with_ir_passes(
+        module: Rc<WasmModule>,
+        loader: parser::FunctionLoader,
+        passes: &IRPassManager,
+        opt_level: &OptLevel,
+    ) -> Result<Self, ExecutionError> {
+        if module.source.kind() == resource_buffer::SourceFormat::Cwasm {
+            return Self::new(module, opt_level);
+        }
+        Self::translate(&module, loader, Some(passes))?;
+        Self::from_translated_module(&module, opt_level)
+    }
+
+    /// Like [`JITExecutor::with_loader`], or [`JITExecutor::with_ir_passes`] with `ir_passes`, but reuses the
+    /// object code from `cache` if the module was compiled with the same settings before and stores it there
+    /// otherwise.
+    pub fn with_cache(
+        module: Rc<WasmModule>,
+        loader: parser::FunctionLoader,
+        ir_passes: Option<&IRPassManager>,
+        opt_level: &OptLevel,
+        cache: &CompilationCache,
+    ) -> Result<Self, ExecutionError> {
+        if module.source.kind() == resource_buffer::SourceFormat::Cwasm {
+            return Self::new(module, opt_level);
+        }
+        let target = TargetInfo::host();
+        let entry = cache.entry(module.source.get(), opt_level, &target, ir_passes);
+        if let Some(precompiled) = entry.load() {
+            return Self::from_cwasm(&precompiled, opt_level);
+        }
+
+        Self::translate(&module, loader, ir_passes)?;
+        let llvm_module = {
+            let artifacts_ref = module.artifact_registry.read().unwrap();
+            let llvm_resources = artifacts_ref.get("llvm-module").unwrap().read().unwrap();
+            llvm_resources
+                .downcast_ref::<LLVMAdditionalResources>()
+                .unwrap()
+                .module
+                .clone()
+        };
+        let object = compile_llvm_module(&llvm_module, opt_level, &target)?;
+        // the module still runs if the cache can't be written
+        if let Err(e) = entry.store(&module, &object, opt_level, target) {
+            log::warn!(
+                "Could not store compiled module in cache {:?}: {e}",
+                cache.dir()
+            );
+        }
+        Self::from_object(&object, opt_level)
+    }
+
+    /// Compiles only a stub for each function, which compiles the function with [`JITExecutor::compile_function`]
+    /// through the runtime on its first call. The functions are parsed with `loader` and optimized with
+    /// `ir_passes`, invalid functions only trap once they are called. Precompiled modules are loaded as with
+    /// [`JITExecutor::new`].
+    pub fn lazy(
+        module: Rc<WasmModule>,
+        loader: parser::FunctionLoader,
+        ir_passes: Option<IRPassManager>,
+        opt_level: &OptLevel,
+    ) -> Result<Self, ExecutionError> {
+        if module.source.kind() == resource_buffer::SourceFormat::Cwasm {
+            return Self::new(module, opt_level);
+        }
+        Translator::translate_module_meta(&module)?;
+        Translator::translate_lazy_stubs(&module)?;
+        let mut instance = Self::from_translated_module(&module, opt_level)?;
+        instance.lazy = Some(LazyFunctions {
+            compiled: vec![None; module.meta.functions.len()],
+            module,
+            loader,
+            passes: ir_passes,
+            contexts: Vec::new(),
+        });
+        Ok(instance)
+    }
+
+    /// Whether functions are compiled on their first call, see [`JITExecutor::lazy`].
+    pub fn is_lazy(&self) -> bool {
+        self.lazy.is_some()
+    }
+
+    /// Translates the function `func_idx` of a lazily compiled module into an LLVM module of its own, compiles it
+    /// and returns its code, which follows the internal calling convention. Each function is only compiled once.
+    pub fn compile_function(&mut self, func_idx: FuncIdx) -> Result<RawPointer, ExecutionError> {
+        let lazy = self.lazy.as_mut().ok_or_else(|| {
+            ExecutionError::Msg("functions of the module are not compiled lazily".into())
+        })?;
+        let compiled = lazy
+            .compiled
+            .get(func_idx as usize)
+            .ok_or_else(|| ExecutionError::FunctionNotFound(func_idx.to_string()))?;
+        if let Some(code) = compiled {
+            return Ok(*code);
+        }
+
+        log::debug!("Compiling function {func_idx} on its first call");
+        let module = lazy.module.clone();
+        let mut ir = lazy
+            .loader
+            .parse_function_ir(&module, func_idx)
+            .map_err(|e| ExecutionError::Msg(e.to_string()))?;
+        if let Some(passes) = &lazy.passes {
+            passes
+                .run(&mut ir)
+                .map_err(|e| ExecutionError::Msg(e.to_string()))?;
+        }
+        let (llvm_module, llvm_context) =
+            Translator::translate_lazy_function(&module, func_idx, &ir)?;
+        lazy.contexts.push(llvm_context);
+        self.add_module(llvm_module)?;
+
+        let code = self.get_symbol_addr(&build_llvm_lazy_body_name(func_idx, &module.meta))?;
+        self.lazy.as_mut().unwrap().compiled[func_idx as usize] = Some(code);
+        Ok(code)
+    }
+
+    /// Translates the module to LLVM IR, either right after parsing it with `loader` or from its `FunctionIR`
+    /// after optimizing it with `ir_passes`.
+    fn translate(
+        module: &WasmModule,
+        loader: parser::FunctionLoader,
+        ir_passes: Option<&IRPassManager>,
+    ) -> Result<(), ExecutionError> {
+        Translator::translate_module_meta(module)?;
+        let Some(passes) = ir_passes else {
+            return FunctionLoader::with_parser(loader)
+                .parse_all_functions(module)
+                .map_err(|e| ExecutionError::Msg(e.to_string()));
+        };
+        loader
+            .parse_all_functions(module)
+            .map_err(|e| ExecutionError::Msg(e.to_string()))?;
+        let mut ir = {
+            let artifacts_ref = module.artifact_registry.read().unwrap();
+            let ir = artifacts_ref.get("ir").unwrap().read().unwrap();
+            ir.downcast_ref::<Vec<FunctionIR>>().unwrap().clone()
+        };
+        passes
+            .run_all(&mut ir)
+            .map_err(|e| ExecutionError::Msg(e.to_string()))?;
+        Translator::translate_functions(module, &ir)?;
+        Ok(())
+    }
+
+    /// Loads the object code of a precompiled module, whose metadata has been validated by
+    /// [`crate::aot::parse_aot_meta_with_key`].
+    fn from_cwasm(module: &WasmModule, opt_level: &OptLevel) -> Result<Self, ExecutionError> {
+        crate::aot::parse_aot_functions(module).map_err(|e| ExecutionError::Msg(e.to_string()))?;
+
+        let artifact_ref = module.artifact_registry.read().unwrap();
+        let obj_buf = artifact_ref.get("llvm-obj").unwrap();
+        let obj_buf = obj_buf.read().unwrap();
+        let obj_buf = obj_buf.downcast_ref::<AOTFunctions>().unwrap();
+        Self::from_object(
+            &module.source.get()[obj_buf.offset..obj_buf.offset + obj_buf.size],
+            opt_level,
+        )
+    }
+
+    fn from_object(object: &[u8], opt_level: &OptLevel) -> Result<Self, ExecutionError> {
+        let mut instance = Self {
+            execution_engine: JITExecutionEngine::init(opt_level)?,
+            context: Rc::new(Context::create()),
+            lazy: None,
         };
+        instance.add_object_file(object)?;
+        Ok(instance)
+    }
+
+    fn from_translated_module(
+        module: &WasmModule,
+        opt_level: &OptLevel,
+    ) -> Result<Self, ExecutionError> {
+        let (llvm_module, llvm_context) = {
+            let artifacts_ref = module.artifact_registry.read().unwrap();
+            let llvm_resources = artifacts_ref.get("llvm-module").unwrap();
+            let llvm_resources = llvm_resources.read().unwrap();
+            let llvm_resources = llvm_resources
+                .downcast_ref::<LLVMAdditionalResources>()
+                .unwrap();
+            (
+                llvm_resources.module.clone(),
+                llvm_resources.context.clone(),
+            )
+        };
+        let mut instance = Self {
+            execution_engine: JITExecutionEngine::init(opt_level)?,
+            context: llvm_context,
+            lazy: None,
+        };
+        instance.add_module(llvm_module)?;
+        Ok(instance)
     }
 
     pub fn get_symbol_addr(&self, name: &str) -> Result<RawPointer, ExecutionError> {
@@ -74,7 +267,10 @@ impl JITExecutor {
     }
 
     pub fn add_module(&mut self, llvm_module: Rc<Module>) -> Result<(), ExecutionError> {
-        PassManager::optimize_module(&llvm_module)?;
+        let opt_level = self.execution_engine.opt_level();
+        let target_machine = TargetMachine::create(opt_level, &TargetInfo::host())?;
+        target_machine.configure_module(&llvm_module);
+        PassManager::optimize_module(&llvm_module, opt_level, &target_machine)?;
 
         #[cfg(debug_assertions)]
         llvm_module.print_to_file();
@@ -82,18 +278,9 @@ impl JITExecutor {
         self.execution_engine.add_llvm_module(llvm_module)
     }
 
-    pub(crate) fn get_module_as_object_buffer(
-        &self,
-        module_idx: usize,
-    ) -> Result<&[u8], ExecutionError> {
-        self.execution_engine
-            .get_module_as_object_buffer(module_idx)
-    }
-
     /// Add object file to the JIT compiler
     ///
-    /// # Warning
-    /// Does not take ownership of the object file buffer. Buffer must be kept alive until the JIT compiler is dropped.
+    /// Note: The object file is copied, so the buffer doesn't have to outlive the JIT compiler.
     pub(crate) fn add_object_file(&mut self, obj_file: &[u8]) -> Result<(), ExecutionError> {
         self.execution_engine.add_object_file(obj_file)
     }
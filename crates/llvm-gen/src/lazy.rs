@@ -0,0 +1,164 @@
+use crate::abstraction::{builder::Builder, context::Context, function::Function, module::Module};
+use crate::util::{build_llvm_function_name, build_llvm_lazy_body_name};
+use crate::{error::TranslationError, LLVMAdditionalResources, Translator};
+use llvm_sys::{LLVMIntPredicate, LLVMLinkage};
+use module::instructions::FunctionIR;
+use module::objects::function::FunctionSource;
+use module::Module as WasmModule;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_types::FuncIdx;
+
+impl<'wasm> Translator<'wasm> {
+    /// Defines every function of the module created by [`Translator::translate_module_meta`] as a stub, which
+    /// compiles the function through the runtime on its first call, caches its code and tail calls it.
+    pub fn translate_lazy_stubs(module: &'wasm WasmModule) -> Result<(), TranslationError> {
+        let artifacts = module.artifact_registry.read().unwrap();
+        let llvm_resources_locked = match artifacts.get("llvm-module") {
+            Some(llvm_resources) => llvm_resources,
+            None => {
+                return Err(TranslationError::Msg(
+                    "LLVM resources not found in module. Parse module meta before creating lazy function stubs."
+                        .to_string(),
+                ))
+            }
+        };
+        let mut llvm_resources_write = llvm_resources_locked.write().unwrap();
+        let llvm_resources: &mut LLVMAdditionalResources =
+            llvm_resources_write.downcast_mut().unwrap();
+
+        // skip if already done for another instance of the module
+        if llvm_resources.lazy_stubs {
+            return Ok(());
+        }
+        if llvm_resources.functions_parsed {
+            return Err(TranslationError::Msg(
+                "Functions are already translated ahead of time, they can't be compiled lazily."
+                    .to_string(),
+            ));
+        }
+
+        let builder = llvm_resources
+            .context
+            .create_builder(llvm_resources.module.clone());
+        let instance = Self {
+            context: llvm_resources.context.clone(),
+            builder,
+            module: llvm_resources.module.clone(),
+            wasm_module_meta: &module.meta,
+            llvm_functions: llvm_resources.functions.clone(),
+        };
+
+        for func_idx in 0..module.meta.functions.len() {
+            // imported functions are declared by the module meta and compiled by their own module
+            if let FunctionSource::Import(_) = module.meta.functions[func_idx].source {
+                continue;
+            }
+            let llvm_func = instance.llvm_functions.borrow()[func_idx];
+            instance.translate_lazy_stub(&llvm_func, func_idx as FuncIdx)?;
+        }
+        llvm_resources.lazy_stubs = true;
+        Ok(())
+    }
+
+    /// Translates the function `func_idx` into an LLVM module of its own, named by
+    /// [`build_llvm_lazy_body_name`]. The other functions are called through their stubs.
+    pub fn translate_lazy_function(
+        module: &'wasm WasmModule,
+        func_idx: FuncIdx,
+        ir: &FunctionIR,
+    ) -> Result<(Rc<Module>, Rc<Context>), TranslationError> {
+        let body_name = build_llvm_lazy_body_name(func_idx, &module.meta);
+        let llvm_context = Rc::new(Context::create());
+        let llvm_module = Rc::new(Module::new(&body_name, &llvm_context));
+        let instance = Self {
+            context: llvm_context.clone(),
+            builder: llvm_context.create_builder(llvm_module.clone()),
+            module: llvm_module.clone(),
+            wasm_module_meta: &module.meta,
+            llvm_functions: Rc::new(RefCell::new(Vec::new())),
+        };
+        instance.declare_globals();
+        for (i, function) in module.meta.functions.iter().enumerate() {
+            let i = i as FuncIdx;
+            let name = if i == func_idx {
+                body_name.clone()
+            } else {
+                build_llvm_function_name(i, &module.meta, false)
+            };
+            let fn_type = instance.llvm_internal_func_type_from_wasm(function.type_idx as usize)?;
+            let llvm_function = instance.module.add_internal_function(
+                &name,
+                fn_type,
+                LLVMLinkage::LLVMExternalLinkage,
+            );
+            instance.llvm_functions.borrow_mut().push(llvm_function);
+        }
+
+        let llvm_func = instance.llvm_functions.borrow()[func_idx as usize];
+        instance.translate_internal_function(
+            ir,
+            module.meta.functions[func_idx as usize].type_idx,
+            &llvm_func,
+            func_idx,
+        )?;
+        Ok((llvm_module, llvm_context))
+    }
+
+    fn translate_lazy_stub(
+        &self,
+        llvm_function: &Function,
+        func_idx: FuncIdx,
+    ) -> Result<(), TranslationError> {
+        let code_cache = self.module.add_private_global(
+            &format!("__wasmine_lazy__{func_idx}"),
+            self.builder.ptr(),
+            self.builder.const_null_ptr(),
+        );
+        let entry_bb = self
+            .context
+            .append_basic_block(llvm_function.get(), "entry");
+        let compile_bb = self
+            .context
+            .append_basic_block(llvm_function.get(), "compile");
+        let call_bb = self.context.append_basic_block(llvm_function.get(), "call");
+
+        self.builder.position_at_end(entry_bb);
+        let cached_code = self
+            .builder
+            .build_load(self.builder.ptr(), code_cache, "cached_code");
+        let is_compiled = self.builder.build_icmp(
+            LLVMIntPredicate::LLVMIntNE,
+            cached_code,
+            self.builder.const_null_ptr(),
+            "is_compiled",
+        );
+        self.builder
+            .build_conditional_branch(is_compiled, call_bb, compile_bb);
+
+        self.builder.position_at_end(compile_bb);
+        let compiled_code = self.compile_function(Self::get_rt_ref(llvm_function), func_idx);
+        self.builder.build_store(compiled_code, code_cache);
+        self.builder.build_unconditional_branch(call_bb);
+
+        self.builder.position_at_end(call_bb);
+        let code = self.builder.build_phi(self.builder.ptr(), "code");
+        Builder::phi_add_incoming(
+            code,
+            &mut [cached_code, compiled_code],
+            &mut [entry_bb, compile_bb],
+        );
+        let type_idx = self.wasm_module_meta.functions[func_idx as usize].type_idx;
+        let num_params = self.wasm_module_meta.function_types[type_idx as usize].num_params();
+        // the runtime ptr is forwarded with the wasm parameters
+        let mut params = (0..=num_params)
+            .map(|i| llvm_function.get_param(i))
+            .collect::<Vec<_>>();
+        let callee = Function::new(code, llvm_function.r#type()).unwrap();
+        self.builder.build_tail_call(&callee, &mut params);
+
+        #[cfg(debug_assertions)]
+        Self::verify_function(&self.module, llvm_function, func_idx, self.wasm_module_meta)?;
+        Ok(())
+    }
+}
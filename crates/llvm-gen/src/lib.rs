@@ -4,15 +4,21 @@ mod error;
 mod function_builder;
 mod instructions;
 mod jit_executor;
+mod lazy;
+mod opt_level;
 mod parser;
 mod runtime_adapter;
+mod target;
 mod translator;
 mod util;
 
 pub use abstraction::context::Context;
 pub use error::*;
 pub use jit_executor::JITExecutor;
+pub use opt_level::OptLevel;
+pub use target::{CompileTarget, TargetInfo};
 pub use translator::Translator;
+pub use util::build_llvm_function_name;
 
 use abstraction::{function::Function, module::Module};
 use module::{objects::module::FunctionLoaderInterface, Module as WasmModule};
@@ -24,17 +30,29 @@ pub(crate) struct LLVMAdditionalResources {
     functions: Rc<RefCell<Vec<Function>>>,
     pub(crate) context: Rc<Context>,
     functions_parsed: bool,
+    // the functions are stubs compiling them on their first call, see `JITExecutor::lazy`
+    lazy_stubs: bool,
 }
 
-#[derive(Debug, Default)]
-pub struct FunctionLoader;
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FunctionLoader {
+    /// decodes and validates the function bodies before they are translated
+    parser: ::parser::FunctionLoader,
+}
 
-impl FunctionLoader {}
+impl FunctionLoader {
+    /// Decodes and validates function bodies with `parser`, e.g. on its number of worker threads.
+    pub fn with_parser(parser: ::parser::FunctionLoader) -> Self {
+        Self { parser }
+    }
+}
 
 impl FunctionLoaderInterface for FunctionLoader {
     fn parse_all_functions(&self, module: &WasmModule) -> Result<(), module::ModuleError> {
         match module.source.kind() {
-            SourceFormat::Wasm => parser::parse_wasm_functions(module),
+            SourceFormat::Wasm | SourceFormat::Wat => {
+                parser::parse_wasm_functions(module, &self.parser)
+            }
             SourceFormat::Cwasm => aot::parse_aot_functions(module)
                 .map_err(|e| module::ModuleError::Msg(format!("Error parsing AOT functions: {e}"))),
         }
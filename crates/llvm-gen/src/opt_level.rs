@@ -0,0 +1,151 @@
+use llvm_sys::{
+    target_machine::LLVMCodeGenOptLevel,
+    transforms::pass_builder::{
+        LLVMPassBuilderOptionsRef, LLVMPassBuilderOptionsSetInlinerThreshold,
+        LLVMPassBuilderOptionsSetLoopUnrolling, LLVMPassBuilderOptionsSetLoopVectorization,
+        LLVMPassBuilderOptionsSetSLPVectorization,
+    },
+};
+use std::{fmt::Display, str::FromStr};
+
+/// How much effort LLVM spends on optimizing the translated module, both in the pass pipeline and in code generation.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub enum OptLevel {
+    /// the JIT's original pipeline: locals are promoted to registers, redundant and dead code is removed and code
+    /// is generated at the default level with FastISel
+    #[default]
+    Basic,
+    /// no optimizations and fast instruction selection, for the quickest compilation
+    O0,
+    /// simple optimizations without inlining or vectorization
+    O1,
+    /// inlining and vectorization of loops and straight line code
+    O2,
+    /// like `O2` with more aggressive inlining and code generation
+    O3,
+    /// like `O2` but favoring small code over speed, loops aren't unrolled
+    Os,
+    /// a custom pass pipeline in the syntax of `opt -passes`, e.g. `"mem2reg,gvn,simplifycfg"`, code generation
+    /// uses the default level
+    Custom(String),
+}
+
+impl OptLevel {
+    /// The pass pipeline handed to `LLVMRunPasses`
+    pub(crate) fn pass_pipeline(&self) -> &str {
+        match self {
+            OptLevel::Basic => "mem2reg,gvn,reassociate,adce,simplifycfg",
+            OptLevel::O0 => "default<O0>",
+            OptLevel::O1 => "default<O1>",
+            OptLevel::O2 => "default<O2>",
+            OptLevel::O3 => "default<O3>",
+            OptLevel::Os => "default<Os>",
+            OptLevel::Custom(passes) => passes,
+        }
+    }
+
+    pub(crate) fn codegen_level(&self) -> LLVMCodeGenOptLevel {
+        match self {
+            OptLevel::O0 => LLVMCodeGenOptLevel::LLVMCodeGenLevelNone,
+            OptLevel::O1 => LLVMCodeGenOptLevel::LLVMCodeGenLevelLess,
+            OptLevel::Basic | OptLevel::O2 | OptLevel::Os | OptLevel::Custom(_) => {
+                LLVMCodeGenOptLevel::LLVMCodeGenLevelDefault
+            }
+            OptLevel::O3 => LLVMCodeGenOptLevel::LLVMCodeGenLevelAggressive,
+        }
+    }
+
+    /// FastISel trades code quality for compilation speed, so it is only used at the lowest levels
+    pub(crate) fn fast_isel(&self) -> bool {
+        matches!(self, OptLevel::Basic | OptLevel::O0 | OptLevel::O1)
+    }
+
+    /// Configures the tuning options of the `default<..>` pipelines, the basic and custom pipelines keep LLVM's
+    /// defaults.
+    pub(crate) fn configure_pass_builder(&self, options: LLVMPassBuilderOptionsRef) {
+        // inliner thresholds as chosen by clang for the respective levels
+        let (vectorize, unroll, inliner_threshold) = match self {
+            OptLevel::O0 | OptLevel::O1 => (false, false, 225),
+            OptLevel::O2 => (true, true, 225),
+            OptLevel::O3 => (true, true, 250),
+            OptLevel::Os => (true, false, 75),
+            OptLevel::Basic | OptLevel::Custom(_) => return,
+        };
+        unsafe {
+            LLVMPassBuilderOptionsSetLoopVectorization(options, vectorize.into());
+            LLVMPassBuilderOptionsSetSLPVectorization(options, vectorize.into());
+            LLVMPassBuilderOptionsSetLoopUnrolling(options, unroll.into());
+            LLVMPassBuilderOptionsSetInlinerThreshold(options, inliner_threshold);
+        }
+    }
+}
+
+impl FromStr for OptLevel {
+    type Err = String;
+
+    /// Parses the presets as `basic`, `0`, `1`, `2`, `3` and `s`, the numbered ones optionally prefixed with `O`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "basic" {
+            return Ok(OptLevel::Basic);
+        }
+        match s.strip_prefix('O').unwrap_or(s) {
+            "0" => Ok(OptLevel::O0),
+            "1" => Ok(OptLevel::O1),
+            "2" => Ok(OptLevel::O2),
+            "3" => Ok(OptLevel::O3),
+            "s" => Ok(OptLevel::Os),
+            _ => Err(format!(
+                "unknown optimization level '{s}', expected one of basic, 0, 1, 2, 3 or s"
+            )),
+        }
+    }
+}
+
+impl Display for OptLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptLevel::Basic => write!(f, "basic"),
+            OptLevel::Custom(passes) => write!(f, "custom pipeline '{passes}'"),
+            level => write!(f, "{level:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_their_names() {
+        for level in [
+            OptLevel::O0,
+            OptLevel::O1,
+            OptLevel::O2,
+            OptLevel::O3,
+            OptLevel::Os,
+        ] {
+            assert_eq!(level.to_string().parse(), Ok(level.clone()));
+            // the `O` prefix is optional
+            assert_eq!(level.to_string()[1..].parse(), Ok(level));
+        }
+        assert_eq!(OptLevel::Basic.to_string().parse(), Ok(OptLevel::Basic));
+    }
+
+    #[test]
+    fn default_is_the_basic_pipeline() {
+        assert_eq!(OptLevel::default(), OptLevel::Basic);
+        assert_eq!(
+            OptLevel::default().pass_pipeline(),
+            "mem2reg,gvn,reassociate,adce,simplifycfg"
+        );
+        assert!(OptLevel::default().fast_isel());
+    }
+
+    #[test]
+    fn unknown_levels_are_rejected() {
+        for level in ["", "O", "4", "Oz", "o2", "default<O2>", "Obasic", "Basic"] {
+            let err = level.parse::<OptLevel>().unwrap_err();
+            assert!(err.contains(&format!("'{level}'")), "{err}");
+        }
+    }
+}
@@ -1,8 +1,18 @@
-use crate::{function_builder::LLVMFunctionBuilder, LLVMAdditionalResources};
-use module::{objects::function::FunctionSource, Module as WasmModule, ModuleError};
+use crate::{function_builder::LLVMFunctionBuilder, LLVMAdditionalResources, Translator};
+use module::{
+    instructions::FunctionIR, objects::function::FunctionSource, Module as WasmModule, ModuleError,
+};
 use wasm_types::FuncIdx;
 
-pub fn parse_wasm_functions(module: &WasmModule) -> Result<(), ModuleError> {
+/// Parses the function bodies with `loader` and emits their LLVM IR.
+///
+/// The LLVM context is single-threaded, so with more than one worker thread the bodies are decoded and validated
+/// to IR in parallel first and translated one after another afterwards. A single thread emits the LLVM IR of each
+/// function while parsing it, without building its IR.
+pub fn parse_wasm_functions(
+    module: &WasmModule,
+    loader: &parser::FunctionLoader,
+) -> Result<(), ModuleError> {
     log::debug!("Loading functions using `llvm-gen`.");
     let artifact_registry = module.artifact_registry.read().unwrap();
     let llvm_resources = artifact_registry.get("llvm-module");
@@ -17,37 +27,74 @@ pub fn parse_wasm_functions(module: &WasmModule) -> Result<(), ModuleError> {
         .downcast_mut::<LLVMAdditionalResources>()
         .unwrap();
 
+    if llvm_resources.lazy_stubs {
+        return Err(module::ModuleError::Msg(
+            "Functions are compiled lazily by `llvm-gen` and can't be parsed ahead of time."
+                .to_string(),
+        ));
+    }
     // skip if already parsed
     if llvm_resources.functions_parsed {
         log::info!("Functions already parsed by `llvm-gen`. Skipping.");
         return Ok(());
     }
 
-    for func_idx in 0..module.meta.functions.len() {
-        let function = &module.meta.functions[func_idx];
-        match &function.source {
-            FunctionSource::Import(_) => continue,
-            FunctionSource::Wasm(function_unparsed) => {
-                // function has unparsed mem and is not an import!
-                let mut function_builder = LLVMFunctionBuilder::new(
-                    llvm_resources.context.clone(),
-                    func_idx as FuncIdx,
-                    llvm_resources.module.clone(),
-                    llvm_resources.functions.clone(),
-                    &module.meta,
-                );
-                parser::FunctionLoader::default()
-                    .parse_single_function(
-                        &module.source,
-                        func_idx as FuncIdx,
-                        &function_unparsed,
-                        &module.meta,
-                        &mut function_builder,
-                    )
-                    .map_err(|e| module::ModuleError::Msg(e.to_string()))?;
-                function_builder.finalize();
-            }
+    let functions: Vec<FuncIdx> = (0..module.meta.functions.len() as FuncIdx)
+        .filter(|func_idx| {
+            matches!(
+                module.meta.functions[*func_idx as usize].source,
+                FunctionSource::Wasm(_)
+            )
+        })
+        .collect();
+    if loader.threads() > 1 {
+        let parsed = loader
+            .parse_functions_ir(module, &functions)
+            .map_err(|e| module::ModuleError::Msg(e.to_string()))?;
+        // the translator takes the IR indexed by function index, imported functions have none
+        let mut ir = vec![FunctionIR::default(); module.meta.functions.len()];
+        for (func_idx, function_ir) in functions.into_iter().zip(parsed) {
+            ir[func_idx as usize] = function_ir;
         }
+        // the translator locks the LLVM resources itself
+        drop(artifact_ref);
+        Translator::translate_functions(module, &ir)
+            .map_err(|e| module::ModuleError::Msg(e.to_string()))?;
+        let mut artifact_ref = artifact_registry
+            .get("llvm-module")
+            .unwrap()
+            .write()
+            .unwrap();
+        artifact_ref
+            .downcast_mut::<LLVMAdditionalResources>()
+            .unwrap()
+            .functions_parsed = true;
+        return Ok(());
+    }
+
+    for func_idx in functions {
+        let FunctionSource::Wasm(function_unparsed) =
+            &module.meta.functions[func_idx as usize].source
+        else {
+            unreachable!()
+        };
+        let mut function_builder = LLVMFunctionBuilder::new(
+            llvm_resources.context.clone(),
+            func_idx,
+            llvm_resources.module.clone(),
+            llvm_resources.functions.clone(),
+            &module.meta,
+        );
+        loader
+            .parse_single_function(
+                &module.source,
+                func_idx,
+                function_unparsed,
+                &module.meta,
+                &mut function_builder,
+            )
+            .map_err(|e| module::ModuleError::Msg(e.to_string()))?;
+        function_builder.finalize();
     }
     llvm_resources.functions_parsed = true;
     Ok(())
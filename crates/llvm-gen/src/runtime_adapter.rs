@@ -7,7 +7,7 @@ use llvm_sys::{
     prelude::{LLVMTypeRef, LLVMValueRef},
 };
 use runtime_interface::{ExecutionContext, MemoryInstance};
-use wasm_types::{DataIdx, ElemIdx, MemIdx, TableIdx, ValType};
+use wasm_types::{DataIdx, ElemIdx, FuncIdx, MemIdx, RefType, TableIdx, TagIdx, TypeIdx, ValType};
 
 impl Translator<'_> {
     pub(crate) fn ec_memories_ptr(&self, ec_ptr: LLVMValueRef) -> LLVMValueRef {
@@ -78,7 +78,28 @@ impl Translator<'_> {
             self.builder
                 .build_bitcast(mem_size_ptr, self.builder.ptr(), "mem_size_ptr");
         self.builder
-            .build_load(self.builder.i32(), mem_size_ptr, "load_mem_size")
+            .build_load(self.builder.i64(), mem_size_ptr, "load_mem_size")
+    }
+
+    /// Type of addresses, sizes and page counts of memory `memory_idx`.
+    fn index_ty(&self, memory_idx: MemIdx) -> LLVMTypeRef {
+        let index_type = self.wasm_module_meta.memories[memory_idx as usize]
+            .r#type
+            .index_type;
+        self.builder.valtype2llvm(index_type.val_type())
+    }
+
+    /// Widens an address or size operand of a memory, the runtime operates on 64-bit values only.
+    fn index_to_i64(&self, val: LLVMValueRef) -> LLVMValueRef {
+        self.builder
+            .build_int_cast(val, self.builder.i64(), false, "extend_index")
+    }
+
+    /// Current size of memory `memory_idx` in pages.
+    pub(crate) fn memory_size(&self, ctxt: LLVMValueRef, memory_idx: MemIdx) -> LLVMValueRef {
+        let size = self.ec_get_mem_size(ctxt, memory_idx as usize);
+        self.builder
+            .build_int_cast(size, self.index_ty(memory_idx), false, "mem_size")
     }
 
     fn get_rt_func(&self, name: &str, func_type: LLVMTypeRef) -> Function {
@@ -99,13 +120,24 @@ impl Translator<'_> {
         grow_by: LLVMValueRef,
     ) -> LLVMValueRef {
         let func_type = Module::create_func_type(
-            self.builder.i32(),
-            &mut [self.builder.ptr(), self.builder.i32(), self.builder.i32()],
+            self.builder.i64(),
+            &mut [self.builder.ptr(), self.builder.i32(), self.builder.i64()],
         );
         let memory_grow_fn = self.get_rt_func("__wasmine_runtime.memory_grow", func_type);
-        self.builder.build_call(
+        let res = self.builder.build_call(
             &memory_grow_fn,
-            &mut [ctxt, self.builder.const_i32(memory_idx as u32), grow_by],
+            &mut [
+                ctxt,
+                self.builder.const_i32(memory_idx as u32),
+                self.index_to_i64(grow_by),
+            ],
+            "memory_grow_res",
+        );
+        // truncating keeps -1 intact for 32-bit memories
+        self.builder.build_int_cast(
+            res,
+            self.index_ty(memory_idx as MemIdx),
+            true,
             "memory_grow_res",
         )
     }
@@ -123,8 +155,8 @@ impl Translator<'_> {
             &mut [
                 self.builder.ptr(),
                 self.builder.i32(),
-                self.builder.i32(),
-                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i64(),
                 self.builder.i8(),
             ],
         );
@@ -134,8 +166,8 @@ impl Translator<'_> {
             &mut [
                 ctxt,
                 self.builder.const_i32(memory_idx),
-                offset,
-                size,
+                self.index_to_i64(offset),
+                self.index_to_i64(size),
                 value,
             ],
             "", /* void */
@@ -145,7 +177,8 @@ impl Translator<'_> {
     pub(crate) fn memory_copy(
         &self,
         ctxt: LLVMValueRef,
-        memory_idx: MemIdx,
+        dst_memory_idx: MemIdx,
+        src_memory_idx: MemIdx,
         src_offset: LLVMValueRef,
         dst_offset: LLVMValueRef,
         size: LLVMValueRef,
@@ -156,8 +189,9 @@ impl Translator<'_> {
                 self.builder.ptr(),
                 self.builder.i32(),
                 self.builder.i32(),
-                self.builder.i32(),
-                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i64(),
+                self.builder.i64(),
             ],
         );
         let memory_copy_fn = self.get_rt_func("__wasmine_runtime.memory_copy", func_type);
@@ -165,10 +199,11 @@ impl Translator<'_> {
             &memory_copy_fn,
             &mut [
                 ctxt,
-                self.builder.const_i32(memory_idx),
-                src_offset,
-                dst_offset,
-                size,
+                self.builder.const_i32(dst_memory_idx),
+                self.builder.const_i32(src_memory_idx),
+                self.index_to_i64(src_offset),
+                self.index_to_i64(dst_offset),
+                self.index_to_i64(size),
             ],
             "", /* void */
         );
@@ -203,7 +238,7 @@ impl Translator<'_> {
                 self.builder.i32(),
                 self.builder.i32(),
                 self.builder.i32(),
-                self.builder.i32(),
+                self.builder.i64(),
                 self.builder.i32(),
             ],
         );
@@ -215,13 +250,138 @@ impl Translator<'_> {
                 self.builder.const_i32(memory_idx),
                 self.builder.const_i32(data_idx),
                 src_offset,
-                dst_offset,
+                self.index_to_i64(dst_offset),
                 size,
             ],
             "", /* void */
         );
     }
 
+    /// `expected` selects between `memory_atomic_wait32` and `memory_atomic_wait64` by its type,
+    /// `address` is the 64-bit effective address.
+    pub(crate) fn memory_atomic_wait(
+        &self,
+        ctxt: LLVMValueRef,
+        memory_idx: MemIdx,
+        address: LLVMValueRef,
+        expected: LLVMValueRef,
+        timeout: LLVMValueRef,
+        is_64: bool,
+    ) -> LLVMValueRef {
+        let (name, expected_ty) = if is_64 {
+            ("__wasmine_runtime.memory_atomic_wait64", self.builder.i64())
+        } else {
+            ("__wasmine_runtime.memory_atomic_wait32", self.builder.i32())
+        };
+        let func_type = Module::create_func_type(
+            self.builder.i32(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i32(),
+                self.builder.i64(),
+                expected_ty,
+                self.builder.i64(),
+            ],
+        );
+        let wait_fn = self.get_rt_func(name, func_type);
+        self.builder.build_call(
+            &wait_fn,
+            &mut [
+                ctxt,
+                self.builder.const_i32(memory_idx),
+                address,
+                expected,
+                timeout,
+            ],
+            "memory_atomic_wait_res",
+        )
+    }
+
+    pub(crate) fn memory_atomic_notify(
+        &self,
+        ctxt: LLVMValueRef,
+        memory_idx: MemIdx,
+        address: LLVMValueRef,
+        count: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i32(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i32(),
+            ],
+        );
+        let notify_fn = self.get_rt_func("__wasmine_runtime.memory_atomic_notify", func_type);
+        self.builder.build_call(
+            &notify_fn,
+            &mut [ctxt, self.builder.const_i32(memory_idx), address, count],
+            "memory_atomic_notify_res",
+        )
+    }
+
+    pub(crate) fn exception_throw(
+        &self,
+        ctxt: LLVMValueRef,
+        tag_idx: TagIdx,
+        payload: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [self.builder.ptr(), self.builder.i32(), self.builder.ptr()],
+        );
+        let throw_fn = self.get_rt_func("__wasmine_runtime.exception_throw", func_type);
+        self.builder.build_call(
+            &throw_fn,
+            &mut [ctxt, self.builder.const_i32(tag_idx), payload],
+            "",
+        );
+    }
+
+    pub(crate) fn exception_throw_ref(&self, ctxt: LLVMValueRef, exn: LLVMValueRef) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [self.builder.ptr(), self.builder.i64()],
+        );
+        let throw_ref_fn = self.get_rt_func("__wasmine_runtime.exception_throw_ref", func_type);
+        self.builder.build_call(&throw_ref_fn, &mut [ctxt, exn], "");
+    }
+
+    pub(crate) fn exception_pending(&self, ctxt: LLVMValueRef) -> LLVMValueRef {
+        let func_type = Module::create_func_type(self.builder.i32(), &mut [self.builder.ptr()]);
+        let pending_fn = self.get_rt_func("__wasmine_runtime.exception_pending", func_type);
+        self.builder
+            .build_call(&pending_fn, &mut [ctxt], "exception_pending_res")
+    }
+
+    pub(crate) fn exception_matches(&self, ctxt: LLVMValueRef, tag_idx: TagIdx) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i32(),
+            &mut [self.builder.ptr(), self.builder.i32()],
+        );
+        let matches_fn = self.get_rt_func("__wasmine_runtime.exception_matches", func_type);
+        self.builder.build_call(
+            &matches_fn,
+            &mut [ctxt, self.builder.const_i32(tag_idx)],
+            "exception_matches_res",
+        )
+    }
+
+    pub(crate) fn exception_catch(
+        &self,
+        ctxt: LLVMValueRef,
+        payload_out: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [self.builder.ptr(), self.builder.ptr()],
+        );
+        let catch_fn = self.get_rt_func("__wasmine_runtime.exception_catch", func_type);
+        self.builder
+            .build_call(&catch_fn, &mut [ctxt, payload_out], "exception_catch_res")
+    }
+
     pub(crate) fn table_init(
         &self,
         ctxt: LLVMValueRef,
@@ -286,6 +446,30 @@ impl Translator<'_> {
         )
     }
 
+    pub(crate) fn ref_call(&self, ctxt: LLVMValueRef, func_ref: LLVMValueRef) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.ptr(),
+            &mut [self.builder.ptr(), self.builder.i64()],
+        );
+        let ref_call_fn = self.get_rt_func("__wasmine_runtime.ref_call", func_type);
+        self.builder
+            .build_call(&ref_call_fn, &mut [ctxt, func_ref], "ref_call_res")
+    }
+
+    /// Compiles the lazily compiled function `func_idx` and returns its code.
+    pub(crate) fn compile_function(&self, ctxt: LLVMValueRef, func_idx: FuncIdx) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.ptr(),
+            &mut [self.builder.ptr(), self.builder.i32()],
+        );
+        let compile_function_fn = self.get_rt_func("__wasmine_runtime.compile_function", func_type);
+        self.builder.build_call(
+            &compile_function_fn,
+            &mut [ctxt, self.builder.const_i32(func_idx)],
+            "compiled_function",
+        )
+    }
+
     pub(crate) fn table_size(&self, ctxt: LLVMValueRef, table_idx: TableIdx) -> LLVMValueRef {
         let func_type = Module::create_func_type(
             self.builder.i32(),
@@ -440,6 +624,465 @@ impl Translator<'_> {
         )
     }
 
+    /// `fields` points to one raw value per field, a null pointer creates a default-initialized struct.
+    pub(crate) fn gc_struct_new(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        fields: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [self.builder.ptr(), self.builder.i32(), self.builder.ptr()],
+        );
+        let struct_new_fn = self.get_rt_func("__wasmine_runtime.gc_struct_new", func_type);
+        self.builder.build_call(
+            &struct_new_fn,
+            &mut [ctxt, self.builder.const_i32(type_idx), fields],
+            "struct_new_res",
+        )
+    }
+
+    pub(crate) fn gc_struct_get(
+        &self,
+        ctxt: LLVMValueRef,
+        obj: LLVMValueRef,
+        field_idx: u32,
+        signed: bool,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i32(),
+            ],
+        );
+        let struct_get_fn = self.get_rt_func("__wasmine_runtime.gc_struct_get", func_type);
+        self.builder.build_call(
+            &struct_get_fn,
+            &mut [
+                ctxt,
+                obj,
+                self.builder.const_i32(field_idx),
+                self.builder.const_i32(signed as u32),
+            ],
+            "struct_get_res",
+        )
+    }
+
+    pub(crate) fn gc_struct_set(
+        &self,
+        ctxt: LLVMValueRef,
+        obj: LLVMValueRef,
+        field_idx: u32,
+        value: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i64(),
+            ],
+        );
+        let struct_set_fn = self.get_rt_func("__wasmine_runtime.gc_struct_set", func_type);
+        self.builder.build_call(
+            &struct_set_fn,
+            &mut [ctxt, obj, self.builder.const_i32(field_idx), value],
+            "", /* void */
+        );
+    }
+
+    pub(crate) fn gc_array_new(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        init: LLVMValueRef,
+        len: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i32(),
+            ],
+        );
+        let array_new_fn = self.get_rt_func("__wasmine_runtime.gc_array_new", func_type);
+        self.builder.build_call(
+            &array_new_fn,
+            &mut [ctxt, self.builder.const_i32(type_idx), init, len],
+            "array_new_res",
+        )
+    }
+
+    pub(crate) fn gc_array_new_default(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        len: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [self.builder.ptr(), self.builder.i32(), self.builder.i32()],
+        );
+        let array_new_fn = self.get_rt_func("__wasmine_runtime.gc_array_new_default", func_type);
+        self.builder.build_call(
+            &array_new_fn,
+            &mut [ctxt, self.builder.const_i32(type_idx), len],
+            "array_new_default_res",
+        )
+    }
+
+    pub(crate) fn gc_array_new_fixed(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        values: LLVMValueRef,
+        len: u32,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i32(),
+                self.builder.ptr(),
+                self.builder.i32(),
+            ],
+        );
+        let array_new_fn = self.get_rt_func("__wasmine_runtime.gc_array_new_fixed", func_type);
+        self.builder.build_call(
+            &array_new_fn,
+            &mut [
+                ctxt,
+                self.builder.const_i32(type_idx),
+                values,
+                self.builder.const_i32(len),
+            ],
+            "array_new_fixed_res",
+        )
+    }
+
+    /// `array.new_data` / `array.new_elem`, `name` selects the runtime function.
+    fn gc_array_new_segment(
+        &self,
+        name: &str,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        segment_idx: u32,
+        offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i32(),
+                self.builder.i32(),
+                self.builder.i32(),
+                self.builder.i32(),
+            ],
+        );
+        let array_new_fn = self.get_rt_func(name, func_type);
+        self.builder.build_call(
+            &array_new_fn,
+            &mut [
+                ctxt,
+                self.builder.const_i32(type_idx),
+                self.builder.const_i32(segment_idx),
+                offset,
+                len,
+            ],
+            "array_new_segment_res",
+        )
+    }
+
+    pub(crate) fn gc_array_new_data(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        data_idx: DataIdx,
+        offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) -> LLVMValueRef {
+        self.gc_array_new_segment(
+            "__wasmine_runtime.gc_array_new_data",
+            ctxt,
+            type_idx,
+            data_idx,
+            offset,
+            len,
+        )
+    }
+
+    pub(crate) fn gc_array_new_elem(
+        &self,
+        ctxt: LLVMValueRef,
+        type_idx: TypeIdx,
+        elem_idx: ElemIdx,
+        offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) -> LLVMValueRef {
+        self.gc_array_new_segment(
+            "__wasmine_runtime.gc_array_new_elem",
+            ctxt,
+            type_idx,
+            elem_idx,
+            offset,
+            len,
+        )
+    }
+
+    pub(crate) fn gc_array_get(
+        &self,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        idx: LLVMValueRef,
+        signed: bool,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i32(),
+            ],
+        );
+        let array_get_fn = self.get_rt_func("__wasmine_runtime.gc_array_get", func_type);
+        self.builder.build_call(
+            &array_get_fn,
+            &mut [ctxt, array, idx, self.builder.const_i32(signed as u32)],
+            "array_get_res",
+        )
+    }
+
+    pub(crate) fn gc_array_set(
+        &self,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        idx: LLVMValueRef,
+        value: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i64(),
+            ],
+        );
+        let array_set_fn = self.get_rt_func("__wasmine_runtime.gc_array_set", func_type);
+        self.builder.build_call(
+            &array_set_fn,
+            &mut [ctxt, array, idx, value],
+            "", /* void */
+        );
+    }
+
+    pub(crate) fn gc_array_len(&self, ctxt: LLVMValueRef, array: LLVMValueRef) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i32(),
+            &mut [self.builder.ptr(), self.builder.i64()],
+        );
+        let array_len_fn = self.get_rt_func("__wasmine_runtime.gc_array_len", func_type);
+        self.builder
+            .build_call(&array_len_fn, &mut [ctxt, array], "array_len_res")
+    }
+
+    pub(crate) fn gc_array_fill(
+        &self,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        offset: LLVMValueRef,
+        value: LLVMValueRef,
+        len: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i32(),
+            ],
+        );
+        let array_fill_fn = self.get_rt_func("__wasmine_runtime.gc_array_fill", func_type);
+        self.builder.build_call(
+            &array_fill_fn,
+            &mut [ctxt, array, offset, value, len],
+            "", /* void */
+        );
+    }
+
+    pub(crate) fn gc_array_copy(
+        &self,
+        ctxt: LLVMValueRef,
+        dst: LLVMValueRef,
+        dst_offset: LLVMValueRef,
+        src: LLVMValueRef,
+        src_offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i32(),
+            ],
+        );
+        let array_copy_fn = self.get_rt_func("__wasmine_runtime.gc_array_copy", func_type);
+        self.builder.build_call(
+            &array_copy_fn,
+            &mut [ctxt, dst, dst_offset, src, src_offset, len],
+            "", /* void */
+        );
+    }
+
+    /// `array.init_data` / `array.init_elem`, `name` selects the runtime function.
+    #[allow(clippy::too_many_arguments)]
+    fn gc_array_init_segment(
+        &self,
+        name: &str,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        segment_idx: u32,
+        dst_offset: LLVMValueRef,
+        src_offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) {
+        let func_type = Module::create_func_type(
+            self.builder.void(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i32(),
+                self.builder.i32(),
+                self.builder.i32(),
+                self.builder.i32(),
+            ],
+        );
+        let array_init_fn = self.get_rt_func(name, func_type);
+        self.builder.build_call(
+            &array_init_fn,
+            &mut [
+                ctxt,
+                array,
+                self.builder.const_i32(segment_idx),
+                dst_offset,
+                src_offset,
+                len,
+            ],
+            "", /* void */
+        );
+    }
+
+    pub(crate) fn gc_array_init_data(
+        &self,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        data_idx: DataIdx,
+        dst_offset: LLVMValueRef,
+        src_offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) {
+        self.gc_array_init_segment(
+            "__wasmine_runtime.gc_array_init_data",
+            ctxt,
+            array,
+            data_idx,
+            dst_offset,
+            src_offset,
+            len,
+        )
+    }
+
+    pub(crate) fn gc_array_init_elem(
+        &self,
+        ctxt: LLVMValueRef,
+        array: LLVMValueRef,
+        elem_idx: ElemIdx,
+        dst_offset: LLVMValueRef,
+        src_offset: LLVMValueRef,
+        len: LLVMValueRef,
+    ) {
+        self.gc_array_init_segment(
+            "__wasmine_runtime.gc_array_init_elem",
+            ctxt,
+            array,
+            elem_idx,
+            dst_offset,
+            src_offset,
+            len,
+        )
+    }
+
+    pub(crate) fn gc_ref_test(
+        &self,
+        ctxt: LLVMValueRef,
+        value: LLVMValueRef,
+        ref_type: RefType,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i32(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i64(),
+                self.builder.i32(),
+            ],
+        );
+        let ref_test_fn = self.get_rt_func("__wasmine_runtime.gc_ref_test", func_type);
+        self.builder.build_call(
+            &ref_test_fn,
+            &mut [
+                ctxt,
+                value,
+                self.builder.const_i64(ref_type.heap_type().to_s33() as u64),
+                self.builder.const_i32(ref_type.is_nullable() as u32),
+            ],
+            "ref_test_res",
+        )
+    }
+
+    pub(crate) fn gc_ref_cast(
+        &self,
+        ctxt: LLVMValueRef,
+        value: LLVMValueRef,
+        ref_type: RefType,
+    ) -> LLVMValueRef {
+        let func_type = Module::create_func_type(
+            self.builder.i64(),
+            &mut [
+                self.builder.ptr(),
+                self.builder.i64(),
+                self.builder.i64(),
+                self.builder.i32(),
+            ],
+        );
+        let ref_cast_fn = self.get_rt_func("__wasmine_runtime.gc_ref_cast", func_type);
+        self.builder.build_call(
+            &ref_cast_fn,
+            &mut [
+                ctxt,
+                value,
+                self.builder.const_i64(ref_type.heap_type().to_s33() as u64),
+                self.builder.const_i32(ref_type.is_nullable() as u32),
+            ],
+            "ref_cast_res",
+        )
+    }
+
     pub(crate) fn get_rt_ref(llvm_function: &Function) -> LLVMValueRef {
         llvm_function.get_param(0)
     }
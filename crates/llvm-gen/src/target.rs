@@ -0,0 +1,135 @@
+use crate::{
+    abstraction::target_machine::{HOST_CPU, HOST_CPU_FEATURES, TARGET_TRIPLE},
+    util::c_str,
+};
+use llvm_sys::{core::LLVMDisposeMessage, target_machine::LLVMNormalizeTargetTriple};
+use std::{ffi::CStr, fmt::Display};
+
+/// The machine object code is generated for.
+///
+/// The JIT always compiles for the host, other targets only make sense for precompiled `.cwasm` files, e.g. to
+/// build them for a baseline CPU that all production hosts support or for another architecture.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct CompileTarget {
+    /// target triple such as `aarch64-unknown-linux-gnu`, `None` for the host's
+    pub triple: Option<String>,
+    /// CPU name as understood by LLVM, e.g. `x86-64-v2` or `generic`. Defaults to the host CPU when compiling
+    /// for the host triple and to `generic` otherwise.
+    pub cpu: Option<String>,
+    /// comma separated CPU features on top of those of the CPU, e.g. `+avx2,-avx512f`. Defaults to the host's
+    /// features when compiling for the host CPU and to none otherwise.
+    pub features: Option<String>,
+}
+
+impl CompileTarget {
+    pub fn host() -> Self {
+        Self::default()
+    }
+
+    /// Fills in the defaults for the fields that are not set.
+    pub fn resolve(&self) -> TargetInfo {
+        let host = TargetInfo::host();
+        let triple = match &self.triple {
+            Some(triple) => normalize_triple(triple),
+            None => host.triple.clone(),
+        };
+        let cpu = match &self.cpu {
+            Some(cpu) => cpu.clone(),
+            None if triple == host.triple => host.cpu.clone(),
+            None => "generic".to_owned(),
+        };
+        let features = match &self.features {
+            Some(features) => features.clone(),
+            None if triple == host.triple && cpu == host.cpu => host.features,
+            None => String::new(),
+        };
+        TargetInfo {
+            triple,
+            cpu,
+            features,
+        }
+    }
+}
+
+/// A fully resolved [`CompileTarget`], as recorded in `.cwasm` files.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetInfo {
+    pub triple: String,
+    pub cpu: String,
+    pub features: String,
+}
+
+impl TargetInfo {
+    pub fn host() -> Self {
+        Self {
+            triple: TARGET_TRIPLE.to_string_lossy().into_owned(),
+            cpu: HOST_CPU.to_string_lossy().into_owned(),
+            features: HOST_CPU_FEATURES.to_string_lossy().into_owned(),
+        }
+    }
+
+    pub fn is_host_triple(&self) -> bool {
+        self.triple == *TARGET_TRIPLE.to_string_lossy()
+    }
+
+    /// Features explicitly enabled for this target that the host CPU lacks. Features implied by the CPU are
+    /// not checked, LLVM doesn't tell which those are.
+    pub fn missing_host_features(&self) -> Vec<&str> {
+        let host_features = HOST_CPU_FEATURES.to_string_lossy();
+        let host_features: Vec<&str> = host_features.split(',').collect();
+        self.features
+            .split(',')
+            .filter(|feature| feature.starts_with('+') && !host_features.contains(feature))
+            .collect()
+    }
+}
+
+impl Display for TargetInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (cpu: {}", self.triple, self.cpu)?;
+        if !self.features.is_empty() {
+            write!(f, ", features: {}", self.features)?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn normalize_triple(triple: &str) -> String {
+    unsafe {
+        let normalized = LLVMNormalizeTargetTriple(c_str(triple).as_ptr());
+        let triple = CStr::from_ptr(normalized).to_string_lossy().into_owned();
+        LLVMDisposeMessage(normalized);
+        triple
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{abstraction::target_machine::TargetMachine, OptLevel};
+
+    #[test]
+    fn foreign_targets_default_to_a_generic_cpu() {
+        let target = CompileTarget {
+            triple: Some("riscv64-unknown-linux-gnu".to_owned()),
+            ..Default::default()
+        }
+        .resolve();
+        assert_eq!(target.cpu, "generic");
+        assert_eq!(target.features, "");
+        assert_eq!(CompileTarget::host().resolve(), TargetInfo::host());
+    }
+
+    #[test]
+    fn unknown_triples_are_rejected() {
+        let target = CompileTarget {
+            triple: Some("bogus-unknown-nowhere".to_owned()),
+            ..Default::default()
+        }
+        .resolve();
+        let err = TargetMachine::create(&OptLevel::default(), &target)
+            .err()
+            .expect("no target machine for an unknown triple");
+        assert!(err.to_string().contains("bogus"), "{err}");
+    }
+}
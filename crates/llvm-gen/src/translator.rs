@@ -1,7 +1,7 @@
 use crate::abstraction::context::Context;
 use crate::abstraction::function::Function;
 use crate::abstraction::module::Module;
-use crate::util::{build_llvm_function_name, c_str};
+use crate::util::{build_llvm_function_name, build_llvm_local_name, c_str};
 use crate::LLVMAdditionalResources;
 use crate::{abstraction::builder::Builder, error::TranslationError};
 use llvm_sys::core::LLVMBuildExtractValue;
@@ -66,6 +66,7 @@ impl<'wasm> Translator<'wasm> {
             context: llvm_context,
             functions: llvm_functions,
             functions_parsed: false,
+            lazy_stubs: false,
         };
         module
             .artifact_registry
@@ -76,6 +77,29 @@ impl<'wasm> Translator<'wasm> {
     }
 
     pub fn translate_functions_from_ir(module: &'wasm WasmModule) -> Result<(), TranslationError> {
+        // cloned, so the artifact registry isn't locked while translating
+        let ir: Vec<FunctionIR> = {
+            let artifacts = module.artifact_registry.read().unwrap();
+            let ir_locked =
+                match artifacts.get("ir") {
+                    Some(ir) => ir,
+                    None => return Err(TranslationError::Msg(
+                        "IR not found in module. Parse IR before running ir function translator."
+                            .to_string(),
+                    )),
+                };
+            let ir_read = ir_locked.read().unwrap();
+            ir_read.downcast_ref::<Vec<FunctionIR>>().unwrap().clone()
+        };
+        Self::translate_functions(module, &ir)
+    }
+
+    /// Translates the IR of the module's functions, indexed by function index, into the LLVM module created by
+    /// [`Translator::translate_module_meta`].
+    pub fn translate_functions(
+        module: &'wasm WasmModule,
+        ir: &[FunctionIR],
+    ) -> Result<(), TranslationError> {
         let artifacts = module.artifact_registry.read().unwrap();
         let llvm_resources_locked = match artifacts.get("llvm-module") {
             Some(llvm_resources) => llvm_resources,
@@ -89,18 +113,6 @@ impl<'wasm> Translator<'wasm> {
         let llvm_resources_read = llvm_resources_locked.read().unwrap();
         let llvm_resources: &LLVMAdditionalResources = llvm_resources_read.downcast_ref().unwrap();
 
-        let ir_locked = match artifacts.get("ir") {
-            Some(ir) => ir,
-            None => {
-                return Err(TranslationError::Msg(
-                    "IR not found in module. Parse IR before running ir function translator."
-                        .to_string(),
-                ))
-            }
-        };
-        let ir_read = ir_locked.read().unwrap();
-        let ir: &Vec<FunctionIR> = ir_read.downcast_ref().unwrap();
-
         let builder = llvm_resources
             .context
             .create_builder(llvm_resources.module.clone());
@@ -113,6 +125,10 @@ impl<'wasm> Translator<'wasm> {
         };
 
         for func_idx in 0..module.meta.functions.len() {
+            // imported functions are declared by the module meta and have no IR
+            if let FunctionSource::Import(_) = module.meta.functions[func_idx].source {
+                continue;
+            }
             let wasm_func_type = module.meta.functions[func_idx].type_idx;
             let llvm_func = instance.llvm_functions.borrow()[func_idx];
             instance.translate_internal_function(
@@ -125,7 +141,8 @@ impl<'wasm> Translator<'wasm> {
         Ok(())
     }
 
-    fn translate_module_meta_internal(&mut self) -> Result<(), TranslationError> {
+    /// Declares the module's globals, whose addresses are registered with the JIT.
+    pub(crate) fn declare_globals(&self) {
         for (global_idx, global) in self.wasm_module_meta.globals.iter().enumerate() {
             let name = format!("__wasmine_global__{global_idx}");
             let global_val_ty = match global.r#type {
@@ -135,6 +152,10 @@ impl<'wasm> Translator<'wasm> {
             self.module
                 .add_global(&name, self.builder.valtype2llvm(global_val_ty));
         }
+    }
+
+    fn translate_module_meta_internal(&mut self) -> Result<(), TranslationError> {
+        self.declare_globals();
         let required_rt_ptr_global_names = self
             .wasm_module_meta
             .imports
@@ -254,11 +275,10 @@ impl<'wasm> Translator<'wasm> {
     ) -> Result<Function, TranslationError> {
         let fn_type = self.llvm_internal_func_type_from_wasm(function.type_idx as usize)?;
         let function_name = build_llvm_function_name(function_idx, &self.wasm_module_meta, false);
-        let llvm_function = self.module.add_function(
+        let llvm_function = self.module.add_internal_function(
             &function_name,
             fn_type,
             LLVMLinkage::LLVMExternalLinkage,
-            LLVMCallConv::LLVMFastCallConv,
         );
         Ok(llvm_function)
     }
@@ -271,7 +291,8 @@ impl<'wasm> Translator<'wasm> {
     ) -> Result<Function, TranslationError> {
         let fn_type = self.llvm_external_func_type_from_wasm()?;
         let internal_fn_type = self.llvm_internal_func_type_from_wasm(type_idx as usize)?;
-        let internal_function_name = func_idx.to_string();
+        let internal_function_name =
+            build_llvm_function_name(func_idx, &self.wasm_module_meta, false);
         if let Some(f) = self
             .module
             .find_func(&internal_function_name, internal_fn_type)
@@ -295,11 +316,10 @@ impl<'wasm> Translator<'wasm> {
 
         // create wrapper with internal signature for calls via different calling conventions
         let internal_fn_wasm_type = self.wasm_module_meta.function_types[type_idx as usize];
-        let internal_function = self.module.add_function(
+        let internal_function = self.module.add_internal_function(
             &internal_function_name,
             internal_fn_type,
             LLVMLinkage::LLVMExternalLinkage,
-            LLVMCallConv::LLVMFastCallConv,
         );
         let entry_bb = self
             .context
@@ -414,12 +434,12 @@ impl<'wasm> Translator<'wasm> {
         out
     }
 
-    fn translate_internal_function(
+    pub(crate) fn translate_internal_function(
         &self,
         wasm_function: &FunctionIR,
         wasm_ty_idx: TypeIdx,
         llvm_function: &Function,
-        _function_idx: FuncIdx,
+        function_idx: FuncIdx,
     ) -> Result<(), TranslationError> {
         let func_type = self
             .wasm_module_meta
@@ -427,7 +447,7 @@ impl<'wasm> Translator<'wasm> {
             .get(wasm_ty_idx as usize)
             .unwrap();
         // allocate locals (function parameters + explicit locals) inside entry block
-        let locals = self.allocate_locals(func_type, wasm_function, llvm_function)?;
+        let locals = self.allocate_locals(function_idx, func_type, wasm_function, llvm_function)?;
 
         let mut variable_map = vec![null_mut() as LLVMValueRef; wasm_function.num_vars];
         let llvm_function_blocks = self.translate_basic_block_map(wasm_function, llvm_function);
@@ -464,7 +484,7 @@ impl<'wasm> Translator<'wasm> {
         Self::verify_function(
             &self.module,
             llvm_function,
-            _function_idx,
+            function_idx,
             self.wasm_module_meta,
         )?;
         Ok(())
@@ -504,7 +524,7 @@ impl<'wasm> Translator<'wasm> {
             );
             params.push(param_val);
         }
-        let ret_val = self.builder.build_call(
+        let ret_val = self.builder.build_internal_call(
             wrapped_function,
             params.as_mut_slice(),
             if func_type.num_results() == 0 {
@@ -553,8 +573,13 @@ impl<'wasm> Translator<'wasm> {
         Ok(())
     }
 
+    fn local_name(&self, function_idx: FuncIdx, local_idx: usize) -> String {
+        build_llvm_local_name(function_idx, local_idx as LocalIdx, self.wasm_module_meta)
+    }
+
     fn allocate_locals(
         &self,
+        function_idx: FuncIdx,
         func_type: &FuncType,
         function: &FunctionIR,
         llvm_function: &Function,
@@ -570,7 +595,7 @@ impl<'wasm> Translator<'wasm> {
             let param_val = llvm_function.get_param(i + 1);
             let local = self
                 .builder
-                .build_alloca(param_llvm_type, &format!("local{i}"));
+                .build_alloca(param_llvm_type, &self.local_name(function_idx, i));
             self.builder.build_store(param_val, local);
             locals.push((local, param_llvm_type));
         }
@@ -579,7 +604,7 @@ impl<'wasm> Translator<'wasm> {
             let local_llvm_type = self.builder.valtype2llvm(*local_ty);
             let local_llvm_storage = self
                 .builder
-                .build_alloca(local_llvm_type, &format!("local{i}"));
+                .build_alloca(local_llvm_type, &self.local_name(function_idx, i));
             self.builder
                 .build_store(self.builder.const_zero(*local_ty), local_llvm_storage);
             locals.push((local_llvm_storage, local_llvm_type));
@@ -615,9 +640,13 @@ impl<'wasm> Translator<'wasm> {
                     variable_map,
                     llvm_function,
                 )?,
-                InstructionType::Reference(i) => {
-                    self.translate_reference(i, instruction, &mut decoder, variable_map)?
-                }
+                InstructionType::Reference(i) => self.translate_reference(
+                    i,
+                    instruction,
+                    &mut decoder,
+                    variable_map,
+                    llvm_function,
+                )?,
                 InstructionType::Variable(i) => {
                     self.translate_variable(i, instruction, &mut decoder, variable_map, local_map)?
                 }
@@ -640,7 +669,20 @@ impl<'wasm> Translator<'wasm> {
                 InstructionType::Table(i) => {
                     self.translate_table(i, instruction, &mut decoder, variable_map, llvm_function)?
                 }
-                _ => todo!("instruction {:?}", instruction),
+                InstructionType::Aggregate(i) => self.translate_aggregate(
+                    i,
+                    instruction,
+                    &mut decoder,
+                    variable_map,
+                    llvm_function,
+                )?,
+                InstructionType::Vector(i) => self.translate_vector(
+                    i,
+                    instruction,
+                    &mut decoder,
+                    variable_map,
+                    llvm_function,
+                )?,
             }
         }
         self.translate_terminator(
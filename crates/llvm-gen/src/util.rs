@@ -4,7 +4,7 @@ use std::{
 };
 
 use module::ModuleMetadata;
-use wasm_types::FuncIdx;
+use wasm_types::{FuncIdx, LocalIdx};
 
 // piratet from the inkwell library
 pub(crate) fn c_str(mut s: &str) -> Cow<'_, CStr> {
@@ -20,7 +20,7 @@ pub(crate) fn c_str(mut s: &str) -> Cow<'_, CStr> {
     unsafe { Cow::from(CStr::from_ptr(s.as_ptr() as *const _)) }
 }
 
-pub(crate) fn build_llvm_function_name(
+pub fn build_llvm_function_name(
     function_idx: FuncIdx,
     wasm_module: &ModuleMetadata,
     is_export: bool,
@@ -31,7 +31,44 @@ pub(crate) fn build_llvm_function_name(
             .map(|s| s.to_string())
             .unwrap_or_else(|| format!("func_{function_idx}"))
     } else {
-        // internally, we simply use the func_idx as the function name (unique-per-module)
-        function_idx.to_string()
+        // internally, we use the func_idx as the function name (unique-per-module), suffixed with
+        // the debug name from the name section to get readable stack traces
+        match wasm_module.names.function_name(function_idx) {
+            Some(name) => format!("{function_idx}.{}", sanitize_debug_name(name)),
+            None => function_idx.to_string(),
+        }
     }
 }
+
+/// Name of the code of a lazily compiled function, its regular name belongs to the stub compiling it on the first
+/// call.
+pub(crate) fn build_llvm_lazy_body_name(
+    function_idx: FuncIdx,
+    wasm_module: &ModuleMetadata,
+) -> String {
+    format!(
+        "{}.body",
+        build_llvm_function_name(function_idx, wasm_module, false)
+    )
+}
+
+pub(crate) fn build_llvm_local_name(
+    function_idx: FuncIdx,
+    local_idx: LocalIdx,
+    wasm_module: &ModuleMetadata,
+) -> String {
+    match wasm_module.names.local_name(function_idx, local_idx) {
+        Some(name) => format!("local{local_idx}.{}", sanitize_debug_name(name)),
+        None => format!("local{local_idx}"),
+    }
+}
+
+/// Names of the name section are arbitrary utf-8 strings, restrict them to characters that are safe in symbols.
+fn sanitize_debug_name(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '$' | '.' => c,
+            _ => '_',
+        })
+        .collect()
+}
@@ -1,6 +1,6 @@
 use super::{
-    basic_block::{BasicBlock, BasicBlockGlue},
-    InstructionDecoder,
+    basic_block::{BasicBlock, BasicBlockGlue, BasicBlockID},
+    DecodingError, InstructionDecoder,
 };
 use crate::{
     instructions::*,
@@ -39,123 +39,189 @@ pub struct FunctionDisplayContext<'a> {
     idx: FuncIdx,
 }
 
+/// `define @name results (params)` header of a function
+fn write_function_header(
+    f: &mut Formatter<'_>,
+    module: &ModuleMetadata,
+    func_idx: FuncIdx,
+    type_idx: TypeIdx,
+) -> std::fmt::Result {
+    let name = Function::debug_function_name(func_idx, module);
+    let Some(function_type) = module.func_type(type_idx) else {
+        return write!(f, "define @{name} <unknown type {type_idx}>");
+    };
+    let ret_types: Vec<String> = function_type
+        .results_iter()
+        .map(|t| format!("{t}"))
+        .collect();
+    let input_types: Vec<String> = function_type
+        .params_iter()
+        .map(|t| format!("{t}"))
+        .collect();
+    write!(
+        f,
+        "define @{name} {} ({})",
+        ret_types.join(", "),
+        input_types.join(", ")
+    )
+}
+
 impl<'a> Display for FunctionDisplayContext<'a> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let name = Function::debug_function_name(self.idx, self.module);
-
-        write!(f, "define @{name}")?;
-
-        let type_idx = self.function.type_idx;
-        let function_type = self.module.function_types.get(type_idx as usize).unwrap();
-        let ret_types: Vec<String> = function_type
-            .results_iter()
-            .map(|t| format!("{t}"))
-            .collect();
-        let ret_types_str = ret_types.join(", ");
-        write!(f, " {ret_types_str}")?;
-
+        write_function_header(f, self.module, self.idx, self.function.type_idx)?;
+        writeln!(f, " {{")?;
         match self.function.source {
             FunctionSource::Import(FunctionImport { import_idx }) => {
-                let import = &self.module.imports[import_idx as usize];
-                let import_name = format!("{}.{}", import.module, import.name);
-                let input_types: Vec<String> = function_type
-                    .params_iter()
-                    .map(|t| format!("{t}"))
-                    .collect();
-                let input_types_str = input_types.join(", ");
-                writeln!(f, " ({input_types_str}) {{",)?;
-                write!(f, "/* imported: {import_name} */",)?
-            }
-            FunctionSource::Wasm(FunctionUnparsed { .. }) => {
-                writeln!(f, " {{",)?;
-                write!(f, "/* raw wasm (unparsed) */",)?
+                match self.module.imports.get(import_idx as usize) {
+                    Some(import) => write!(f, "/* imported: {}.{} */", import.module, import.name)?,
+                    None => write!(f, "/* imported: <unknown import {import_idx}> */")?,
+                }
             }
+            FunctionSource::Wasm(FunctionUnparsed { .. }) => write!(f, "/* raw wasm (unparsed) */")?,
         }
         write!(f, "}}")
     }
 }
 
-pub struct BasicBlockDisplayContext<'a> {
-    module: &'a ModuleMetadata,
-    bb: &'a BasicBlock,
+/// SSA-style IR of a parsed function.
+pub struct FunctionIRDisplayContext<'a> {
+    pub module: &'a ModuleMetadata,
+    pub func_idx: FuncIdx,
+    pub ir: &'a FunctionIR,
 }
 
-#[rustfmt::skip]
-impl<'a> Display for BasicBlockDisplayContext<'a> {
+impl FunctionIRDisplayContext<'_> {
+    pub fn basic_blocks(&self) -> impl Iterator<Item = BasicBlockDisplayContext<'_>> {
+        self.ir
+            .bbs
+            .iter()
+            .map(|bb| BasicBlockDisplayContext::new(self.module, self.func_idx, bb))
+    }
+
+    /// Types of the function's locals including its parameters, annotated with their debug names.
+    pub fn locals(&self) -> Vec<String> {
+        self.ir
+            .locals
+            .iter()
+            .enumerate()
+            .map(|(local_idx, t)| {
+                let name = self.module.names.local_name(self.func_idx, local_idx as LocalIdx);
+                format!("{t}{}", NameComment(name))
+            })
+            .collect()
+    }
+}
+
+impl Display for FunctionIRDisplayContext<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for phi in &self.bb.inputs {
-            writeln!(f, "{phi}")?;
+        let type_idx = self
+            .module
+            .functions
+            .get(self.func_idx as usize)
+            .map(|function| function.type_idx)
+            .unwrap_or(TypeIdx::MAX);
+        write_function_header(f, self.module, self.func_idx, type_idx)?;
+        writeln!(f, " {{")?;
+        writeln!(f, "\t; locals: {}", self.locals().join(", "))?;
+        for bb in self.basic_blocks() {
+            writeln!(f, "bb{}:", bb.bb.id)?;
+            write!(f, "{bb}")?;
         }
+        write!(f, "}}")
+    }
+}
 
-        let mut decoder = InstructionDecoder::new(self.bb.instructions.clone());
-        while let Ok(next_instr_t) = decoder.read_instruction_type() {
-            write!(f, "\t")?;
+pub struct BasicBlockDisplayContext<'a> {
+    pub module: &'a ModuleMetadata,
+    pub func_idx: FuncIdx,
+    pub bb: &'a BasicBlock,
+}
 
-            match next_instr_t.clone() {
-                InstructionType::Memory(MemoryInstructionCategory::Load(_)) => writeln!(f, "{}", decoder.read::<LoadInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Store(_)) => writeln!(f, "{}", decoder.read::<StoreInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Copy)) => writeln!(f, "{}", decoder.read::<MemoryCopyInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Size)) => writeln!(f, "{}", decoder.read::<MemorySizeInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Drop)) => writeln!(f, "{}", decoder.read::<DataDropInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Fill)) => writeln!(f, "{}", decoder.read::<MemoryFillInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Grow)) => writeln!(f, "{}", decoder.read::<MemoryGrowInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Init)) => writeln!(f, "{}", decoder.read::<MemoryInitInstruction>(next_instr_t).unwrap())?,
+impl BasicBlockDisplayContext<'_> {
+    fn local_name(&self, local_idx: LocalIdx) -> NameComment<'_> {
+        NameComment(self.module.names.local_name(self.func_idx, local_idx))
+    }
+
+    fn global_name(&self, global_idx: GlobalIdx) -> NameComment<'_> {
+        NameComment(self.module.names.global_name(global_idx))
+    }
+}
 
-                InstructionType::Numeric(NumericInstructionCategory::Constant) => writeln!(f, "{}", decoder.read::<Constant>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::IUnary(_)) => writeln!(f, "{}", decoder.read::<IUnaryInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::FUnary(_)) => writeln!(f, "{}", decoder.read::<FUnaryInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::IBinary(_)) => writeln!(f, "{}", decoder.read::<IBinaryInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::FBinary(_)) => writeln!(f, "{}", decoder.read::<FBinaryInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::IRelational(_)) => writeln!(f, "{}", decoder.read::<IRelationalInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::FRelational(_)) => writeln!(f, "{}", decoder.read::<FRelationalInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Convert)) => writeln!(f, "{}", decoder.read::<ConvertInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Demote)) => writeln!(f, "{}", decoder.read::<DemoteInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::ExtendBits)) => writeln!(f, "{}", decoder.read::<ExtendBitsInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::ExtendType)) => writeln!(f, "{}", decoder.read::<ExtendTypeInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Promote)) => writeln!(f, "{}", decoder.read::<PromoteInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Reinterpret)) => writeln!(f, "{}", decoder.read::<ReinterpretInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Trunc)) => writeln!(f, "{}", decoder.read::<TruncInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::TruncSat)) => writeln!(f, "{}", decoder.read::<TruncSaturationInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Wrap)) => writeln!(f, "{}", decoder.read::<WrapInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Numeric(NumericInstructionCategory::ITest(_)) => writeln!(f, "{}", decoder.read::<ITestInstruction>(next_instr_t).unwrap())?,
+/// Debug name from the name section as a trailing comment, if there is one
+struct NameComment<'a>(Option<&'a str>);
 
-                InstructionType::Parametric(ParametricInstructionType::Drop) => writeln!(f, "{}", decoder.read::<DropInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Parametric(ParametricInstructionType::Select) => writeln!(f, "{}", decoder.read::<SelectInstruction>(next_instr_t).unwrap())?,
+impl Display for NameComment<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(name) => write!(f, " /* ${name} */"),
+            None => Ok(()),
+        }
+    }
+}
 
-                InstructionType::Table(TableInstructionCategory::Copy) => writeln!(f, "{}", decoder.read::<TableCopyInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Fill) => writeln!(f, "{}", decoder.read::<TableFillInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Get) => writeln!(f, "{}", decoder.read::<TableGetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Grow) => writeln!(f, "{}", decoder.read::<TableGrowInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Init) => writeln!(f, "{}", decoder.read::<TableInitInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Set) => writeln!(f, "{}", decoder.read::<TableSetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Size) => writeln!(f, "{}", decoder.read::<TableSizeInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Table(TableInstructionCategory::Drop) => writeln!(f, "{}", decoder.read::<ElemDropInstruction>(next_instr_t).unwrap())?,
+impl<'a> BasicBlockDisplayContext<'a> {
+    pub fn new(module: &'a ModuleMetadata, func_idx: FuncIdx, bb: &'a BasicBlock) -> Self {
+        Self {
+            module,
+            func_idx,
+            bb,
+        }
+    }
 
-                InstructionType::Reference(ReferenceInstructionType::RefFunc) => writeln!(f, "{}", decoder.read::<ReferenceFunctionInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Reference(ReferenceInstructionType::RefNull) => writeln!(f, "{}", decoder.read::<ReferenceNullInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Reference(ReferenceInstructionType::RefIsNull) => writeln!(f, "{}", decoder.read::<ReferenceIsNullInstruction>(next_instr_t).unwrap())?,
+    /// Text of the block's instructions without its phi nodes and terminator.
+    ///
+    /// An undecodable instruction is reported in place and ends the block, as the following instructions can't
+    /// be located anymore.
+    pub fn instructions(&self) -> Vec<String> {
+        let mut decoder = InstructionDecoder::new(self.bb.instructions.clone());
+        let mut instructions = Vec::new();
+        while let Ok(instr_t) = decoder.read_instruction_type() {
+            match self.decode_instruction(&mut decoder, instr_t) {
+                Ok(instruction) => instructions.push(instruction),
+                Err(e) => {
+                    instructions.push(format!("<{e}>"));
+                    break;
+                }
+            }
+        }
+        instructions
+    }
 
-                InstructionType::Variable(VariableInstructionType::GlobalGet) => writeln!(f, "{}", decoder.read::<GlobalGetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Variable(VariableInstructionType::GlobalSet) => writeln!(f, "{}", decoder.read::<GlobalSetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Variable(VariableInstructionType::LocalGet) => writeln!(f, "{}", decoder.read::<LocalGetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Variable(VariableInstructionType::LocalSet) => writeln!(f, "{}", decoder.read::<LocalSetInstruction>(next_instr_t).unwrap())?,
-                InstructionType::Variable(VariableInstructionType::LocalTee) => writeln!(f, "{}", decoder.read::<LocalTeeInstruction>(next_instr_t).unwrap())?,
+    pub fn terminator(&self) -> String {
+        BasicBlockGlueDisplayContext {
+            bbg: &self.bb.terminator,
+            module: self.module,
+        }
+        .to_string()
+    }
 
-                InstructionType::Meta(MetaInstructionType::PhiNode) => unreachable!("Phi nodes are not encoded."),
-                InstructionType::Control(_) => unreachable!("Control instructions should already be converted into BasicBlockGlue"),
+    fn decode_instruction(
+        &self,
+        decoder: &mut InstructionDecoder,
+        instr_t: InstructionType,
+    ) -> Result<String, DecodingError> {
+        let instruction = IRInstruction::decode(decoder, instr_t)?;
+        let name = match &instruction {
+            IRInstruction::GlobalGet(i) => self.global_name(i.global_idx),
+            IRInstruction::GlobalSet(i) => self.global_name(i.global_idx),
+            IRInstruction::LocalGet(i) => self.local_name(i.local_idx),
+            IRInstruction::LocalSet(i) => self.local_name(i.local_idx),
+            IRInstruction::LocalTee(i) => self.local_name(i.local_idx),
+            _ => NameComment(None),
+        };
+        Ok(format!("{instruction}{name}"))
+    }
+}
 
-                InstructionType::Vector => unimplemented!(),
-            }
+impl Display for BasicBlockDisplayContext<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for phi in &self.bb.inputs {
+            writeln!(f, "{phi}")?;
         }
-        writeln!(
-            f,
-            "{}",
-            BasicBlockGlueDisplayContext {
-                bbg: &self.bb.terminator,
-                module: self.module
-            }
-        )?;
-        Ok(())
+        for instruction in self.instructions() {
+            writeln!(f, "\t{instruction}")?;
+        }
+        writeln!(f, "\t{}", self.terminator())
     }
 }
 
@@ -169,9 +235,14 @@ fn format_vars(vars: &[VariableID]) -> String {
     vars.join(", ")
 }
 
+fn format_unwind(unwind_bb: &Option<BasicBlockID>) -> String {
+    unwind_bb
+        .map(|bb| format!(" unwind bb{bb}"))
+        .unwrap_or_default()
+}
+
 impl Display for BasicBlockGlueDisplayContext<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "\t")?;
         match self.bbg {
             BasicBlockGlue::Jmp {
                 target,
@@ -217,6 +288,7 @@ impl Display for BasicBlockGlueDisplayContext<'_> {
                 return_bb,
                 call_params,
                 return_vars,
+                unwind_bb,
             } => {
                 let function_name = Function::debug_function_name(*func_idx, self.module);
                 match return_vars.len() {
@@ -226,10 +298,11 @@ impl Display for BasicBlockGlueDisplayContext<'_> {
                 }
                 write!(
                     f,
-                    "call {}({}) -> bb{}",
+                    "call {}({}) -> bb{}{}",
                     function_name,
                     format_vars(call_params),
-                    return_bb
+                    return_bb,
+                    format_unwind(unwind_bb)
                 )
             }
             BasicBlockGlue::CallIndirect {
@@ -238,6 +311,7 @@ impl Display for BasicBlockGlueDisplayContext<'_> {
                 return_bb,
                 call_params,
                 return_vars,
+                unwind_bb,
                 ..
             } => {
                 match return_vars.len() {
@@ -247,18 +321,115 @@ impl Display for BasicBlockGlueDisplayContext<'_> {
                 }
                 write!(
                     f,
-                    "call_indirect (table_{}[%{}])({}) -> bb{}",
+                    "call_indirect (table_{}[%{}])({}) -> bb{}{}",
                     table_idx,
                     selector_var,
                     format_vars(call_params),
-                    return_bb
+                    return_bb,
+                    format_unwind(unwind_bb)
+                )
+            }
+            BasicBlockGlue::CallRef {
+                selector_var,
+                return_bb,
+                call_params,
+                return_vars,
+                unwind_bb,
+                ..
+            } => {
+                match return_vars.len() {
+                    0 => write!(f, "void = ")?,
+                    1 => write!(f, "%{} = ", return_vars[0])?,
+                    _ => write!(f, "({}) = ", format_vars(return_vars))?,
+                }
+                write!(
+                    f,
+                    "call_ref %{}({}) -> bb{}{}",
+                    selector_var,
+                    format_vars(call_params),
+                    return_bb,
+                    format_unwind(unwind_bb)
                 )
             }
             BasicBlockGlue::Return { return_vars } => {
                 write!(f, "ret {}", format_vars(return_vars))
             }
-            BasicBlockGlue::ElseMarker { .. } => {
-                unreachable!("ElseMarker should not be printed")
+            BasicBlockGlue::ReturnCall {
+                func_idx,
+                call_params,
+            } => {
+                let function_name = Function::debug_function_name(*func_idx, self.module);
+                write!(
+                    f,
+                    "return_call {}({})",
+                    function_name,
+                    format_vars(call_params)
+                )
+            }
+            BasicBlockGlue::ReturnCallIndirect {
+                selector_var,
+                table_idx,
+                call_params,
+                ..
+            } => write!(
+                f,
+                "return_call_indirect (table_{}[%{}])({})",
+                table_idx,
+                selector_var,
+                format_vars(call_params)
+            ),
+            BasicBlockGlue::ReturnCallRef {
+                selector_var,
+                call_params,
+                ..
+            } => write!(
+                f,
+                "return_call_ref %{}({})",
+                selector_var,
+                format_vars(call_params)
+            ),
+            BasicBlockGlue::Throw {
+                tag_idx,
+                values,
+                unwind_bb,
+            } => write!(
+                f,
+                "throw tag_{}({}){}",
+                tag_idx,
+                format_vars(values),
+                format_unwind(unwind_bb)
+            ),
+            BasicBlockGlue::ThrowRef { exn_var, unwind_bb } => {
+                write!(f, "throw_ref %{}{}", exn_var, format_unwind(unwind_bb))
+            }
+            BasicBlockGlue::CatchDispatch {
+                handlers,
+                unwind_bb,
+            } => {
+                let handlers = handlers
+                    .iter()
+                    .map(|handler| {
+                        let mut out_vars = handler.payload_vars.clone();
+                        out_vars.extend(handler.exn_var);
+                        match handler.tag_idx {
+                            Some(tag_idx) => format!(
+                                "tag_{} => bb{}({})",
+                                tag_idx,
+                                handler.target,
+                                format_vars(&out_vars)
+                            ),
+                            None => {
+                                format!("all => bb{}({})", handler.target, format_vars(&out_vars))
+                            }
+                        }
+                    })
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "catch [{}]{}", handlers, format_unwind(unwind_bb))
+            }
+            // only exists during parsing
+            BasicBlockGlue::ElseMarker { output_vars } => {
+                write!(f, "else_marker ({})", format_vars(output_vars))
             }
             BasicBlockGlue::Unreachable => write!(f, "unreachable"),
         }
@@ -267,8 +438,12 @@ impl Display for BasicBlockGlueDisplayContext<'_> {
 
 impl Display for ModuleMetadata {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(name) = &self.names.module {
+            writeln!(f, "; module ${name}")?;
+        }
         for (idx, global) in self.globals.iter().enumerate() {
-            writeln!(f, "{}", GlobalDisplayContext { global, idx })?;
+            let name = NameComment(self.names.global_name(idx as GlobalIdx));
+            writeln!(f, "{}", GlobalDisplayContext { global, idx, name })?;
         }
         write!(f, "{}", IRDisplayContext { module: self })?;
         Ok(())
@@ -278,6 +453,7 @@ impl Display for ModuleMetadata {
 struct GlobalDisplayContext<'a> {
     global: &'a Global,
     idx: usize,
+    name: NameComment<'a>,
 }
 
 impl Display for GlobalDisplayContext<'_> {
@@ -287,12 +463,16 @@ impl Display for GlobalDisplayContext<'_> {
             GlobalType::Const(t) => format!("const {t}"),
         };
         if self.global.import {
-            writeln!(f, "@__wasmine_global__{}: {} = imported", self.idx, gt)
+            writeln!(
+                f,
+                "@__wasmine_global__{}: {} = imported{}",
+                self.idx, gt, self.name
+            )
         } else {
             writeln!(
                 f,
-                "@__wasmine_global__{}: {} = {:?};",
-                self.idx, gt, self.global.init
+                "@__wasmine_global__{}: {} = {:?};{}",
+                self.idx, gt, self.global.init, self.name
             )
         }
     }
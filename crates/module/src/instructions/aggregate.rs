@@ -0,0 +1,480 @@
+use super::*;
+
+/// Segment providing the initial elements of an array.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArraySegment {
+    Data(DataIdx),
+    Elem(ElemIdx),
+}
+
+impl Display for ArraySegment {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ArraySegment::Data(data_idx) => write!(f, "data {data_idx}"),
+            ArraySegment::Elem(elem_idx) => write!(f, "elem {elem_idx}"),
+        }
+    }
+}
+
+fn read_variables(i: &mut InstructionDecoder) -> Result<Vec<VariableID>, DecodingError> {
+    (0..i.read_immediate::<u32>()?)
+        .map(|_| i.read_variable())
+        .collect()
+}
+
+fn fmt_variables(vars: &[VariableID]) -> String {
+    vars.iter()
+        .map(|var| format!("%{var}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Allocates a struct, fields are default-initialized if `fields` is empty.
+#[derive(Debug, Clone)]
+pub struct StructNewInstruction {
+    pub type_idx: TypeIdx,
+    pub fields: Vec<VariableID>,
+    pub out1: VariableID,
+}
+
+impl Instruction for StructNewInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let fields = read_variables(i)?;
+        let out1 = i.read_variable()?;
+        Ok(StructNewInstruction {
+            type_idx,
+            fields,
+            out1,
+        })
+    }
+}
+
+impl Display for StructNewInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = struct.new {} ({})",
+            self.out1,
+            self.type_idx,
+            fmt_variables(&self.fields)
+        )
+    }
+}
+
+/// `signed` selects sign extension of packed fields.
+#[derive(Debug, Clone)]
+pub struct StructGetInstruction {
+    pub type_idx: TypeIdx,
+    pub field_idx: u32,
+    pub signed: bool,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for StructGetInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let field_idx = i.read_immediate()?;
+        let signed = i.read_immediate::<u8>()? != 0;
+        let in1 = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(StructGetInstruction {
+            type_idx,
+            field_idx,
+            signed,
+            in1,
+            out1,
+        })
+    }
+}
+
+impl Display for StructGetInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = struct.get{} {} {} %{}",
+            self.out1,
+            if self.signed { "_s" } else { "" },
+            self.type_idx,
+            self.field_idx,
+            self.in1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StructSetInstruction {
+    pub type_idx: TypeIdx,
+    pub field_idx: u32,
+    /// struct reference
+    pub in1: VariableID,
+    /// value to store
+    pub in2: VariableID,
+}
+
+impl Instruction for StructSetInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let field_idx = i.read_immediate()?;
+        let in1 = i.read_variable()?;
+        let in2 = i.read_variable()?;
+        Ok(StructSetInstruction {
+            type_idx,
+            field_idx,
+            in1,
+            in2,
+        })
+    }
+}
+
+impl Display for StructSetInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "struct.set {} {} %{} %{}",
+            self.type_idx, self.field_idx, self.in1, self.in2
+        )
+    }
+}
+
+/// Allocates an array of `len` elements, which are default-initialized if `init` is `None`.
+#[derive(Debug, Clone)]
+pub struct ArrayNewInstruction {
+    pub type_idx: TypeIdx,
+    pub init: Option<VariableID>,
+    pub len: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ArrayNewInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let init = match i.read_immediate::<u8>()? {
+            0 => None,
+            _ => Some(i.read_variable()?),
+        };
+        let len = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ArrayNewInstruction {
+            type_idx,
+            init,
+            len,
+            out1,
+        })
+    }
+}
+
+impl Display for ArrayNewInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.init {
+            Some(init) => write!(
+                f,
+                "%{} = array.new {} %{} %{}",
+                self.out1, self.type_idx, init, self.len
+            ),
+            None => write!(
+                f,
+                "%{} = array.new_default {} %{}",
+                self.out1, self.type_idx, self.len
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayNewFixedInstruction {
+    pub type_idx: TypeIdx,
+    pub values: Vec<VariableID>,
+    pub out1: VariableID,
+}
+
+impl Instruction for ArrayNewFixedInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let values = read_variables(i)?;
+        let out1 = i.read_variable()?;
+        Ok(ArrayNewFixedInstruction {
+            type_idx,
+            values,
+            out1,
+        })
+    }
+}
+
+impl Display for ArrayNewFixedInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = array.new_fixed {} ({})",
+            self.out1,
+            self.type_idx,
+            fmt_variables(&self.values)
+        )
+    }
+}
+
+/// Allocates an array initialized from `len` elements of a segment, starting at `offset`.
+#[derive(Debug, Clone)]
+pub struct ArrayNewSegmentInstruction {
+    pub type_idx: TypeIdx,
+    pub segment: ArraySegment,
+    pub offset: VariableID,
+    pub len: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ArrayNewSegmentInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let segment = match t {
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayNewData) => {
+                ArraySegment::Data(i.read_immediate()?)
+            }
+            _ => ArraySegment::Elem(i.read_immediate()?),
+        };
+        let offset = i.read_variable()?;
+        let len = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ArrayNewSegmentInstruction {
+            type_idx,
+            segment,
+            offset,
+            len,
+            out1,
+        })
+    }
+}
+
+impl Display for ArrayNewSegmentInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = array.new {} ({}) %{} %{}",
+            self.out1, self.type_idx, self.segment, self.offset, self.len
+        )
+    }
+}
+
+/// `signed` selects sign extension of packed elements.
+#[derive(Debug, Clone)]
+pub struct ArrayGetInstruction {
+    pub type_idx: TypeIdx,
+    pub signed: bool,
+    pub array: VariableID,
+    pub idx: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ArrayGetInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let signed = i.read_immediate::<u8>()? != 0;
+        let array = i.read_variable()?;
+        let idx = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ArrayGetInstruction {
+            type_idx,
+            signed,
+            array,
+            idx,
+            out1,
+        })
+    }
+}
+
+impl Display for ArrayGetInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = array.get{} {} %{}[%{}]",
+            self.out1,
+            if self.signed { "_s" } else { "" },
+            self.type_idx,
+            self.array,
+            self.idx
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArraySetInstruction {
+    pub type_idx: TypeIdx,
+    pub array: VariableID,
+    pub idx: VariableID,
+    pub value: VariableID,
+}
+
+impl Instruction for ArraySetInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let array = i.read_variable()?;
+        let idx = i.read_variable()?;
+        let value = i.read_variable()?;
+        Ok(ArraySetInstruction {
+            type_idx,
+            array,
+            idx,
+            value,
+        })
+    }
+}
+
+impl Display for ArraySetInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "array.set {} %{}[%{}] %{}",
+            self.type_idx, self.array, self.idx, self.value
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayLenInstruction {
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ArrayLenInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ArrayLenInstruction { in1, out1 })
+    }
+}
+
+impl Display for ArrayLenInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "%{} = array.len %{}", self.out1, self.in1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayFillInstruction {
+    pub type_idx: TypeIdx,
+    pub array: VariableID,
+    pub offset: VariableID,
+    pub value: VariableID,
+    pub len: VariableID,
+}
+
+impl Instruction for ArrayFillInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let array = i.read_variable()?;
+        let offset = i.read_variable()?;
+        let value = i.read_variable()?;
+        let len = i.read_variable()?;
+        Ok(ArrayFillInstruction {
+            type_idx,
+            array,
+            offset,
+            value,
+            len,
+        })
+    }
+}
+
+impl Display for ArrayFillInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "array.fill {} %{} %{} %{} %{}",
+            self.type_idx, self.array, self.offset, self.value, self.len
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ArrayCopyInstruction {
+    pub dst_type_idx: TypeIdx,
+    pub src_type_idx: TypeIdx,
+    pub dst: VariableID,
+    pub dst_offset: VariableID,
+    pub src: VariableID,
+    pub src_offset: VariableID,
+    pub len: VariableID,
+}
+
+impl Instruction for ArrayCopyInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let dst_type_idx = i.read_immediate()?;
+        let src_type_idx = i.read_immediate()?;
+        let dst = i.read_variable()?;
+        let dst_offset = i.read_variable()?;
+        let src = i.read_variable()?;
+        let src_offset = i.read_variable()?;
+        let len = i.read_variable()?;
+        Ok(ArrayCopyInstruction {
+            dst_type_idx,
+            src_type_idx,
+            dst,
+            dst_offset,
+            src,
+            src_offset,
+            len,
+        })
+    }
+}
+
+impl Display for ArrayCopyInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "array.copy {} {} %{} %{} %{} %{} %{}",
+            self.dst_type_idx,
+            self.src_type_idx,
+            self.dst,
+            self.dst_offset,
+            self.src,
+            self.src_offset,
+            self.len
+        )
+    }
+}
+
+/// Copies `len` elements of a segment into an existing array.
+#[derive(Debug, Clone)]
+pub struct ArrayInitSegmentInstruction {
+    pub type_idx: TypeIdx,
+    pub segment: ArraySegment,
+    pub array: VariableID,
+    pub dst_offset: VariableID,
+    pub src_offset: VariableID,
+    pub len: VariableID,
+}
+
+impl Instruction for ArrayInitSegmentInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let type_idx = i.read_immediate()?;
+        let segment = match t {
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayInitData) => {
+                ArraySegment::Data(i.read_immediate()?)
+            }
+            _ => ArraySegment::Elem(i.read_immediate()?),
+        };
+        let array = i.read_variable()?;
+        let dst_offset = i.read_variable()?;
+        let src_offset = i.read_variable()?;
+        let len = i.read_variable()?;
+        Ok(ArrayInitSegmentInstruction {
+            type_idx,
+            segment,
+            array,
+            dst_offset,
+            src_offset,
+            len,
+        })
+    }
+}
+
+impl Display for ArrayInitSegmentInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "array.init {} ({}) %{} %{} %{} %{}",
+            self.type_idx, self.segment, self.array, self.dst_offset, self.src_offset, self.len
+        )
+    }
+}
@@ -2,21 +2,15 @@ use crate::{
     instructions::{PhiNode, VariableID},
     objects::instruction::ControlInstruction,
 };
-use once_cell::sync::Lazy;
 use rkyv::{Archive, Deserialize, Serialize};
 use smallvec::{smallvec, SmallVec};
-use std::{
-    collections::VecDeque,
-    fmt::Debug,
-    sync::atomic::{AtomicU32, Ordering},
-};
+use std::{collections::VecDeque, fmt::Debug};
 use wasm_types::*;
 
-static BASIC_BLOCK_ID: Lazy<AtomicU32> = Lazy::new(|| AtomicU32::new(0));
-
 pub type BasicBlockID = u32;
 
 #[derive(Default, Clone, Debug, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct BasicBlock {
     // instructions encoded
     pub instructions: BasicBlockStorage,
@@ -26,6 +20,7 @@ pub struct BasicBlock {
 }
 
 #[derive(Debug, Default, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct BasicBlockStorage {
     pub immediate_storage: VecDeque<u8>,
     pub variable_storage: VecDeque<VariableID>,
@@ -43,10 +38,6 @@ impl BasicBlock {
         }
     }
 
-    pub fn next_id() -> u32 {
-        BASIC_BLOCK_ID.fetch_add(1, Ordering::SeqCst)
-    }
-
     pub fn successors(&self) -> impl Iterator<Item = BasicBlockID> {
         match &self.terminator {
             BasicBlockGlue::Jmp { target, .. } => smallvec![*target].into_iter(),
@@ -64,6 +55,21 @@ impl BasicBlock {
                 res.push(*default_target);
                 res.into_iter()
             }
+            BasicBlockGlue::Throw { unwind_bb, .. }
+            | BasicBlockGlue::ThrowRef { unwind_bb, .. } => unwind_bb
+                .iter()
+                .copied()
+                .collect::<SmallVec<_>>()
+                .into_iter(),
+            BasicBlockGlue::CatchDispatch {
+                handlers,
+                unwind_bb,
+            } => handlers
+                .iter()
+                .map(|handler| handler.target)
+                .chain(*unwind_bb)
+                .collect::<SmallVec<_>>()
+                .into_iter(),
             _ => smallvec!().into_iter(),
         }
     }
@@ -110,6 +116,7 @@ impl BasicBlock {
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum BasicBlockGlue {
     // jump to another block
     Jmp {
@@ -132,11 +139,13 @@ pub enum BasicBlockGlue {
         default_output_vars: SmallVec<[VariableID; 0]>,
     },
 
+    // exceptions raised by the callee continue at `unwind_bb` or leave the function if there is none
     Call {
         func_idx: u32,
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
     },
 
     CallIndirect {
@@ -146,12 +155,61 @@ pub enum BasicBlockGlue {
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
+    },
+
+    // calls the function referenced by `selector_var`, trapping on null references
+    CallRef {
+        type_idx: u32,
+        selector_var: VariableID,
+        return_bb: BasicBlockID,
+        call_params: SmallVec<[VariableID; 2]>,
+        return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
     },
 
     Return {
         return_vars: SmallVec<[VariableID; 1]>,
     },
 
+    // tail calls return the callee's results directly to the caller's caller
+    ReturnCall {
+        func_idx: u32,
+        call_params: SmallVec<[VariableID; 2]>,
+    },
+
+    ReturnCallIndirect {
+        type_idx: u32,
+        selector_var: VariableID,
+        table_idx: u32,
+        call_params: SmallVec<[VariableID; 2]>,
+    },
+
+    ReturnCallRef {
+        type_idx: u32,
+        selector_var: VariableID,
+        call_params: SmallVec<[VariableID; 2]>,
+    },
+
+    // raises a new exception, which continues at `unwind_bb` or leaves the function if there is none
+    Throw {
+        tag_idx: TagIdx,
+        values: SmallVec<[VariableID; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    },
+
+    ThrowRef {
+        exn_var: VariableID,
+        unwind_bb: Option<BasicBlockID>,
+    },
+
+    // entry of a try_table's exception handlers: the first handler matching the pending exception
+    // catches it, exceptions without a matching handler continue at `unwind_bb`
+    CatchDispatch {
+        handlers: SmallVec<[CatchHandler; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    },
+
     // only required during parsing
     ElseMarker {
         output_vars: SmallVec<[VariableID; 0]>,
@@ -160,3 +218,134 @@ pub enum BasicBlockGlue {
     #[default]
     Unreachable,
 }
+
+impl BasicBlockGlue {
+    /// All blocks the terminator can continue at, including the return and unwind blocks of calls.
+    pub fn targets(&self) -> SmallVec<[BasicBlockID; 2]> {
+        match self {
+            BasicBlockGlue::Jmp { target, .. } => smallvec![*target],
+            BasicBlockGlue::JmpCond {
+                target_if_true,
+                target_if_false,
+                ..
+            } => smallvec![*target_if_true, *target_if_false],
+            BasicBlockGlue::JmpTable {
+                targets,
+                default_target,
+                ..
+            } => targets.iter().copied().chain([*default_target]).collect(),
+            BasicBlockGlue::Call {
+                return_bb,
+                unwind_bb,
+                ..
+            }
+            | BasicBlockGlue::CallIndirect {
+                return_bb,
+                unwind_bb,
+                ..
+            }
+            | BasicBlockGlue::CallRef {
+                return_bb,
+                unwind_bb,
+                ..
+            } => [*return_bb].into_iter().chain(*unwind_bb).collect(),
+            BasicBlockGlue::Throw { unwind_bb, .. }
+            | BasicBlockGlue::ThrowRef { unwind_bb, .. } => unwind_bb.iter().copied().collect(),
+            BasicBlockGlue::CatchDispatch {
+                handlers,
+                unwind_bb,
+            } => handlers
+                .iter()
+                .map(|handler| handler.target)
+                .chain(*unwind_bb)
+                .collect(),
+            BasicBlockGlue::Return { .. }
+            | BasicBlockGlue::ReturnCall { .. }
+            | BasicBlockGlue::ReturnCallIndirect { .. }
+            | BasicBlockGlue::ReturnCallRef { .. }
+            | BasicBlockGlue::ElseMarker { .. }
+            | BasicBlockGlue::Unreachable => smallvec![],
+        }
+    }
+
+    /// Variables read by the terminator, including the output variables of jumps.
+    pub fn uses(&self) -> SmallVec<[VariableID; 4]> {
+        self.clone().uses_mut().into_iter().map(|var| *var).collect()
+    }
+
+    /// Mutable references to the variables read by the terminator, including the output variables of jumps.
+    pub fn uses_mut(&mut self) -> SmallVec<[&mut VariableID; 4]> {
+        match self {
+            BasicBlockGlue::Jmp { output_vars, .. }
+            | BasicBlockGlue::ElseMarker { output_vars } => output_vars.iter_mut().collect(),
+            BasicBlockGlue::JmpCond {
+                cond_var,
+                output_vars,
+                ..
+            } => [cond_var].into_iter().chain(output_vars).collect(),
+            BasicBlockGlue::JmpTable {
+                selector_var,
+                targets_output_vars,
+                default_output_vars,
+                ..
+            } => [selector_var]
+                .into_iter()
+                .chain(targets_output_vars.iter_mut().flatten())
+                .chain(default_output_vars)
+                .collect(),
+            BasicBlockGlue::Call { call_params, .. }
+            | BasicBlockGlue::ReturnCall { call_params, .. } => call_params.iter_mut().collect(),
+            BasicBlockGlue::CallIndirect {
+                selector_var,
+                call_params,
+                ..
+            }
+            | BasicBlockGlue::CallRef {
+                selector_var,
+                call_params,
+                ..
+            }
+            | BasicBlockGlue::ReturnCallIndirect {
+                selector_var,
+                call_params,
+                ..
+            }
+            | BasicBlockGlue::ReturnCallRef {
+                selector_var,
+                call_params,
+                ..
+            } => [selector_var].into_iter().chain(call_params).collect(),
+            BasicBlockGlue::Return { return_vars } => return_vars.iter_mut().collect(),
+            BasicBlockGlue::Throw { values, .. } => values.iter_mut().collect(),
+            BasicBlockGlue::ThrowRef { exn_var, .. } => smallvec![exn_var],
+            BasicBlockGlue::CatchDispatch { .. } | BasicBlockGlue::Unreachable => smallvec![],
+        }
+    }
+
+    /// Variables defined by the terminator: the results of calls and the exceptions caught by handlers.
+    pub fn defs(&self) -> SmallVec<[VariableID; 2]> {
+        match self {
+            BasicBlockGlue::Call { return_vars, .. }
+            | BasicBlockGlue::CallIndirect { return_vars, .. }
+            | BasicBlockGlue::CallRef { return_vars, .. } => return_vars.iter().copied().collect(),
+            BasicBlockGlue::CatchDispatch { handlers, .. } => handlers
+                .iter()
+                .flat_map(|handler| handler.payload_vars.iter().copied().chain(handler.exn_var))
+                .collect(),
+            _ => smallvec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct CatchHandler {
+    /// `None` catches every exception
+    pub tag_idx: Option<TagIdx>,
+    /// block without inputs that is only entered from its dispatch block
+    pub target: BasicBlockID,
+    /// variables defined by the dispatch block receiving the exception payload
+    pub payload_vars: SmallVec<[VariableID; 2]>,
+    /// variable defined by the dispatch block receiving the `exnref` to the exception
+    pub exn_var: Option<VariableID>,
+}
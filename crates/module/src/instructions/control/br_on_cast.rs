@@ -0,0 +1,34 @@
+use super::*;
+use wasm_types::{LabelIdx, RefType};
+
+/// branches if the operand of type `from` matches `to`
+#[derive(Debug, Clone)]
+pub struct BrOnCast {
+    pub label_idx: LabelIdx,
+    pub from: RefType,
+    pub to: RefType,
+}
+
+impl Instruction for BrOnCast {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
+
+/// branches if the operand of type `from` does not match `to`
+#[derive(Debug, Clone)]
+pub struct BrOnCastFail {
+    pub label_idx: LabelIdx,
+    pub from: RefType,
+    pub to: RefType,
+}
+
+impl Instruction for BrOnCastFail {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
@@ -0,0 +1,28 @@
+use super::*;
+use wasm_types::LabelIdx;
+
+#[derive(Debug, Clone)]
+pub struct BrOnNull {
+    pub label_idx: LabelIdx,
+}
+
+impl Instruction for BrOnNull {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BrOnNonNull {
+    pub label_idx: LabelIdx,
+}
+
+impl Instruction for BrOnNonNull {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
@@ -0,0 +1,15 @@
+use super::*;
+use wasm_types::TypeIdx;
+
+#[derive(Debug, Clone)]
+pub struct CallRef {
+    pub type_idx: TypeIdx,
+}
+
+impl Instruction for CallRef {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
@@ -1,25 +1,43 @@
 pub mod block;
 pub mod br;
 pub mod br_if;
+pub mod br_on_cast;
+pub mod br_on_null;
 pub mod br_table;
 pub mod call;
 pub mod call_indirect;
+pub mod call_ref;
 pub mod if_else;
 pub mod r#loop;
 pub mod pseudo;
 pub mod r#return;
+pub mod return_call;
+pub mod return_call_indirect;
+pub mod return_call_ref;
+pub mod throw;
+pub mod throw_ref;
+pub mod try_table;
 pub mod unreachable;
 
 pub use block::*;
 pub use br::*;
 pub use br_if::*;
+pub use br_on_cast::*;
+pub use br_on_null::*;
 pub use br_table::*;
 pub use call::*;
 pub use call_indirect::*;
+pub use call_ref::*;
 pub use if_else::*;
 pub use pseudo::*;
 pub use r#loop::*;
 pub use r#return::*;
+pub use return_call::*;
+pub use return_call_indirect::*;
+pub use return_call_ref::*;
+pub use throw::*;
+pub use throw_ref::*;
+pub use try_table::*;
 pub use unreachable::*;
 
 use super::*;
@@ -0,0 +1,16 @@
+use super::*;
+use wasm_types::{TableIdx, TypeIdx};
+
+#[derive(Debug, Clone)]
+pub struct ReturnCallIndirect {
+    pub type_idx: TypeIdx,
+    pub table_idx: TableIdx,
+}
+
+impl Instruction for ReturnCallIndirect {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
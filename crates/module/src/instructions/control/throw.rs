@@ -0,0 +1,15 @@
+use super::*;
+use wasm_types::TagIdx;
+
+#[derive(Debug, Clone)]
+pub struct Throw {
+    pub tag_idx: TagIdx,
+}
+
+impl Instruction for Throw {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
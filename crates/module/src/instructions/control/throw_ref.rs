@@ -0,0 +1,12 @@
+use super::*;
+
+#[derive(Debug, Clone)]
+pub struct ThrowRef {}
+
+impl Instruction for ThrowRef {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
@@ -0,0 +1,16 @@
+use super::*;
+use crate::objects::instruction::Catch;
+
+#[derive(Debug, Clone)]
+pub struct TryTable {
+    pub block_type: BlockType,
+    pub catches: Vec<Catch>,
+}
+
+impl Instruction for TryTable {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        unimplemented!(
+            "Control instructions are not serialized and can therefore not be deserialized."
+        )
+    }
+}
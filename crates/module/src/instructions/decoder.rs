@@ -11,6 +11,8 @@ pub enum DecodingError {
     TypeMismatch,
     #[error("Instruction storage exhausted")]
     InstructionStorageExhausted,
+    #[error("immediate storage exhausted")]
+    ImmediateStorageExhausted,
     #[error("value type storage exhausted")]
     ValueTypeStorageExhausted,
     #[error("variable storage exhausted")]
@@ -43,7 +45,9 @@ impl InstructionDecoder {
 
     pub fn read_immediate<T: Integer>(&mut self) -> Result<T, DecodingError> {
         let size = std::mem::size_of::<T>();
-        debug_assert!(self.storage.immediate_storage.len() >= size);
+        if self.storage.immediate_storage.len() < size {
+            return Err(DecodingError::ImmediateStorageExhausted);
+        }
         // we can do this because the VecDeque is made contiguous upon completion of the block,
         // so the first slice is the entire buffer. This seems to be faster than simply using
         // the drain return value, likely because the compiler knows we're operating only on the first slice.
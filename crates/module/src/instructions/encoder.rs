@@ -2,7 +2,9 @@ use super::{basic_block::BasicBlockStorage, *};
 use crate::{objects::instruction::ControlInstruction, utils::integer_traits::Integer};
 use instruction_consumer::InstructionConsumer;
 use std::collections::VecDeque;
-use wasm_types::{InstructionType, NumericInstructionCategory, ValType};
+use wasm_types::{
+    AggregateInstructionCategory, InstructionType, NumericInstructionCategory, ValType,
+};
 
 #[derive(Clone)]
 pub struct InstructionEncoder {
@@ -47,6 +49,13 @@ impl InstructionEncoder {
         self.storage.variable_storage.push_back(var);
     }
 
+    fn write_variables(&mut self, vars: &[VariableID]) {
+        self.write_immediate(vars.len() as u32);
+        for var in vars {
+            self.write_variable(*var);
+        }
+    }
+
     fn write_value_type(&mut self, type_: ValType) {
         self.storage.type_storage.push_back(type_);
     }
@@ -142,6 +151,53 @@ impl InstructionConsumer for InstructionEncoder {
         self.finish(ControlInstruction::Return);
     }
 
+    fn write_return_call(&mut self, i: ReturnCall) {
+        self.finish(ControlInstruction::ReturnCall(i.func_idx));
+    }
+
+    fn write_return_call_indirect(&mut self, i: ReturnCallIndirect) {
+        self.finish(ControlInstruction::ReturnCallIndirect(
+            i.type_idx,
+            i.table_idx,
+        ));
+    }
+
+    fn write_call_ref(&mut self, i: CallRef) {
+        self.finish(ControlInstruction::CallRef(i.type_idx));
+    }
+
+    fn write_return_call_ref(&mut self, i: ReturnCallRef) {
+        self.finish(ControlInstruction::ReturnCallRef(i.type_idx));
+    }
+
+    fn write_br_on_null(&mut self, i: BrOnNull) {
+        self.finish(ControlInstruction::BrOnNull(i.label_idx));
+    }
+
+    fn write_br_on_non_null(&mut self, i: BrOnNonNull) {
+        self.finish(ControlInstruction::BrOnNonNull(i.label_idx));
+    }
+
+    fn write_br_on_cast(&mut self, i: BrOnCast) {
+        self.finish(ControlInstruction::BrOnCast(i.label_idx, i.from, i.to));
+    }
+
+    fn write_br_on_cast_fail(&mut self, i: BrOnCastFail) {
+        self.finish(ControlInstruction::BrOnCastFail(i.label_idx, i.from, i.to));
+    }
+
+    fn write_try_table(&mut self, i: TryTable) {
+        self.finish(ControlInstruction::TryTable(i.block_type, i.catches));
+    }
+
+    fn write_throw(&mut self, i: Throw) {
+        self.finish(ControlInstruction::Throw(i.tag_idx));
+    }
+
+    fn write_throw_ref(&mut self) {
+        self.finish(ControlInstruction::ThrowRef);
+    }
+
     fn write_unreachable(&mut self) {
         self.finish(ControlInstruction::Unreachable);
     }
@@ -152,6 +208,7 @@ impl InstructionConsumer for InstructionEncoder {
         )));
         self.write_immediate(i.memarg.align);
         self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
         self.write_variable(i.addr_in);
         self.write_variable(i.value_in);
         self.write_value_type(ValType::Number(i.in_type))
@@ -163,11 +220,99 @@ impl InstructionConsumer for InstructionEncoder {
         )));
         self.write_immediate(i.memarg.align);
         self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
         self.write_variable(i.addr);
         self.write_variable(i.out1);
         self.write_value_type(ValType::Number(i.out1_type))
     }
 
+    fn write_atomic_load(&mut self, i: AtomicLoadInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Load,
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.width);
+        self.write_value_type(ValType::Number(i.out1_type));
+        self.write_variable(i.addr);
+        self.write_variable(i.out1);
+    }
+
+    fn write_atomic_store(&mut self, i: AtomicStoreInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Store,
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.width);
+        self.write_value_type(ValType::Number(i.in_type));
+        self.write_variable(i.addr_in);
+        self.write_variable(i.value_in);
+    }
+
+    fn write_atomic_rmw(&mut self, i: AtomicRmwInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Rmw(i.op.clone()),
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.width);
+        self.write_value_type(ValType::Number(i.r#type));
+        self.write_variable(i.addr);
+        self.write_variable(i.value);
+        self.write_variable(i.out1);
+    }
+
+    fn write_atomic_cmpxchg(&mut self, i: AtomicCmpxchgInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Cmpxchg,
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.width);
+        self.write_value_type(ValType::Number(i.r#type));
+        self.write_variable(i.addr);
+        self.write_variable(i.expected);
+        self.write_variable(i.replacement);
+        self.write_variable(i.out1);
+    }
+
+    fn write_atomic_wait(&mut self, i: AtomicWaitInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Wait,
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_value_type(ValType::Number(i.r#type));
+        self.write_variable(i.addr);
+        self.write_variable(i.expected);
+        self.write_variable(i.timeout);
+        self.write_variable(i.out1);
+    }
+
+    fn write_atomic_notify(&mut self, i: AtomicNotifyInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Notify,
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_variable(i.addr);
+        self.write_variable(i.count);
+        self.write_variable(i.out1);
+    }
+
+    fn write_atomic_fence(&mut self, _: AtomicFenceInstruction) {
+        self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Atomic(
+            AtomicOp::Fence,
+        )));
+    }
+
     fn write_test(&mut self, i: ITestInstruction) {
         self.write_instruction_type(InstructionType::Numeric(NumericInstructionCategory::ITest(
             i.op.clone(),
@@ -181,6 +326,7 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Memory(
             MemoryOp::Size,
         )));
+        self.write_immediate(i.memory_idx);
         self.write_variable(i.out1)
     }
 
@@ -188,6 +334,7 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Memory(
             MemoryOp::Grow,
         )));
+        self.write_immediate(i.memory_idx);
         self.write_variable(i.in1);
         self.write_variable(i.out1);
     }
@@ -196,6 +343,8 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Memory(
             MemoryOp::Copy,
         )));
+        self.write_immediate(i.memory_idx_x);
+        self.write_immediate(i.memory_idx_y);
         self.write_variable(i.n);
         self.write_variable(i.s);
         self.write_variable(i.d);
@@ -205,6 +354,7 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_instruction_type(InstructionType::Memory(MemoryInstructionCategory::Memory(
             MemoryOp::Fill,
         )));
+        self.write_immediate(i.memory_idx);
         self.write_variable(i.n);
         self.write_variable(i.val);
         self.write_variable(i.d);
@@ -215,6 +365,7 @@ impl InstructionConsumer for InstructionEncoder {
             MemoryOp::Init,
         )));
         self.write_immediate(i.data_idx);
+        self.write_immediate(i.memory_idx);
         self.write_variable(i.n);
         self.write_variable(i.s);
         self.write_variable(i.d);
@@ -376,6 +527,184 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_immediate(i.func_idx);
     }
 
+    fn write_reference_as_non_null(&mut self, i: ReferenceAsNonNullInstruction) {
+        self.write_instruction_type(InstructionType::Reference(
+            ReferenceInstructionType::RefAsNonNull,
+        ));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_reference_eq(&mut self, i: ReferenceEqInstruction) {
+        self.write_instruction_type(InstructionType::Reference(ReferenceInstructionType::RefEq));
+        self.write_variable(i.in1);
+        self.write_variable(i.in2);
+        self.write_variable(i.out1);
+    }
+
+    fn write_reference_test(&mut self, i: ReferenceTestInstruction) {
+        self.write_instruction_type(InstructionType::Reference(
+            ReferenceInstructionType::RefTest,
+        ));
+        self.write_variable(i.in1);
+        self.write_value_type(ValType::Reference(i.ref_type));
+        self.write_variable(i.out1);
+    }
+
+    fn write_reference_cast(&mut self, i: ReferenceCastInstruction) {
+        self.write_instruction_type(InstructionType::Reference(
+            ReferenceInstructionType::RefCast,
+        ));
+        self.write_variable(i.in1);
+        self.write_value_type(ValType::Reference(i.ref_type));
+        self.write_variable(i.out1);
+    }
+
+    fn write_reference_i31(&mut self, i: ReferenceI31Instruction) {
+        self.write_instruction_type(InstructionType::Reference(ReferenceInstructionType::RefI31));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_i31_get(&mut self, i: I31GetInstruction) {
+        self.write_instruction_type(InstructionType::Reference(ReferenceInstructionType::I31Get));
+        self.write_variable(i.in1);
+        self.write_immediate(i.signed as u8);
+        self.write_variable(i.out1);
+    }
+
+    fn write_struct_new(&mut self, i: StructNewInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::StructNew,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_variables(&i.fields);
+        self.write_variable(i.out1);
+    }
+
+    fn write_struct_get(&mut self, i: StructGetInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::StructGet,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(i.field_idx);
+        self.write_immediate(i.signed as u8);
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_struct_set(&mut self, i: StructSetInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::StructSet,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(i.field_idx);
+        self.write_variable(i.in1);
+        self.write_variable(i.in2);
+    }
+
+    fn write_array_new(&mut self, i: ArrayNewInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayNew,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(i.init.is_some() as u8);
+        if let Some(init) = i.init {
+            self.write_variable(init);
+        }
+        self.write_variable(i.len);
+        self.write_variable(i.out1);
+    }
+
+    fn write_array_new_fixed(&mut self, i: ArrayNewFixedInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayNewFixed,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_variables(&i.values);
+        self.write_variable(i.out1);
+    }
+
+    fn write_array_new_segment(&mut self, i: ArrayNewSegmentInstruction) {
+        let (category, segment_idx) = match i.segment {
+            ArraySegment::Data(data_idx) => (AggregateInstructionCategory::ArrayNewData, data_idx),
+            ArraySegment::Elem(elem_idx) => (AggregateInstructionCategory::ArrayNewElem, elem_idx),
+        };
+        self.write_instruction_type(InstructionType::Aggregate(category));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(segment_idx);
+        self.write_variable(i.offset);
+        self.write_variable(i.len);
+        self.write_variable(i.out1);
+    }
+
+    fn write_array_get(&mut self, i: ArrayGetInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayGet,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(i.signed as u8);
+        self.write_variable(i.array);
+        self.write_variable(i.idx);
+        self.write_variable(i.out1);
+    }
+
+    fn write_array_set(&mut self, i: ArraySetInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArraySet,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_variable(i.array);
+        self.write_variable(i.idx);
+        self.write_variable(i.value);
+    }
+
+    fn write_array_len(&mut self, i: ArrayLenInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayLen,
+        ));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_array_fill(&mut self, i: ArrayFillInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayFill,
+        ));
+        self.write_immediate(i.type_idx);
+        self.write_variable(i.array);
+        self.write_variable(i.offset);
+        self.write_variable(i.value);
+        self.write_variable(i.len);
+    }
+
+    fn write_array_copy(&mut self, i: ArrayCopyInstruction) {
+        self.write_instruction_type(InstructionType::Aggregate(
+            AggregateInstructionCategory::ArrayCopy,
+        ));
+        self.write_immediate(i.dst_type_idx);
+        self.write_immediate(i.src_type_idx);
+        self.write_variable(i.dst);
+        self.write_variable(i.dst_offset);
+        self.write_variable(i.src);
+        self.write_variable(i.src_offset);
+        self.write_variable(i.len);
+    }
+
+    fn write_array_init_segment(&mut self, i: ArrayInitSegmentInstruction) {
+        let (category, segment_idx) = match i.segment {
+            ArraySegment::Data(data_idx) => (AggregateInstructionCategory::ArrayInitData, data_idx),
+            ArraySegment::Elem(elem_idx) => (AggregateInstructionCategory::ArrayInitElem, elem_idx),
+        };
+        self.write_instruction_type(InstructionType::Aggregate(category));
+        self.write_immediate(i.type_idx);
+        self.write_immediate(segment_idx);
+        self.write_variable(i.array);
+        self.write_variable(i.dst_offset);
+        self.write_variable(i.src_offset);
+        self.write_variable(i.len);
+    }
+
     fn write_select(&mut self, i: SelectInstruction) {
         self.write_instruction_type(InstructionType::Parametric(
             ParametricInstructionType::Select,
@@ -481,6 +810,151 @@ impl InstructionConsumer for InstructionEncoder {
         self.write_variable(i.out1);
     }
 
+    fn write_v128_const(&mut self, i: VConstInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Constant));
+        self.write_immediate(u128::from_le_bytes(i.imm));
+        self.write_variable(i.out1);
+    }
+
+    fn write_v128_load(&mut self, i: VLoadInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Load(
+            i.operation.clone(),
+        )));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_variable(i.addr);
+        self.write_variable(i.out1);
+    }
+
+    fn write_v128_store(&mut self, i: VStoreInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Store));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_variable(i.addr_in);
+        self.write_variable(i.value_in);
+    }
+
+    fn write_v128_load_lane(&mut self, i: VLoadLaneInstruction) {
+        self.write_instruction_type(InstructionType::Vector(
+            VectorInstructionCategory::LoadLane(i.shape),
+        ));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.lane);
+        self.write_variable(i.addr);
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_v128_store_lane(&mut self, i: VStoreLaneInstruction) {
+        self.write_instruction_type(InstructionType::Vector(
+            VectorInstructionCategory::StoreLane(i.shape),
+        ));
+        self.write_immediate(i.memarg.align);
+        self.write_immediate(i.memarg.offset);
+        self.write_immediate(i.memarg.memory);
+        self.write_immediate(i.lane);
+        self.write_variable(i.addr_in);
+        self.write_variable(i.value_in);
+    }
+
+    fn write_shuffle(&mut self, i: VShuffleInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Shuffle));
+        self.write_immediate(u128::from_le_bytes(i.lanes));
+        self.write_variable(i.lhs);
+        self.write_variable(i.rhs);
+        self.write_variable(i.out1);
+    }
+
+    fn write_splat(&mut self, i: VSplatInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Splat(
+            i.shape,
+        )));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_extract_lane(&mut self, i: VExtractLaneInstruction) {
+        self.write_instruction_type(InstructionType::Vector(match i.signed {
+            None => VectorInstructionCategory::ExtractLane(i.shape),
+            Some(true) => VectorInstructionCategory::ExtractLaneS(i.shape),
+            Some(false) => VectorInstructionCategory::ExtractLaneU(i.shape),
+        }));
+        self.write_immediate(i.lane);
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_replace_lane(&mut self, i: VReplaceLaneInstruction) {
+        self.write_instruction_type(InstructionType::Vector(
+            VectorInstructionCategory::ReplaceLane(i.shape),
+        ));
+        self.write_immediate(i.lane);
+        self.write_variable(i.in1);
+        self.write_variable(i.value_in);
+        self.write_variable(i.out1);
+    }
+
+    fn write_vunary(&mut self, i: VUnaryInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Unary(
+            i.shape,
+            i.op.clone(),
+        )));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
+    fn write_vbinary(&mut self, i: VBinaryInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Binary(
+            i.shape,
+            i.op.clone(),
+        )));
+        self.write_variable(i.lhs);
+        self.write_variable(i.rhs);
+        self.write_variable(i.out1);
+    }
+
+    fn write_bitselect(&mut self, i: VBitselectInstruction) {
+        self.write_instruction_type(InstructionType::Vector(
+            VectorInstructionCategory::Bitselect,
+        ));
+        self.write_variable(i.in1);
+        self.write_variable(i.in2);
+        self.write_variable(i.mask);
+        self.write_variable(i.out1);
+    }
+
+    fn write_vrelational(&mut self, i: VRelationalInstruction) {
+        self.write_instruction_type(InstructionType::Vector(
+            VectorInstructionCategory::Relational(i.shape, i.op.clone()),
+        ));
+        self.write_variable(i.lhs);
+        self.write_variable(i.rhs);
+        self.write_variable(i.out1);
+    }
+
+    fn write_vshift(&mut self, i: VShiftInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Shift(
+            i.shape,
+            i.op.clone(),
+        )));
+        self.write_variable(i.in1);
+        self.write_variable(i.amount);
+        self.write_variable(i.out1);
+    }
+
+    fn write_vtest(&mut self, i: VTestInstruction) {
+        self.write_instruction_type(InstructionType::Vector(VectorInstructionCategory::Test(
+            i.shape,
+            i.op.clone(),
+        )));
+        self.write_variable(i.in1);
+        self.write_variable(i.out1);
+    }
+
     fn finish(&mut self, terminator: ControlInstruction) {
         self.storage.terminator = terminator;
         self.storage.immediate_storage.make_contiguous();
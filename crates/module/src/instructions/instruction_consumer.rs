@@ -17,6 +17,17 @@ pub trait InstructionConsumer {
     fn write_loop(&mut self, i: Loop);
     fn write_end(&mut self);
     fn write_return(&mut self);
+    fn write_return_call(&mut self, i: ReturnCall);
+    fn write_return_call_indirect(&mut self, i: ReturnCallIndirect);
+    fn write_call_ref(&mut self, i: CallRef);
+    fn write_return_call_ref(&mut self, i: ReturnCallRef);
+    fn write_br_on_null(&mut self, i: BrOnNull);
+    fn write_br_on_non_null(&mut self, i: BrOnNonNull);
+    fn write_br_on_cast(&mut self, i: BrOnCast);
+    fn write_br_on_cast_fail(&mut self, i: BrOnCastFail);
+    fn write_try_table(&mut self, i: TryTable);
+    fn write_throw(&mut self, i: Throw);
+    fn write_throw_ref(&mut self);
     fn write_unreachable(&mut self);
     fn write_store(&mut self, i: StoreInstruction);
     fn write_load(&mut self, i: LoadInstruction);
@@ -42,6 +53,24 @@ pub trait InstructionConsumer {
     fn write_reference_is_null(&mut self, i: ReferenceIsNullInstruction);
     fn write_reference_null(&mut self, i: ReferenceNullInstruction);
     fn write_reference_function(&mut self, i: ReferenceFunctionInstruction);
+    fn write_reference_as_non_null(&mut self, i: ReferenceAsNonNullInstruction);
+    fn write_reference_eq(&mut self, i: ReferenceEqInstruction);
+    fn write_reference_test(&mut self, i: ReferenceTestInstruction);
+    fn write_reference_cast(&mut self, i: ReferenceCastInstruction);
+    fn write_reference_i31(&mut self, i: ReferenceI31Instruction);
+    fn write_i31_get(&mut self, i: I31GetInstruction);
+    fn write_struct_new(&mut self, i: StructNewInstruction);
+    fn write_struct_get(&mut self, i: StructGetInstruction);
+    fn write_struct_set(&mut self, i: StructSetInstruction);
+    fn write_array_new(&mut self, i: ArrayNewInstruction);
+    fn write_array_new_fixed(&mut self, i: ArrayNewFixedInstruction);
+    fn write_array_new_segment(&mut self, i: ArrayNewSegmentInstruction);
+    fn write_array_get(&mut self, i: ArrayGetInstruction);
+    fn write_array_set(&mut self, i: ArraySetInstruction);
+    fn write_array_len(&mut self, i: ArrayLenInstruction);
+    fn write_array_fill(&mut self, i: ArrayFillInstruction);
+    fn write_array_copy(&mut self, i: ArrayCopyInstruction);
+    fn write_array_init_segment(&mut self, i: ArrayInitSegmentInstruction);
     fn write_select(&mut self, i: SelectInstruction);
     fn write_table_set(&mut self, i: TableSetInstruction);
     fn write_table_get(&mut self, i: TableGetInstruction);
@@ -56,6 +85,28 @@ pub trait InstructionConsumer {
     fn write_local_set(&mut self, i: LocalSetInstruction);
     fn write_global_set(&mut self, i: GlobalSetInstruction);
     fn write_local_tee(&mut self, i: LocalTeeInstruction);
+    fn write_v128_const(&mut self, i: VConstInstruction);
+    fn write_v128_load(&mut self, i: VLoadInstruction);
+    fn write_v128_store(&mut self, i: VStoreInstruction);
+    fn write_v128_load_lane(&mut self, i: VLoadLaneInstruction);
+    fn write_v128_store_lane(&mut self, i: VStoreLaneInstruction);
+    fn write_shuffle(&mut self, i: VShuffleInstruction);
+    fn write_splat(&mut self, i: VSplatInstruction);
+    fn write_extract_lane(&mut self, i: VExtractLaneInstruction);
+    fn write_replace_lane(&mut self, i: VReplaceLaneInstruction);
+    fn write_vunary(&mut self, i: VUnaryInstruction);
+    fn write_vbinary(&mut self, i: VBinaryInstruction);
+    fn write_bitselect(&mut self, i: VBitselectInstruction);
+    fn write_vrelational(&mut self, i: VRelationalInstruction);
+    fn write_vshift(&mut self, i: VShiftInstruction);
+    fn write_vtest(&mut self, i: VTestInstruction);
+    fn write_atomic_load(&mut self, i: AtomicLoadInstruction);
+    fn write_atomic_store(&mut self, i: AtomicStoreInstruction);
+    fn write_atomic_rmw(&mut self, i: AtomicRmwInstruction);
+    fn write_atomic_cmpxchg(&mut self, i: AtomicCmpxchgInstruction);
+    fn write_atomic_wait(&mut self, i: AtomicWaitInstruction);
+    fn write_atomic_notify(&mut self, i: AtomicNotifyInstruction);
+    fn write_atomic_fence(&mut self, i: AtomicFenceInstruction);
     fn finish(&mut self, terminator: ControlInstruction);
     fn is_finished(&self) -> bool;
     fn peek_terminator(&self) -> &ControlInstruction;
@@ -0,0 +1,437 @@
+use super::*;
+use crate::{basic_block::BasicBlockStorage, InstructionConsumer, InstructionEncoder};
+use smallvec::{smallvec, SmallVec};
+
+macro_rules! ir_instructions {
+    ($($variant:ident($instruction:ty) => $write:ident,)*) => {
+        /// An instruction decoded from the storage of a basic block, so it can be inspected and rewritten.
+        #[derive(Debug, Clone)]
+        pub enum IRInstruction {
+            $($variant($instruction),)*
+        }
+
+        impl IRInstruction {
+            /// Appends the instruction to the storage of `encoder`.
+            pub fn encode(self, encoder: &mut InstructionEncoder) {
+                match self {
+                    $(IRInstruction::$variant(i) => encoder.$write(i),)*
+                }
+            }
+        }
+
+        impl Display for IRInstruction {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                match self {
+                    $(IRInstruction::$variant(i) => i.fmt(f),)*
+                }
+            }
+        }
+    };
+}
+
+ir_instructions! {
+    Load(LoadInstruction) => write_load,
+    Store(StoreInstruction) => write_store,
+    MemoryCopy(MemoryCopyInstruction) => write_memory_copy,
+    MemorySize(MemorySizeInstruction) => write_memory_size,
+    DataDrop(DataDropInstruction) => write_data_drop,
+    MemoryFill(MemoryFillInstruction) => write_memory_fill,
+    MemoryGrow(MemoryGrowInstruction) => write_memory_grow,
+    MemoryInit(MemoryInitInstruction) => write_memory_init,
+    AtomicLoad(AtomicLoadInstruction) => write_atomic_load,
+    AtomicStore(AtomicStoreInstruction) => write_atomic_store,
+    AtomicRmw(AtomicRmwInstruction) => write_atomic_rmw,
+    AtomicCmpxchg(AtomicCmpxchgInstruction) => write_atomic_cmpxchg,
+    AtomicWait(AtomicWaitInstruction) => write_atomic_wait,
+    AtomicNotify(AtomicNotifyInstruction) => write_atomic_notify,
+    AtomicFence(AtomicFenceInstruction) => write_atomic_fence,
+
+    Constant(Constant) => write_const,
+    IUnary(IUnaryInstruction) => write_iunary,
+    FUnary(FUnaryInstruction) => write_funary,
+    IBinary(IBinaryInstruction) => write_ibinary,
+    FBinary(FBinaryInstruction) => write_fbinary,
+    IRelational(IRelationalInstruction) => write_irelational,
+    FRelational(FRelationalInstruction) => write_frelational,
+    Convert(ConvertInstruction) => write_convert,
+    Demote(DemoteInstruction) => write_demote,
+    ExtendBits(ExtendBitsInstruction) => write_extend_bits,
+    ExtendType(ExtendTypeInstruction) => write_extend_type,
+    Promote(PromoteInstruction) => write_promote,
+    Reinterpret(ReinterpretInstruction) => write_reinterpret,
+    Trunc(TruncInstruction) => write_trunc,
+    TruncSaturation(TruncSaturationInstruction) => write_trunc_saturation,
+    Wrap(WrapInstruction) => write_wrap,
+    ITest(ITestInstruction) => write_test,
+
+    Select(SelectInstruction) => write_select,
+
+    TableCopy(TableCopyInstruction) => write_table_copy,
+    TableFill(TableFillInstruction) => write_table_fill,
+    TableGet(TableGetInstruction) => write_table_get,
+    TableGrow(TableGrowInstruction) => write_table_grow,
+    TableInit(TableInitInstruction) => write_table_init,
+    TableSet(TableSetInstruction) => write_table_set,
+    TableSize(TableSizeInstruction) => write_table_size,
+    ElemDrop(ElemDropInstruction) => write_elem_drop,
+
+    ReferenceFunction(ReferenceFunctionInstruction) => write_reference_function,
+    ReferenceNull(ReferenceNullInstruction) => write_reference_null,
+    ReferenceIsNull(ReferenceIsNullInstruction) => write_reference_is_null,
+    ReferenceAsNonNull(ReferenceAsNonNullInstruction) => write_reference_as_non_null,
+    ReferenceEq(ReferenceEqInstruction) => write_reference_eq,
+    ReferenceTest(ReferenceTestInstruction) => write_reference_test,
+    ReferenceCast(ReferenceCastInstruction) => write_reference_cast,
+    ReferenceI31(ReferenceI31Instruction) => write_reference_i31,
+    I31Get(I31GetInstruction) => write_i31_get,
+
+    StructNew(StructNewInstruction) => write_struct_new,
+    StructGet(StructGetInstruction) => write_struct_get,
+    StructSet(StructSetInstruction) => write_struct_set,
+    ArrayNew(ArrayNewInstruction) => write_array_new,
+    ArrayNewFixed(ArrayNewFixedInstruction) => write_array_new_fixed,
+    ArrayNewSegment(ArrayNewSegmentInstruction) => write_array_new_segment,
+    ArrayGet(ArrayGetInstruction) => write_array_get,
+    ArraySet(ArraySetInstruction) => write_array_set,
+    ArrayLen(ArrayLenInstruction) => write_array_len,
+    ArrayFill(ArrayFillInstruction) => write_array_fill,
+    ArrayCopy(ArrayCopyInstruction) => write_array_copy,
+    ArrayInitSegment(ArrayInitSegmentInstruction) => write_array_init_segment,
+
+    GlobalGet(GlobalGetInstruction) => write_global_get,
+    GlobalSet(GlobalSetInstruction) => write_global_set,
+    LocalGet(LocalGetInstruction) => write_local_get,
+    LocalSet(LocalSetInstruction) => write_local_set,
+    LocalTee(LocalTeeInstruction) => write_local_tee,
+
+    VConst(VConstInstruction) => write_v128_const,
+    VLoad(VLoadInstruction) => write_v128_load,
+    VStore(VStoreInstruction) => write_v128_store,
+    VLoadLane(VLoadLaneInstruction) => write_v128_load_lane,
+    VStoreLane(VStoreLaneInstruction) => write_v128_store_lane,
+    VShuffle(VShuffleInstruction) => write_shuffle,
+    VSplat(VSplatInstruction) => write_splat,
+    VExtractLane(VExtractLaneInstruction) => write_extract_lane,
+    VReplaceLane(VReplaceLaneInstruction) => write_replace_lane,
+    VUnary(VUnaryInstruction) => write_vunary,
+    VBinary(VBinaryInstruction) => write_vbinary,
+    VBitselect(VBitselectInstruction) => write_bitselect,
+    VRelational(VRelationalInstruction) => write_vrelational,
+    VShift(VShiftInstruction) => write_vshift,
+    VTest(VTestInstruction) => write_vtest,
+}
+
+impl IRInstruction {
+    /// Decodes the instruction of type `instr_t` whose type has just been read from `decoder`.
+    #[rustfmt::skip]
+    pub fn decode(decoder: &mut InstructionDecoder, instr_t: InstructionType) -> Result<Self, DecodingError> {
+        use IRInstruction as I;
+        let instruction = match instr_t.clone() {
+            InstructionType::Memory(MemoryInstructionCategory::Load(_)) => I::Load(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Store(_)) => I::Store(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Copy)) => I::MemoryCopy(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Size)) => I::MemorySize(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Drop)) => I::DataDrop(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Fill)) => I::MemoryFill(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Grow)) => I::MemoryGrow(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Memory(MemoryOp::Init)) => I::MemoryInit(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Load)) => I::AtomicLoad(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Store)) => I::AtomicStore(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Rmw(_))) => I::AtomicRmw(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Cmpxchg)) => I::AtomicCmpxchg(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Wait)) => I::AtomicWait(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Notify)) => I::AtomicNotify(decoder.read(instr_t)?),
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(AtomicOp::Fence)) => I::AtomicFence(decoder.read(instr_t)?),
+
+            InstructionType::Numeric(NumericInstructionCategory::Constant) => I::Constant(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::IUnary(_)) => I::IUnary(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::FUnary(_)) => I::FUnary(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::IBinary(_)) => I::IBinary(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::FBinary(_)) => I::FBinary(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::IRelational(_)) => I::IRelational(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::FRelational(_)) => I::FRelational(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Convert)) => I::Convert(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Demote)) => I::Demote(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::ExtendBits)) => I::ExtendBits(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::ExtendType)) => I::ExtendType(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Promote)) => I::Promote(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Reinterpret)) => I::Reinterpret(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Trunc)) => I::Trunc(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::TruncSat)) => I::TruncSaturation(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::Conversion(ConversionOp::Wrap)) => I::Wrap(decoder.read(instr_t)?),
+            InstructionType::Numeric(NumericInstructionCategory::ITest(_)) => I::ITest(decoder.read(instr_t)?),
+
+            InstructionType::Parametric(ParametricInstructionType::Select) => I::Select(decoder.read(instr_t)?),
+
+            InstructionType::Table(TableInstructionCategory::Copy) => I::TableCopy(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Fill) => I::TableFill(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Get) => I::TableGet(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Grow) => I::TableGrow(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Init) => I::TableInit(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Set) => I::TableSet(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Size) => I::TableSize(decoder.read(instr_t)?),
+            InstructionType::Table(TableInstructionCategory::Drop) => I::ElemDrop(decoder.read(instr_t)?),
+
+            InstructionType::Reference(ReferenceInstructionType::RefFunc) => I::ReferenceFunction(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefNull) => I::ReferenceNull(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefIsNull) => I::ReferenceIsNull(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefAsNonNull) => I::ReferenceAsNonNull(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefEq) => I::ReferenceEq(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefTest) => I::ReferenceTest(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefCast) => I::ReferenceCast(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::RefI31) => I::ReferenceI31(decoder.read(instr_t)?),
+            InstructionType::Reference(ReferenceInstructionType::I31Get) => I::I31Get(decoder.read(instr_t)?),
+
+            InstructionType::Aggregate(AggregateInstructionCategory::StructNew) => I::StructNew(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::StructGet) => I::StructGet(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::StructSet) => I::StructSet(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayNew) => I::ArrayNew(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayNewFixed) => I::ArrayNewFixed(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayNewData | AggregateInstructionCategory::ArrayNewElem) => I::ArrayNewSegment(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayGet) => I::ArrayGet(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArraySet) => I::ArraySet(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayLen) => I::ArrayLen(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayFill) => I::ArrayFill(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayCopy) => I::ArrayCopy(decoder.read(instr_t)?),
+            InstructionType::Aggregate(AggregateInstructionCategory::ArrayInitData | AggregateInstructionCategory::ArrayInitElem) => I::ArrayInitSegment(decoder.read(instr_t)?),
+
+            InstructionType::Variable(VariableInstructionType::GlobalGet) => I::GlobalGet(decoder.read(instr_t)?),
+            InstructionType::Variable(VariableInstructionType::GlobalSet) => I::GlobalSet(decoder.read(instr_t)?),
+            InstructionType::Variable(VariableInstructionType::LocalGet) => I::LocalGet(decoder.read(instr_t)?),
+            InstructionType::Variable(VariableInstructionType::LocalSet) => I::LocalSet(decoder.read(instr_t)?),
+            InstructionType::Variable(VariableInstructionType::LocalTee) => I::LocalTee(decoder.read(instr_t)?),
+
+            InstructionType::Vector(VectorInstructionCategory::Constant) => I::VConst(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Load(_)) => I::VLoad(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Store) => I::VStore(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::LoadLane(_)) => I::VLoadLane(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::StoreLane(_)) => I::VStoreLane(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Shuffle) => I::VShuffle(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Splat(_)) => I::VSplat(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::ExtractLane(_))
+            | InstructionType::Vector(VectorInstructionCategory::ExtractLaneS(_))
+            | InstructionType::Vector(VectorInstructionCategory::ExtractLaneU(_)) => I::VExtractLane(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::ReplaceLane(_)) => I::VReplaceLane(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Unary(..)) => I::VUnary(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Binary(..)) => I::VBinary(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Bitselect) => I::VBitselect(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Relational(..)) => I::VRelational(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Shift(..)) => I::VShift(decoder.read(instr_t)?),
+            InstructionType::Vector(VectorInstructionCategory::Test(..)) => I::VTest(decoder.read(instr_t)?),
+
+            // drops are not encoded, phi nodes are stored with the block and control instructions are converted
+            // into BasicBlockGlue
+            InstructionType::Parametric(ParametricInstructionType::Drop)
+            | InstructionType::Meta(MetaInstructionType::PhiNode)
+            | InstructionType::Control(_) => {
+                return Err(DecodingError::DecodingError(format!("unexpected instruction {instr_t:?}")))
+            }
+        };
+        Ok(instruction)
+    }
+
+    /// Decodes all instructions of a basic block.
+    pub fn decode_all(storage: BasicBlockStorage) -> Result<Vec<Self>, DecodingError> {
+        let mut decoder = InstructionDecoder::new(storage);
+        let mut instructions = Vec::new();
+        while let Ok(instr_t) = decoder.read_instruction_type() {
+            instructions.push(Self::decode(&mut decoder, instr_t)?);
+        }
+        Ok(instructions)
+    }
+
+    /// The variable defined by the instruction.
+    #[rustfmt::skip]
+    pub fn def(&self) -> Option<VariableID> {
+        use IRInstruction as I;
+        let out1 = match self {
+            I::Load(i) => i.out1, I::MemorySize(i) => i.out1, I::MemoryGrow(i) => i.out1,
+            I::AtomicLoad(i) => i.out1, I::AtomicRmw(i) => i.out1, I::AtomicCmpxchg(i) => i.out1,
+            I::AtomicWait(i) => i.out1, I::AtomicNotify(i) => i.out1,
+
+            I::Constant(i) => i.out1, I::IUnary(i) => i.out1, I::FUnary(i) => i.out1, I::IBinary(i) => i.out1,
+            I::FBinary(i) => i.out1, I::IRelational(i) => i.out1, I::FRelational(i) => i.out1,
+            I::Convert(i) => i.out1, I::Demote(i) => i.out1, I::ExtendBits(i) => i.out1, I::ExtendType(i) => i.out1,
+            I::Promote(i) => i.out1, I::Reinterpret(i) => i.out1, I::Trunc(i) => i.out1,
+            I::TruncSaturation(i) => i.out1, I::Wrap(i) => i.out1, I::ITest(i) => i.out1,
+
+            I::Select(i) => i.out1,
+
+            I::TableGet(i) => i.out1, I::TableGrow(i) => i.out1, I::TableSize(i) => i.out1,
+
+            I::ReferenceFunction(i) => i.out1, I::ReferenceNull(i) => i.out1, I::ReferenceIsNull(i) => i.out1,
+            I::ReferenceAsNonNull(i) => i.out1, I::ReferenceEq(i) => i.out1, I::ReferenceTest(i) => i.out1,
+            I::ReferenceCast(i) => i.out1, I::ReferenceI31(i) => i.out1, I::I31Get(i) => i.out1,
+
+            I::StructNew(i) => i.out1, I::StructGet(i) => i.out1, I::ArrayNew(i) => i.out1,
+            I::ArrayNewFixed(i) => i.out1, I::ArrayNewSegment(i) => i.out1, I::ArrayGet(i) => i.out1,
+            I::ArrayLen(i) => i.out1,
+
+            I::GlobalGet(i) => i.out1, I::LocalGet(i) => i.out1, I::LocalTee(i) => i.out1,
+
+            I::VConst(i) => i.out1, I::VLoad(i) => i.out1, I::VLoadLane(i) => i.out1, I::VShuffle(i) => i.out1,
+            I::VSplat(i) => i.out1, I::VExtractLane(i) => i.out1, I::VReplaceLane(i) => i.out1,
+            I::VUnary(i) => i.out1, I::VBinary(i) => i.out1, I::VBitselect(i) => i.out1,
+            I::VRelational(i) => i.out1, I::VShift(i) => i.out1, I::VTest(i) => i.out1,
+
+            I::Store(_) | I::MemoryCopy(_) | I::DataDrop(_) | I::MemoryFill(_) | I::MemoryInit(_)
+            | I::AtomicStore(_) | I::AtomicFence(_) | I::TableCopy(_) | I::TableFill(_) | I::TableInit(_)
+            | I::TableSet(_) | I::ElemDrop(_) | I::StructSet(_) | I::ArraySet(_) | I::ArrayFill(_)
+            | I::ArrayCopy(_) | I::ArrayInitSegment(_) | I::GlobalSet(_) | I::LocalSet(_) | I::VStore(_)
+            | I::VStoreLane(_) => return None,
+        };
+        Some(out1)
+    }
+
+    /// Variables read by the instruction.
+    pub fn uses(&self) -> SmallVec<[VariableID; 4]> {
+        self.clone().uses_mut().into_iter().map(|var| *var).collect()
+    }
+
+    /// Mutable references to the variables read by the instruction.
+    #[rustfmt::skip]
+    pub fn uses_mut(&mut self) -> SmallVec<[&mut VariableID; 4]> {
+        use IRInstruction as I;
+        match self {
+            I::Load(i) => smallvec![&mut i.addr],
+            I::Store(i) => smallvec![&mut i.addr_in, &mut i.value_in],
+            I::MemoryCopy(i) => smallvec![&mut i.n, &mut i.s, &mut i.d],
+            I::MemorySize(_) | I::DataDrop(_) | I::AtomicFence(_) => smallvec![],
+            I::MemoryFill(i) => smallvec![&mut i.n, &mut i.val, &mut i.d],
+            I::MemoryGrow(i) => smallvec![&mut i.in1],
+            I::MemoryInit(i) => smallvec![&mut i.n, &mut i.s, &mut i.d],
+            I::AtomicLoad(i) => smallvec![&mut i.addr],
+            I::AtomicStore(i) => smallvec![&mut i.addr_in, &mut i.value_in],
+            I::AtomicRmw(i) => smallvec![&mut i.addr, &mut i.value],
+            I::AtomicCmpxchg(i) => smallvec![&mut i.addr, &mut i.expected, &mut i.replacement],
+            I::AtomicWait(i) => smallvec![&mut i.addr, &mut i.expected, &mut i.timeout],
+            I::AtomicNotify(i) => smallvec![&mut i.addr, &mut i.count],
+
+            I::Constant(_) => smallvec![],
+            I::IUnary(i) => smallvec![&mut i.in1],
+            I::FUnary(i) => smallvec![&mut i.in1],
+            I::IBinary(i) => smallvec![&mut i.lhs, &mut i.rhs],
+            I::FBinary(i) => smallvec![&mut i.lhs, &mut i.rhs],
+            I::IRelational(i) => smallvec![&mut i.in1, &mut i.in2],
+            I::FRelational(i) => smallvec![&mut i.in1, &mut i.in2],
+            I::Convert(i) => smallvec![&mut i.in1],
+            I::Demote(i) => smallvec![&mut i.in1],
+            I::ExtendBits(i) => smallvec![&mut i.in1],
+            I::ExtendType(i) => smallvec![&mut i.in1],
+            I::Promote(i) => smallvec![&mut i.in1],
+            I::Reinterpret(i) => smallvec![&mut i.in1],
+            I::Trunc(i) => smallvec![&mut i.in1],
+            I::TruncSaturation(i) => smallvec![&mut i.in1],
+            I::Wrap(i) => smallvec![&mut i.in1],
+            I::ITest(i) => smallvec![&mut i.in1],
+
+            I::Select(i) => {
+                let [in1, in2] = &mut i.input_vals;
+                smallvec![in1, in2, &mut i.select_val]
+            }
+
+            I::TableCopy(i) => smallvec![&mut i.n, &mut i.s, &mut i.d],
+            I::TableFill(i) => smallvec![&mut i.i, &mut i.n, &mut i.ref_value],
+            I::TableGet(i) => smallvec![&mut i.idx],
+            I::TableGrow(i) => smallvec![&mut i.size, &mut i.value_to_fill],
+            I::TableInit(i) => smallvec![&mut i.n, &mut i.s, &mut i.d],
+            I::TableSet(i) => smallvec![&mut i.in1, &mut i.idx],
+            I::TableSize(_) | I::ElemDrop(_) => smallvec![],
+
+            I::ReferenceFunction(_) | I::ReferenceNull(_) => smallvec![],
+            I::ReferenceIsNull(i) => smallvec![&mut i.in1],
+            I::ReferenceAsNonNull(i) => smallvec![&mut i.in1],
+            I::ReferenceEq(i) => smallvec![&mut i.in1, &mut i.in2],
+            I::ReferenceTest(i) => smallvec![&mut i.in1],
+            I::ReferenceCast(i) => smallvec![&mut i.in1],
+            I::ReferenceI31(i) => smallvec![&mut i.in1],
+            I::I31Get(i) => smallvec![&mut i.in1],
+
+            I::StructNew(i) => i.fields.iter_mut().collect(),
+            I::StructGet(i) => smallvec![&mut i.in1],
+            I::StructSet(i) => smallvec![&mut i.in1, &mut i.in2],
+            I::ArrayNew(i) => i.init.iter_mut().chain([&mut i.len]).collect(),
+            I::ArrayNewFixed(i) => i.values.iter_mut().collect(),
+            I::ArrayNewSegment(i) => smallvec![&mut i.offset, &mut i.len],
+            I::ArrayGet(i) => smallvec![&mut i.array, &mut i.idx],
+            I::ArraySet(i) => smallvec![&mut i.array, &mut i.idx, &mut i.value],
+            I::ArrayLen(i) => smallvec![&mut i.in1],
+            I::ArrayFill(i) => smallvec![&mut i.array, &mut i.offset, &mut i.value, &mut i.len],
+            I::ArrayCopy(i) => smallvec![&mut i.dst, &mut i.dst_offset, &mut i.src, &mut i.src_offset, &mut i.len],
+            I::ArrayInitSegment(i) => smallvec![&mut i.array, &mut i.dst_offset, &mut i.src_offset, &mut i.len],
+
+            I::GlobalGet(_) | I::LocalGet(_) => smallvec![],
+            I::GlobalSet(i) => smallvec![&mut i.in1],
+            I::LocalSet(i) => smallvec![&mut i.in1],
+            I::LocalTee(i) => smallvec![&mut i.in1],
+
+            I::VConst(_) => smallvec![],
+            I::VLoad(i) => smallvec![&mut i.addr],
+            I::VStore(i) => smallvec![&mut i.addr_in, &mut i.value_in],
+            I::VLoadLane(i) => smallvec![&mut i.addr, &mut i.in1],
+            I::VStoreLane(i) => smallvec![&mut i.addr_in, &mut i.value_in],
+            I::VShuffle(i) => smallvec![&mut i.lhs, &mut i.rhs],
+            I::VSplat(i) => smallvec![&mut i.in1],
+            I::VExtractLane(i) => smallvec![&mut i.in1],
+            I::VReplaceLane(i) => smallvec![&mut i.in1, &mut i.value_in],
+            I::VUnary(i) => smallvec![&mut i.in1],
+            I::VBinary(i) => smallvec![&mut i.lhs, &mut i.rhs],
+            I::VBitselect(i) => smallvec![&mut i.in1, &mut i.in2, &mut i.mask],
+            I::VRelational(i) => smallvec![&mut i.lhs, &mut i.rhs],
+            I::VShift(i) => smallvec![&mut i.in1, &mut i.amount],
+            I::VTest(i) => smallvec![&mut i.in1],
+        }
+    }
+
+    /// Whether the instruction can be removed when its result is unused, i.e. it can neither trap nor
+    /// change any state observable by the rest of the program.
+    pub fn is_pure(&self) -> bool {
+        use IRInstruction as I;
+        match self {
+            I::IBinary(i) => !matches!(
+                i.op,
+                IBinaryOp::DivS | IBinaryOp::DivU | IBinaryOp::RemS | IBinaryOp::RemU
+            ),
+            // float to integer truncation traps on NaN and values out of range
+            I::Trunc(_) => false,
+            I::Constant(_)
+            | I::IUnary(_)
+            | I::FUnary(_)
+            | I::FBinary(_)
+            | I::IRelational(_)
+            | I::FRelational(_)
+            | I::Convert(_)
+            | I::Demote(_)
+            | I::ExtendBits(_)
+            | I::ExtendType(_)
+            | I::Promote(_)
+            | I::Reinterpret(_)
+            | I::TruncSaturation(_)
+            | I::Wrap(_)
+            | I::ITest(_)
+            | I::Select(_)
+            | I::MemorySize(_)
+            | I::TableSize(_)
+            | I::ReferenceFunction(_)
+            | I::ReferenceNull(_)
+            | I::ReferenceIsNull(_)
+            | I::ReferenceEq(_)
+            | I::ReferenceTest(_)
+            | I::ReferenceI31(_)
+            | I::GlobalGet(_)
+            | I::LocalGet(_)
+            | I::VConst(_)
+            | I::VShuffle(_)
+            | I::VSplat(_)
+            | I::VExtractLane(_)
+            | I::VReplaceLane(_)
+            | I::VUnary(_)
+            | I::VBinary(_)
+            | I::VBitselect(_)
+            | I::VRelational(_)
+            | I::VShift(_)
+            | I::VTest(_) => true,
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,366 @@
+use super::*;
+
+macro_rules! extract_atomic_op {
+    ($t:expr) => {
+        match $t {
+            InstructionType::Memory(MemoryInstructionCategory::Atomic(op)) => op,
+            _ => return Err(DecodingError::TypeMismatch),
+        }
+    };
+}
+
+/// Bit width of narrow atomic accesses (e.g. `8` for `i32.atomic.load8_u`) or an empty string for full width ones.
+fn narrow_bits(t: NumType, width: u8) -> String {
+    let full_width = match t {
+        NumType::I32 | NumType::F32 => 4,
+        NumType::I64 | NumType::F64 => 8,
+    };
+    if width == full_width {
+        String::new()
+    } else {
+        (width as u32 * 8).to_string()
+    }
+}
+
+/// Zero-extension suffix of narrow atomic accesses.
+fn unsigned_suffix(t: NumType, width: u8) -> &'static str {
+    if narrow_bits(t, width).is_empty() {
+        ""
+    } else {
+        "_u"
+    }
+}
+
+/// `iNN.atomic.load{8,16,32}_u`: atomically loads `width` bytes and zero-extends them to `out1_type`.
+#[derive(Debug, Clone)]
+pub struct AtomicLoadInstruction {
+    pub memarg: MemArg,
+    /// access width in bytes
+    pub width: u8,
+    pub out1_type: NumType,
+    pub addr: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for AtomicLoadInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let width = i.read_immediate()?;
+        let out1_type = extract_numtype!(i.read_value_type()?);
+        let addr = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(AtomicLoadInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            width,
+            out1_type,
+            addr,
+            out1,
+        })
+    }
+}
+
+impl Display for AtomicLoadInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: {} = {}.atomic.load{}{} %{} (align: {}, offset: {})",
+            self.out1,
+            self.out1_type,
+            self.out1_type,
+            narrow_bits(self.out1_type, self.width),
+            unsigned_suffix(self.out1_type, self.width),
+            self.addr,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `iNN.atomic.store{8,16,32}`: atomically stores the lowest `width` bytes of `value_in`.
+#[derive(Debug, Clone)]
+pub struct AtomicStoreInstruction {
+    pub memarg: MemArg,
+    /// access width in bytes
+    pub width: u8,
+    pub in_type: NumType,
+    pub addr_in: VariableID,
+    pub value_in: VariableID,
+}
+
+impl Instruction for AtomicStoreInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let width = i.read_immediate()?;
+        let in_type = extract_numtype!(i.read_value_type()?);
+        let addr_in = i.read_variable()?;
+        let value_in = i.read_variable()?;
+        Ok(AtomicStoreInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            width,
+            in_type,
+            addr_in,
+            value_in,
+        })
+    }
+}
+
+impl Display for AtomicStoreInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}.atomic.store{} %{} %{} (align: {}, offset: {})",
+            self.in_type,
+            narrow_bits(self.in_type, self.width),
+            self.addr_in,
+            self.value_in,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `iNN.atomic.rmw{8,16,32}.<op>_u`: atomically combines memory with `value`, returning the old memory value.
+#[derive(Debug, Clone)]
+pub struct AtomicRmwInstruction {
+    pub memarg: MemArg,
+    pub op: AtomicRmwOp,
+    /// access width in bytes
+    pub width: u8,
+    pub r#type: NumType,
+    pub addr: VariableID,
+    pub value: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for AtomicRmwInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let op = match extract_atomic_op!(t) {
+            AtomicOp::Rmw(op) => op,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let width = i.read_immediate()?;
+        let r#type = extract_numtype!(i.read_value_type()?);
+        let addr = i.read_variable()?;
+        let value = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(AtomicRmwInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            op,
+            width,
+            r#type,
+            addr,
+            value,
+            out1,
+        })
+    }
+}
+
+impl Display for AtomicRmwInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: {} = {}.atomic.rmw{}.{}{} %{} %{} (align: {}, offset: {})",
+            self.out1,
+            self.r#type,
+            self.r#type,
+            narrow_bits(self.r#type, self.width),
+            self.op,
+            unsigned_suffix(self.r#type, self.width),
+            self.addr,
+            self.value,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `iNN.atomic.rmw{8,16,32}.cmpxchg_u`: atomically replaces the memory value with `replacement` if it equals
+/// `expected`, returning the old memory value.
+#[derive(Debug, Clone)]
+pub struct AtomicCmpxchgInstruction {
+    pub memarg: MemArg,
+    /// access width in bytes
+    pub width: u8,
+    pub r#type: NumType,
+    pub addr: VariableID,
+    pub expected: VariableID,
+    pub replacement: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for AtomicCmpxchgInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let width = i.read_immediate()?;
+        let r#type = extract_numtype!(i.read_value_type()?);
+        let addr = i.read_variable()?;
+        let expected = i.read_variable()?;
+        let replacement = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(AtomicCmpxchgInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            width,
+            r#type,
+            addr,
+            expected,
+            replacement,
+            out1,
+        })
+    }
+}
+
+impl Display for AtomicCmpxchgInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: {} = {}.atomic.rmw{}.cmpxchg{} %{} %{} %{} (align: {}, offset: {})",
+            self.out1,
+            self.r#type,
+            self.r#type,
+            narrow_bits(self.r#type, self.width),
+            unsigned_suffix(self.r#type, self.width),
+            self.addr,
+            self.expected,
+            self.replacement,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `memory.atomic.wait{32,64}`: suspends the agent until notified, if the memory value equals `expected`.
+/// `r#type` is the type of `expected` and selects between `wait32` and `wait64`.
+#[derive(Debug, Clone)]
+pub struct AtomicWaitInstruction {
+    pub memarg: MemArg,
+    pub r#type: NumType,
+    pub addr: VariableID,
+    pub expected: VariableID,
+    /// relative timeout in nanoseconds, negative values never time out
+    pub timeout: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for AtomicWaitInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let r#type = extract_numtype!(i.read_value_type()?);
+        let addr = i.read_variable()?;
+        let expected = i.read_variable()?;
+        let timeout = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(AtomicWaitInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            r#type,
+            addr,
+            expected,
+            timeout,
+            out1,
+        })
+    }
+}
+
+impl Display for AtomicWaitInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: i32 = memory.atomic.wait{} %{} %{} %{} (align: {}, offset: {})",
+            self.out1,
+            match self.r#type {
+                NumType::I64 | NumType::F64 => 64,
+                NumType::I32 | NumType::F32 => 32,
+            },
+            self.addr,
+            self.expected,
+            self.timeout,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `memory.atomic.notify`: wakes up to `count` agents waiting on the address, returning the number of woken agents.
+#[derive(Debug, Clone)]
+pub struct AtomicNotifyInstruction {
+    pub memarg: MemArg,
+    pub addr: VariableID,
+    pub count: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for AtomicNotifyInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        let addr = i.read_variable()?;
+        let count = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(AtomicNotifyInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            addr,
+            count,
+            out1,
+        })
+    }
+}
+
+impl Display for AtomicNotifyInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: i32 = memory.atomic.notify %{} %{} (align: {}, offset: {})",
+            self.out1, self.addr, self.count, self.memarg.align, self.memarg.offset
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AtomicFenceInstruction {}
+
+impl Instruction for AtomicFenceInstruction {
+    fn deserialize(_: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        Ok(AtomicFenceInstruction {})
+    }
+}
+
+impl Display for AtomicFenceInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "atomic.fence")
+    }
+}
@@ -13,6 +13,7 @@ impl Instruction for LoadInstruction {
     fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
         let align = i.read_immediate()?;
         let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
         let addr = i.read_variable()?;
         let out1 = i.read_variable()?;
         let out1_type = extract_numtype!(i.read_value_type()?);
@@ -21,7 +22,11 @@ impl Instruction for LoadInstruction {
             _ => return Err(DecodingError::TypeMismatch),
         };
         Ok(LoadInstruction {
-            memarg: MemArg { align, offset },
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
             out1,
             out1_type,
             addr,
@@ -2,44 +2,64 @@ use super::*;
 
 #[derive(Debug, Clone)]
 pub struct MemorySizeInstruction {
+    pub memory_idx: MemIdx,
     pub out1: VariableID,
 }
 
 impl Instruction for MemorySizeInstruction {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let memory_idx = i.read_immediate()?;
         let out1 = i.read_variable()?;
-        Ok(MemorySizeInstruction { out1 })
+        Ok(MemorySizeInstruction { memory_idx, out1 })
     }
 }
 
 impl Display for MemorySizeInstruction {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "%{}: i32 = memory.size", self.out1)
+        write!(
+            f,
+            "%{}: i32 = memory.size(i32 {})",
+            self.out1, self.memory_idx
+        )
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MemoryGrowInstruction {
+    pub memory_idx: MemIdx,
     pub in1: VariableID,
     pub out1: VariableID,
 }
 
 impl Display for MemoryGrowInstruction {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(f, "%{}: i32 = memory.grow i32 %{}", self.out1, self.in1)
+        write!(
+            f,
+            "%{}: i32 = memory.grow(i32 {}) i32 %{}",
+            self.out1, self.memory_idx, self.in1
+        )
     }
 }
 
 impl Instruction for MemoryGrowInstruction {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let memory_idx = i.read_immediate()?;
         let in1 = i.read_variable()?;
         let out1 = i.read_variable()?;
-        Ok(MemoryGrowInstruction { in1, out1 })
+        Ok(MemoryGrowInstruction {
+            memory_idx,
+            in1,
+            out1,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MemoryCopyInstruction {
+    /// destination memory
+    pub memory_idx_x: MemIdx,
+    /// source memory
+    pub memory_idx_y: MemIdx,
     pub n: VariableID,
     pub s: VariableID,
     pub d: VariableID,
@@ -47,10 +67,18 @@ pub struct MemoryCopyInstruction {
 
 impl Instruction for MemoryCopyInstruction {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let memory_idx_x = i.read_immediate()?;
+        let memory_idx_y = i.read_immediate()?;
         let n = i.read_variable()?;
         let s = i.read_variable()?;
         let d = i.read_variable()?;
-        Ok(MemoryCopyInstruction { n, s, d })
+        Ok(MemoryCopyInstruction {
+            memory_idx_x,
+            memory_idx_y,
+            n,
+            s,
+            d,
+        })
     }
 }
 
@@ -58,14 +86,15 @@ impl Display for MemoryCopyInstruction {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "memory.copy i32 %{} i32 %{} i32 %{}",
-            self.n, self.s, self.d
+            "memory.copy(i32 {}, i32 {}) i32 %{} i32 %{} i32 %{}",
+            self.memory_idx_x, self.memory_idx_y, self.n, self.s, self.d
         )
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MemoryFillInstruction {
+    pub memory_idx: MemIdx,
     pub n: VariableID,
     pub val: VariableID,
     pub d: VariableID,
@@ -75,24 +104,31 @@ impl Display for MemoryFillInstruction {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "memory.fill i32 %{} i32 %{} i32 %{}",
-            self.n, self.val, self.d
+            "memory.fill(i32 {}) i32 %{} i32 %{} i32 %{}",
+            self.memory_idx, self.n, self.val, self.d
         )
     }
 }
 
 impl Instruction for MemoryFillInstruction {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let memory_idx = i.read_immediate()?;
         let n = i.read_variable()?;
         let val = i.read_variable()?;
         let d = i.read_variable()?;
-        Ok(MemoryFillInstruction { n, val, d })
+        Ok(MemoryFillInstruction {
+            memory_idx,
+            n,
+            val,
+            d,
+        })
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct MemoryInitInstruction {
     pub data_idx: DataIdx,
+    pub memory_idx: MemIdx,
     pub n: VariableID,
     pub s: VariableID,
     pub d: VariableID,
@@ -101,10 +137,17 @@ pub struct MemoryInitInstruction {
 impl Instruction for MemoryInitInstruction {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
         let data_idx = i.read_immediate()?;
+        let memory_idx = i.read_immediate()?;
         let n = i.read_variable()?;
         let s = i.read_variable()?;
         let d = i.read_variable()?;
-        Ok(MemoryInitInstruction { data_idx, n, s, d })
+        Ok(MemoryInitInstruction {
+            data_idx,
+            memory_idx,
+            n,
+            s,
+            d,
+        })
     }
 }
 
@@ -112,8 +155,8 @@ impl Display for MemoryInitInstruction {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         write!(
             f,
-            "memory.init(i32 {}) i32 %{} i32 %{} i32 %{}",
-            self.data_idx, self.n, self.s, self.d
+            "memory.init(i32 {}, i32 {}) i32 %{} i32 %{} i32 %{}",
+            self.data_idx, self.memory_idx, self.n, self.s, self.d
         )
     }
 }
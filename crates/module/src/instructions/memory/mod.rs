@@ -1,7 +1,9 @@
+pub mod atomic;
 pub mod load;
 pub mod manage;
 pub mod store;
 
+pub use atomic::*;
 pub use load::*;
 pub use manage::*;
 pub use store::*;
@@ -13,6 +13,7 @@ impl Instruction for StoreInstruction {
     fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
         let align = i.read_immediate()?;
         let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
         let addr_in = i.read_variable()?;
         let value_in = i.read_variable()?;
         let in_type = extract_numtype!(i.read_value_type()?);
@@ -21,7 +22,11 @@ impl Instruction for StoreInstruction {
             _ => return Err(DecodingError::TypeMismatch),
         };
         Ok(StoreInstruction {
-            memarg: MemArg { align, offset },
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
             addr_in,
             value_in,
             operation,
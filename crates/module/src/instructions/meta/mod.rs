@@ -7,6 +7,7 @@ use wasm_types::InstructionType;
 use super::{Instruction, VariableID};
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct PhiNode {
     pub inputs: SmallVec<[(BasicBlockID, VariableID); 2]>,
     pub out: VariableID,
@@ -1,8 +1,10 @@
+mod aggregate;
 pub mod basic_block;
 mod control;
 pub(crate) mod decoder;
 pub(crate) mod encoder;
 pub(crate) mod instruction_consumer;
+mod ir_instruction;
 mod memory;
 pub mod meta;
 mod numeric;
@@ -10,8 +12,11 @@ mod parametric;
 mod reference;
 mod table;
 mod variable;
+mod vector;
 
+pub use aggregate::*;
 pub use control::*;
+pub use ir_instruction::*;
 pub use memory::*;
 pub use meta::*;
 pub use numeric::*;
@@ -19,6 +24,7 @@ pub use parametric::*;
 pub use reference::*;
 pub use table::*;
 pub use variable::*;
+pub use vector::*;
 
 use crate::{BasicBlock, DecodingError, InstructionDecoder};
 use std::fmt::{self, Display, Formatter};
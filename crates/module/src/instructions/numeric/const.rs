@@ -13,7 +13,7 @@ impl Instruction for Constant {
     fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
         let imm = i.read_immediate::<u64>()?.into();
         let out1 = i.read_variable()?;
-        let num_type = extract_numtype!(i.read_value_type().unwrap());
+        let num_type = extract_numtype!(i.read_value_type()?);
         Ok(Constant {
             imm,
             out1,
@@ -24,10 +24,12 @@ impl Instruction for Constant {
 
 impl Display for Constant {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        write!(
-            f,
-            "%{}: {} = const {:?}",
-            self.out1, self.out1_type, self.imm
-        )
+        write!(f, "%{}: {} = const ", self.out1, self.out1_type)?;
+        match self.out1_type {
+            NumType::I32 => write!(f, "{}", self.imm.as_i32()),
+            NumType::I64 => write!(f, "{}", self.imm.as_i64()),
+            NumType::F32 => write!(f, "{}", f32::from_bits(self.imm.as_f32())),
+            NumType::F64 => write!(f, "{}", f64::from_bits(self.imm.as_f64())),
+        }
     }
 }
@@ -72,3 +72,163 @@ impl Display for ReferenceFunctionInstruction {
         write!(f, "%{} = ref.func {}", self.out1, self.func_idx)
     }
 }
+
+/// Traps on null references, `out1` has the non-nullable type of `in1`.
+#[derive(Debug, Clone)]
+pub struct ReferenceAsNonNullInstruction {
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ReferenceAsNonNullInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ReferenceAsNonNullInstruction { in1, out1 })
+    }
+}
+
+impl Display for ReferenceAsNonNullInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "%{} = ref.as_non_null %{}", self.out1, self.in1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferenceEqInstruction {
+    pub in1: VariableID,
+    pub in2: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ReferenceEqInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let in2 = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ReferenceEqInstruction { in1, in2, out1 })
+    }
+}
+
+impl Display for ReferenceEqInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "%{} = ref.eq %{} %{}", self.out1, self.in1, self.in2)
+    }
+}
+
+/// `out1` is 1 if `in1` matches `ref_type`, 0 otherwise.
+#[derive(Debug, Clone)]
+pub struct ReferenceTestInstruction {
+    pub in1: VariableID,
+    pub ref_type: RefType,
+    pub out1: VariableID,
+}
+
+impl Instruction for ReferenceTestInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let ref_type = match i.read_value_type()? {
+            ValType::Reference(rt) => rt,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let out1 = i.read_variable()?;
+        Ok(ReferenceTestInstruction {
+            in1,
+            ref_type,
+            out1,
+        })
+    }
+}
+
+impl Display for ReferenceTestInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = ref.test {} %{}",
+            self.out1, self.ref_type, self.in1
+        )
+    }
+}
+
+/// Traps if `in1` does not match `ref_type`.
+#[derive(Debug, Clone)]
+pub struct ReferenceCastInstruction {
+    pub in1: VariableID,
+    pub ref_type: RefType,
+    pub out1: VariableID,
+}
+
+impl Instruction for ReferenceCastInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let ref_type = match i.read_value_type()? {
+            ValType::Reference(rt) => rt,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let out1 = i.read_variable()?;
+        Ok(ReferenceCastInstruction {
+            in1,
+            ref_type,
+            out1,
+        })
+    }
+}
+
+impl Display for ReferenceCastInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = ref.cast {} %{}",
+            self.out1, self.ref_type, self.in1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReferenceI31Instruction {
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for ReferenceI31Instruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let out1 = i.read_variable()?;
+        Ok(ReferenceI31Instruction { in1, out1 })
+    }
+}
+
+impl Display for ReferenceI31Instruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "%{} = ref.i31 %{}", self.out1, self.in1)
+    }
+}
+
+/// Traps on null references, `signed` selects sign extension of the 31-bit value.
+#[derive(Debug, Clone)]
+pub struct I31GetInstruction {
+    pub in1: VariableID,
+    pub signed: bool,
+    pub out1: VariableID,
+}
+
+impl Instruction for I31GetInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let in1 = i.read_variable()?;
+        let signed = i.read_immediate::<u8>()? != 0;
+        let out1 = i.read_variable()?;
+        Ok(I31GetInstruction { in1, signed, out1 })
+    }
+}
+
+impl Display for I31GetInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{} = i31.get_{} %{}",
+            self.out1,
+            if self.signed { "s" } else { "u" },
+            self.in1
+        )
+    }
+}
@@ -0,0 +1,553 @@
+use super::*;
+use crate::objects::memory::MemArg;
+use wasm_types::*;
+
+macro_rules! extract_vector_category {
+    ($t:expr) => {
+        match $t {
+            InstructionType::Vector(c) => c,
+            _ => return Err(DecodingError::TypeMismatch),
+        }
+    };
+}
+
+#[derive(Debug, Clone)]
+pub struct VConstInstruction {
+    pub imm: [u8; 16],
+    pub out1: VariableID,
+}
+
+impl Instruction for VConstInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let imm = i.read_immediate::<u128>()?.to_le_bytes();
+        let out1 = i.read_variable()?;
+        Ok(VConstInstruction { imm, out1 })
+    }
+}
+
+impl Display for VConstInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = const 0x{:032x}",
+            self.out1,
+            u128::from_le_bytes(self.imm)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VLoadInstruction {
+    pub memarg: MemArg,
+    pub operation: VecLoadOp,
+    pub addr: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VLoadInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let operation = match extract_vector_category!(t) {
+            VectorInstructionCategory::Load(op) => op,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        Ok(VLoadInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            operation,
+            addr: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VLoadInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = v128.{} %{} (align: {}, offset: {})",
+            self.out1, self.operation, self.addr, self.memarg.align, self.memarg.offset
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VStoreInstruction {
+    pub memarg: MemArg,
+    pub addr_in: VariableID,
+    pub value_in: VariableID,
+}
+
+impl Instruction for VStoreInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        Ok(VStoreInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            addr_in: i.read_variable()?,
+            value_in: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VStoreInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "v128.store %{} %{} (align: {}, offset: {})",
+            self.addr_in, self.value_in, self.memarg.align, self.memarg.offset
+        )
+    }
+}
+
+/// `v128.loadN_lane`: loads a single lane from memory into a copy of `in1`.
+#[derive(Debug, Clone)]
+pub struct VLoadLaneInstruction {
+    pub memarg: MemArg,
+    pub shape: VecShape,
+    pub lane: u8,
+    pub addr: VariableID,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VLoadLaneInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let shape = match extract_vector_category!(t) {
+            VectorInstructionCategory::LoadLane(shape) => shape,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        Ok(VLoadLaneInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            shape,
+            lane: i.read_immediate()?,
+            addr: i.read_variable()?,
+            in1: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VLoadLaneInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = v128.load{}_lane {} %{} %{} (align: {}, offset: {})",
+            self.out1,
+            self.shape.lane_bytes() * 8,
+            self.lane,
+            self.addr,
+            self.in1,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+/// `v128.storeN_lane`: stores a single lane of `value_in` to memory.
+#[derive(Debug, Clone)]
+pub struct VStoreLaneInstruction {
+    pub memarg: MemArg,
+    pub shape: VecShape,
+    pub lane: u8,
+    pub addr_in: VariableID,
+    pub value_in: VariableID,
+}
+
+impl Instruction for VStoreLaneInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let shape = match extract_vector_category!(t) {
+            VectorInstructionCategory::StoreLane(shape) => shape,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        let align = i.read_immediate()?;
+        let offset = i.read_immediate()?;
+        let memory = i.read_immediate()?;
+        Ok(VStoreLaneInstruction {
+            memarg: MemArg {
+                memory,
+                offset,
+                align,
+            },
+            shape,
+            lane: i.read_immediate()?,
+            addr_in: i.read_variable()?,
+            value_in: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VStoreLaneInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "v128.store{}_lane {} %{} %{} (align: {}, offset: {})",
+            self.shape.lane_bytes() * 8,
+            self.lane,
+            self.addr_in,
+            self.value_in,
+            self.memarg.align,
+            self.memarg.offset
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VShuffleInstruction {
+    pub lanes: [u8; 16],
+    pub lhs: VariableID,
+    pub rhs: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VShuffleInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        let lanes = i.read_immediate::<u128>()?.to_le_bytes();
+        Ok(VShuffleInstruction {
+            lanes,
+            lhs: i.read_variable()?,
+            rhs: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VShuffleInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = i8x16.shuffle {:?} %{} %{}",
+            self.out1, self.lanes, self.lhs, self.rhs
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VSplatInstruction {
+    pub shape: VecShape,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VSplatInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let shape = match extract_vector_category!(t) {
+            VectorInstructionCategory::Splat(shape) => shape,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VSplatInstruction {
+            shape,
+            in1: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VSplatInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.splat %{}",
+            self.out1, self.shape, self.in1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VExtractLaneInstruction {
+    pub shape: VecShape,
+    /// `None` for lanes that are not widened (i32, i64, f32, f64).
+    pub signed: Option<bool>,
+    pub lane: u8,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VExtractLaneInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, signed) = match extract_vector_category!(t) {
+            VectorInstructionCategory::ExtractLane(shape) => (shape, None),
+            VectorInstructionCategory::ExtractLaneS(shape) => (shape, Some(true)),
+            VectorInstructionCategory::ExtractLaneU(shape) => (shape, Some(false)),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VExtractLaneInstruction {
+            shape,
+            signed,
+            lane: i.read_immediate()?,
+            in1: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VExtractLaneInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: {} = {}.extract_lane{} {} %{}",
+            self.out1,
+            self.shape.unpacked_type(),
+            self.shape,
+            match self.signed {
+                None => "",
+                Some(true) => "_s",
+                Some(false) => "_u",
+            },
+            self.lane,
+            self.in1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VReplaceLaneInstruction {
+    pub shape: VecShape,
+    pub lane: u8,
+    pub in1: VariableID,
+    pub value_in: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VReplaceLaneInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let shape = match extract_vector_category!(t) {
+            VectorInstructionCategory::ReplaceLane(shape) => shape,
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VReplaceLaneInstruction {
+            shape,
+            lane: i.read_immediate()?,
+            in1: i.read_variable()?,
+            value_in: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VReplaceLaneInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.replace_lane {} %{} %{}",
+            self.out1, self.shape, self.lane, self.in1, self.value_in
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VUnaryInstruction {
+    pub shape: VecShape,
+    pub op: VUnaryOp,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VUnaryInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, op) = match extract_vector_category!(t) {
+            VectorInstructionCategory::Unary(shape, op) => (shape, op),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VUnaryInstruction {
+            shape,
+            op,
+            in1: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VUnaryInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.{} %{}",
+            self.out1, self.shape, self.op, self.in1
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VBinaryInstruction {
+    pub shape: VecShape,
+    pub op: VBinaryOp,
+    pub lhs: VariableID,
+    pub rhs: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VBinaryInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, op) = match extract_vector_category!(t) {
+            VectorInstructionCategory::Binary(shape, op) => (shape, op),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VBinaryInstruction {
+            shape,
+            op,
+            lhs: i.read_variable()?,
+            rhs: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VBinaryInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.{} %{} %{}",
+            self.out1, self.shape, self.op, self.lhs, self.rhs
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VBitselectInstruction {
+    pub in1: VariableID,
+    pub in2: VariableID,
+    pub mask: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VBitselectInstruction {
+    fn deserialize(i: &mut InstructionDecoder, _: InstructionType) -> Result<Self, DecodingError> {
+        Ok(VBitselectInstruction {
+            in1: i.read_variable()?,
+            in2: i.read_variable()?,
+            mask: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VBitselectInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = v128.bitselect %{} %{} %{}",
+            self.out1, self.in1, self.in2, self.mask
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VRelationalInstruction {
+    pub shape: VecShape,
+    pub op: VRelationalOp,
+    pub lhs: VariableID,
+    pub rhs: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VRelationalInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, op) = match extract_vector_category!(t) {
+            VectorInstructionCategory::Relational(shape, op) => (shape, op),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VRelationalInstruction {
+            shape,
+            op,
+            lhs: i.read_variable()?,
+            rhs: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VRelationalInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.{} %{} %{}",
+            self.out1, self.shape, self.op, self.lhs, self.rhs
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VShiftInstruction {
+    pub shape: VecShape,
+    pub op: VShiftOp,
+    pub in1: VariableID,
+    pub amount: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VShiftInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, op) = match extract_vector_category!(t) {
+            VectorInstructionCategory::Shift(shape, op) => (shape, op),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VShiftInstruction {
+            shape,
+            op,
+            in1: i.read_variable()?,
+            amount: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VShiftInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: v128 = {}.{} %{} %{}",
+            self.out1, self.shape, self.op, self.in1, self.amount
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct VTestInstruction {
+    pub shape: VecShape,
+    pub op: VTestOp,
+    pub in1: VariableID,
+    pub out1: VariableID,
+}
+
+impl Instruction for VTestInstruction {
+    fn deserialize(i: &mut InstructionDecoder, t: InstructionType) -> Result<Self, DecodingError> {
+        let (shape, op) = match extract_vector_category!(t) {
+            VectorInstructionCategory::Test(shape, op) => (shape, op),
+            _ => return Err(DecodingError::TypeMismatch),
+        };
+        Ok(VTestInstruction {
+            shape,
+            op,
+            in1: i.read_variable()?,
+            out1: i.read_variable()?,
+        })
+    }
+}
+
+impl Display for VTestInstruction {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "%{}: i32 = {}.{} %{}",
+            self.out1, self.shape, self.op, self.in1
+        )
+    }
+}
@@ -1,10 +1,11 @@
 pub mod error;
 pub mod instructions;
 pub mod objects;
+pub mod passes;
 pub mod utils;
+pub mod verifier;
+pub mod writer;
 
-// formatting is not production-save (lots of unwraps)
-#[cfg(debug_assertions)]
 pub mod fmt;
 
 pub use instructions::basic_block;
@@ -15,3 +16,5 @@ pub use instructions::instruction_consumer::InstructionConsumer;
 
 pub use error::ModuleError;
 pub use objects::module::{FunctionLoaderInterface, Module, ModuleMetadata};
+pub use verifier::VerifierError;
+pub use writer::{ModuleWriter, WriterError};
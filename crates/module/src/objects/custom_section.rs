@@ -0,0 +1,10 @@
+use rkyv::{Archive, Deserialize, Serialize};
+
+/// Custom section payload without its name. (offset into memory region (mmaped for file), length)
+#[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct CustomSection {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
@@ -3,6 +3,7 @@ use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::MemIdx;
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum DataMode {
     Active {
         memory: MemIdx,
@@ -12,6 +13,7 @@ pub enum DataMode {
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Data {
     pub init: Vec<u8>,
     pub mode: DataMode,
@@ -3,12 +3,14 @@ use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::{FuncIdx, RefType, TableIdx};
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ElementInit {
     Unresolved(Vec<FuncIdx>),
     Final(Vec<ConstantValue>),
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Element {
     pub type_: RefType,
     pub init: ElementInit,
@@ -16,6 +18,7 @@ pub struct Element {
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ElemMode {
     Passive,
     Active {
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use rkyv::{Archive, Deserialize, Serialize};
-use wasm_types::{FuncIdx, GlobalIdx, MemIdx, Name, TableIdx};
+use wasm_types::{FuncIdx, GlobalIdx, MemIdx, Name, TableIdx, TagIdx};
 
 #[derive(Debug, Clone)]
 pub struct FuncExport {
@@ -27,21 +27,30 @@ pub struct GlobalExport {
     pub idx: GlobalIdx,
 }
 
+#[derive(Debug, Clone)]
+pub struct TagExport {
+    pub name: Name,
+    pub idx: TagIdx,
+}
+
 #[derive(Debug, Clone)]
 pub enum Export {
     Func(FuncExport),
     Table(TableExport),
     Mem(MemoryExport),
     Global(GlobalExport),
+    Tag(TagExport),
 }
 
 #[derive(Debug, Clone, Default, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct WasmExports {
     pub functions: HashMap<String, FuncIdx>,
     pub functions_rev: HashMap<FuncIdx, String>,
     pub tables: HashMap<String, TableIdx>,
     pub memories: HashMap<String, MemIdx>,
     pub globals: HashMap<String, GlobalIdx>,
+    pub tags: HashMap<String, TagIdx>,
 }
 
 impl WasmExports {
@@ -54,6 +63,7 @@ impl WasmExports {
             && self.tables.is_empty()
             && self.memories.is_empty()
             && self.globals.is_empty()
+            && self.tags.is_empty()
     }
 
     #[inline]
@@ -77,6 +87,11 @@ impl WasmExports {
         self.globals.insert(e.name, e.idx);
     }
 
+    #[inline]
+    pub fn add_tag_export(&mut self, e: TagExport) {
+        self.tags.insert(e.name, e.idx);
+    }
+
     pub fn find_function_name(&self, idx: FuncIdx) -> Option<&str> {
         self.functions_rev.get(&idx).map(String::as_str)
     }
@@ -97,11 +112,16 @@ impl WasmExports {
         self.globals.get(name).copied()
     }
 
+    pub fn find_tag_idx(&self, name: &str) -> Option<TagIdx> {
+        self.tags.get(name).copied()
+    }
+
     pub fn append(&mut self, other: Self) {
         self.functions.extend(other.functions);
         self.tables.extend(other.tables);
         self.memories.extend(other.memories);
         self.globals.extend(other.globals);
+        self.tags.extend(other.tags);
         self.functions_rev.extend(other.functions_rev);
     }
 
@@ -1,14 +1,20 @@
-use super::value::{ConstantValue, Number, Value};
+use super::value::{ConstantAllocation, ConstantOp, ConstantValue, Number, Value, ValueRaw};
 use crate::{
     basic_block::BasicBlockStorage,
-    instructions::{Constant, GlobalGetInstruction, ReferenceFunctionInstruction},
+    instructions::{
+        ArrayNewFixedInstruction, ArrayNewInstruction, Constant, GlobalGetInstruction,
+        IBinaryInstruction, ReferenceFunctionInstruction, ReferenceNullInstruction,
+        StructNewInstruction, VConstInstruction, VariableID,
+    },
     objects::value::Reference,
     DecodingError, InstructionDecoder, ModuleMetadata,
 };
+use std::collections::HashMap;
 use thiserror::Error;
 use wasm_types::{
-    GlobalType, InstructionType, NumericInstructionCategory, ReferenceInstructionType, ValType,
-    VariableInstructionType,
+    AggregateInstructionCategory, GlobalIdx, GlobalType, IBinaryOp, InstructionType, NumType,
+    NumericInstructionCategory, ReferenceInstructionType, ValType, VariableInstructionType,
+    VectorInstructionCategory,
 };
 
 #[derive(Debug, Clone, Default)]
@@ -22,12 +28,48 @@ pub enum ConstantExpressionError {
     Msg(String),
     #[error("Error during decoding for constant expression: {0}")]
     DecodingError(#[from] DecodingError),
+    #[error(
+        "Constant expression error: `{0}` isn't supported in this constant expression, objects can only be allocated by functions and initializers of globals and tables"
+    )]
+    UnsupportedAllocation(&'static str),
+}
+
+fn is_allocation(instr: &InstructionType) -> bool {
+    // `struct.new_default` and `array.new_default` share their categories with `struct.new` and `array.new`
+    matches!(
+        instr,
+        InstructionType::Aggregate(
+            AggregateInstructionCategory::StructNew
+                | AggregateInstructionCategory::ArrayNew
+                | AggregateInstructionCategory::ArrayNewFixed
+        )
+    )
 }
 
 impl ConstantExpression {
+    /// Evaluates the expression at parsing time as far as possible. Allocations of the GC proposal are rejected.
     pub fn eval(self, m: &ModuleMetadata) -> Result<ConstantValue, ConstantExpressionError> {
-        debug_assert_eq!(self.expression.instruction_storage.len(), 1);
-        let mut decoder = InstructionDecoder::new(self.expression);
+        self.eval_impl(m, false)
+    }
+
+    /// Like [`ConstantExpression::eval`], but allocations of the GC proposal are evaluated at instantiation. Only
+    /// initializers of globals and tables allocate, because their values are roots of the heap.
+    pub fn eval_allocating(
+        self,
+        m: &ModuleMetadata,
+    ) -> Result<ConstantValue, ConstantExpressionError> {
+        self.eval_impl(m, true)
+    }
+
+    fn eval_impl(
+        self,
+        m: &ModuleMetadata,
+        allocating: bool,
+    ) -> Result<ConstantValue, ConstantExpressionError> {
+        if self.expression.instruction_storage.len() > 1 {
+            return self.eval_extended(m, allocating);
+        }
+        let mut decoder = InstructionDecoder::new(self.expression.clone());
         let instr = decoder.read_instruction_type()?;
         // https://webassembly.github.io/spec/core/bikeshed/index.html#constant-expressions%E2%91%A0
         match instr {
@@ -37,26 +79,14 @@ impl ConstantExpression {
                 let value = Value::from_raw(imm, ValType::Number(constant_instruction.out1_type));
                 Ok(ConstantValue::V(value))
             }
+            InstructionType::Vector(VectorInstructionCategory::Constant) => {
+                let constant_instruction = decoder.read::<VConstInstruction>(instr)?;
+                Ok(ConstantValue::V(Value::Vector(constant_instruction.imm)))
+            }
             InstructionType::Variable(VariableInstructionType::GlobalGet) => {
                 let instruction = decoder.read::<GlobalGetInstruction>(instr)?;
-                let global_idx = instruction.global_idx;
-                if global_idx > m.globals.len() as u32 {
-                    return Err(ConstantExpressionError::Msg(format!(
-                        "global index {global_idx} out of bounds",
-                    )));
-                }
-                if !m.globals[global_idx as usize].import {
-                    return Err(ConstantExpressionError::Msg(format!(
-                        "constant global initializer expressions may only reference imported globals, found non-imported global index {global_idx}",
-
-                    )));
-                }
-                if !matches!(m.globals[global_idx as usize].r#type, GlobalType::Const(_)) {
-                    return Err(ConstantExpressionError::Msg(format!(
-                        "global index {global_idx} is not a const global",
-                    )));
-                }
-                Ok(ConstantValue::Global(global_idx))
+                check_constant_global(m, instruction.global_idx)?;
+                Ok(ConstantValue::Global(instruction.global_idx))
             }
             InstructionType::Reference(ReferenceInstructionType::RefNull) => {
                 Ok(ConstantValue::V(Value::Reference(Reference::Null)))
@@ -71,11 +101,267 @@ impl ConstantExpression {
                 }
                 Ok(ConstantValue::FuncPtr(instruction.func_idx))
             }
+            _ if is_allocation(&instr) => self.eval_extended(m, allocating),
             _ => Err(ConstantExpressionError::Msg(format!(
-                "invalid constant instruction `{instr:?}`",
+                "invalid constant instruction `{instr:?}`"
             ))),
         }
     }
+
+    // extended constant expressions: integer constants and globals combined with add, sub and mul
+    // https://github.com/WebAssembly/extended-const/blob/main/proposals/extended-const/Overview.md
+    // and the allocations of the GC proposal, whose operands may be constants of any type
+    // https://webassembly.github.io/gc/core/valid/instructions.html#constant-expressions
+    fn eval_extended(
+        self,
+        m: &ModuleMetadata,
+        allocating: bool,
+    ) -> Result<ConstantValue, ConstantExpressionError> {
+        let mut decoder = InstructionDecoder::new(self.expression);
+        // postfix operations computing each variable
+        let mut operands: HashMap<VariableID, Vec<ConstantOp>> = HashMap::new();
+        let operand = |operands: &mut HashMap<_, _>, var| {
+            operands
+                .remove(&var)
+                .ok_or_else(|| ConstantExpressionError::Msg(format!("undefined operand %{var}")))
+        };
+        let mut result = None;
+        while let Ok(instr) = decoder.read_instruction_type() {
+            let (out, ops) = match instr {
+                InstructionType::Numeric(NumericInstructionCategory::Constant) => {
+                    let instruction = decoder.read::<Constant>(instr)?;
+                    let value =
+                        Value::from_raw(instruction.imm, ValType::Number(instruction.out1_type));
+                    (instruction.out1, vec![ConstantOp::Const(value)])
+                }
+                InstructionType::Vector(VectorInstructionCategory::Constant) => {
+                    let instruction = decoder.read::<VConstInstruction>(instr)?;
+                    let value = Value::Vector(instruction.imm);
+                    (instruction.out1, vec![ConstantOp::Const(value)])
+                }
+                InstructionType::Variable(VariableInstructionType::GlobalGet) => {
+                    let instruction = decoder.read::<GlobalGetInstruction>(instr)?;
+                    let global_idx = instruction.global_idx;
+                    check_constant_global(m, global_idx)?;
+                    let t = m.globals[global_idx as usize].val_type();
+                    (instruction.out1, vec![ConstantOp::Global(global_idx, t)])
+                }
+                InstructionType::Reference(ReferenceInstructionType::RefNull) => {
+                    let instruction = decoder.read::<ReferenceNullInstruction>(instr)?;
+                    let heap_type = instruction.out1_type.heap_type();
+                    (instruction.out1, vec![ConstantOp::RefNull(heap_type)])
+                }
+                InstructionType::Reference(ReferenceInstructionType::RefFunc) => {
+                    let instruction = decoder.read::<ReferenceFunctionInstruction>(instr)?;
+                    if instruction.func_idx >= m.functions.len() as u32 {
+                        return Err(ConstantExpressionError::Msg(format!(
+                            "function index {} out of bounds",
+                            instruction.func_idx
+                        )));
+                    }
+                    let value = Value::funcref(instruction.func_idx);
+                    (instruction.out1, vec![ConstantOp::Const(value)])
+                }
+                InstructionType::Numeric(NumericInstructionCategory::IBinary(
+                    IBinaryOp::Add | IBinaryOp::Sub | IBinaryOp::Mul,
+                )) => {
+                    let instruction = decoder.read::<IBinaryInstruction>(instr)?;
+                    check_extended_type(instruction.types)?;
+                    let mut ops = operand(&mut operands, instruction.lhs)?;
+                    ops.extend(operand(&mut operands, instruction.rhs)?);
+                    ops.push(ConstantOp::IBinary(instruction.types, instruction.op));
+                    (instruction.out1, ops)
+                }
+                InstructionType::Aggregate(AggregateInstructionCategory::StructNew) => {
+                    let instruction = decoder.read::<StructNewInstruction>(instr)?;
+                    let num_fields = m
+                        .types
+                        .get(instruction.type_idx as usize)
+                        .and_then(|ty| ty.as_struct())
+                        .map_or(0, |fields| fields.len());
+                    let allocation = match instruction.fields.len() {
+                        0 if num_fields > 0 => {
+                            ConstantAllocation::StructNewDefault(instruction.type_idx)
+                        }
+                        fields => ConstantAllocation::StructNew {
+                            type_idx: instruction.type_idx,
+                            fields: fields as u32,
+                        },
+                    };
+                    let mut ops = Vec::new();
+                    for field in instruction.fields {
+                        ops.extend(operand(&mut operands, field)?);
+                    }
+                    (
+                        instruction.out1,
+                        allocation_ops(ops, allocation, allocating)?,
+                    )
+                }
+                InstructionType::Aggregate(AggregateInstructionCategory::ArrayNew) => {
+                    let instruction = decoder.read::<ArrayNewInstruction>(instr)?;
+                    let (mut ops, allocation) = match instruction.init {
+                        Some(init) => (
+                            operand(&mut operands, init)?,
+                            ConstantAllocation::ArrayNew(instruction.type_idx),
+                        ),
+                        None => (
+                            Vec::new(),
+                            ConstantAllocation::ArrayNewDefault(instruction.type_idx),
+                        ),
+                    };
+                    ops.extend(operand(&mut operands, instruction.len)?);
+                    (
+                        instruction.out1,
+                        allocation_ops(ops, allocation, allocating)?,
+                    )
+                }
+                InstructionType::Aggregate(AggregateInstructionCategory::ArrayNewFixed) => {
+                    let instruction = decoder.read::<ArrayNewFixedInstruction>(instr)?;
+                    let allocation = ConstantAllocation::ArrayNewFixed {
+                        type_idx: instruction.type_idx,
+                        len: instruction.values.len() as u32,
+                    };
+                    let mut ops = Vec::new();
+                    for value in instruction.values {
+                        ops.extend(operand(&mut operands, value)?);
+                    }
+                    (
+                        instruction.out1,
+                        allocation_ops(ops, allocation, allocating)?,
+                    )
+                }
+                _ => {
+                    return Err(ConstantExpressionError::Msg(format!(
+                        "invalid extended constant instruction `{instr:?}`",
+                    )))
+                }
+            };
+            operands.insert(out, ops);
+            result = Some(out);
+        }
+        let ops = result
+            .and_then(|out| operands.remove(&out))
+            .ok_or_else(|| ConstantExpressionError::Msg("empty constant expression".into()))?;
+
+        if ops
+            .iter()
+            .any(|op| matches!(op, ConstantOp::Global(..) | ConstantOp::Alloc(_)))
+        {
+            return Ok(ConstantValue::Expr(ops));
+        }
+        let value = eval_constant_ops(&ops, |_| -> Result<_, ConstantExpressionError> {
+            unreachable!("constant operations without globals")
+        })?;
+        Ok(ConstantValue::V(value))
+    }
+}
+
+/// Appends `allocation` to the operations computing its operands, unless the expression can't allocate.
+fn allocation_ops(
+    mut ops: Vec<ConstantOp>,
+    allocation: ConstantAllocation,
+    allocating: bool,
+) -> Result<Vec<ConstantOp>, ConstantExpressionError> {
+    if !allocating {
+        return Err(ConstantExpressionError::UnsupportedAllocation(
+            allocation.name(),
+        ));
+    }
+    ops.push(ConstantOp::Alloc(allocation));
+    Ok(ops)
+}
+
+// since wasm 3.0, constant expressions may read any immutable global defined before them, not just imported ones.
+// Globals after the one being initialized are unknown while the global section is parsed.
+fn check_constant_global(
+    m: &ModuleMetadata,
+    global_idx: GlobalIdx,
+) -> Result<(), ConstantExpressionError> {
+    if global_idx >= m.globals.len() as u32 {
+        return Err(ConstantExpressionError::Msg(format!(
+            "global index {global_idx} out of bounds",
+        )));
+    }
+    if !matches!(m.globals[global_idx as usize].r#type, GlobalType::Const(_)) {
+        return Err(ConstantExpressionError::Msg(format!(
+            "global index {global_idx} is not a const global",
+        )));
+    }
+    Ok(())
+}
+
+fn check_extended_type(t: NumType) -> Result<(), ConstantExpressionError> {
+    match t {
+        NumType::I32 | NumType::I64 => Ok(()),
+        NumType::F32 | NumType::F64 => Err(ConstantExpressionError::Msg(format!(
+            "invalid type {t:?} in extended constant expression",
+        ))),
+    }
+}
+
+/// Evaluates the operations of an extended constant expression, looking up global values with `global_value`.
+/// The operations must not allocate, see [`eval_allocating_constant_ops`].
+pub fn eval_constant_ops<E>(
+    ops: &[ConstantOp],
+    global_value: impl FnMut(GlobalIdx) -> Result<ValueRaw, E>,
+) -> Result<Value, E> {
+    eval_allocating_constant_ops(ops, global_value, |allocation, _| {
+        unreachable!(
+            "`{}` in a constant expression that can't allocate",
+            allocation.name()
+        )
+    })
+}
+
+/// Like [`eval_constant_ops`], but objects are allocated with `alloc`, which receives the operands of the
+/// allocation and returns the reference to the object.
+pub fn eval_allocating_constant_ops<E>(
+    ops: &[ConstantOp],
+    mut global_value: impl FnMut(GlobalIdx) -> Result<ValueRaw, E>,
+    mut alloc: impl FnMut(&ConstantAllocation, Vec<ValueRaw>) -> Result<u64, E>,
+) -> Result<Value, E> {
+    let mut stack: Vec<Value> = Vec::with_capacity(ops.len());
+    for op in ops {
+        let value = match op {
+            ConstantOp::Const(value) => value.clone(),
+            ConstantOp::Global(global_idx, t) => Value::from_raw(global_value(*global_idx)?, *t),
+            ConstantOp::RefNull(_) => Value::Reference(Reference::Null),
+            ConstantOp::Alloc(allocation) => {
+                let operands = stack
+                    .split_off(stack.len() - allocation.num_operands())
+                    .into_iter()
+                    .map(ValueRaw::from)
+                    .collect();
+                Value::Reference(Reference::Gc(alloc(allocation, operands)?))
+            }
+            ConstantOp::IBinary(t, op) => {
+                let rhs = ValueRaw::from(stack.pop().unwrap());
+                let lhs = ValueRaw::from(stack.pop().unwrap());
+                match t {
+                    NumType::I32 => {
+                        let (lhs, rhs) = (lhs.as_u32(), rhs.as_u32());
+                        Value::i32(match op {
+                            IBinaryOp::Add => lhs.wrapping_add(rhs),
+                            IBinaryOp::Sub => lhs.wrapping_sub(rhs),
+                            IBinaryOp::Mul => lhs.wrapping_mul(rhs),
+                            _ => unreachable!("not a constant operation: {op:?}"),
+                        })
+                    }
+                    _ => {
+                        let (lhs, rhs) = (lhs.as_u64(), rhs.as_u64());
+                        Value::i64(match op {
+                            IBinaryOp::Add => lhs.wrapping_add(rhs),
+                            IBinaryOp::Sub => lhs.wrapping_sub(rhs),
+                            IBinaryOp::Mul => lhs.wrapping_mul(rhs),
+                            _ => unreachable!("not a constant operation: {op:?}"),
+                        })
+                    }
+                }
+            }
+        };
+        stack.push(value);
+    }
+    Ok(stack.pop().unwrap())
 }
 
 impl TryInto<u32> for Value {
@@ -3,12 +3,14 @@ use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::{FuncIdx, TypeIdx};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Archive)]
+#[archive(check_bytes)]
 pub struct Function {
     pub type_idx: u32,
     pub source: FunctionSource,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, Archive)]
+#[archive(check_bytes)]
 pub enum FunctionSource {
     Import(FunctionImport),
     Wasm(FunctionUnparsed),
@@ -19,8 +21,12 @@ impl Function {
         module.exports.find_function_name(func_idx)
     }
 
+    /// Name for diagnostics, the name section takes precedence over export names.
     pub fn debug_function_name(func_idx: FuncIdx, module: &ModuleMetadata) -> String {
-        Self::query_function_name(func_idx, module)
+        module
+            .names
+            .function_name(func_idx)
+            .or_else(|| Self::query_function_name(func_idx, module))
             .map(|s| s.to_string())
             .unwrap_or(format!("<anonymous:{func_idx}>"))
     }
@@ -70,12 +76,14 @@ impl Function {
 
 /// Function import information.
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct FunctionImport {
     pub import_idx: u32,
 }
 
 /// Unparsed binary wasm function code section. (offset into memory region (mmaped for file), length)
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct FunctionUnparsed {
     pub offset: usize,
     pub size: usize,
@@ -3,6 +3,7 @@ use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::{GlobalType, ValType};
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Global {
     pub r#type: GlobalType,
     // the appropriate type transmuted to a u64
@@ -2,6 +2,7 @@ use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::{ImportDesc, Name};
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Import {
     pub module: Name,
     pub name: Name,
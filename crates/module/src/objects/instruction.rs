@@ -1,9 +1,10 @@
 use rkyv::{Archive, Deserialize, Serialize};
-use wasm_types::{BlockType, FuncIdx, LabelIdx, TableIdx, TypeIdx};
+use wasm_types::{BlockType, FuncIdx, LabelIdx, RefType, TableIdx, TagIdx, TypeIdx};
 
 pub enum Instructon {}
 
 #[derive(Debug, Clone, Default, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ControlInstruction {
     Nop,
     #[default]
@@ -17,8 +18,58 @@ pub enum ControlInstruction {
     Return,
     Call(FuncIdx),
     CallIndirect(TypeIdx, TableIdx),
+    ReturnCall(FuncIdx),
+    ReturnCallIndirect(TypeIdx, TableIdx),
+    CallRef(TypeIdx),
+    ReturnCallRef(TypeIdx),
+    BrOnNull(LabelIdx),
+    BrOnNonNull(LabelIdx),
+    /// label, type of the operand, target type
+    BrOnCast(LabelIdx, RefType, RefType),
+    BrOnCastFail(LabelIdx, RefType, RefType),
+    TryTable(BlockType, Vec<Catch>),
+    Throw(TagIdx),
+    ThrowRef,
 
     // these are not real wasm terminators, but rather a signal to our parser that we reached the end of a block / the else statement
     End,
     Else,
 }
+
+/// https://webassembly.github.io/exception-handling/core/syntax/instructions.html#control-instructions
+#[derive(Debug, Clone, PartialEq, Eq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum Catch {
+    /// branches with the exception payload
+    Catch(TagIdx, LabelIdx),
+    /// branches with the exception payload and an `exnref` to the exception
+    CatchRef(TagIdx, LabelIdx),
+    /// branches without values, catching every exception
+    CatchAll(LabelIdx),
+    /// branches with an `exnref`, catching every exception
+    CatchAllRef(LabelIdx),
+}
+
+impl Catch {
+    pub fn label_idx(&self) -> LabelIdx {
+        match *self {
+            Catch::Catch(_, label)
+            | Catch::CatchRef(_, label)
+            | Catch::CatchAll(label)
+            | Catch::CatchAllRef(label) => label,
+        }
+    }
+
+    /// `None` for catch-all clauses
+    pub fn tag_idx(&self) -> Option<TagIdx> {
+        match *self {
+            Catch::Catch(tag, _) | Catch::CatchRef(tag, _) => Some(tag),
+            Catch::CatchAll(_) | Catch::CatchAllRef(_) => None,
+        }
+    }
+
+    /// whether the clause passes an `exnref` to its label
+    pub fn is_ref(&self) -> bool {
+        matches!(self, Catch::CatchRef(..) | Catch::CatchAllRef(_))
+    }
+}
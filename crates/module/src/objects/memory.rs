@@ -1,14 +1,19 @@
 use rkyv::{Archive, Deserialize, Serialize};
-use wasm_types::Limits;
+use wasm_types::{MemIdx, MemType};
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Memory {
-    pub limits: Limits,
+    pub r#type: MemType,
     pub import: bool,
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct MemArg {
-    pub offset: u32,
+    /// index of the accessed memory, always `0` without multi-memory
+    pub memory: MemIdx,
+    /// 64-bit memories allow offsets beyond `u32::MAX`
+    pub offset: u64,
     pub align: u32,
 }
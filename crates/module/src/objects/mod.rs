@@ -1,3 +1,4 @@
+pub mod custom_section;
 pub mod data;
 pub mod element;
 pub mod export;
@@ -8,5 +9,7 @@ pub mod import;
 pub mod instruction;
 pub mod memory;
 pub mod module;
+pub mod names;
 pub mod table;
+pub mod tag;
 pub mod value;
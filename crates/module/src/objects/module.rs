@@ -1,15 +1,17 @@
 use crate::error::ModuleError;
 
 use super::{
-    data::Data, element::Element, export::WasmExports, function::Function, global::Global,
-    import::Import, memory::Memory, table::Table,
+    custom_section::CustomSection, data::Data, element::Element, export::WasmExports,
+    function::Function, global::Global, import::Import, memory::Memory, names::WasmNames,
+    table::Table, tag::Tag,
 };
 use resource_buffer::ResourceBuffer;
 use rkyv::{Archive, Deserialize, Serialize};
 use std::{any::Any, collections::HashMap, fmt::Debug, sync::RwLock};
-use wasm_types::{FuncIdx, FuncType};
+use wasm_types::{FuncIdx, FuncType, SubType, TypeIdx};
 
-#[derive(Default, Debug, Deserialize, Serialize, Archive)]
+#[derive(Default, Debug, Clone, Deserialize, Serialize, Archive)]
+#[archive(check_bytes)]
 pub struct ModuleMetadata {
     /// Wasm function tables (icall lookup)
     pub tables: Vec<Table>,
@@ -23,15 +25,23 @@ pub struct ModuleMetadata {
     /// Wasm globals
     pub globals: Vec<Global>,
 
+    /// Wasm exception tags
+    pub tags: Vec<Tag>,
+
     /// Wasm data segments
     pub datas: Vec<Data>,
 
     /// Wasm number of data segments (if section is present)
     pub datacount: Option<u32>,
 
-    /// Wasm function signatures
+    /// Wasm function signatures, indexed by type index
+    ///
+    /// Struct and array types hold an empty placeholder signature, see [`ModuleMetadata::func_type`].
     pub function_types: Vec<FuncType>,
 
+    /// Wasm type definitions of all types including struct and array types (GC proposal)
+    pub types: Vec<SubType>,
+
     /// Wasm start function
     pub entry_point: Option<FuncIdx>,
 
@@ -43,6 +53,19 @@ pub struct ModuleMetadata {
 
     /// Wasm functions
     pub functions: Vec<Function>,
+
+    /// Debug names of the module, its functions, locals and globals (name section)
+    pub names: WasmNames,
+
+    /// All custom sections in module order, including the name section
+    pub custom_sections: Vec<CustomSection>,
+}
+
+impl ModuleMetadata {
+    /// The function type of a type index, `None` for out-of-bounds indices and non-function types.
+    pub fn func_type(&self, type_idx: TypeIdx) -> Option<FuncType> {
+        self.types.get(type_idx as usize).and_then(SubType::as_func)
+    }
 }
 
 /// WebAssembly module metadata
@@ -69,6 +92,35 @@ pub trait FunctionLoaderInterface {
 }
 
 impl Module {
+    /// Name and payload of all custom sections in module order.
+    ///
+    /// Note: Names are not unique, a module may contain multiple custom sections of the same name.
+    pub fn custom_sections(&self) -> impl Iterator<Item = (&str, &[u8])> {
+        self.meta
+            .custom_sections
+            .iter()
+            .filter_map(|section| Some((section.name.as_str(), self.custom_section_data(section)?)))
+    }
+
+    /// Payload of the first custom section with the given name.
+    pub fn custom_section(&self, name: &str) -> Option<&[u8]> {
+        self.meta
+            .custom_sections
+            .iter()
+            .find(|section| section.name == name)
+            .and_then(|section| self.custom_section_data(section))
+    }
+
+    /// `None` if the section lies outside of the source, which only happens for corrupted metadata.
+    fn custom_section_data(&self, section: &CustomSection) -> Option<&[u8]> {
+        let source = self.source.get();
+        let end = section
+            .offset
+            .checked_add(section.size)
+            .filter(|end| *end <= source.len())?;
+        source.get(section.offset..end)
+    }
+
     pub fn load_all_functions(
         &self,
         loader: impl FunctionLoaderInterface,
@@ -0,0 +1,40 @@
+use rkyv::{Archive, Deserialize, Serialize};
+use std::collections::HashMap;
+use wasm_types::{FuncIdx, GlobalIdx, LocalIdx};
+
+/// https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+///
+/// Debug names of the "name" custom section, only used for diagnostics. Modules without a name section
+/// (or with a malformed one) simply have no names.
+#[derive(Debug, Clone, Default, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct WasmNames {
+    pub module: Option<String>,
+    pub functions: HashMap<FuncIdx, String>,
+    pub locals: HashMap<FuncIdx, HashMap<LocalIdx, String>>,
+    pub globals: HashMap<GlobalIdx, String>,
+}
+
+impl WasmNames {
+    pub fn is_empty(&self) -> bool {
+        self.module.is_none()
+            && self.functions.is_empty()
+            && self.locals.is_empty()
+            && self.globals.is_empty()
+    }
+
+    pub fn function_name(&self, idx: FuncIdx) -> Option<&str> {
+        self.functions.get(&idx).map(String::as_str)
+    }
+
+    pub fn local_name(&self, func_idx: FuncIdx, idx: LocalIdx) -> Option<&str> {
+        self.locals
+            .get(&func_idx)
+            .and_then(|locals| locals.get(&idx))
+            .map(String::as_str)
+    }
+
+    pub fn global_name(&self, idx: GlobalIdx) -> Option<&str> {
+        self.globals.get(&idx).map(String::as_str)
+    }
+}
@@ -1,8 +1,12 @@
+use super::value::ConstantValue;
 use rkyv::{Archive, Deserialize, Serialize};
 use wasm_types::TableType;
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Table {
     pub r#type: TableType,
     pub import: bool,
+    /// initial value of all elements, `None` means null
+    pub init: Option<ConstantValue>,
 }
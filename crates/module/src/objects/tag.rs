@@ -0,0 +1,11 @@
+use rkyv::{Archive, Deserialize, Serialize};
+use wasm_types::TypeIdx;
+
+/// https://webassembly.github.io/exception-handling/core/syntax/modules.html#tags
+#[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct Tag {
+    /// function type of the exception payload, its results are always empty
+    pub type_idx: TypeIdx,
+    pub import: bool,
+}
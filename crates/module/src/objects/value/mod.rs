@@ -3,12 +3,13 @@
 use crate::utils::numeric_transmutes::{Bit32, Bit64};
 use rkyv::{Archive, Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
-use wasm_types::{FuncIdx, GlobalIdx, NumType, RefType, ValType};
+use wasm_types::{FuncIdx, GlobalIdx, HeapType, IBinaryOp, NumType, RefType, TypeIdx, ValType};
 
 mod number_impls;
 mod number_ops;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum Number {
     I32(u32),
     I64(u64),
@@ -47,10 +48,71 @@ pub type FunctionReference = FuncIdx;
 pub type ExternReference = u32;
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum Reference {
     Null,
     Function(FuncIdx),
     Extern(u64),
+    /// opaque handle of a caught exception
+    Exception(u64),
+    /// reference of the `any` hierarchy, see [`gc`]
+    Gc(u64),
+}
+
+/// Runtime representation of references of the `any` hierarchy (GC proposal).
+///
+/// Struct and array references are tagged slot indices into the GC heap of the cluster, i31 references carry their
+/// value next to another tag. Concrete struct and array references share their heap types with function references
+/// and are told apart by the tag. Internalized extern references keep their untagged value.
+///
+/// Object references also carry the generation of their slot, which changes whenever the slot is freed, so
+/// references to collected objects are detected instead of aliasing the next object in the slot.
+pub mod gc {
+    pub const NULL: u64 = u64::MAX;
+    pub const OBJECT_TAG: u64 = 1 << 62;
+    pub const I31_TAG: u64 = 1 << 61;
+    /// generations are stored below the tags and wrap around
+    pub const GENERATION_MASK: u32 = (1 << 29) - 1;
+
+    #[inline]
+    pub const fn object(slot: u32, generation: u32) -> u64 {
+        OBJECT_TAG | ((generation & GENERATION_MASK) as u64) << 32 | slot as u64
+    }
+
+    #[inline]
+    pub const fn is_object(raw: u64) -> bool {
+        raw != NULL && raw & OBJECT_TAG != 0
+    }
+
+    #[inline]
+    pub const fn object_slot(raw: u64) -> u32 {
+        raw as u32
+    }
+
+    #[inline]
+    pub const fn object_generation(raw: u64) -> u32 {
+        (raw >> 32) as u32 & GENERATION_MASK
+    }
+
+    /// Truncates `value` to 31 bits.
+    #[inline]
+    pub const fn i31(value: u32) -> u64 {
+        I31_TAG | (value & 0x7FFF_FFFF) as u64
+    }
+
+    #[inline]
+    pub const fn is_i31(raw: u64) -> bool {
+        raw != NULL && raw & OBJECT_TAG == 0 && raw & I31_TAG != 0
+    }
+
+    #[inline]
+    pub const fn i31_get(raw: u64, signed: bool) -> u32 {
+        let value = raw as u32 & 0x7FFF_FFFF;
+        match signed {
+            true => ((value << 1) as i32 >> 1) as u32,
+            false => value,
+        }
+    }
 }
 
 impl Reference {
@@ -59,6 +121,8 @@ impl Reference {
             Reference::Null => 0,
             Reference::Function(f) => f as u64,
             Reference::Extern(e) => e as u64,
+            Reference::Exception(e) => e,
+            Reference::Gc(r) => r,
         }
     }
 
@@ -66,9 +130,9 @@ impl Reference {
         if n == 0 {
             return Reference::Null;
         }
-        match t {
+        match t.untyped() {
             RefType::FunctionReference => Reference::Function(n as FunctionReference),
-            RefType::ExternReference => panic!("Cannot create pointer from u32"),
+            _ => panic!("Cannot create pointer from u32"),
         }
     }
 }
@@ -79,11 +143,17 @@ impl Display for Reference {
             Reference::Null => write!(f, "null"),
             Reference::Function(idx) => write!(f, "func[{}]", *idx as u64),
             Reference::Extern(idx) => write!(f, "extern[{}]", *idx),
+            Reference::Exception(handle) => write!(f, "exn[{:#x}]", *handle),
+            Reference::Gc(r) if gc::is_i31(*r) => {
+                write!(f, "i31[{}]", gc::i31_get(*r, true) as i32)
+            }
+            Reference::Gc(r) => write!(f, "any[{:#x}]", *r),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum Value {
     Number(Number),
     Vector(Vector),
@@ -94,7 +164,8 @@ impl Value {
     pub fn trans_to_u64(&self) -> u64 {
         match self {
             Value::Number(n) => n.trans_to_u64(),
-            Value::Vector(_) => todo!("Vector to u64 lmao"),
+            // only the low 64 bits of a vector fit
+            Value::Vector(v) => u64::from_le_bytes(v[..8].try_into().unwrap()),
             Value::Reference(r) => r.as_u64(),
         }
     }
@@ -103,7 +174,7 @@ impl Value {
         match t {
             ValType::Number(t) => Value::Number(Number::trans_from_u64(n, &t)),
             ValType::Reference(t) => Value::Reference(Reference::from_u32(n as u32, &t)),
-            ValType::VecType => todo!(),
+            ValType::VecType => Value::Vector((n as u128).to_le_bytes()),
         }
     }
 }
@@ -134,6 +205,10 @@ impl Value {
                 ValType::Reference(RefType::FunctionReference)
             }
             Value::Reference(Reference::Extern(_)) => ValType::Reference(RefType::ExternReference),
+            Value::Reference(Reference::Exception(_)) => ValType::exnref(),
+            Value::Reference(Reference::Gc(_)) => {
+                ValType::Reference(RefType::new(HeapType::Any, true))
+            }
             Value::Reference(Reference::Null) => ValType::Reference(RefType::FunctionReference),
         }
     }
@@ -322,6 +397,8 @@ impl From<Value> for ValueRaw {
             Value::Vector(val) => ValueRaw::v128(val),
             Value::Reference(Reference::Function(val)) => ValueRaw::funcref(val),
             Value::Reference(Reference::Extern(val)) => ValueRaw::externref(val),
+            Value::Reference(Reference::Exception(val)) => ValueRaw::u64(val),
+            Value::Reference(Reference::Gc(val)) => ValueRaw::u64(val),
             Value::Reference(Reference::Null) => ValueRaw::u64(u64::MAX),
         }
     }
@@ -442,16 +519,102 @@ impl Value {
                     Value::funcref(raw.as_funcref())
                 }
             }
+            ValType::Reference(RefType::ExceptionReference) => {
+                if raw.as_u64() == ValueRaw::from(Value::Reference(Reference::Null)).as_u64() {
+                    Value::Reference(Reference::Null)
+                } else {
+                    Value::Reference(Reference::Exception(raw.as_u64()))
+                }
+            }
+            ValType::Reference(ref_type @ RefType::Typed { heap_type, .. }) => {
+                let is_gc_ref = match heap_type {
+                    HeapType::Any
+                    | HeapType::Eq
+                    | HeapType::I31
+                    | HeapType::Struct
+                    | HeapType::Array
+                    | HeapType::None => true,
+                    HeapType::Concrete(_) => gc::is_object(raw.as_u64()),
+                    _ => false,
+                };
+                if raw.as_u64() == gc::NULL {
+                    Value::Reference(Reference::Null)
+                } else if is_gc_ref {
+                    Value::Reference(Reference::Gc(raw.as_u64()))
+                } else {
+                    Value::from_raw(raw, ValType::Reference(ref_type.untyped()))
+                }
+            }
             ValType::VecType => Value::v128(raw.as_v128()),
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ConstantValue {
     V(Value),
-    // we can't resolve the value of imported globals at parsing time
+    // we can't resolve the value of globals at parsing time
     Global(GlobalIdx),
     // we can't resolve the function pointer at parsing time
     FuncPtr(FuncIdx),
+    // extended constant expression depending on globals or allocating objects, in postfix order
+    Expr(Vec<ConstantOp>),
+}
+
+/// Operation of an extended constant expression.
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum ConstantOp {
+    Const(Value),
+    Global(GlobalIdx, ValType),
+    IBinary(NumType, IBinaryOp),
+    RefNull(HeapType),
+    /// allocates an object from the topmost values, see [`ConstantAllocation::num_operands`]
+    Alloc(ConstantAllocation),
+}
+
+/// Allocation of a struct or array of the GC proposal in a constant expression.
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum ConstantAllocation {
+    StructNew { type_idx: TypeIdx, fields: u32 },
+    StructNewDefault(TypeIdx),
+    ArrayNew(TypeIdx),
+    ArrayNewDefault(TypeIdx),
+    ArrayNewFixed { type_idx: TypeIdx, len: u32 },
+}
+
+impl ConstantAllocation {
+    pub fn type_idx(&self) -> TypeIdx {
+        match *self {
+            ConstantAllocation::StructNew { type_idx, .. }
+            | ConstantAllocation::StructNewDefault(type_idx)
+            | ConstantAllocation::ArrayNew(type_idx)
+            | ConstantAllocation::ArrayNewDefault(type_idx)
+            | ConstantAllocation::ArrayNewFixed { type_idx, .. } => type_idx,
+        }
+    }
+
+    /// Number of values taken from the stack: the fields of `struct.new`, the element and length of `array.new`,
+    /// the length of `array.new_default` and the elements of `array.new_fixed`.
+    pub fn num_operands(&self) -> usize {
+        match *self {
+            ConstantAllocation::StructNew { fields, .. } => fields as usize,
+            ConstantAllocation::StructNewDefault(_) => 0,
+            ConstantAllocation::ArrayNew(_) => 2,
+            ConstantAllocation::ArrayNewDefault(_) => 1,
+            ConstantAllocation::ArrayNewFixed { len, .. } => len as usize,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConstantAllocation::StructNew { .. } => "struct.new",
+            ConstantAllocation::StructNewDefault(_) => "struct.new_default",
+            ConstantAllocation::ArrayNew(_) => "array.new",
+            ConstantAllocation::ArrayNewDefault(_) => "array.new_default",
+            ConstantAllocation::ArrayNewFixed { .. } => "array.new_fixed",
+        }
+    }
 }
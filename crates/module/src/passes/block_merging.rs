@@ -0,0 +1,139 @@
+use super::{resolve, DecodedBlock, DecodedFunction, FunctionPass};
+use crate::{basic_block::BasicBlockGlue, instructions::VariableID};
+use std::collections::{HashMap, HashSet};
+
+/// Removes blocks that can't be reached from the entry block and merges blocks into their only predecessor
+/// if it unconditionally jumps to them.
+pub struct BlockMerging;
+
+impl FunctionPass for BlockMerging {
+    fn name(&self) -> &'static str {
+        "block-merging"
+    }
+
+    fn run(&self, function: &mut DecodedFunction) -> bool {
+        let removed = remove_unreachable_blocks(function);
+        removed | merge_blocks(function)
+    }
+}
+
+fn remove_unreachable_blocks(function: &mut DecodedFunction) -> bool {
+    let mut reachable = HashSet::from([function.bbs[0].id]);
+    let mut worklist = vec![function.bbs[0].id];
+    while let Some(id) = worklist.pop() {
+        let Some(position) = function.position(id) else {
+            continue;
+        };
+        for target in function.bbs[position].terminator.targets() {
+            if reachable.insert(target) {
+                worklist.push(target);
+            }
+        }
+    }
+    if reachable.len() == function.bbs.len() {
+        return false;
+    }
+
+    function.bbs.retain(|bb| reachable.contains(&bb.id));
+    for phi in function.bbs.iter_mut().flat_map(|bb| bb.inputs.iter_mut()) {
+        phi.inputs.retain(|(pred, _)| reachable.contains(pred));
+    }
+    true
+}
+
+fn merge_blocks(function: &mut DecodedFunction) -> bool {
+    let mut predecessors = function.predecessors();
+    let mut def_positions = function.def_positions();
+    let entry = function.bbs[0].id;
+    let positions: HashMap<_, _> = function
+        .bbs
+        .iter()
+        .enumerate()
+        .map(|(position, bb)| (bb.id, position))
+        .collect();
+    let mut bbs: Vec<Option<DecodedBlock>> = std::mem::take(&mut function.bbs)
+        .into_iter()
+        .map(Some)
+        .collect();
+    let mut replacements: HashMap<VariableID, VariableID> = HashMap::new();
+
+    let mut changed = false;
+    for position in 0..bbs.len() {
+        loop {
+            let Some(DecodedBlock {
+                id: pred_id,
+                terminator: BasicBlockGlue::Jmp { target, .. },
+                ..
+            }) = bbs[position]
+            else {
+                break;
+            };
+            let target_position = positions[&target];
+            let only_pred = predecessors
+                .get(&target)
+                .is_some_and(|preds| preds.as_slice() == [pred_id]);
+            if target == entry || !only_pred || target_position <= position {
+                break;
+            }
+            let bb = bbs[target_position].as_mut().unwrap();
+            // blocks are translated in order, so variables used by the merged block have to be defined before
+            // or in the predecessor
+            let uses_defined = bb
+                .instructions
+                .iter_mut()
+                .flat_map(|instruction| instruction.uses_mut())
+                .chain(bb.terminator.uses_mut())
+                .all(|var| {
+                    let var = resolve(&replacements, *var);
+                    def_positions[var].is_some_and(|def| def <= position || def == target_position)
+                });
+            if !uses_defined {
+                break;
+            }
+
+            let bb = bbs[target_position].take().unwrap();
+            for phi in bb.inputs.iter() {
+                debug_assert_eq!(phi.inputs.len(), 1);
+                let input = resolve(&replacements, phi.inputs[0].1);
+                replacements.insert(phi.out, input);
+                def_positions[phi.out] = def_positions[input];
+            }
+            for instruction in bb.instructions.iter() {
+                if let Some(var) = instruction.def() {
+                    def_positions[var] = Some(position);
+                }
+            }
+            for var in bb.terminator.defs() {
+                def_positions[var] = Some(position);
+            }
+            // successors are now entered from the predecessor
+            for successor in bb.terminator.targets() {
+                if let Some(preds) = predecessors.get_mut(&successor) {
+                    preds
+                        .iter_mut()
+                        .filter(|id| **id == bb.id)
+                        .for_each(|id| *id = pred_id);
+                }
+                let Some(successor) = bbs[positions[&successor]].as_mut() else {
+                    continue;
+                };
+                for phi in successor.inputs.iter_mut() {
+                    phi.inputs
+                        .iter_mut()
+                        .filter(|(id, _)| *id == bb.id)
+                        .for_each(|(id, _)| *id = pred_id);
+                }
+            }
+
+            let pred = bbs[position].as_mut().unwrap();
+            pred.instructions.extend(bb.instructions);
+            pred.terminator = bb.terminator;
+            pred.storage_terminator = bb.storage_terminator;
+            changed = true;
+        }
+    }
+
+    function.bbs = bbs.into_iter().flatten().collect();
+    function.replace_variables(&replacements);
+    changed
+}
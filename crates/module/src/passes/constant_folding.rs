@@ -0,0 +1,286 @@
+use super::{DecodedFunction, FunctionPass};
+use crate::{
+    basic_block::{BasicBlockGlue, BasicBlockID},
+    instructions::{Constant, IRInstruction, VariableID},
+    objects::value::ValueRaw,
+};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use wasm_types::{IBinaryOp, IRelationalOp, ITestOp, IUnaryOp, NumType, ValType};
+
+/// Evaluates integer instructions whose inputs are constants and turns conditional jumps on constants into
+/// unconditional ones. Instructions that would trap are kept.
+pub struct ConstantFolding;
+
+/// Integer constant, `i32` values are zero extended.
+type IntConstant = (NumType, u64);
+
+impl FunctionPass for ConstantFolding {
+    fn name(&self) -> &'static str {
+        "constant-folding"
+    }
+
+    fn run(&self, function: &mut DecodedFunction) -> bool {
+        let mut constants: HashMap<VariableID, IntConstant> = HashMap::new();
+        for instruction in function.bbs.iter().flat_map(|bb| bb.instructions.iter()) {
+            if let IRInstruction::Constant(c) = instruction {
+                if let Some(value) = int_constant(c) {
+                    constants.insert(c.out1, value);
+                }
+            }
+        }
+
+        let mut changed = false;
+        for bb in function.bbs.iter_mut() {
+            // phis receiving the same constant from all predecessors
+            let mut phi_constants = Vec::new();
+            bb.inputs.retain(|phi| {
+                let mut values = phi.inputs.iter().map(|(_, var)| constants.get(var));
+                let Some(Some(first)) = values.next() else {
+                    return true;
+                };
+                if !values.all(|value| value == Some(first)) {
+                    return true;
+                }
+                debug_assert_eq!(phi.r#type, ValType::Number(first.0));
+                phi_constants.push((phi.out, *first));
+                false
+            });
+            changed |= !phi_constants.is_empty();
+            let phi_constants = phi_constants.into_iter().map(|(out1, value)| {
+                constants.insert(out1, value);
+                IRInstruction::Constant(constant(out1, value))
+            });
+            bb.instructions
+                .splice(0..0, phi_constants.collect::<Vec<_>>());
+
+            for instruction in bb.instructions.iter_mut() {
+                if let Some(value) = fold(instruction, &constants) {
+                    let out1 = instruction.def().unwrap();
+                    constants.insert(out1, value);
+                    *instruction = IRInstruction::Constant(constant(out1, value));
+                    changed = true;
+                }
+            }
+        }
+
+        changed | fold_branches(function, &constants)
+    }
+}
+
+/// Replaces conditional jumps and jump tables on constants by jumps and removes the phi inputs of the targets
+/// that are no longer taken.
+fn fold_branches(
+    function: &mut DecodedFunction,
+    constants: &HashMap<VariableID, IntConstant>,
+) -> bool {
+    // (block, taken target, targets that are no longer jumped to)
+    let mut folded: Vec<(BasicBlockID, BasicBlockID, SmallVec<[BasicBlockID; 2]>)> = Vec::new();
+    for bb in function.bbs.iter_mut() {
+        let (target, output_vars) = match &bb.terminator {
+            BasicBlockGlue::JmpCond {
+                cond_var,
+                target_if_true,
+                target_if_false,
+                output_vars,
+            } => match constants.get(cond_var) {
+                Some((_, 0)) => (*target_if_false, output_vars.clone()),
+                Some(_) => (*target_if_true, output_vars.clone()),
+                None => continue,
+            },
+            BasicBlockGlue::JmpTable {
+                selector_var,
+                targets,
+                targets_output_vars,
+                default_target,
+                default_output_vars,
+            } => match constants.get(selector_var) {
+                Some(&(_, selector)) => match targets.get(selector as usize) {
+                    Some(target) => (*target, targets_output_vars[selector as usize].clone()),
+                    None => (*default_target, default_output_vars.clone()),
+                },
+                None => continue,
+            },
+            _ => continue,
+        };
+        let mut untaken: SmallVec<[BasicBlockID; 2]> = bb
+            .terminator
+            .targets()
+            .into_iter()
+            .filter(|&other| other != target)
+            .collect();
+        untaken.sort_unstable();
+        untaken.dedup();
+        folded.push((bb.id, target, untaken));
+        bb.terminator = BasicBlockGlue::Jmp {
+            target,
+            output_vars,
+        };
+    }
+
+    for (pred, target, untaken) in folded.iter() {
+        for bb in function.bbs.iter_mut() {
+            if bb.id == *target {
+                // conditional jumps with identical targets add one input per target
+                for phi in bb.inputs.iter_mut() {
+                    let mut seen = false;
+                    phi.inputs.retain(|(bb, _)| {
+                        let duplicate = *bb == *pred && seen;
+                        seen |= *bb == *pred;
+                        !duplicate
+                    });
+                }
+            } else if untaken.contains(&bb.id) {
+                for phi in bb.inputs.iter_mut() {
+                    phi.inputs.retain(|(bb, _)| *bb != *pred);
+                }
+            }
+        }
+    }
+    !folded.is_empty()
+}
+
+fn int_constant(c: &Constant) -> Option<IntConstant> {
+    match c.out1_type {
+        NumType::I32 => Some((NumType::I32, c.imm.as_u32() as u64)),
+        NumType::I64 => Some((NumType::I64, c.imm.as_u64())),
+        NumType::F32 | NumType::F64 => None,
+    }
+}
+
+fn constant(out1: VariableID, (out1_type, value): IntConstant) -> Constant {
+    let imm = match out1_type {
+        NumType::I32 => ValueRaw::u32(value as u32),
+        _ => ValueRaw::u64(value),
+    };
+    Constant {
+        imm,
+        out1,
+        out1_type,
+    }
+}
+
+fn fold(
+    instruction: &IRInstruction,
+    constants: &HashMap<VariableID, IntConstant>,
+) -> Option<IntConstant> {
+    let get = |var: &VariableID| constants.get(var).map(|&(_, value)| value);
+    let value = match instruction {
+        IRInstruction::IBinary(i) => {
+            let (lhs, rhs) = (get(&i.lhs)?, get(&i.rhs)?);
+            match i.types {
+                NumType::I32 => (
+                    NumType::I32,
+                    ibinary_32(&i.op, lhs as u32, rhs as u32, rhs as u32)? as u64,
+                ),
+                NumType::I64 => (NumType::I64, ibinary_64(&i.op, lhs, rhs, rhs as u32)?),
+                _ => return None,
+            }
+        }
+        IRInstruction::IUnary(i) => {
+            let in1 = get(&i.in1)?;
+            match i.types {
+                NumType::I32 => (NumType::I32, iunary_32(&i.op, in1 as u32) as u64),
+                NumType::I64 => (NumType::I64, iunary_64(&i.op, in1)),
+                _ => return None,
+            }
+        }
+        IRInstruction::IRelational(i) => {
+            let (in1, in2) = (get(&i.in1)?, get(&i.in2)?);
+            let res = match i.input_types {
+                NumType::I32 => irelational_32(&i.op, in1 as u32, in2 as u32),
+                NumType::I64 => irelational_64(&i.op, in1, in2),
+                _ => return None,
+            };
+            (NumType::I32, res as u64)
+        }
+        IRInstruction::ITest(i) => match i.op {
+            ITestOp::Eqz => (NumType::I32, (get(&i.in1)? == 0) as u64),
+        },
+        IRInstruction::Wrap(i) => (NumType::I32, get(&i.in1)? as u32 as u64),
+        IRInstruction::ExtendType(i) => {
+            let in1 = get(&i.in1)? as u32;
+            match i.signed {
+                true => (NumType::I64, in1 as i32 as i64 as u64),
+                false => (NumType::I64, in1 as u64),
+            }
+        }
+        IRInstruction::ExtendBits(i) => {
+            let in1 = get(&i.in1)?;
+            match i.out1_type {
+                NumType::I32 => {
+                    let shift = 32 - i.input_size as u32;
+                    (NumType::I32, ((in1 as i32) << shift >> shift) as u32 as u64)
+                }
+                NumType::I64 => {
+                    let shift = 64 - i.input_size as u32;
+                    (NumType::I64, ((in1 as i64) << shift >> shift) as u64)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+macro_rules! integer_folding {
+    ($ibinary:ident, $iunary:ident, $irelational:ident, $unsigned:ty, $signed:ty) => {
+        /// `None` if the instruction traps. `shift` is `rhs` truncated to 32 bits, shift and rotation counts
+        /// are taken modulo the bit width.
+        fn $ibinary(
+            op: &IBinaryOp,
+            lhs: $unsigned,
+            rhs: $unsigned,
+            shift: u32,
+        ) -> Option<$unsigned> {
+            let res = match op {
+                IBinaryOp::Add => lhs.wrapping_add(rhs),
+                IBinaryOp::Sub => lhs.wrapping_sub(rhs),
+                IBinaryOp::Mul => lhs.wrapping_mul(rhs),
+                // division by zero and overflow trap
+                IBinaryOp::DivS => (lhs as $signed).checked_div(rhs as $signed)? as $unsigned,
+                IBinaryOp::DivU => lhs.checked_div(rhs)?,
+                IBinaryOp::RemS if rhs == 0 => return None,
+                IBinaryOp::RemS => (lhs as $signed).wrapping_rem(rhs as $signed) as $unsigned,
+                IBinaryOp::RemU => lhs.checked_rem(rhs)?,
+                IBinaryOp::And => lhs & rhs,
+                IBinaryOp::Or => lhs | rhs,
+                IBinaryOp::Xor => lhs ^ rhs,
+                IBinaryOp::Shl => lhs.wrapping_shl(shift),
+                IBinaryOp::ShrS => (lhs as $signed).wrapping_shr(shift) as $unsigned,
+                IBinaryOp::ShrU => lhs.wrapping_shr(shift),
+                IBinaryOp::Rotl => lhs.rotate_left(shift),
+                IBinaryOp::Rotr => lhs.rotate_right(shift),
+            };
+            Some(res)
+        }
+
+        fn $iunary(op: &IUnaryOp, in1: $unsigned) -> $unsigned {
+            match op {
+                IUnaryOp::Clz => in1.leading_zeros().into(),
+                IUnaryOp::Ctz => in1.trailing_zeros().into(),
+                IUnaryOp::Popcnt => in1.count_ones().into(),
+            }
+        }
+
+        fn $irelational(op: &IRelationalOp, in1: $unsigned, in2: $unsigned) -> bool {
+            let (in1_s, in2_s) = (in1 as $signed, in2 as $signed);
+            match op {
+                IRelationalOp::Eq => in1 == in2,
+                IRelationalOp::Ne => in1 != in2,
+                IRelationalOp::LtS => in1_s < in2_s,
+                IRelationalOp::LtU => in1 < in2,
+                IRelationalOp::GtS => in1_s > in2_s,
+                IRelationalOp::GtU => in1 > in2,
+                IRelationalOp::LeS => in1_s <= in2_s,
+                IRelationalOp::LeU => in1 <= in2,
+                IRelationalOp::GeS => in1_s >= in2_s,
+                IRelationalOp::GeU => in1 >= in2,
+            }
+        }
+    };
+}
+
+integer_folding!(ibinary_32, iunary_32, irelational_32, u32, i32);
+integer_folding!(ibinary_64, iunary_64, irelational_64, u64, i64);
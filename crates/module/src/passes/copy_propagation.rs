@@ -0,0 +1,84 @@
+use super::{resolve, DecodedFunction, FunctionPass};
+use crate::instructions::{IRInstruction, LocalSetInstruction, VariableID};
+use std::collections::HashMap;
+use wasm_types::LocalIdx;
+
+/// Replaces variables that are copies of other variables: reads of locals whose value is already known within
+/// the block, the results of `local.tee` and phis that receive the same variable from all predecessors.
+pub struct CopyPropagation;
+
+impl FunctionPass for CopyPropagation {
+    fn name(&self) -> &'static str {
+        "copy-propagation"
+    }
+
+    fn run(&self, function: &mut DecodedFunction) -> bool {
+        let mut replacements: HashMap<VariableID, VariableID> = HashMap::new();
+        let mut changed = false;
+        for bb in function.bbs.iter_mut() {
+            // locals only change through instructions of the function itself, calls end the block
+            let mut locals: HashMap<LocalIdx, VariableID> = HashMap::new();
+            let mut instructions = Vec::with_capacity(bb.instructions.len());
+            for mut instruction in std::mem::take(&mut bb.instructions) {
+                for var in instruction.uses_mut() {
+                    *var = resolve(&replacements, *var);
+                }
+                match instruction {
+                    IRInstruction::LocalGet(ref i) => match locals.get(&i.local_idx) {
+                        Some(&var) => {
+                            replacements.insert(i.out1, var);
+                            changed = true;
+                            continue;
+                        }
+                        None => {
+                            locals.insert(i.local_idx, i.out1);
+                        }
+                    },
+                    IRInstruction::LocalSet(ref i) => {
+                        locals.insert(i.local_idx, i.in1);
+                    }
+                    IRInstruction::LocalTee(i) => {
+                        replacements.insert(i.out1, i.in1);
+                        locals.insert(i.local_idx, i.in1);
+                        instructions.push(IRInstruction::LocalSet(LocalSetInstruction {
+                            local_idx: i.local_idx,
+                            in1: i.in1,
+                        }));
+                        changed = true;
+                        continue;
+                    }
+                    _ => {}
+                }
+                instructions.push(instruction);
+            }
+            bb.instructions = instructions;
+        }
+
+        // a phi is a copy if all predecessors pass the same variable, apart from the phi itself on back
+        // edges. The variable has to be defined in an earlier block, as backends translate blocks in order.
+        let def_positions = function.def_positions();
+        for (position, bb) in function.bbs.iter_mut().enumerate() {
+            bb.inputs.retain(|phi| {
+                let mut inputs = phi
+                    .inputs
+                    .iter()
+                    .map(|&(_, var)| resolve(&replacements, var))
+                    .filter(|&var| var != phi.out);
+                let Some(first) = inputs.next() else {
+                    return true;
+                };
+                if !inputs.all(|var| var == first)
+                    || !def_positions[first].is_some_and(|def| def < position)
+                {
+                    return true;
+                }
+                replacements.insert(phi.out, first);
+                false
+            });
+        }
+
+        changed |= !replacements.is_empty();
+        function.replace_variables(&replacements);
+        changed
+    }
+}
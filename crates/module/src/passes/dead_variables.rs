@@ -0,0 +1,69 @@
+use super::{DecodedFunction, FunctionPass};
+
+/// Removes phis and side effect free instructions whose variables are never used.
+pub struct DeadVariableElimination;
+
+impl FunctionPass for DeadVariableElimination {
+    fn name(&self) -> &'static str {
+        "dead-variable-elimination"
+    }
+
+    fn run(&self, function: &mut DecodedFunction) -> bool {
+        let mut uses = vec![0usize; function.num_vars];
+        for bb in function.bbs.iter_mut() {
+            for phi in bb.inputs.iter() {
+                // a phi using itself on a back edge doesn't keep it alive
+                for &(_, var) in phi.inputs.iter().filter(|&&(_, var)| var != phi.out) {
+                    uses[var] += 1;
+                }
+            }
+            for instruction in bb.instructions.iter_mut() {
+                for var in instruction.uses_mut() {
+                    uses[*var] += 1;
+                }
+            }
+            for var in bb.terminator.uses_mut() {
+                uses[*var] += 1;
+            }
+        }
+
+        // removing a variable can make the variables it uses dead, so repeat until nothing changes
+        let mut changed = false;
+        loop {
+            let mut removed = false;
+            for bb in function.bbs.iter_mut().rev() {
+                // backwards, so chains of dead instructions within the block are removed at once
+                let mut instructions = Vec::with_capacity(bb.instructions.len());
+                for mut instruction in std::mem::take(&mut bb.instructions).into_iter().rev() {
+                    if instruction.is_pure() && instruction.def().is_some_and(|var| uses[var] == 0)
+                    {
+                        instruction
+                            .uses_mut()
+                            .into_iter()
+                            .for_each(|var| uses[*var] -= 1);
+                        removed = true;
+                    } else {
+                        instructions.push(instruction);
+                    }
+                }
+                instructions.reverse();
+                bb.instructions = instructions;
+                bb.inputs.retain(|phi| {
+                    if uses[phi.out] > 0 {
+                        return true;
+                    }
+                    for &(_, var) in phi.inputs.iter().filter(|&&(_, var)| var != phi.out) {
+                        uses[var] -= 1;
+                    }
+                    removed = true;
+                    false
+                });
+            }
+            if !removed {
+                break;
+            }
+            changed = true;
+        }
+        changed
+    }
+}
@@ -0,0 +1,461 @@
+//! Backend independent optimizations on the IR of functions. Passes work on a decoded copy of a function,
+//! which is encoded back into a [`FunctionIR`] once all passes have run.
+
+mod block_merging;
+mod constant_folding;
+mod copy_propagation;
+mod dead_variables;
+
+pub use block_merging::BlockMerging;
+pub use constant_folding::ConstantFolding;
+pub use copy_propagation::CopyPropagation;
+pub use dead_variables::DeadVariableElimination;
+
+use crate::{
+    basic_block::{BasicBlockGlue, BasicBlockID},
+    instructions::{FunctionIR, IRInstruction, PhiNode, VariableID},
+    objects::instruction::ControlInstruction,
+    BasicBlock, DecodingError, InstructionConsumer, InstructionEncoder, VerifierError,
+};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use wasm_types::{FuncIdx, ValType};
+
+/// Passes are repeated until none of them changes the function anymore, but at most this often.
+const MAX_ROUNDS: usize = 8;
+
+/// A basic block whose instructions are decoded.
+#[derive(Debug, Clone)]
+pub struct DecodedBlock {
+    pub id: BasicBlockID,
+    pub inputs: SmallVec<[PhiNode; 0]>,
+    pub instructions: Vec<IRInstruction>,
+    pub terminator: BasicBlockGlue,
+    // parser state kept in the instruction storage
+    storage_terminator: ControlInstruction,
+    storage_inputs: Vec<PhiNode>,
+}
+
+/// A function whose basic blocks are decoded. Blocks are ordered by their ID and the first one is the entry.
+#[derive(Debug, Clone)]
+pub struct DecodedFunction {
+    pub locals: Vec<ValType>,
+    pub bbs: Vec<DecodedBlock>,
+    pub num_vars: usize,
+}
+
+impl DecodedFunction {
+    pub fn decode(function: &FunctionIR) -> Result<Self, DecodingError> {
+        let bbs = function
+            .bbs
+            .iter()
+            .map(|bb| {
+                Ok(DecodedBlock {
+                    id: bb.id,
+                    inputs: bb.inputs.clone(),
+                    instructions: IRInstruction::decode_all(bb.instructions.clone())?,
+                    terminator: bb.terminator.clone(),
+                    storage_terminator: bb.instructions.terminator.clone(),
+                    storage_inputs: bb.instructions.inputs.clone(),
+                })
+            })
+            .collect::<Result<_, DecodingError>>()?;
+        Ok(DecodedFunction {
+            locals: function.locals.clone(),
+            bbs,
+            num_vars: function.num_vars,
+        })
+    }
+
+    pub fn encode(self) -> FunctionIR {
+        let bbs = self
+            .bbs
+            .into_iter()
+            .map(|bb| {
+                let mut encoder = InstructionEncoder::new();
+                for instruction in bb.instructions {
+                    instruction.encode(&mut encoder);
+                }
+                // makes the immediate storage contiguous, which the decoder relies on
+                encoder.finish(bb.storage_terminator);
+                let mut instructions = encoder.extract_data();
+                instructions.inputs = bb.storage_inputs;
+                BasicBlock {
+                    instructions,
+                    inputs: bb.inputs,
+                    terminator: bb.terminator,
+                    id: bb.id,
+                }
+            })
+            .collect();
+        FunctionIR {
+            locals: self.locals,
+            bbs,
+            num_vars: self.num_vars,
+        }
+    }
+
+    /// Position of the block with ID `id` in [`DecodedFunction::bbs`].
+    pub fn position(&self, id: BasicBlockID) -> Option<usize> {
+        self.bbs.binary_search_by_key(&id, |bb| bb.id).ok()
+    }
+
+    /// Position of the block defining each variable, `None` for variables that are no longer defined.
+    pub fn def_positions(&self) -> Vec<Option<usize>> {
+        let mut positions = vec![None; self.num_vars];
+        for (position, bb) in self.bbs.iter().enumerate() {
+            let defs = bb
+                .inputs
+                .iter()
+                .map(|phi| phi.out)
+                .chain(bb.instructions.iter().filter_map(IRInstruction::def))
+                .chain(bb.terminator.defs());
+            for var in defs {
+                positions[var] = Some(position);
+            }
+        }
+        positions
+    }
+
+    /// Predecessors of each block, once per edge.
+    pub fn predecessors(&self) -> HashMap<BasicBlockID, SmallVec<[BasicBlockID; 2]>> {
+        let mut predecessors: HashMap<_, SmallVec<_>> = HashMap::new();
+        for bb in self.bbs.iter() {
+            for target in bb.terminator.targets() {
+                predecessors.entry(target).or_default().push(bb.id);
+            }
+        }
+        predecessors
+    }
+
+    /// Sets the output variables of jumps to the inputs the phis of their targets receive from them, as passes
+    /// removing phis don't update them. Conditional jumps pass the same variables to both targets, so they are
+    /// taken from the target with phis.
+    pub fn sync_output_vars(&mut self) {
+        let outputs = |target: BasicBlockID, pred: BasicBlockID| -> SmallVec<[VariableID; 0]> {
+            let Some(position) = self.position(target) else {
+                return SmallVec::new();
+            };
+            self.bbs[position]
+                .inputs
+                .iter()
+                .filter_map(|phi| phi.inputs.iter().find(|(bb, _)| *bb == pred))
+                .map(|&(_, var)| var)
+                .collect()
+        };
+        let terminators: Vec<BasicBlockGlue> = self
+            .bbs
+            .iter()
+            .map(|bb| {
+                let mut terminator = bb.terminator.clone();
+                match &mut terminator {
+                    BasicBlockGlue::Jmp {
+                        target,
+                        output_vars,
+                    } => *output_vars = outputs(*target, bb.id),
+                    BasicBlockGlue::JmpCond {
+                        target_if_true,
+                        target_if_false,
+                        output_vars,
+                        ..
+                    } => {
+                        *output_vars = outputs(*target_if_true, bb.id);
+                        if output_vars.is_empty() {
+                            *output_vars = outputs(*target_if_false, bb.id);
+                        }
+                    }
+                    BasicBlockGlue::JmpTable {
+                        targets,
+                        targets_output_vars,
+                        default_target,
+                        default_output_vars,
+                        ..
+                    } => {
+                        for (target, output_vars) in
+                            targets.iter().zip(targets_output_vars.iter_mut())
+                        {
+                            *output_vars = outputs(*target, bb.id);
+                        }
+                        *default_output_vars = outputs(*default_target, bb.id);
+                    }
+                    _ => {}
+                }
+                terminator
+            })
+            .collect();
+        for (bb, terminator) in self.bbs.iter_mut().zip(terminators) {
+            bb.terminator = terminator;
+        }
+    }
+
+    /// Replaces every use of a variable by its replacement. Replacements may be chained.
+    pub fn replace_variables(&mut self, replacements: &HashMap<VariableID, VariableID>) {
+        if replacements.is_empty() {
+            return;
+        }
+        let resolve = |var: &mut VariableID| *var = resolve(replacements, *var);
+        for bb in self.bbs.iter_mut() {
+            for phi in bb.inputs.iter_mut() {
+                phi.inputs.iter_mut().for_each(|(_, var)| resolve(var));
+            }
+            for instruction in bb.instructions.iter_mut() {
+                instruction.uses_mut().into_iter().for_each(resolve);
+            }
+            bb.terminator.uses_mut().into_iter().for_each(resolve);
+        }
+    }
+}
+
+/// Follows a chain of replacements to the variable replacing `var`.
+fn resolve(replacements: &HashMap<VariableID, VariableID>, mut var: VariableID) -> VariableID {
+    while let Some(&replacement) = replacements.get(&var) {
+        var = replacement;
+    }
+    var
+}
+
+/// An optimization on a single function.
+pub trait FunctionPass {
+    fn name(&self) -> &'static str;
+
+    /// Returns whether the function was changed.
+    fn run(&self, function: &mut DecodedFunction) -> bool;
+}
+
+/// Runs a pipeline of passes on functions until they no longer change them.
+pub struct PassManager {
+    passes: Vec<Box<dyn FunctionPass + Send + Sync>>,
+    verify: bool,
+}
+
+impl Default for PassManager {
+    /// The standard pipeline.
+    fn default() -> Self {
+        Self::new()
+            .with_pass(BlockMerging)
+            .with_pass(CopyPropagation)
+            .with_pass(ConstantFolding)
+            .with_pass(DeadVariableElimination)
+    }
+}
+
+impl PassManager {
+    /// A pass manager without any passes.
+    pub fn new() -> Self {
+        Self {
+            passes: Vec::new(),
+            verify: false,
+        }
+    }
+
+    pub fn with_pass(mut self, pass: impl FunctionPass + Send + Sync + 'static) -> Self {
+        self.passes.push(Box::new(pass));
+        self
+    }
+
+    /// Names of the passes in the order they run.
+    pub fn pass_names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.passes.iter().map(|pass| pass.name())
+    }
+
+    /// Verifies functions after every pass that changed them, which debug builds always do.
+    pub fn with_verification(mut self, verify: bool) -> Self {
+        self.verify = verify;
+        self
+    }
+
+    /// Optimizes a function. The empty IR of imported functions is left as is.
+    pub fn run(&self, function: &mut FunctionIR) -> Result<(), VerifierError> {
+        if function.bbs.is_empty() || self.passes.is_empty() {
+            return Ok(());
+        }
+        let verify = self.verify || cfg!(debug_assertions);
+        let mut decoded = DecodedFunction::decode(function)?;
+        for _ in 0..MAX_ROUNDS {
+            let mut changed = false;
+            for pass in self.passes.iter() {
+                if !pass.run(&mut decoded) {
+                    continue;
+                }
+                changed = true;
+                if verify {
+                    decoded.sync_output_vars();
+                    decoded.verify().map_err(|e| VerifierError::AfterPass {
+                        pass: pass.name(),
+                        error: Box::new(e),
+                    })?;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+        decoded.sync_output_vars();
+        *function = decoded.encode();
+        Ok(())
+    }
+
+    /// Optimizes all functions of a module, indexed by function index.
+    pub fn run_all(&self, functions: &mut [FunctionIR]) -> Result<(), VerifierError> {
+        functions
+            .iter_mut()
+            .enumerate()
+            .try_for_each(|(func_idx, function)| {
+                self.run(function)
+                    .map_err(|e| e.in_function(func_idx as FuncIdx))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{Constant, IBinaryInstruction, LocalGetInstruction},
+        objects::value::ValueRaw,
+    };
+    use smallvec::smallvec;
+    use wasm_types::{IBinaryOp, NumType};
+
+    fn block(
+        id: BasicBlockID,
+        inputs: SmallVec<[PhiNode; 0]>,
+        instructions: Vec<IRInstruction>,
+        terminator: BasicBlockGlue,
+    ) -> DecodedBlock {
+        DecodedBlock {
+            id,
+            inputs,
+            instructions,
+            terminator,
+            storage_terminator: ControlInstruction::default(),
+            storage_inputs: Vec::new(),
+        }
+    }
+
+    fn i32_const(out1: VariableID, value: i32) -> IRInstruction {
+        IRInstruction::Constant(Constant {
+            imm: ValueRaw::from(value),
+            out1,
+            out1_type: NumType::I32,
+        })
+    }
+
+    fn i32_binary(
+        op: IBinaryOp,
+        lhs: VariableID,
+        rhs: VariableID,
+        out1: VariableID,
+    ) -> IRInstruction {
+        IRInstruction::IBinary(IBinaryInstruction {
+            types: NumType::I32,
+            op,
+            lhs,
+            rhs,
+            out1,
+        })
+    }
+
+    fn local_get(local_idx: u32, out1: VariableID) -> IRInstruction {
+        IRInstruction::LocalGet(LocalGetInstruction { local_idx, out1 })
+    }
+
+    /// `(param i32) (result i32 i32)`: returns `2 + 3` and `local 0 * local 0` from a second block
+    fn function() -> FunctionIR {
+        let i32 = ValType::Number(NumType::I32);
+        DecodedFunction {
+            locals: vec![i32],
+            bbs: vec![
+                block(
+                    0,
+                    smallvec![],
+                    vec![
+                        local_get(0, 0),
+                        local_get(0, 1),
+                        i32_const(2, 2),
+                        i32_const(3, 3),
+                        i32_binary(IBinaryOp::Add, 2, 3, 4),
+                        i32_binary(IBinaryOp::Mul, 0, 1, 5),
+                        // unused, but `0 / 0` traps and is kept
+                        i32_const(6, 0),
+                        i32_binary(IBinaryOp::DivU, 6, 6, 7),
+                        i32_binary(IBinaryOp::Sub, 0, 1, 8),
+                    ],
+                    BasicBlockGlue::Jmp {
+                        target: 1,
+                        output_vars: smallvec![4, 5],
+                    },
+                ),
+                block(
+                    1,
+                    smallvec![
+                        PhiNode {
+                            inputs: smallvec![(0, 4)],
+                            out: 9,
+                            r#type: i32,
+                        },
+                        PhiNode {
+                            inputs: smallvec![(0, 5)],
+                            out: 10,
+                            r#type: i32,
+                        },
+                    ],
+                    Vec::new(),
+                    BasicBlockGlue::Return {
+                        return_vars: smallvec![9, 10],
+                    },
+                ),
+            ],
+            num_vars: 11,
+        }
+        .encode()
+    }
+
+    #[test]
+    fn test_standard_pipeline() {
+        let mut function = function();
+        PassManager::default()
+            .with_verification(true)
+            .run(&mut function)
+            .unwrap();
+        let function = DecodedFunction::decode(&function).unwrap();
+        function.verify().unwrap();
+
+        // the blocks are merged and the copies of the phis and of local 0 are replaced
+        let [bb] = &function.bbs[..] else {
+            panic!("blocks weren't merged: {function:?}");
+        };
+        assert!(bb.inputs.is_empty());
+        let BasicBlockGlue::Return { return_vars } = &bb.terminator else {
+            panic!("unexpected terminator {:?}", bb.terminator);
+        };
+        assert_eq!(return_vars.as_slice(), [4, 5]);
+
+        let instructions = bb
+            .instructions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        let expected = [
+            local_get(0, 0),
+            i32_const(4, 5),
+            i32_binary(IBinaryOp::Mul, 0, 0, 5),
+            i32_const(6, 0),
+            i32_binary(IBinaryOp::DivU, 6, 6, 7),
+        ]
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+        assert_eq!(instructions, expected);
+    }
+
+    #[test]
+    fn test_empty_pipeline_keeps_function() {
+        let mut function = function();
+        let before = format!("{:?}", DecodedFunction::decode(&function).unwrap());
+        PassManager::new().run(&mut function).unwrap();
+        let after = format!("{:?}", DecodedFunction::decode(&function).unwrap());
+        assert_eq!(before, after);
+        assert_eq!(PassManager::new().pass_names().count(), 0);
+    }
+}
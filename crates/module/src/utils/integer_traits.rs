@@ -137,6 +137,16 @@ impl Integer for u64 {
     }
 }
 
+impl Integer for u128 {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        u128::from_ne_bytes(<[u8; 16]>::try_from(bytes).unwrap())
+    }
+
+    fn to_bytes(self) -> Vec<u8> {
+        self.to_ne_bytes().to_vec()
+    }
+}
+
 impl Integer for usize {
     fn from_bytes(bytes: &[u8]) -> Self {
         usize::from_ne_bytes(<[u8; std::mem::size_of::<usize>()]>::try_from(bytes).unwrap())
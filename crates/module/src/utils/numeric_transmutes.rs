@@ -13,9 +13,9 @@ pub trait Bit32 {
     }
 
     fn to_reference(&self, r#type: &RefType) -> Reference {
-        match r#type {
+        match r#type.untyped() {
             RefType::FunctionReference => Reference::Function(self.trans_u32()),
-            RefType::ExternReference => panic!("Cannot create pointer from u32"),
+            _ => panic!("Cannot create pointer from u32"),
         }
     }
 }
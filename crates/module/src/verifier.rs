@@ -0,0 +1,805 @@
+//! Consistency checks on the IR of functions, so bugs in IR producers and passes are reported where they happen
+//! instead of crashing the interpreter or the LLVM translation.
+
+use crate::{
+    basic_block::{BasicBlockGlue, BasicBlockID},
+    instructions::{FunctionIR, IRInstruction, VariableID},
+    passes::DecodedFunction,
+    DecodingError,
+};
+use smallvec::{smallvec, SmallVec};
+use std::collections::HashMap;
+use thiserror::Error;
+use wasm_types::{FuncIdx, LocalIdx, ValType};
+
+#[derive(Debug, Error)]
+pub enum VerifierError {
+    #[error("{0}")]
+    DecodingError(#[from] DecodingError),
+    #[error("function has no basic blocks")]
+    NoBasicBlocks,
+    #[error("basic blocks are not sorted by ID at bb{0}")]
+    UnsortedBlocks(BasicBlockID),
+    #[error("bb{bb} continues at bb{target}, which doesn't exist")]
+    UnknownTarget {
+        bb: BasicBlockID,
+        target: BasicBlockID,
+    },
+    #[error("jump table of bb{bb} has {targets} targets but {outputs} lists of output variables")]
+    JumpTableOutputs {
+        bb: BasicBlockID,
+        targets: usize,
+        outputs: usize,
+    },
+    #[error("%{var} in bb{bb} is out of range, the function has {num_vars} variables")]
+    VariableOutOfRange {
+        var: VariableID,
+        bb: BasicBlockID,
+        num_vars: usize,
+    },
+    #[error("%{var} is defined more than once, again in bb{bb}")]
+    Redefined { var: VariableID, bb: BasicBlockID },
+    #[error("%{var} is used in bb{bb} but never defined")]
+    Undefined { var: VariableID, bb: BasicBlockID },
+    #[error("%{var} is used in bb{bb} before it is defined on every path to it")]
+    UsedBeforeDefinition { var: VariableID, bb: BasicBlockID },
+    #[error("local {local_idx} used in bb{bb} doesn't exist")]
+    UnknownLocal {
+        local_idx: LocalIdx,
+        bb: BasicBlockID,
+    },
+    #[error("phi %{phi} in bb{bb} has no input from its predecessor bb{pred}")]
+    MissingPhiInput {
+        phi: VariableID,
+        bb: BasicBlockID,
+        pred: BasicBlockID,
+    },
+    #[error("phi %{phi} in bb{bb} has an input from bb{pred}, which doesn't continue at bb{bb}")]
+    UnexpectedPhiInput {
+        phi: VariableID,
+        bb: BasicBlockID,
+        pred: BasicBlockID,
+    },
+    #[error("phi %{phi} in bb{bb} receives different variables from bb{pred}")]
+    ConflictingPhiInputs {
+        phi: VariableID,
+        bb: BasicBlockID,
+        pred: BasicBlockID,
+    },
+    #[error("bb{pred} passes %{output} to phi %{phi} in bb{bb}, but the phi receives %{input}")]
+    OutputMismatch {
+        pred: BasicBlockID,
+        phi: VariableID,
+        bb: BasicBlockID,
+        output: VariableID,
+        input: VariableID,
+    },
+    #[error("%{var} used in bb{bb} has type {actual}, expected {expected}")]
+    TypeMismatch {
+        var: VariableID,
+        bb: BasicBlockID,
+        expected: ValType,
+        actual: ValType,
+    },
+    #[error("{error} (function {func_idx})")]
+    InFunction {
+        func_idx: FuncIdx,
+        error: Box<VerifierError>,
+    },
+    #[error("{error} (after pass {pass})")]
+    AfterPass {
+        pass: &'static str,
+        error: Box<VerifierError>,
+    },
+}
+
+impl VerifierError {
+    pub fn in_function(self, func_idx: FuncIdx) -> Self {
+        VerifierError::InFunction {
+            func_idx,
+            error: Box::new(self),
+        }
+    }
+}
+
+/// Verifies the IR of a function that has a body.
+pub fn verify_function(function: &FunctionIR) -> Result<(), VerifierError> {
+    DecodedFunction::decode(function)?.verify()
+}
+
+impl DecodedFunction {
+    /// Checks that
+    /// - blocks are sorted by ID and all targets of terminators exist,
+    /// - every variable is below `num_vars`, defined once and defined before its uses on every path from the
+    ///   entry block, which isn't checked for unreachable blocks,
+    /// - phis have inputs from exactly the predecessors of their block, which match the output variables of
+    ///   the jumps,
+    /// - variables have the types expected by their uses, as far as the IR records types.
+    pub fn verify(&self) -> Result<(), VerifierError> {
+        if self.bbs.is_empty() {
+            return Err(VerifierError::NoBasicBlocks);
+        }
+        if let Some(bbs) = self.bbs.windows(2).find(|bbs| bbs[0].id >= bbs[1].id) {
+            return Err(VerifierError::UnsortedBlocks(bbs[1].id));
+        }
+        let mut successors = Vec::with_capacity(self.bbs.len());
+        for bb in self.bbs.iter() {
+            let targets = bb.terminator.targets();
+            let positions = targets
+                .iter()
+                .map(|&target| {
+                    self.position(target)
+                        .ok_or(VerifierError::UnknownTarget { bb: bb.id, target })
+                })
+                .collect::<Result<SmallVec<[usize; 2]>, _>>()?;
+            successors.push(positions);
+        }
+
+        let definitions = self.definitions()?;
+        let types = self.types()?;
+        let dominators = Dominators::new(&successors);
+        let predecessors = self.predecessors();
+
+        for (position, bb) in self.bbs.iter().enumerate() {
+            let check_use = |var: VariableID, order: usize| {
+                self.check_use(&definitions, &dominators, var, position, order)
+            };
+            let check_type = |var: VariableID, expected: ValType| match types[var] {
+                Some(actual) if !compatible(expected, actual) => Err(VerifierError::TypeMismatch {
+                    var,
+                    bb: bb.id,
+                    expected,
+                    actual,
+                }),
+                _ => Ok(()),
+            };
+
+            let preds = predecessors
+                .get(&bb.id)
+                .map_or(&[][..], |preds| preds.as_slice());
+            for phi in bb.inputs.iter() {
+                // jumps to the same block with several edges may add an input per edge, which all have to
+                // agree
+                let mut inputs: HashMap<BasicBlockID, VariableID> = HashMap::new();
+                for &(pred, var) in phi.inputs.iter() {
+                    if !preds.contains(&pred) {
+                        return Err(VerifierError::UnexpectedPhiInput {
+                            phi: phi.out,
+                            bb: bb.id,
+                            pred,
+                        });
+                    }
+                    if *inputs.entry(pred).or_insert(var) != var {
+                        return Err(VerifierError::ConflictingPhiInputs {
+                            phi: phi.out,
+                            bb: bb.id,
+                            pred,
+                        });
+                    }
+                    // the input has to be available at the end of the predecessor
+                    let pred_position = self.position(pred).unwrap();
+                    let end = self.bbs[pred_position].instructions.len() + 2;
+                    self.check_use(&definitions, &dominators, var, pred_position, end)?;
+                    check_type(var, phi.r#type)?;
+                }
+                if let Some(&pred) = preds.iter().find(|pred| !inputs.contains_key(pred)) {
+                    return Err(VerifierError::MissingPhiInput {
+                        phi: phi.out,
+                        bb: bb.id,
+                        pred,
+                    });
+                }
+            }
+
+            for (i, instruction) in bb.instructions.iter().enumerate() {
+                for var in instruction.uses() {
+                    check_use(var, i + 1)?;
+                }
+                let (inputs, _) = signature(instruction, &self.locals, bb.id)?;
+                for (var, expected) in inputs {
+                    check_type(var, expected)?;
+                }
+            }
+
+            let terminator_order = bb.instructions.len() + 1;
+            for var in bb.terminator.uses() {
+                check_use(var, terminator_order)?;
+            }
+            match &bb.terminator {
+                BasicBlockGlue::JmpCond { cond_var, .. } => check_type(*cond_var, ValType::i32())?,
+                BasicBlockGlue::JmpTable { selector_var, .. } => {
+                    check_type(*selector_var, ValType::i32())?
+                }
+                _ => {}
+            }
+            for (target, outputs) in jump_outputs(bb.id, &bb.terminator)? {
+                let target = &self.bbs[self.position(target).unwrap()];
+                for (phi, &output) in target.inputs.iter().zip(outputs.iter()) {
+                    let mismatch = phi
+                        .inputs
+                        .iter()
+                        .find(|&&(pred, input)| pred == bb.id && input != output);
+                    if let Some(&(_, input)) = mismatch {
+                        return Err(VerifierError::OutputMismatch {
+                            pred: bb.id,
+                            phi: phi.out,
+                            bb: target.id,
+                            output,
+                            input,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Block position and order within the block of each variable's definition. Phis come first, instructions
+    /// are numbered from 1 and the terminator follows the last instruction.
+    fn definitions(&self) -> Result<Vec<Option<(usize, usize)>>, VerifierError> {
+        let mut definitions = vec![None; self.num_vars];
+        for (position, bb) in self.bbs.iter().enumerate() {
+            let defs = bb
+                .inputs
+                .iter()
+                .map(|phi| (phi.out, 0))
+                .chain(
+                    bb.instructions
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(i, instruction)| Some((instruction.def()?, i + 1))),
+                )
+                .chain(
+                    bb.terminator
+                        .defs()
+                        .into_iter()
+                        .map(|var| (var, bb.instructions.len() + 1)),
+                );
+            for (var, order) in defs {
+                let definition =
+                    definitions
+                        .get_mut(var)
+                        .ok_or(VerifierError::VariableOutOfRange {
+                            var,
+                            bb: bb.id,
+                            num_vars: self.num_vars,
+                        })?;
+                if definition.is_some() {
+                    return Err(VerifierError::Redefined { var, bb: bb.id });
+                }
+                *definition = Some((position, order));
+            }
+        }
+        Ok(definitions)
+    }
+
+    /// Types of the variables whose definitions record them.
+    fn types(&self) -> Result<Vec<Option<ValType>>, VerifierError> {
+        let mut types = vec![None; self.num_vars];
+        for bb in self.bbs.iter() {
+            for phi in bb.inputs.iter() {
+                types[phi.out] = Some(phi.r#type);
+            }
+            for instruction in bb.instructions.iter() {
+                if let (_, Some((var, r#type))) = signature(instruction, &self.locals, bb.id)? {
+                    types[var] = Some(r#type);
+                }
+            }
+        }
+        Ok(types)
+    }
+
+    fn check_use(
+        &self,
+        definitions: &[Option<(usize, usize)>],
+        dominators: &Dominators,
+        var: VariableID,
+        position: usize,
+        order: usize,
+    ) -> Result<(), VerifierError> {
+        let bb = self.bbs[position].id;
+        let definition = definitions
+            .get(var)
+            .ok_or(VerifierError::VariableOutOfRange {
+                var,
+                bb,
+                num_vars: self.num_vars,
+            })?
+            .ok_or(VerifierError::Undefined { var, bb })?;
+        let defined_before = match definition {
+            _ if !dominators.is_reachable(position) => true,
+            (def_position, def_order) if def_position == position => def_order < order,
+            (def_position, _) => dominators.dominates(def_position, position),
+        };
+        match defined_before {
+            true => Ok(()),
+            false => Err(VerifierError::UsedBeforeDefinition { var, bb }),
+        }
+    }
+}
+
+/// Targets of jumps with the variables passed to their phis. Both targets of a conditional jump receive the
+/// same variables.
+fn jump_outputs(
+    bb: BasicBlockID,
+    terminator: &BasicBlockGlue,
+) -> Result<SmallVec<[(BasicBlockID, &[VariableID]); 2]>, VerifierError> {
+    let outputs = match terminator {
+        BasicBlockGlue::Jmp {
+            target,
+            output_vars,
+        } => smallvec![(*target, output_vars.as_slice())],
+        BasicBlockGlue::JmpCond {
+            target_if_true,
+            target_if_false,
+            output_vars,
+            ..
+        } => smallvec![
+            (*target_if_true, output_vars.as_slice()),
+            (*target_if_false, output_vars.as_slice())
+        ],
+        BasicBlockGlue::JmpTable {
+            targets,
+            targets_output_vars,
+            default_target,
+            default_output_vars,
+            ..
+        } => {
+            if targets.len() != targets_output_vars.len() {
+                return Err(VerifierError::JumpTableOutputs {
+                    bb,
+                    targets: targets.len(),
+                    outputs: targets_output_vars.len(),
+                });
+            }
+            targets
+                .iter()
+                .zip(targets_output_vars.iter())
+                .chain([(default_target, default_output_vars)])
+                .map(|(target, output_vars)| (*target, output_vars.as_slice()))
+                .collect()
+        }
+        _ => SmallVec::new(),
+    };
+    Ok(outputs)
+}
+
+/// Expected types of the instruction's inputs and the type of its result, for instructions recording them.
+#[allow(clippy::type_complexity)]
+fn signature(
+    instruction: &IRInstruction,
+    locals: &[ValType],
+    bb: BasicBlockID,
+) -> Result<
+    (
+        SmallVec<[(VariableID, ValType); 2]>,
+        Option<(VariableID, ValType)>,
+    ),
+    VerifierError,
+> {
+    use IRInstruction as I;
+    use ValType::Number;
+    let local = |local_idx: LocalIdx| {
+        locals
+            .get(local_idx as usize)
+            .copied()
+            .ok_or(VerifierError::UnknownLocal { local_idx, bb })
+    };
+    let signature = match instruction {
+        I::Constant(i) => (smallvec![], Some((i.out1, Number(i.out1_type)))),
+        I::IUnary(i) => (
+            smallvec![(i.in1, Number(i.types))],
+            Some((i.out1, Number(i.types))),
+        ),
+        I::FUnary(i) => (
+            smallvec![(i.in1, Number(i.types))],
+            Some((i.out1, Number(i.types))),
+        ),
+        I::IBinary(i) => (
+            smallvec![(i.lhs, Number(i.types)), (i.rhs, Number(i.types))],
+            Some((i.out1, Number(i.types))),
+        ),
+        I::FBinary(i) => (
+            smallvec![(i.lhs, Number(i.types)), (i.rhs, Number(i.types))],
+            Some((i.out1, Number(i.types))),
+        ),
+        I::IRelational(i) => (
+            smallvec![
+                (i.in1, Number(i.input_types)),
+                (i.in2, Number(i.input_types))
+            ],
+            Some((i.out1, ValType::i32())),
+        ),
+        I::FRelational(i) => (
+            smallvec![
+                (i.in1, Number(i.input_types)),
+                (i.in2, Number(i.input_types))
+            ],
+            Some((i.out1, ValType::i32())),
+        ),
+        I::ITest(i) => (
+            smallvec![(i.in1, Number(i.input_type))],
+            Some((i.out1, ValType::i32())),
+        ),
+        I::Convert(i) => (
+            smallvec![(i.in1, Number(i.in1_type))],
+            Some((i.out1, Number(i.out1_type))),
+        ),
+        I::Reinterpret(i) => (
+            smallvec![(i.in1, Number(i.in1_type))],
+            Some((i.out1, Number(i.out1_type))),
+        ),
+        I::ExtendBits(i) => (
+            smallvec![(i.in1, Number(i.in1_type))],
+            Some((i.out1, Number(i.out1_type))),
+        ),
+        I::Trunc(i) => (
+            smallvec![(i.in1, Number(i.in1_type))],
+            Some((i.out1, Number(i.out1_type))),
+        ),
+        I::TruncSaturation(i) => (
+            smallvec![(i.in1, Number(i.in1_type))],
+            Some((i.out1, Number(i.out1_type))),
+        ),
+        I::Wrap(i) => (
+            smallvec![(i.in1, ValType::i64())],
+            Some((i.out1, ValType::i32())),
+        ),
+        I::ExtendType(i) => (
+            smallvec![(i.in1, ValType::i32())],
+            Some((i.out1, ValType::i64())),
+        ),
+        I::Demote(i) => (
+            smallvec![(i.in1, ValType::f64())],
+            Some((i.out1, ValType::f32())),
+        ),
+        I::Promote(i) => (
+            smallvec![(i.in1, ValType::f32())],
+            Some((i.out1, ValType::f64())),
+        ),
+        I::Select(i) => (smallvec![(i.select_val, ValType::i32())], None),
+        I::LocalGet(i) => (smallvec![], Some((i.out1, local(i.local_idx)?))),
+        I::LocalSet(i) => (smallvec![(i.in1, local(i.local_idx)?)], None),
+        I::LocalTee(i) => {
+            let r#type = local(i.local_idx)?;
+            (smallvec![(i.in1, r#type)], Some((i.out1, r#type)))
+        }
+        _ => (smallvec![], None),
+    };
+    Ok(signature)
+}
+
+/// Reference types are only compared by kind, as values of a subtype can be used in their place.
+fn compatible(expected: ValType, actual: ValType) -> bool {
+    match (expected, actual) {
+        (ValType::Reference(_), ValType::Reference(_)) => true,
+        (ValType::Number(expected), ValType::Number(actual)) => expected == actual,
+        (ValType::VecType, ValType::VecType) => true,
+        _ => false,
+    }
+}
+
+/// Dominator tree of the blocks reachable from the entry block, by block position.
+struct Dominators {
+    // immediate dominator of each reachable block, the entry block dominates itself
+    idoms: Vec<Option<usize>>,
+}
+
+impl Dominators {
+    fn new(successors: &[SmallVec<[usize; 2]>]) -> Self {
+        // reverse postorder of the reachable blocks
+        let mut postorder = Vec::with_capacity(successors.len());
+        let mut visited = vec![false; successors.len()];
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+        while let Some((position, next)) = stack.last_mut() {
+            match successors[*position].get(*next) {
+                Some(&successor) => {
+                    *next += 1;
+                    if !visited[successor] {
+                        visited[successor] = true;
+                        stack.push((successor, 0));
+                    }
+                }
+                None => {
+                    postorder.push(*position);
+                    stack.pop();
+                }
+            }
+        }
+        let mut postorder_numbers = vec![usize::MAX; successors.len()];
+        for (number, &position) in postorder.iter().enumerate() {
+            postorder_numbers[position] = number;
+        }
+        let mut predecessors = vec![SmallVec::<[usize; 2]>::new(); successors.len()];
+        for (position, targets) in successors.iter().enumerate() {
+            for &target in targets.iter() {
+                predecessors[target].push(position);
+            }
+        }
+
+        // "A Simple, Fast Dominance Algorithm" by Cooper, Harvey and Kennedy
+        let mut idoms: Vec<Option<usize>> = vec![None; successors.len()];
+        idoms[0] = Some(0);
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &position in postorder.iter().rev().skip(1) {
+                let mut processed = predecessors[position]
+                    .iter()
+                    .copied()
+                    .filter(|&pred| idoms[pred].is_some());
+                let Some(first) = processed.next() else {
+                    continue;
+                };
+                let idom = processed.fold(first, |mut a, mut b| {
+                    while a != b {
+                        while postorder_numbers[a] < postorder_numbers[b] {
+                            a = idoms[a].unwrap();
+                        }
+                        while postorder_numbers[b] < postorder_numbers[a] {
+                            b = idoms[b].unwrap();
+                        }
+                    }
+                    a
+                });
+                if idoms[position] != Some(idom) {
+                    idoms[position] = Some(idom);
+                    changed = true;
+                }
+            }
+        }
+        Self { idoms }
+    }
+
+    fn is_reachable(&self, position: usize) -> bool {
+        self.idoms[position].is_some()
+    }
+
+    /// Whether every path from the entry block to the reachable block `b` passes `a`.
+    fn dominates(&self, a: usize, mut b: usize) -> bool {
+        loop {
+            if a == b {
+                return true;
+            }
+            match self.idoms[b] {
+                Some(idom) if idom != b => b = idom,
+                _ => return false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{Constant, IBinaryInstruction, PhiNode},
+        objects::{instruction::ControlInstruction, value::ValueRaw},
+        BasicBlock, InstructionConsumer, InstructionEncoder,
+    };
+    use wasm_types::{IBinaryOp, NumType};
+
+    const I32: ValType = ValType::Number(NumType::I32);
+
+    fn block(
+        id: BasicBlockID,
+        inputs: SmallVec<[PhiNode; 0]>,
+        instructions: Vec<IRInstruction>,
+        terminator: BasicBlockGlue,
+    ) -> BasicBlock {
+        let mut encoder = InstructionEncoder::new();
+        for instruction in instructions {
+            instruction.encode(&mut encoder);
+        }
+        encoder.finish(ControlInstruction::default());
+        BasicBlock {
+            instructions: encoder.extract_data(),
+            inputs,
+            terminator,
+            id,
+        }
+    }
+
+    fn constant(out1: VariableID, out1_type: NumType) -> IRInstruction {
+        IRInstruction::Constant(Constant {
+            imm: ValueRaw::from(1u64),
+            out1,
+            out1_type,
+        })
+    }
+
+    fn i32_add(lhs: VariableID, rhs: VariableID, out1: VariableID) -> IRInstruction {
+        IRInstruction::IBinary(IBinaryInstruction {
+            types: NumType::I32,
+            op: IBinaryOp::Add,
+            lhs,
+            rhs,
+            out1,
+        })
+    }
+
+    fn phi(out: VariableID, inputs: &[(BasicBlockID, VariableID)]) -> PhiNode {
+        PhiNode {
+            inputs: inputs.iter().copied().collect(),
+            out,
+            r#type: I32,
+        }
+    }
+
+    fn jmp(target: BasicBlockID, output_vars: &[VariableID]) -> BasicBlockGlue {
+        BasicBlockGlue::Jmp {
+            target,
+            output_vars: output_vars.iter().copied().collect(),
+        }
+    }
+
+    fn ret(return_vars: &[VariableID]) -> BasicBlockGlue {
+        BasicBlockGlue::Return {
+            return_vars: return_vars.iter().copied().collect(),
+        }
+    }
+
+    /// Verifies a function with an `i32` constant `%0` in its entry block and the given blocks after it
+    fn verify(bbs: Vec<BasicBlock>, num_vars: usize) -> Result<(), VerifierError> {
+        let entry = block(
+            0,
+            smallvec![],
+            vec![constant(0, NumType::I32)],
+            jmp(1, &[0]),
+        );
+        verify_function(&FunctionIR {
+            locals: Vec::new(),
+            bbs: std::iter::once(entry).chain(bbs).collect(),
+            num_vars,
+        })
+    }
+
+    #[test]
+    fn test_valid_function() {
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            vec![i32_add(0, 1, 2)],
+            ret(&[2]),
+        )];
+        verify(bbs, 3).unwrap();
+    }
+
+    #[test]
+    fn test_variables() {
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            vec![i32_add(0, 5, 2)],
+            ret(&[2]),
+        )];
+        assert!(matches!(
+            verify(bbs, 6),
+            Err(VerifierError::Undefined { var: 5, bb: 1 })
+        ));
+
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            vec![i32_add(0, 1, 2)],
+            ret(&[2]),
+        )];
+        assert!(matches!(
+            verify(bbs, 2),
+            Err(VerifierError::VariableOutOfRange {
+                var: 2,
+                num_vars: 2,
+                ..
+            })
+        ));
+
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            vec![i32_add(0, 1, 1)],
+            ret(&[1]),
+        )];
+        assert!(matches!(
+            verify(bbs, 2),
+            Err(VerifierError::Redefined { var: 1, bb: 1 })
+        ));
+
+        // %2 is only defined on one of the paths to bb3
+        let bbs = vec![
+            block(
+                1,
+                smallvec![phi(1, &[(0, 0)])],
+                Vec::new(),
+                BasicBlockGlue::JmpCond {
+                    cond_var: 1,
+                    target_if_true: 2,
+                    target_if_false: 3,
+                    output_vars: smallvec![],
+                },
+            ),
+            block(2, smallvec![], vec![i32_add(0, 1, 2)], jmp(3, &[])),
+            block(3, smallvec![], Vec::new(), ret(&[2])),
+        ];
+        assert!(matches!(
+            verify(bbs, 3),
+            Err(VerifierError::UsedBeforeDefinition { var: 2, bb: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_phis_and_targets() {
+        let bbs = vec![block(1, smallvec![phi(1, &[])], Vec::new(), ret(&[1]))];
+        assert!(matches!(
+            verify(bbs, 2),
+            Err(VerifierError::MissingPhiInput {
+                phi: 1,
+                bb: 1,
+                pred: 0
+            })
+        ));
+
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0), (1, 0)])],
+            Vec::new(),
+            ret(&[1]),
+        )];
+        assert!(matches!(
+            verify(bbs, 2),
+            Err(VerifierError::UnexpectedPhiInput {
+                phi: 1,
+                bb: 1,
+                pred: 1
+            })
+        ));
+
+        // the entry block passes %0 while the phi receives %2
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 2)])],
+            vec![constant(2, NumType::I32)],
+            ret(&[1]),
+        )];
+        assert!(matches!(
+            verify(bbs, 3),
+            Err(VerifierError::OutputMismatch {
+                pred: 0,
+                phi: 1,
+                output: 0,
+                input: 2,
+                ..
+            })
+        ));
+
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            Vec::new(),
+            jmp(7, &[]),
+        )];
+        assert!(matches!(
+            verify(bbs, 2),
+            Err(VerifierError::UnknownTarget { bb: 1, target: 7 })
+        ));
+    }
+
+    #[test]
+    fn test_types() {
+        let bbs = vec![block(
+            1,
+            smallvec![phi(1, &[(0, 0)])],
+            vec![constant(2, NumType::I64), i32_add(1, 2, 3)],
+            ret(&[3]),
+        )];
+        assert!(matches!(
+            verify(bbs, 4),
+            Err(VerifierError::TypeMismatch {
+                var: 2,
+                bb: 1,
+                expected: ValType::Number(NumType::I32),
+                actual: ValType::Number(NumType::I64),
+            })
+        ));
+    }
+}
@@ -0,0 +1,888 @@
+//! Wasm binary encoding of modules, the counterpart of the parser.
+//!
+//! Function bodies are copied from the module's source unless they are replaced, the IR is not lowered
+//! back to wasm instructions.
+
+use crate::{
+    objects::{
+        data::DataMode,
+        element::{ElemMode, Element, ElementInit},
+        function::FunctionSource,
+        value::{ConstantAllocation, ConstantOp, ConstantValue, Number, Reference, Value},
+    },
+    Module,
+};
+use resource_buffer::SourceFormat;
+use std::collections::HashMap;
+use thiserror::Error;
+use wasm_types::{
+    CompositeType, FieldType, FuncIdx, FuncType, GlobalType, HeapType, IBinaryOp, ImportDesc,
+    IndexType, Limits, MemType, NumType, RefType, Section, StorageType, SubType, TableType,
+    ValType,
+};
+
+const WASM_MODULE_PREAMBLE: &[u8] = b"\0asm";
+const WASM_MODULE_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum WriterError {
+    #[error("No code available for function {0}")]
+    MissingFunctionBody(FuncIdx),
+    #[error("Constant value {0:?} can't be encoded as constant expression of type {1}")]
+    UnencodableConstant(ConstantValue, ValType),
+    #[error("Reference {0:?} only exists at runtime and can't be encoded as constant expression")]
+    UnencodableReference(Reference),
+}
+
+/// Writes a parsed module back to a wasm binary.
+///
+/// Parsing the output yields a module equivalent to the input: index spaces, types, initializers and
+/// function bodies are kept, while the order of exports and the grouping of recursive types may differ.
+pub struct ModuleWriter<'a> {
+    module: &'a Module,
+    function_bodies: HashMap<FuncIdx, Vec<u8>>,
+    custom_sections: bool,
+}
+
+impl<'a> ModuleWriter<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        Self {
+            module,
+            function_bodies: HashMap::new(),
+            custom_sections: true,
+        }
+    }
+
+    /// Replaces the code of a function defined by the module.
+    ///
+    /// `body` is the content of the function's code section entry, i.e. its local declarations followed by
+    /// its instructions including the final `end`.
+    pub fn with_function_body(mut self, func_idx: FuncIdx, body: Vec<u8>) -> Self {
+        self.function_bodies.insert(func_idx, body);
+        self
+    }
+
+    /// Whether to copy the custom sections of the source, enabled by default.
+    pub fn with_custom_sections(mut self, custom_sections: bool) -> Self {
+        self.custom_sections = custom_sections;
+        self
+    }
+
+    pub fn finish(self) -> Result<Vec<u8>, WriterError> {
+        let mut out = WasmBinaryWriter::default();
+        out.write_bytes(WASM_MODULE_PREAMBLE);
+        out.write_bytes(&WASM_MODULE_VERSION.to_le_bytes());
+
+        self.write_type_section(&mut out);
+        self.write_import_section(&mut out);
+        self.write_function_section(&mut out);
+        self.write_table_section(&mut out)?;
+        self.write_memory_section(&mut out);
+        self.write_tag_section(&mut out);
+        self.write_global_section(&mut out)?;
+        self.write_export_section(&mut out);
+        self.write_start_section(&mut out);
+        self.write_element_section(&mut out)?;
+        self.write_datacount_section(&mut out);
+        self.write_code_section(&mut out)?;
+        self.write_data_section(&mut out)?;
+        if self.custom_sections {
+            self.write_custom_sections(&mut out);
+        }
+        Ok(out.buf)
+    }
+
+    /// Recursion groups are flattened by the parser, types are regrouped so that every group is as small as
+    /// possible while forward references stay within their group.
+    fn write_type_section(&self, out: &mut WasmBinaryWriter) {
+        let types = &self.module.meta.types;
+        if types.is_empty() {
+            return;
+        }
+        let mut groups = Vec::new();
+        let mut start = 0;
+        while start < types.len() {
+            let mut end = start;
+            let mut type_idx = start;
+            while type_idx <= end {
+                end = end.max(max_type_reference(&types[type_idx]).unwrap_or(0) as usize);
+                type_idx += 1;
+            }
+            let end = end.min(types.len() - 1);
+            groups.push(&types[start..=end]);
+            start = end + 1;
+        }
+        out.write_section(Section::Type, |s| {
+            s.write_u32(groups.len() as u32);
+            for group in groups {
+                if group.len() > 1 {
+                    s.write_byte(0x4E);
+                    s.write_u32(group.len() as u32);
+                }
+                group.iter().for_each(|sub_type| s.write_sub_type(sub_type));
+            }
+        });
+    }
+
+    fn write_import_section(&self, out: &mut WasmBinaryWriter) {
+        let imports = &self.module.meta.imports;
+        if imports.is_empty() {
+            return;
+        }
+        out.write_section(Section::Import, |s| {
+            s.write_u32(imports.len() as u32);
+            for import in imports {
+                s.write_name(&import.module);
+                s.write_name(&import.name);
+                match import.desc {
+                    ImportDesc::Func(type_idx) => {
+                        s.write_byte(0x00);
+                        s.write_u32(type_idx);
+                    }
+                    ImportDesc::Table(table_type) => {
+                        s.write_byte(0x01);
+                        s.write_table_type(&table_type);
+                    }
+                    ImportDesc::Mem(mem_type) => {
+                        s.write_byte(0x02);
+                        s.write_mem_type(&mem_type);
+                    }
+                    ImportDesc::Global((global_type, _)) => {
+                        s.write_byte(0x03);
+                        s.write_global_type(&global_type);
+                    }
+                    ImportDesc::Tag(type_idx) => {
+                        s.write_byte(0x04);
+                        s.write_byte(0x00);
+                        s.write_u32(type_idx);
+                    }
+                }
+            }
+        });
+    }
+
+    fn write_function_section(&self, out: &mut WasmBinaryWriter) {
+        let type_idxs = self
+            .module
+            .meta
+            .functions
+            .iter()
+            .filter(|function| matches!(function.source, FunctionSource::Wasm(_)))
+            .map(|function| function.type_idx)
+            .collect::<Vec<_>>();
+        if type_idxs.is_empty() {
+            return;
+        }
+        out.write_section(Section::Function, |s| {
+            s.write_u32(type_idxs.len() as u32);
+            type_idxs
+                .into_iter()
+                .for_each(|type_idx| s.write_u32(type_idx));
+        });
+    }
+
+    fn write_table_section(&self, out: &mut WasmBinaryWriter) -> Result<(), WriterError> {
+        let tables = self
+            .module
+            .meta
+            .tables
+            .iter()
+            .filter(|table| !table.import)
+            .collect::<Vec<_>>();
+        if tables.is_empty() {
+            return Ok(());
+        }
+        out.try_write_section(Section::Table, |s| {
+            s.write_u32(tables.len() as u32);
+            for table in tables {
+                match &table.init {
+                    Some(init) => {
+                        // tables with an explicit initializer are prefixed by 0x40 0x00
+                        s.write_byte(0x40);
+                        s.write_byte(0x00);
+                        s.write_table_type(&table.r#type);
+                        s.write_constant_expression(
+                            init,
+                            ValType::Reference(table.r#type.ref_type),
+                        )?;
+                    }
+                    None => s.write_table_type(&table.r#type),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn write_memory_section(&self, out: &mut WasmBinaryWriter) {
+        let memories = self
+            .module
+            .meta
+            .memories
+            .iter()
+            .filter(|memory| !memory.import)
+            .collect::<Vec<_>>();
+        if memories.is_empty() {
+            return;
+        }
+        out.write_section(Section::Memory, |s| {
+            s.write_u32(memories.len() as u32);
+            memories
+                .into_iter()
+                .for_each(|memory| s.write_mem_type(&memory.r#type));
+        });
+    }
+
+    fn write_tag_section(&self, out: &mut WasmBinaryWriter) {
+        let tags = self
+            .module
+            .meta
+            .tags
+            .iter()
+            .filter(|tag| !tag.import)
+            .collect::<Vec<_>>();
+        if tags.is_empty() {
+            return;
+        }
+        out.write_section(Section::Tag, |s| {
+            s.write_u32(tags.len() as u32);
+            for tag in tags {
+                // the only tag attribute defined so far is "exception"
+                s.write_byte(0x00);
+                s.write_u32(tag.type_idx);
+            }
+        });
+    }
+
+    fn write_global_section(&self, out: &mut WasmBinaryWriter) -> Result<(), WriterError> {
+        let globals = self
+            .module
+            .meta
+            .globals
+            .iter()
+            .filter(|global| !global.import)
+            .collect::<Vec<_>>();
+        if globals.is_empty() {
+            return Ok(());
+        }
+        out.try_write_section(Section::Global, |s| {
+            s.write_u32(globals.len() as u32);
+            for global in globals {
+                s.write_global_type(&global.r#type);
+                s.write_constant_expression(&global.init, global.val_type())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn write_export_section(&self, out: &mut WasmBinaryWriter) {
+        let exports = &self.module.meta.exports;
+        if exports.is_empty() {
+            return;
+        }
+        // export order is not kept by the parser, exports of each kind are sorted by index
+        let mut sorted_exports = Vec::new();
+        for (kind, exports) in [
+            (0x00, &exports.functions),
+            (0x01, &exports.tables),
+            (0x02, &exports.memories),
+            (0x03, &exports.globals),
+            (0x04, &exports.tags),
+        ] {
+            let mut exports = exports.iter().collect::<Vec<_>>();
+            exports.sort_by_key(|(name, idx)| (**idx, *name));
+            sorted_exports.extend(exports.into_iter().map(|(name, idx)| (name, kind, *idx)));
+        }
+        out.write_section(Section::Export, |s| {
+            s.write_u32(sorted_exports.len() as u32);
+            for (name, kind, idx) in sorted_exports {
+                s.write_name(name);
+                s.write_byte(kind);
+                s.write_u32(idx);
+            }
+        });
+    }
+
+    fn write_start_section(&self, out: &mut WasmBinaryWriter) {
+        if let Some(func_idx) = self.module.meta.entry_point {
+            out.write_section(Section::Start, |s| s.write_u32(func_idx));
+        }
+    }
+
+    fn write_element_section(&self, out: &mut WasmBinaryWriter) -> Result<(), WriterError> {
+        let elements = &self.module.meta.elements;
+        if elements.is_empty() {
+            return Ok(());
+        }
+        out.try_write_section(Section::Element, |s| {
+            s.write_u32(elements.len() as u32);
+            elements
+                .iter()
+                .try_for_each(|element| s.write_element(element))
+        })
+    }
+
+    fn write_datacount_section(&self, out: &mut WasmBinaryWriter) {
+        if let Some(datacount) = self.module.meta.datacount {
+            out.write_section(Section::DataCount, |s| s.write_u32(datacount));
+        }
+    }
+
+    fn write_code_section(&self, out: &mut WasmBinaryWriter) -> Result<(), WriterError> {
+        let bodies = self
+            .module
+            .meta
+            .functions
+            .iter()
+            .enumerate()
+            .filter_map(|(func_idx, function)| match &function.source {
+                FunctionSource::Wasm(unparsed) => Some((func_idx as FuncIdx, unparsed)),
+                FunctionSource::Import(_) => None,
+            })
+            .map(|(func_idx, unparsed)| {
+                if let Some(body) = self.function_bodies.get(&func_idx) {
+                    return Ok(body.as_slice());
+                }
+                // precompiled modules don't keep the code of their functions
+                if self.module.source.kind() == SourceFormat::Cwasm {
+                    return Err(WriterError::MissingFunctionBody(func_idx));
+                }
+                self.module
+                    .source
+                    .get()
+                    .get(unparsed.offset..unparsed.offset.saturating_add(unparsed.size))
+                    .ok_or(WriterError::MissingFunctionBody(func_idx))
+            })
+            .collect::<Result<Vec<&[u8]>, WriterError>>()?;
+        if bodies.is_empty() {
+            return Ok(());
+        }
+        out.write_section(Section::Code, |s| {
+            s.write_u32(bodies.len() as u32);
+            for body in bodies {
+                s.write_u32(body.len() as u32);
+                s.write_bytes(body);
+            }
+        });
+        Ok(())
+    }
+
+    fn write_data_section(&self, out: &mut WasmBinaryWriter) -> Result<(), WriterError> {
+        let meta = &self.module.meta;
+        if meta.datas.is_empty() {
+            return Ok(());
+        }
+        out.try_write_section(Section::Data, |s| {
+            s.write_u32(meta.datas.len() as u32);
+            for data in &meta.datas {
+                match &data.mode {
+                    DataMode::Active { memory, offset } => {
+                        if *memory == 0 {
+                            s.write_u32(0);
+                        } else {
+                            s.write_u32(2);
+                            s.write_u32(*memory);
+                        }
+                        let index_type = meta
+                            .memories
+                            .get(*memory as usize)
+                            .map(|memory| memory.r#type.index_type)
+                            .unwrap_or_default();
+                        s.write_constant_expression(offset, index_type.val_type())?;
+                    }
+                    DataMode::Passive => s.write_u32(1),
+                }
+                s.write_u32(data.init.len() as u32);
+                s.write_bytes(&data.init);
+            }
+            Ok(())
+        })
+    }
+
+    fn write_custom_sections(&self, out: &mut WasmBinaryWriter) {
+        for (name, payload) in self.module.custom_sections() {
+            out.write_section(Section::Custom, |s| {
+                s.write_name(name);
+                s.write_bytes(payload);
+            });
+        }
+    }
+}
+
+/// The largest type index referenced by a type definition.
+fn max_type_reference(sub_type: &SubType) -> Option<u32> {
+    fn val_type_reference(val_type: ValType) -> Option<u32> {
+        match val_type {
+            ValType::Reference(ref_type) => match ref_type.heap_type() {
+                HeapType::Concrete(type_idx) => Some(type_idx),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+    let field_reference = |field: &FieldType| match field.storage_type {
+        StorageType::Val(val_type) => val_type_reference(val_type),
+        StorageType::I8 | StorageType::I16 => None,
+    };
+    let composite_reference = match &sub_type.composite_type {
+        CompositeType::Func(func_type) => func_type
+            .params_iter()
+            .chain(func_type.results_iter())
+            .filter_map(val_type_reference)
+            .max(),
+        CompositeType::Struct(fields) => fields.iter().filter_map(field_reference).max(),
+        CompositeType::Array(field) => field_reference(field),
+    };
+    composite_reference.max(sub_type.supertype)
+}
+
+#[derive(Default)]
+struct WasmBinaryWriter {
+    buf: Vec<u8>,
+}
+
+impl WasmBinaryWriter {
+    fn write_byte(&mut self, byte: u8) {
+        self.buf.push(byte);
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    fn write_u32(&mut self, value: u32) {
+        self.write_u64(value as u64);
+    }
+
+    /// unsigned LEB128
+    fn write_u64(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.write_byte(byte);
+                return;
+            }
+            self.write_byte(byte | 0x80);
+        }
+    }
+
+    /// signed LEB128
+    fn write_i64(&mut self, mut value: i64) {
+        loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            let sign_bit_clear = byte & 0x40 == 0;
+            if (value == 0 && sign_bit_clear) || (value == -1 && !sign_bit_clear) {
+                self.write_byte(byte);
+                return;
+            }
+            self.write_byte(byte | 0x80);
+        }
+    }
+
+    fn write_name(&mut self, name: &str) {
+        self.write_u32(name.len() as u32);
+        self.write_bytes(name.as_bytes());
+    }
+
+    fn write_section(&mut self, section: Section, f: impl FnOnce(&mut WasmBinaryWriter)) {
+        let _ = self.try_write_section(section, |s| {
+            f(s);
+            Ok(())
+        });
+    }
+
+    fn try_write_section(
+        &mut self,
+        section: Section,
+        f: impl FnOnce(&mut WasmBinaryWriter) -> Result<(), WriterError>,
+    ) -> Result<(), WriterError> {
+        let mut payload = WasmBinaryWriter::default();
+        f(&mut payload)?;
+        self.write_byte(section.id());
+        self.write_u32(payload.buf.len() as u32);
+        self.write_bytes(&payload.buf);
+        Ok(())
+    }
+
+    fn write_val_type(&mut self, val_type: ValType) {
+        match val_type {
+            ValType::Number(NumType::I32) => self.write_byte(0x7F),
+            ValType::Number(NumType::I64) => self.write_byte(0x7E),
+            ValType::Number(NumType::F32) => self.write_byte(0x7D),
+            ValType::Number(NumType::F64) => self.write_byte(0x7C),
+            ValType::VecType => self.write_byte(0x7B),
+            ValType::Reference(ref_type) => self.write_ref_type(ref_type),
+        }
+    }
+
+    fn write_ref_type(&mut self, ref_type: RefType) {
+        let heap_type = ref_type.heap_type();
+        match (heap_type, ref_type.is_nullable()) {
+            // shorthands of nullable references are encoded like their abstract heap type (one byte s33)
+            (HeapType::Concrete(_), _) | (_, false) => {
+                self.write_byte(if ref_type.is_nullable() { 0x63 } else { 0x64 });
+                self.write_i64(heap_type.to_s33());
+            }
+            (_, true) => self.write_byte((heap_type.to_s33() + 0x80) as u8),
+        }
+    }
+
+    fn write_func_type(&mut self, func_type: &FuncType) {
+        self.write_byte(0x60);
+        self.write_u32(func_type.num_params() as u32);
+        func_type.params_iter().for_each(|t| self.write_val_type(t));
+        self.write_u32(func_type.num_results() as u32);
+        func_type
+            .results_iter()
+            .for_each(|t| self.write_val_type(t));
+    }
+
+    fn write_field_type(&mut self, field: &FieldType) {
+        match field.storage_type {
+            StorageType::Val(val_type) => self.write_val_type(val_type),
+            StorageType::I8 => self.write_byte(0x78),
+            StorageType::I16 => self.write_byte(0x77),
+        }
+        self.write_byte(field.mutable as u8);
+    }
+
+    fn write_sub_type(&mut self, sub_type: &SubType) {
+        // composite types without sub prefix are final and have no supertypes
+        if sub_type.is_final && sub_type.supertype.is_none() {
+            return self.write_composite_type(&sub_type.composite_type);
+        }
+        self.write_byte(if sub_type.is_final { 0x4F } else { 0x50 });
+        match sub_type.supertype {
+            Some(supertype) => {
+                self.write_u32(1);
+                self.write_u32(supertype);
+            }
+            None => self.write_u32(0),
+        }
+        self.write_composite_type(&sub_type.composite_type);
+    }
+
+    fn write_composite_type(&mut self, composite_type: &CompositeType) {
+        match composite_type {
+            CompositeType::Func(func_type) => self.write_func_type(func_type),
+            CompositeType::Struct(fields) => {
+                self.write_byte(0x5F);
+                self.write_u32(fields.len() as u32);
+                fields.iter().for_each(|field| self.write_field_type(field));
+            }
+            CompositeType::Array(field) => {
+                self.write_byte(0x5E);
+                self.write_field_type(field);
+            }
+        }
+    }
+
+    /// `flags` announce the maximum and other properties of the limited type
+    fn write_limits(&mut self, flags: u8, lim: &Limits) {
+        match lim.max {
+            Some(max) => {
+                self.write_byte(flags | 0x01);
+                self.write_u64(lim.min);
+                self.write_u64(max);
+            }
+            None => {
+                self.write_byte(flags);
+                self.write_u64(lim.min);
+            }
+        }
+    }
+
+    fn write_table_type(&mut self, table_type: &TableType) {
+        self.write_ref_type(table_type.ref_type);
+        self.write_limits(0x00, &table_type.lim);
+    }
+
+    fn write_mem_type(&mut self, mem_type: &MemType) {
+        // the threads proposal marks shared memories with bit 1 of the limits flags,
+        // the memory64 proposal marks 64-bit memories with bit 2
+        let mut flags = 0x00;
+        if mem_type.shared {
+            flags |= 0x02;
+        }
+        if mem_type.index_type == IndexType::I64 {
+            flags |= 0x04;
+        }
+        self.write_limits(flags, &mem_type.lim);
+    }
+
+    fn write_global_type(&mut self, global_type: &GlobalType) {
+        match *global_type {
+            GlobalType::Const(val_type) => {
+                self.write_val_type(val_type);
+                self.write_byte(0x00);
+            }
+            GlobalType::Mut(val_type) => {
+                self.write_val_type(val_type);
+                self.write_byte(0x01);
+            }
+        }
+    }
+
+    fn write_element(&mut self, element: &Element) -> Result<(), WriterError> {
+        // bit 0: passive or declarative, bit 1: explicit table index or declarative, bit 2: expressions
+        let mut flags = match &element.mode {
+            ElemMode::Active { table: 0, .. } => 0b000,
+            ElemMode::Active { .. } => 0b010,
+            ElemMode::Passive => 0b001,
+            ElemMode::Declarative => 0b011,
+        };
+        if let ElementInit::Final(_) = element.init {
+            flags |= 0b100;
+        }
+        // only active segments of table 0 omit the element type, which is funcref then
+        if flags == 0b100 && element.type_ != RefType::FunctionReference {
+            flags = 0b110;
+        }
+        self.write_u32(flags);
+
+        if let ElemMode::Active { table, offset } = &element.mode {
+            if flags & 0b010 != 0 {
+                self.write_u32(*table);
+            }
+            self.write_constant_expression(offset, ValType::i32())?;
+        }
+        match &element.init {
+            ElementInit::Unresolved(func_idxs) => {
+                if flags != 0b000 {
+                    // elemkind funcref
+                    self.write_byte(0x00);
+                }
+                self.write_u32(func_idxs.len() as u32);
+                func_idxs
+                    .iter()
+                    .for_each(|func_idx| self.write_u32(*func_idx));
+            }
+            ElementInit::Final(values) => {
+                if flags != 0b100 {
+                    self.write_ref_type(element.type_);
+                }
+                self.write_u32(values.len() as u32);
+                for value in values {
+                    self.write_constant_expression(value, ValType::Reference(element.type_))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `val_type` is the type of the expression, it determines the heap type of null references.
+    fn write_constant_expression(
+        &mut self,
+        value: &ConstantValue,
+        val_type: ValType,
+    ) -> Result<(), WriterError> {
+        let unencodable = || WriterError::UnencodableConstant(value.clone(), val_type);
+        match value {
+            ConstantValue::V(v) => self.write_constant(v, val_type)?,
+            ConstantValue::Global(global_idx) => {
+                self.write_byte(0x23);
+                self.write_u32(*global_idx);
+            }
+            ConstantValue::FuncPtr(func_idx) => {
+                self.write_byte(0xD2);
+                self.write_u32(*func_idx);
+            }
+            ConstantValue::Expr(ops) => {
+                for op in ops {
+                    match op {
+                        ConstantOp::Const(v) => self.write_constant(v, val_type)?,
+                        ConstantOp::Global(global_idx, _) => {
+                            self.write_byte(0x23);
+                            self.write_u32(*global_idx);
+                        }
+                        ConstantOp::IBinary(t, op) => {
+                            let opcode = match (t, op) {
+                                (NumType::I32, IBinaryOp::Add) => 0x6A,
+                                (NumType::I32, IBinaryOp::Sub) => 0x6B,
+                                (NumType::I32, IBinaryOp::Mul) => 0x6C,
+                                (NumType::I64, IBinaryOp::Add) => 0x7C,
+                                (NumType::I64, IBinaryOp::Sub) => 0x7D,
+                                (NumType::I64, IBinaryOp::Mul) => 0x7E,
+                                _ => return Err(unencodable()),
+                            };
+                            self.write_byte(opcode);
+                        }
+                        ConstantOp::RefNull(heap_type) => {
+                            self.write_byte(0xD0);
+                            self.write_i64(heap_type.to_s33());
+                        }
+                        ConstantOp::Alloc(allocation) => {
+                            let opcode = match allocation {
+                                ConstantAllocation::StructNew { .. } => 0x00,
+                                ConstantAllocation::StructNewDefault(_) => 0x01,
+                                ConstantAllocation::ArrayNew(_) => 0x06,
+                                ConstantAllocation::ArrayNewDefault(_) => 0x07,
+                                ConstantAllocation::ArrayNewFixed { .. } => 0x08,
+                            };
+                            self.write_byte(0xFB);
+                            self.write_u32(opcode);
+                            self.write_u32(allocation.type_idx());
+                            if let ConstantAllocation::ArrayNewFixed { len, .. } = allocation {
+                                self.write_u32(*len);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        // end
+        self.write_byte(0x0B);
+        Ok(())
+    }
+
+    /// Writes the instruction producing the constant `value`, which fails for runtime-only references.
+    fn write_constant(&mut self, value: &Value, val_type: ValType) -> Result<(), WriterError> {
+        match value {
+            Value::Number(Number::I32(n) | Number::U32(n)) => {
+                self.write_byte(0x41);
+                self.write_i64(*n as i32 as i64);
+            }
+            Value::Number(Number::S32(n)) => {
+                self.write_byte(0x41);
+                self.write_i64(*n as i64);
+            }
+            Value::Number(Number::I64(n) | Number::U64(n)) => {
+                self.write_byte(0x42);
+                self.write_i64(*n as i64);
+            }
+            Value::Number(Number::S64(n)) => {
+                self.write_byte(0x42);
+                self.write_i64(*n);
+            }
+            Value::Number(Number::F32(f)) => {
+                self.write_byte(0x43);
+                self.write_bytes(&f.to_bits().to_le_bytes());
+            }
+            Value::Number(Number::F64(f)) => {
+                self.write_byte(0x44);
+                self.write_bytes(&f.to_bits().to_le_bytes());
+            }
+            Value::Vector(v) => {
+                self.write_byte(0xFD);
+                self.write_u32(12);
+                self.write_bytes(v);
+            }
+            Value::Reference(Reference::Null) => {
+                let ValType::Reference(ref_type) = val_type else {
+                    return Err(WriterError::UnencodableConstant(
+                        ConstantValue::V(value.clone()),
+                        val_type,
+                    ));
+                };
+                self.write_byte(0xD0);
+                self.write_i64(ref_type.heap_type().to_s33());
+            }
+            Value::Reference(Reference::Function(func_idx)) => {
+                self.write_byte(0xD2);
+                self.write_u32(*func_idx);
+            }
+            Value::Reference(reference) => {
+                return Err(WriterError::UnencodableReference(reference.clone()))
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        objects::{global::Global, value::ConstantOp},
+        ModuleMetadata,
+    };
+    use resource_buffer::ResourceBuffer;
+
+    fn module_with_globals(globals: Vec<(ValType, ConstantValue)>) -> Module {
+        let globals = globals
+            .into_iter()
+            .map(|(val_type, init)| Global {
+                r#type: GlobalType::Const(val_type),
+                init,
+                import: false,
+            })
+            .collect();
+        Module {
+            meta: ModuleMetadata {
+                globals,
+                ..Default::default()
+            },
+            source: ResourceBuffer::from_wasm_buf(Vec::new()),
+            artifact_registry: Default::default(),
+        }
+    }
+
+    /// Content of the global section of `module`, which is its only section
+    fn global_section(module: &Module) -> Vec<u8> {
+        let binary = ModuleWriter::new(module).finish().unwrap();
+        assert_eq!(binary[8], Section::Global as u8);
+        binary[10..].to_vec()
+    }
+
+    #[test]
+    fn test_constant_expressions() {
+        let anyref = ValType::Reference(RefType::new(HeapType::Any, true));
+        let module = module_with_globals(vec![
+            (
+                anyref,
+                ConstantValue::Expr(vec![ConstantOp::RefNull(HeapType::Any)]),
+            ),
+            (
+                anyref,
+                ConstantValue::Expr(vec![
+                    ConstantOp::Const(Value::i32(1)),
+                    ConstantOp::Alloc(ConstantAllocation::StructNew {
+                        type_idx: 0,
+                        fields: 1,
+                    }),
+                ]),
+            ),
+            (
+                anyref,
+                ConstantValue::Expr(vec![ConstantOp::Alloc(ConstantAllocation::ArrayNewFixed {
+                    type_idx: 1,
+                    len: 0,
+                })]),
+            ),
+        ]);
+        #[rustfmt::skip]
+        let expected = [
+            0x03,
+            // const anyref: ref.null any
+            0x6e, 0x00, 0xd0, 0x6e, 0x0b,
+            // struct.new 0 (i32.const 1)
+            0x6e, 0x00, 0x41, 0x01, 0xfb, 0x00, 0x00, 0x0b,
+            // array.new_fixed 1 0
+            0x6e, 0x00, 0xfb, 0x08, 0x01, 0x00, 0x0b,
+        ];
+        assert_eq!(global_section(&module), expected);
+    }
+
+    #[test]
+    fn test_runtime_references_are_unencodable() {
+        let externref = ValType::Reference(RefType::new(HeapType::Extern, true));
+        let module = module_with_globals(vec![(
+            externref,
+            ConstantValue::V(Value::Reference(Reference::Extern(5))),
+        )]);
+        assert!(matches!(
+            ModuleWriter::new(&module).finish(),
+            Err(WriterError::UnencodableReference(Reference::Extern(5)))
+        ));
+
+        let module = module_with_globals(vec![(
+            ValType::i32(),
+            ConstantValue::V(Value::Reference(Reference::Null)),
+        )]);
+        assert!(matches!(
+            ModuleWriter::new(&module).finish(),
+            Err(WriterError::UnencodableConstant(..))
+        ));
+    }
+}
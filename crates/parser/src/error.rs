@@ -1,6 +1,7 @@
-use module::{objects::expression::ConstantExpressionError, DecodingError};
+use module::{objects::expression::ConstantExpressionError, DecodingError, VerifierError};
+use std::fmt::{Display, Formatter};
 use thiserror::Error;
-use wasm_types::{FuncIdx, MemIdx};
+use wasm_types::{FieldType, FuncIdx, GlobalIdx, MemIdx, RefType, Section, TypeIdx, ValType};
 
 #[derive(Debug, Error)]
 pub enum ParserError {
@@ -22,6 +23,8 @@ pub enum ParserError {
     ConstantExpressionError(#[from] ConstantExpressionError),
     #[error("Decoding error: {0}")]
     DecodingError(#[from] DecodingError),
+    #[error("Invalid IR: {0}")]
+    InvalidIR(#[from] VerifierError),
     #[error("unknown memory {0}")]
     UnknownMemory(MemIdx),
     #[error("size minimum must not be greater than maximum")]
@@ -30,16 +33,390 @@ pub enum ParserError {
     StartFunctionDoesNotExist,
     #[error("alignment must not be larger than natural")]
     AlignmentLargerThanNatural,
+    #[error("alignment must be equal to natural")]
+    AlignmentNotNatural,
+    #[error("shared memory must have maximum")]
+    SharedMemoryWithoutMaximum,
+    #[error("memory size must be at most {0} pages")]
+    MemorySizeTooLarge(u64),
+    #[error("offset out of range")]
+    MemArgOffsetOutOfRange,
+    #[error("invalid lane index")]
+    InvalidLaneIndex,
     #[error("Loader error: {0}")]
     LoaderError(#[from] resource_buffer::ResourceBufferError),
     #[error("Unexepected EOF")]
     UnexpectedEOF,
     #[error("Missing function implementation for function {0}")]
     MissingFunctionImplementation(FuncIdx),
+    #[error("{limit} {actual} exceeds the limit of {max}")]
+    LimitExceeded {
+        limit: ResourceLimit,
+        actual: u64,
+        max: u64,
+    },
 }
 
 #[derive(Debug, Error)]
 pub enum ValidationError {
-    #[error("Validation error: {0}")]
-    Msg(String),
+    #[error("type mismatch: expected {expected}, got {actual}")]
+    TypeMismatch { expected: ValType, actual: ValType },
+    #[error(
+        "type mismatch: expected {}, but the operand stack is empty",
+        display_expected(expected)
+    )]
+    StackUnderflow { expected: Option<ValType> },
+    #[error(
+        "type mismatch: expected [{}], got [{}]",
+        display_types(expected),
+        display_types(actual)
+    )]
+    StackMismatch {
+        expected: Vec<ValType>,
+        actual: Vec<ValType>,
+    },
+    #[error("unknown {space} {idx}")]
+    UnknownIndex { space: IndexSpace, idx: u32 },
+    #[error("type mismatch: {instruction} expects a reference, got {actual}")]
+    ExpectedReference {
+        instruction: &'static str,
+        actual: ValType,
+    },
+    #[error("type mismatch: cannot cast {actual} to {target}")]
+    CastMismatch { target: RefType, actual: ValType },
+    #[error("type mismatch: expected field of type {expected}, got {actual}")]
+    FieldTypeMismatch {
+        expected: FieldType,
+        actual: FieldType,
+    },
+    #[error("type mismatch: select without type annotation expects numeric operands, got {0}")]
+    InvalidSelectType(ValType),
+    #[error("invalid result arity: select expects 1 type, got {0}")]
+    InvalidSelectArity(u32),
+    #[error("global {0} is immutable")]
+    ImmutableGlobal(GlobalIdx),
+    #[error("field of type {0} is immutable")]
+    ImmutableField(FieldType),
+    #[error("invalid sign extension for field of type {0}")]
+    InvalidFieldExtension(FieldType),
+    #[error("type {0} is not a struct type")]
+    NotAStructType(TypeIdx),
+    #[error("type {0} is not an array type")]
+    NotAnArrayType(TypeIdx),
+    #[error("type {0} has fields without default value")]
+    NotDefaultable(TypeIdx),
+    #[error("arrays of references can not be initialized from data segments")]
+    ReferenceArrayFromData,
+    #[error("data count section required")]
+    DataCountRequired,
+    #[error(
+        "{error} ({section:?} section, {}offset 0x{offset:x})",
+        display_function(func_idx)
+    )]
+    Located {
+        section: Section,
+        /// function of a code section error, `None` for constant expressions of other sections
+        func_idx: Option<FuncIdx>,
+        /// byte offset of the offending instruction within the module binary
+        offset: usize,
+        error: Box<ValidationError>,
+    },
+}
+
+impl ValidationError {
+    /// The error without its location
+    pub fn kind(&self) -> &ValidationError {
+        match self {
+            ValidationError::Located { error, .. } => error.kind(),
+            e => e,
+        }
+    }
+}
+
+/// Index spaces of a module and its functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexSpace {
+    Type,
+    Function,
+    Table,
+    Memory,
+    Global,
+    Local,
+    Element,
+    Data,
+    Tag,
+    Field,
+    Label,
+}
+
+impl Display for IndexSpace {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            IndexSpace::Type => "type",
+            IndexSpace::Function => "function",
+            IndexSpace::Table => "table",
+            IndexSpace::Memory => "memory",
+            IndexSpace::Global => "global",
+            IndexSpace::Local => "local",
+            IndexSpace::Element => "elem segment",
+            IndexSpace::Data => "data segment",
+            IndexSpace::Tag => "tag",
+            IndexSpace::Field => "field",
+            IndexSpace::Label => "label",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Resources bounded by [`crate::ParserLimits`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceLimit {
+    ModuleSize,
+    Types,
+    Functions,
+    Locals,
+    FunctionSize,
+    NestingDepth,
+    TableSize,
+    ElementSegmentSize,
+    DataSegmentSize,
+}
+
+impl Display for ResourceLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ResourceLimit::ModuleSize => "module size",
+            ResourceLimit::Types => "number of types",
+            ResourceLimit::Functions => "number of functions",
+            ResourceLimit::Locals => "number of locals",
+            ResourceLimit::FunctionSize => "function body size",
+            ResourceLimit::NestingDepth => "block nesting depth",
+            ResourceLimit::TableSize => "table size",
+            ResourceLimit::ElementSegmentSize => "elem segment size",
+            ResourceLimit::DataSegmentSize => "data segment size",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn display_types(types: &[ValType]) -> String {
+    types
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn display_expected(expected: &Option<ValType>) -> String {
+    match expected {
+        Some(t) => t.to_string(),
+        None => "a value".into(),
+    }
+}
+
+fn display_function(func_idx: &Option<FuncIdx>) -> String {
+    match func_idx {
+        Some(func_idx) => format!("function {func_idx}, "),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limits::tests::{leb128, section};
+    use crate::Parser;
+    use resource_buffer::ResourceBuffer;
+
+    fn module(sections: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        sections.iter().for_each(|section| bytes.extend(section));
+        bytes
+    }
+
+    /// Sections of a single `[] -> []` function, the globals go between its declaration and its code
+    fn function(globals: &[u8], code: &[u8]) -> Vec<Vec<u8>> {
+        let mut body = vec![0x00];
+        body.extend_from_slice(code);
+        body.push(0x0b);
+        let mut code_section = leb128(body.len() as u64);
+        code_section.extend(body);
+
+        let mut sections = vec![section(1, 1, &[0x60, 0x00, 0x00]), section(3, 1, &[0x00])];
+        if !globals.is_empty() {
+            sections.push(section(6, 1, globals));
+        }
+        sections.push(section(10, 1, &code_section));
+        sections
+    }
+
+    fn validate(bytes: Vec<u8>) -> ParserError {
+        match Parser::validate(ResourceBuffer::from_wasm_buf(bytes)) {
+            Ok(()) => panic!("module is valid"),
+            Err(e) => e,
+        }
+    }
+
+    /// Location and kind of the validation error of `bytes`
+    fn located(bytes: Vec<u8>) -> (Section, Option<FuncIdx>, usize, ValidationError) {
+        match validate(bytes) {
+            ParserError::ValidationError(ValidationError::Located {
+                section,
+                func_idx,
+                offset,
+                error,
+            }) => (section, func_idx, offset, *error),
+            e => panic!("expected a located validation error, got {e}"),
+        }
+    }
+
+    /// byte offset of the first instruction of `code` in a module ending with its function body
+    fn code_offset(bytes: &[u8], code: &[u8]) -> usize {
+        bytes.len() - code.len() - 1
+    }
+
+    #[test]
+    fn test_code_section_errors() {
+        // table.size 0 without tables
+        let code = [0xfc, 0x10, 0x00, 0x1a];
+        let bytes = module(&function(&[], &code));
+        let offset = code_offset(&bytes, &code);
+        let (section, func_idx, actual_offset, error) = located(bytes);
+        assert_eq!(
+            (section, func_idx, actual_offset),
+            (Section::Code, Some(0), offset)
+        );
+        assert!(matches!(
+            error,
+            ValidationError::UnknownIndex {
+                space: IndexSpace::Table,
+                idx: 0
+            }
+        ));
+
+        // call 1 with a single function
+        let (.., error) = located(module(&function(&[], &[0x10, 0x01])));
+        assert!(matches!(
+            error,
+            ValidationError::UnknownIndex {
+                space: IndexSpace::Function,
+                idx: 1
+            }
+        ));
+
+        // global.set of an immutable global
+        let global = [0x7f, 0x00, 0x41, 0x00, 0x0b];
+        let (.., error) = located(module(&function(&global, &[0x41, 0x00, 0x24, 0x00])));
+        assert!(matches!(error, ValidationError::ImmutableGlobal(0)));
+
+        // ref.is_null of an i32
+        let (.., error) = located(module(&function(&[], &[0x41, 0x00, 0xd1, 0x1a])));
+        assert!(matches!(
+            error,
+            ValidationError::ExpectedReference {
+                instruction: "ref.is_null",
+                actual: ValType::Number(_)
+            }
+        ));
+
+        // untyped select of references
+        let code = [0xd0, 0x70, 0xd0, 0x70, 0x41, 0x00, 0x1b, 0x1a];
+        let (.., error) = located(module(&function(&[], &code)));
+        assert!(matches!(
+            error,
+            ValidationError::InvalidSelectType(ValType::Reference(_))
+        ));
+
+        // data.drop without data count section
+        let (.., error) = located(module(&function(&[], &[0xfc, 0x09, 0x00])));
+        assert!(matches!(error, ValidationError::DataCountRequired));
+    }
+
+    #[test]
+    fn test_control_errors() {
+        let unknown = |code: &[u8]| match located(module(&function(&[], code))) {
+            (.., ValidationError::UnknownIndex { space, idx }) => (space, idx),
+            (.., e) => panic!("expected an unknown index, got {e}"),
+        };
+        // br 1, br_if 2 and br_table with an unknown target in the function's body
+        assert_eq!(unknown(&[0x0c, 0x01]), (IndexSpace::Label, 1));
+        assert_eq!(unknown(&[0x41, 0x00, 0x0d, 0x02]), (IndexSpace::Label, 2));
+        let br_table = [0x41, 0x00, 0x0e, 0x01, 0x03, 0x00];
+        assert_eq!(unknown(&br_table), (IndexSpace::Label, 3));
+        // br_on_null 1 within a block
+        let code = [0x02, 0x40, 0xd0, 0x70, 0xd5, 0x02, 0x1a, 0x0b];
+        assert_eq!(unknown(&code), (IndexSpace::Label, 2));
+
+        // return_call 5, call_ref of type 5 and throw of tag 0
+        assert_eq!(unknown(&[0x12, 0x05]), (IndexSpace::Function, 5));
+        assert_eq!(unknown(&[0xd0, 0x70, 0x14, 0x05]), (IndexSpace::Type, 5));
+        assert_eq!(unknown(&[0x08, 0x00]), (IndexSpace::Tag, 0));
+
+        // try_table with an invalid catch clause is malformed rather than invalid
+        let code = [0x1f, 0x40, 0x01, 0x04, 0x00, 0x0b];
+        match validate(module(&function(&[], &code))) {
+            ParserError::PositionalError(e, _) => {
+                assert!(matches!(*e, ParserError::InvalidEncoding), "{e}")
+            }
+            e => panic!("expected an invalid encoding, got {e}"),
+        }
+    }
+
+    #[test]
+    fn test_constant_expression_errors() {
+        // global.get 0 in the initializer of the first global, at offset 13 after the section header
+        let (location, func_idx, offset, error) =
+            located(module(&[section(6, 1, &[0x7f, 0x00, 0x23, 0x00, 0x0b])]));
+        assert_eq!((location, func_idx, offset), (Section::Global, None, 13));
+        assert!(matches!(
+            error,
+            ValidationError::UnknownIndex {
+                space: IndexSpace::Global,
+                idx: 0
+            }
+        ));
+
+        // i32 initializer of an i64 global
+        let (location, _, _, error) =
+            located(module(&[section(6, 1, &[0x7e, 0x00, 0x41, 0x00, 0x0b])]));
+        assert_eq!(location, Section::Global);
+        match error {
+            ValidationError::StackMismatch { expected, actual } => {
+                assert_eq!(expected, vec![ValType::i64()]);
+                assert_eq!(actual, vec![ValType::i32()]);
+            }
+            e => panic!("expected a stack mismatch, got {e}"),
+        }
+
+        // active data segment with an unknown global as offset
+        let (location, ..) = located(module(&[
+            section(5, 1, &[0x00, 0x01]),
+            section(11, 1, &[0x00, 0x23, 0x00, 0x0b, 0x00]),
+        ]));
+        assert_eq!(location, Section::Data);
+    }
+
+    #[test]
+    fn test_messages() {
+        let code = [0xfc, 0x10, 0x00, 0x1a];
+        let bytes = module(&function(&[], &code));
+        let offset = code_offset(&bytes, &code);
+        let e = validate(bytes);
+        assert_eq!(
+            e.to_string(),
+            format!(
+                "Validation error: unknown table 0 (Code section, function 0, offset 0x{offset:x})"
+            )
+        );
+        let ParserError::ValidationError(e) = e else {
+            unreachable!()
+        };
+        assert!(matches!(e.kind(), ValidationError::UnknownIndex { .. }));
+
+        let e = validate(module(&[section(6, 1, &[0x7f, 0x00, 0x23, 0x00, 0x0b])]));
+        assert_eq!(
+            e.to_string(),
+            "Validation error: unknown global 0 (Global section, offset 0xd)"
+        );
+    }
 }
@@ -0,0 +1,532 @@
+use super::*;
+use crate::parsable::Parse;
+use module::objects::element::Element;
+
+fn struct_fields(ctxt: &mut Context, type_idx: TypeIdx) -> Vec<FieldType> {
+    match ctxt
+        .module
+        .types
+        .get(type_idx as usize)
+        .and_then(|t| t.as_struct())
+    {
+        Some(fields) => fields.to_vec(),
+        None => ctxt.poison(ValidationError::NotAStructType(type_idx)),
+    }
+}
+
+fn struct_field(ctxt: &mut Context, type_idx: TypeIdx, field_idx: u32) -> Option<FieldType> {
+    let field = struct_fields(ctxt, type_idx)
+        .get(field_idx as usize)
+        .copied();
+    if field.is_none() {
+        ctxt.poison::<()>(ValidationError::UnknownIndex {
+            space: IndexSpace::Field,
+            idx: field_idx,
+        });
+    }
+    field
+}
+
+fn array_field(ctxt: &mut Context, type_idx: TypeIdx) -> Option<FieldType> {
+    let field = ctxt
+        .module
+        .types
+        .get(type_idx as usize)
+        .and_then(|t| t.as_array());
+    if field.is_none() {
+        ctxt.poison::<()>(ValidationError::NotAnArrayType(type_idx));
+    }
+    field
+}
+
+/// packed fields must be read with an explicit extension, other fields without one
+fn validate_field_access(ctxt: &mut Context, field: &FieldType, extension: Option<bool>) {
+    if field.storage_type.is_packed() != extension.is_some() {
+        ctxt.poison(ValidationError::InvalidFieldExtension(*field))
+    }
+}
+
+fn validate_mutable(ctxt: &mut Context, field: &FieldType) {
+    if !field.mutable {
+        ctxt.poison(ValidationError::ImmutableField(*field))
+    }
+}
+
+fn is_defaultable(field: &FieldType) -> bool {
+    match field.storage_type.unpacked() {
+        ValType::Reference(ref_type) => ref_type.is_nullable(),
+        _ => true,
+    }
+}
+
+fn validate_data_segment(ctxt: &mut Context, data_idx: DataIdx, field: &FieldType) {
+    match ctxt.module.datacount {
+        Some(data_count) if data_idx < data_count => {}
+        _ => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Data,
+            idx: data_idx,
+        }),
+    }
+    if matches!(field.storage_type.unpacked(), ValType::Reference(_)) {
+        ctxt.poison(ValidationError::ReferenceArrayFromData)
+    }
+}
+
+fn validate_elem_segment(ctxt: &mut Context, elem_idx: ElemIdx, field: &FieldType) {
+    let elem_type = match ctxt.module.elements.get(elem_idx as usize) {
+        Some(Element { type_, .. }) => *type_,
+        None => {
+            return ctxt.poison(ValidationError::UnknownIndex {
+                space: IndexSpace::Element,
+                idx: elem_idx,
+            })
+        }
+    };
+    if !ctxt.matches(
+        &ValType::Reference(elem_type),
+        &field.storage_type.unpacked(),
+    ) {
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: field.storage_type.unpacked(),
+            actual: ValType::Reference(elem_type),
+        })
+    }
+}
+
+fn concrete_ref(type_idx: TypeIdx, nullable: bool) -> ValType {
+    ValType::Reference(RefType::new(HeapType::Concrete(type_idx), nullable))
+}
+
+pub(crate) fn struct_new(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let mut fields = struct_fields(ctxt, type_idx)
+        .iter()
+        .rev()
+        .map(|field| ctxt.pop_var_with_type(field.storage_type.unpacked()).id)
+        .collect::<Vec<_>>();
+    fields.reverse();
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_struct_new(StructNewInstruction {
+        type_idx,
+        fields,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn struct_new_default(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    if !struct_fields(ctxt, type_idx).iter().all(is_defaultable) {
+        ctxt.poison(ValidationError::NotDefaultable(type_idx))
+    }
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_struct_new(StructNewInstruction {
+        type_idx,
+        fields: Vec::new(),
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn struct_get_extended(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    extension: Option<bool>,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let field_idx = u32::parse(i)?;
+    let field = struct_field(ctxt, type_idx, field_idx).unwrap_or(FieldType {
+        storage_type: StorageType::Val(ValType::i32()),
+        mutable: false,
+    });
+    validate_field_access(ctxt, &field, extension);
+    let in1 = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    let out = ctxt.create_var(field.storage_type.unpacked());
+    o.write_struct_get(StructGetInstruction {
+        type_idx,
+        field_idx,
+        signed: extension.unwrap_or(false),
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn struct_get(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    struct_get_extended(ctxt, i, o, None)
+}
+
+pub(crate) fn struct_get_s(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    struct_get_extended(ctxt, i, o, Some(true))
+}
+
+pub(crate) fn struct_get_u(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    struct_get_extended(ctxt, i, o, Some(false))
+}
+
+pub(crate) fn struct_set(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let field_idx = u32::parse(i)?;
+    let Some(field) = struct_field(ctxt, type_idx, field_idx) else {
+        return Ok(());
+    };
+    validate_mutable(ctxt, &field);
+    let value = ctxt.pop_var_with_type(field.storage_type.unpacked());
+    let in1 = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    o.write_struct_set(StructSetInstruction {
+        type_idx,
+        field_idx,
+        in1: in1.id,
+        in2: value.id,
+    });
+    Ok(())
+}
+
+pub(crate) fn array_new(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let init = ctxt.pop_var_with_type(field.storage_type.unpacked());
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_array_new(ArrayNewInstruction {
+        type_idx,
+        init: Some(init.id),
+        len: len.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_new_default(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    if !is_defaultable(&field) {
+        ctxt.poison(ValidationError::NotDefaultable(type_idx))
+    }
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_array_new(ArrayNewInstruction {
+        type_idx,
+        init: None,
+        len: len.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_new_fixed(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let count = u32::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    let mut values = (0..count)
+        .map(|_| ctxt.pop_var_with_type(field.storage_type.unpacked()).id)
+        .collect::<Vec<_>>();
+    values.reverse();
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_array_new_fixed(ArrayNewFixedInstruction {
+        type_idx,
+        values,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_new_data(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let data_idx = DataIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    validate_data_segment(ctxt, data_idx, &field);
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let offset = ctxt.pop_var_with_type(ValType::i32());
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_array_new_segment(ArrayNewSegmentInstruction {
+        type_idx,
+        segment: ArraySegment::Data(data_idx),
+        offset: offset.id,
+        len: len.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_new_elem(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let elem_idx = ElemIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    validate_elem_segment(ctxt, elem_idx, &field);
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let offset = ctxt.pop_var_with_type(ValType::i32());
+    let out = ctxt.create_var(concrete_ref(type_idx, false));
+    o.write_array_new_segment(ArrayNewSegmentInstruction {
+        type_idx,
+        segment: ArraySegment::Elem(elem_idx),
+        offset: offset.id,
+        len: len.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn array_get_extended(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    extension: Option<bool>,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let field = array_field(ctxt, type_idx).unwrap_or(FieldType {
+        storage_type: StorageType::Val(ValType::i32()),
+        mutable: false,
+    });
+    validate_field_access(ctxt, &field, extension);
+    let idx = ctxt.pop_var_with_type(ValType::i32());
+    let array = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    let out = ctxt.create_var(field.storage_type.unpacked());
+    o.write_array_get(ArrayGetInstruction {
+        type_idx,
+        signed: extension.unwrap_or(false),
+        array: array.id,
+        idx: idx.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_get(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    array_get_extended(ctxt, i, o, None)
+}
+
+pub(crate) fn array_get_s(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    array_get_extended(ctxt, i, o, Some(true))
+}
+
+pub(crate) fn array_get_u(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    array_get_extended(ctxt, i, o, Some(false))
+}
+
+pub(crate) fn array_set(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    validate_mutable(ctxt, &field);
+    let value = ctxt.pop_var_with_type(field.storage_type.unpacked());
+    let idx = ctxt.pop_var_with_type(ValType::i32());
+    let array = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    o.write_array_set(ArraySetInstruction {
+        type_idx,
+        array: array.id,
+        idx: idx.id,
+        value: value.id,
+    });
+    Ok(())
+}
+
+pub(crate) fn array_len(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let array = ctxt.pop_var_with_type(ValType::Reference(RefType::new(HeapType::Array, true)));
+    let out = ctxt.create_var(ValType::i32());
+    o.write_array_len(ArrayLenInstruction {
+        in1: array.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn array_fill(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    validate_mutable(ctxt, &field);
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let value = ctxt.pop_var_with_type(field.storage_type.unpacked());
+    let offset = ctxt.pop_var_with_type(ValType::i32());
+    let array = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    o.write_array_fill(ArrayFillInstruction {
+        type_idx,
+        array: array.id,
+        offset: offset.id,
+        value: value.id,
+        len: len.id,
+    });
+    Ok(())
+}
+
+pub(crate) fn array_copy(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let dst_type_idx = TypeIdx::parse(i)?;
+    let src_type_idx = TypeIdx::parse(i)?;
+    let (Some(dst_field), Some(src_field)) = (
+        array_field(ctxt, dst_type_idx),
+        array_field(ctxt, src_type_idx),
+    ) else {
+        return Ok(());
+    };
+    validate_mutable(ctxt, &dst_field);
+    if !src_field.storage_type.matches(
+        &ctxt.module.types,
+        &dst_field.storage_type,
+        &ctxt.module.types,
+    ) {
+        ctxt.poison(ValidationError::FieldTypeMismatch {
+            expected: dst_field,
+            actual: src_field,
+        })
+    }
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let src_offset = ctxt.pop_var_with_type(ValType::i32());
+    let src = ctxt.pop_var_with_type(concrete_ref(src_type_idx, true));
+    let dst_offset = ctxt.pop_var_with_type(ValType::i32());
+    let dst = ctxt.pop_var_with_type(concrete_ref(dst_type_idx, true));
+    o.write_array_copy(ArrayCopyInstruction {
+        dst_type_idx,
+        src_type_idx,
+        dst: dst.id,
+        dst_offset: dst_offset.id,
+        src: src.id,
+        src_offset: src_offset.id,
+        len: len.id,
+    });
+    Ok(())
+}
+
+fn array_init_segment(
+    ctxt: &mut Context,
+    o: &mut dyn InstructionConsumer,
+    type_idx: TypeIdx,
+    segment: ArraySegment,
+) -> ParseResult {
+    let Some(field) = array_field(ctxt, type_idx) else {
+        return Ok(());
+    };
+    validate_mutable(ctxt, &field);
+    match segment {
+        ArraySegment::Data(data_idx) => validate_data_segment(ctxt, data_idx, &field),
+        ArraySegment::Elem(elem_idx) => validate_elem_segment(ctxt, elem_idx, &field),
+    }
+    let len = ctxt.pop_var_with_type(ValType::i32());
+    let src_offset = ctxt.pop_var_with_type(ValType::i32());
+    let dst_offset = ctxt.pop_var_with_type(ValType::i32());
+    let array = ctxt.pop_var_with_type(concrete_ref(type_idx, true));
+    o.write_array_init_segment(ArrayInitSegmentInstruction {
+        type_idx,
+        segment,
+        array: array.id,
+        dst_offset: dst_offset.id,
+        src_offset: src_offset.id,
+        len: len.id,
+    });
+    Ok(())
+}
+
+pub(crate) fn array_init_data(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let data_idx = DataIdx::parse(i)?;
+    array_init_segment(ctxt, o, type_idx, ArraySegment::Data(data_idx))
+}
+
+pub(crate) fn array_init_elem(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let elem_idx = ElemIdx::parse(i)?;
+    array_init_segment(ctxt, o, type_idx, ArraySegment::Elem(elem_idx))
+}
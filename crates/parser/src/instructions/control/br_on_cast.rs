@@ -0,0 +1,42 @@
+use super::*;
+use wasm_types::LabelIdx;
+
+/// castflags, the first bit marks the operand type nullable, the second bit the target type
+fn parse_cast_types(i: &mut WasmBinaryReader) -> Result<(LabelIdx, RefType, RefType), ParserError> {
+    let flags = i.read_byte()?;
+    if flags > 0b11 {
+        return Err(ParserError::InvalidEncoding);
+    }
+    let label_idx = LabelIdx::parse(i)?;
+    let from = RefType::new(HeapType::parse(i)?, flags & 0b01 != 0);
+    let to = RefType::new(HeapType::parse(i)?, flags & 0b10 != 0);
+    Ok((label_idx, from, to))
+}
+
+pub(crate) fn br_on_cast(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let (label_idx, from, to) = parse_cast_types(i)?;
+    o.write_br_on_cast(BrOnCast {
+        label_idx,
+        from,
+        to,
+    });
+    Ok(())
+}
+
+pub(crate) fn br_on_cast_fail(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let (label_idx, from, to) = parse_cast_types(i)?;
+    o.write_br_on_cast_fail(BrOnCastFail {
+        label_idx,
+        from,
+        to,
+    });
+    Ok(())
+}
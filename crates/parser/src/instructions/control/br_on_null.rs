@@ -0,0 +1,22 @@
+use super::*;
+use wasm_types::LabelIdx;
+
+pub(crate) fn br_on_null(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let label_idx = LabelIdx::parse(i)?;
+    o.write_br_on_null(BrOnNull { label_idx });
+    Ok(())
+}
+
+pub(crate) fn br_on_non_null(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let label_idx = LabelIdx::parse(i)?;
+    o.write_br_on_non_null(BrOnNonNull { label_idx });
+    Ok(())
+}
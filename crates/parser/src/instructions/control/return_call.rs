@@ -0,0 +1,12 @@
+use super::*;
+use wasm_types::FuncIdx;
+
+pub(crate) fn return_call(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let func_idx = FuncIdx::parse(i)?;
+    o.write_return_call(ReturnCall { func_idx });
+    Ok(())
+}
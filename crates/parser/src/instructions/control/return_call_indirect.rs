@@ -0,0 +1,16 @@
+use super::*;
+use wasm_types::{TableIdx, TypeIdx};
+
+pub(crate) fn return_call_indirect(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    let table_idx = TableIdx::parse(i)?;
+    o.write_return_call_indirect(ReturnCallIndirect {
+        type_idx,
+        table_idx,
+    });
+    Ok(())
+}
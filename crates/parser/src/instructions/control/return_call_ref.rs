@@ -0,0 +1,12 @@
+use super::*;
+use wasm_types::TypeIdx;
+
+pub(crate) fn return_call_ref(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let type_idx = TypeIdx::parse(i)?;
+    o.write_return_call_ref(ReturnCallRef { type_idx });
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use super::*;
+use wasm_types::TagIdx;
+
+pub(crate) fn throw(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let tag_idx = TagIdx::parse(i)?;
+    o.write_throw(Throw { tag_idx });
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use super::*;
+
+pub(crate) fn throw_ref(
+    _: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    o.write_throw_ref();
+    Ok(())
+}
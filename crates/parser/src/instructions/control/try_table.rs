@@ -0,0 +1,29 @@
+use super::*;
+use crate::parsable::ParseWithContext;
+use module::objects::instruction::Catch;
+use wasm_types::{LabelIdx, TagIdx};
+
+pub(crate) fn try_table(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let block_type = BlockType::parse_with_context(i, ctxt.module)?;
+    let num_catches = i.read_leb128::<u32>()?;
+    let catches = (0..num_catches)
+        .map(|_| {
+            Ok(match i.read_byte()? {
+                0x00 => Catch::Catch(TagIdx::parse(i)?, LabelIdx::parse(i)?),
+                0x01 => Catch::CatchRef(TagIdx::parse(i)?, LabelIdx::parse(i)?),
+                0x02 => Catch::CatchAll(LabelIdx::parse(i)?),
+                0x03 => Catch::CatchAllRef(LabelIdx::parse(i)?),
+                _ => return Err(ParserError::InvalidEncoding),
+            })
+        })
+        .collect::<Result<Vec<Catch>, ParserError>>()?;
+    o.write_try_table(TryTable {
+        block_type,
+        catches,
+    });
+    Ok(())
+}
@@ -0,0 +1,222 @@
+use super::*;
+
+/// Atomic accesses must be naturally aligned, i.e. the alignment hint must equal the access width.
+fn parse_atomic_memarg(
+    ctxt: &Context,
+    i: &mut WasmBinaryReader,
+    width: u8,
+) -> Result<(MemArg, IndexType), ParserError> {
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
+    if memarg.align >= 32 || 2_u32.pow(memarg.align) != width as u32 {
+        return Err(ParserError::AlignmentNotNatural);
+    }
+    Ok((memarg, index_type))
+}
+
+fn parse_atomic_load(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    out_type: NumType,
+    width: u8,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, width)?;
+
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::Number(out_type));
+    o.write_atomic_load(AtomicLoadInstruction {
+        memarg,
+        width,
+        out1_type: out_type,
+        addr: addr.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_atomic_store(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    in_type: NumType,
+    width: u8,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, width)?;
+
+    let value = ctxt.pop_var_with_type(ValType::Number(in_type));
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    o.write_atomic_store(AtomicStoreInstruction {
+        memarg,
+        width,
+        in_type,
+        addr_in: addr.id,
+        value_in: value.id,
+    });
+    Ok(())
+}
+
+fn parse_atomic_rmw(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    r#type: NumType,
+    width: u8,
+    op: AtomicRmwOp,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, width)?;
+
+    let value = ctxt.pop_var_with_type(ValType::Number(r#type));
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::Number(r#type));
+    o.write_atomic_rmw(AtomicRmwInstruction {
+        memarg,
+        op,
+        width,
+        r#type,
+        addr: addr.id,
+        value: value.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_atomic_cmpxchg(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    r#type: NumType,
+    width: u8,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, width)?;
+
+    let replacement = ctxt.pop_var_with_type(ValType::Number(r#type));
+    let expected = ctxt.pop_var_with_type(ValType::Number(r#type));
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::Number(r#type));
+    o.write_atomic_cmpxchg(AtomicCmpxchgInstruction {
+        memarg,
+        width,
+        r#type,
+        addr: addr.id,
+        expected: expected.id,
+        replacement: replacement.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_atomic_wait(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    r#type: NumType,
+    width: u8,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, width)?;
+
+    let timeout = ctxt.pop_var_with_type(ValType::i64());
+    let expected = ctxt.pop_var_with_type(ValType::Number(r#type));
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::i32());
+    o.write_atomic_wait(AtomicWaitInstruction {
+        memarg,
+        r#type,
+        addr: addr.id,
+        expected: expected.id,
+        timeout: timeout.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn memory_atomic_notify(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let (memarg, index_type) = parse_atomic_memarg(ctxt, i, 4)?;
+
+    let count = ctxt.pop_var_with_type(ValType::i32());
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::i32());
+    o.write_atomic_notify(AtomicNotifyInstruction {
+        memarg,
+        addr: addr.id,
+        count: count.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn atomic_fence(
+    _: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    if i.read_byte()? != 0 {
+        return Err(ParserError::Msg(
+            "atomic fence instruction invalid encoding".into(),
+        ));
+    }
+    o.write_atomic_fence(AtomicFenceInstruction {});
+    Ok(())
+}
+
+#[rustfmt::skip]
+mod atomic_specializations {
+    use super::*;
+    pub(crate) fn memory_atomic_wait32(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_wait(c, i, o, NumType::I32, 4)}
+    pub(crate) fn memory_atomic_wait64(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_wait(c, i, o, NumType::I64, 8)}
+
+    pub(crate) fn i32_atomic_load(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I32, 4)}
+    pub(crate) fn i64_atomic_load(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I64, 8)}
+    pub(crate) fn i32_atomic_load8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I32, 1)}
+    pub(crate) fn i32_atomic_load16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I32, 2)}
+    pub(crate) fn i64_atomic_load8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I64, 1)}
+    pub(crate) fn i64_atomic_load16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I64, 2)}
+    pub(crate) fn i64_atomic_load32_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_load(c, i, o, NumType::I64, 4)}
+
+    pub(crate) fn i32_atomic_store(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I32, 4)}
+    pub(crate) fn i64_atomic_store(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I64, 8)}
+    pub(crate) fn i32_atomic_store8(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I32, 1)}
+    pub(crate) fn i32_atomic_store16(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I32, 2)}
+    pub(crate) fn i64_atomic_store8(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I64, 1)}
+    pub(crate) fn i64_atomic_store16(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I64, 2)}
+    pub(crate) fn i64_atomic_store32(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_store(c, i, o, NumType::I64, 4)}
+
+    macro_rules! rmw_specializations {
+        ($op:expr, $i32:ident, $i64:ident, $i32_8:ident, $i32_16:ident, $i64_8:ident, $i64_16:ident, $i64_32:ident) => {
+            pub(crate) fn $i32(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I32, 4, $op)}
+            pub(crate) fn $i64(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I64, 8, $op)}
+            pub(crate) fn $i32_8(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I32, 1, $op)}
+            pub(crate) fn $i32_16(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I32, 2, $op)}
+            pub(crate) fn $i64_8(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I64, 1, $op)}
+            pub(crate) fn $i64_16(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I64, 2, $op)}
+            pub(crate) fn $i64_32(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_rmw(c, i, o, NumType::I64, 4, $op)}
+        };
+    }
+    rmw_specializations!(AtomicRmwOp::Add, i32_atomic_rmw_add, i64_atomic_rmw_add, i32_atomic_rmw8_add_u, i32_atomic_rmw16_add_u, i64_atomic_rmw8_add_u, i64_atomic_rmw16_add_u, i64_atomic_rmw32_add_u);
+    rmw_specializations!(AtomicRmwOp::Sub, i32_atomic_rmw_sub, i64_atomic_rmw_sub, i32_atomic_rmw8_sub_u, i32_atomic_rmw16_sub_u, i64_atomic_rmw8_sub_u, i64_atomic_rmw16_sub_u, i64_atomic_rmw32_sub_u);
+    rmw_specializations!(AtomicRmwOp::And, i32_atomic_rmw_and, i64_atomic_rmw_and, i32_atomic_rmw8_and_u, i32_atomic_rmw16_and_u, i64_atomic_rmw8_and_u, i64_atomic_rmw16_and_u, i64_atomic_rmw32_and_u);
+    rmw_specializations!(AtomicRmwOp::Or, i32_atomic_rmw_or, i64_atomic_rmw_or, i32_atomic_rmw8_or_u, i32_atomic_rmw16_or_u, i64_atomic_rmw8_or_u, i64_atomic_rmw16_or_u, i64_atomic_rmw32_or_u);
+    rmw_specializations!(AtomicRmwOp::Xor, i32_atomic_rmw_xor, i64_atomic_rmw_xor, i32_atomic_rmw8_xor_u, i32_atomic_rmw16_xor_u, i64_atomic_rmw8_xor_u, i64_atomic_rmw16_xor_u, i64_atomic_rmw32_xor_u);
+    rmw_specializations!(AtomicRmwOp::Xchg, i32_atomic_rmw_xchg, i64_atomic_rmw_xchg, i32_atomic_rmw8_xchg_u, i32_atomic_rmw16_xchg_u, i64_atomic_rmw8_xchg_u, i64_atomic_rmw16_xchg_u, i64_atomic_rmw32_xchg_u);
+
+    pub(crate) fn i32_atomic_rmw_cmpxchg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I32, 4)}
+    pub(crate) fn i64_atomic_rmw_cmpxchg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I64, 8)}
+    pub(crate) fn i32_atomic_rmw8_cmpxchg_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I32, 1)}
+    pub(crate) fn i32_atomic_rmw16_cmpxchg_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I32, 2)}
+    pub(crate) fn i64_atomic_rmw8_cmpxchg_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I64, 1)}
+    pub(crate) fn i64_atomic_rmw16_cmpxchg_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I64, 2)}
+    pub(crate) fn i64_atomic_rmw32_cmpxchg_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_atomic_cmpxchg(c, i, o, NumType::I64, 4)}
+}
+pub(crate) use atomic_specializations::*;
+use module::instructions::{
+    AtomicCmpxchgInstruction, AtomicFenceInstruction, AtomicLoadInstruction,
+    AtomicNotifyInstruction, AtomicRmwInstruction, AtomicStoreInstruction, AtomicWaitInstruction,
+};
@@ -7,7 +7,7 @@ fn parse_load(
     out_type: NumType,
     operation: LoadOp,
 ) -> ParseResult {
-    let memarg = MemArg::parse(i)?;
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
     let natural_alignment = match operation {
         LoadOp::INNLoad | LoadOp::FNNLoad => match out_type {
             NumType::I32 | NumType::F32 => 4,
@@ -21,7 +21,7 @@ fn parse_load(
         return Err(ParserError::AlignmentLargerThanNatural);
     }
 
-    let in_ = ctxt.pop_var_with_type(ValType::i32());
+    let in_ = ctxt.pop_var_with_type(index_type.val_type());
     let out = ctxt.create_var(ValType::Number(out_type));
     o.write_load(LoadInstruction {
         memarg,
@@ -55,4 +55,4 @@ mod load_specializations {
     pub(crate) fn i64_load32_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_load(c, i, o, NumType::I64, LoadOp::INNLoad32U)}
 }
 pub(crate) use load_specializations::*;
-use module::{instructions::LoadInstruction, objects::memory::MemArg};
+use module::instructions::LoadInstruction;
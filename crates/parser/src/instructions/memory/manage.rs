@@ -5,14 +5,14 @@ pub(crate) fn memory_size(
     i: &mut WasmBinaryReader,
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
-    if i.read_byte()? != 0 {
-        return Err(ParserError::Msg(
-            "memory size instruction invalid encoding".into(),
-        ));
-    }
+    let memory_idx = MemIdx::parse(i)?;
+    let index_type = memory_index_type(ctxt, memory_idx)?;
 
-    let out = ctxt.create_var(ValType::i32());
-    o.write_memory_size(MemorySizeInstruction { out1: out.id });
+    let out = ctxt.create_var(index_type.val_type());
+    o.write_memory_size(MemorySizeInstruction {
+        memory_idx,
+        out1: out.id,
+    });
     ctxt.push_var(out);
     Ok(())
 }
@@ -22,15 +22,13 @@ pub(crate) fn memory_grow(
     i: &mut WasmBinaryReader,
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
-    if i.read_byte()? != 0 {
-        return Err(ParserError::Msg(
-            "memory grow instruction invalid encoding".into(),
-        ));
-    }
+    let memory_idx = MemIdx::parse(i)?;
+    let index_type = memory_index_type(ctxt, memory_idx)?;
 
-    let size_in = ctxt.pop_var_with_type(ValType::i32());
-    let out = ctxt.create_var(ValType::i32());
+    let size_in = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(index_type.val_type());
     o.write_memory_grow(MemoryGrowInstruction {
+        memory_idx,
         in1: size_in.id,
         out1: out.id,
     });
@@ -43,19 +41,17 @@ pub(crate) fn memory_copy(
     i: &mut WasmBinaryReader,
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
-    if i.read_byte()? != 0 || i.read_byte()? != 0 {
-        return Err(ParserError::Msg(
-            "memory copy instruction invalid encoding".into(),
-        ));
-    }
-    if ctxt.module.memories.is_empty() {
-        return Err(ParserError::Msg("unknown memory 0".into()));
-    }
-
-    let n = ctxt.pop_var_with_type(ValType::i32());
-    let s = ctxt.pop_var_with_type(ValType::i32());
-    let d = ctxt.pop_var_with_type(ValType::i32());
+    let memory_idx_x = MemIdx::parse(i)?;
+    let memory_idx_y = MemIdx::parse(i)?;
+    let dst_index_type = memory_index_type(ctxt, memory_idx_x)?;
+    let src_index_type = memory_index_type(ctxt, memory_idx_y)?;
+    // copies between a 32-bit and a 64-bit memory take a 32-bit length
+    let n = ctxt.pop_var_with_type(dst_index_type.min(src_index_type).val_type());
+    let s = ctxt.pop_var_with_type(src_index_type.val_type());
+    let d = ctxt.pop_var_with_type(dst_index_type.val_type());
     o.write_memory_copy(MemoryCopyInstruction {
+        memory_idx_x,
+        memory_idx_y,
         n: n.id,
         s: s.id,
         d: d.id,
@@ -68,19 +64,14 @@ pub(crate) fn memory_fill(
     i: &mut WasmBinaryReader,
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
-    if i.read_byte()? != 0 {
-        return Err(ParserError::Msg(
-            "memory fill instruction invalid encoding".into(),
-        ));
-    }
-    if ctxt.module.memories.is_empty() {
-        return Err(ParserError::Msg("unknown memory 0".into()));
-    }
+    let memory_idx = MemIdx::parse(i)?;
+    let index_type = memory_index_type(ctxt, memory_idx)?;
 
-    let n = ctxt.pop_var_with_type(ValType::i32());
+    let n = ctxt.pop_var_with_type(index_type.val_type());
     let val = ctxt.pop_var_with_type(ValType::i32());
-    let d = ctxt.pop_var_with_type(ValType::i32());
+    let d = ctxt.pop_var_with_type(index_type.val_type());
     o.write_memory_fill(MemoryFillInstruction {
+        memory_idx,
         n: n.id,
         val: val.id,
         d: d.id,
@@ -97,27 +88,25 @@ pub(crate) fn memory_init(
 
     if let Some(data_count) = ctxt.module.datacount {
         if data_idx >= data_count {
-            ctxt.poison(ValidationError::Msg(
-                "memory init instruction data index out of bounds".into(),
-            ))
+            ctxt.poison(ValidationError::UnknownIndex {
+                space: IndexSpace::Data,
+                idx: data_idx,
+            })
         }
     } else {
-        ctxt.poison(ValidationError::Msg(
-            "memory init instruction without data section".into(),
-        ))
+        ctxt.poison(ValidationError::DataCountRequired)
     }
 
-    if i.read_byte()? != 0 {
-        return Err(ParserError::Msg(
-            "memory init instruction invalid encoding".into(),
-        ));
-    }
+    let memory_idx = MemIdx::parse(i)?;
+    let index_type = memory_index_type(ctxt, memory_idx)?;
+
     let n = ctxt.pop_var_with_type(ValType::i32());
     let s = ctxt.pop_var_with_type(ValType::i32());
-    let d = ctxt.pop_var_with_type(ValType::i32());
+    let d = ctxt.pop_var_with_type(index_type.val_type());
 
     o.write_memory_init(MemoryInitInstruction {
         data_idx,
+        memory_idx,
         n: n.id,
         s: s.id,
         d: d.id,
@@ -133,14 +122,13 @@ pub(crate) fn data_drop(
     let data_idx = DataIdx::parse(i)?;
     if let Some(data_count) = ctxt.module.datacount {
         if data_idx >= data_count {
-            ctxt.poison(ValidationError::Msg(
-                "memory init instruction data index out of bounds".into(),
-            ))
+            ctxt.poison(ValidationError::UnknownIndex {
+                space: IndexSpace::Data,
+                idx: data_idx,
+            })
         }
     } else {
-        ctxt.poison(ValidationError::Msg(
-            "memory init instruction without data section".into(),
-        ))
+        ctxt.poison(ValidationError::DataCountRequired)
     }
 
     o.write_data_drop(DataDropInstruction { data_idx });
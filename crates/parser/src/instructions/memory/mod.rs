@@ -1,7 +1,9 @@
+pub(crate) mod atomic;
 pub(crate) mod load;
 pub(crate) mod manage;
 pub(crate) mod store;
 
+pub(crate) use atomic::*;
 pub(crate) use load::*;
 pub(crate) use manage::*;
 pub(crate) use store::*;
@@ -10,3 +12,28 @@ use super::*;
 use crate::parsable::Parse;
 use module::objects::memory::*;
 use wasm_types::*;
+
+/// Index type of memory `memory_idx`, memory instructions are only valid if the module declares the memory.
+pub(crate) fn memory_index_type(
+    ctxt: &Context,
+    memory_idx: MemIdx,
+) -> Result<IndexType, ParserError> {
+    match ctxt.module.memories.get(memory_idx as usize) {
+        Some(memory) => Ok(memory.r#type.index_type),
+        None => Err(ParserError::UnknownMemory(memory_idx)),
+    }
+}
+
+/// Parses the memarg of a memory access and returns it along with the accessed memory's index type.
+/// Offsets into 32-bit memories must fit into `u32`.
+pub(crate) fn parse_memarg(
+    ctxt: &Context,
+    i: &mut WasmBinaryReader,
+) -> Result<(MemArg, IndexType), ParserError> {
+    let memarg = MemArg::parse(i)?;
+    let index_type = memory_index_type(ctxt, memarg.memory)?;
+    if index_type == IndexType::I32 && memarg.offset > u32::MAX as u64 {
+        return Err(ParserError::MemArgOffsetOutOfRange);
+    }
+    Ok((memarg, index_type))
+}
@@ -7,9 +7,9 @@ fn parse_store(
     input_type: NumType,
     operation: StoreOp,
 ) -> ParseResult {
-    let memarg = MemArg::parse(i)?;
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
     let value_in = ctxt.pop_var_with_type(ValType::Number(input_type));
-    let addr_in = ctxt.pop_var_with_type(ValType::i32());
+    let addr_in = ctxt.pop_var_with_type(index_type.val_type());
     o.write_store(StoreInstruction {
         memarg,
         addr_in: addr_in.id,
@@ -1,3 +1,4 @@
+mod aggregate;
 mod control;
 mod memory;
 pub(crate) mod meta;
@@ -6,7 +7,9 @@ mod parametric;
 mod reference;
 mod table;
 mod variable;
+mod vector;
 
+pub(crate) use aggregate::*;
 pub(crate) use control::*;
 pub(crate) use memory::*;
 pub(crate) use numeric::*;
@@ -14,10 +17,13 @@ pub(crate) use parametric::*;
 pub(crate) use reference::*;
 pub(crate) use table::*;
 pub(crate) use variable::*;
+pub(crate) use vector::*;
 
 use crate::ir::context::Context;
 use crate::parsable::Parse;
-use crate::{wasm_stream_reader::WasmBinaryReader, ParseResult, ParserError, ValidationError};
+use crate::{
+    wasm_stream_reader::WasmBinaryReader, IndexSpace, ParseResult, ParserError, ValidationError,
+};
 use module::instructions::*;
 use module::InstructionConsumer;
 use wasm_types::*;
@@ -21,17 +21,14 @@ pub(crate) fn select_numeric(
     let val2 = ctxt.pop_var();
     let val1 = ctxt.pop_var();
     if val1.type_ != val2.type_ {
-        ctxt.poison(ValidationError::Msg(format!(
-            "type mismatch for select: {:?} != {:?}",
-            val1.type_, val2.type_
-        )))
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: val1.type_,
+            actual: val2.type_,
+        })
     }
     match &val1.type_ {
         ValType::Number(_) | ValType::VecType => {}
-        _ => ctxt.poison(ValidationError::Msg(format!(
-            "invalid type for numeric select: {:?}",
-            val1.type_
-        ))),
+        _ => ctxt.poison(ValidationError::InvalidSelectType(val1.type_)),
     }
 
     let out = ctxt.create_var(val1.type_);
@@ -51,9 +48,7 @@ pub(crate) fn select_generic(
 ) -> ParseResult {
     let num_val_types = i.read_leb128::<u32>()?;
     if num_val_types != 1 {
-        ctxt.poison(ValidationError::Msg(format!(
-            "invalid number of val types for select: {num_val_types} != 1"
-        )))
+        ctxt.poison(ValidationError::InvalidSelectArity(num_val_types))
     }
     let val_type = ValType::parse(i)?;
     let select_val = ctxt.pop_var_with_type(ValType::i32());
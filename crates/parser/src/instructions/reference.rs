@@ -10,9 +10,10 @@ pub(crate) fn ref_is_null(
     let out = ctxt.create_var(ValType::i32());
 
     if !matches!(val.type_, ValType::Reference(_)) {
-        ctxt.poison::<()>(ValidationError::Msg(
-            "ref.is_null expects a reference type on stack".into(),
-        ));
+        ctxt.poison::<()>(ValidationError::ExpectedReference {
+            instruction: "ref.is_null",
+            actual: val.type_,
+        });
     } else {
         o.write_reference_is_null(ReferenceIsNullInstruction {
             in1: val.id,
@@ -29,7 +30,7 @@ pub(crate) fn ref_null(
     i: &mut WasmBinaryReader,
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
-    let ref_type = RefType::parse(i)?;
+    let ref_type = RefType::new(HeapType::parse(i)?, true);
     let out = ctxt.create_var(ValType::Reference(ref_type));
     o.write_reference_null(ReferenceNullInstruction {
         out1: out.id,
@@ -45,7 +46,11 @@ pub(crate) fn ref_func(
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
     let func_idx = FuncIdx::parse(i)?;
-    let out = ctxt.create_var(ValType::funcref());
+    let ref_type = match ctxt.module.functions.get(func_idx as usize) {
+        Some(function) => RefType::new(HeapType::Concrete(function.type_idx), false),
+        None => RefType::FunctionReference,
+    };
+    let out = ctxt.create_var(ValType::Reference(ref_type));
     o.write_reference_function(ReferenceFunctionInstruction {
         out1: out.id,
         func_idx,
@@ -53,3 +58,215 @@ pub(crate) fn ref_func(
     ctxt.push_var(out);
     Ok(())
 }
+
+pub(crate) fn ref_as_non_null(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let val = ctxt.pop_var();
+    let ValType::Reference(ref_type) = val.type_ else {
+        ctxt.poison::<()>(ValidationError::ExpectedReference {
+            instruction: "ref.as_non_null",
+            actual: val.type_,
+        });
+        ctxt.push_var(val);
+        return Ok(());
+    };
+    let out = ctxt.create_var(ValType::Reference(ref_type.as_non_null()));
+    o.write_reference_as_non_null(ReferenceAsNonNullInstruction {
+        in1: val.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn ref_eq(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let eqref = ValType::Reference(RefType::new(HeapType::Eq, true));
+    let in2 = ctxt.pop_var_with_type(eqref);
+    let in1 = ctxt.pop_var_with_type(eqref);
+    let out = ctxt.create_var(ValType::i32());
+    o.write_reference_eq(ReferenceEqInstruction {
+        in1: in1.id,
+        in2: in2.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+/// the operand must be of the same type hierarchy as the target type
+fn pop_cast_operand(ctxt: &mut Context, ref_type: RefType) -> Variable {
+    let val = ctxt.pop_var();
+    let top = match ref_type.heap_type() {
+        HeapType::Func | HeapType::NoFunc => HeapType::Func,
+        HeapType::Extern | HeapType::NoExtern => HeapType::Extern,
+        HeapType::Exn | HeapType::NoExn => HeapType::Exn,
+        HeapType::Concrete(type_idx)
+            if matches!(
+                ctxt.module.types.get(type_idx as usize),
+                Some(SubType {
+                    composite_type: CompositeType::Func(_),
+                    ..
+                })
+            ) =>
+        {
+            HeapType::Func
+        }
+        _ => HeapType::Any,
+    };
+    if !ctxt.matches(&val.type_, &ValType::Reference(RefType::new(top, true))) {
+        ctxt.poison::<()>(ValidationError::CastMismatch {
+            target: ref_type,
+            actual: val.type_,
+        });
+    }
+    val
+}
+
+fn ref_test_nullable(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    nullable: bool,
+) -> ParseResult {
+    let ref_type = RefType::new(HeapType::parse(i)?, nullable);
+    let val = pop_cast_operand(ctxt, ref_type);
+    let out = ctxt.create_var(ValType::i32());
+    o.write_reference_test(ReferenceTestInstruction {
+        in1: val.id,
+        ref_type,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn ref_test(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    ref_test_nullable(ctxt, i, o, false)
+}
+
+pub(crate) fn ref_test_null(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    ref_test_nullable(ctxt, i, o, true)
+}
+
+fn ref_cast_nullable(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+    nullable: bool,
+) -> ParseResult {
+    let ref_type = RefType::new(HeapType::parse(i)?, nullable);
+    let val = pop_cast_operand(ctxt, ref_type);
+    let out = ctxt.create_var(ValType::Reference(ref_type));
+    o.write_reference_cast(ReferenceCastInstruction {
+        in1: val.id,
+        ref_type,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn ref_cast(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    ref_cast_nullable(ctxt, i, o, false)
+}
+
+pub(crate) fn ref_cast_null(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    ref_cast_nullable(ctxt, i, o, true)
+}
+
+/// `any.convert_extern` and `extern.convert_any` share the representation of both hierarchies and only
+/// change the static type of the operand.
+fn convert_hierarchy(ctxt: &mut Context, from: HeapType, to: HeapType) -> ParseResult {
+    let val = ctxt.pop_var_with_type(ValType::Reference(RefType::new(from, true)));
+    let nullable = match val.type_ {
+        ValType::Reference(ref_type) => ref_type.is_nullable(),
+        _ => true,
+    };
+    ctxt.push_var(Variable {
+        id: val.id,
+        type_: ValType::Reference(RefType::new(to, nullable)),
+    });
+    Ok(())
+}
+
+pub(crate) fn any_convert_extern(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    _: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    convert_hierarchy(ctxt, HeapType::Extern, HeapType::Any)
+}
+
+pub(crate) fn extern_convert_any(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    _: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    convert_hierarchy(ctxt, HeapType::Any, HeapType::Extern)
+}
+
+pub(crate) fn ref_i31(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let val = ctxt.pop_var_with_type(ValType::i32());
+    let out = ctxt.create_var(ValType::Reference(RefType::new(HeapType::I31, false)));
+    o.write_reference_i31(ReferenceI31Instruction {
+        in1: val.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn i31_get(ctxt: &mut Context, o: &mut dyn InstructionConsumer, signed: bool) -> ParseResult {
+    let val = ctxt.pop_var_with_type(ValType::Reference(RefType::new(HeapType::I31, true)));
+    let out = ctxt.create_var(ValType::i32());
+    o.write_i31_get(I31GetInstruction {
+        in1: val.id,
+        signed,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn i31_get_s(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    i31_get(ctxt, o, true)
+}
+
+pub(crate) fn i31_get_u(
+    ctxt: &mut Context,
+    _: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    i31_get(ctxt, o, false)
+}
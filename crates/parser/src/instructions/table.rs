@@ -10,17 +10,20 @@ pub(crate) fn table_set(
 ) -> ParseResult {
     let table_idx = TableIdx::parse(i)?;
     if table_idx as usize >= ctxt.module.tables.len() {
-        return Ok(ctxt.poison::<()>(ValidationError::Msg(format!(
-            "table with id {table_idx} not found"
-        ))));
+        return Ok(ctxt.poison::<()>(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx,
+        }));
     }
     let table = &ctxt.module.tables[table_idx as usize];
     let value_to_set = ctxt.pop_var();
+    let table_ref_type = ValType::Reference(table.r#type.ref_type);
     match value_to_set.type_ {
-        ValType::Reference(rt) if rt == table.r#type.ref_type => {}
-        _ => ctxt.poison(ValidationError::Msg(
-            "table value to set must be of reference type".into(),
-        )),
+        ValType::Reference(_) if ctxt.matches(&value_to_set.type_, &table_ref_type) => {}
+        _ => ctxt.poison(ValidationError::TypeMismatch {
+            expected: table_ref_type,
+            actual: value_to_set.type_,
+        }),
     }
     let idx = ctxt.pop_var_with_type(ValType::i32());
     o.write_table_set(TableSetInstruction {
@@ -41,9 +44,10 @@ pub(crate) fn table_get(
     let table = match ctxt.module.tables.get(table_idx as usize) {
         Some(table) => table,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "table with id {table_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Table,
+                idx: table_idx,
+            });
             return Ok(());
         }
     };
@@ -68,9 +72,10 @@ pub(crate) fn table_grow(
     let table = match ctxt.module.tables.get(table_idx as usize) {
         Some(table) => table,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "table with id {table_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Table,
+                idx: table_idx,
+            });
             return Ok(());
         }
     };
@@ -80,10 +85,10 @@ pub(crate) fn table_grow(
     let value_to_fill = ctxt.pop_var_with_type(table_type);
     match value_to_fill.type_ {
         ValType::Reference(_) => {}
-        _ => ctxt.poison(ValidationError::Msg(format!(
-            "type {:?} of value supplied to table.grow instruction does not match table type {:?}.",
-            value_to_fill.type_, table_type
-        ))),
+        _ => ctxt.poison(ValidationError::TypeMismatch {
+            expected: table_type,
+            actual: value_to_fill.type_,
+        }),
     }
     let out = ctxt.create_var(ValType::i32());
     o.write_table_grow(TableGrowInstruction {
@@ -102,10 +107,11 @@ pub(crate) fn table_size(
     o: &mut dyn InstructionConsumer,
 ) -> ParseResult {
     let table_idx = TableIdx::parse(i)?;
-    if table_idx as usize > ctxt.module.tables.len() {
-        ctxt.poison(ValidationError::Msg(format!(
-            "table with id {table_idx} not found",
-        )))
+    if table_idx as usize >= ctxt.module.tables.len() {
+        ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx,
+        })
     }
     let out = ctxt.create_var(ValType::i32());
     o.write_table_size(TableSizeInstruction {
@@ -129,10 +135,10 @@ pub(crate) fn table_fill(
     // validate
     let ref_value_type = match ref_value.type_ {
         ValType::Reference(ref_type) => ref_type,
-        _ => ctxt.poison(ValidationError::Msg(format!(
-            "table value to set must be of reference type, but got {:?}",
-            ref_value.type_
-        ))),
+        _ => ctxt.poison(ValidationError::ExpectedReference {
+            instruction: "table.fill",
+            actual: ref_value.type_,
+        }),
     };
     match ctxt
         .module
@@ -142,14 +148,16 @@ pub(crate) fn table_fill(
     {
         Some(table_type) => {
             if ref_value_type != table_type {
-                ctxt.poison(ValidationError::Msg(format!(
-                    "table value to set must be of reference type {table_type:?}, but got {ref_value_type:?}"
-                )))
+                ctxt.poison(ValidationError::TypeMismatch {
+                    expected: ValType::Reference(table_type),
+                    actual: ValType::Reference(ref_value_type),
+                })
             }
         }
-        None => ctxt.poison(ValidationError::Msg(format!(
-            "table with id {table_idx} does not exist"
-        ))),
+        None => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx,
+        }),
     }
 
     o.write_table_fill(TableFillInstruction {
@@ -180,9 +188,10 @@ pub(crate) fn table_copy(
         .map(|t| t.r#type.ref_type)
     {
         Some(table_type) => table_type,
-        None => ctxt.poison(ValidationError::Msg(format!(
-            "table with id {table_idx_x} does not exist"
-        ))),
+        None => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx_x,
+        }),
     };
 
     let table_type_y = match ctxt
@@ -192,15 +201,17 @@ pub(crate) fn table_copy(
         .map(|t| t.r#type.ref_type)
     {
         Some(table_type) => table_type,
-        None => ctxt.poison(ValidationError::Msg(format!(
-            "table with id {table_idx_y} does not exist"
-        ))),
+        None => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx_y,
+        }),
     };
 
     if table_type_x != table_type_y {
-        ctxt.poison(ValidationError::Msg(format!(
-            "table types must match, but got {table_type_x:?} and {table_type_y:?}"
-        )))
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: ValType::Reference(table_type_x),
+            actual: ValType::Reference(table_type_y),
+        })
     }
 
     o.write_table_copy(TableCopyInstruction {
@@ -232,22 +243,25 @@ pub(crate) fn table_init(
         .map(|t| t.r#type.ref_type)
     {
         Some(table_type) => table_type,
-        None => ctxt.poison(ValidationError::Msg(format!(
-            "table with id {table_idx} does not exist",
-        ))),
+        None => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Table,
+            idx: table_idx,
+        }),
     };
     let elem_type = match ctxt.module.elements.get(elem_idx as usize) {
         Some(Element {
             type_: elem_type, ..
         }) => *elem_type,
-        None => ctxt.poison(ValidationError::Msg(format!(
-            "elem with id {elem_idx} does not exist",
-        ))),
+        None => ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Element,
+            idx: elem_idx,
+        }),
     };
     if table_type != elem_type {
-        ctxt.poison(ValidationError::Msg(format!(
-            "table type {table_type:?} must match elem type {elem_type:?}",
-        )))
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: ValType::Reference(table_type),
+            actual: ValType::Reference(elem_type),
+        })
     }
 
     o.write_table_init(TableInitInstruction {
@@ -269,9 +283,10 @@ pub(crate) fn elem_drop(
 
     // validate
     if ctxt.module.elements.get(elem_idx as usize).is_none() {
-        ctxt.poison(ValidationError::Msg(format!(
-            "elem with idx {elem_idx} does not exist",
-        )))
+        ctxt.poison(ValidationError::UnknownIndex {
+            space: IndexSpace::Element,
+            idx: elem_idx,
+        })
     }
 
     o.write_elem_drop(ElemDropInstruction { elem_idx });
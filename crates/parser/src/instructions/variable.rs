@@ -11,9 +11,10 @@ pub(crate) fn local_get(
     let local_ty = match ctxt.locals.get(local_idx as usize) {
         Some(local_ty) => local_ty,
         None => {
-            return Err(ValidationError::Msg(format!(
-                "local variable with index {local_idx} not found",
-            ))
+            return Err(ValidationError::UnknownIndex {
+                space: IndexSpace::Local,
+                idx: local_idx,
+            }
             .into());
         }
     };
@@ -36,9 +37,10 @@ pub(crate) fn global_get(
     let global = match ctxt.module.globals.get(global_idx as usize) {
         Some(g) => g,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "global variable with index {global_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Global,
+                idx: global_idx,
+            });
             return Ok(());
         }
     };
@@ -65,19 +67,20 @@ pub(crate) fn local_set(
     let local_ty = match ctxt.locals.get(local_idx as usize) {
         Some(local_ty) => *local_ty,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "local variable with index {local_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Local,
+                idx: local_idx,
+            });
             return Ok(());
         }
     };
 
     let value = ctxt.pop_var_with_type(local_ty);
-    if value.type_ != local_ty {
-        ctxt.poison(ValidationError::Msg(format!(
-            "stack value type does not match local {} type: {:?} vs {:?}",
-            local_idx, value.type_, local_ty
-        )))
+    if !ctxt.matches(&value.type_, &local_ty) {
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: local_ty,
+            actual: value.type_,
+        })
     } else {
         o.write_local_set(LocalSetInstruction {
             local_idx,
@@ -96,29 +99,28 @@ pub(crate) fn global_set(
     let global = match ctxt.module.globals.get(global_idx as usize) {
         Some(g) => g,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "global variable with index {global_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Global,
+                idx: global_idx,
+            });
             return Ok(());
         }
     };
 
     let global_type = match &global.r#type {
         GlobalType::Const(_) => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "cannot set const global {global_idx}",
-            )));
+            ctxt.poison::<()>(ValidationError::ImmutableGlobal(global_idx));
             return Ok(());
         }
         GlobalType::Mut(t) => *t,
     };
 
     let value = ctxt.pop_var_with_type(global_type);
-    if value.type_ != global_type {
-        ctxt.poison(ValidationError::Msg(format!(
-            "stack value type does not match global {} type: {:?} vs {:?}",
-            global_idx, value.type_, global_type
-        )))
+    if !ctxt.matches(&value.type_, &global_type) {
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: global_type,
+            actual: value.type_,
+        })
     }
 
     o.write_global_set(GlobalSetInstruction {
@@ -137,19 +139,20 @@ pub(crate) fn local_tee(
     let local_ty = match ctxt.locals.get(local_idx as usize) {
         Some(local_ty) => *local_ty,
         None => {
-            ctxt.poison::<()>(ValidationError::Msg(format!(
-                "local variable with index {local_idx} not found",
-            )));
+            ctxt.poison::<()>(ValidationError::UnknownIndex {
+                space: IndexSpace::Local,
+                idx: local_idx,
+            });
             return Ok(());
         }
     };
 
     let in_stack_var = ctxt.pop_var_with_type(local_ty);
-    if in_stack_var.type_ != local_ty {
-        ctxt.poison(ValidationError::Msg(format!(
-            "stack value type does not match local {} type: {:?} vs {:?}",
-            local_idx, in_stack_var.type_, local_ty
-        )))
+    if !ctxt.matches(&in_stack_var.type_, &local_ty) {
+        ctxt.poison(ValidationError::TypeMismatch {
+            expected: local_ty,
+            actual: in_stack_var.type_,
+        })
     }
 
     let out_stack_var = ctxt.create_var(local_ty);
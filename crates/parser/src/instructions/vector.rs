@@ -0,0 +1,599 @@
+use super::*;
+use module::objects::memory::MemArg;
+
+fn read_lane_idx(i: &mut I, shape: VecShape) -> Result<u8, ParserError> {
+    let lane = i.read_byte()?;
+    if lane >= shape.lane_count() {
+        return Err(ParserError::InvalidLaneIndex);
+    }
+    Ok(lane)
+}
+
+fn check_alignment(memarg: &MemArg, natural_alignment: u32) -> Result<(), ParserError> {
+    if 2_u32.pow(memarg.align) > natural_alignment {
+        return Err(ParserError::AlignmentLargerThanNatural);
+    }
+    Ok(())
+}
+
+pub(crate) fn v128_const(ctxt: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {
+    let mut imm = [0u8; 16];
+    for b in imm.iter_mut() {
+        *b = i.read_byte()?;
+    }
+    let out = ctxt.create_var(ValType::vec());
+    o.write_v128_const(VConstInstruction { imm, out1: out.id });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_v128_load(
+    ctxt: &mut C,
+    i: &mut I,
+    o: &mut dyn InstructionConsumer,
+    operation: VecLoadOp,
+) -> PR {
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
+    let natural_alignment = match operation {
+        VecLoadOp::Load128 => 16,
+        VecLoadOp::Load8x8S
+        | VecLoadOp::Load8x8U
+        | VecLoadOp::Load16x4S
+        | VecLoadOp::Load16x4U
+        | VecLoadOp::Load32x2S
+        | VecLoadOp::Load32x2U
+        | VecLoadOp::Load64Splat
+        | VecLoadOp::Load64Zero => 8,
+        VecLoadOp::Load8Splat => 1,
+        VecLoadOp::Load16Splat => 2,
+        VecLoadOp::Load32Splat | VecLoadOp::Load32Zero => 4,
+    };
+    check_alignment(&memarg, natural_alignment)?;
+
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_v128_load(VLoadInstruction {
+        memarg,
+        operation,
+        addr: addr.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn v128_store(ctxt: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
+    check_alignment(&memarg, 16)?;
+    let value_in = ctxt.pop_var_with_type(ValType::vec());
+    let addr_in = ctxt.pop_var_with_type(index_type.val_type());
+    o.write_v128_store(VStoreInstruction {
+        memarg,
+        addr_in: addr_in.id,
+        value_in: value_in.id,
+    });
+    Ok(())
+}
+
+fn parse_v128_load_lane(
+    ctxt: &mut C,
+    i: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+) -> PR {
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
+    check_alignment(&memarg, shape.lane_bytes() as u32)?;
+    let lane = read_lane_idx(i, shape)?;
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let addr = ctxt.pop_var_with_type(index_type.val_type());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_v128_load_lane(VLoadLaneInstruction {
+        memarg,
+        shape,
+        lane,
+        addr: addr.id,
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_v128_store_lane(
+    ctxt: &mut C,
+    i: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+) -> PR {
+    let (memarg, index_type) = parse_memarg(ctxt, i)?;
+    check_alignment(&memarg, shape.lane_bytes() as u32)?;
+    let lane = read_lane_idx(i, shape)?;
+    let value_in = ctxt.pop_var_with_type(ValType::vec());
+    let addr_in = ctxt.pop_var_with_type(index_type.val_type());
+    o.write_v128_store_lane(VStoreLaneInstruction {
+        memarg,
+        shape,
+        lane,
+        addr_in: addr_in.id,
+        value_in: value_in.id,
+    });
+    Ok(())
+}
+
+pub(crate) fn i8x16_shuffle(ctxt: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {
+    let mut lanes = [0u8; 16];
+    for lane in lanes.iter_mut() {
+        *lane = i.read_byte()?;
+        if *lane >= 32 {
+            return Err(ParserError::InvalidLaneIndex);
+        }
+    }
+    let rhs = ctxt.pop_var_with_type(ValType::vec());
+    let lhs = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_shuffle(VShuffleInstruction {
+        lanes,
+        lhs: lhs.id,
+        rhs: rhs.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_splat(ctxt: &mut C, _: &mut I, o: &mut dyn InstructionConsumer, shape: VecShape) -> PR {
+    let in1 = ctxt.pop_var_with_type(ValType::Number(shape.unpacked_type()));
+    let out = ctxt.create_var(ValType::vec());
+    o.write_splat(VSplatInstruction {
+        shape,
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_extract_lane(
+    ctxt: &mut C,
+    i: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    signed: Option<bool>,
+) -> PR {
+    let lane = read_lane_idx(i, shape)?;
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::Number(shape.unpacked_type()));
+    o.write_extract_lane(VExtractLaneInstruction {
+        shape,
+        signed,
+        lane,
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_replace_lane(
+    ctxt: &mut C,
+    i: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+) -> PR {
+    let lane = read_lane_idx(i, shape)?;
+    let value_in = ctxt.pop_var_with_type(ValType::Number(shape.unpacked_type()));
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_replace_lane(VReplaceLaneInstruction {
+        shape,
+        lane,
+        in1: in1.id,
+        value_in: value_in.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_vunary(
+    ctxt: &mut C,
+    _: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    op: VUnaryOp,
+) -> PR {
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_vunary(VUnaryInstruction {
+        shape,
+        op,
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_vbinary(
+    ctxt: &mut C,
+    _: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    op: VBinaryOp,
+) -> PR {
+    let rhs = ctxt.pop_var_with_type(ValType::vec());
+    let lhs = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_vbinary(VBinaryInstruction {
+        shape,
+        op,
+        lhs: lhs.id,
+        rhs: rhs.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+pub(crate) fn v128_bitselect(ctxt: &mut C, _: &mut I, o: &mut dyn InstructionConsumer) -> PR {
+    let mask = ctxt.pop_var_with_type(ValType::vec());
+    let in2 = ctxt.pop_var_with_type(ValType::vec());
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_bitselect(VBitselectInstruction {
+        in1: in1.id,
+        in2: in2.id,
+        mask: mask.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_vrelational(
+    ctxt: &mut C,
+    _: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    op: VRelationalOp,
+) -> PR {
+    let rhs = ctxt.pop_var_with_type(ValType::vec());
+    let lhs = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_vrelational(VRelationalInstruction {
+        shape,
+        op,
+        lhs: lhs.id,
+        rhs: rhs.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_vshift(
+    ctxt: &mut C,
+    _: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    op: VShiftOp,
+) -> PR {
+    let amount = ctxt.pop_var_with_type(ValType::i32());
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::vec());
+    o.write_vshift(VShiftInstruction {
+        shape,
+        op,
+        in1: in1.id,
+        amount: amount.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+fn parse_vtest(
+    ctxt: &mut C,
+    _: &mut I,
+    o: &mut dyn InstructionConsumer,
+    shape: VecShape,
+    op: VTestOp,
+) -> PR {
+    let in1 = ctxt.pop_var_with_type(ValType::vec());
+    let out = ctxt.create_var(ValType::i32());
+    o.write_vtest(VTestInstruction {
+        shape,
+        op,
+        in1: in1.id,
+        out1: out.id,
+    });
+    ctxt.push_var(out);
+    Ok(())
+}
+
+#[rustfmt::skip]
+mod vector_memory_specializations {
+    use super::*;
+    pub(crate) fn v128_load(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load128)}
+    pub(crate) fn v128_load8x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load8x8S)}
+    pub(crate) fn v128_load8x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load8x8U)}
+    pub(crate) fn v128_load16x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load16x4S)}
+    pub(crate) fn v128_load16x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load16x4U)}
+    pub(crate) fn v128_load32x2_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load32x2S)}
+    pub(crate) fn v128_load32x2_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load32x2U)}
+    pub(crate) fn v128_load8_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load8Splat)}
+    pub(crate) fn v128_load16_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load16Splat)}
+    pub(crate) fn v128_load32_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load32Splat)}
+    pub(crate) fn v128_load64_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load64Splat)}
+    pub(crate) fn v128_load32_zero(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load32Zero)}
+    pub(crate) fn v128_load64_zero(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load(c, i, o, VecLoadOp::Load64Zero)}
+
+    pub(crate) fn v128_load8_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load_lane(c, i, o, VecShape::I8x16)}
+    pub(crate) fn v128_load16_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load_lane(c, i, o, VecShape::I16x8)}
+    pub(crate) fn v128_load32_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load_lane(c, i, o, VecShape::I32x4)}
+    pub(crate) fn v128_load64_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_load_lane(c, i, o, VecShape::I64x2)}
+    pub(crate) fn v128_store8_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_store_lane(c, i, o, VecShape::I8x16)}
+    pub(crate) fn v128_store16_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_store_lane(c, i, o, VecShape::I16x8)}
+    pub(crate) fn v128_store32_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_store_lane(c, i, o, VecShape::I32x4)}
+    pub(crate) fn v128_store64_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_v128_store_lane(c, i, o, VecShape::I64x2)}
+}
+pub(crate) use vector_memory_specializations::*;
+
+#[rustfmt::skip]
+mod vector_lane_specializations {
+    use super::*;
+    pub(crate) fn i8x16_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::I8x16)}
+    pub(crate) fn i16x8_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::I16x8)}
+    pub(crate) fn i32x4_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::I32x4)}
+    pub(crate) fn i64x2_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::I64x2)}
+    pub(crate) fn f32x4_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::F32x4)}
+    pub(crate) fn f64x2_splat(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_splat(c, i, o, VecShape::F64x2)}
+
+    pub(crate) fn i8x16_extract_lane_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I8x16, Some(true))}
+    pub(crate) fn i8x16_extract_lane_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I8x16, Some(false))}
+    pub(crate) fn i16x8_extract_lane_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I16x8, Some(true))}
+    pub(crate) fn i16x8_extract_lane_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I16x8, Some(false))}
+    pub(crate) fn i32x4_extract_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I32x4, None)}
+    pub(crate) fn i64x2_extract_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::I64x2, None)}
+    pub(crate) fn f32x4_extract_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::F32x4, None)}
+    pub(crate) fn f64x2_extract_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_extract_lane(c, i, o, VecShape::F64x2, None)}
+
+    pub(crate) fn i8x16_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::I8x16)}
+    pub(crate) fn i16x8_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::I16x8)}
+    pub(crate) fn i32x4_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::I32x4)}
+    pub(crate) fn i64x2_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::I64x2)}
+    pub(crate) fn f32x4_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::F32x4)}
+    pub(crate) fn f64x2_replace_lane(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_replace_lane(c, i, o, VecShape::F64x2)}
+}
+pub(crate) use vector_lane_specializations::*;
+
+#[rustfmt::skip]
+mod vector_relational_specializations {
+    use super::*;
+    pub(crate) fn i8x16_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::Eq)}
+    pub(crate) fn i8x16_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::Ne)}
+    pub(crate) fn i8x16_lt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::LtS)}
+    pub(crate) fn i8x16_lt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::LtU)}
+    pub(crate) fn i8x16_gt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::GtS)}
+    pub(crate) fn i8x16_gt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::GtU)}
+    pub(crate) fn i8x16_le_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::LeS)}
+    pub(crate) fn i8x16_le_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::LeU)}
+    pub(crate) fn i8x16_ge_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::GeS)}
+    pub(crate) fn i8x16_ge_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I8x16, VRelationalOp::GeU)}
+
+    pub(crate) fn i16x8_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::Eq)}
+    pub(crate) fn i16x8_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::Ne)}
+    pub(crate) fn i16x8_lt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::LtS)}
+    pub(crate) fn i16x8_lt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::LtU)}
+    pub(crate) fn i16x8_gt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::GtS)}
+    pub(crate) fn i16x8_gt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::GtU)}
+    pub(crate) fn i16x8_le_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::LeS)}
+    pub(crate) fn i16x8_le_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::LeU)}
+    pub(crate) fn i16x8_ge_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::GeS)}
+    pub(crate) fn i16x8_ge_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I16x8, VRelationalOp::GeU)}
+
+    pub(crate) fn i32x4_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::Eq)}
+    pub(crate) fn i32x4_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::Ne)}
+    pub(crate) fn i32x4_lt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::LtS)}
+    pub(crate) fn i32x4_lt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::LtU)}
+    pub(crate) fn i32x4_gt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::GtS)}
+    pub(crate) fn i32x4_gt_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::GtU)}
+    pub(crate) fn i32x4_le_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::LeS)}
+    pub(crate) fn i32x4_le_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::LeU)}
+    pub(crate) fn i32x4_ge_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::GeS)}
+    pub(crate) fn i32x4_ge_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I32x4, VRelationalOp::GeU)}
+
+    pub(crate) fn i64x2_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::Eq)}
+    pub(crate) fn i64x2_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::Ne)}
+    pub(crate) fn i64x2_lt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::LtS)}
+    pub(crate) fn i64x2_gt_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::GtS)}
+    pub(crate) fn i64x2_le_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::LeS)}
+    pub(crate) fn i64x2_ge_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::I64x2, VRelationalOp::GeS)}
+
+    pub(crate) fn f32x4_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Eq)}
+    pub(crate) fn f32x4_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Ne)}
+    pub(crate) fn f32x4_lt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Lt)}
+    pub(crate) fn f32x4_gt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Gt)}
+    pub(crate) fn f32x4_le(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Le)}
+    pub(crate) fn f32x4_ge(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F32x4, VRelationalOp::Ge)}
+
+    pub(crate) fn f64x2_eq(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Eq)}
+    pub(crate) fn f64x2_ne(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Ne)}
+    pub(crate) fn f64x2_lt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Lt)}
+    pub(crate) fn f64x2_gt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Gt)}
+    pub(crate) fn f64x2_le(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Le)}
+    pub(crate) fn f64x2_ge(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vrelational(c, i, o, VecShape::F64x2, VRelationalOp::Ge)}
+}
+pub(crate) use vector_relational_specializations::*;
+
+#[rustfmt::skip]
+mod vector_unary_specializations {
+    use super::*;
+    pub(crate) fn v128_not(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I8x16, VUnaryOp::Not)}
+
+    pub(crate) fn i8x16_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I8x16, VUnaryOp::Abs)}
+    pub(crate) fn i8x16_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I8x16, VUnaryOp::Neg)}
+    pub(crate) fn i8x16_popcnt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I8x16, VUnaryOp::Popcnt)}
+    pub(crate) fn i16x8_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::Abs)}
+    pub(crate) fn i16x8_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::Neg)}
+    pub(crate) fn i32x4_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::Abs)}
+    pub(crate) fn i32x4_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::Neg)}
+    pub(crate) fn i64x2_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::Abs)}
+    pub(crate) fn i64x2_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::Neg)}
+
+    pub(crate) fn f32x4_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Abs)}
+    pub(crate) fn f32x4_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Neg)}
+    pub(crate) fn f32x4_sqrt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Sqrt)}
+    pub(crate) fn f32x4_ceil(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Ceil)}
+    pub(crate) fn f32x4_floor(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Floor)}
+    pub(crate) fn f32x4_trunc(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Trunc)}
+    pub(crate) fn f32x4_nearest(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::Nearest)}
+    pub(crate) fn f64x2_abs(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Abs)}
+    pub(crate) fn f64x2_neg(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Neg)}
+    pub(crate) fn f64x2_sqrt(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Sqrt)}
+    pub(crate) fn f64x2_ceil(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Ceil)}
+    pub(crate) fn f64x2_floor(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Floor)}
+    pub(crate) fn f64x2_trunc(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Trunc)}
+    pub(crate) fn f64x2_nearest(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::Nearest)}
+
+    pub(crate) fn i16x8_extend_low_i8x16_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtendLowS)}
+    pub(crate) fn i16x8_extend_high_i8x16_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtendHighS)}
+    pub(crate) fn i16x8_extend_low_i8x16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtendLowU)}
+    pub(crate) fn i16x8_extend_high_i8x16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtendHighU)}
+    pub(crate) fn i32x4_extend_low_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtendLowS)}
+    pub(crate) fn i32x4_extend_high_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtendHighS)}
+    pub(crate) fn i32x4_extend_low_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtendLowU)}
+    pub(crate) fn i32x4_extend_high_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtendHighU)}
+    pub(crate) fn i64x2_extend_low_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::ExtendLowS)}
+    pub(crate) fn i64x2_extend_high_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::ExtendHighS)}
+    pub(crate) fn i64x2_extend_low_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::ExtendLowU)}
+    pub(crate) fn i64x2_extend_high_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I64x2, VUnaryOp::ExtendHighU)}
+
+    pub(crate) fn i16x8_extadd_pairwise_i8x16_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtAddPairwiseS)}
+    pub(crate) fn i16x8_extadd_pairwise_i8x16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I16x8, VUnaryOp::ExtAddPairwiseU)}
+    pub(crate) fn i32x4_extadd_pairwise_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtAddPairwiseS)}
+    pub(crate) fn i32x4_extadd_pairwise_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::ExtAddPairwiseU)}
+
+    pub(crate) fn i32x4_trunc_sat_f32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::TruncSatS)}
+    pub(crate) fn i32x4_trunc_sat_f32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::TruncSatU)}
+    pub(crate) fn i32x4_trunc_sat_f64x2_s_zero(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::TruncSatZeroS)}
+    pub(crate) fn i32x4_trunc_sat_f64x2_u_zero(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::I32x4, VUnaryOp::TruncSatZeroU)}
+    pub(crate) fn f32x4_convert_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::ConvertS)}
+    pub(crate) fn f32x4_convert_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::ConvertU)}
+    pub(crate) fn f64x2_convert_low_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::ConvertLowS)}
+    pub(crate) fn f64x2_convert_low_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::ConvertLowU)}
+    pub(crate) fn f32x4_demote_f64x2_zero(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F32x4, VUnaryOp::DemoteZero)}
+    pub(crate) fn f64x2_promote_low_f32x4(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vunary(c, i, o, VecShape::F64x2, VUnaryOp::PromoteLow)}
+}
+pub(crate) use vector_unary_specializations::*;
+
+#[rustfmt::skip]
+mod vector_binary_specializations {
+    use super::*;
+    pub(crate) fn v128_and(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::And)}
+    pub(crate) fn v128_andnot(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::AndNot)}
+    pub(crate) fn v128_or(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::Or)}
+    pub(crate) fn v128_xor(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::Xor)}
+    pub(crate) fn i8x16_swizzle(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::Swizzle)}
+
+    pub(crate) fn i8x16_narrow_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::NarrowS)}
+    pub(crate) fn i8x16_narrow_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::NarrowU)}
+    pub(crate) fn i8x16_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::Add)}
+    pub(crate) fn i8x16_add_sat_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::AddSatS)}
+    pub(crate) fn i8x16_add_sat_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::AddSatU)}
+    pub(crate) fn i8x16_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::Sub)}
+    pub(crate) fn i8x16_sub_sat_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::SubSatS)}
+    pub(crate) fn i8x16_sub_sat_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::SubSatU)}
+    pub(crate) fn i8x16_min_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::MinS)}
+    pub(crate) fn i8x16_min_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::MinU)}
+    pub(crate) fn i8x16_max_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::MaxS)}
+    pub(crate) fn i8x16_max_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::MaxU)}
+    pub(crate) fn i8x16_avgr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I8x16, VBinaryOp::AvgrU)}
+
+    pub(crate) fn i16x8_q15mulr_sat_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::Q15MulrSatS)}
+    pub(crate) fn i16x8_narrow_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::NarrowS)}
+    pub(crate) fn i16x8_narrow_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::NarrowU)}
+    pub(crate) fn i16x8_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::Add)}
+    pub(crate) fn i16x8_add_sat_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::AddSatS)}
+    pub(crate) fn i16x8_add_sat_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::AddSatU)}
+    pub(crate) fn i16x8_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::Sub)}
+    pub(crate) fn i16x8_sub_sat_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::SubSatS)}
+    pub(crate) fn i16x8_sub_sat_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::SubSatU)}
+    pub(crate) fn i16x8_mul(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::Mul)}
+    pub(crate) fn i16x8_min_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::MinS)}
+    pub(crate) fn i16x8_min_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::MinU)}
+    pub(crate) fn i16x8_max_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::MaxS)}
+    pub(crate) fn i16x8_max_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::MaxU)}
+    pub(crate) fn i16x8_avgr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::AvgrU)}
+    pub(crate) fn i16x8_extmul_low_i8x16_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::ExtMulLowS)}
+    pub(crate) fn i16x8_extmul_high_i8x16_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::ExtMulHighS)}
+    pub(crate) fn i16x8_extmul_low_i8x16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::ExtMulLowU)}
+    pub(crate) fn i16x8_extmul_high_i8x16_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I16x8, VBinaryOp::ExtMulHighU)}
+
+    pub(crate) fn i32x4_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::Add)}
+    pub(crate) fn i32x4_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::Sub)}
+    pub(crate) fn i32x4_mul(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::Mul)}
+    pub(crate) fn i32x4_min_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::MinS)}
+    pub(crate) fn i32x4_min_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::MinU)}
+    pub(crate) fn i32x4_max_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::MaxS)}
+    pub(crate) fn i32x4_max_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::MaxU)}
+    pub(crate) fn i32x4_dot_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::DotS)}
+    pub(crate) fn i32x4_extmul_low_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::ExtMulLowS)}
+    pub(crate) fn i32x4_extmul_high_i16x8_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::ExtMulHighS)}
+    pub(crate) fn i32x4_extmul_low_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::ExtMulLowU)}
+    pub(crate) fn i32x4_extmul_high_i16x8_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I32x4, VBinaryOp::ExtMulHighU)}
+
+    pub(crate) fn i64x2_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::Add)}
+    pub(crate) fn i64x2_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::Sub)}
+    pub(crate) fn i64x2_mul(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::Mul)}
+    pub(crate) fn i64x2_extmul_low_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::ExtMulLowS)}
+    pub(crate) fn i64x2_extmul_high_i32x4_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::ExtMulHighS)}
+    pub(crate) fn i64x2_extmul_low_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::ExtMulLowU)}
+    pub(crate) fn i64x2_extmul_high_i32x4_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::I64x2, VBinaryOp::ExtMulHighU)}
+
+    pub(crate) fn f32x4_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Add)}
+    pub(crate) fn f32x4_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Sub)}
+    pub(crate) fn f32x4_mul(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Mul)}
+    pub(crate) fn f32x4_div(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Div)}
+    pub(crate) fn f32x4_min(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Min)}
+    pub(crate) fn f32x4_max(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::Max)}
+    pub(crate) fn f32x4_pmin(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::PMin)}
+    pub(crate) fn f32x4_pmax(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F32x4, VBinaryOp::PMax)}
+    pub(crate) fn f64x2_add(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Add)}
+    pub(crate) fn f64x2_sub(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Sub)}
+    pub(crate) fn f64x2_mul(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Mul)}
+    pub(crate) fn f64x2_div(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Div)}
+    pub(crate) fn f64x2_min(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Min)}
+    pub(crate) fn f64x2_max(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::Max)}
+    pub(crate) fn f64x2_pmin(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::PMin)}
+    pub(crate) fn f64x2_pmax(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vbinary(c, i, o, VecShape::F64x2, VBinaryOp::PMax)}
+}
+pub(crate) use vector_binary_specializations::*;
+
+#[rustfmt::skip]
+mod vector_shift_test_specializations {
+    use super::*;
+    pub(crate) fn i8x16_shl(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I8x16, VShiftOp::Shl)}
+    pub(crate) fn i8x16_shr_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I8x16, VShiftOp::ShrS)}
+    pub(crate) fn i8x16_shr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I8x16, VShiftOp::ShrU)}
+    pub(crate) fn i16x8_shl(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I16x8, VShiftOp::Shl)}
+    pub(crate) fn i16x8_shr_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I16x8, VShiftOp::ShrS)}
+    pub(crate) fn i16x8_shr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I16x8, VShiftOp::ShrU)}
+    pub(crate) fn i32x4_shl(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I32x4, VShiftOp::Shl)}
+    pub(crate) fn i32x4_shr_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I32x4, VShiftOp::ShrS)}
+    pub(crate) fn i32x4_shr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I32x4, VShiftOp::ShrU)}
+    pub(crate) fn i64x2_shl(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I64x2, VShiftOp::Shl)}
+    pub(crate) fn i64x2_shr_s(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I64x2, VShiftOp::ShrS)}
+    pub(crate) fn i64x2_shr_u(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vshift(c, i, o, VecShape::I64x2, VShiftOp::ShrU)}
+
+    pub(crate) fn v128_any_true(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I8x16, VTestOp::AnyTrue)}
+    pub(crate) fn i8x16_all_true(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I8x16, VTestOp::AllTrue)}
+    pub(crate) fn i8x16_bitmask(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I8x16, VTestOp::Bitmask)}
+    pub(crate) fn i16x8_all_true(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I16x8, VTestOp::AllTrue)}
+    pub(crate) fn i16x8_bitmask(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I16x8, VTestOp::Bitmask)}
+    pub(crate) fn i32x4_all_true(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I32x4, VTestOp::AllTrue)}
+    pub(crate) fn i32x4_bitmask(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I32x4, VTestOp::Bitmask)}
+    pub(crate) fn i64x2_all_true(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I64x2, VTestOp::AllTrue)}
+    pub(crate) fn i64x2_bitmask(c: &mut C, i: &mut I, o: &mut dyn InstructionConsumer) -> PR {parse_vtest(c, i, o, VecShape::I64x2, VTestOp::Bitmask)}
+}
+pub(crate) use vector_shift_test_specializations::*;
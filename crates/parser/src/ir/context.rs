@@ -1,8 +1,8 @@
 use super::stack::ParserStack;
-use crate::ValidationError;
+use crate::{ParserLimits, ValidationError};
 use module::{instructions::Variable, ModuleMetadata};
 use std::sync::atomic::{AtomicUsize, Ordering};
-use wasm_types::ValType;
+use wasm_types::{FuncIdx, Section, ValType};
 
 pub struct Context<'a> {
     pub(crate) module: &'a ModuleMetadata,
@@ -10,12 +10,25 @@ pub struct Context<'a> {
     pub(crate) locals: Vec<ValType>,
     pub(crate) var_count: AtomicUsize,
     pub(crate) poison: Option<ValidationError>,
+    /// section of the parsed instructions, validation errors are located within it
+    pub(crate) section: Section,
+    /// function whose body is parsed, `None` for constant expressions
+    pub(crate) func_idx: Option<FuncIdx>,
+    /// byte offset of the instruction currently parsed
+    pub(crate) instr_offset: usize,
+    /// bounds the nesting depth of blocks while instructions are parsed
+    pub(crate) limits: ParserLimits,
 }
 
 impl<'a> Context<'a> {
     pub(crate) fn poison<V: Default>(&mut self, err: ValidationError) -> V {
         if self.poison.is_none() {
-            self.poison = Some(err);
+            self.poison = Some(ValidationError::Located {
+                section: self.section.clone(),
+                func_idx: self.func_idx,
+                offset: self.instr_offset,
+                error: Box::new(err),
+            });
         }
         Default::default()
     }
@@ -34,13 +47,23 @@ impl<'a> Context<'a> {
         }
     }
 
-    pub(crate) fn new(module: &'a ModuleMetadata, locals: Vec<ValType>) -> Self {
+    /// whether `a` is a subtype of `b` within the types of the current module
+    pub(crate) fn matches(&self, a: &ValType, b: &ValType) -> bool {
+        a.matches(&self.module.types, b, &self.module.types)
+    }
+
+    /// Context of a constant expression within `section`
+    pub(crate) fn new(module: &'a ModuleMetadata, section: Section) -> Self {
         Self {
             module,
             stack: ParserStack::new(),
-            locals,
+            locals: Vec::new(),
             var_count: AtomicUsize::new(0),
             poison: None,
+            section,
+            func_idx: None,
+            instr_offset: 0,
+            limits: ParserLimits::default(),
         }
     }
 
@@ -52,7 +75,7 @@ impl<'a> Context<'a> {
     }
 
     pub(crate) fn pop_var_with_type(&mut self, type_: ValType) -> Variable {
-        let pop_result = self.stack.pop_var_with_type(type_);
+        let pop_result = self.stack.pop_var_with_type(type_, &self.module.types);
         self.extract_poison(pop_result)
     }
 
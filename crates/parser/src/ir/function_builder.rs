@@ -1,18 +1,19 @@
 use super::context::Context;
 use itertools::Itertools;
 use module::{
-    basic_block::{BasicBlock, BasicBlockGlue, BasicBlockID},
+    basic_block::{BasicBlock, BasicBlockGlue, BasicBlockID, CatchHandler},
     instructions::{FunctionIR, PhiNode, Variable, VariableID},
     InstructionConsumer, InstructionEncoder,
 };
 use smallvec::SmallVec;
 use std::{collections::HashMap, hash::BuildHasherDefault, hash::Hasher};
-use wasm_types::{FuncType, LocalIdx, ValType};
+use wasm_types::{FuncType, LocalIdx, TagIdx, ValType};
 
 pub(crate) struct FunctionIRBuilder {
     bbs: HashMap<BasicBlockID, (BasicBlock, InstructionEncoder), BuildHasherDefault<SimpleHasher>>,
     current_bb_instrs: InstructionEncoder,
     current_bb: BasicBlockID,
+    next_bb_id: BasicBlockID,
     locals: Vec<ValType>,
     num_vars: usize,
     func_type: FuncType,
@@ -37,6 +38,7 @@ impl FunctionIRBuilder {
             bbs: HashMap::with_capacity_and_hasher(32, BuildHasherDefault::default()),
             current_bb_instrs: InstructionEncoder::new(),
             current_bb: u32::MAX,
+            next_bb_id: 0,
             locals: Vec::new(),
             num_vars: 0,
             func_type: FuncType::default(),
@@ -119,6 +121,11 @@ impl FunctionBuilderInterface for FunctionIRBuilder {
         self.num_vars = var_count;
     }
 
+    fn next_bb_id(&mut self) -> BasicBlockID {
+        self.next_bb_id += 1;
+        self.next_bb_id - 1
+    }
+
     fn reserve_bb_with_id(&mut self, id: BasicBlockID) -> BasicBlockID {
         debug_assert!(!self.bbs.contains_key(&id));
         self.bbs
@@ -212,6 +219,7 @@ impl FunctionBuilderInterface for FunctionIRBuilder {
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
     ) {
         self.current_bb_get_mut().terminator = BasicBlockGlue::CallIndirect {
             type_idx,
@@ -220,6 +228,7 @@ impl FunctionBuilderInterface for FunctionIRBuilder {
             return_bb,
             call_params,
             return_vars,
+            unwind_bb,
         };
     }
 
@@ -229,12 +238,68 @@ impl FunctionBuilderInterface for FunctionIRBuilder {
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
     ) {
         self.current_bb_get_mut().terminator = BasicBlockGlue::Call {
             func_idx,
             return_bb,
             call_params,
             return_vars,
+            unwind_bb,
+        };
+    }
+
+    fn terminate_return_call(&mut self, func_idx: u32, call_params: SmallVec<[VariableID; 2]>) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::ReturnCall {
+            func_idx,
+            call_params,
+        };
+    }
+
+    fn terminate_return_call_indirect(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        table_idx: u32,
+        call_params: SmallVec<[VariableID; 2]>,
+    ) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::ReturnCallIndirect {
+            type_idx,
+            selector_var,
+            table_idx,
+            call_params,
+        };
+    }
+
+    fn terminate_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        return_bb: BasicBlockID,
+        call_params: SmallVec<[VariableID; 2]>,
+        return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::CallRef {
+            type_idx,
+            selector_var,
+            return_bb,
+            call_params,
+            return_vars,
+            unwind_bb,
+        };
+    }
+
+    fn terminate_return_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        call_params: SmallVec<[VariableID; 2]>,
+    ) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::ReturnCallRef {
+            type_idx,
+            selector_var,
+            call_params,
         };
     }
 
@@ -293,6 +358,34 @@ impl FunctionBuilderInterface for FunctionIRBuilder {
         };
     }
 
+    fn terminate_throw(
+        &mut self,
+        tag_idx: TagIdx,
+        values: SmallVec<[VariableID; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::Throw {
+            tag_idx,
+            values,
+            unwind_bb,
+        };
+    }
+
+    fn terminate_throw_ref(&mut self, exn_var: VariableID, unwind_bb: Option<BasicBlockID>) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::ThrowRef { exn_var, unwind_bb };
+    }
+
+    fn terminate_catch_dispatch(
+        &mut self,
+        handlers: SmallVec<[CatchHandler; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    ) {
+        self.current_bb_get_mut().terminator = BasicBlockGlue::CatchDispatch {
+            handlers,
+            unwind_bb,
+        };
+    }
+
     fn current_bb_id_get(&self) -> BasicBlockID {
         self.current_bb
     }
@@ -312,9 +405,14 @@ pub trait FunctionBuilderInterface {
     fn set_var_count(&mut self, var_count: usize);
 
     fn reserve_bb(&mut self) -> BasicBlockID {
-        self.reserve_bb_with_id(module::BasicBlock::next_id())
+        let id = self.next_bb_id();
+        self.reserve_bb_with_id(id)
     }
 
+    /// Allocates a basic block ID that is unique within the function. IDs are handed out in parsing order
+    /// starting at 0, so they don't depend on other functions parsed before or concurrently.
+    fn next_bb_id(&mut self) -> BasicBlockID;
+
     fn reserve_bb_with_id(&mut self, id: BasicBlockID) -> BasicBlockID;
     fn set_bb_phi_inputs(
         &mut self,
@@ -343,6 +441,7 @@ pub trait FunctionBuilderInterface {
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
     );
     fn terminate_call(
         &mut self,
@@ -350,6 +449,30 @@ pub trait FunctionBuilderInterface {
         return_bb: BasicBlockID,
         call_params: SmallVec<[VariableID; 2]>,
         return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
+    );
+    fn terminate_return_call(&mut self, func_idx: u32, call_params: SmallVec<[VariableID; 2]>);
+    fn terminate_return_call_indirect(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        table_idx: u32,
+        call_params: SmallVec<[VariableID; 2]>,
+    );
+    fn terminate_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        return_bb: BasicBlockID,
+        call_params: SmallVec<[VariableID; 2]>,
+        return_vars: SmallVec<[VariableID; 1]>,
+        unwind_bb: Option<BasicBlockID>,
+    );
+    fn terminate_return_call_ref(
+        &mut self,
+        type_idx: u32,
+        selector_var: VariableID,
+        call_params: SmallVec<[VariableID; 2]>,
     );
     fn terminate_jmp_cond(
         &mut self,
@@ -366,5 +489,18 @@ pub trait FunctionBuilderInterface {
         default_target: BasicBlockID,
         default_output_vars: SmallVec<[VariableID; 0]>,
     );
+    fn terminate_throw(
+        &mut self,
+        tag_idx: TagIdx,
+        values: SmallVec<[VariableID; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    );
+    fn terminate_throw_ref(&mut self, exn_var: VariableID, unwind_bb: Option<BasicBlockID>);
+    // handler targets must be reserved blocks without inputs, terminated after the dispatch block
+    fn terminate_catch_dispatch(
+        &mut self,
+        handlers: SmallVec<[CatchHandler; 2]>,
+        unwind_bb: Option<BasicBlockID>,
+    );
     fn eliminate_current_bb(&mut self);
 }
@@ -1,12 +1,16 @@
-use crate::{parsable::Parse, wasm_stream_reader::WasmBinaryReader, ParseResult, ParserError};
+use crate::{
+    parsable::Parse, wasm_stream_reader::WasmBinaryReader, IndexSpace, ParseResult, ParserError,
+    ParserLimits, ResourceLimit, ValidationError,
+};
 use context::Context;
 use function_builder::{FunctionBuilderInterface, FunctionIRBuilder};
 use module::{
     instructions::{FunctionIR, VariableID},
     objects::{
-        function::{FunctionSource, FunctionUnparsed},
+        function::{Function, FunctionSource, FunctionUnparsed},
         instruction::ControlInstruction,
     },
+    verifier::verify_function,
     Module, ModuleMetadata,
 };
 use parse_basic_blocks::{parse_basic_blocks, validate_and_extract_result_from_stack, Label};
@@ -17,7 +21,7 @@ use std::sync::{
     atomic::{AtomicUsize, Ordering},
     RwLock,
 };
-use wasm_types::{FuncIdx, ValType};
+use wasm_types::{FuncIdx, Section, ValType};
 
 pub(crate) mod context;
 pub(crate) mod function_builder;
@@ -25,6 +29,9 @@ mod opcode_tbl;
 pub(crate) mod parse_basic_blocks;
 pub(crate) mod stack;
 
+/// Stack size of the threads parsing function bodies, see [`ParserLimits::max_nesting_depth`].
+const WORKER_STACK_SIZE: usize = 64 << 20;
+
 pub(crate) struct FunctionParser;
 
 impl FunctionParser {
@@ -33,6 +40,7 @@ impl FunctionParser {
         function_idx: FuncIdx,
         function_unparsed: &FunctionUnparsed,
         module: &ModuleMetadata,
+        limits: &ParserLimits,
         builder: &mut impl FunctionBuilderInterface,
     ) -> Result<(), ParserError> {
         let binary_source = buffer.get();
@@ -49,16 +57,15 @@ impl FunctionParser {
         builder.begin_locals();
         let num_locals = binary_source.read_leb128::<u32>()?;
         let mut local_defs: SmallVec<[(u32, ValType); 2]> = SmallVec::new();
-        let mut num_declared_locals: u64 = 0;
+        let mut num_all_locals = function_type.num_params() as u64;
         for _ in 0..num_locals {
             local_defs.push((
                 binary_source.read_leb128::<u32>()?,
                 ValType::parse(&mut binary_source)?,
             ));
-            num_declared_locals += local_defs.last().unwrap().0 as u64;
-        }
-        if num_declared_locals > u32::MAX as u64 {
-            return Err(ParserError::Msg("too many locals".into()));
+            // checked before any of the locals is allocated, the limit is at most `u32::MAX`
+            num_all_locals += local_defs.last().unwrap().0 as u64;
+            limits.check(ResourceLimit::Locals, num_all_locals)?;
         }
 
         let mut ctxt_locals = function_type.params();
@@ -79,6 +86,10 @@ impl FunctionParser {
             locals: ctxt_locals,
             var_count: AtomicUsize::new(0),
             poison: None,
+            section: Section::Code,
+            func_idx: Some(function_idx),
+            instr_offset: binary_source.pos,
+            limits: *limits,
         };
         let entry_basic_block = builder.reserve_bb();
         let exit_basic_block = builder.reserve_bb();
@@ -90,15 +101,37 @@ impl FunctionParser {
             loop_after_bb_id: None,
             loop_after_result_type: None,
             result_type: function_type.results(),
+            unwind_bb: None,
         };
         let mut labels = vec![function_scope_label];
         builder.continue_bb(entry_basic_block);
-        parse_basic_blocks(&mut binary_source, &mut ctxt, &mut labels, builder)?;
+        parse_basic_blocks(&mut binary_source, &mut ctxt, &mut labels, builder).map_err(
+            |e| match e {
+                ParserError::ValidationError(err @ ValidationError::Located { .. }) => {
+                    ParserError::ValidationError(err)
+                }
+                ParserError::ValidationError(err) => {
+                    ParserError::ValidationError(ValidationError::Located {
+                        section: Section::Code,
+                        func_idx: Some(function_idx),
+                        offset: ctxt.instr_offset,
+                        error: Box::new(err),
+                    })
+                }
+                e => ParserError::PositionalError(Box::new(e), ctxt.instr_offset),
+            },
+        )?;
 
         // insert last basic block that always returns from function (jump target for function scope label)
         if !matches!(
             builder.current_bb_instrs().peek_terminator(),
-            ControlInstruction::Unreachable | ControlInstruction::Return
+            ControlInstruction::Unreachable
+                | ControlInstruction::Return
+                | ControlInstruction::ReturnCall(..)
+                | ControlInstruction::ReturnCallIndirect(..)
+                | ControlInstruction::ReturnCallRef(..)
+                | ControlInstruction::Throw(_)
+                | ControlInstruction::ThrowRef
         ) {
             let _: SmallVec<[VariableID; 0]> =
                 validate_and_extract_result_from_stack(&mut ctxt, &function_type.results(), false);
@@ -114,7 +147,116 @@ impl FunctionParser {
         Ok(())
     }
 
-    pub(crate) fn parse_all_functions(module: &Module) -> ParseResult {
+    /// Parses the bodies of `functions` on up to `threads` worker threads and returns their IR in the same order.
+    ///
+    /// The result doesn't depend on the number of threads: basic block and variable IDs are allocated per
+    /// function and on failure the error of the first failing function in `functions` is returned. With
+    /// `verify_ir`, the IR of each function is checked by [`module::verifier`] once it is built. Function bodies
+    /// exceeding `limits` are rejected.
+    pub(crate) fn parse_functions(
+        buffer: &ResourceBuffer,
+        module: &ModuleMetadata,
+        functions: &[FuncIdx],
+        threads: usize,
+        verify_ir: bool,
+        limits: &ParserLimits,
+    ) -> Result<Vec<FunctionIR>, ParserError> {
+        let parse = |func_idx: FuncIdx| -> Result<FunctionIR, ParserError> {
+            let function_unparsed = match module.functions.get(func_idx as usize) {
+                Some(Function {
+                    source: FunctionSource::Wasm(function_unparsed),
+                    ..
+                }) => function_unparsed,
+                Some(_) => {
+                    return Err(ParserError::Msg(format!(
+                        "function {func_idx} is imported and has no body"
+                    )))
+                }
+                None => {
+                    return Err(ValidationError::UnknownIndex {
+                        space: IndexSpace::Function,
+                        idx: func_idx,
+                    }
+                    .into())
+                }
+            };
+            let mut builder = FunctionIRBuilder::new();
+            Self::parse_single_function(
+                buffer,
+                func_idx,
+                function_unparsed,
+                module,
+                limits,
+                &mut builder,
+            )?;
+            let function_ir = builder.finalize();
+            if verify_ir {
+                verify_function(&function_ir).map_err(|e| e.in_function(func_idx))?;
+            }
+            Ok(function_ir)
+        };
+
+        // nested blocks are parsed recursively, so even a single function is parsed on a worker whose stack fits
+        // the nesting depth allowed by default
+        let threads = threads.min(functions.len());
+
+        // workers take the next function from `next`, functions after the first failing one are skipped
+        let next = AtomicUsize::new(0);
+        let first_error = AtomicUsize::new(usize::MAX);
+        let mut results: Vec<Option<Result<FunctionIR, ParserError>>> =
+            std::iter::repeat_with(|| None)
+                .take(functions.len())
+                .collect();
+        std::thread::scope(|scope| -> ParseResult {
+            let workers = (0..threads)
+                .map(|_| {
+                    std::thread::Builder::new()
+                        .stack_size(WORKER_STACK_SIZE)
+                        .spawn_scoped(scope, || {
+                            let mut parsed = Vec::new();
+                            loop {
+                                let pos = next.fetch_add(1, Ordering::Relaxed);
+                                if pos >= functions.len()
+                                    || pos > first_error.load(Ordering::Relaxed)
+                                {
+                                    break parsed;
+                                }
+                                let result = parse(functions[pos]);
+                                if result.is_err() {
+                                    first_error.fetch_min(pos, Ordering::Relaxed);
+                                }
+                                parsed.push((pos, result));
+                            }
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for worker in workers {
+                let parsed = worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                for (pos, result) in parsed {
+                    results[pos] = Some(result);
+                }
+            }
+            Ok(())
+        })?;
+
+        let first_error = first_error.into_inner();
+        if first_error != usize::MAX {
+            return Err(results[first_error].take().unwrap().unwrap_err());
+        }
+        Ok(results
+            .into_iter()
+            .map(|result| result.unwrap().unwrap())
+            .collect())
+    }
+
+    pub(crate) fn parse_all_functions(
+        module: &Module,
+        threads: usize,
+        verify_ir: bool,
+        limits: &ParserLimits,
+    ) -> ParseResult {
         if !module.artifact_registry.read().unwrap().contains_key("ir") {
             module.artifact_registry.write().unwrap().insert(
                 "ir".to_string(),
@@ -127,27 +269,111 @@ impl FunctionParser {
         let ir = artifact_ref.downcast_mut::<Vec<FunctionIR>>().unwrap();
 
         ir.resize(module.meta.functions.len(), FunctionIR::default());
-        for func_idx in 0..module.meta.functions.len() {
-            if ir[func_idx].bbs.len() > 0 {
+        let unparsed: Vec<FuncIdx> = (0..module.meta.functions.len())
+            .filter(|func_idx| {
+                ir[*func_idx].bbs.is_empty()
+                    && matches!(
+                        module.meta.functions[*func_idx].source,
+                        FunctionSource::Wasm(_)
+                    )
+            })
+            .map(|func_idx| func_idx as FuncIdx)
+            .collect();
+        let parsed = Self::parse_functions(
+            &module.source,
+            &module.meta,
+            &unparsed,
+            threads,
+            verify_ir,
+            limits,
+        )?;
+        for (func_idx, function_ir) in unparsed.into_iter().zip(parsed) {
+            ir[func_idx as usize] = function_ir;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Parser;
+
+    /// `count` functions with nested blocks, the bodies of the functions in `invalid` don't validate
+    fn module(count: u32, invalid: &[u32]) -> Module {
+        let mut text = String::from("(module");
+        for func_idx in 0..count {
+            if invalid.contains(&func_idx) {
+                text.push_str("\n(func (result i32) (i32.add))");
                 continue;
             }
+            text.push_str(&format!(
+                "\n(func (param i32) (result i32)
+                   (block (result i32)
+                     (loop (result i32)
+                       (drop (br_if 1 (i32.const {func_idx}) (local.get 0)))
+                       (br_if 0 (local.tee 0 (i32.sub (local.get 0) (i32.const 1))))
+                       (i32.const 0))))"
+            ));
+        }
+        text.push(')');
+        Parser::parse(ResourceBuffer::from_wat_buf(text.as_bytes()).unwrap()).unwrap()
+    }
 
-            let function_meta = &module.meta.functions[func_idx];
-            match &function_meta.source {
-                FunctionSource::Import(_) => continue,
-                FunctionSource::Wasm(function_unparsed) => {
-                    let mut builder = FunctionIRBuilder::new();
-                    Self::parse_single_function(
-                        &module.source,
-                        func_idx as FuncIdx,
-                        function_unparsed,
-                        &module.meta,
-                        &mut builder,
-                    )?;
-                    ir[func_idx] = builder.finalize();
-                }
-            }
+    fn parse(
+        module: &Module,
+        functions: &[FuncIdx],
+        threads: usize,
+    ) -> Result<String, ParserError> {
+        let ir = FunctionParser::parse_functions(
+            &module.source,
+            &module.meta,
+            functions,
+            threads,
+            true,
+            &ParserLimits::default(),
+        )?;
+        Ok(format!("{ir:?}"))
+    }
+
+    fn failing_function(error: ParserError) -> Option<FuncIdx> {
+        match error {
+            ParserError::ValidationError(ValidationError::Located { func_idx, .. }) => func_idx,
+            _ => None,
         }
-        Ok(())
+    }
+
+    #[test]
+    fn test_ir_is_independent_of_thread_count() {
+        let module = module(64, &[]);
+        let functions: Vec<FuncIdx> = (0..64).collect();
+        let sequential = parse(&module, &functions, 1).unwrap();
+        for threads in [2, 8, 128] {
+            assert_eq!(parse(&module, &functions, threads).unwrap(), sequential);
+        }
+
+        // IDs are allocated per function, a function's IR doesn't depend on the functions parsed before it
+        let single = |func_idx: FuncIdx| parse(&module, &[func_idx], 1).unwrap();
+        assert_eq!(
+            parse(&module, &[3, 17], 4).unwrap(),
+            format!(
+                "[{}, {}]",
+                single(3).trim_matches(['[', ']']),
+                single(17).trim_matches(['[', ']'])
+            )
+        );
+    }
+
+    #[test]
+    fn test_first_failing_function_is_reported() {
+        let module = module(64, &[40, 9]);
+        let functions: Vec<FuncIdx> = (0..64).collect();
+        for threads in [1, 2, 8, 128] {
+            let error = parse(&module, &functions, threads).unwrap_err();
+            assert_eq!(failing_function(error), Some(9), "{threads} threads");
+        }
+        // the order of `functions` decides which function fails first, not the function index
+        let error = parse(&module, &[40, 9], 2).unwrap_err();
+        assert_eq!(failing_function(error), Some(40));
     }
 }
@@ -20,7 +20,14 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
             | 0x0E 𝑙*:vec(labelidx) 𝑙𝑁 :labelidx ⇒ br_table 𝑙* 𝑙𝑁
             | 0x0F ⇒ return
             | 0x10 𝑥:funcidx ⇒ call 𝑥
-            | 0x11 𝑦:typeidx 𝑥:tableidx ⇒ call_indirect 𝑥 𝑦 */
+            | 0x11 𝑦:typeidx 𝑥:tableidx ⇒ call_indirect 𝑥 𝑦
+            | 0x12 𝑥:funcidx ⇒ return_call 𝑥
+            | 0x13 𝑦:typeidx 𝑥:tableidx ⇒ return_call_indirect 𝑥 𝑦
+            | 0x14 𝑥:typeidx ⇒ call_ref 𝑥
+            | 0x15 𝑥:typeidx ⇒ return_call_ref 𝑥
+            | 0x08 𝑥:tagidx ⇒ throw 𝑥
+            | 0x0A ⇒ throw_ref
+            | 0x1F bt:blocktype 𝑐*:vec(catch) (in:instr)* 0x0B ⇒ try_table bt 𝑐* in* end */
     /* 0x00 */ unreachable,
     /* 0x01 */ nop,
     /* 0x02 */ block,
@@ -29,8 +36,12 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
 
     /* 0x05 */ r#else,
 
-    /* Unused */
-    /* 0x06 - 0x0A */ e, e, e, e, e,
+    /* Unused (legacy exception handling) */
+    /* 0x06 - 0x07 */ e, e,
+    /* 0x08 */ throw,
+    /* Unused (legacy exception handling) */
+    /* 0x09 */ e,
+    /* 0x0A */ throw_ref,
 
     /* 0x0B */ end,
 
@@ -40,9 +51,13 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
     /* 0x0F */ r#return,
     /* 0x10 */ call,
     /* 0x11 */ call_indirect,
+    /* 0x12 */ return_call,
+    /* 0x13 */ return_call_indirect,
+    /* 0x14 */ call_ref,
+    /* 0x15 */ return_call_ref,
 
     /* Unused */
-    /* 0x12 - 0x19 */ e, e, e, e, e, e, e, e,
+    /* 0x16 - 0x19 */ e, e, e, e,
 
     /* Parametric Instructions */
     /* instr ::= ...
@@ -54,7 +69,9 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
     /* 0x1C */ select_generic,
 
     /* Unused */
-    /* 0x1D - 0x1F */ e, e, e,
+    /* 0x1D - 0x1E */ e, e,
+
+    /* 0x1F */ try_table,
 
     /* Variable Instructions */
     /* instr ::= ...
@@ -409,8 +426,22 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
     /* 0xD0 */ ref_null,
     /* 0xD1 */ ref_is_null,
     /* 0xD2 */ ref_func,
+    /* 0xD3 */ ref_eq,
+    /* 0xD4 */ ref_as_non_null,
+    /* 0xD5 */ br_on_null,
+    /* 0xD6 */ br_on_non_null,
+
+    /* Unused */ e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e,
+
+    /* Aggregate Instructions */
+    /*  instr ::= ...
+                | 0xFB 0:u32 𝑥:typeidx ⇒ struct.new 𝑥
+                | 0xFB 6:u32 𝑥:typeidx ⇒ array.new 𝑥
+                | 0xFB 20:u32 ht:heaptype ⇒ ref.test (ref ht)
+                | 0xFB 28:u32 ⇒ ref.i31
 
-    /* Unused */ e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e, e,
+            + all struct, array, cast and i31 instructions, opcode 0xFB! */
+    /* 0xFB */ lvl2_instruction_gc,
 
     /* 0xFC */ lvl2_instruction_gp,
 
@@ -424,7 +455,54 @@ pub(crate) const LVL1_JMP_TABLE: [fn(&mut Context, &mut WasmBinaryReader, &mut d
 
     /* 0xFD */ lvl2_instruction_vec,
 
-    /* Unused */ e, e
+    /* Threads Instructions */
+    /*  instr ::= ...
+                | 0xFE 0:u32 𝑚:memarg ⇒ memory.atomic.notify 𝑚
+                | 0xFE 1:u32 𝑚:memarg ⇒ memory.atomic.wait32 𝑚
+                | 0xFE 2:u32 𝑚:memarg ⇒ memory.atomic.wait64 𝑚
+                | 0xFE 3:u32 0x00 ⇒ atomic.fence
+
+            + all atomic loads, stores and read-modify-write instructions, opcode 0xFE! */
+    /* 0xFE */ lvl2_instruction_atomic,
+
+    /* Unused */ e
+];
+
+/// Reference: https://webassembly.github.io/gc/core/binary/instructions.html
+#[rustfmt::skip]
+#[allow(non_upper_case_globals)]
+pub(crate) const LVL2_JMP_TABLE_0xFB: [fn(&mut Context, &mut WasmBinaryReader, &mut dyn InstructionConsumer) -> ParseResult; 31] = [
+    /* 0x00 */ struct_new,
+    /* 0x01 */ struct_new_default,
+    /* 0x02 */ struct_get,
+    /* 0x03 */ struct_get_s,
+    /* 0x04 */ struct_get_u,
+    /* 0x05 */ struct_set,
+    /* 0x06 */ array_new,
+    /* 0x07 */ array_new_default,
+    /* 0x08 */ array_new_fixed,
+    /* 0x09 */ array_new_data,
+    /* 0x0A */ array_new_elem,
+    /* 0x0B */ array_get,
+    /* 0x0C */ array_get_s,
+    /* 0x0D */ array_get_u,
+    /* 0x0E */ array_set,
+    /* 0x0F */ array_len,
+    /* 0x10 */ array_fill,
+    /* 0x11 */ array_copy,
+    /* 0x12 */ array_init_data,
+    /* 0x13 */ array_init_elem,
+    /* 0x14 */ ref_test,
+    /* 0x15 */ ref_test_null,
+    /* 0x16 */ ref_cast,
+    /* 0x17 */ ref_cast_null,
+    /* 0x18 */ br_on_cast,
+    /* 0x19 */ br_on_cast_fail,
+    /* 0x1A */ any_convert_extern,
+    /* 0x1B */ extern_convert_any,
+    /* 0x1C */ ref_i31,
+    /* 0x1D */ i31_get_s,
+    /* 0x1E */ i31_get_u,
 ];
 
 #[rustfmt::skip]
@@ -481,14 +559,376 @@ pub(crate) const LVL2_JMP_TABLE_0xFC: [fn(&mut Context, &mut WasmBinaryReader, &
     /* 17 */ table_fill,
 ];
 
+/// Reference: https://webassembly.github.io/spec/core/bikeshed/#vector-instructions
+/// Opcodes that are not assigned by the fixed-width SIMD proposal map to `e`.
 #[rustfmt::skip]
 #[allow(non_upper_case_globals)]
-pub(crate) const LVL2_JMP_TABLE_0xFD: [fn(&mut Context, &mut WasmBinaryReader, &mut dyn InstructionConsumer) -> ParseResult; 0] = [/* TODO */];
+pub(crate) const LVL2_JMP_TABLE_0xFD: [fn(&mut Context, &mut WasmBinaryReader, &mut dyn InstructionConsumer) -> ParseResult; 256] = [
+    /* Vector Instructions
+      instr ::= ...
+            | 0xFD 0:u32 𝑚:memarg ⇒ v128.load 𝑚
+            | 0xFD 12:u32 (𝑏:byte)16 ⇒ v128.const 𝑏0 ... 𝑏15
+            | 0xFD 13:u32 (𝑙:laneidx)16 ⇒ i8x16.shuffle 𝑙16
+            | 0xFD 21:u32 𝑙:laneidx ⇒ i8x16.extract_lane_s 𝑙
+            | ... */
+    /* 0x00 */ v128_load,
+    /* 0x01 */ v128_load8x8_s,
+    /* 0x02 */ v128_load8x8_u,
+    /* 0x03 */ v128_load16x4_s,
+    /* 0x04 */ v128_load16x4_u,
+    /* 0x05 */ v128_load32x2_s,
+    /* 0x06 */ v128_load32x2_u,
+    /* 0x07 */ v128_load8_splat,
+    /* 0x08 */ v128_load16_splat,
+    /* 0x09 */ v128_load32_splat,
+    /* 0x0A */ v128_load64_splat,
+    /* 0x0B */ v128_store,
+    /* 0x0C */ v128_const,
+    /* 0x0D */ i8x16_shuffle,
+    /* 0x0E */ i8x16_swizzle,
+    /* 0x0F */ i8x16_splat,
+    /* 0x10 */ i16x8_splat,
+    /* 0x11 */ i32x4_splat,
+    /* 0x12 */ i64x2_splat,
+    /* 0x13 */ f32x4_splat,
+    /* 0x14 */ f64x2_splat,
+    /* 0x15 */ i8x16_extract_lane_s,
+    /* 0x16 */ i8x16_extract_lane_u,
+    /* 0x17 */ i8x16_replace_lane,
+    /* 0x18 */ i16x8_extract_lane_s,
+    /* 0x19 */ i16x8_extract_lane_u,
+    /* 0x1A */ i16x8_replace_lane,
+    /* 0x1B */ i32x4_extract_lane,
+    /* 0x1C */ i32x4_replace_lane,
+    /* 0x1D */ i64x2_extract_lane,
+    /* 0x1E */ i64x2_replace_lane,
+    /* 0x1F */ f32x4_extract_lane,
+    /* 0x20 */ f32x4_replace_lane,
+    /* 0x21 */ f64x2_extract_lane,
+    /* 0x22 */ f64x2_replace_lane,
+    /* 0x23 */ i8x16_eq,
+    /* 0x24 */ i8x16_ne,
+    /* 0x25 */ i8x16_lt_s,
+    /* 0x26 */ i8x16_lt_u,
+    /* 0x27 */ i8x16_gt_s,
+    /* 0x28 */ i8x16_gt_u,
+    /* 0x29 */ i8x16_le_s,
+    /* 0x2A */ i8x16_le_u,
+    /* 0x2B */ i8x16_ge_s,
+    /* 0x2C */ i8x16_ge_u,
+    /* 0x2D */ i16x8_eq,
+    /* 0x2E */ i16x8_ne,
+    /* 0x2F */ i16x8_lt_s,
+    /* 0x30 */ i16x8_lt_u,
+    /* 0x31 */ i16x8_gt_s,
+    /* 0x32 */ i16x8_gt_u,
+    /* 0x33 */ i16x8_le_s,
+    /* 0x34 */ i16x8_le_u,
+    /* 0x35 */ i16x8_ge_s,
+    /* 0x36 */ i16x8_ge_u,
+    /* 0x37 */ i32x4_eq,
+    /* 0x38 */ i32x4_ne,
+    /* 0x39 */ i32x4_lt_s,
+    /* 0x3A */ i32x4_lt_u,
+    /* 0x3B */ i32x4_gt_s,
+    /* 0x3C */ i32x4_gt_u,
+    /* 0x3D */ i32x4_le_s,
+    /* 0x3E */ i32x4_le_u,
+    /* 0x3F */ i32x4_ge_s,
+    /* 0x40 */ i32x4_ge_u,
+    /* 0x41 */ f32x4_eq,
+    /* 0x42 */ f32x4_ne,
+    /* 0x43 */ f32x4_lt,
+    /* 0x44 */ f32x4_gt,
+    /* 0x45 */ f32x4_le,
+    /* 0x46 */ f32x4_ge,
+    /* 0x47 */ f64x2_eq,
+    /* 0x48 */ f64x2_ne,
+    /* 0x49 */ f64x2_lt,
+    /* 0x4A */ f64x2_gt,
+    /* 0x4B */ f64x2_le,
+    /* 0x4C */ f64x2_ge,
+    /* 0x4D */ v128_not,
+    /* 0x4E */ v128_and,
+    /* 0x4F */ v128_andnot,
+    /* 0x50 */ v128_or,
+    /* 0x51 */ v128_xor,
+    /* 0x52 */ v128_bitselect,
+    /* 0x53 */ v128_any_true,
+    /* 0x54 */ v128_load8_lane,
+    /* 0x55 */ v128_load16_lane,
+    /* 0x56 */ v128_load32_lane,
+    /* 0x57 */ v128_load64_lane,
+    /* 0x58 */ v128_store8_lane,
+    /* 0x59 */ v128_store16_lane,
+    /* 0x5A */ v128_store32_lane,
+    /* 0x5B */ v128_store64_lane,
+    /* 0x5C */ v128_load32_zero,
+    /* 0x5D */ v128_load64_zero,
+    /* 0x5E */ f32x4_demote_f64x2_zero,
+    /* 0x5F */ f64x2_promote_low_f32x4,
+    /* 0x60 */ i8x16_abs,
+    /* 0x61 */ i8x16_neg,
+    /* 0x62 */ i8x16_popcnt,
+    /* 0x63 */ i8x16_all_true,
+    /* 0x64 */ i8x16_bitmask,
+    /* 0x65 */ i8x16_narrow_i16x8_s,
+    /* 0x66 */ i8x16_narrow_i16x8_u,
+    /* 0x67 */ f32x4_ceil,
+    /* 0x68 */ f32x4_floor,
+    /* 0x69 */ f32x4_trunc,
+    /* 0x6A */ f32x4_nearest,
+    /* 0x6B */ i8x16_shl,
+    /* 0x6C */ i8x16_shr_s,
+    /* 0x6D */ i8x16_shr_u,
+    /* 0x6E */ i8x16_add,
+    /* 0x6F */ i8x16_add_sat_s,
+    /* 0x70 */ i8x16_add_sat_u,
+    /* 0x71 */ i8x16_sub,
+    /* 0x72 */ i8x16_sub_sat_s,
+    /* 0x73 */ i8x16_sub_sat_u,
+    /* 0x74 */ f64x2_ceil,
+    /* 0x75 */ f64x2_floor,
+    /* 0x76 */ i8x16_min_s,
+    /* 0x77 */ i8x16_min_u,
+    /* 0x78 */ i8x16_max_s,
+    /* 0x79 */ i8x16_max_u,
+    /* 0x7A */ f64x2_trunc,
+    /* 0x7B */ i8x16_avgr_u,
+    /* 0x7C */ i16x8_extadd_pairwise_i8x16_s,
+    /* 0x7D */ i16x8_extadd_pairwise_i8x16_u,
+    /* 0x7E */ i32x4_extadd_pairwise_i16x8_s,
+    /* 0x7F */ i32x4_extadd_pairwise_i16x8_u,
+    /* 0x80 */ i16x8_abs,
+    /* 0x81 */ i16x8_neg,
+    /* 0x82 */ i16x8_q15mulr_sat_s,
+    /* 0x83 */ i16x8_all_true,
+    /* 0x84 */ i16x8_bitmask,
+    /* 0x85 */ i16x8_narrow_i32x4_s,
+    /* 0x86 */ i16x8_narrow_i32x4_u,
+    /* 0x87 */ i16x8_extend_low_i8x16_s,
+    /* 0x88 */ i16x8_extend_high_i8x16_s,
+    /* 0x89 */ i16x8_extend_low_i8x16_u,
+    /* 0x8A */ i16x8_extend_high_i8x16_u,
+    /* 0x8B */ i16x8_shl,
+    /* 0x8C */ i16x8_shr_s,
+    /* 0x8D */ i16x8_shr_u,
+    /* 0x8E */ i16x8_add,
+    /* 0x8F */ i16x8_add_sat_s,
+    /* 0x90 */ i16x8_add_sat_u,
+    /* 0x91 */ i16x8_sub,
+    /* 0x92 */ i16x8_sub_sat_s,
+    /* 0x93 */ i16x8_sub_sat_u,
+    /* 0x94 */ f64x2_nearest,
+    /* 0x95 */ i16x8_mul,
+    /* 0x96 */ i16x8_min_s,
+    /* 0x97 */ i16x8_min_u,
+    /* 0x98 */ i16x8_max_s,
+    /* 0x99 */ i16x8_max_u,
+    /* 0x9A */ e,
+    /* 0x9B */ i16x8_avgr_u,
+    /* 0x9C */ i16x8_extmul_low_i8x16_s,
+    /* 0x9D */ i16x8_extmul_high_i8x16_s,
+    /* 0x9E */ i16x8_extmul_low_i8x16_u,
+    /* 0x9F */ i16x8_extmul_high_i8x16_u,
+    /* 0xA0 */ i32x4_abs,
+    /* 0xA1 */ i32x4_neg,
+    /* 0xA2 */ e,
+    /* 0xA3 */ i32x4_all_true,
+    /* 0xA4 */ i32x4_bitmask,
+    /* 0xA5 */ e,
+    /* 0xA6 */ e,
+    /* 0xA7 */ i32x4_extend_low_i16x8_s,
+    /* 0xA8 */ i32x4_extend_high_i16x8_s,
+    /* 0xA9 */ i32x4_extend_low_i16x8_u,
+    /* 0xAA */ i32x4_extend_high_i16x8_u,
+    /* 0xAB */ i32x4_shl,
+    /* 0xAC */ i32x4_shr_s,
+    /* 0xAD */ i32x4_shr_u,
+    /* 0xAE */ i32x4_add,
+    /* 0xAF */ e,
+    /* 0xB0 */ e,
+    /* 0xB1 */ i32x4_sub,
+    /* 0xB2 */ e,
+    /* 0xB3 */ e,
+    /* 0xB4 */ e,
+    /* 0xB5 */ i32x4_mul,
+    /* 0xB6 */ i32x4_min_s,
+    /* 0xB7 */ i32x4_min_u,
+    /* 0xB8 */ i32x4_max_s,
+    /* 0xB9 */ i32x4_max_u,
+    /* 0xBA */ i32x4_dot_i16x8_s,
+    /* 0xBB */ e,
+    /* 0xBC */ i32x4_extmul_low_i16x8_s,
+    /* 0xBD */ i32x4_extmul_high_i16x8_s,
+    /* 0xBE */ i32x4_extmul_low_i16x8_u,
+    /* 0xBF */ i32x4_extmul_high_i16x8_u,
+    /* 0xC0 */ i64x2_abs,
+    /* 0xC1 */ i64x2_neg,
+    /* 0xC2 */ e,
+    /* 0xC3 */ i64x2_all_true,
+    /* 0xC4 */ i64x2_bitmask,
+    /* 0xC5 */ e,
+    /* 0xC6 */ e,
+    /* 0xC7 */ i64x2_extend_low_i32x4_s,
+    /* 0xC8 */ i64x2_extend_high_i32x4_s,
+    /* 0xC9 */ i64x2_extend_low_i32x4_u,
+    /* 0xCA */ i64x2_extend_high_i32x4_u,
+    /* 0xCB */ i64x2_shl,
+    /* 0xCC */ i64x2_shr_s,
+    /* 0xCD */ i64x2_shr_u,
+    /* 0xCE */ i64x2_add,
+    /* 0xCF */ e,
+    /* 0xD0 */ e,
+    /* 0xD1 */ i64x2_sub,
+    /* 0xD2 */ e,
+    /* 0xD3 */ e,
+    /* 0xD4 */ e,
+    /* 0xD5 */ i64x2_mul,
+    /* 0xD6 */ i64x2_eq,
+    /* 0xD7 */ i64x2_ne,
+    /* 0xD8 */ i64x2_lt_s,
+    /* 0xD9 */ i64x2_gt_s,
+    /* 0xDA */ i64x2_le_s,
+    /* 0xDB */ i64x2_ge_s,
+    /* 0xDC */ i64x2_extmul_low_i32x4_s,
+    /* 0xDD */ i64x2_extmul_high_i32x4_s,
+    /* 0xDE */ i64x2_extmul_low_i32x4_u,
+    /* 0xDF */ i64x2_extmul_high_i32x4_u,
+    /* 0xE0 */ f32x4_abs,
+    /* 0xE1 */ f32x4_neg,
+    /* 0xE2 */ e,
+    /* 0xE3 */ f32x4_sqrt,
+    /* 0xE4 */ f32x4_add,
+    /* 0xE5 */ f32x4_sub,
+    /* 0xE6 */ f32x4_mul,
+    /* 0xE7 */ f32x4_div,
+    /* 0xE8 */ f32x4_min,
+    /* 0xE9 */ f32x4_max,
+    /* 0xEA */ f32x4_pmin,
+    /* 0xEB */ f32x4_pmax,
+    /* 0xEC */ f64x2_abs,
+    /* 0xED */ f64x2_neg,
+    /* 0xEE */ e,
+    /* 0xEF */ f64x2_sqrt,
+    /* 0xF0 */ f64x2_add,
+    /* 0xF1 */ f64x2_sub,
+    /* 0xF2 */ f64x2_mul,
+    /* 0xF3 */ f64x2_div,
+    /* 0xF4 */ f64x2_min,
+    /* 0xF5 */ f64x2_max,
+    /* 0xF6 */ f64x2_pmin,
+    /* 0xF7 */ f64x2_pmax,
+    /* 0xF8 */ i32x4_trunc_sat_f32x4_s,
+    /* 0xF9 */ i32x4_trunc_sat_f32x4_u,
+    /* 0xFA */ f32x4_convert_i32x4_s,
+    /* 0xFB */ f32x4_convert_i32x4_u,
+    /* 0xFC */ i32x4_trunc_sat_f64x2_s_zero,
+    /* 0xFD */ i32x4_trunc_sat_f64x2_u_zero,
+    /* 0xFE */ f64x2_convert_low_i32x4_s,
+    /* 0xFF */ f64x2_convert_low_i32x4_u,
+];
+
+/// Reference: https://webassembly.github.io/threads/core/binary/instructions.html#atomic-memory-instructions
+#[rustfmt::skip]
+#[allow(non_upper_case_globals)]
+pub(crate) const LVL2_JMP_TABLE_0xFE: [fn(&mut Context, &mut WasmBinaryReader, &mut dyn InstructionConsumer) -> ParseResult; 79] = [
+    /* 0x00 */ memory_atomic_notify,
+    /* 0x01 */ memory_atomic_wait32,
+    /* 0x02 */ memory_atomic_wait64,
+    /* 0x03 */ atomic_fence,
+
+    /* Unused */ e, e, e, e, e, e, e, e, e, e, e, e,
+
+    /* 0x10 */ i32_atomic_load,
+    /* 0x11 */ i64_atomic_load,
+    /* 0x12 */ i32_atomic_load8_u,
+    /* 0x13 */ i32_atomic_load16_u,
+    /* 0x14 */ i64_atomic_load8_u,
+    /* 0x15 */ i64_atomic_load16_u,
+    /* 0x16 */ i64_atomic_load32_u,
+
+    /* 0x17 */ i32_atomic_store,
+    /* 0x18 */ i64_atomic_store,
+    /* 0x19 */ i32_atomic_store8,
+    /* 0x1A */ i32_atomic_store16,
+    /* 0x1B */ i64_atomic_store8,
+    /* 0x1C */ i64_atomic_store16,
+    /* 0x1D */ i64_atomic_store32,
+
+    /* 0x1E */ i32_atomic_rmw_add,
+    /* 0x1F */ i64_atomic_rmw_add,
+    /* 0x20 */ i32_atomic_rmw8_add_u,
+    /* 0x21 */ i32_atomic_rmw16_add_u,
+    /* 0x22 */ i64_atomic_rmw8_add_u,
+    /* 0x23 */ i64_atomic_rmw16_add_u,
+    /* 0x24 */ i64_atomic_rmw32_add_u,
+
+    /* 0x25 */ i32_atomic_rmw_sub,
+    /* 0x26 */ i64_atomic_rmw_sub,
+    /* 0x27 */ i32_atomic_rmw8_sub_u,
+    /* 0x28 */ i32_atomic_rmw16_sub_u,
+    /* 0x29 */ i64_atomic_rmw8_sub_u,
+    /* 0x2A */ i64_atomic_rmw16_sub_u,
+    /* 0x2B */ i64_atomic_rmw32_sub_u,
+
+    /* 0x2C */ i32_atomic_rmw_and,
+    /* 0x2D */ i64_atomic_rmw_and,
+    /* 0x2E */ i32_atomic_rmw8_and_u,
+    /* 0x2F */ i32_atomic_rmw16_and_u,
+    /* 0x30 */ i64_atomic_rmw8_and_u,
+    /* 0x31 */ i64_atomic_rmw16_and_u,
+    /* 0x32 */ i64_atomic_rmw32_and_u,
+
+    /* 0x33 */ i32_atomic_rmw_or,
+    /* 0x34 */ i64_atomic_rmw_or,
+    /* 0x35 */ i32_atomic_rmw8_or_u,
+    /* 0x36 */ i32_atomic_rmw16_or_u,
+    /* 0x37 */ i64_atomic_rmw8_or_u,
+    /* 0x38 */ i64_atomic_rmw16_or_u,
+    /* 0x39 */ i64_atomic_rmw32_or_u,
+
+    /* 0x3A */ i32_atomic_rmw_xor,
+    /* 0x3B */ i64_atomic_rmw_xor,
+    /* 0x3C */ i32_atomic_rmw8_xor_u,
+    /* 0x3D */ i32_atomic_rmw16_xor_u,
+    /* 0x3E */ i64_atomic_rmw8_xor_u,
+    /* 0x3F */ i64_atomic_rmw16_xor_u,
+    /* 0x40 */ i64_atomic_rmw32_xor_u,
+
+    /* 0x41 */ i32_atomic_rmw_xchg,
+    /* 0x42 */ i64_atomic_rmw_xchg,
+    /* 0x43 */ i32_atomic_rmw8_xchg_u,
+    /* 0x44 */ i32_atomic_rmw16_xchg_u,
+    /* 0x45 */ i64_atomic_rmw8_xchg_u,
+    /* 0x46 */ i64_atomic_rmw16_xchg_u,
+    /* 0x47 */ i64_atomic_rmw32_xchg_u,
+
+    /* 0x48 */ i32_atomic_rmw_cmpxchg,
+    /* 0x49 */ i64_atomic_rmw_cmpxchg,
+    /* 0x4A */ i32_atomic_rmw8_cmpxchg_u,
+    /* 0x4B */ i32_atomic_rmw16_cmpxchg_u,
+    /* 0x4C */ i64_atomic_rmw8_cmpxchg_u,
+    /* 0x4D */ i64_atomic_rmw16_cmpxchg_u,
+    /* 0x4E */ i64_atomic_rmw32_cmpxchg_u,
+];
 
 fn e(_: &mut Context, _: &mut WasmBinaryReader, _: &mut dyn InstructionConsumer) -> ParseResult {
     Err(ParserError::InvalidOpcode)
 }
 
+fn lvl2_instruction_gc(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let opcode = i.read_leb128::<u32>()? as usize;
+    if opcode >= LVL2_JMP_TABLE_0xFB.len() {
+        return Err(ParserError::InvalidEncoding);
+    }
+    LVL2_JMP_TABLE_0xFB[opcode](ctxt, i, o)
+}
+
 fn lvl2_instruction_gp(
     ctxt: &mut Context,
     i: &mut WasmBinaryReader,
@@ -512,3 +952,15 @@ fn lvl2_instruction_vec(
     }
     LVL2_JMP_TABLE_0xFD[opcode](ctxt, i, o)
 }
+
+fn lvl2_instruction_atomic(
+    ctxt: &mut Context,
+    i: &mut WasmBinaryReader,
+    o: &mut dyn InstructionConsumer,
+) -> ParseResult {
+    let opcode = i.read_leb128::<u32>()? as usize;
+    if opcode >= LVL2_JMP_TABLE_0xFE.len() {
+        return Err(ParserError::InvalidEncoding);
+    }
+    LVL2_JMP_TABLE_0xFE[opcode](ctxt, i, o)
+}
@@ -5,17 +5,23 @@ use super::{
     stack::ParserStack,
 };
 use crate::{
-    error::{ParserError, ValidationError},
+    error::{IndexSpace, ParserError, ResourceLimit, ValidationError},
     wasm_stream_reader::WasmBinaryReader,
     ParseResult,
 };
 use module::{
-    basic_block::{BasicBlockGlue, BasicBlockID},
-    instructions::{Variable, VariableID},
-    objects::instruction::ControlInstruction,
+    basic_block::{BasicBlockGlue, BasicBlockID, CatchHandler},
+    instructions::{
+        ITestInstruction, ReferenceIsNullInstruction, ReferenceTestInstruction, Variable,
+        VariableID,
+    },
+    objects::instruction::{Catch, ControlInstruction},
 };
 use smallvec::{SmallVec, ToSmallVec};
-use wasm_types::{BlockType, RefType, ResType, ValType};
+use wasm_types::{
+    BlockType, FuncType, HeapType, ITestOp, LabelIdx, NumType, RefType, ResType, TagIdx, TypeIdx,
+    ValType,
+};
 
 struct BTWrapper(BlockType);
 
@@ -26,10 +32,11 @@ impl BTWrapper {
         ctxt.stack.stash_with_keep(input_length);
         let mut input_vars = SmallVec::new();
         for (i, input_var) in self.block_inputs(ctxt).enumerate() {
-            if ctxt.stack[i].type_ != input_var {
-                ctxt.poison(ValidationError::Msg(
-                    "mismatched input signature in target label".to_string(),
-                ))
+            if !ctxt.matches(&ctxt.stack[i].type_, &input_var) {
+                ctxt.poison(ValidationError::TypeMismatch {
+                    expected: input_var,
+                    actual: ctxt.stack[i].type_,
+                })
             } else {
                 input_vars.push(ctxt.stack[i].id);
             }
@@ -86,28 +93,20 @@ where
 {
     let mut return_vars = SmallVec::new();
     let stack_depth = ctxt.stack.len();
-    if stack_depth < out_params.len() {
-        return ctxt.poison(ValidationError::Msg(
-            "stack underflow in target label".to_string(),
-        ));
-    }
-    if check_empty_stack && stack_depth > out_params.len() {
-        return ctxt.poison(ValidationError::Msg(format!(
-            "unexpected stack state at end of function: {:?}, expected {:?}",
-            ctxt.stack.stack[ctxt.stack.stack.len() - stack_depth..]
-                .iter()
-                .map(|var| var.type_)
-                .collect::<Vec<_>>(),
-            out_params
-        )));
+    let stack_mismatch = |ctxt: &Context| ValidationError::StackMismatch {
+        expected: out_params.clone(),
+        actual: (0..stack_depth).map(|i| ctxt.stack[i].type_).collect(),
+    };
+    if stack_depth < out_params.len() || (check_empty_stack && stack_depth > out_params.len()) {
+        let err = stack_mismatch(ctxt);
+        return ctxt.poison(err);
     }
     for (i, return_value) in out_params.iter().enumerate() {
         let idx = stack_depth - out_params.len() + i;
         let stack_var = &ctxt.stack[idx];
-        if stack_var.type_ != *return_value {
-            return ctxt.poison(ValidationError::Msg(
-                "mismatched return type in target label".to_string(),
-            ));
+        if !ctxt.matches(&stack_var.type_, return_value) {
+            let err = stack_mismatch(ctxt);
+            return ctxt.poison(err);
         }
         return_vars.push(stack_var.id);
     }
@@ -123,10 +122,16 @@ fn parse_until_next_end(
     let mut saved_stack = ParserStack::new();
     let saved_poison = ctxt.poison.take();
     let mut trash_builder = FunctionIRBuilder::new();
-    let id = trash_builder.reserve_bb();
-    trash_builder.continue_bb(id);
     std::mem::swap(&mut saved_stack, &mut ctxt.stack);
-    parse_basic_blocks(i, ctxt, labels, &mut trash_builder)?;
+    // unreachable code may itself end in a branch, the code after it is skipped in the same way
+    loop {
+        let id = trash_builder.reserve_bb();
+        trash_builder.continue_bb(id);
+        ctxt.stack = ParserStack::new();
+        if parse_reachable_basic_blocks(i, ctxt, labels, &mut trash_builder)? == Flow::ScopeEnd {
+            break;
+        }
+    }
     ctxt.poison = saved_poison;
     ctxt.stack = saved_stack;
 
@@ -151,6 +156,34 @@ pub(crate) struct Label {
     // currently only required for loops
     pub(crate) loop_after_bb_id: Option<BasicBlockID>,
     pub(crate) loop_after_result_type: Option<ResType>,
+
+    // exception dispatch block of the innermost enclosing try_table, exceptions leave the function if there is none
+    pub(crate) unwind_bb: Option<BasicBlockID>,
+}
+
+/// Exceptions raised in the current scope continue at this block.
+fn current_unwind_bb(labels: &[Label]) -> Option<BasicBlockID> {
+    labels.last().and_then(|label| label.unwind_bb)
+}
+
+/// Enters a nested block. Blocks are parsed recursively, so their depth is bounded to keep the stack usage finite.
+fn push_label(ctxt: &Context, labels: &mut Vec<Label>, label: Label) -> ParseResult {
+    // the outermost label belongs to the function itself
+    ctxt.limits
+        .check(ResourceLimit::NestingDepth, labels.len() as u64)?;
+    labels.push(label);
+    Ok(())
+}
+
+/// How parsing continues after the terminator of a basic block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flow {
+    /// the code after the terminator is reachable and belongs to the same scope
+    Continue,
+    /// the terminator is the `end` or `else` of the current scope
+    ScopeEnd,
+    /// the code up to the end of the current scope is unreachable
+    Unreachable,
 }
 
 fn parse_terminator(
@@ -158,8 +191,8 @@ fn parse_terminator(
     ctxt: &mut Context,
     labels: &mut Vec<Label>,
     builder: &mut impl FunctionBuilderInterface,
-) -> Result<(), ParserError> {
-    match builder.current_bb_instrs().peek_terminator().clone() {
+) -> Result<Flow, ParserError> {
+    let flow = match builder.current_bb_instrs().peek_terminator().clone() {
         ControlInstruction::Block(block_type) => {
             let block_type = BTWrapper(block_type);
             let block_input_vars = block_type.setup_block_stack(ctxt);
@@ -178,8 +211,9 @@ fn parse_terminator(
                 result_type: block_type.block_returns(ctxt).collect(),
                 loop_after_bb_id: None,
                 loop_after_result_type: None,
+                unwind_bb: current_unwind_bb(labels),
             };
-            labels.push(block_label.clone());
+            push_label(ctxt, labels, block_label.clone())?;
 
             builder.set_bb_phi_inputs(after_block_bb_id, ctxt, block_type.block_returns(ctxt));
 
@@ -196,7 +230,7 @@ fn parse_terminator(
             builder.put_phi_inputs_on_stack(ctxt);
 
             // collect all other blocks until the next outside "end"
-            parse_basic_blocks(i, ctxt, labels, builder)?;
+            Flow::Continue
         }
 
         ControlInstruction::Loop(block_type) => {
@@ -236,8 +270,9 @@ fn parse_terminator(
                 result_type: block_type.block_inputs(ctxt).collect(),
                 loop_after_bb_id: Some(loop_exit_bb_id),
                 loop_after_result_type: Some(block_type.block_returns(ctxt).collect()),
+                unwind_bb: current_unwind_bb(labels),
             };
-            labels.push(block_label.clone());
+            push_label(ctxt, labels, block_label.clone())?;
 
             // parse block instructions until the block's "end"
             builder.continue_bb(loop_body_bb_id);
@@ -250,7 +285,7 @@ fn parse_terminator(
             // collect all other blocks until the next outside "end"
             builder.continue_bb(loop_exit_bb_id);
             builder.put_phi_inputs_on_stack(ctxt);
-            parse_basic_blocks(i, ctxt, labels, builder)?;
+            Flow::Continue
         }
 
         ControlInstruction::IfElse(block_type) => {
@@ -270,8 +305,9 @@ fn parse_terminator(
                 loop_after_bb_id: None,
                 loop_after_result_type: None,
                 result_type: block_type.block_returns(ctxt).collect(),
+                unwind_bb: current_unwind_bb(labels),
             };
-            labels.push(block_label.clone());
+            push_label(ctxt, labels, block_label.clone())?;
 
             let block_input_vars = block_type.setup_block_stack(ctxt);
 
@@ -327,26 +363,23 @@ fn parse_terminator(
             // parse blocks after if-else
             builder.continue_bb(if_else_exit_bb);
             builder.put_phi_inputs_on_stack(ctxt);
-            parse_basic_blocks(i, ctxt, labels, builder)?;
+            Flow::Continue
         }
 
         ControlInstruction::Br(label_idx) => {
-            if labels.is_empty() || label_idx >= labels.len() as u32 {
-                return Err(ParserError::Msg("label index out of bounds".to_string()));
-            }
-            let target_label = labels[labels.len() - label_idx as usize - 1].clone();
+            let target_label = label(labels, label_idx)?;
             let output_vars =
                 validate_and_extract_result_from_stack(ctxt, &target_label.result_type, false);
             builder.terminate_jmp(target_label.bb_id, output_vars);
 
             // unconditional branch -> following blocks only need to parsed, but not validated
-            parse_until_next_end(i, ctxt, labels, builder)?;
+            Flow::Unreachable
         }
 
         ControlInstruction::BrIf(label_idx) => {
             let cond_var = ctxt.pop_var_with_type(ValType::i32()).id;
             let target_if_false = builder.reserve_bb();
-            let target_if_true = labels[labels.len() - label_idx as usize - 1].clone();
+            let target_if_true = label(labels, label_idx)?;
             let output_vars =
                 validate_and_extract_result_from_stack(ctxt, &target_if_true.result_type, false);
 
@@ -357,16 +390,12 @@ fn parse_terminator(
                 output_vars,
             );
             builder.continue_bb(target_if_false);
-            parse_basic_blocks(i, ctxt, labels, builder)?;
+            Flow::Continue
         }
 
         ControlInstruction::BrTable(default_label, label_table) => {
             let selector_var = ctxt.pop_var_with_type(ValType::i32()).id;
-            let default_bb = if default_label >= labels.len() as u32 {
-                return Err(ParserError::Msg("label index out of bounds".to_string()));
-            } else {
-                labels[labels.len() - default_label as usize - 1].clone()
-            };
+            let default_bb = label(labels, default_label)?;
             let default_output_vars =
                 validate_and_extract_result_from_stack(ctxt, &default_bb.result_type, false);
 
@@ -375,7 +404,9 @@ fn parse_terminator(
                 SmallVec<[SmallVec<[VariableID; 0]>; 8]>,
             ) = label_table
                 .into_iter()
-                .map(|label_idx| &labels[labels.len() - label_idx as usize - 1])
+                .map(|label_idx| label(labels, label_idx))
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
                 .map(|target_label| {
                     let output_vars = validate_and_extract_result_from_stack(
                         ctxt,
@@ -394,20 +425,24 @@ fn parse_terminator(
             );
 
             // unconditional branch -> following blocks only need to parsed, but not validated
-            parse_until_next_end(i, ctxt, labels, builder)?;
+            Flow::Unreachable
         }
 
         ControlInstruction::Unreachable => {
             builder.terminate_unreachable();
             // parse away following junk
-            parse_until_next_end(i, ctxt, labels, builder)?;
+            Flow::Unreachable
         }
 
         ControlInstruction::Call(func_idx) => {
-            if func_idx > ctxt.module.functions.len() as u32 {
-                ctxt.poison(ValidationError::Msg(
-                    "call function index out of bounds".to_string(),
-                ))
+            if func_idx >= ctxt.module.functions.len() as u32 {
+                ctxt.poison::<()>(ValidationError::UnknownIndex {
+                    space: IndexSpace::Function,
+                    idx: func_idx,
+                });
+                // the callee is unknown, so the rest of the scope can't be validated
+                builder.terminate_unreachable();
+                return Ok(Flow::Unreachable);
             }
             let func_type = ctxt
                 .module
@@ -437,31 +472,44 @@ fn parse_terminator(
                 })
                 .collect();
             let return_bb = builder.reserve_bb();
-            builder.terminate_call(func_idx, return_bb, call_params, return_vars);
+            builder.terminate_call(
+                func_idx,
+                return_bb,
+                call_params,
+                return_vars,
+                current_unwind_bb(labels),
+            );
 
             // parse continuation basic blocks
             builder.continue_bb(return_bb);
-            parse_basic_blocks(i, ctxt, labels, builder)?;
+            Flow::Continue
         }
 
         ControlInstruction::CallIndirect(type_idx, table_idx) => {
-            if table_idx > ctxt.module.tables.len() as u32 {
-                ctxt.poison(ValidationError::Msg(
-                    "icall table index out of bounds".to_string(),
-                ))
-            } else if !matches!(
-                ctxt.module.tables[table_idx as usize].r#type.ref_type,
-                RefType::FunctionReference
-            ) {
-                ctxt.poison(ValidationError::Msg(
-                    "icall table type mismatch".to_string(),
-                ))
+            if table_idx >= ctxt.module.tables.len() as u32 {
+                ctxt.poison(ValidationError::UnknownIndex {
+                    space: IndexSpace::Table,
+                    idx: table_idx,
+                })
+            } else {
+                let table_type =
+                    ValType::Reference(ctxt.module.tables[table_idx as usize].r#type.ref_type);
+                if !ctxt.matches(&table_type, &ValType::funcref()) {
+                    ctxt.poison(ValidationError::TypeMismatch {
+                        expected: ValType::funcref(),
+                        actual: table_type,
+                    })
+                }
             }
 
-            if type_idx > ctxt.module.function_types.len() as u32 {
-                ctxt.poison(ValidationError::Msg(
-                    "icall function index out of bounds".to_string(),
-                ))
+            if ctxt.module.func_type(type_idx).is_none() {
+                ctxt.poison::<()>(ValidationError::UnknownIndex {
+                    space: IndexSpace::Type,
+                    idx: type_idx,
+                });
+                // the callee is unknown, so the rest of the scope can't be validated
+                builder.terminate_unreachable();
+                return Ok(Flow::Unreachable);
             }
 
             let selector_var = ctxt.pop_var_with_type(ValType::i32()).id;
@@ -490,11 +538,364 @@ fn parse_terminator(
                 return_bb,
                 call_params,
                 return_vars,
+                current_unwind_bb(labels),
+            );
+
+            // parse continuation basic blocks
+            builder.continue_bb(return_bb);
+            Flow::Continue
+        }
+
+        ControlInstruction::ReturnCall(func_idx) => {
+            let func_type = ctxt
+                .module
+                .functions
+                .get(func_idx as usize)
+                .and_then(|func| {
+                    ctxt.module
+                        .function_types
+                        .get(func.type_idx as usize)
+                        .cloned()
+                })
+                .ok_or(ValidationError::UnknownIndex {
+                    space: IndexSpace::Function,
+                    idx: func_idx,
+                })?;
+            validate_tail_call_results(ctxt, labels, &func_type)?;
+            let call_params =
+                validate_and_extract_result_from_stack(ctxt, &func_type.params(), false);
+            builder.terminate_return_call(func_idx, call_params);
+            // the callee returns on our behalf -> parse away the following end instruction and any junk
+            Flow::Unreachable
+        }
+
+        ControlInstruction::ReturnCallIndirect(type_idx, table_idx) => {
+            match ctxt.module.tables.get(table_idx as usize) {
+                None => ctxt.poison(ValidationError::UnknownIndex {
+                    space: IndexSpace::Table,
+                    idx: table_idx,
+                }),
+                Some(table)
+                    if !ctxt.matches(
+                        &ValType::Reference(table.r#type.ref_type),
+                        &ValType::funcref(),
+                    ) =>
+                {
+                    ctxt.poison(ValidationError::TypeMismatch {
+                        expected: ValType::funcref(),
+                        actual: ValType::Reference(table.r#type.ref_type),
+                    })
+                }
+                Some(_) => {}
+            }
+            let func_type = ctxt
+                .module
+                .func_type(type_idx)
+                .ok_or(unknown_type(type_idx))?;
+            validate_tail_call_results(ctxt, labels, &func_type)?;
+            let selector_var = ctxt.pop_var_with_type(ValType::i32()).id;
+            let call_params =
+                validate_and_extract_result_from_stack(ctxt, &func_type.params(), false);
+            builder.terminate_return_call_indirect(type_idx, selector_var, table_idx, call_params);
+            // the callee returns on our behalf -> parse away the following end instruction and any junk
+            Flow::Unreachable
+        }
+
+        ControlInstruction::CallRef(type_idx) => {
+            let func_type = ctxt
+                .module
+                .func_type(type_idx)
+                .ok_or(unknown_type(type_idx))?;
+            let selector_var = ctxt
+                .pop_var_with_type(ValType::Reference(RefType::new(
+                    HeapType::Concrete(type_idx),
+                    true,
+                )))
+                .id;
+            let call_params =
+                validate_and_extract_result_from_stack(ctxt, &func_type.params(), false);
+            // pop all parameters from the stack
+            ctxt.stack
+                .stack
+                .truncate(ctxt.stack.stack.len() - call_params.len());
+
+            let return_vars = func_type
+                .results_iter()
+                .map(|val| {
+                    let var = ctxt.create_var(val);
+                    let tmp = var.id;
+                    ctxt.push_var(var);
+                    tmp
+                })
+                .collect();
+            let return_bb = builder.reserve_bb();
+            builder.terminate_call_ref(
+                type_idx,
+                selector_var,
+                return_bb,
+                call_params,
+                return_vars,
+                current_unwind_bb(labels),
             );
 
             // parse continuation basic blocks
             builder.continue_bb(return_bb);
+            Flow::Continue
+        }
+
+        ControlInstruction::ReturnCallRef(type_idx) => {
+            let func_type = ctxt
+                .module
+                .func_type(type_idx)
+                .ok_or(unknown_type(type_idx))?;
+            validate_tail_call_results(ctxt, labels, &func_type)?;
+            let selector_var = ctxt
+                .pop_var_with_type(ValType::Reference(RefType::new(
+                    HeapType::Concrete(type_idx),
+                    true,
+                )))
+                .id;
+            let call_params =
+                validate_and_extract_result_from_stack(ctxt, &func_type.params(), false);
+            builder.terminate_return_call_ref(type_idx, selector_var, call_params);
+            // the callee returns on our behalf -> parse away the following end instruction and any junk
+            Flow::Unreachable
+        }
+
+        ControlInstruction::BrOnNull(label_idx) => {
+            let target_if_null = label(labels, label_idx)?;
+            let ref_var = ctxt.pop_var();
+            let ref_type = match ref_var.type_ {
+                ValType::Reference(ref_type) => ref_type,
+                _ => {
+                    ctxt.poison::<()>(ValidationError::ExpectedReference {
+                        instruction: "br_on_null",
+                        actual: ref_var.type_,
+                    });
+                    RefType::FunctionReference
+                }
+            };
+            // lowered to a conditional jump on ref.is_null
+            let is_null_var = ctxt.create_var(ValType::i32());
+            builder
+                .current_bb_instrs()
+                .write_reference_is_null(ReferenceIsNullInstruction {
+                    in1: ref_var.id,
+                    in1_type: ref_var.type_,
+                    out1: is_null_var.id,
+                });
+            let target_if_non_null = builder.reserve_bb();
+            let output_vars =
+                validate_and_extract_result_from_stack(ctxt, &target_if_null.result_type, false);
+            builder.terminate_jmp_cond(
+                is_null_var.id,
+                target_if_null.bb_id,
+                target_if_non_null,
+                output_vars,
+            );
+
+            // the reference is known to be non-null on fallthrough
+            builder.continue_bb(target_if_non_null);
+            ctxt.push_var(Variable {
+                id: ref_var.id,
+                type_: ValType::Reference(ref_type.as_non_null()),
+            });
+            Flow::Continue
+        }
+
+        ControlInstruction::BrOnNonNull(label_idx) => {
+            let target_if_non_null = label(labels, label_idx)?;
+            let ref_var = ctxt.pop_var();
+            let ref_type = match ref_var.type_ {
+                ValType::Reference(ref_type) => ref_type,
+                _ => {
+                    ctxt.poison::<()>(ValidationError::ExpectedReference {
+                        instruction: "br_on_non_null",
+                        actual: ref_var.type_,
+                    });
+                    RefType::FunctionReference
+                }
+            };
+            // lowered to a conditional jump on the negated ref.is_null
+            let is_null_var = ctxt.create_var(ValType::i32());
+            let is_non_null_var = ctxt.create_var(ValType::i32());
+            let instrs = builder.current_bb_instrs();
+            instrs.write_reference_is_null(ReferenceIsNullInstruction {
+                in1: ref_var.id,
+                in1_type: ref_var.type_,
+                out1: is_null_var.id,
+            });
+            instrs.write_test(ITestInstruction {
+                input_type: NumType::I32,
+                op: ITestOp::Eqz,
+                in1: is_null_var.id,
+                out1: is_non_null_var.id,
+            });
+
+            // the non-null reference is passed to the target label as its last result
+            ctxt.push_var(Variable {
+                id: ref_var.id,
+                type_: ValType::Reference(ref_type.as_non_null()),
+            });
+            let target_if_null = builder.reserve_bb();
+            let output_vars = validate_and_extract_result_from_stack(
+                ctxt,
+                &target_if_non_null.result_type,
+                false,
+            );
+            builder.terminate_jmp_cond(
+                is_non_null_var.id,
+                target_if_non_null.bb_id,
+                target_if_null,
+                output_vars,
+            );
+
+            builder.continue_bb(target_if_null);
+            ctxt.pop_var();
+            Flow::Continue
+        }
+
+        terminator @ (ControlInstruction::BrOnCast(label_idx, from, to)
+        | ControlInstruction::BrOnCastFail(label_idx, from, to)) => {
+            let target = label(labels, label_idx)?;
+            let ref_var = ctxt.pop_var_with_type(ValType::Reference(from));
+            if !ctxt.matches(&ValType::Reference(to), &ValType::Reference(from)) {
+                ctxt.poison::<()>(ValidationError::CastMismatch {
+                    target: to,
+                    actual: ValType::Reference(from),
+                });
+            }
+            // the operand is known not to be null if a nullable target type did not match
+            let diff = RefType::new(from.heap_type(), from.is_nullable() && !to.is_nullable());
+
+            // lowered to a conditional jump on ref.test
+            let is_cast_var = ctxt.create_var(ValType::i32());
+            builder
+                .current_bb_instrs()
+                .write_reference_test(ReferenceTestInstruction {
+                    in1: ref_var.id,
+                    ref_type: to,
+                    out1: is_cast_var.id,
+                });
+            let (cond_var, branch_type, fallthrough_type) = match terminator {
+                ControlInstruction::BrOnCastFail(..) => {
+                    let is_not_cast_var = ctxt.create_var(ValType::i32());
+                    builder.current_bb_instrs().write_test(ITestInstruction {
+                        input_type: NumType::I32,
+                        op: ITestOp::Eqz,
+                        in1: is_cast_var.id,
+                        out1: is_not_cast_var.id,
+                    });
+                    (is_not_cast_var.id, diff, to)
+                }
+                _ => (is_cast_var.id, to, diff),
+            };
+
+            // the operand is passed to the target label as its last result
+            ctxt.push_var(Variable {
+                id: ref_var.id,
+                type_: ValType::Reference(branch_type),
+            });
+            let fallthrough = builder.reserve_bb();
+            let output_vars =
+                validate_and_extract_result_from_stack(ctxt, &target.result_type, false);
+            builder.terminate_jmp_cond(cond_var, target.bb_id, fallthrough, output_vars);
+
+            builder.continue_bb(fallthrough);
+            ctxt.pop_var();
+            ctxt.push_var(Variable {
+                id: ref_var.id,
+                type_: ValType::Reference(fallthrough_type),
+            });
+            Flow::Continue
+        }
+
+        ControlInstruction::TryTable(block_type, catches) => {
+            let block_type = BTWrapper(block_type);
+
+            // catch clauses branch to labels outside of the try_table
+            let catch_targets = catches
+                .iter()
+                .map(|catch| resolve_catch_target(ctxt, labels, catch))
+                .collect::<Result<SmallVec<[_; 2]>, ParserError>>()?;
+
+            let block_input_vars = block_type.setup_block_stack(ctxt);
+
+            // complete leading bb
+            let first_nested_block_id = builder.reserve_bb();
+            builder.terminate_jmp(first_nested_block_id, block_input_vars);
+
+            // exceptions raised inside of the try_table are dispatched to the catch handlers
+            let dispatch_bb_id = builder.reserve_bb();
+            let handlers = catch_targets
+                .iter()
+                .map(|(catch, payload_types, _)| CatchHandler {
+                    tag_idx: catch.tag_idx(),
+                    target: builder.reserve_bb(),
+                    payload_vars: payload_types
+                        .iter()
+                        .map(|val_type| ctxt.create_var(*val_type).id)
+                        .collect(),
+                    exn_var: catch
+                        .is_ref()
+                        .then(|| ctxt.create_var(ValType::exnref()).id),
+                })
+                .collect::<SmallVec<[CatchHandler; 2]>>();
+            builder.continue_bb(dispatch_bb_id);
+            builder.terminate_catch_dispatch(handlers.clone(), current_unwind_bb(labels));
+            for (handler, (_, _, target_label)) in handlers.iter().zip(catch_targets.iter()) {
+                let mut output_vars: SmallVec<[VariableID; 0]> =
+                    handler.payload_vars.iter().copied().collect();
+                output_vars.extend(handler.exn_var);
+                builder.continue_bb(handler.target);
+                builder.terminate_jmp(target_label.bb_id, output_vars);
+            }
+
+            // save label stack size outside of block
+            let label_depth = labels.len();
+
+            // add next label to jump to (one more recursion level)
+            let after_block_bb_id = builder.reserve_bb();
+            let block_label = Label {
+                bb_id: after_block_bb_id,
+                result_type: block_type.block_returns(ctxt).collect(),
+                loop_after_bb_id: None,
+                loop_after_result_type: None,
+                unwind_bb: Some(dispatch_bb_id),
+            };
+            push_label(ctxt, labels, block_label.clone())?;
+
+            builder.set_bb_phi_inputs(after_block_bb_id, ctxt, block_type.block_returns(ctxt));
+
+            // parse block instructions until the block's "end"
+            builder.continue_bb(first_nested_block_id);
             parse_basic_blocks(i, ctxt, labels, builder)?;
+
+            // restore outer scope
+            labels.truncate(label_depth);
+            ctxt.stack.unstash();
+
+            // put phis onto stack for block tail / bbs after block
+            builder.continue_bb(after_block_bb_id);
+            builder.put_phi_inputs_on_stack(ctxt);
+
+            // collect all other blocks until the next outside "end"
+            Flow::Continue
+        }
+
+        ControlInstruction::Throw(tag_idx) => {
+            let tag_type = tag_type(ctxt, tag_idx)?;
+            let values = validate_and_extract_result_from_stack(ctxt, &tag_type.params(), false);
+            builder.terminate_throw(tag_idx, values, current_unwind_bb(labels));
+            // the exception never returns -> parse away the following end instruction and any junk
+            Flow::Unreachable
+        }
+
+        ControlInstruction::ThrowRef => {
+            let exn_var = ctxt.pop_var_with_type(ValType::exnref()).id;
+            builder.terminate_throw_ref(exn_var, current_unwind_bb(labels));
+            // the exception never returns -> parse away the following end instruction and any junk
+            Flow::Unreachable
         }
 
         ControlInstruction::End => {
@@ -531,18 +932,17 @@ fn parse_terminator(
                     );
                 }
             }
+            Flow::ScopeEnd
         }
 
         ControlInstruction::Return => {
             // validate return parameter types
-            let func_scope_label = labels.first().ok_or(ParserError::Msg(
-                "return instruction outside of function scope".to_string(),
-            ))?;
+            let func_scope_label = function_scope_label(labels)?;
             let return_vars =
                 validate_and_extract_result_from_stack(ctxt, &func_scope_label.result_type, false);
             builder.terminate_return(return_vars);
             // parse away the following end instruction and any junk that might follow
-            parse_until_next_end(i, ctxt, labels, builder)?;
+            Flow::Unreachable
         }
 
         ControlInstruction::Else => {
@@ -551,26 +951,139 @@ fn parse_terminator(
                 validate_and_extract_result_from_stack(ctxt, &ifscopelabel.result_type, false);
             builder.terminate_else(output_vars);
             // stop parsing here, because the "else" block is parsed in the "if" block
+            Flow::ScopeEnd
         }
 
         ControlInstruction::Nop => {
             // we don't parse this at all.
             unreachable!()
         }
+    };
+    Ok(flow)
+}
+
+/// A tail call replaces the current function, so the callee must return exactly what the caller returns.
+fn validate_tail_call_results(
+    ctxt: &mut Context,
+    labels: &[Label],
+    callee_type: &FuncType,
+) -> Result<(), ParserError> {
+    let func_scope_label = function_scope_label(labels)?;
+    let results = callee_type.results();
+    if results.len() != func_scope_label.result_type.len()
+        || !results
+            .iter()
+            .zip(func_scope_label.result_type.iter())
+            .all(|(result, expected)| ctxt.matches(result, expected))
+    {
+        ctxt.poison(ValidationError::StackMismatch {
+            expected: func_scope_label.result_type.to_vec(),
+            actual: results.to_vec(),
+        })
     }
     Ok(())
 }
 
+/// Function type of an exception tag, whose params are the exception payload.
+fn tag_type(ctxt: &Context, tag_idx: TagIdx) -> Result<FuncType, ParserError> {
+    ctxt.module
+        .tags
+        .get(tag_idx as usize)
+        .map(|tag| ctxt.module.function_types[tag.type_idx as usize])
+        .ok_or(
+            ValidationError::UnknownIndex {
+                space: IndexSpace::Tag,
+                idx: tag_idx,
+            }
+            .into(),
+        )
+}
+
+/// The label `label_idx` refers to, counted from the innermost enclosing block.
+fn label(labels: &[Label], label_idx: LabelIdx) -> Result<Label, ValidationError> {
+    (label_idx as usize)
+        .checked_add(1)
+        .and_then(|depth| labels.len().checked_sub(depth))
+        .map(|position| labels[position].clone())
+        .ok_or(ValidationError::UnknownIndex {
+            space: IndexSpace::Label,
+            idx: label_idx,
+        })
+}
+
+/// The label of the function body, which `return` and tail calls leave.
+fn function_scope_label(labels: &[Label]) -> Result<&Label, ValidationError> {
+    labels.first().ok_or(ValidationError::UnknownIndex {
+        space: IndexSpace::Label,
+        idx: 0,
+    })
+}
+
+fn unknown_type(type_idx: TypeIdx) -> ValidationError {
+    ValidationError::UnknownIndex {
+        space: IndexSpace::Type,
+        idx: type_idx,
+    }
+}
+
+/// Resolves the label a catch clause branches to and the payload it receives from the exception
+/// (without the trailing `exnref` of `catch_ref` clauses).
+fn resolve_catch_target<'a>(
+    ctxt: &mut Context,
+    labels: &[Label],
+    catch: &'a Catch,
+) -> Result<(&'a Catch, ResType, Label), ParserError> {
+    let label_idx = catch.label_idx();
+    let target_label = label(labels, label_idx)?;
+    let payload_types = match catch.tag_idx() {
+        Some(tag_idx) => tag_type(ctxt, tag_idx)?.params(),
+        None => ResType::new(),
+    };
+    let mut label_types = payload_types.clone();
+    if catch.is_ref() {
+        label_types.push(ValType::exnref());
+    }
+    if label_types != target_label.result_type {
+        ctxt.poison(ValidationError::StackMismatch {
+            expected: target_label.result_type.to_vec(),
+            actual: label_types.to_vec(),
+        })
+    }
+    Ok((catch, payload_types, target_label))
+}
+
+/// Parses the basic blocks of the current scope up to its end, skipping unreachable code.
+///
+/// Only nested scopes recurse, so the stack usage is bounded by [`crate::ParserLimits::max_nesting_depth`].
 pub(crate) fn parse_basic_blocks(
     i: &mut WasmBinaryReader,
     ctxt: &mut Context,
     labels: &mut Vec<Label>,
     builder: &mut impl FunctionBuilderInterface,
 ) -> Result<(), ParserError> {
-    let instrs = builder.current_bb_instrs();
-    while !instrs.is_finished() {
-        let opcode: u8 = i.read_byte()?;
-        LVL1_JMP_TABLE[opcode as usize](ctxt, i, instrs)?;
+    if parse_reachable_basic_blocks(i, ctxt, labels, builder)? == Flow::Unreachable {
+        parse_until_next_end(i, ctxt, labels, builder)?;
+    }
+    Ok(())
+}
+
+/// Parses basic blocks until the end of the current scope or until the following code is unreachable.
+fn parse_reachable_basic_blocks(
+    i: &mut WasmBinaryReader,
+    ctxt: &mut Context,
+    labels: &mut Vec<Label>,
+    builder: &mut impl FunctionBuilderInterface,
+) -> Result<Flow, ParserError> {
+    loop {
+        let instrs = builder.current_bb_instrs();
+        while !instrs.is_finished() {
+            ctxt.instr_offset = i.pos;
+            let opcode: u8 = i.read_byte()?;
+            LVL1_JMP_TABLE[opcode as usize](ctxt, i, instrs)?;
+        }
+        match parse_terminator(i, ctxt, labels, builder)? {
+            Flow::Continue => {}
+            flow => return Ok(flow),
+        }
     }
-    parse_terminator(i, ctxt, labels, builder)
 }
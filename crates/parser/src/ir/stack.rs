@@ -1,7 +1,7 @@
 use std::ops::Index;
 
 use module::instructions::Variable;
-use wasm_types::ValType;
+use wasm_types::{SubType, ValType};
 
 use crate::ValidationError;
 
@@ -38,33 +38,43 @@ impl ParserStack {
     pub(crate) fn pop_var_with_type(
         &mut self,
         type_: ValType,
+        types: &[SubType],
     ) -> Result<Variable, ValidationError> {
         if self
             .stash
             .last()
             .map_or(false, |&stash| stash >= self.stack.len())
         {
-            return Err(ValidationError::Msg("stack underflow".into()));
+            return Err(ValidationError::StackUnderflow {
+                expected: Some(type_),
+            });
         }
 
         let var = match self.stack.pop() {
             Some(var) => var,
-            None => return Err(ValidationError::Msg("stack underflow".into())),
+            None => {
+                return Err(ValidationError::StackUnderflow {
+                    expected: Some(type_),
+                })
+            }
         };
-        if var.type_ != type_ {
-            return Err(ValidationError::Msg("type mismatch".into()));
+        if !var.type_.matches(types, &type_, types) {
+            return Err(ValidationError::TypeMismatch {
+                expected: type_,
+                actual: var.type_,
+            });
         }
         Ok(var)
     }
 
     pub(crate) fn pop_var(&mut self) -> Result<Variable, ValidationError> {
         if *self.stash.last().unwrap() >= self.stack.len() {
-            return Err(ValidationError::Msg("stack underflow".into()));
+            return Err(ValidationError::StackUnderflow { expected: None });
         }
 
         self.stack
             .pop()
-            .ok_or(ValidationError::Msg("stack underflow".into()))
+            .ok_or(ValidationError::StackUnderflow { expected: None })
     }
 
     pub(crate) fn len(&self) -> usize {
@@ -1,6 +1,7 @@
 pub mod error;
 pub(crate) mod instructions;
 pub(crate) mod ir;
+pub mod limits;
 #[allow(clippy::module_inception)]
 pub mod module_parser;
 pub(crate) mod parsable;
@@ -9,17 +10,19 @@ pub(crate) mod wasm_stream_reader;
 pub(crate) type ParseResult = Result<(), ParserError>;
 
 pub use crate::module_parser::ModuleParser;
-pub use error::{ParserError, ValidationError};
+pub use error::{IndexSpace, ParserError, ResourceLimit, ValidationError};
 pub use ir::context::Context;
 pub use ir::function_builder::FunctionBuilderInterface;
+pub use limits::ParserLimits;
 
 use ir::FunctionParser;
-use module::objects::function::FunctionUnparsed;
-use module::{objects::module::FunctionLoaderInterface, Module, ModuleError, ModuleMetadata};
+use module::objects::function::{FunctionSource, FunctionUnparsed};
+use module::{
+    instructions::FunctionIR, objects::module::FunctionLoaderInterface, Module, ModuleError,
+    ModuleMetadata,
+};
 use resource_buffer::{ResourceBuffer, SourceFormat};
-#[cfg(debug_assertions)]
-use std::io::Write;
-use std::path::Path;
+use std::{num::NonZeroUsize, path::Path};
 use wasm_stream_reader::WasmBinaryReader;
 use wasm_types::FuncIdx;
 
@@ -42,7 +45,44 @@ impl Parser {
         Self::parse(buffer)
     }
 
+    /// Fully validate a module including all function bodies, without keeping IR or backend artifacts.
+    pub fn validate(buffer: ResourceBuffer) -> Result<(), ParserError> {
+        if buffer.kind() == SourceFormat::Cwasm {
+            return Err(ParserError::Msg(
+                "precompiled cwasm modules can't be validated".into(),
+            ));
+        }
+        let module = Self::parse(buffer)?;
+        let functions: Vec<FuncIdx> = (0..module.meta.functions.len() as FuncIdx)
+            .filter(|func_idx| {
+                matches!(
+                    module.meta.functions[*func_idx as usize].source,
+                    FunctionSource::Wasm(_)
+                )
+            })
+            .collect();
+        let loader = FunctionLoader::default();
+        FunctionParser::parse_functions(
+            &module.source,
+            &module.meta,
+            &functions,
+            loader.threads(),
+            loader.verifies_ir(),
+            &loader.limits,
+        )?;
+        Ok(())
+    }
+
     pub fn parse(buffer: ResourceBuffer) -> Result<Module, ParserError> {
+        Self::parse_with_limits(buffer, ParserLimits::default())
+    }
+
+    /// Parse the module, failing with [`ParserError::LimitExceeded`] if it requests more than `limits` allow.
+    /// Function bodies are checked when they are parsed, see [`FunctionLoader::with_limits`].
+    pub fn parse_with_limits(
+        buffer: ResourceBuffer,
+        limits: ParserLimits,
+    ) -> Result<Module, ParserError> {
         let mut module = Module {
             meta: ModuleMetadata::default(),
             source: buffer,
@@ -52,17 +92,19 @@ impl Parser {
             module: &mut module.meta,
             is_complete: false,
             next_empty_function: 0,
+            limits,
         };
         let input = module.source.get();
+        limits.check(ResourceLimit::ModuleSize, input.len() as u64)?;
         let mut reader = WasmBinaryReader::new(&input);
         match instance.parse_module(&mut reader) {
+            Err(e @ ParserError::ValidationError(ValidationError::Located { .. })) => Err(e),
             Err(e) => Err(ParserError::PositionalError(Box::new(e), reader.pos)),
             _ => {
+                // write parsed module to file as string, see `wasm_rt inspect` for release builds
                 #[cfg(debug_assertions)]
-                {
-                    // write parsed module to file as string
-                    let mut f = std::fs::File::create("debug_output.parsed").unwrap();
-                    f.write_all(module.meta.to_string().as_bytes()).unwrap();
+                if let Err(e) = std::fs::write("debug_output.parsed", module.meta.to_string()) {
+                    log::debug!("Failed to write debug_output.parsed: {}", e);
                 }
                 Ok(module)
             }
@@ -70,17 +112,92 @@ impl Parser {
     }
 }
 
-#[derive(Debug, Default)]
-pub struct FunctionLoader;
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FunctionLoader {
+    /// worker threads parsing function bodies, defaults to the available parallelism
+    threads: Option<NonZeroUsize>,
+    /// verify the IR of parsed functions, which debug builds always do
+    verify_ir: bool,
+    /// limits for the locals, size and nesting depth of function bodies
+    limits: ParserLimits,
+}
 
 impl FunctionLoader {
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Parse function bodies on `threads` worker threads. The resulting IR is the same for any thread count.
+    pub fn with_threads(mut self, threads: NonZeroUsize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Check the IR of every parsed function with [`module::verifier`].
+    pub fn with_ir_verification(mut self, verify_ir: bool) -> Self {
+        self.verify_ir = verify_ir;
+        self
+    }
+
+    /// Reject function bodies exceeding `limits`, the module sections are checked by [`Parser::parse_with_limits`].
+    pub fn with_limits(mut self, limits: ParserLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn verifies_ir(&self) -> bool {
+        self.verify_ir || cfg!(debug_assertions)
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+            .or_else(|| std::thread::available_parallelism().ok())
+            .map_or(1, NonZeroUsize::get)
+    }
+
     fn load_wasm_functions_ir(&self, module: &Module) -> Result<(), ParserError> {
-        log::debug!("Loading functions using `parser` to ir");
-        FunctionParser::parse_all_functions(module)
+        log::debug!(
+            "Loading functions using `parser` to ir on {} threads",
+            self.threads()
+        );
+        FunctionParser::parse_all_functions(
+            module,
+            self.threads(),
+            self.verifies_ir(),
+            &self.limits,
+        )
+    }
+
+    /// Parses the body of a single function to IR, e.g. to compile functions lazily on their first call.
+    pub fn parse_function_ir(
+        &self,
+        module: &Module,
+        func_idx: FuncIdx,
+    ) -> Result<FunctionIR, ParserError> {
+        let mut ir = self.parse_functions_ir(module, &[func_idx])?;
+        Ok(ir.remove(0))
+    }
+
+    /// Parses the bodies of `functions` to IR on the worker threads and returns it in the same order, without
+    /// storing it in the module's artifacts.
+    pub fn parse_functions_ir(
+        &self,
+        module: &Module,
+        functions: &[FuncIdx],
+    ) -> Result<Vec<FunctionIR>, ParserError> {
+        if module.source.kind() == SourceFormat::Cwasm {
+            return Err(ParserError::Msg(
+                "precompiled cwasm modules contain no function bodies".into(),
+            ));
+        }
+        FunctionParser::parse_functions(
+            &module.source,
+            &module.meta,
+            functions,
+            self.threads(),
+            self.verifies_ir(),
+            &self.limits,
+        )
     }
 
     pub fn parse_single_function(
@@ -96,6 +213,7 @@ impl FunctionLoader {
             function_idx,
             function_unparsed,
             module,
+            &self.limits,
             builder,
         )
     }
@@ -104,7 +222,7 @@ impl FunctionLoader {
 impl FunctionLoaderInterface for FunctionLoader {
     fn parse_all_functions(&self, module: &Module) -> Result<(), ModuleError> {
         match module.source.kind() {
-            SourceFormat::Wasm => self.load_wasm_functions_ir(module),
+            SourceFormat::Wasm | SourceFormat::Wat => self.load_wasm_functions_ir(module),
             SourceFormat::Cwasm => {
                 return Err(ModuleError::Msg(
                     "AOT execution is only supported via the llvm function parser.".to_string(),
@@ -0,0 +1,216 @@
+use crate::{error::ResourceLimit, ParseResult, ParserError};
+
+/// Upper bounds for the resources a module may request while it is parsed.
+///
+/// Counts and sizes declared by the module are checked before anything is allocated for them, so that
+/// untrusted modules can't make the parser allocate unbounded memory or recurse arbitrarily deep. The
+/// defaults are generous enough for real world modules and roughly follow the limits of browser engines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserLimits {
+    /// size of the module binary in bytes
+    pub max_module_size: usize,
+    /// types defined in the type section, counting each type of a recursion group
+    pub max_types: u32,
+    /// imported and defined functions
+    pub max_functions: u32,
+    /// locals of a function, including its parameters
+    pub max_locals: u32,
+    /// size of a function body in bytes
+    pub max_function_size: u32,
+    /// nested blocks, loops, ifs and try_tables within a function body. They are parsed recursively on worker
+    /// threads with a 64 MiB stack, which fits a few thousand levels even in debug builds.
+    pub max_nesting_depth: u32,
+    /// initial size of a table in elements
+    pub max_table_size: u64,
+    /// entries of an element segment
+    pub max_element_segment_size: u32,
+    /// size of a data segment in bytes
+    pub max_data_segment_size: u32,
+}
+
+impl Default for ParserLimits {
+    fn default() -> Self {
+        Self {
+            max_module_size: 1 << 30,
+            max_types: 1_000_000,
+            max_functions: 1_000_000,
+            max_locals: 50_000,
+            max_function_size: 7_654_321,
+            max_nesting_depth: 1_000,
+            max_table_size: 10_000_000,
+            max_element_segment_size: 10_000_000,
+            max_data_segment_size: 1 << 30,
+        }
+    }
+}
+
+impl ParserLimits {
+    /// Limits that only reject what the binary format or the address space can't represent anyway.
+    pub fn unlimited() -> Self {
+        Self {
+            max_module_size: usize::MAX,
+            max_types: u32::MAX,
+            max_functions: u32::MAX,
+            max_locals: u32::MAX,
+            max_function_size: u32::MAX,
+            max_nesting_depth: u32::MAX,
+            max_table_size: u64::MAX,
+            max_element_segment_size: u32::MAX,
+            max_data_segment_size: u32::MAX,
+        }
+    }
+
+    fn max(&self, limit: ResourceLimit) -> u64 {
+        match limit {
+            ResourceLimit::ModuleSize => self.max_module_size as u64,
+            ResourceLimit::Types => self.max_types.into(),
+            ResourceLimit::Functions => self.max_functions.into(),
+            ResourceLimit::Locals => self.max_locals.into(),
+            ResourceLimit::FunctionSize => self.max_function_size.into(),
+            ResourceLimit::NestingDepth => self.max_nesting_depth.into(),
+            ResourceLimit::TableSize => self.max_table_size,
+            ResourceLimit::ElementSegmentSize => self.max_element_segment_size.into(),
+            ResourceLimit::DataSegmentSize => self.max_data_segment_size.into(),
+        }
+    }
+
+    /// Fails with [`ParserError::LimitExceeded`] if `actual` is larger than the maximum of `limit`.
+    pub(crate) fn check(&self, limit: ResourceLimit, actual: u64) -> ParseResult {
+        let max = self.max(limit);
+        if actual > max {
+            return Err(ParserError::LimitExceeded { limit, actual, max });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{FunctionLoader, Parser};
+    use resource_buffer::ResourceBuffer;
+
+    pub(crate) fn leb128(mut n: u64) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        loop {
+            let byte = (n & 0x7f) as u8;
+            n >>= 7;
+            if n == 0 {
+                bytes.push(byte);
+                return bytes;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+
+    pub(crate) fn section(id: u8, count: u64, content: &[u8]) -> Vec<u8> {
+        let mut payload = leb128(count);
+        payload.extend_from_slice(content);
+        let mut bytes = vec![id];
+        bytes.extend(leb128(payload.len() as u64));
+        bytes.extend(payload);
+        bytes
+    }
+
+    /// Module with a single `[] -> []` function
+    fn module_with_body(locals: &[(u64, u8)], code: &[u8]) -> Vec<u8> {
+        let mut body = leb128(locals.len() as u64);
+        for (count, val_type) in locals {
+            body.extend(leb128(*count));
+            body.push(*val_type);
+        }
+        body.extend_from_slice(code);
+        body.push(0x0b);
+        let mut code_section = leb128(body.len() as u64);
+        code_section.extend(body);
+
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend(section(1, 1, &[0x60, 0x00, 0x00]));
+        bytes.extend(section(3, 1, &[0x00]));
+        bytes.extend(section(10, 1, &code_section));
+        bytes
+    }
+
+    fn parse_body(bytes: Vec<u8>, limits: ParserLimits) -> Result<(), ParserError> {
+        let module = Parser::parse_with_limits(ResourceBuffer::from_wasm_buf(bytes), limits)?;
+        FunctionLoader::default()
+            .with_limits(limits)
+            .parse_function_ir(&module, 0)
+            .map(|_| ())
+    }
+
+    fn exceeded(result: Result<(), ParserError>) -> Option<(ResourceLimit, u64, u64)> {
+        match result {
+            Err(ParserError::LimitExceeded { limit, actual, max }) => Some((limit, actual, max)),
+            Err(ParserError::PositionalError(e, _)) => exceeded(Err(*e)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_locals() {
+        let limits = ParserLimits {
+            max_locals: 100,
+            ..Default::default()
+        };
+        assert!(parse_body(module_with_body(&[(60, 0x7f), (40, 0x7e)], &[]), limits).is_ok());
+        let huge = module_with_body(&[(60, 0x7f), (u32::MAX as u64, 0x7e)], &[]);
+        assert_eq!(
+            exceeded(parse_body(huge, limits)),
+            Some((ResourceLimit::Locals, 60 + u32::MAX as u64, 100))
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth() {
+        let limits = ParserLimits {
+            max_nesting_depth: 10,
+            ..Default::default()
+        };
+        let nested = |depth: usize| {
+            let mut code = [0x02, 0x40].repeat(depth);
+            code.extend(vec![0x0b; depth]);
+            module_with_body(&[], &code)
+        };
+        assert!(parse_body(nested(10), limits).is_ok());
+        assert_eq!(
+            exceeded(parse_body(nested(11), limits)),
+            Some((ResourceLimit::NestingDepth, 11, 10))
+        );
+        // blocks following each other and unreachable code don't recurse
+        let sequential = [0x02, 0x40, 0x0b].repeat(100_000);
+        assert!(parse_body(module_with_body(&[], &sequential), limits).is_ok());
+        let unreachable = [0x0c, 0x00].repeat(100_000);
+        assert!(parse_body(module_with_body(&[], &unreachable), limits).is_ok());
+    }
+
+    #[test]
+    fn test_module_sections() {
+        let limits = ParserLimits {
+            max_module_size: 64,
+            max_function_size: 4,
+            max_data_segment_size: 8,
+            ..Default::default()
+        };
+        assert!(parse_body(module_with_body(&[], &[0x01]), limits).is_ok());
+        assert_eq!(
+            exceeded(parse_body(module_with_body(&[], &[0x01; 4]), limits)),
+            Some((ResourceLimit::FunctionSize, 6, 4))
+        );
+        let large = module_with_body(&[], &[0x01; 64]);
+        let size = large.len() as u64;
+        assert_eq!(
+            exceeded(parse_body(large, limits)),
+            Some((ResourceLimit::ModuleSize, size, 64))
+        );
+
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend(section(11, 1, &[0x01, 0x09, 0x00]));
+        assert_eq!(
+            exceeded(
+                Parser::parse_with_limits(ResourceBuffer::from_wasm_buf(bytes), limits).map(|_| ())
+            ),
+            Some((ResourceLimit::DataSegmentSize, 9, 8))
+        );
+    }
+}
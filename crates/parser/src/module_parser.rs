@@ -1,22 +1,31 @@
-use super::parsable::{Parse, ParseWithContext};
+use super::parsable::{parse_constant_expression, parse_element, Parse, ParseWithContext};
 use super::ParseResult;
-use super::{error::ParserError, wasm_stream_reader::WasmBinaryReader};
+use super::{
+    error::{ParserError, ResourceLimit},
+    limits::ParserLimits,
+    wasm_stream_reader::WasmBinaryReader,
+};
 use module::objects::data::{Data, DataMode};
 use module::objects::function::{FunctionSource, FunctionUnparsed};
 use module::objects::{
+    custom_section::CustomSection,
     element::Element,
     export::{Export, WasmExports},
-    expression::ConstantExpression,
     function::{Function, FunctionImport},
     global::Global,
     import::Import,
     memory::Memory,
+    names::WasmNames,
     table::Table,
+    tag::Tag,
     value::ConstantValue,
 };
 use module::ModuleMetadata;
-use std::vec;
-use wasm_types::{FuncIdx, FuncType, ImportDesc, MemIdx, Name, Section, TypeIdx};
+use std::collections::HashMap;
+use wasm_types::{
+    CompositeType, FuncIdx, HeapType, ImportDesc, MemIdx, Name, Section, StorageType, SubType,
+    TableType, TypeIdx, ValType,
+};
 
 const WASM_MODULE_PREAMBLE: &[u8] = b"\0asm";
 const WASM_MODULE_VERSION: u32 = 1;
@@ -25,6 +34,7 @@ pub struct ModuleParser<'a> {
     pub(crate) module: &'a mut ModuleMetadata,
     pub(crate) is_complete: bool,
     pub(crate) next_empty_function: FuncIdx,
+    pub(crate) limits: ParserLimits,
 }
 
 impl<'a> ModuleParser<'a> {
@@ -69,6 +79,7 @@ impl<'a> ModuleParser<'a> {
                 Section::Function => self.parse_function_section(i)?,
                 Section::Table => self.parse_table_section(i)?,
                 Section::Memory => self.parse_memory_section(i)?,
+                Section::Tag => self.parse_tag_section(i)?,
                 Section::Global => self.parse_global_section(i)?,
                 Section::Export => self.parse_export_section(i)?,
                 Section::Start => self.parse_start_section(i)?,
@@ -95,13 +106,58 @@ impl<'a> ModuleParser<'a> {
 
     fn parse_custom_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
         let section_size = i.read_leb128::<u32>()?;
-        let reader_pos_safe = i.pos;
-        let name = Name::parse(i)?;
-        let name_byte_len = i.pos - reader_pos_safe;
+        let section_offset = i.pos;
+        let mut section = i.sub_reader(section_size as usize)?;
+        let name = Name::parse(&mut section)?;
+
+        self.module.custom_sections.push(CustomSection {
+            name: name.clone(),
+            offset: section_offset + section.pos,
+            size: section_size as usize - section.pos,
+        });
+
+        if name != "name" {
+            log::debug!("Skipping parsing of custom section \"{}\"", name);
+            return Ok(());
+        }
 
-        log::warn!("Skipping parsing of custom section \"{}\"", name);
-        for _ in name_byte_len..(section_size as usize) {
-            let _ = i.read_byte()?;
+        // the name section is only used for diagnostics, a malformed one must not reject the module
+        if let Err(e) = self.parse_name_section(&mut section) {
+            log::warn!("Ignoring malformed name section: {}", e);
+            self.module.names = WasmNames::default();
+        }
+        Ok(())
+    }
+
+    /// https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+    fn parse_name_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
+        fn parse_name_map(i: &mut WasmBinaryReader) -> Result<HashMap<u32, String>, ParserError> {
+            let len = i.read_leb128::<u32>()?;
+            (0..len)
+                .map(|_| Ok((i.read_leb128::<u32>()?, Name::parse(i)?)))
+                .collect()
+        }
+
+        while !i.eof()? {
+            let id = i.read_byte()?;
+            let size = i.read_leb128::<u32>()?;
+            let mut subsection = i.sub_reader(size as usize)?;
+            let names = &mut self.module.names;
+            match id {
+                0 => names.module = Some(Name::parse(&mut subsection)?),
+                1 => names.functions = parse_name_map(&mut subsection)?,
+                2 => {
+                    let len = subsection.read_leb128::<u32>()?;
+                    for _ in 0..len {
+                        let func_idx = subsection.read_leb128::<FuncIdx>()?;
+                        let locals = parse_name_map(&mut subsection)?;
+                        names.locals.insert(func_idx, locals);
+                    }
+                }
+                7 => names.globals = parse_name_map(&mut subsection)?,
+                // labels, types, tables, memories, etc. are not used anywhere yet
+                _ => log::debug!("Skipping name subsection {}", id),
+            }
         }
         Ok(())
     }
@@ -109,16 +165,91 @@ impl<'a> ModuleParser<'a> {
     fn parse_type_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
         // drop section size as we currently don't need it
         let _ = i.read_leb128::<u32>()?;
-        let num_types = i.read_leb128::<u32>()?;
-        let mut parsed_function_types = (0..num_types)
-            .map(|_| FuncType::parse(i))
-            .collect::<Result<Vec<FuncType>, ParserError>>()?;
-        self.module
-            .function_types
-            .append(&mut parsed_function_types);
+        let num_rec_groups = i.read_leb128::<u32>()?;
+        for _ in 0..num_rec_groups {
+            // recursion groups are flattened, types of a group may refer to each other
+            let num_types = match i.peek_byte()? {
+                0x4E => {
+                    i.advance(1);
+                    i.read_leb128::<u32>()?
+                }
+                _ => 1,
+            };
+            self.limits.check(
+                ResourceLimit::Types,
+                self.module.types.len() as u64 + num_types as u64,
+            )?;
+            for _ in 0..num_types {
+                let sub_type = SubType::parse(i)?;
+                self.module
+                    .function_types
+                    .push(sub_type.as_func().unwrap_or_default());
+                self.module.types.push(sub_type);
+            }
+        }
+        self.validate_type_definitions()
+    }
+
+    /// Subtypes must match their declared supertype, which has to be defined before and not final.
+    fn validate_type_definitions(&self) -> ParseResult {
+        let types = &self.module.types;
+        for (type_idx, sub_type) in types.iter().enumerate() {
+            let fields = match &sub_type.composite_type {
+                CompositeType::Func(_) => &[][..],
+                CompositeType::Struct(fields) => fields,
+                CompositeType::Array(field) => std::slice::from_ref(field),
+            };
+            for field in fields {
+                match field.storage_type {
+                    StorageType::Val(ValType::VecType) => {
+                        return Err(ParserError::Msg(
+                            "vector fields of structs and arrays are not supported".into(),
+                        ))
+                    }
+                    StorageType::Val(ValType::Reference(ref_type)) => {
+                        self.validate_heap_type(ref_type.heap_type())?
+                    }
+                    _ => {}
+                }
+            }
+            if let CompositeType::Func(func_type) = sub_type.composite_type {
+                for val_type in func_type.params_iter().chain(func_type.results_iter()) {
+                    if let ValType::Reference(ref_type) = val_type {
+                        self.validate_heap_type(ref_type.heap_type())?
+                    }
+                }
+            }
+            let Some(supertype_idx) = sub_type.supertype else {
+                continue;
+            };
+            if supertype_idx as usize >= type_idx {
+                return Err(ParserError::Msg("supertype index out of bounds".into()));
+            }
+            let supertype = &types[supertype_idx as usize];
+            if supertype.is_final {
+                return Err(ParserError::Msg("sub type of final type".into()));
+            }
+            if !sub_type
+                .composite_type
+                .matches(types, &supertype.composite_type, types)
+            {
+                return Err(ParserError::Msg(
+                    "sub type does not match its supertype".into(),
+                ));
+            }
+        }
         Ok(())
     }
 
+    fn validate_heap_type(&self, heap_type: HeapType) -> ParseResult {
+        match heap_type {
+            HeapType::Concrete(type_idx) if type_idx as usize >= self.module.types.len() => {
+                Err(ParserError::Msg("unknown type".into()))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn parse_import_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
         let _ = i.read_leb128::<u32>()?;
         let num_imports = i.read_leb128::<u32>()?;
@@ -126,22 +257,30 @@ impl<'a> ModuleParser<'a> {
             let mut import = Import::parse(i)?;
             match &mut import.desc {
                 ImportDesc::Func(type_idx) => {
-                    if *type_idx as usize >= self.module.function_types.len() {
+                    if self.module.func_type(*type_idx).is_none() {
                         return Err(ParserError::Msg("function type index out of bounds".into()));
                     }
+                    self.limits.check(
+                        ResourceLimit::Functions,
+                        self.module.functions.len() as u64 + 1,
+                    )?;
                     self.module.functions.push(Function {
                         type_idx: *type_idx,
                         source: FunctionSource::Import(FunctionImport { import_idx }),
                     });
                 }
-                ImportDesc::Table(r#type) => self.module.tables.push(Table {
+                ImportDesc::Table(r#type) => {
+                    self.check_table_size(r#type)?;
+                    self.module.tables.push(Table {
+                        r#type: *r#type,
+                        import: true,
+                        init: None,
+                    })
+                }
+                ImportDesc::Mem(r#type) => self.module.memories.push(Memory {
                     r#type: *r#type,
                     import: true,
                 }),
-                ImportDesc::Mem(limits) => self.module.memories.push(Memory {
-                    limits: *limits,
-                    import: true,
-                }),
                 ImportDesc::Global((r#type, idx)) => {
                     *idx = self.module.globals.len() as u32;
                     self.module.globals.push(Global {
@@ -150,6 +289,13 @@ impl<'a> ModuleParser<'a> {
                         init: ConstantValue::FuncPtr(import_idx),
                     })
                 }
+                ImportDesc::Tag(type_idx) => {
+                    self.validate_tag_type(*type_idx)?;
+                    self.module.tags.push(Tag {
+                        type_idx: *type_idx,
+                        import: true,
+                    })
+                }
             }
             self.module.imports.push(import);
         }
@@ -162,11 +308,17 @@ impl<'a> ModuleParser<'a> {
         if num_functions == 0 {
             return Ok(());
         }
+        self.limits.check(
+            ResourceLimit::Functions,
+            self.module.functions.len() as u64 + num_functions as u64,
+        )?;
         let mut parsed_functions = (0..num_functions)
             .map(|_| TypeIdx::parse(i).map(Function::placeholder))
             .collect::<Result<Vec<Function>, ParserError>>()?;
-        let max_func_type: u32 = parsed_functions.iter().map(|f| f.type_idx).max().unwrap();
-        if max_func_type as usize >= self.module.function_types.len() {
+        if parsed_functions
+            .iter()
+            .any(|f| self.module.func_type(f.type_idx).is_none())
+        {
             return Err(ParserError::Msg("function type index out of bounds".into()));
         }
         self.module.functions.append(&mut parsed_functions);
@@ -177,34 +329,61 @@ impl<'a> ModuleParser<'a> {
         let _ = i.read_leb128::<u32>()?;
         let num_tables = i.read_leb128::<u32>()?;
         let mut parsed_tables = (0..num_tables)
-            .map(|_| Table::parse(i))
+            .map(|_| {
+                let table = Table::parse_with_context(i, &self.module)?;
+                self.check_table_size(&table.r#type)?;
+                Ok(table)
+            })
             .collect::<Result<Vec<Table>, ParserError>>()?;
         self.module.tables.append(&mut parsed_tables);
         Ok(())
     }
 
+    /// Tables are allocated with their minimum size when the module is instantiated.
+    fn check_table_size(&self, r#type: &TableType) -> ParseResult {
+        self.limits.check(ResourceLimit::TableSize, r#type.lim.min)
+    }
+
     fn parse_memory_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
         let _ = i.read_leb128::<u32>()?;
         let num_memories = i.read_leb128::<u32>()?;
-        if num_memories == 0 {
-            return Ok(());
-        }
-        if num_memories > 1 || !self.module.memories.is_empty() {
-            return Err(ParserError::Msg(
-                "multiple memories are not supported".into(),
-            ));
+        let mut parsed_memories = (0..num_memories)
+            .map(|_| Memory::parse(i))
+            .collect::<Result<Vec<Memory>, ParserError>>()?;
+        self.module.memories.append(&mut parsed_memories);
+        Ok(())
+    }
+
+    fn parse_tag_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
+        let _ = i.read_leb128::<u32>()?;
+        let num_tags = i.read_leb128::<u32>()?;
+        for _ in 0..num_tags {
+            let tag = Tag::parse(i)?;
+            self.validate_tag_type(tag.type_idx)?;
+            self.module.tags.push(tag);
         }
-        self.module.memories = vec![Memory::parse(i)?];
         Ok(())
     }
 
+    /// Exception tags are typed by function types without results.
+    fn validate_tag_type(&self, type_idx: TypeIdx) -> ParseResult {
+        match self.module.func_type(type_idx) {
+            None => Err(ParserError::Msg("tag type index out of bounds".into())),
+            Some(func_type) if func_type.num_results() != 0 => {
+                Err(ParserError::Msg("non-empty tag result type".into()))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
     fn parse_global_section(&mut self, i: &mut WasmBinaryReader) -> ParseResult {
         let _ = i.read_leb128::<u32>()?;
         let num_globals = i.read_leb128::<u32>()?;
-        let mut parsed_globals = (0..num_globals)
-            .map(|_| Global::parse_with_context(i, self.module))
-            .collect::<Result<Vec<Global>, ParserError>>()?;
-        self.module.globals.append(&mut parsed_globals);
+        // initializers may refer to the globals before them
+        for _ in 0..num_globals {
+            let global = Global::parse_with_context(i, self.module)?;
+            self.module.globals.push(global);
+        }
         Ok(())
     }
 
@@ -226,8 +405,19 @@ impl<'a> ModuleParser<'a> {
                     parsed_exports.add_function_export(e)
                 }
                 Export::Table(e) => parsed_exports.add_table_export(e),
-                Export::Mem(e) => parsed_exports.add_memory_export(e),
+                Export::Mem(e) => {
+                    if e.idx as usize >= self.module.memories.len() {
+                        return Err(ParserError::UnknownMemory(e.idx));
+                    }
+                    parsed_exports.add_memory_export(e)
+                }
                 Export::Global(e) => parsed_exports.add_global_export(e),
+                Export::Tag(e) => {
+                    if e.idx as usize >= self.module.tags.len() {
+                        return Err(ParserError::Msg("unknown tag".into()));
+                    }
+                    parsed_exports.add_tag_export(e)
+                }
             }
         }
         self.module.exports.append(parsed_exports);
@@ -250,7 +440,7 @@ impl<'a> ModuleParser<'a> {
         let _ = i.read_leb128::<u32>()?;
         let num_elements = i.read_leb128::<u32>()?;
         let mut parsed_elements = (0..num_elements)
-            .map(|_| Element::parse_with_context(i, &self.module))
+            .map(|_| parse_element(i, self.module, &self.limits))
             .collect::<Result<Vec<Element>, ParserError>>()?;
         self.module.elements.append(&mut parsed_elements);
         Ok(())
@@ -292,6 +482,8 @@ impl<'a> ModuleParser<'a> {
             .collect::<Vec<_>>();
         for func_idx in functions_to_parse {
             let function_size = i.read_leb128::<u32>()?;
+            self.limits
+                .check(ResourceLimit::FunctionSize, function_size as u64)?;
             self.module.functions[func_idx as usize].source =
                 FunctionSource::Wasm(FunctionUnparsed {
                     offset: i.pos,
@@ -326,22 +518,22 @@ impl<'a> ModuleParser<'a> {
                 0 => {
                     data.mode = DataMode::Active {
                         memory: 0,
-                        offset: ConstantExpression::parse_with_context(i, &self.module)?
+                        offset: parse_constant_expression(i, self.module, Section::Data, None)?
                             .eval(&self.module)?,
                     };
-                    data.init = Vec::parse(i)?;
+                    data.init = self.parse_data_init(i)?;
                 }
                 1 => {
                     data.mode = DataMode::Passive;
-                    data.init = Vec::parse(i)?;
+                    data.init = self.parse_data_init(i)?;
                 }
                 2 => {
                     data.mode = DataMode::Active {
                         memory: MemIdx::parse(i)?,
-                        offset: ConstantExpression::parse_with_context(i, &self.module)?
+                        offset: parse_constant_expression(i, self.module, Section::Data, None)?
                             .eval(&self.module)?,
                     };
-                    data.init = Vec::parse(i)?;
+                    data.init = self.parse_data_init(i)?;
                 }
                 _ => {
                     return Err(ParserError::Msg(
@@ -349,6 +541,11 @@ impl<'a> ModuleParser<'a> {
                     ))
                 }
             }
+            if let DataMode::Active { memory, .. } = &data.mode {
+                if *memory as usize >= self.module.memories.len() {
+                    return Err(ParserError::UnknownMemory(*memory));
+                }
+            }
             self.module.datas.push(data);
         }
         if i.pos != expected_reader_pos_after_section {
@@ -358,4 +555,118 @@ impl<'a> ModuleParser<'a> {
         }
         Ok(())
     }
+
+    fn parse_data_init(&self, i: &mut WasmBinaryReader) -> Result<Vec<u8>, ParserError> {
+        let len = i.read_leb128::<u32>()?;
+        self.limits
+            .check(ResourceLimit::DataSegmentSize, len as u64)?;
+        (0..len).map(|_| i.read_byte()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::limits::tests::section;
+    use crate::Parser;
+    use module::{
+        objects::function::{FunctionSource, FunctionUnparsed},
+        Module, ModuleWriter,
+    };
+    use resource_buffer::ResourceBuffer;
+
+    fn custom_section(name: &str, payload: &[u8]) -> Vec<u8> {
+        let mut content = name.as_bytes().to_vec();
+        content.extend_from_slice(payload);
+        section(0, name.len() as u64, &content)
+    }
+
+    #[test]
+    fn test_custom_sections() {
+        let mut bytes = b"\0asm\x01\0\0\0".to_vec();
+        bytes.extend(custom_section("build_id", &[1, 2, 3]));
+        bytes.extend(section(1, 1, &[0x60, 0x00, 0x00]));
+        bytes.extend(custom_section("producers", &[]));
+        bytes.extend(custom_section("build_id", &[4]));
+        let mut module = Parser::parse(ResourceBuffer::from_wasm_buf(bytes)).unwrap();
+
+        assert_eq!(
+            module.custom_sections().collect::<Vec<_>>(),
+            [
+                ("build_id", &[1, 2, 3][..]),
+                ("producers", &[]),
+                ("build_id", &[4])
+            ]
+        );
+        assert_eq!(module.custom_section("build_id"), Some(&[1, 2, 3][..]));
+        assert_eq!(module.custom_section("missing"), None);
+
+        // ranges outside of the source are skipped instead of panicking
+        let section = &mut module.meta.custom_sections[0];
+        section.offset = usize::MAX;
+        assert_eq!(module.custom_section("build_id"), None);
+        assert_eq!(module.custom_sections().count(), 2);
+    }
+
+    fn function_body(module: &Module, func_idx: usize) -> &[u8] {
+        let FunctionSource::Wasm(FunctionUnparsed { offset, size }) =
+            module.meta.functions[func_idx].source
+        else {
+            panic!("function {func_idx} is imported");
+        };
+        &module.source.get()[offset..offset + size]
+    }
+
+    #[test]
+    fn test_writer_round_trip() {
+        let text = r#"
+            (module
+              (type $s (struct (field i32) (field (ref null $s))))
+              (type $a (array (mut i8)))
+              (global $base i32 (i32.const 10))
+              (global $extended i32 (i32.add (global.get $base) (i32.mul (i32.const 2) (i32.const 3))))
+              (global $null (ref null $s) (ref.null $s))
+              (global $struct (ref null $s) (struct.new $s (global.get $extended) (ref.null $s)))
+              (global $array (ref $a) (array.new_fixed $a 2 (i32.const 1) (i32.const 2)))
+              (func $f (export "f") (result i32) (i32.const 1))
+              (func $g (result i32) (i32.const 2)))
+        "#;
+        let mut bytes = ResourceBuffer::from_wat_buf(text.as_bytes())
+            .unwrap()
+            .get()
+            .to_vec();
+        bytes.extend(custom_section("build_id", &[1, 2, 3]));
+        let module = Parser::parse(ResourceBuffer::from_wasm_buf(bytes)).unwrap();
+
+        // `(i32.const 42)` without locals
+        let body = vec![0x00, 0x41, 0x2a, 0x0b];
+        let written = ModuleWriter::new(&module)
+            .with_function_body(1, body.clone())
+            .finish()
+            .unwrap();
+        let reparsed = Parser::parse(ResourceBuffer::from_wasm_buf(written)).unwrap();
+
+        assert_eq!(
+            format!("{:?}", reparsed.meta.types),
+            format!("{:?}", module.meta.types)
+        );
+        assert_eq!(
+            format!("{:?}", reparsed.meta.globals),
+            format!("{:?}", module.meta.globals)
+        );
+        assert_eq!(reparsed.meta.exports.find_function_idx("f"), Some(0));
+        assert_eq!(function_body(&reparsed, 0), function_body(&module, 0));
+        assert_eq!(function_body(&reparsed, 1), body);
+        assert_eq!(
+            reparsed.custom_sections().collect::<Vec<_>>(),
+            module.custom_sections().collect::<Vec<_>>()
+        );
+        assert_eq!(reparsed.custom_section("build_id"), Some(&[1, 2, 3][..]));
+
+        let written = ModuleWriter::new(&module)
+            .with_custom_sections(false)
+            .finish()
+            .unwrap();
+        let reparsed = Parser::parse(ResourceBuffer::from_wasm_buf(written)).unwrap();
+        assert_eq!(reparsed.custom_sections().count(), 0);
+    }
 }
@@ -1,30 +1,40 @@
 use crate::{
-    error::ParserError,
+    error::{ParserError, ResourceLimit, ValidationError},
     ir::{
         context::Context,
         function_builder::{FunctionBuilderInterface, FunctionIRBuilder},
         parse_basic_blocks::parse_basic_blocks,
     },
+    limits::ParserLimits,
     wasm_stream_reader::WasmBinaryReader,
 };
 use module::{
     objects::{
         element::{ElemMode, Element, ElementInit},
-        export::{Export, FuncExport, GlobalExport, MemoryExport, TableExport},
+        export::{Export, FuncExport, GlobalExport, MemoryExport, TableExport, TagExport},
         expression::{ConstantExpression, ConstantExpressionError},
         global::Global,
         import::Import,
         memory::{MemArg, Memory},
         table::Table,
+        tag::Tag,
         value::ConstantValue,
     },
     ModuleMetadata,
 };
 use wasm_types::{
-    BlockType, FuncIdx, FuncType, FuncTypeBuilder, GlobalIdx, GlobalType, ImportDesc, Limits,
-    MemIdx, MemType, Name, RefType, Section, TableIdx, TableType, TypeIdx, ValType,
+    BlockType, CompositeType, FieldType, FuncIdx, FuncType, FuncTypeBuilder, GlobalIdx, GlobalType,
+    HeapType, ImportDesc, IndexType, Limits, MemIdx, MemType, Name, RefType, Section, StorageType,
+    SubType, TableIdx, TableType, TagIdx, TypeIdx, ValType,
 };
 
+// maximum amount of wasm pages of 32-bit memories (4GiB)
+const MAX_PAGES_32: u64 = 2_u64.pow(16);
+// maximum amount of wasm pages of 64-bit memories (2^64 bytes)
+const MAX_PAGES_64: u64 = 2_u64.pow(48);
+// memarg alignment flag announcing a memory index
+const MEMARG_MEMORY_IDX_FLAG: u32 = 1 << 6;
+
 pub(crate) trait Parse {
     fn parse(i: &mut WasmBinaryReader) -> Result<Self, ParserError>
     where
@@ -51,6 +61,84 @@ impl Parse for FuncType {
     }
 }
 
+impl Parse for StorageType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<StorageType, ParserError> {
+        match i.peek_byte()? {
+            0x78 => {
+                i.advance(1);
+                Ok(StorageType::I8)
+            }
+            0x77 => {
+                i.advance(1);
+                Ok(StorageType::I16)
+            }
+            _ => Ok(StorageType::Val(ValType::parse(i)?)),
+        }
+    }
+}
+
+impl Parse for FieldType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<FieldType, ParserError> {
+        let storage_type = StorageType::parse(i)?;
+        let mutable = match i.read_byte()? {
+            0x00 => false,
+            0x01 => true,
+            _ => return Err(ParserError::Msg("invalid field mutability".into())),
+        };
+        Ok(FieldType {
+            storage_type,
+            mutable,
+        })
+    }
+}
+
+impl Parse for CompositeType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<CompositeType, ParserError> {
+        match i.peek_byte()? {
+            0x5E => {
+                i.advance(1);
+                Ok(CompositeType::Array(FieldType::parse(i)?))
+            }
+            0x5F => {
+                i.advance(1);
+                let fields = (0..i.read_leb128::<u32>()?)
+                    .map(|_| FieldType::parse(i))
+                    .collect::<Result<Vec<FieldType>, ParserError>>()?;
+                Ok(CompositeType::Struct(fields))
+            }
+            _ => Ok(CompositeType::Func(FuncType::parse(i)?)),
+        }
+    }
+}
+
+impl Parse for SubType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<SubType, ParserError> {
+        let is_final = match i.peek_byte()? {
+            0x50 => false,
+            0x4F => true,
+            // composite types without sub prefix are final and have no supertypes
+            _ => {
+                return Ok(SubType {
+                    is_final: true,
+                    supertype: None,
+                    composite_type: CompositeType::parse(i)?,
+                })
+            }
+        };
+        i.advance(1);
+        let supertype = match i.read_leb128::<u32>()? {
+            0 => None,
+            1 => Some(TypeIdx::parse(i)?),
+            _ => return Err(ParserError::Msg("multiple supertypes".into())),
+        };
+        Ok(SubType {
+            is_final,
+            supertype,
+            composite_type: CompositeType::parse(i)?,
+        })
+    }
+}
+
 impl Parse for ValType {
     fn parse(i: &mut WasmBinaryReader) -> Result<ValType, ParserError> {
         let prefix = i.read_byte()?;
@@ -62,10 +150,12 @@ impl Parse for ValType {
 
             0x7B => Ok(ValType::vec()),
 
-            0x70 => Ok(ValType::funcref()),
-            0x6F => Ok(ValType::externref()),
+            0x63 | 0x64 | 0x69..=0x74 => {
+                i.pos -= 1;
+                Ok(ValType::Reference(RefType::parse(i)?))
+            }
 
-            _ => Err(ParserError::Msg(format!("invalid value type prefix. Expected 0x7F, 0x7E, 0x7D, 0x7C, 0x7B, 0x70, or 0x6F, got 0x{} at position {:x}", prefix, i.pos))),
+            _ => Err(ParserError::Msg(format!("invalid value type prefix. Expected a number, vector or reference type, got 0x{} at position {:x}", prefix, i.pos))),
         }
     }
 }
@@ -91,37 +181,93 @@ impl Parse for u32 {
 
 impl Parse for Limits {
     fn parse(i: &mut WasmBinaryReader) -> Result<Limits, ParserError> {
-        match i.read_byte()? {
-            0x00 => Ok(Limits {
-                min: i.read_leb128()?,
-                max: None,
-            }),
-            0x01 => {
-                let min = i.read_leb128()?;
-                let max = i.read_leb128()?;
-                if max < min {
-                    return Err(ParserError::LimitsMinimumGreaterThanMaximum);
-                }
-                Ok(Limits {
-                    min,
-                    max: Some(max),
-                })
+        let flags = i.read_byte()?;
+        parse_limits(i, flags, IndexType::I32)
+    }
+}
+
+/// Parses the limits following the `flags` byte, `index_type` determines the width of their encoding.
+fn parse_limits(
+    i: &mut WasmBinaryReader,
+    flags: u8,
+    index_type: IndexType,
+) -> Result<Limits, ParserError> {
+    let mut read_limit = || match index_type {
+        IndexType::I32 => i.read_leb128::<u32>().map(u64::from),
+        IndexType::I64 => i.read_leb128::<u64>(),
+    };
+    match flags {
+        0x00 => Ok(Limits {
+            min: read_limit()?,
+            max: None,
+        }),
+        0x01 => {
+            let min = read_limit()?;
+            let max = read_limit()?;
+            if max < min {
+                return Err(ParserError::LimitsMinimumGreaterThanMaximum);
             }
-            _ => Err(ParserError::Msg("invalid limit type prefix".into())),
+            Ok(Limits {
+                min,
+                max: Some(max),
+            })
+        }
+        _ => Err(ParserError::Msg("invalid limit type prefix".into())),
+    }
+}
+
+impl Parse for MemType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<MemType, ParserError> {
+        // the threads proposal marks shared memories with bit 1 of the limits flags,
+        // the memory64 proposal marks 64-bit memories with bit 2
+        let flags = i.read_byte()?;
+        let shared = flags & 0x02 != 0;
+        let index_type = if flags & 0x04 != 0 {
+            IndexType::I64
+        } else {
+            IndexType::I32
+        };
+        let lim = parse_limits(i, flags & !0x06, index_type)?;
+        if shared && lim.max.is_none() {
+            return Err(ParserError::SharedMemoryWithoutMaximum);
         }
+        let max_pages = match index_type {
+            IndexType::I32 => MAX_PAGES_32,
+            IndexType::I64 => MAX_PAGES_64,
+        };
+        if lim.min > max_pages || lim.max.is_some_and(|max| max > max_pages) {
+            return Err(ParserError::MemorySizeTooLarge(max_pages));
+        }
+        Ok(MemType {
+            lim,
+            shared,
+            index_type,
+        })
     }
 }
 
 impl Parse for RefType {
     fn parse(i: &mut WasmBinaryReader) -> Result<RefType, ParserError> {
         match i.read_byte()? {
-            0x70 => Ok(RefType::FunctionReference),
-            0x6F => Ok(RefType::ExternReference),
+            0x64 => Ok(RefType::new(HeapType::parse(i)?, false)),
+            0x63 => Ok(RefType::new(HeapType::parse(i)?, true)),
+            // shorthands of nullable references are encoded like their abstract heap type (one byte s33)
+            byte @ 0x69..=0x74 => match HeapType::from_s33(byte as i64 - 0x80) {
+                Some(heap_type) => Ok(RefType::new(heap_type, true)),
+                None => Err(ParserError::Msg("invalid reference type prefix".into())),
+            },
             _ => Err(ParserError::Msg("invalid reference type prefix".into())),
         }
     }
 }
 
+impl Parse for HeapType {
+    fn parse(i: &mut WasmBinaryReader) -> Result<HeapType, ParserError> {
+        HeapType::from_s33(i.read_leb128::<i64>()?)
+            .ok_or_else(|| ParserError::Msg("invalid heap type".into()))
+    }
+}
+
 impl Parse for TableType {
     fn parse(i: &mut WasmBinaryReader) -> Result<TableType, ParserError> {
         let ref_type = RefType::parse(i)?;
@@ -148,6 +294,7 @@ impl Parse for ImportDesc {
             0x01 => Ok(ImportDesc::Table(TableType::parse(i)?)),
             0x02 => Ok(ImportDesc::Mem(MemType::parse(i)?)),
             0x03 => Ok(ImportDesc::Global((GlobalType::parse(i)?, u32::MAX))),
+            0x04 => Ok(ImportDesc::Tag(Tag::parse(i)?.type_idx)),
             _ => Err(ParserError::Msg("invalid import description prefix".into())),
         }
     }
@@ -162,11 +309,41 @@ impl Parse for Import {
     }
 }
 
-impl Parse for Table {
-    fn parse(i: &mut WasmBinaryReader) -> Result<Table, ParserError> {
+impl ParseWithContext for Table {
+    fn parse_with_context(
+        i: &mut WasmBinaryReader,
+        m: &ModuleMetadata,
+    ) -> Result<Table, ParserError> {
+        // tables with an explicit initializer are prefixed by 0x40 0x00
+        if i.peek_byte()? != 0x40 {
+            let r#type = TableType::parse(i)?;
+            if !r#type.ref_type.is_nullable() {
+                return Err(ParserError::Msg(
+                    "tables of non-nullable references require an initializer".into(),
+                ));
+            }
+            return Ok(Table {
+                r#type,
+                import: false,
+                init: None,
+            });
+        }
+        i.read_byte()?;
+        if i.read_byte()? != 0x00 {
+            return Err(ParserError::Msg("invalid table prefix".into()));
+        }
+        let r#type = TableType::parse(i)?;
+        let init = parse_constant_expression(
+            i,
+            m,
+            Section::Table,
+            Some(ValType::Reference(r#type.ref_type)),
+        )?
+        .eval_allocating(m)?;
         Ok(Table {
-            r#type: TableType::parse(i)?,
+            r#type,
             import: false,
+            init: Some(init),
         })
     }
 }
@@ -174,7 +351,20 @@ impl Parse for Table {
 impl Parse for Memory {
     fn parse(i: &mut WasmBinaryReader) -> Result<Memory, ParserError> {
         Ok(Memory {
-            limits: Limits::parse(i)?,
+            r#type: MemType::parse(i)?,
+            import: false,
+        })
+    }
+}
+
+impl Parse for Tag {
+    fn parse(i: &mut WasmBinaryReader) -> Result<Tag, ParserError> {
+        // the only tag attribute defined so far is "exception"
+        if i.read_byte()? != 0x00 {
+            return Err(ParserError::Msg("invalid tag attribute".into()));
+        }
+        Ok(Tag {
+            type_idx: TypeIdx::parse(i)?,
             import: false,
         })
     }
@@ -201,86 +391,85 @@ impl Parse for Export {
                 name,
                 idx: GlobalIdx::parse(i)?,
             })),
+            0x04 => Ok(Export::Tag(TagExport {
+                name,
+                idx: TagIdx::parse(i)?,
+            })),
             _ => Err(ParserError::Msg("invalid export description prefix".into())),
         }
     }
 }
 
-impl ParseWithContext for Element {
-    fn parse_with_context(
-        i: &mut WasmBinaryReader,
-        m: &ModuleMetadata,
-    ) -> Result<Element, ParserError> {
-        let code = i.read_leb128::<u32>()?;
-        if code > 7 {
-            return Err(ParserError::Msg("invalid element prefix code".into()));
-        }
+/// Parses an element segment, its number of entries is bounded by `limits`.
+pub(crate) fn parse_element(
+    i: &mut WasmBinaryReader,
+    m: &ModuleMetadata,
+    limits: &ParserLimits,
+) -> Result<Element, ParserError> {
+    let code = i.read_leb128::<u32>()?;
+    if code > 7 {
+        return Err(ParserError::Msg("invalid element prefix code".into()));
+    }
+
+    let table_idx = if code & 0b11 == 2 {
+        TableIdx::parse(i)?
+    } else {
+        0
+    };
 
-        let table_idx = if code & 0b11 == 2 {
-            TableIdx::parse(i)?
+    let mode = if code & 0b1 != 0 {
+        if code & 0b10 != 0 {
+            ElemMode::Declarative
         } else {
-            0
-        };
+            ElemMode::Passive
+        }
+    } else {
+        let constant_expr = parse_constant_expression(i, m, Section::Element, None)?;
+        ElemMode::Active {
+            table: table_idx,
+            offset: constant_expr.eval(m)?,
+        }
+    };
 
-        let mode = if code & 0b1 != 0 {
-            if code & 0b10 != 0 {
-                ElemMode::Declarative
-            } else {
-                ElemMode::Passive
-            }
+    if code & 0b100 != 0 {
+        let type_ = if code == 4 {
+            RefType::FunctionReference
         } else {
-            let constant_expr = ConstantExpression::parse_with_context(i, m)?;
-            ElemMode::Active {
-                table: table_idx,
-                offset: constant_expr.eval(m)?,
-            }
+            RefType::parse(i)?
         };
-
-        if code & 0b100 != 0 {
-            let type_ = if code == 4 {
-                RefType::FunctionReference
-            } else {
-                RefType::parse(i)?
-            };
-            let num_elems = i.read_leb128::<u32>()?;
-            let elems = (0..num_elems)
-                .map(|_| ConstantExpression::parse_with_context(i, m))
-                .flat_map(|r| {
-                    r.map(|e| e.eval(m).map_err(ParserError::from))
-                        .map_err(|_| ParserError::InvalidEncoding)
-                })
-                .collect::<Result<Vec<ConstantValue>, ParserError>>()?;
-            Ok(Element {
-                mode,
-                type_,
-                init: ElementInit::Final(elems),
+        let num_elems = i.read_leb128::<u32>()?;
+        limits.check(ResourceLimit::ElementSegmentSize, num_elems as u64)?;
+        let elems = (0..num_elems)
+            .map(|_| parse_constant_expression(i, m, Section::Element, None))
+            .flat_map(|r| {
+                r.map(|e| e.eval(m).map_err(ParserError::from))
+                    .map_err(|_| ParserError::InvalidEncoding)
             })
+            .collect::<Result<Vec<ConstantValue>, ParserError>>()?;
+        Ok(Element {
+            mode,
+            type_,
+            init: ElementInit::Final(elems),
+        })
+    } else {
+        let type_ = if code == 0 {
+            RefType::FunctionReference
         } else {
-            let type_ = if code == 0 {
-                RefType::FunctionReference
-            } else {
-                if i.read_byte()? != 0 {
-                    return Err(ParserError::Msg("invalid elemkind code".into()));
-                }
-                RefType::FunctionReference
-            };
-            let num_elems = i.read_leb128::<u32>()?;
-            let elems = (0..num_elems)
-                .map(|_| FuncIdx::parse(i))
-                .collect::<Result<Vec<FuncIdx>, ParserError>>()?;
-            Ok(Element {
-                mode,
-                type_,
-                init: ElementInit::Unresolved(elems),
-            })
-        }
-    }
-}
-
-impl Parse for Vec<u8> {
-    fn parse(i: &mut WasmBinaryReader) -> Result<Vec<u8>, ParserError> {
-        let len = i.read_leb128::<u32>()?;
-        (0..len).map(|_| i.read_byte()).collect()
+            if i.read_byte()? != 0 {
+                return Err(ParserError::Msg("invalid elemkind code".into()));
+            }
+            RefType::FunctionReference
+        };
+        let num_elems = i.read_leb128::<u32>()?;
+        limits.check(ResourceLimit::ElementSegmentSize, num_elems as u64)?;
+        let elems = (0..num_elems)
+            .map(|_| FuncIdx::parse(i))
+            .collect::<Result<Vec<FuncIdx>, ParserError>>()?;
+        Ok(Element {
+            mode,
+            type_,
+            init: ElementInit::Unresolved(elems),
+        })
     }
 }
 
@@ -302,7 +491,7 @@ impl ParseWithContext for BlockType {
         }
 
         let type_idx = i.read_leb128::<i64>()? as TypeIdx;
-        if type_idx >= m.function_types.len() as u32 {
+        if m.func_type(type_idx).is_none() {
             return Err(ParserError::Msg("function index out of bounds".to_string()));
         }
         Ok(BlockType::FunctionSig(type_idx))
@@ -311,9 +500,21 @@ impl ParseWithContext for BlockType {
 
 impl Parse for MemArg {
     fn parse(i: &mut WasmBinaryReader) -> Result<MemArg, ParserError> {
-        let align = i.read_leb128::<u32>()?;
-        let offset = i.read_leb128::<u32>()?;
-        Ok(MemArg { align, offset })
+        let mut align = i.read_leb128::<u32>()?;
+        // bit 6 of the alignment signals an explicit memory index (multi-memory)
+        let memory = if align & MEMARG_MEMORY_IDX_FLAG != 0 {
+            align &= !MEMARG_MEMORY_IDX_FLAG;
+            MemIdx::parse(i)?
+        } else {
+            0
+        };
+        // offsets are 64-bit for 64-bit memories, their range is validated against the memory's index type
+        let offset = i.read_leb128::<u64>()?;
+        Ok(MemArg {
+            memory,
+            offset,
+            align,
+        })
     }
 }
 
@@ -333,6 +534,7 @@ impl Parse for Section {
             10 => Section::Code,
             11 => Section::Data,
             12 => Section::DataCount,
+            13 => Section::Tag,
             i => return Err(ParserError::Msg(format!("invalid section id {i}"))),
         })
     }
@@ -347,33 +549,50 @@ pub(crate) trait ParseWithContext {
         Self: std::marker::Sized;
 }
 
-impl ParseWithContext for ConstantExpression {
-    // only used for parsing constant expressions
-    fn parse_with_context(
-        i: &mut WasmBinaryReader,
-        module: &ModuleMetadata,
-    ) -> Result<ConstantExpression, ParserError> {
-        let mut ctxt = Context::new(module, Vec::new());
-        let mut labels = Vec::new();
-        let mut builder = FunctionIRBuilder::new();
-        let id = builder.reserve_bb();
-        builder.continue_bb(id);
-
-        parse_basic_blocks(i, &mut ctxt, &mut labels, &mut builder)?;
-        let mut parsed_init_blocks = builder.finalize_bbs();
-        if parsed_init_blocks.len() != 1
-            || parsed_init_blocks[0].instructions.instruction_storage.len() != 1
-        {
-            return Err(ConstantExpressionError::Msg(
-                "invalid constant expression. Expected a single constant initializer instruction."
-                    .into(),
-            )
-            .into());
+/// Parses a constant expression of `section`, which has to produce a value of type `result` if one
+/// is given.
+pub(crate) fn parse_constant_expression(
+    i: &mut WasmBinaryReader,
+    module: &ModuleMetadata,
+    section: Section,
+    result: Option<ValType>,
+) -> Result<ConstantExpression, ParserError> {
+    let mut ctxt = Context::new(module, section);
+    let mut labels = Vec::new();
+    let mut builder = FunctionIRBuilder::new();
+    let id = builder.reserve_bb();
+    builder.continue_bb(id);
+
+    parse_basic_blocks(i, &mut ctxt, &mut labels, &mut builder)?;
+    if let Some(result) = result {
+        let actual: Vec<ValType> = ctxt.stack.stack.iter().map(|var| var.type_).collect();
+        if !matches!(actual.as_slice(), [t] if ctxt.matches(t, &result)) {
+            ctxt.poison(ValidationError::StackMismatch {
+                expected: vec![result],
+                actual,
+            })
         }
-        Ok(ConstantExpression {
-            expression: parsed_init_blocks.remove(0).instructions,
-        })
     }
+    if let Some(poison) = ctxt.poison {
+        return Err(poison.into());
+    }
+    let mut parsed_init_blocks = builder.finalize_bbs();
+    // extended constant expressions may consist of several instructions, but never of control flow
+    if parsed_init_blocks.len() != 1
+        || parsed_init_blocks[0]
+            .instructions
+            .instruction_storage
+            .is_empty()
+    {
+        return Err(ConstantExpressionError::Msg(
+            "invalid constant expression. Expected a single basic block of constant instructions."
+                .into(),
+        )
+        .into());
+    }
+    Ok(ConstantExpression {
+        expression: parsed_init_blocks.remove(0).instructions,
+    })
 }
 
 impl ParseWithContext for Global {
@@ -382,10 +601,11 @@ impl ParseWithContext for Global {
         m: &ModuleMetadata,
     ) -> Result<Self, ParserError> {
         let r#type = GlobalType::parse(i)?;
-        let const_expr = ConstantExpression::parse_with_context(i, m)?;
+        let (GlobalType::Const(val_type) | GlobalType::Mut(val_type)) = r#type;
+        let const_expr = parse_constant_expression(i, m, Section::Global, Some(val_type))?;
         Ok(Global {
             r#type,
-            init: const_expr.eval(m)?,
+            init: const_expr.eval_allocating(m)?,
             import: false,
         })
     }
@@ -39,6 +39,23 @@ impl<'a> WasmBinaryReader<'a> {
         self.binary_source = &self.binary_source[..limit];
     }
 
+    /// Split off the next n bytes as a reader of their own.
+    ///
+    /// The stream is advanced past those bytes, so a malformed sub-stream can't desync the stream.
+    /// @param n the number of bytes of the sub-stream.
+    pub(crate) fn sub_reader(&mut self, n: usize) -> Result<WasmBinaryReader<'a>, ParserError> {
+        let end = self.pos.checked_add(n).ok_or(ParserError::UnexpectedEOF)?;
+        let binary_source = self
+            .binary_source
+            .get(self.pos..end)
+            .ok_or(ParserError::UnexpectedEOF)?;
+        self.pos = end;
+        Ok(WasmBinaryReader {
+            binary_source,
+            pos: 0,
+        })
+    }
+
     /// Read a single byte from the stream.
     ///
     /// @return the byte read from the stream.
@@ -194,6 +211,20 @@ mod tests {
         assert!(reader.read_byte().is_err());
     }
 
+    #[test]
+    fn test_sub_reader() {
+        let stream_data = [0x42, 0x43, 0x44];
+        let mut reader = WasmBinaryReader::new(&stream_data);
+
+        let mut sub_reader = reader.sub_reader(2).unwrap();
+        assert_eq!(sub_reader.read_byte().unwrap(), 0x42);
+        assert_eq!(sub_reader.read_byte().unwrap(), 0x43);
+        assert!(sub_reader.read_byte().is_err());
+
+        assert_eq!(reader.read_byte().unwrap(), 0x44);
+        assert!(reader.sub_reader(1).is_err());
+    }
+
     #[test]
     fn test_read_u32() {
         let stream_data = [];
@@ -8,6 +8,8 @@ pub enum ResourceBufferError {
     InvalidFileEnding(String),
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+    #[error("Invalid WebAssembly text: {0}")]
+    WatError(String),
 }
 
 pub struct ResourceBuffer {
@@ -31,6 +33,8 @@ enum Source {
 pub enum SourceFormat {
     Wasm,
     Cwasm,
+    /// WebAssembly text, the buffer holds the assembled binary
+    Wat,
 }
 
 impl SourceFormat {
@@ -41,6 +45,7 @@ impl SourceFormat {
         match ext.to_str().unwrap() {
             "wasm" => Ok(Self::Wasm),
             "cwasm" => Ok(Self::Cwasm),
+            "wat" => Ok(Self::Wat),
             _ => Err(ResourceBufferError::InvalidFileEnding(
                 ext.to_str().unwrap().to_string(),
             )),
@@ -58,6 +63,16 @@ impl ResourceBuffer {
         };
         let source_format = SourceFormat::from_path(&path)?;
 
+        if source_format == SourceFormat::Wat {
+            let text = std::fs::read(&path)?;
+            return Ok(Self {
+                source: Source::Mem {
+                    buf: Box::pin(assemble_wat(&text, Some(&path))?),
+                },
+                kind: source_format,
+            });
+        }
+
         let file = std::fs::File::open(path)?;
         let file_len = file.metadata()?.len() as usize;
         let file_mmap = unsafe { memmap2::Mmap::map(&file)? };
@@ -78,6 +93,15 @@ impl ResourceBuffer {
         }
     }
 
+    pub fn from_wat_buf(text: &[u8]) -> Result<Self, ResourceBufferError> {
+        Ok(Self {
+            source: Source::Mem {
+                buf: Box::pin(assemble_wat(text, None)?),
+            },
+            kind: SourceFormat::Wat,
+        })
+    }
+
     pub fn get<'a>(&'a self) -> &'a [u8] {
         match &self.source {
             Source::File {
@@ -93,3 +117,20 @@ impl ResourceBuffer {
         self.kind
     }
 }
+
+/// Assemble WebAssembly text into its binary format, errors point to line and column of the text.
+fn assemble_wat(text: &[u8], path: Option<&Path>) -> Result<Vec<u8>, ResourceBufferError> {
+    let text = std::str::from_utf8(text)
+        .map_err(|e| ResourceBufferError::WatError(format!("input is not valid utf-8: {e}")))?;
+    let with_location = |mut e: wast::Error| {
+        e.set_text(text);
+        if let Some(path) = path {
+            e.set_path(path);
+        }
+        ResourceBufferError::WatError(e.to_string())
+    };
+
+    let buf = wast::parser::ParseBuffer::new(text).map_err(with_location)?;
+    let mut wat = wast::parser::parse::<wast::Wat>(&buf).map_err(with_location)?;
+    wat.encode().map_err(with_location)
+}
@@ -1,6 +1,6 @@
 use module::{objects::value::ValueRaw, Module as WasmModule};
-use std::{ffi, ptr::NonNull, rc::Rc};
-use wasm_types::{DataIdx, ElemIdx, MemIdx, TableIdx, TypeIdx};
+use std::{cell::RefCell, ffi, ptr::NonNull, rc::Rc};
+use wasm_types::{DataIdx, ElemIdx, FuncIdx, MemIdx, TableIdx, TagIdx, TypeIdx};
 
 pub type RawPointer = NonNull<core::ffi::c_void>;
 
@@ -17,10 +17,15 @@ pub struct ExecutionContext {
     pub memories_ptr: *mut MemoryInstance,
     pub memories_len: usize,
 
+    pub tags_ptr: *mut ffi::c_void,
+    pub tags_len: usize,
+
     pub trap_msg: Option<String>,
 
     pub wasm_module: Rc<WasmModule>,
     pub engine: *mut ffi::c_void,
+    /// the cluster owning this context, hosts the wait queues of `memory.atomic.wait` / `memory.atomic.notify`
+    pub cluster: *const ffi::c_void,
 
     /// number of current recursion levels, used to prevent stack overflowing
     pub recursion_size: u32,
@@ -33,8 +38,10 @@ pub struct ExecutionContext {
 #[repr(C)]
 pub struct MemoryInstance {
     pub data: *mut u8,
-    pub size: u32,
-    pub max_size: u32,
+    /// current size in wasm pages
+    pub size: u64,
+    /// maximum size in wasm pages
+    pub max_size: u64,
 }
 
 #[derive(Clone)]
@@ -56,36 +63,59 @@ pub struct GlobalInstance {
 // u64 is enough to represent table values since we only ever receive reference types there
 #[allow(improper_ctypes)]
 extern "C" {
-    pub fn memory_grow(ctxt: &mut ExecutionContext, memory_idx: usize, grow_by: u32) -> i32;
+    pub fn memory_grow(ctxt: &mut ExecutionContext, memory_idx: usize, grow_by: u64) -> i64;
     pub fn memory_fill(
         ctxt: &mut ExecutionContext,
         memory_idx: MemIdx,
-        offset: u32,
-        size: u32,
+        offset: u64,
+        size: u64,
         value: u8,
     );
     pub fn memory_copy(
         ctxt: &mut ExecutionContext,
-        memory_idx: MemIdx,
-        src_offset: u32,
-        dst_offset: u32,
-        size: u32,
+        dst_memory_idx: MemIdx,
+        src_memory_idx: MemIdx,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
     );
     pub fn memory_init(
         ctxt: &mut ExecutionContext,
         memory_idx: MemIdx,
         data_idx: DataIdx,
         src_offset: u32,
-        dst_offset: u32,
+        dst_offset: u64,
         size: u32,
     );
     pub fn data_drop(ctxt: &mut ExecutionContext, data_idx: DataIdx);
+    pub fn memory_atomic_wait32(
+        ctxt: &mut ExecutionContext,
+        memory_idx: MemIdx,
+        address: u64,
+        expected: u32,
+        timeout: i64,
+    ) -> u32;
+    pub fn memory_atomic_wait64(
+        ctxt: &mut ExecutionContext,
+        memory_idx: MemIdx,
+        address: u64,
+        expected: u64,
+        timeout: i64,
+    ) -> u32;
+    pub fn memory_atomic_notify(
+        ctxt: &mut ExecutionContext,
+        memory_idx: MemIdx,
+        address: u64,
+        count: u32,
+    ) -> u32;
     pub fn indirect_call(
         ctxt: &mut ExecutionContext,
         table_idx: TableIdx,
         type_idx: TypeIdx,
         entry_idx: u32,
     ) -> RawPointer;
+    pub fn ref_call(ctxt: &mut ExecutionContext, func_ref: u64) -> RawPointer;
+    pub fn compile_function(ctxt: &mut ExecutionContext, func_idx: FuncIdx) -> RawPointer;
     pub fn table_set(ctxt: &mut ExecutionContext, table_idx: usize, value: u64, idx: u32);
     pub fn table_get(ctxt: &mut ExecutionContext, table_idx: usize, idx: u32) -> u64;
     pub fn table_grow(
@@ -119,4 +149,128 @@ extern "C" {
         len: u32,
     );
     pub fn elem_drop(ctxt: &mut ExecutionContext, elem_idx: ElemIdx);
+    // exceptions are not unwound natively. A thrown exception stays pending until it is caught, callers check
+    // `exception_pending` after every call and propagate it by returning.
+    pub fn exception_throw(ctxt: &mut ExecutionContext, tag_idx: TagIdx, payload: *const ValueRaw);
+    pub fn exception_throw_ref(ctxt: &mut ExecutionContext, exn: u64);
+    pub fn exception_pending(ctxt: &mut ExecutionContext) -> u32;
+    pub fn exception_matches(ctxt: &mut ExecutionContext, tag_idx: TagIdx) -> u32;
+    pub fn exception_catch(ctxt: &mut ExecutionContext, payload_out: *mut ValueRaw) -> u64;
+    // struct and array objects live on the heap of the cluster, see `gc_*` in runtime-lib. Field values are
+    // passed as u64, packed fields are truncated when stored and extended according to `signed` when loaded.
+    // `fields` / `values` point to one value per field, a null `fields` pointer creates default-initialized structs.
+    pub fn gc_struct_new(ctxt: &mut ExecutionContext, type_idx: TypeIdx, fields: *const u64)
+        -> u64;
+    pub fn gc_struct_get(ctxt: &mut ExecutionContext, obj: u64, field_idx: u32, signed: u32)
+        -> u64;
+    pub fn gc_struct_set(ctxt: &mut ExecutionContext, obj: u64, field_idx: u32, value: u64);
+    pub fn gc_array_new(ctxt: &mut ExecutionContext, type_idx: TypeIdx, init: u64, len: u32)
+        -> u64;
+    pub fn gc_array_new_default(ctxt: &mut ExecutionContext, type_idx: TypeIdx, len: u32) -> u64;
+    pub fn gc_array_new_fixed(
+        ctxt: &mut ExecutionContext,
+        type_idx: TypeIdx,
+        values: *const u64,
+        len: u32,
+    ) -> u64;
+    pub fn gc_array_new_data(
+        ctxt: &mut ExecutionContext,
+        type_idx: TypeIdx,
+        data_idx: DataIdx,
+        offset: u32,
+        len: u32,
+    ) -> u64;
+    pub fn gc_array_new_elem(
+        ctxt: &mut ExecutionContext,
+        type_idx: TypeIdx,
+        elem_idx: ElemIdx,
+        offset: u32,
+        len: u32,
+    ) -> u64;
+    pub fn gc_array_get(ctxt: &mut ExecutionContext, array: u64, idx: u32, signed: u32) -> u64;
+    pub fn gc_array_set(ctxt: &mut ExecutionContext, array: u64, idx: u32, value: u64);
+    pub fn gc_array_len(ctxt: &mut ExecutionContext, array: u64) -> u32;
+    pub fn gc_array_fill(
+        ctxt: &mut ExecutionContext,
+        array: u64,
+        offset: u32,
+        value: u64,
+        len: u32,
+    );
+    pub fn gc_array_copy(
+        ctxt: &mut ExecutionContext,
+        dst: u64,
+        dst_offset: u32,
+        src: u64,
+        src_offset: u32,
+        len: u32,
+    );
+    pub fn gc_array_init_data(
+        ctxt: &mut ExecutionContext,
+        array: u64,
+        data_idx: DataIdx,
+        dst_offset: u32,
+        src_offset: u32,
+        len: u32,
+    );
+    pub fn gc_array_init_elem(
+        ctxt: &mut ExecutionContext,
+        array: u64,
+        elem_idx: ElemIdx,
+        dst_offset: u32,
+        src_offset: u32,
+        len: u32,
+    );
+    // the heap type is passed in its binary s33 encoding, see `HeapType::to_s33`
+    pub fn gc_ref_test(
+        ctxt: &mut ExecutionContext,
+        value: u64,
+        heap_type: i64,
+        nullable: u32,
+    ) -> u32;
+    pub fn gc_ref_cast(
+        ctxt: &mut ExecutionContext,
+        value: u64,
+        heap_type: i64,
+        nullable: u32,
+    ) -> u64;
+}
+
+/// Roots of the garbage collected heap that are not owned by the cluster, e.g. the variables of interpreter
+/// frames. `scan` is called with `data` and reports the raw value of every reference that may be live.
+#[derive(Clone, Copy)]
+pub struct GcRootScanner {
+    pub data: *const ffi::c_void,
+    pub scan: unsafe fn(*const ffi::c_void, &mut dyn FnMut(u64)),
+}
+
+thread_local! {
+    // scanners of the wasm frames currently executing on this thread, innermost last
+    static GC_ROOT_SCANNERS: RefCell<Vec<GcRootScanner>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Registers roots for the duration of a wasm call, `scanner.data` must stay valid until [`pop_gc_roots`].
+pub fn push_gc_roots(scanner: GcRootScanner) {
+    GC_ROOT_SCANNERS.with_borrow_mut(|scanners| scanners.push(scanner));
+}
+
+pub fn pop_gc_roots() {
+    GC_ROOT_SCANNERS.with_borrow_mut(|scanners| scanners.pop());
+}
+
+/// Number of registered scanners, traps skip the matching [`pop_gc_roots`] calls and truncate to this depth.
+pub fn gc_roots_depth() -> usize {
+    GC_ROOT_SCANNERS.with_borrow(|scanners| scanners.len())
+}
+
+pub fn truncate_gc_roots(depth: usize) {
+    GC_ROOT_SCANNERS.with_borrow_mut(|scanners| scanners.truncate(depth));
+}
+
+/// Reports the roots of all registered scanners of the current thread.
+pub fn scan_gc_roots(visit: &mut dyn FnMut(u64)) {
+    let scanners = GC_ROOT_SCANNERS.with_borrow(|scanners| scanners.clone());
+    for scanner in scanners {
+        unsafe { (scanner.scan)(scanner.data, visit) };
+    }
 }
@@ -1,12 +1,21 @@
 use crate::{
     helper::segmented_list::SegmentedList,
     objects::{
-        functions::Function, globals::GlobalsObject, memory::MemoryObject, tables::TableObject,
+        exceptions::TagInstance,
+        functions::Function,
+        gc::{GcHeap, GcSafepoint},
+        globals::GlobalsObject,
+        memory::MemoryObject,
+        tables::{TableItem, TableObject},
     },
     Engine,
 };
 use runtime_interface::ExecutionContext;
-use std::sync::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
 use uuid::Uuid;
 use wasi::WasiContext;
 
@@ -17,21 +26,46 @@ pub struct ClusterConfig {/* TODO */}
 ///  - Memories
 ///  - Tables
 ///  - Globals
+///  - Exception tags
+///  - Structs and arrays of the GC proposal and thrown exceptions
 ///  - Functions (Closures)
 ///  - Engines
 ///  - Execution Contexts (which itself are just collections of pointers to this clusters resources)
 ///
 /// Instance Handles are mere references to the resources and are therefore non-owning.
+///
+/// The cluster also takes the role of the threads proposal's agent cluster: it hosts the wait queues that
+/// `memory.atomic.wait` suspends on and `memory.atomic.notify` wakes up.
 pub struct Cluster {
     pub(crate) uuid: Uuid,
     pub(crate) config: ClusterConfig,
     memories: Mutex<SegmentedList<MemoryObject>>,
     tables: Mutex<SegmentedList<TableObject>>,
     globals: Mutex<SegmentedList<GlobalsObject>>,
+    tags: Mutex<SegmentedList<TagInstance>>,
+    gc_heap: Mutex<GcHeap>,
+    gc_safepoint: GcSafepoint,
     execution_contexts: Mutex<SegmentedList<ExecutionContext>>,
     engines: Mutex<SegmentedList<Engine>>,
     functions: Mutex<SegmentedList<Function>>,
     wasi_ctxt: Mutex<SegmentedList<WasiContext>>,
+    /// waiting agents, keyed by the host address they are waiting on
+    wait_queues: Mutex<HashMap<usize, VecDeque<Arc<Waiter>>>>,
+}
+
+/// An agent suspended in `memory.atomic.wait`.
+#[derive(Default)]
+struct Waiter {
+    notified: Mutex<bool>,
+    condvar: Condvar,
+}
+
+/// Return values of `memory.atomic.wait`, as defined by the threads proposal.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum WaitResult {
+    Ok = 0,
+    NotEqual = 1,
+    TimedOut = 2,
 }
 
 impl Cluster {
@@ -63,6 +97,37 @@ impl Cluster {
         memories_lock.get_last_segments_ref()
     }
 
+    // unsafe: extracts mut-ref on cluster.tags without owning cluster.tags
+    pub(crate) fn alloc_tags(&self, tags: Vec<TagInstance>) -> &mut [TagInstance] {
+        let mut tags_lock = self.tags.lock().unwrap();
+        tags_lock.extend(tags);
+        tags_lock.get_last_segments_ref()
+    }
+
+    pub(crate) fn gc_heap(&self) -> MutexGuard<'_, GcHeap> {
+        self.gc_heap.lock().unwrap()
+    }
+
+    pub(crate) fn gc_safepoint(&self) -> &GcSafepoint {
+        &self.gc_safepoint
+    }
+
+    /// Reports the raw values of all globals and table entries as roots of the heap.
+    pub(crate) fn scan_gc_roots(&self, visit: &mut dyn FnMut(u64)) {
+        for globals in self.globals.lock().unwrap().iter() {
+            for global in globals.inner.globals.iter() {
+                visit(unsafe { global.addr.as_ptr().read() }.as_u64());
+            }
+        }
+        for table in self.tables.lock().unwrap().iter() {
+            for item in table.0.iter() {
+                if let TableItem::GcReference(raw) | TableItem::ExceptionReference(raw) = item {
+                    visit(*raw);
+                }
+            }
+        }
+    }
+
     pub(crate) fn alloc_execution_context(
         &self,
         mut execution_context: ExecutionContext,
@@ -90,6 +155,82 @@ impl Cluster {
         wasi_ctxt_lock.push(wasi_ctxt);
         &mut wasi_ctxt_lock.get_last_segments_ref()[0]
     }
+
+    /// Suspends the calling agent on `addr` until it is notified or `timeout` elapsed (`None` waits forever).
+    /// `value_matches` is evaluated while holding the wait queue lock, so no notification can get lost between
+    /// comparing the memory value and enqueueing the agent.
+    pub(crate) fn wait(
+        &self,
+        addr: usize,
+        value_matches: impl FnOnce() -> bool,
+        timeout: Option<Duration>,
+    ) -> WaitResult {
+        let waiter = {
+            let mut queues = self.wait_queues.lock().unwrap();
+            if !value_matches() {
+                return WaitResult::NotEqual;
+            }
+            let waiter = Arc::new(Waiter::default());
+            queues.entry(addr).or_default().push_back(waiter.clone());
+            waiter
+        };
+
+        // waiting agents don't delay garbage collections of the other agents
+        let notified = self.gc_safepoint.blocking(|| {
+            let notified = waiter.notified.lock().unwrap();
+            *match timeout {
+                Some(timeout) => {
+                    waiter
+                        .condvar
+                        .wait_timeout_while(notified, timeout, |notified| !*notified)
+                        .unwrap()
+                        .0
+                }
+                None => waiter
+                    .condvar
+                    .wait_while(notified, |notified| !*notified)
+                    .unwrap(),
+            }
+        });
+        if notified {
+            return WaitResult::Ok;
+        }
+
+        // timed out, but a notify might have dequeued us in the meantime
+        let mut queues = self.wait_queues.lock().unwrap();
+        let Some(queue) = queues.get_mut(&addr) else {
+            return WaitResult::Ok;
+        };
+        let Some(pos) = queue.iter().position(|w| Arc::ptr_eq(w, &waiter)) else {
+            return WaitResult::Ok;
+        };
+        queue.remove(pos);
+        if queue.is_empty() {
+            queues.remove(&addr);
+        }
+        WaitResult::TimedOut
+    }
+
+    /// Wakes up to `count` agents waiting on `addr` in FIFO order and returns the number of woken agents.
+    pub(crate) fn notify(&self, addr: usize, count: u32) -> u32 {
+        let mut queues = self.wait_queues.lock().unwrap();
+        let Some(queue) = queues.get_mut(&addr) else {
+            return 0;
+        };
+        let mut woken = 0;
+        while woken < count {
+            let Some(waiter) = queue.pop_front() else {
+                break;
+            };
+            *waiter.notified.lock().unwrap() = true;
+            waiter.condvar.notify_one();
+            woken += 1;
+        }
+        if queue.is_empty() {
+            queues.remove(&addr);
+        }
+        woken
+    }
 }
 
 impl PartialEq for Cluster {
@@ -106,10 +247,14 @@ impl Default for Cluster {
             memories: Mutex::new(SegmentedList::new()),
             tables: Mutex::new(SegmentedList::new()),
             globals: Mutex::new(SegmentedList::new()),
+            tags: Mutex::new(SegmentedList::new()),
+            gc_heap: Mutex::new(GcHeap::default()),
+            gc_safepoint: GcSafepoint::default(),
             execution_contexts: Mutex::new(SegmentedList::new()),
             engines: Mutex::new(SegmentedList::new()),
             functions: Mutex::new(SegmentedList::new()),
             wasi_ctxt: Mutex::new(SegmentedList::new()),
+            wait_queues: Mutex::new(HashMap::new()),
         }
     }
 }
@@ -1,13 +1,32 @@
-use crate::cluster::ClusterConfig;
-use std::path::PathBuf;
+use crate::{cluster::ClusterConfig, EngineConfig};
+use std::{fmt::Debug, num::NonZeroUsize, path::PathBuf};
 
-#[derive(Debug, Default)]
+#[derive(Default)]
 pub struct Config {
     pub wasi_enabled: bool,
     pub wasi_dirs: Vec<(PathBuf, String)>,
     pub wasi_args: Vec<String>,
     pub start_function: Option<String>,
     pub cluster_config: ClusterConfig,
+    /// settings for the engine the module is executed with
+    pub engine_config: EngineConfig,
+    /// HMAC-SHA256 key that cwasm files have to be authenticated with, any intact file is loaded without one
+    pub cwasm_key: Option<Vec<u8>>,
+}
+
+impl Debug for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Config")
+            .field("wasi_enabled", &self.wasi_enabled)
+            .field("wasi_dirs", &self.wasi_dirs)
+            .field("wasi_args", &self.wasi_args)
+            .field("start_function", &self.start_function)
+            .field("cluster_config", &self.cluster_config)
+            .field("engine_config", &self.engine_config)
+            // the key is a secret, so it is kept out of logs
+            .field("cwasm_key", &self.cwasm_key.as_ref().map(|_| "<redacted>"))
+            .finish()
+    }
 }
 
 #[derive(Debug, Default)]
@@ -44,6 +63,41 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn set_engine_config(&mut self, engine_config: EngineConfig) -> &mut Self {
+        self.config.engine_config = engine_config;
+        self
+    }
+
+    pub fn set_parser_threads(&mut self, threads: NonZeroUsize) -> &mut Self {
+        self.config.engine_config.parser_threads = Some(threads);
+        self
+    }
+
+    #[cfg(feature = "llvm")]
+    pub fn set_opt_level(&mut self, opt_level: crate::OptLevel) -> &mut Self {
+        self.config.engine_config.llvm_opt_level = opt_level;
+        self
+    }
+
+    #[cfg(feature = "llvm")]
+    pub fn set_compilation_cache(&mut self, cache: crate::CompilationCache) -> &mut Self {
+        self.config.engine_config.llvm_cache = Some(cache);
+        self
+    }
+
+    pub fn set_cwasm_key(&mut self, key: Vec<u8>) -> &mut Self {
+        self.config.cwasm_key = Some(key);
+        self
+    }
+
+    pub fn engine_config(&self) -> &EngineConfig {
+        &self.config.engine_config
+    }
+
+    pub fn cwasm_key(&self) -> Option<&[u8]> {
+        self.config.cwasm_key.as_deref()
+    }
+
     pub fn finish(self) -> Config {
         self.config
     }
@@ -1,10 +1,11 @@
 use crate::{
     linker::LinkingError,
     objects::{
-        engine::EngineError, functions::FunctionError, instance_handle::InstantiationError,
-        memory::MemoryError, tables::TableError,
+        engine::EngineError, exceptions::ExceptionError, functions::FunctionError, gc::GcError,
+        instance_handle::InstantiationError, memory::MemoryError, tables::TableError,
     },
 };
+use module::objects::value::Value;
 use thiserror::Error;
 use wasi::WasiError;
 use wasm_types::ValType;
@@ -29,6 +30,8 @@ pub enum RuntimeError {
     Trap(String),
     #[error("Stack exhausted")]
     Exhaustion,
+    #[error("Uncaught exception with payload {0:?}")]
+    UncaughtException(Vec<Value>),
 
     #[error("Engine error: {0}")]
     EngineError(#[from] EngineError),
@@ -57,6 +60,12 @@ pub enum RuntimeError {
     #[error("Memory error: {0}")]
     MemoryError(#[from] MemoryError),
 
+    #[error("Exception error: {0}")]
+    ExceptionError(#[from] ExceptionError),
+
+    #[error("GC error: {0}")]
+    GcError(#[from] GcError),
+
     #[error("Function error: {0}")]
     FunctionError(#[from] FunctionError),
 
@@ -26,6 +26,10 @@ impl<T> SegmentedList<T> {
         self.segments.iter().map(|s| s.len()).sum()
     }
 
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &T> {
+        self.segments.iter().flat_map(|segment| segment.iter())
+    }
+
     pub(crate) fn get_last_segments_ref<'b>(&self) -> &'b mut [T] {
         unsafe {
             slice::from_raw_parts_mut(
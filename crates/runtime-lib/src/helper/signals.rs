@@ -5,13 +5,13 @@ use nix::{
 };
 use once_cell::sync::Lazy;
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     ffi::CStr,
     mem::MaybeUninit,
     ptr,
     sync::{
         atomic::{AtomicBool, AtomicI32},
-        Mutex,
+        Mutex, RwLock,
     },
 };
 
@@ -21,6 +21,10 @@ static SIGNAL_HANDLER_FALLBACK_FUNCS: Lazy<Mutex<HashMap<libc::c_int, SigAction>
 
 static SIGNAL_HANDLER_REGISTER_COUNT: Lazy<AtomicI32> = Lazy::new(|| AtomicI32::new(0));
 
+// start addresses of the jit compiled functions of all engines, mapped to their debug names
+static JIT_FUNCTIONS: Lazy<RwLock<BTreeMap<usize, String>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
 thread_local! {
     static THREAD_CURRENTLY_EXECUTES_WASM: AtomicBool = const {AtomicBool::new(false)};
     static SIGNAL_ALT_STACK: SigAltStack = SigAltStack::new();
@@ -52,6 +56,36 @@ fn is_stack_overflow(sig_code: i32, addr: *mut libc::c_void) -> bool {
         || addr < unsafe { stack_addr.add(stack_size - 8) }
 }
 
+/// Address of the instruction that raised the signal
+#[allow(unused_variables)]
+fn faulting_instruction(ucontext: *mut libc::c_void) -> Option<usize> {
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    return Some(unsafe {
+        (*ucontext.cast::<libc::ucontext_t>()).uc_mcontext.gregs[libc::REG_RIP as usize] as usize
+    });
+    #[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+    return Some(unsafe { (*ucontext.cast::<libc::ucontext_t>()).uc_mcontext.pc as usize });
+    #[allow(unreachable_code)]
+    None
+}
+
+/// Debug name of the jit compiled function containing `addr`
+///
+/// The jit doesn't report the size of the functions, but its code isn't part of any loaded object. Addresses that
+/// `dladdr` resolves belong to native code like the runtime functions and are never attributed to wasm functions.
+fn jit_function_at(addr: usize) -> Option<String> {
+    let mut info = MaybeUninit::uninit();
+    if unsafe { libc::dladdr(addr as *const libc::c_void, info.as_mut_ptr()) } != 0 {
+        return None;
+    }
+    // don't block in the signal handler, the lock is only ever held shortly for (de)registering functions
+    let functions = JIT_FUNCTIONS.try_read().ok()?;
+    functions
+        .range(..=addr)
+        .next_back()
+        .map(|(_, name)| name.clone())
+}
+
 impl SignalHandler {
     extern "C" fn handle_signal(
         sig: libc::c_int,
@@ -63,10 +97,14 @@ impl SignalHandler {
             if is_stack_overflow(sig, unsafe { (*info).si_addr() }) {
                 ExecutionContextWrapper::trap(RuntimeError::Exhaustion);
             } else {
-                ExecutionContextWrapper::trap(RuntimeError::Trap(format!(
-                    "execution triggered {:?} signal",
-                    unsafe { CStr::from_ptr(libc::strsignal(sig)) }
-                )))
+                let mut msg = format!("execution triggered {:?} signal", unsafe {
+                    CStr::from_ptr(libc::strsignal(sig))
+                });
+                if let Some(function) = faulting_instruction(ucontext).and_then(jit_function_at) {
+                    // same format as the interpreter's errors
+                    msg.push_str(&format!(" (in function {function})"));
+                }
+                ExecutionContextWrapper::trap(RuntimeError::Trap(msg))
             }
         }
 
@@ -169,6 +207,21 @@ impl SignalHandler {
         THREAD_CURRENTLY_EXECUTES_WASM
             .with(|b| b.store(false, std::sync::atomic::Ordering::Relaxed));
     }
+
+    /// Register the start addresses of jit compiled functions, traps in them are reported with the function's name.
+    #[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+    pub(crate) fn register_jit_functions(functions: impl IntoIterator<Item = (usize, String)>) {
+        JIT_FUNCTIONS.write().unwrap().extend(functions);
+    }
+
+    /// Deregister jit compiled functions, e.g. once their code is freed.
+    #[cfg_attr(not(feature = "llvm"), allow(dead_code))]
+    pub(crate) fn deregister_jit_functions(addrs: &[usize]) {
+        let mut functions = JIT_FUNCTIONS.write().unwrap();
+        for addr in addrs {
+            functions.remove(addr);
+        }
+    }
 }
 
 struct SigAltStack {
@@ -224,3 +277,24 @@ impl Drop for SigAltStack {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jit_functions_are_found_by_address() {
+        // stands in for jit compiled code, which isn't part of any loaded object
+        let code = vec![0u8; 64];
+        let start = code.as_ptr() as usize;
+        SignalHandler::register_jit_functions([(start, "fac".to_string())]);
+
+        assert_eq!(jit_function_at(start).as_deref(), Some("fac"));
+        assert_eq!(jit_function_at(start + 32).as_deref(), Some("fac"));
+        // native code is never attributed to a wasm function
+        assert_eq!(jit_function_at(jit_function_at as *const () as usize), None);
+
+        SignalHandler::deregister_jit_functions(&[start]);
+        assert_ne!(jit_function_at(start).as_deref(), Some("fac"));
+    }
+}
@@ -57,6 +57,20 @@ wasm_type_for_floats! {
     f64, as_f64, F64
 }
 
+impl WasmType for u128 {
+    fn valtype() -> ValType {
+        ValType::VecType
+    }
+
+    unsafe fn from_raw(raw: &ValueRaw) -> Self {
+        u128::from_le_bytes(raw.as_v128())
+    }
+
+    unsafe fn to_raw(self) -> ValueRaw {
+        ValueRaw::v128(self.to_le_bytes())
+    }
+}
+
 pub(crate) trait WasmTypeList {
     fn valtypes() -> impl Iterator<Item = ValType>;
     unsafe fn from_raw(values: &[MaybeUninit<ValueRaw>]) -> Self;
@@ -7,7 +7,7 @@ compile_error!("The \"asm\" engine is not available yet.");
 #[cfg(not(any(feature = "llvm", feature = "interp", feature = "asm")))]
 compile_error!("You need to enable at least one execution backend!");
 
-pub use objects::engine::Engine;
+pub use objects::engine::{CompilationMode, Engine, EngineConfig};
 pub use objects::instance_handle::InstanceHandle;
 
 mod cluster;
@@ -24,6 +24,8 @@ pub use linker::{BoundLinker, Linker};
 
 // reexports
 pub use config::{Config, ConfigBuilder};
+#[cfg(feature = "llvm")]
+pub use llvm_gen::{aot::CompilationCache, OptLevel};
 pub use module::{objects::module::FunctionLoaderInterface, objects::module::Module as WasmModule};
 pub use parser::{FunctionLoader, Parser, ParserError, ValidationError};
 pub use resource_buffer::ResourceBuffer;
@@ -31,6 +33,8 @@ pub use resource_buffer::ResourceBuffer;
 pub const WASM_PAGE_SIZE: u32 = 2_u32.pow(16);
 // maximum amount of wasm pages
 pub const WASM_PAGE_LIMIT: u32 = 2_u32.pow(16);
+// maximum amount of wasm pages of 64-bit memories (1TiB), bounded by the address space we reserve per memory
+pub const WASM64_PAGE_LIMIT: u64 = 2_u64.pow(24);
 // maximal address accessible from 32-bit wasm code
 pub const WASM_MAX_ADDRESS: u64 = 2_u64.pow(33) + 15;
 // least amount of reserved intl pages to encorporate max wasm address
@@ -2,6 +2,7 @@ use crate::{
     helper::segmented_list::SegmentedList,
     objects::{
         engine::EngineError,
+        exceptions::TagInstance,
         functions::{Function, IntoFunc},
         instance_handle::{InstanceHandle, InstantiationError},
         tables::TableObject,
@@ -13,7 +14,9 @@ use once_cell::sync::Lazy;
 use runtime_interface::{ExecutionContext, RawPointer};
 use std::{collections::HashMap, ptr::NonNull, rc::Rc};
 use wasi::{WasiContext, WASI_FUNCS};
-use wasm_types::{FuncType, GlobalIdx, GlobalType, ImportDesc, Limits, MemType, TableType};
+use wasm_types::{
+    FuncType, GlobalIdx, GlobalType, ImportDesc, Limits, MemType, SubType, TableType,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum LinkingError {
@@ -47,6 +50,13 @@ pub enum LinkingError {
         module_name: String,
         memory_name: String,
     },
+    #[error("Tag type mismatch for module \"{module_name}\", tag \"{tag_name}\". Requested: {requested}, Actual: {actual}.")]
+    TagTypeMismatch {
+        requested: FuncType,
+        actual: FuncType,
+        module_name: String,
+        tag_name: String,
+    },
     #[error("Global '{global_name}' not found in module '{module_name}'.")]
     GlobalNotFound {
         global_name: String,
@@ -57,6 +67,11 @@ pub enum LinkingError {
         memory_name: String,
         module_name: String,
     },
+    #[error("Tag '{tag_name}' not found in module '{module_name}'.")]
+    TagNotFound {
+        tag_name: String,
+        module_name: String,
+    },
     #[error("Encountered error during call to execution engine: {0}")]
     EngineError(#[from] EngineError),
     #[error("Function '{name}' not found from module '{module_name}'.")]
@@ -180,6 +195,8 @@ impl<'a> BoundLinker<'a> {
 
                     let func;
                     let actual_functype;
+                    // types referenced by concrete heap types of the actual function type
+                    let mut actual_types: &[SubType] = &[];
 
                     // filter out host & wasi function imports early: Option 1 = Host supplied function
                     #[allow(clippy::borrow_interior_mutable_const)]
@@ -200,6 +217,7 @@ impl<'a> BoundLinker<'a> {
                         actual_functype = func.functype()
                     } else {
                         let exporting_module = (exporting_module)()?;
+                        actual_types = &exporting_module.wasm_module().meta.types;
                         actual_functype =
                             match exporting_module.get_function_type_from_name(&import.name) {
                                 Some(t) => t,
@@ -226,7 +244,11 @@ impl<'a> BoundLinker<'a> {
                             }
                         };
                     }
-                    if requested_function_type != actual_functype {
+                    if !actual_functype.matches(
+                        actual_types,
+                        &requested_function_type,
+                        &module.meta.types,
+                    ) {
                         return Err(LinkingError::FunctionTypeMismatch {
                             requested: requested_function_type,
                             actual: actual_functype,
@@ -255,7 +277,20 @@ impl<'a> BoundLinker<'a> {
                     let exported_global =
                         &exporting_module.wasm_module().meta.globals[exported_global_idx as usize];
                     let actual_type = &exported_global.r#type;
-                    if requested_type != actual_type {
+                    let requested_types = &module.meta.types;
+                    let actual_types = &exporting_module.wasm_module().meta.types;
+                    // immutable globals are covariant, mutable ones invariant
+                    let matches = match (requested_type, actual_type) {
+                        (GlobalType::Const(requested), GlobalType::Const(actual)) => {
+                            actual.matches(actual_types, requested, requested_types)
+                        }
+                        (GlobalType::Mut(requested), GlobalType::Mut(actual)) => {
+                            actual.matches(actual_types, requested, requested_types)
+                                && requested.matches(requested_types, actual, actual_types)
+                        }
+                        _ => false,
+                    };
+                    if !matches {
                         return Err(LinkingError::GlobalTypeMismatch {
                             requested: *requested_type,
                             actual: *actual_type,
@@ -269,7 +304,7 @@ impl<'a> BoundLinker<'a> {
                         idx: *idx,
                     });
                 }
-                ImportDesc::Mem(expected_limits) => {
+                ImportDesc::Mem(expected_type) => {
                     let exporting_module = (exporting_module)()?;
                     let exported_memory_idx = match exporting_module
                         .wasm_module()
@@ -288,28 +323,31 @@ impl<'a> BoundLinker<'a> {
                     let exported_memory =
                         &exporting_module.wasm_module().meta.memories[exported_memory_idx as usize];
 
-                    let mut actual_limits = exported_memory.limits;
-                    actual_limits.min = actual_limits
+                    let mut actual_type = exported_memory.r#type;
+                    actual_type.lim.min = actual_type
+                        .lim
                         .min
                         .max(exporting_module.memories(exported_memory_idx).0.size);
 
-                    let max_actual_len = actual_limits.max.unwrap_or(u32::MAX);
-                    let max_expected_len = expected_limits.max.unwrap_or(u32::MAX);
-                    if expected_limits.min <= actual_limits.min
+                    let max_actual_len = actual_type.lim.max.unwrap_or(u64::MAX);
+                    let max_expected_len = expected_type.lim.max.unwrap_or(u64::MAX);
+                    if expected_type.lim.min <= actual_type.lim.min
                         && max_expected_len >= max_actual_len
+                        && expected_type.shared == actual_type.shared
+                        && expected_type.index_type == actual_type.index_type
                     {
                         /* Good, these are exactly the qualities we need :) */
                     } else {
                         return Err(LinkingError::MemoryTypeMismatch {
-                            requested: *expected_limits,
-                            actual: actual_limits,
+                            requested: *expected_type,
+                            actual: actual_type,
                             module_name: import.module.clone(),
                             memory_name: import.name.clone(),
                         });
                     }
                     imports.memories.push(RTMemoryImport {
                         name: import.name.clone(),
-                        limits: actual_limits,
+                        limits: actual_type.lim,
                     });
                 }
                 ImportDesc::Table(expected_type) => {
@@ -332,10 +370,20 @@ impl<'a> BoundLinker<'a> {
                         &exporting_module.wasm_module().meta.tables[exported_table_idx as usize];
                     let actual_type = &exported_table.r#type;
 
-                    let max_expected_len = expected_type.lim.max.unwrap_or(u32::MAX);
-                    let max_actual_len = actual_type.lim.max.unwrap_or(u32::MAX);
-                    if expected_type.ref_type == actual_type.ref_type
-                        && expected_type.lim.min <= actual_type.lim.min
+                    let max_expected_len = expected_type.lim.max.unwrap_or(u64::MAX);
+                    let max_actual_len = actual_type.lim.max.unwrap_or(u64::MAX);
+                    let expected_types = &module.meta.types;
+                    let actual_types = &exporting_module.wasm_module().meta.types;
+                    // table element types are invariant
+                    if actual_type.ref_type.matches(
+                        actual_types,
+                        &expected_type.ref_type,
+                        expected_types,
+                    ) && expected_type.ref_type.matches(
+                        expected_types,
+                        &actual_type.ref_type,
+                        actual_types,
+                    ) && expected_type.lim.min <= actual_type.lim.min
                         && max_expected_len >= max_actual_len
                     {
                         /* Good, these are exactly the qualities we need :) */
@@ -355,6 +403,38 @@ impl<'a> BoundLinker<'a> {
                         r#type: *expected_type,
                     });
                 }
+                ImportDesc::Tag(type_idx) => {
+                    let exporting_module = (exporting_module)()?;
+                    let exported_tag_idx = match exporting_module
+                        .wasm_module()
+                        .meta
+                        .exports
+                        .find_tag_idx(&import.name)
+                    {
+                        Some(idx) => idx,
+                        None => {
+                            return Err(LinkingError::TagNotFound {
+                                tag_name: import.name.clone(),
+                                module_name: import.module.clone(),
+                            })
+                        }
+                    };
+                    let exported_tag = exporting_module.tags(exported_tag_idx);
+                    let requested_type = module.meta.function_types[*type_idx as usize];
+                    if !requested_type.equivalent(
+                        &module.meta.types,
+                        &exported_tag.ty,
+                        &exporting_module.wasm_module().meta.types,
+                    ) {
+                        return Err(LinkingError::TagTypeMismatch {
+                            requested: requested_type,
+                            actual: exported_tag.ty,
+                            module_name: import.module.clone(),
+                            tag_name: import.name.clone(),
+                        });
+                    }
+                    imports.tags.push(*exported_tag);
+                }
             }
         }
         Ok(imports)
@@ -430,12 +510,42 @@ static DATA_DROP_RT_FUNC: Lazy<Function> = Lazy::new(|| {
     })
 });
 
+static MEMORY_ATOMIC_WAIT32_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::memory_atomic_wait32 as _)
+    })
+});
+
+static MEMORY_ATOMIC_WAIT64_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::memory_atomic_wait64 as _)
+    })
+});
+
+static MEMORY_ATOMIC_NOTIFY_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::memory_atomic_notify as _)
+    })
+});
+
 static INDIRECT_CALL_RT_FUNC: Lazy<Function> = Lazy::new(|| {
     Function::from_runtime_func(unsafe {
         RawPointer::new_unchecked(runtime_interface::indirect_call as _)
     })
 });
 
+static REF_CALL_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::ref_call as _)
+    })
+});
+
+static COMPILE_FUNCTION_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::compile_function as _)
+    })
+});
+
 static TABLE_SET_RT_FUNC: Lazy<Function> = Lazy::new(|| {
     Function::from_runtime_func(unsafe {
         RawPointer::new_unchecked(runtime_interface::table_set as _)
@@ -484,6 +594,138 @@ static ELEM_DROP_RT_FUNC: Lazy<Function> = Lazy::new(|| {
     })
 });
 
+static EXCEPTION_THROW_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::exception_throw as _)
+    })
+});
+
+static EXCEPTION_THROW_REF_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::exception_throw_ref as _)
+    })
+});
+
+static EXCEPTION_PENDING_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::exception_pending as _)
+    })
+});
+
+static EXCEPTION_MATCHES_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::exception_matches as _)
+    })
+});
+
+static EXCEPTION_CATCH_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::exception_catch as _)
+    })
+});
+
+static GC_STRUCT_NEW_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_struct_new as _)
+    })
+});
+
+static GC_STRUCT_GET_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_struct_get as _)
+    })
+});
+
+static GC_STRUCT_SET_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_struct_set as _)
+    })
+});
+
+static GC_ARRAY_NEW_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_new as _)
+    })
+});
+
+static GC_ARRAY_NEW_DEFAULT_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_new_default as _)
+    })
+});
+
+static GC_ARRAY_NEW_FIXED_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_new_fixed as _)
+    })
+});
+
+static GC_ARRAY_NEW_DATA_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_new_data as _)
+    })
+});
+
+static GC_ARRAY_NEW_ELEM_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_new_elem as _)
+    })
+});
+
+static GC_ARRAY_GET_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_get as _)
+    })
+});
+
+static GC_ARRAY_SET_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_set as _)
+    })
+});
+
+static GC_ARRAY_LEN_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_len as _)
+    })
+});
+
+static GC_ARRAY_FILL_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_fill as _)
+    })
+});
+
+static GC_ARRAY_COPY_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_copy as _)
+    })
+});
+
+static GC_ARRAY_INIT_DATA_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_init_data as _)
+    })
+});
+
+static GC_ARRAY_INIT_ELEM_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_array_init_elem as _)
+    })
+});
+
+static GC_REF_TEST_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_ref_test as _)
+    })
+});
+
+static GC_REF_CAST_RT_FUNC: Lazy<Function> = Lazy::new(|| {
+    Function::from_runtime_func(unsafe {
+        RawPointer::new_unchecked(runtime_interface::gc_ref_cast as _)
+    })
+});
+
 #[allow(clippy::fn_to_numeric_cast)]
 pub(crate) fn rt_func_imports(
     execution_context: *mut ExecutionContext,
@@ -525,6 +767,27 @@ pub(crate) fn rt_func_imports(
             },
             func: &DATA_DROP_RT_FUNC,
         },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "memory_atomic_wait32".to_string(),
+            },
+            func: &MEMORY_ATOMIC_WAIT32_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "memory_atomic_wait64".to_string(),
+            },
+            func: &MEMORY_ATOMIC_WAIT64_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "memory_atomic_notify".to_string(),
+            },
+            func: &MEMORY_ATOMIC_NOTIFY_RT_FUNC,
+        },
         FunctionDependency {
             name: DependencyName {
                 module: module_name.clone(),
@@ -532,6 +795,20 @@ pub(crate) fn rt_func_imports(
             },
             func: &INDIRECT_CALL_RT_FUNC,
         },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "ref_call".to_string(),
+            },
+            func: &REF_CALL_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "compile_function".to_string(),
+            },
+            func: &COMPILE_FUNCTION_RT_FUNC,
+        },
         FunctionDependency {
             name: DependencyName {
                 module: module_name.clone(),
@@ -588,6 +865,160 @@ pub(crate) fn rt_func_imports(
             },
             func: &ELEM_DROP_RT_FUNC,
         },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "exception_throw".to_string(),
+            },
+            func: &EXCEPTION_THROW_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "exception_throw_ref".to_string(),
+            },
+            func: &EXCEPTION_THROW_REF_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "exception_pending".to_string(),
+            },
+            func: &EXCEPTION_PENDING_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "exception_matches".to_string(),
+            },
+            func: &EXCEPTION_MATCHES_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "exception_catch".to_string(),
+            },
+            func: &EXCEPTION_CATCH_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_struct_new".to_string(),
+            },
+            func: &GC_STRUCT_NEW_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_struct_get".to_string(),
+            },
+            func: &GC_STRUCT_GET_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_struct_set".to_string(),
+            },
+            func: &GC_STRUCT_SET_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_new".to_string(),
+            },
+            func: &GC_ARRAY_NEW_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_new_default".to_string(),
+            },
+            func: &GC_ARRAY_NEW_DEFAULT_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_new_fixed".to_string(),
+            },
+            func: &GC_ARRAY_NEW_FIXED_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_new_data".to_string(),
+            },
+            func: &GC_ARRAY_NEW_DATA_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_new_elem".to_string(),
+            },
+            func: &GC_ARRAY_NEW_ELEM_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_get".to_string(),
+            },
+            func: &GC_ARRAY_GET_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_set".to_string(),
+            },
+            func: &GC_ARRAY_SET_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_len".to_string(),
+            },
+            func: &GC_ARRAY_LEN_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_fill".to_string(),
+            },
+            func: &GC_ARRAY_FILL_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_copy".to_string(),
+            },
+            func: &GC_ARRAY_COPY_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_init_data".to_string(),
+            },
+            func: &GC_ARRAY_INIT_DATA_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_array_init_elem".to_string(),
+            },
+            func: &GC_ARRAY_INIT_ELEM_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_ref_test".to_string(),
+            },
+            func: &GC_REF_TEST_RT_FUNC,
+        },
+        FunctionDependency {
+            name: DependencyName {
+                module: module_name.clone(),
+                name: "gc_ref_cast".to_string(),
+            },
+            func: &GC_REF_CAST_RT_FUNC,
+        },
     ]
 }
 
@@ -603,6 +1034,7 @@ pub(crate) struct DependencyStore<'a> {
     pub(crate) globals: Vec<RTGlobalImport>,
     pub(crate) tables: Vec<RTTableImport>,
     pub(crate) memories: Vec<RTMemoryImport>,
+    pub(crate) tags: Vec<TagInstance>,
 }
 
 #[derive(Debug)]
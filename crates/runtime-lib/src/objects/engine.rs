@@ -1,9 +1,16 @@
-use crate::{objects::execution_context::TrapUnwrap, RuntimeError};
+use crate::{
+    objects::execution_context::{trap_on_err, TrapUnwrap},
+    RuntimeError,
+};
 use interpreter::{Interpreter, InterpreterError};
-use module::objects::{module::Module as WasmModule, value::ValueRaw};
-use runtime_interface::RawPointer;
+use module::{
+    objects::{module::Module as WasmModule, value::ValueRaw},
+    passes::PassManager,
+};
+use runtime_interface::{ExecutionContext, RawPointer};
 use std::{
     cell::RefCell,
+    num::NonZeroUsize,
     ops::{Deref, DerefMut},
     rc::Rc,
 };
@@ -35,6 +42,51 @@ pub enum EngineError {
     TranslationError(#[from] llvm_gen::TranslationError),
 }
 
+/// When an engine parses and compiles the functions of its module.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CompilationMode {
+    /// all functions are parsed, validated and compiled in [`WasmEngine::init`]
+    #[default]
+    Eager,
+    /// functions are parsed on their first call, invalid functions only trap once they are called. LLVM compiles
+    /// each function into a module of its own and doesn't use its `llvm_cache`
+    Lazy,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct EngineConfig {
+    pub compilation_mode: CompilationMode,
+    /// run the optimization passes of [`module::passes`] on the IR of the functions, `None` uses the engine's
+    /// default: on for the interpreter and off for LLVM, which optimizes the functions itself
+    pub optimize_ir: Option<bool>,
+    /// verify the IR of functions after parsing and after each optimization pass, which debug builds always do.
+    /// LLVM only builds IR with `optimize_ir` or more than one of the `parser_threads`.
+    pub verify_ir: bool,
+    /// worker threads decoding and validating function bodies, `None` uses the available parallelism
+    pub parser_threads: Option<NonZeroUsize>,
+    /// optimization passes and code generation level LLVM compiles the module with
+    #[cfg(feature = "llvm")]
+    pub llvm_opt_level: llvm_gen::OptLevel,
+    /// directory in which LLVM keeps compiled modules to reuse them instead of compiling them again
+    #[cfg(feature = "llvm")]
+    pub llvm_cache: Option<llvm_gen::aot::CompilationCache>,
+}
+
+impl EngineConfig {
+    /// Loader parsing function bodies with the configured threads and IR verification
+    pub fn function_loader(&self) -> parser::FunctionLoader {
+        let loader = parser::FunctionLoader::default().with_ir_verification(self.verify_ir);
+        match self.parser_threads {
+            Some(threads) => loader.with_threads(threads),
+            None => loader,
+        }
+    }
+
+    fn ir_passes(&self) -> PassManager {
+        PassManager::default().with_verification(self.verify_ir)
+    }
+}
+
 #[allow(private_interfaces)]
 pub struct Engine(Box<dyn WasmEngine>);
 
@@ -55,6 +107,10 @@ pub trait WasmEngine {
     /// Get a raw function pointer that follows the engine backend's internal calling convention
     fn get_internal_function_ptr(&self, function_idx: FuncIdx) -> Result<RawPointer, EngineError>;
 
+    /// Compile a function of a lazily compiled module on its first call and get a raw pointer to its code, which
+    /// follows the engine backend's internal calling convention
+    fn compile_function(&mut self, function_idx: FuncIdx) -> Result<RawPointer, EngineError>;
+
     /// Get a typed function pointer that follows the Boundary calling convention
     fn get_external_function_ptr(
         &self,
@@ -68,12 +124,27 @@ pub trait WasmEngine {
 impl Engine {
     #[cfg(feature = "llvm")]
     pub fn llvm() -> Result<Self, EngineError> {
-        Ok(Self(Box::new(llvm_engine_impl::LLVMEngine::new()?)))
+        Self::llvm_with_config(EngineConfig::default())
+    }
+    #[cfg(feature = "llvm")]
+    pub fn llvm_with_opt_level(opt_level: llvm_gen::OptLevel) -> Result<Self, EngineError> {
+        Self::llvm_with_config(EngineConfig {
+            llvm_opt_level: opt_level,
+            ..Default::default()
+        })
+    }
+    #[cfg(feature = "llvm")]
+    pub fn llvm_with_config(config: EngineConfig) -> Result<Self, EngineError> {
+        Ok(Self(Box::new(llvm_engine_impl::LLVMEngine::new(config)?)))
     }
     #[cfg(feature = "interp")]
     pub fn interpreter() -> Result<Self, EngineError> {
+        Self::interpreter_with_config(EngineConfig::default())
+    }
+    #[cfg(feature = "interp")]
+    pub fn interpreter_with_config(config: EngineConfig) -> Result<Self, EngineError> {
         Ok(Self(Box::new(
-            interpreter_engine_impl::InterpreterEngine::new()?,
+            interpreter_engine_impl::InterpreterEngine::new(config)?,
         )))
     }
 }
@@ -95,27 +166,117 @@ impl DerefMut for Engine {
 #[cfg(feature = "llvm")]
 mod llvm_engine_impl {
     use super::*;
+    use crate::helper::signals::SignalHandler;
+    use module::objects::function::{Function as WasmFunction, FunctionSource};
     use wasm_types::GlobalIdx;
 
     pub(crate) struct LLVMEngine {
         executor: Option<llvm_gen::JITExecutor>,
         wasm_module: Option<Rc<WasmModule>>,
+        config: EngineConfig,
+        /// start addresses of the compiled functions, registered to name the function a trap occurs in
+        function_addrs: Vec<usize>,
     }
 
     impl LLVMEngine {
-        pub(crate) fn new() -> Result<Self, EngineError> {
+        pub(crate) fn new(config: EngineConfig) -> Result<Self, EngineError> {
             Ok(Self {
                 executor: None,
                 wasm_module: None,
+                config,
+                function_addrs: Vec::new(),
             })
         }
+
+        fn register_functions(&mut self) -> Result<(), EngineError> {
+            let wasm_module = self.wasm_module.as_ref().unwrap();
+            let executor = self.executor.as_ref().unwrap();
+            let mut functions = Vec::new();
+            for (function_idx, function) in wasm_module.meta.functions.iter().enumerate() {
+                if matches!(function.source, FunctionSource::Import(_)) {
+                    continue;
+                }
+                let function_idx = function_idx as FuncIdx;
+                let func_name =
+                    llvm_gen::build_llvm_function_name(function_idx, &wasm_module.meta, false);
+                let addr = executor.get_symbol_addr(&func_name)?.as_ptr() as usize;
+                functions.push((
+                    addr,
+                    WasmFunction::debug_function_name(function_idx, &wasm_module.meta),
+                ));
+            }
+            SignalHandler::deregister_jit_functions(&self.function_addrs);
+            self.function_addrs = functions.iter().map(|(addr, _)| *addr).collect();
+            SignalHandler::register_jit_functions(functions);
+            Ok(())
+        }
+    }
+
+    impl Drop for LLVMEngine {
+        fn drop(&mut self) {
+            SignalHandler::deregister_jit_functions(&self.function_addrs);
+        }
     }
 
     impl WasmEngine for LLVMEngine {
         fn init(&mut self, wasm_module: Rc<WasmModule>) -> Result<(), EngineError> {
             self.wasm_module = Some(wasm_module.clone());
-            self.executor = Some(llvm_gen::JITExecutor::new(wasm_module)?);
-            Ok(())
+            let loader = self.config.function_loader();
+            let optimize_ir = self.config.optimize_ir.unwrap_or(false);
+            let opt_level = &self.config.llvm_opt_level;
+            let executor = if self.config.compilation_mode == CompilationMode::Lazy {
+                let ir_passes = optimize_ir.then(|| self.config.ir_passes());
+                llvm_gen::JITExecutor::lazy(wasm_module, loader, ir_passes, opt_level)?
+            } else {
+                let ir_passes = self.config.ir_passes();
+                let ir_passes = optimize_ir.then_some(&ir_passes);
+                match (&self.config.llvm_cache, ir_passes) {
+                    (Some(cache), ir_passes) => llvm_gen::JITExecutor::with_cache(
+                        wasm_module,
+                        loader,
+                        ir_passes,
+                        opt_level,
+                        cache,
+                    )?,
+                    (None, Some(passes)) => llvm_gen::JITExecutor::with_ir_passes(
+                        wasm_module,
+                        loader,
+                        passes,
+                        opt_level,
+                    )?,
+                    (None, None) => {
+                        llvm_gen::JITExecutor::with_loader(wasm_module, loader, opt_level)?
+                    }
+                }
+            };
+            let is_lazy = executor.is_lazy();
+            self.executor = Some(executor);
+            // lazily compiled functions are registered once they are compiled, precompiled modules as a whole
+            if is_lazy {
+                return Ok(());
+            }
+            self.register_functions()
+        }
+
+        fn compile_function(&mut self, function_idx: FuncIdx) -> Result<RawPointer, EngineError> {
+            let wasm_module = self
+                .wasm_module
+                .as_ref()
+                .ok_or(EngineError::EngineUninitialized)?;
+            let code = self
+                .executor
+                .as_mut()
+                .unwrap()
+                .compile_function(function_idx)?;
+            let addr = code.as_ptr() as usize;
+            if !self.function_addrs.contains(&addr) {
+                self.function_addrs.push(addr);
+                SignalHandler::register_jit_functions([(
+                    addr,
+                    WasmFunction::debug_function_name(function_idx, &wasm_module.meta),
+                )]);
+            }
+            Ok(code)
         }
 
         fn get_global_value(&self, global_idx: GlobalIdx) -> Result<ValueRaw, EngineError> {
@@ -158,11 +319,17 @@ mod llvm_engine_impl {
             &self,
             function_idx: FuncIdx,
         ) -> Result<RawPointer, EngineError> {
+            let wasm_module = self
+                .wasm_module
+                .as_ref()
+                .ok_or(EngineError::EngineUninitialized)?;
+            let func_name =
+                llvm_gen::build_llvm_function_name(function_idx, &wasm_module.meta, false);
             Ok(self
                 .executor
                 .as_ref()
                 .unwrap()
-                .get_symbol_addr(&function_idx.to_string())?)
+                .get_symbol_addr(&func_name)?)
         }
 
         fn set_global_addr(&mut self, global_idx: GlobalIdx, addr: RawPointer) {
@@ -182,22 +349,27 @@ mod interpreter_engine_impl {
     use crate::objects::functions::{CalleeCtxt, HostFuncRawContainer};
 
     use super::*;
-    use interpreter::Interpreter;
+    use interpreter::{FunctionIRStore, Interpreter};
 
-    use module::{instructions::FunctionIR, objects::value::Value};
+    use module::{
+        instructions::FunctionIR,
+        objects::{function::Function as WasmFunction, value::Value},
+    };
     use runtime_interface::ExecutionContext;
     use wasm_types::FuncType;
 
     pub(crate) struct InterpreterEngine {
         interpreter: Rc<RefCell<Interpreter>>,
         module: Option<Rc<WasmModule>>,
+        config: EngineConfig,
     }
 
     impl InterpreterEngine {
-        pub(crate) fn new() -> Result<Self, EngineError> {
+        pub(crate) fn new(config: EngineConfig) -> Result<Self, EngineError> {
             Ok(Self {
                 interpreter: Rc::new(RefCell::new(Interpreter::new())),
                 module: None,
+                config,
             })
         }
     }
@@ -205,11 +377,25 @@ mod interpreter_engine_impl {
     impl WasmEngine for InterpreterEngine {
         // this is to set the module to be run so it does not have to be provided when the Engine is created
         fn init(&mut self, wasm_module: Rc<WasmModule>) -> Result<(), EngineError> {
-            wasm_module.load_all_functions(parser::FunctionLoader)?;
             self.interpreter
                 .borrow_mut()
                 .set_module(wasm_module.clone());
             self.module = Some(wasm_module.clone());
+            let passes = self
+                .config
+                .optimize_ir
+                .unwrap_or(true)
+                .then(|| self.config.ir_passes());
+            if self.config.compilation_mode == CompilationMode::Lazy {
+                let mut ir =
+                    FunctionIRStore::lazy(&wasm_module).with_loader(self.config.function_loader());
+                if let Some(passes) = passes {
+                    ir = ir.with_passes(passes);
+                }
+                self.interpreter.borrow_mut().set_ir(Rc::new(ir));
+                return Ok(());
+            }
+            wasm_module.load_all_functions(self.config.function_loader())?;
 
             let artifact_lock = self
                 .module
@@ -223,9 +409,17 @@ mod interpreter_engine_impl {
                 None => return Err(EngineError::InterpreterError(InterpreterError::NoIR)),
             };
             let ir_read = ir_locked.read().unwrap();
-            let ir: &Vec<FunctionIR> = ir_read.downcast_ref().unwrap();
+            let mut ir: Vec<FunctionIR> =
+                ir_read.downcast_ref::<Vec<FunctionIR>>().unwrap().clone();
+            if let Some(passes) = passes {
+                passes
+                    .run_all(&mut ir)
+                    .map_err(InterpreterError::InvalidIR)?;
+            }
 
-            self.interpreter.borrow_mut().set_ir(Rc::new(ir.clone()));
+            self.interpreter
+                .borrow_mut()
+                .set_ir(Rc::new(FunctionIRStore::eager(ir)));
 
             Ok(())
         }
@@ -286,13 +480,8 @@ mod interpreter_engine_impl {
 
                 exec_ctx.recursion_size += 1;
 
-                let func_name = exec_ctx
-                    .wasm_module
-                    .meta
-                    .exports
-                    .find_function_name(*fn_idx)
-                    .expect(&format!("function not found: {fn_idx}"))
-                    .to_string();
+                let func_name =
+                    WasmFunction::debug_function_name(*fn_idx, &exec_ctx.wasm_module.meta);
 
                 log::debug!("Wrapper function is for {func_name}");
 
@@ -351,6 +540,11 @@ mod interpreter_engine_impl {
             Ok(non_null_ptr)
         }
 
+        fn compile_function(&mut self, function_idx: FuncIdx) -> Result<RawPointer, EngineError> {
+            // lazily parsed functions are loaded by the interpreter's IR store, there is nothing to compile
+            self.get_internal_function_ptr(function_idx)
+        }
+
         fn get_global_value(&self, global_idx: GlobalIdx) -> Result<ValueRaw, EngineError> {
             self.interpreter
                 .borrow_mut()
@@ -366,6 +560,13 @@ mod interpreter_engine_impl {
     }
 }
 
+#[no_mangle]
+pub extern "C" fn compile_function(ctxt: &mut ExecutionContext, func_idx: FuncIdx) -> RawPointer {
+    let engine = unsafe { &mut *(ctxt.engine as *mut Engine) };
+    let res = engine.compile_function(func_idx);
+    trap_on_err(ctxt, res)
+}
+
 impl From<InterpreterError> for RuntimeError {
     fn from(interp_err: InterpreterError) -> Self {
         match interp_err {
@@ -374,3 +575,120 @@ impl From<InterpreterError> for RuntimeError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sugar::module_from_buf, Cluster, ClusterConfig, Linker};
+    use module::objects::value::{Number, Value};
+
+    const MODULE: &str = r#"
+        (module
+          (type $t (func (result i32)))
+          (table $funcs 1 funcref)
+          (table $typed 1 (ref null $t))
+          (elem (table $funcs) (i32.const 0) func $indirect)
+          (elem (table $typed) (i32.const 0) (ref null $t) (ref.func $from_table))
+          (func $indirect (type $t) (i32.const 1))
+          (func $from_table (type $t) (i32.const 2))
+          (func $invalid (type $t) (i32.add))
+          (func (export "call_indirect") (result i32)
+            (call_indirect $funcs (type $t) (i32.const 0)))
+          (func (export "table_get") (result i32)
+            (call_ref $t (table.get $typed (i32.const 0))))
+          (func (export "invalid") (result i32) (call $invalid)))
+    "#;
+
+    /// Runs the exports of [`MODULE`] on a lazily compiling `engine` and returns the error of the invalid function
+    fn run_lazily(mut engine: Engine) -> String {
+        let module = Rc::new(module_from_buf(MODULE.as_bytes().to_vec()).unwrap());
+        // the invalid function is never parsed during instantiation
+        engine.init(module.clone()).unwrap();
+        let cluster = Cluster::new(ClusterConfig::default());
+        let linker = Linker::new().bind_to(&cluster);
+        let instance = linker.instantiate_and_link(module, engine).unwrap();
+
+        for (export, expected) in [("call_indirect", 1), ("table_get", 2)] {
+            let result = instance.get_export_by_name(export).unwrap().call(&[]);
+            assert_eq!(
+                result.unwrap(),
+                vec![Value::Number(Number::I32(expected))],
+                "{export}"
+            );
+        }
+        let err = instance
+            .get_export_by_name("invalid")
+            .unwrap()
+            .call(&[])
+            .unwrap_err();
+        err.to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "interp")]
+    fn lazy_functions_are_parsed_when_first_reached() {
+        let err = run_lazily(
+            Engine::interpreter_with_config(EngineConfig {
+                compilation_mode: CompilationMode::Lazy,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        assert!(err.contains("Parser error"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn lazy_functions_are_compiled_when_first_called() {
+        let err = run_lazily(
+            Engine::llvm_with_config(EngineConfig {
+                compilation_mode: CompilationMode::Lazy,
+                ..Default::default()
+            })
+            .unwrap(),
+        );
+        assert!(err.contains("LLVM execution error"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "interp")]
+    fn eager_compilation_rejects_invalid_functions() {
+        let module = Rc::new(module_from_buf(MODULE.as_bytes().to_vec()).unwrap());
+        let mut engine = Engine::interpreter().unwrap();
+        assert!(engine.init(module).is_err());
+    }
+
+    /// Message of a trap raised in a function that is only named by the name section
+    fn trap_message(mut engine: Engine) -> String {
+        let text = r#"
+            (module
+              (func $boom (param i32) (result i32)
+                (if (local.get 0) (then (unreachable)))
+                (local.get 0))
+              (func (export "run") (result i32) (call $boom (i32.const 1))))
+        "#;
+        let module = Rc::new(module_from_buf(text.as_bytes().to_vec()).unwrap());
+        engine.init(module.clone()).unwrap();
+        let cluster = Cluster::new(ClusterConfig::default());
+        let instance = Linker::new()
+            .bind_to(&cluster)
+            .instantiate_and_link(module, engine)
+            .unwrap();
+        let run = instance.get_export_by_name("run").unwrap();
+        run.call(&[]).unwrap_err().to_string()
+    }
+
+    #[test]
+    #[cfg(feature = "interp")]
+    fn interpreter_traps_name_the_function() {
+        let err = trap_message(Engine::interpreter().unwrap());
+        assert!(err.contains("(in function boom)"), "{err}");
+    }
+
+    #[test]
+    #[cfg(feature = "llvm")]
+    fn llvm_traps_name_the_function() {
+        let err = trap_message(Engine::llvm().unwrap());
+        assert!(err.contains("(in function boom)"), "{err}");
+    }
+}
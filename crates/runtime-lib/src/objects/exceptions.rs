@@ -0,0 +1,186 @@
+use crate::{
+    objects::{
+        execution_context::{trap_on_err, ExecutionContextWrapper},
+        gc,
+    },
+    Cluster, RuntimeError,
+};
+use core::slice;
+use module::objects::value::{Value, ValueRaw};
+use runtime_interface::ExecutionContext;
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use wasm_types::{FuncType, TagIdx};
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExceptionError {
+    #[error("Tag index out of bounds")]
+    TagIndexOutOfBounds,
+    #[error("Reference is null.")]
+    NullDeref,
+    #[error("Invalid reference to an exception")]
+    InvalidReference,
+}
+
+// raw representation of a null exnref
+const NULL_EXNREF: u64 = u64::MAX;
+
+static NEXT_TAG_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    // `exnref` to the exception currently unwinding the wasm stack of this thread, and the cluster owning it
+    static PENDING_EXCEPTION: Cell<Option<(u64, *const Cluster)>> = const { Cell::new(None) };
+}
+
+/// Runtime identity of an exception tag. Imported tags share the instance of the exporting module, so two tags
+/// match iff their ids are equal.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TagInstance {
+    pub(crate) id: u64,
+    pub(crate) ty: FuncType,
+}
+
+impl TagInstance {
+    pub(crate) fn new(ty: FuncType) -> Self {
+        Self {
+            id: NEXT_TAG_ID.fetch_add(1, Ordering::Relaxed),
+            ty,
+        }
+    }
+}
+
+/// A thrown exception, allocated on the heap of the cluster. `exnref`s refer to it like references to structs and
+/// arrays, it is collected once none of them is reachable.
+pub(crate) struct ExceptionObject {
+    pub(crate) tag: TagInstance,
+    pub(crate) payload: Vec<ValueRaw>,
+}
+
+impl ExceptionObject {
+    pub(crate) fn payload_values(&self) -> Vec<Value> {
+        self.tag
+            .ty
+            .params_iter()
+            .zip(self.payload.iter())
+            .map(|(ty, val)| Value::from_raw(*val, ty))
+            .collect()
+    }
+}
+
+/// Takes the exception that unwound the whole wasm stack, if any, and turns it into an error for the host.
+pub(crate) fn take_uncaught_exception() -> Option<RuntimeError> {
+    PENDING_EXCEPTION.take().map(|(exn, cluster)| {
+        let payload = unsafe { &*cluster }
+            .gc_heap()
+            .get_exception(exn)
+            .map(ExceptionObject::payload_values)
+            .unwrap_or_default();
+        RuntimeError::UncaughtException(payload)
+    })
+}
+
+pub(crate) fn clear_pending_exception() {
+    PENDING_EXCEPTION.set(None);
+}
+
+/// Reports the pending exception of the calling thread as a root of the heap.
+pub(crate) fn scan_pending_exception(visit: &mut dyn FnMut(u64)) {
+    if let Some((exn, _)) = PENDING_EXCEPTION.get() {
+        visit(exn)
+    }
+}
+
+fn cluster(ctxt: &ExecutionContext) -> &Cluster {
+    unsafe { &*(ctxt.cluster as *const Cluster) }
+}
+
+fn exception_throw_impl(
+    ctxt: &mut ExecutionContext,
+    tag_idx: TagIdx,
+    payload: *const ValueRaw,
+) -> Result<(), ExceptionError> {
+    let tag = *ExecutionContextWrapper(ctxt)
+        .get_tags()
+        .get(tag_idx as usize)
+        .ok_or(ExceptionError::TagIndexOutOfBounds)?;
+    let payload = match tag.ty.num_params() {
+        0 => Vec::new(),
+        len => unsafe { slice::from_raw_parts(payload, len) }.to_vec(),
+    };
+    let cluster = cluster(ctxt);
+    let exn = gc::alloc_exception(cluster, ExceptionObject { tag, payload });
+    PENDING_EXCEPTION.set(Some((exn, cluster)));
+    Ok(())
+}
+
+#[no_mangle]
+extern "C" fn exception_throw(
+    ctxt: &mut ExecutionContext,
+    tag_idx: TagIdx,
+    payload: *const ValueRaw,
+) {
+    let res = exception_throw_impl(ctxt, tag_idx, payload);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn exception_throw_ref(ctxt: &mut ExecutionContext, exn: u64) {
+    if exn == NULL_EXNREF {
+        trap_on_err::<(), _>(ctxt, Err(ExceptionError::NullDeref));
+    }
+    PENDING_EXCEPTION.set(Some((exn, cluster(ctxt))));
+}
+
+#[no_mangle]
+extern "C" fn exception_pending(_ctxt: &mut ExecutionContext) -> u32 {
+    PENDING_EXCEPTION.get().is_some() as u32
+}
+
+fn exception_matches_impl(
+    ctxt: &mut ExecutionContext,
+    tag_idx: TagIdx,
+    exn: u64,
+) -> Result<bool, ExceptionError> {
+    let tag = *ExecutionContextWrapper(ctxt)
+        .get_tags()
+        .get(tag_idx as usize)
+        .ok_or(ExceptionError::TagIndexOutOfBounds)?;
+    let heap = cluster(ctxt).gc_heap();
+    let exception = heap
+        .get_exception(exn)
+        .map_err(|_| ExceptionError::InvalidReference)?;
+    Ok(tag.id == exception.tag.id)
+}
+
+#[no_mangle]
+extern "C" fn exception_matches(ctxt: &mut ExecutionContext, tag_idx: TagIdx) -> u32 {
+    let Some((exn, _)) = PENDING_EXCEPTION.get() else {
+        return 0;
+    };
+    let res = exception_matches_impl(ctxt, tag_idx, exn);
+    trap_on_err(ctxt, res) as u32
+}
+
+/// Catches the pending exception, writing its payload to `payload_out` unless it is null and returning an
+/// `exnref` to it.
+#[no_mangle]
+extern "C" fn exception_catch(ctxt: &mut ExecutionContext, payload_out: *mut ValueRaw) -> u64 {
+    let Some((exn, _)) = PENDING_EXCEPTION.take() else {
+        return NULL_EXNREF;
+    };
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get_exception(exn)
+        .map(|exception| {
+            let payload = &exception.payload;
+            if !payload.is_empty() && !payload_out.is_null() {
+                unsafe { slice::from_raw_parts_mut(payload_out, payload.len()) }
+                    .copy_from_slice(payload);
+            }
+        })
+        .map_err(|_| ExceptionError::InvalidReference);
+    trap_on_err(ctxt, res);
+    exn
+}
@@ -1,13 +1,15 @@
 use crate::error::RuntimeError;
+use crate::objects::exceptions::TagInstance;
 use crate::objects::tables::{TableError, TableInstance, TableItem};
 use crate::Engine;
 use cee_scape::SigJmpBuf;
 use core::slice;
+use module::objects::value::{Reference, Value, ValueRaw};
 use runtime_interface::RawPointer;
 use std::cell::RefCell;
 use std::fmt::Display;
 use std::ptr::null;
-use wasm_types::{TableIdx, TypeIdx};
+use wasm_types::{HeapType, RefType, TableIdx, TypeIdx};
 
 thread_local! {
     static TRAP_RETURN: RefCell<SigJmpBuf> = const { RefCell::new(null()) };
@@ -35,10 +37,25 @@ impl ExecutionContextWrapper<'_> {
         }
     }
 
+    pub(crate) fn get_tags(&mut self) -> &[TagInstance] {
+        unsafe { slice::from_raw_parts(self.0.tags_ptr as *const TagInstance, self.0.tags_len) }
+    }
+
     pub(crate) fn set_trap_return_point(buf: SigJmpBuf) {
         TRAP_RETURN.replace(buf);
     }
 
+    /// Resolves the function referenced by `func_ref`, a function index of the current module.
+    pub(crate) fn ref_call(&mut self, func_ref: u64) -> Result<RawPointer, RuntimeError> {
+        if func_ref == ValueRaw::from(Value::Reference(Reference::Null)).as_u64() {
+            return Err(TableError::NullDeref.into());
+        }
+        let engine = unsafe { &mut *(self.0.engine as *mut Engine) };
+        Ok(engine
+            .get_internal_function_ptr(ValueRaw::from(func_ref).as_funcref())
+            .map_err(TableError::from)?)
+    }
+
     pub(crate) fn indirect_call(
         &mut self,
         table_idx: TableIdx,
@@ -61,7 +78,13 @@ impl ExecutionContextWrapper<'_> {
             } => {
                 let expected_function_type = &wasm_module.meta.function_types[ty_idx as usize];
                 let actual_function_type = &wasm_module.meta.function_types[*func_type as usize];
-                if expected_function_type != actual_function_type {
+                let types = &wasm_module.meta.types;
+                // declared subtypes of the expected type are accepted as well
+                if !HeapType::Concrete(*func_type).matches(
+                    types,
+                    &HeapType::Concrete(ty_idx),
+                    types,
+                ) {
                     return Err(TableError::TableFunctionTypeMismatch {
                         expected: *expected_function_type,
                         actual: *actual_function_type,
@@ -87,6 +110,16 @@ impl ExecutionContextWrapper<'_> {
                 log::debug!("Indirect call on an external function reference");
                 Ok(func_ptr.unwrap())
             }
+            TableItem::ExceptionReference(_) => Err(TableError::TableElementTypeMismatch {
+                expected: RefType::FunctionReference,
+                actual: RefType::ExceptionReference,
+            }
+            .into()),
+            TableItem::GcReference(_) => Err(TableError::TableElementTypeMismatch {
+                expected: RefType::FunctionReference,
+                actual: RefType::new(HeapType::Any, true),
+            }
+            .into()),
             TableItem::Null => Err(TableError::NullDeref.into()),
         }
     }
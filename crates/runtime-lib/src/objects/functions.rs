@@ -4,8 +4,12 @@ use crate::{
         types::{WasmReturnType, WasmType, WasmTypeList},
         utils::macro_invoke_for_each_function_signature,
     },
-    objects::execution_context::ExecutionContextWrapper,
-    RuntimeError,
+    objects::{
+        exceptions::{clear_pending_exception, take_uncaught_exception},
+        execution_context::ExecutionContextWrapper,
+        gc,
+    },
+    Cluster, RuntimeError,
 };
 use cee_scape::call_with_sigsetjmp;
 use core::ffi;
@@ -166,6 +170,21 @@ impl Function {
             .map(ValueRaw::from)
             .collect::<Vec<ValueRaw>>();
 
+        // the thread takes part in the garbage collections of the cluster while it executes its wasm code
+        let gc_safepoint = match &self.0 {
+            FunctionKind::Wasm(_, ctxt, _) => {
+                let cluster = unsafe { &*((*ctxt.execution_context).cluster as *const Cluster) };
+                Some(cluster.gc_safepoint()).filter(|safepoint| safepoint.enter())
+            }
+            _ => None,
+        };
+
+        // the frames of wasm code start below this one, their references are roots of the heap
+        let stack_base = 0u8;
+        let outermost_call =
+            gc::enter_wasm_stack(std::hint::black_box(&stack_base) as *const u8 as usize);
+        let gc_roots_depth = runtime_interface::gc_roots_depth();
+
         let jmp_res = call_with_sigsetjmp(true, |jmp_buf| {
             ExecutionContextWrapper::set_trap_return_point(jmp_buf);
             SignalHandler::set_thread_executing_wasm();
@@ -177,9 +196,23 @@ impl Function {
             SignalHandler::unset_thread_executing_wasm();
             0
         });
+        if outermost_call {
+            gc::leave_wasm_stack();
+        }
+        // the uncaught exception is read while it is still a root of the heap
+        let uncaught_exception = (jmp_res == 0).then(take_uncaught_exception).flatten();
+        if let Some(gc_safepoint) = gc_safepoint {
+            gc_safepoint.leave();
+        }
         if jmp_res != 0 {
+            // traps skip the deregistration of the frames they unwind
+            runtime_interface::truncate_gc_roots(gc_roots_depth);
+            clear_pending_exception();
             return Err(ExecutionContextWrapper::take_trap());
         }
+        if let Some(e) = uncaught_exception {
+            return Err(e);
+        }
         Ok(ret_values
             .iter()
             .zip(ty.results_iter())
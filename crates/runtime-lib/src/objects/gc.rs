@@ -0,0 +1,1442 @@
+use crate::{
+    objects::{
+        exceptions::{self, ExceptionObject},
+        execution_context::trap_on_err,
+    },
+    Cluster,
+};
+use core::slice;
+use module::objects::{
+    element::ElementInit,
+    expression::eval_constant_ops,
+    module::Module as WasmModule,
+    value::{gc, ConstantAllocation, ConstantValue, ValueRaw},
+};
+use runtime_interface::ExecutionContext;
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    rc::Rc,
+    sync::{Condvar, Mutex, MutexGuard},
+    thread::ThreadId,
+    time::Duration,
+};
+use wasm_types::{
+    CompositeType, DataIdx, ElemIdx, FieldType, HeapType, NumType, StorageType, SubType, TypeIdx,
+    ValType,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum GcError {
+    #[error("Reference is null.")]
+    NullDeref,
+    #[error("Invalid reference to a heap object")]
+    InvalidReference,
+    #[error("Type with id {0} is not a struct or array type")]
+    InvalidType(TypeIdx),
+    #[error("Invalid heap type {0}")]
+    InvalidHeapType(i64),
+    #[error("Array access out of bounds")]
+    ArrayAccessOutOfBounds,
+    #[error("Data access out of bounds")]
+    DataAccessOutOfBounds,
+    #[error("Elem access out of bounds")]
+    ElemAccessOutOfBounds,
+    #[error("Cast failure")]
+    CastFailure,
+    #[error("Out of memory: array of {0} elements is too large")]
+    OutOfMemory(u32),
+}
+
+/// collections are triggered once this many fields are allocated
+const MIN_THRESHOLD: usize = 1 << 16;
+/// larger arrays trap instead of exhausting the memory of the host
+const MAX_ARRAY_LEN: u32 = 1 << 27;
+
+/// how long a collecting thread waits for the other threads of the cluster to reach a safepoint
+const SAFEPOINT_TIMEOUT: Duration = Duration::from_millis(10);
+
+thread_local! {
+    // highest address of the native stack used by wasm code on this thread, set while wasm code is executing
+    static STACK_BASE: Cell<Option<usize>> = const { Cell::new(None) };
+    // safepoints of the clusters this thread is a mutator of
+    static MUTATOR_OF: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A struct or array, allocated by `struct.new` / `array.new` on the heap of the cluster. Fields are stored as
+/// their raw 64-bit values, packed fields are truncated to their width.
+pub(crate) struct GcObject {
+    /// the module defining `type_idx`
+    module: Rc<WasmModule>,
+    type_idx: TypeIdx,
+    fields: Vec<u64>,
+}
+
+impl GcObject {
+    fn sub_type(&self) -> &SubType {
+        &self.module.meta.types[self.type_idx as usize]
+    }
+
+    fn field_type(&self, field_idx: u32) -> Result<FieldType, GcError> {
+        match &self.sub_type().composite_type {
+            CompositeType::Struct(fields) => fields
+                .get(field_idx as usize)
+                .copied()
+                .ok_or(GcError::InvalidReference),
+            CompositeType::Array(field) => Ok(*field),
+            CompositeType::Func(_) => Err(GcError::InvalidType(self.type_idx)),
+        }
+    }
+
+    fn get(&self, idx: u32, signed: bool) -> Result<u64, GcError> {
+        let value = *self
+            .fields
+            .get(idx as usize)
+            .ok_or(GcError::ArrayAccessOutOfBounds)?;
+        Ok(unpack(self.field_type(idx)?.storage_type, value, signed))
+    }
+
+    fn set(&mut self, idx: u32, value: u64) -> Result<(), GcError> {
+        let storage_type = self.field_type(idx)?.storage_type;
+        let field = self
+            .fields
+            .get_mut(idx as usize)
+            .ok_or(GcError::ArrayAccessOutOfBounds)?;
+        *field = pack(storage_type, value);
+        Ok(())
+    }
+
+    fn range(&self, offset: u32, len: u32) -> Result<std::ops::Range<usize>, GcError> {
+        let end = offset
+            .checked_add(len)
+            .filter(|end| *end as usize <= self.fields.len())
+            .ok_or(GcError::ArrayAccessOutOfBounds)?;
+        Ok(offset as usize..end as usize)
+    }
+
+    /// Reports all objects referenced by fields of this object.
+    fn trace(&self, visit: &mut impl FnMut(u64)) {
+        match &self.sub_type().composite_type {
+            CompositeType::Struct(fields) => {
+                for (field, value) in fields.iter().zip(self.fields.iter()) {
+                    if is_reference(field) {
+                        visit(*value)
+                    }
+                }
+            }
+            CompositeType::Array(field) if is_reference(field) => {
+                self.fields.iter().for_each(|value| visit(*value))
+            }
+            _ => {}
+        }
+    }
+}
+
+/// An object on the heap of a cluster.
+enum HeapObject {
+    /// a struct or array
+    Composite(GcObject),
+    /// a thrown exception, referred to by `exnref`s
+    Exception(ExceptionObject),
+}
+
+impl HeapObject {
+    /// number of fields the object accounts for in the heap size
+    fn size(&self) -> usize {
+        match self {
+            HeapObject::Composite(object) => object.fields.len() + 1,
+            HeapObject::Exception(exception) => exception.payload.len() + 1,
+        }
+    }
+
+    fn trace(&self, visit: &mut impl FnMut(u64)) {
+        match self {
+            HeapObject::Composite(object) => object.trace(visit),
+            HeapObject::Exception(exception) => exception
+                .tag
+                .ty
+                .params_iter()
+                .zip(exception.payload.iter())
+                .filter(|(ty, _)| matches!(ty, ValType::Reference(_)))
+                .for_each(|(_, value)| visit(value.as_u64())),
+        }
+    }
+}
+
+fn is_reference(field: &FieldType) -> bool {
+    matches!(field.storage_type, StorageType::Val(ValType::Reference(_)))
+}
+
+fn pack(storage_type: StorageType, value: u64) -> u64 {
+    match storage_type {
+        StorageType::I8 => value & 0xFF,
+        StorageType::I16 => value & 0xFFFF,
+        StorageType::Val(ValType::Number(NumType::I32 | NumType::F32)) => value & 0xFFFF_FFFF,
+        StorageType::Val(_) => value,
+    }
+}
+
+fn unpack(storage_type: StorageType, value: u64, signed: bool) -> u64 {
+    match (storage_type, signed) {
+        (StorageType::I8, true) => value as u8 as i8 as i32 as u32 as u64,
+        (StorageType::I16, true) => value as u16 as i16 as i32 as u32 as u64,
+        _ => value,
+    }
+}
+
+fn default_value(field: &FieldType) -> u64 {
+    match field.storage_type.unpacked() {
+        ValType::Reference(_) => gc::NULL,
+        _ => 0,
+    }
+}
+
+/// Heap of the struct and array objects and the thrown exceptions of a cluster, collected by a non-moving mark & sweep collector.
+///
+/// Objects are referenced by their slot and its generation, see [`gc`]. The collector traces the fields of objects
+/// precisely. Roots are scanned conservatively: every root value tagged as an object keeps the object alive. The
+/// roots are
+///  - all globals and tables of the cluster
+///  - the exception currently unwinding the wasm stack of each thread
+///  - the frames registered by the interpreter, see [`runtime_interface::push_gc_roots`]
+///  - the native stacks and callee-saved registers of the threads executing wasm code of the cluster, which hold
+///    the variables of compiled code, see [`GcSafepoint`]
+///
+/// References held by the host are not roots. Once their object is collected, they trap instead of referring to
+/// the next object allocated in the slot.
+pub(crate) struct GcHeap {
+    slots: Vec<Slot>,
+    free: Vec<u32>,
+    /// number of fields of all objects allocated since the last collection or still live after it
+    allocated: usize,
+    threshold: usize,
+}
+
+struct Slot {
+    /// incremented whenever the object of the slot is freed
+    generation: u32,
+    marked: bool,
+    object: Option<HeapObject>,
+}
+
+impl Default for GcHeap {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            allocated: 0,
+            threshold: MIN_THRESHOLD,
+        }
+    }
+}
+
+impl GcHeap {
+    fn slot(&self, raw: u64) -> Result<&Slot, GcError> {
+        if raw == gc::NULL {
+            return Err(GcError::NullDeref);
+        }
+        if !gc::is_object(raw) {
+            return Err(GcError::InvalidReference);
+        }
+        self.slots
+            .get(gc::object_slot(raw) as usize)
+            .filter(|slot| slot.generation == gc::object_generation(raw))
+            .ok_or(GcError::InvalidReference)
+    }
+
+    fn get(&self, raw: u64) -> Result<&GcObject, GcError> {
+        match &self.slot(raw)?.object {
+            Some(HeapObject::Composite(object)) => Ok(object),
+            _ => Err(GcError::InvalidReference),
+        }
+    }
+
+    fn get_mut(&mut self, raw: u64) -> Result<&mut GcObject, GcError> {
+        self.slot(raw)?;
+        match &mut self.slots[gc::object_slot(raw) as usize].object {
+            Some(HeapObject::Composite(object)) => Ok(object),
+            _ => Err(GcError::InvalidReference),
+        }
+    }
+
+    /// The exception an `exnref` refers to.
+    pub(crate) fn get_exception(&self, raw: u64) -> Result<&ExceptionObject, GcError> {
+        match &self.slot(raw)?.object {
+            Some(HeapObject::Exception(exception)) => Ok(exception),
+            _ => Err(GcError::InvalidReference),
+        }
+    }
+
+    fn insert(&mut self, object: GcObject) -> u64 {
+        self.insert_object(HeapObject::Composite(object))
+    }
+
+    fn insert_object(&mut self, object: HeapObject) -> u64 {
+        self.allocated += object.size();
+        let slot = match self.free.pop() {
+            Some(slot) => {
+                self.slots[slot as usize].object = Some(object);
+                slot
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    marked: false,
+                    object: Some(object),
+                });
+                (self.slots.len() - 1) as u32
+            }
+        };
+        gc::object(slot, self.slots[slot as usize].generation)
+    }
+
+    /// Whether allocating `fields` more fields exceeds the threshold of the next collection.
+    fn needs_collection(&self, fields: usize) -> bool {
+        self.allocated + fields >= self.threshold
+    }
+
+    /// Delays the next collection until the heap doubled, because other threads didn't reach a safepoint.
+    fn postpone_collection(&mut self) {
+        self.threshold = MIN_THRESHOLD.max(self.allocated * 2);
+    }
+
+    fn mark(&mut self, raw: u64, worklist: &mut Vec<u32>) {
+        if !gc::is_object(raw) {
+            return;
+        }
+        let slot = gc::object_slot(raw);
+        if let Some(Slot {
+            generation,
+            marked,
+            object: Some(_),
+        }) = self.slots.get_mut(slot as usize)
+        {
+            if *generation == gc::object_generation(raw) && !*marked {
+                *marked = true;
+                worklist.push(slot);
+            }
+        }
+    }
+
+    /// Frees all objects that aren't reachable from `roots`.
+    fn collect(&mut self, roots: impl IntoIterator<Item = u64>) {
+        let mut worklist = Vec::new();
+        for raw in roots {
+            self.mark(raw, &mut worklist);
+        }
+        while let Some(slot) = worklist.pop() {
+            let mut children = Vec::new();
+            if let Some(object) = &self.slots[slot as usize].object {
+                object.trace(&mut |raw| children.push(raw));
+            }
+            for raw in children {
+                self.mark(raw, &mut worklist);
+            }
+        }
+
+        self.allocated = 0;
+        for (idx, slot) in self.slots.iter_mut().enumerate() {
+            match &slot.object {
+                Some(object) if slot.marked => {
+                    slot.marked = false;
+                    self.allocated += object.size();
+                }
+                Some(_) => {
+                    slot.object = None;
+                    slot.generation = slot.generation.wrapping_add(1) & gc::GENERATION_MASK;
+                    self.free.push(idx as u32);
+                }
+                None => {}
+            }
+        }
+        self.threshold = MIN_THRESHOLD.max(self.allocated * 2);
+        log::debug!(
+            "garbage collection finished, {} fields live, next collection at {}",
+            self.allocated,
+            self.threshold
+        );
+    }
+}
+
+/// Coordinates collections with the threads executing wasm code of a cluster, the mutators of its heap.
+///
+/// Their references live on their native stacks, which only they can scan. A thread that has to collect waits
+/// until every other mutator reached a safepoint: allocations park the thread until the collection finished,
+/// blocking calls like `memory.atomic.wait` report their roots up front. Threads can't start executing wasm code
+/// during a collection. Mutators that don't reach a safepoint in time, e.g. because they don't allocate, postpone
+/// the collection instead of stalling the collecting thread.
+#[derive(Default)]
+pub(crate) struct GcSafepoint {
+    state: Mutex<SafepointState>,
+    condvar: Condvar,
+}
+
+#[derive(Default)]
+struct SafepointState {
+    /// number of threads executing wasm code of the cluster
+    mutators: usize,
+    /// whether a thread waits for the other mutators or collects
+    collecting: bool,
+    /// number of finished collections, parked threads wait for it to change
+    epoch: u64,
+    /// number of mutators parked in the current collection
+    parked: usize,
+    /// roots of the parked mutators
+    parked_roots: Vec<u64>,
+    /// roots of the mutators in blocking calls, reported once for the duration of the call
+    blocked: HashMap<ThreadId, Vec<u64>>,
+}
+
+impl GcSafepoint {
+    fn key(&self) -> usize {
+        self as *const Self as usize
+    }
+
+    fn is_mutator(&self) -> bool {
+        MUTATOR_OF.with_borrow(|clusters| clusters.contains(&self.key()))
+    }
+
+    /// Registers the calling thread as a mutator, unless it already is one because of a surrounding call.
+    /// Returns whether it was registered by this call and has to be deregistered with [`GcSafepoint::leave`].
+    pub(crate) fn enter(&self) -> bool {
+        if self.is_mutator() {
+            return false;
+        }
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .condvar
+            .wait_while(state, |state| state.collecting)
+            .unwrap();
+        state.mutators += 1;
+        MUTATOR_OF.with_borrow_mut(|clusters| clusters.push(self.key()));
+        true
+    }
+
+    pub(crate) fn leave(&self) {
+        MUTATOR_OF.with_borrow_mut(|clusters| clusters.retain(|key| *key != self.key()));
+        self.state.lock().unwrap().mutators -= 1;
+        // a collecting thread may only be waiting for this one
+        self.condvar.notify_all();
+    }
+
+    /// Runs `f`, which blocks without touching references, while collections may proceed without the calling
+    /// thread reaching a safepoint.
+    pub(crate) fn blocking<R>(&self, f: impl FnOnce() -> R) -> R {
+        if !self.is_mutator() {
+            return f();
+        }
+        let mut roots = Vec::new();
+        scan_thread_roots(&mut |raw| roots.push(raw));
+        let thread = std::thread::current().id();
+        self.state.lock().unwrap().blocked.insert(thread, roots);
+        self.condvar.notify_all();
+
+        let result = f();
+
+        let state = self.state.lock().unwrap();
+        let mut state = self
+            .condvar
+            .wait_while(state, |state| state.collecting)
+            .unwrap();
+        state.blocked.remove(&thread);
+        result
+    }
+
+    /// The safepoint of allocations: parks the thread during collections of other threads and collects the heap
+    /// if it exceeds its threshold. `pending` are the fields of the object about to be allocated.
+    fn allocate(&self, cluster: &Cluster, pending: &[u64]) {
+        let mut state = self.state.lock().unwrap();
+        while state.collecting {
+            state = self.park(state, pending);
+        }
+        if !cluster.gc_heap().needs_collection(pending.len()) {
+            return;
+        }
+
+        state.collecting = true;
+        state.parked = 0;
+        state.parked_roots.clear();
+        let others = state.mutators - self.is_mutator() as usize;
+        let (mut state, timeout) = self
+            .condvar
+            .wait_timeout_while(state, SAFEPOINT_TIMEOUT, |state| {
+                state.parked + state.blocked.len() < state.mutators - self.is_mutator() as usize
+            })
+            .unwrap();
+
+        let mut heap = cluster.gc_heap();
+        if timeout.timed_out() {
+            log::debug!(
+                "garbage collection postponed, {} of {others} threads reached a safepoint",
+                state.parked + state.blocked.len()
+            );
+            heap.postpone_collection();
+        } else {
+            let mut roots = std::mem::take(&mut state.parked_roots);
+            roots.extend(state.blocked.values().flatten());
+            roots.extend_from_slice(pending);
+            let mut visit = |raw: u64| {
+                if gc::is_object(raw) {
+                    roots.push(raw)
+                }
+            };
+            cluster.scan_gc_roots(&mut visit);
+            scan_thread_roots(&mut visit);
+            heap.collect(roots);
+        }
+        drop(heap);
+
+        state.collecting = false;
+        state.epoch += 1;
+        self.condvar.notify_all();
+    }
+
+    /// Reports the roots of the calling thread and waits until the current collection finished.
+    fn park<'a>(
+        &self,
+        mut state: MutexGuard<'a, SafepointState>,
+        pending: &[u64],
+    ) -> MutexGuard<'a, SafepointState> {
+        let epoch = state.epoch;
+        state.parked_roots.extend_from_slice(pending);
+        scan_thread_roots(&mut |raw| {
+            if gc::is_object(raw) {
+                state.parked_roots.push(raw)
+            }
+        });
+        state.parked += 1;
+        self.condvar.notify_all();
+        self.condvar
+            .wait_while(state, |state| state.epoch == epoch)
+            .unwrap()
+    }
+}
+
+/// Reports the roots of the wasm code executing on the calling thread.
+fn scan_thread_roots(visit: &mut dyn FnMut(u64)) {
+    exceptions::scan_pending_exception(visit);
+    runtime_interface::scan_gc_roots(visit);
+    scan_native_stack(visit);
+}
+
+/// Marks `base` as the bottom of the native wasm stack of this thread, unless a surrounding call already did.
+/// Returns whether the base was set by this call and has to be reset with [`leave_wasm_stack`].
+pub(crate) fn enter_wasm_stack(base: usize) -> bool {
+    if STACK_BASE.get().is_some() {
+        return false;
+    }
+    STACK_BASE.set(Some(base));
+    true
+}
+
+pub(crate) fn leave_wasm_stack() {
+    STACK_BASE.set(None);
+}
+
+/// Conservatively reports every word between the current stack pointer and the stack base, after spilling the
+/// callee-saved registers that may hold references of compiled code.
+#[inline(never)]
+fn scan_native_stack(visit: &mut dyn FnMut(u64)) {
+    let Some(base) = STACK_BASE.get() else {
+        return;
+    };
+    let mut registers = [0u64; 12];
+    spill_callee_saved_registers(&mut registers);
+    let registers = std::hint::black_box(&registers);
+    registers.iter().for_each(|value| visit(*value));
+
+    let mut addr = (registers.as_ptr() as usize) & !(size_of::<u64>() - 1);
+    while addr + size_of::<u64>() <= base {
+        visit(unsafe { (addr as *const u64).read_volatile() });
+        addr += size_of::<u64>();
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[inline(never)]
+fn spill_callee_saved_registers(out: &mut [u64; 12]) {
+    unsafe {
+        std::arch::asm!(
+            "mov [{out}], rbx",
+            "mov [{out} + 8], rbp",
+            "mov [{out} + 16], r12",
+            "mov [{out} + 24], r13",
+            "mov [{out} + 32], r14",
+            "mov [{out} + 40], r15",
+            out = in(reg) out.as_mut_ptr(),
+            options(nostack, preserves_flags),
+        )
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+#[inline(never)]
+fn spill_callee_saved_registers(out: &mut [u64; 12]) {
+    unsafe {
+        std::arch::asm!(
+            "stp x19, x20, [{out}]",
+            "stp x21, x22, [{out}, #16]",
+            "stp x23, x24, [{out}, #32]",
+            "stp x25, x26, [{out}, #48]",
+            "stp x27, x28, [{out}, #64]",
+            "str x29, [{out}, #80]",
+            out = in(reg) out.as_mut_ptr(),
+            options(nostack, preserves_flags),
+        )
+    }
+}
+
+// other architectures rely on the callee-saved registers being spilled to the stack by the calls into the runtime
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+fn spill_callee_saved_registers(_: &mut [u64; 12]) {}
+
+fn cluster(ctxt: &ExecutionContext) -> &Cluster {
+    unsafe { &*(ctxt.cluster as *const Cluster) }
+}
+
+/// Allocates a thrown exception, collecting garbage first if the heap grew past its threshold. Returns the
+/// `exnref` to it.
+pub(crate) fn alloc_exception(cluster: &Cluster, exception: ExceptionObject) -> u64 {
+    let payload: Vec<u64> = exception
+        .payload
+        .iter()
+        .map(|value| value.as_u64())
+        .collect();
+    cluster.gc_safepoint().allocate(cluster, &payload);
+    cluster
+        .gc_heap()
+        .insert_object(HeapObject::Exception(exception))
+}
+
+/// Allocates an object of `type_idx`, collecting garbage first if the heap grew past its threshold.
+fn alloc(ctxt: &ExecutionContext, type_idx: TypeIdx, fields: Vec<u64>) -> u64 {
+    let cluster = cluster(ctxt);
+    cluster.gc_safepoint().allocate(cluster, &fields);
+    cluster.gc_heap().insert(GcObject {
+        module: ctxt.wasm_module.clone(),
+        type_idx,
+        fields,
+    })
+}
+
+/// Allocates the objects of the constant expressions initializing globals and tables during instantiation. They
+/// are roots until the allocator is dropped, since the globals and tables holding them may not be scanned yet.
+pub(crate) struct ConstantAllocator<'a> {
+    cluster: &'a Cluster,
+    module: Rc<WasmModule>,
+    allocated: Vec<u64>,
+}
+
+impl<'a> ConstantAllocator<'a> {
+    pub(crate) fn new(cluster: &'a Cluster, module: Rc<WasmModule>) -> Self {
+        Self {
+            cluster,
+            module,
+            allocated: Vec::new(),
+        }
+    }
+
+    pub(crate) fn alloc(
+        &mut self,
+        allocation: &ConstantAllocation,
+        operands: Vec<ValueRaw>,
+    ) -> Result<u64, GcError> {
+        let type_idx = allocation.type_idx();
+        let sub_type = self.module.meta.types.get(type_idx as usize);
+        let operands: Vec<u64> = operands.into_iter().map(ValueRaw::as_u64).collect();
+        let fields = match allocation {
+            ConstantAllocation::StructNew { .. } | ConstantAllocation::StructNewDefault(_) => {
+                let field_types = sub_type
+                    .and_then(SubType::as_struct)
+                    .ok_or(GcError::InvalidType(type_idx))?;
+                match allocation {
+                    ConstantAllocation::StructNewDefault(_) => {
+                        field_types.iter().map(default_value).collect()
+                    }
+                    _ => operands
+                        .iter()
+                        .zip(field_types.iter())
+                        .map(|(value, field)| pack(field.storage_type, *value))
+                        .collect(),
+                }
+            }
+            _ => {
+                let field = sub_type
+                    .and_then(SubType::as_array)
+                    .ok_or(GcError::InvalidType(type_idx))?;
+                match allocation {
+                    ConstantAllocation::ArrayNew(_) => {
+                        check_array_len(operands[1] as u32)?;
+                        vec![pack(field.storage_type, operands[0]); operands[1] as u32 as usize]
+                    }
+                    ConstantAllocation::ArrayNewDefault(_) => {
+                        check_array_len(operands[0] as u32)?;
+                        vec![default_value(&field); operands[0] as u32 as usize]
+                    }
+                    _ => operands
+                        .iter()
+                        .map(|value| pack(field.storage_type, *value))
+                        .collect(),
+                }
+            }
+        };
+
+        let mut pending = self.allocated.clone();
+        pending.extend_from_slice(&fields);
+        self.cluster.gc_safepoint().allocate(self.cluster, &pending);
+        let object = self.cluster.gc_heap().insert(GcObject {
+            module: self.module.clone(),
+            type_idx,
+            fields,
+        });
+        self.allocated.push(object);
+        Ok(object)
+    }
+}
+
+fn struct_fields(ctxt: &ExecutionContext, type_idx: TypeIdx) -> Result<&[FieldType], GcError> {
+    ctxt.wasm_module
+        .meta
+        .types
+        .get(type_idx as usize)
+        .and_then(SubType::as_struct)
+        .ok_or(GcError::InvalidType(type_idx))
+}
+
+fn array_field(ctxt: &ExecutionContext, type_idx: TypeIdx) -> Result<FieldType, GcError> {
+    ctxt.wasm_module
+        .meta
+        .types
+        .get(type_idx as usize)
+        .and_then(SubType::as_array)
+        .ok_or(GcError::InvalidType(type_idx))
+}
+
+fn check_array_len(len: u32) -> Result<(), GcError> {
+    match len > MAX_ARRAY_LEN {
+        true => Err(GcError::OutOfMemory(len)),
+        false => Ok(()),
+    }
+}
+
+/// Reads `len` elements of `field` type from the data segment, starting at byte `offset`.
+fn data_values(
+    ctxt: &ExecutionContext,
+    field: FieldType,
+    data_idx: DataIdx,
+    offset: u32,
+    len: u32,
+) -> Result<Vec<u64>, GcError> {
+    let data = &ctxt
+        .wasm_module
+        .meta
+        .datas
+        .get(data_idx as usize)
+        .ok_or(GcError::DataAccessOutOfBounds)?
+        .init;
+    let width = match field.storage_type {
+        StorageType::I8 => 1,
+        StorageType::I16 => 2,
+        StorageType::Val(ValType::Number(NumType::I32 | NumType::F32)) => 4,
+        StorageType::Val(ValType::Number(NumType::I64 | NumType::F64)) => 8,
+        StorageType::Val(_) => return Err(GcError::DataAccessOutOfBounds),
+    };
+    let end = (offset as u64) + (len as u64) * width as u64;
+    if end > data.len() as u64 {
+        return Err(GcError::DataAccessOutOfBounds);
+    }
+    Ok(data[offset as usize..end as usize]
+        .chunks_exact(width)
+        .map(|bytes| {
+            let mut value = [0u8; 8];
+            value[..width].copy_from_slice(bytes);
+            u64::from_le_bytes(value)
+        })
+        .collect())
+}
+
+/// Evaluates `len` references of the element segment, starting at `offset`.
+fn elem_values(
+    ctxt: &ExecutionContext,
+    elem_idx: ElemIdx,
+    offset: u32,
+    len: u32,
+) -> Result<Vec<u64>, GcError> {
+    let elem = ctxt
+        .wasm_module
+        .meta
+        .elements
+        .get(elem_idx as usize)
+        .ok_or(GcError::ElemAccessOutOfBounds)?;
+    let elem_len = match &elem.init {
+        ElementInit::Final(values) => values.len(),
+        ElementInit::Unresolved(func_idxs) => func_idxs.len(),
+    };
+    let end = offset
+        .checked_add(len)
+        .filter(|end| *end as usize <= elem_len)
+        .ok_or(GcError::ElemAccessOutOfBounds)? as usize;
+    let range = offset as usize..end;
+    let globals = unsafe { &*ctxt.globals_ptr };
+    let global_value =
+        |idx: u32| Ok::<_, GcError>(unsafe { *globals.globals[idx as usize].addr.as_ptr() });
+    match &elem.init {
+        ElementInit::Unresolved(func_idxs) => Ok(func_idxs[range]
+            .iter()
+            .map(|func_idx| ValueRaw::funcref(*func_idx).as_u64())
+            .collect()),
+        ElementInit::Final(values) => values[range]
+            .iter()
+            .map(|value| match value {
+                ConstantValue::V(value) => Ok(ValueRaw::from(value.clone()).as_u64()),
+                ConstantValue::Global(idx) => Ok(global_value(*idx)?.as_u64()),
+                ConstantValue::FuncPtr(func_idx) => Ok(ValueRaw::funcref(*func_idx).as_u64()),
+                ConstantValue::Expr(ops) => {
+                    Ok(ValueRaw::from(eval_constant_ops(ops, global_value)?).as_u64())
+                }
+            })
+            .collect(),
+    }
+}
+
+/// The top type of the hierarchy `heap_type` belongs to, it decides how raw values are interpreted.
+fn top_type(heap_type: HeapType, types: &[SubType]) -> HeapType {
+    match heap_type {
+        HeapType::Func | HeapType::NoFunc => HeapType::Func,
+        HeapType::Extern | HeapType::NoExtern => HeapType::Extern,
+        HeapType::Exn | HeapType::NoExn => HeapType::Exn,
+        HeapType::Concrete(type_idx) => match types.get(type_idx as usize) {
+            Some(SubType {
+                composite_type: CompositeType::Func(_),
+                ..
+            }) => HeapType::Func,
+            _ => HeapType::Any,
+        },
+        HeapType::Any
+        | HeapType::Eq
+        | HeapType::I31
+        | HeapType::Struct
+        | HeapType::Array
+        | HeapType::None => HeapType::Any,
+    }
+}
+
+fn ref_test_impl(
+    ctxt: &ExecutionContext,
+    value: u64,
+    heap_type: i64,
+    nullable: bool,
+) -> Result<bool, GcError> {
+    let heap_type = HeapType::from_s33(heap_type).ok_or(GcError::InvalidHeapType(heap_type))?;
+    if value == gc::NULL {
+        return Ok(nullable);
+    }
+    let types = &ctxt.wasm_module.meta.types;
+    Ok(match top_type(heap_type, types) {
+        HeapType::Func => ctxt
+            .wasm_module
+            .meta
+            .functions
+            .get(ValueRaw::from(value).as_funcref() as usize)
+            .is_some_and(|function| {
+                HeapType::Concrete(function.type_idx).matches(types, &heap_type, types)
+            }),
+        HeapType::Any if gc::is_i31(value) => HeapType::I31.matches(types, &heap_type, types),
+        HeapType::Any if gc::is_object(value) => {
+            let heap = cluster(ctxt).gc_heap();
+            let object = heap.get(value)?;
+            HeapType::Concrete(object.type_idx).matches(
+                &object.module.meta.types,
+                &heap_type,
+                types,
+            )
+        }
+        // internalized extern references are only of type any, non-null exception and extern references
+        // only of their top type
+        top => heap_type == top,
+    })
+}
+
+fn struct_new_impl(
+    ctxt: &ExecutionContext,
+    type_idx: TypeIdx,
+    fields: *const u64,
+) -> Result<u64, GcError> {
+    let field_types = struct_fields(ctxt, type_idx)?;
+    let fields = match fields.is_null() {
+        true => field_types.iter().map(default_value).collect(),
+        false => unsafe { slice::from_raw_parts(fields, field_types.len()) }
+            .iter()
+            .zip(field_types.iter())
+            .map(|(value, field)| pack(field.storage_type, *value))
+            .collect(),
+    };
+    Ok(alloc(ctxt, type_idx, fields))
+}
+
+fn array_new_impl(
+    ctxt: &ExecutionContext,
+    type_idx: TypeIdx,
+    init: Option<u64>,
+    len: u32,
+) -> Result<u64, GcError> {
+    let field = array_field(ctxt, type_idx)?;
+    check_array_len(len)?;
+    let init = match init {
+        Some(init) => pack(field.storage_type, init),
+        None => default_value(&field),
+    };
+    Ok(alloc(ctxt, type_idx, vec![init; len as usize]))
+}
+
+fn array_new_fixed_impl(
+    ctxt: &ExecutionContext,
+    type_idx: TypeIdx,
+    values: *const u64,
+    len: u32,
+) -> Result<u64, GcError> {
+    let field = array_field(ctxt, type_idx)?;
+    let values = match len {
+        0 => Vec::new(),
+        len => unsafe { slice::from_raw_parts(values, len as usize) }
+            .iter()
+            .map(|value| pack(field.storage_type, *value))
+            .collect(),
+    };
+    Ok(alloc(ctxt, type_idx, values))
+}
+
+fn array_new_data_impl(
+    ctxt: &ExecutionContext,
+    type_idx: TypeIdx,
+    data_idx: DataIdx,
+    offset: u32,
+    len: u32,
+) -> Result<u64, GcError> {
+    let field = array_field(ctxt, type_idx)?;
+    check_array_len(len)?;
+    let values = data_values(ctxt, field, data_idx, offset, len)?;
+    Ok(alloc(ctxt, type_idx, values))
+}
+
+fn array_new_elem_impl(
+    ctxt: &ExecutionContext,
+    type_idx: TypeIdx,
+    elem_idx: ElemIdx,
+    offset: u32,
+    len: u32,
+) -> Result<u64, GcError> {
+    array_field(ctxt, type_idx)?;
+    let values = elem_values(ctxt, elem_idx, offset, len)?;
+    Ok(alloc(ctxt, type_idx, values))
+}
+
+fn array_fill_impl(
+    ctxt: &ExecutionContext,
+    array: u64,
+    offset: u32,
+    value: u64,
+    len: u32,
+) -> Result<(), GcError> {
+    let mut heap = cluster(ctxt).gc_heap();
+    let object = heap.get_mut(array)?;
+    let range = object.range(offset, len)?;
+    let value = pack(object.field_type(0)?.storage_type, value);
+    object.fields[range].fill(value);
+    Ok(())
+}
+
+fn array_copy_impl(
+    ctxt: &ExecutionContext,
+    dst: u64,
+    dst_offset: u32,
+    src: u64,
+    src_offset: u32,
+    len: u32,
+) -> Result<(), GcError> {
+    let mut heap = cluster(ctxt).gc_heap();
+    let src_range = heap.get(src)?.range(src_offset, len)?;
+    let dst_range = heap.get(dst)?.range(dst_offset, len)?;
+    // overlapping ranges of the same array behave like memmove
+    let values = heap.get(src)?.fields[src_range].to_vec();
+    heap.get_mut(dst)?.fields[dst_range].copy_from_slice(&values);
+    Ok(())
+}
+
+fn array_init_impl(
+    ctxt: &ExecutionContext,
+    array: u64,
+    dst_offset: u32,
+    values: Result<Vec<u64>, GcError>,
+) -> Result<(), GcError> {
+    let mut heap = cluster(ctxt).gc_heap();
+    let object = heap.get_mut(array)?;
+    let values = values?;
+    let range = object.range(dst_offset, values.len() as u32)?;
+    object.fields[range].copy_from_slice(&values);
+    Ok(())
+}
+
+fn array_init_data_impl(
+    ctxt: &ExecutionContext,
+    array: u64,
+    data_idx: DataIdx,
+    dst_offset: u32,
+    src_offset: u32,
+    len: u32,
+) -> Result<(), GcError> {
+    let field = {
+        let heap = cluster(ctxt).gc_heap();
+        let object = heap.get(array)?;
+        object.range(dst_offset, len)?;
+        object.field_type(0)?
+    };
+    let values = data_values(ctxt, field, data_idx, src_offset, len);
+    array_init_impl(ctxt, array, dst_offset, values)
+}
+
+fn array_init_elem_impl(
+    ctxt: &ExecutionContext,
+    array: u64,
+    elem_idx: ElemIdx,
+    dst_offset: u32,
+    src_offset: u32,
+    len: u32,
+) -> Result<(), GcError> {
+    cluster(ctxt).gc_heap().get(array)?.range(dst_offset, len)?;
+    let values = elem_values(ctxt, elem_idx, src_offset, len);
+    array_init_impl(ctxt, array, dst_offset, values)
+}
+
+#[no_mangle]
+extern "C" fn gc_struct_new(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    fields: *const u64,
+) -> u64 {
+    let res = struct_new_impl(ctxt, type_idx, fields);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_struct_get(
+    ctxt: &mut ExecutionContext,
+    obj: u64,
+    field_idx: u32,
+    signed: u32,
+) -> u64 {
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get(obj)
+        .and_then(|object| object.get(field_idx, signed != 0));
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_struct_set(ctxt: &mut ExecutionContext, obj: u64, field_idx: u32, value: u64) {
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get_mut(obj)
+        .and_then(|object| object.set(field_idx, value));
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_new(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    init: u64,
+    len: u32,
+) -> u64 {
+    let res = array_new_impl(ctxt, type_idx, Some(init), len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_new_default(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    len: u32,
+) -> u64 {
+    let res = array_new_impl(ctxt, type_idx, None, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_new_fixed(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    values: *const u64,
+    len: u32,
+) -> u64 {
+    let res = array_new_fixed_impl(ctxt, type_idx, values, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_new_data(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    data_idx: DataIdx,
+    offset: u32,
+    len: u32,
+) -> u64 {
+    let res = array_new_data_impl(ctxt, type_idx, data_idx, offset, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_new_elem(
+    ctxt: &mut ExecutionContext,
+    type_idx: TypeIdx,
+    elem_idx: ElemIdx,
+    offset: u32,
+    len: u32,
+) -> u64 {
+    let res = array_new_elem_impl(ctxt, type_idx, elem_idx, offset, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_get(ctxt: &mut ExecutionContext, array: u64, idx: u32, signed: u32) -> u64 {
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get(array)
+        .and_then(|object| object.get(idx, signed != 0));
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_set(ctxt: &mut ExecutionContext, array: u64, idx: u32, value: u64) {
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get_mut(array)
+        .and_then(|object| object.set(idx, value));
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_len(ctxt: &mut ExecutionContext, array: u64) -> u32 {
+    let res = cluster(ctxt)
+        .gc_heap()
+        .get(array)
+        .map(|object| object.fields.len() as u32);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_fill(
+    ctxt: &mut ExecutionContext,
+    array: u64,
+    offset: u32,
+    value: u64,
+    len: u32,
+) {
+    let res = array_fill_impl(ctxt, array, offset, value, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_copy(
+    ctxt: &mut ExecutionContext,
+    dst: u64,
+    dst_offset: u32,
+    src: u64,
+    src_offset: u32,
+    len: u32,
+) {
+    let res = array_copy_impl(ctxt, dst, dst_offset, src, src_offset, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_init_data(
+    ctxt: &mut ExecutionContext,
+    array: u64,
+    data_idx: DataIdx,
+    dst_offset: u32,
+    src_offset: u32,
+    len: u32,
+) {
+    let res = array_init_data_impl(ctxt, array, data_idx, dst_offset, src_offset, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_array_init_elem(
+    ctxt: &mut ExecutionContext,
+    array: u64,
+    elem_idx: ElemIdx,
+    dst_offset: u32,
+    src_offset: u32,
+    len: u32,
+) {
+    let res = array_init_elem_impl(ctxt, array, elem_idx, dst_offset, src_offset, len);
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn gc_ref_test(
+    ctxt: &mut ExecutionContext,
+    value: u64,
+    heap_type: i64,
+    nullable: u32,
+) -> u32 {
+    let res = ref_test_impl(ctxt, value, heap_type, nullable != 0);
+    trap_on_err(ctxt, res) as u32
+}
+
+#[no_mangle]
+extern "C" fn gc_ref_cast(
+    ctxt: &mut ExecutionContext,
+    value: u64,
+    heap_type: i64,
+    nullable: u32,
+) -> u64 {
+    let res =
+        ref_test_impl(ctxt, value, heap_type, nullable != 0).and_then(|matches| match matches {
+            true => Ok(value),
+            false => Err(GcError::CastFailure),
+        });
+    trap_on_err(ctxt, res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{objects::exceptions::TagInstance, sugar::module_from_buf};
+    #[cfg(feature = "interp")]
+    use crate::{Engine, Linker};
+    use module::objects::value::Value;
+    use wasm_types::{FuncTypeBuilder, RefType};
+
+    /// Heap with objects of a struct type with a single reference field
+    fn heap() -> (GcHeap, Rc<WasmModule>) {
+        let module = module_from_buf(b"(module (type (struct (field (mut anyref)))))".to_vec())
+            .expect("valid module");
+        (GcHeap::default(), Rc::new(module))
+    }
+
+    /// Fills the heap of `cluster` with unreachable objects up to its collection threshold.
+    fn fill_heap(cluster: &Cluster, module: &Rc<WasmModule>) -> Vec<u64> {
+        let mut heap = cluster.gc_heap();
+        (0..MIN_THRESHOLD / 2)
+            .map(|_| new_object(&mut heap, module, gc::NULL))
+            .collect()
+    }
+
+    fn new_object(heap: &mut GcHeap, module: &Rc<WasmModule>, field: u64) -> u64 {
+        heap.insert(GcObject {
+            module: module.clone(),
+            type_idx: 0,
+            fields: vec![field],
+        })
+    }
+
+    #[test]
+    fn collect_frees_unreachable_objects() {
+        let (mut heap, module) = heap();
+        let leaf = new_object(&mut heap, &module, gc::NULL);
+        let root = new_object(&mut heap, &module, leaf);
+        let garbage = new_object(&mut heap, &module, leaf);
+
+        heap.collect([root]);
+        assert!(heap.get(root).is_ok());
+        assert_eq!(heap.get(root).unwrap().get(0, false).unwrap(), leaf);
+        assert!(heap.get(leaf).is_ok());
+        assert!(matches!(heap.get(garbage), Err(GcError::InvalidReference)));
+        assert_eq!(heap.allocated, 4);
+    }
+
+    /// Exception of a tag with an `i64` and an `anyref` parameter
+    fn new_exception(heap: &mut GcHeap, payload: [u64; 2]) -> u64 {
+        let anyref = ValType::Reference(RefType::new(HeapType::Any, true));
+        let tag = TagInstance::new(FuncTypeBuilder::create(&[ValType::i64(), anyref], &[]));
+        heap.insert_object(HeapObject::Exception(ExceptionObject {
+            tag,
+            payload: payload.map(ValueRaw::u64).to_vec(),
+        }))
+    }
+
+    #[test]
+    fn collect_frees_cycles() {
+        let (mut heap, module) = heap();
+        let a = new_object(&mut heap, &module, gc::NULL);
+        let b = new_object(&mut heap, &module, a);
+        heap.get_mut(a).unwrap().set(0, b).unwrap();
+
+        heap.collect([b]);
+        assert!(heap.get(a).is_ok() && heap.get(b).is_ok());
+        heap.collect([]);
+        assert!(heap.get(a).is_err() && heap.get(b).is_err());
+        assert_eq!(heap.allocated, 0);
+    }
+
+    #[test]
+    fn collect_ignores_non_object_roots() {
+        let (mut heap, module) = heap();
+        let object = new_object(&mut heap, &module, gc::NULL);
+        heap.collect([
+            gc::NULL,
+            gc::i31(gc::object_slot(object)),
+            gc::object_slot(object) as u64,
+        ]);
+        assert!(heap.get(object).is_err());
+    }
+
+    #[test]
+    fn collect_traces_exception_payloads() {
+        let (mut heap, module) = heap();
+        let referenced = new_object(&mut heap, &module, gc::NULL);
+        let number = new_object(&mut heap, &module, gc::NULL);
+        let exception = new_exception(&mut heap, [number, referenced]);
+        let garbage = new_exception(&mut heap, [0, referenced]);
+
+        heap.collect([exception]);
+        assert!(heap.get_exception(exception).is_ok());
+        assert!(heap.get(referenced).is_ok());
+        // the payload is traced precisely, numbers don't keep objects alive
+        assert!(heap.get(number).is_err());
+        assert!(heap.get_exception(garbage).is_err());
+        // exnrefs and references to structs and arrays aren't interchangeable
+        assert!(heap.get(exception).is_err());
+        assert!(heap.get_exception(referenced).is_err());
+    }
+
+    #[cfg(feature = "interp")]
+    #[test]
+    fn caught_exceptions_are_collected() {
+        let text = r#"
+            (module
+              (tag $e (param i64 i64))
+              (func (export "run") (param $n i32)
+                (loop $l
+                  (block $h (result i64 i64)
+                    (try_table (catch $e $h) (throw $e (i64.const 1) (i64.const 2)))
+                    (unreachable))
+                  (drop)
+                  (drop)
+                  (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))))
+        "#;
+        let module = Rc::new(module_from_buf(text.as_bytes().to_vec()).unwrap());
+        let mut engine = Engine::interpreter().unwrap();
+        engine.init(module.clone()).unwrap();
+        let cluster = Cluster::default();
+        let instance = Linker::new()
+            .bind_to(&cluster)
+            .instantiate_and_link(module, engine)
+            .unwrap();
+        let run = instance.get_export_by_name("run").unwrap();
+        run.call(&[Value::i32(MIN_THRESHOLD as u32)]).unwrap();
+
+        // three times as many fields as the threshold were thrown, but the heap only grows up to it
+        let heap = cluster.gc_heap();
+        assert!(!heap.slots.is_empty() && heap.slots.len() <= MIN_THRESHOLD / 3);
+    }
+
+    #[test]
+    fn stale_references_trap_after_slot_reuse() {
+        let (mut heap, module) = heap();
+        let stale = new_object(&mut heap, &module, gc::NULL);
+        heap.collect([]);
+        let fresh = new_object(&mut heap, &module, gc::NULL);
+
+        // the slot is reused with the next generation
+        assert_eq!(gc::object_slot(stale), gc::object_slot(fresh));
+        assert_ne!(stale, fresh);
+        assert!(matches!(heap.get(stale), Err(GcError::InvalidReference)));
+        assert!(matches!(
+            heap.get_mut(stale),
+            Err(GcError::InvalidReference)
+        ));
+
+        // stale roots don't keep the new object of the slot alive
+        heap.collect([stale]);
+        assert!(heap.get(fresh).is_err());
+    }
+
+    #[test]
+    fn collect_adapts_threshold() {
+        let (mut heap, module) = heap();
+        let objects: Vec<u64> = (0..MIN_THRESHOLD)
+            .map(|_| new_object(&mut heap, &module, gc::NULL))
+            .collect();
+        assert!(heap.needs_collection(0));
+
+        heap.collect(objects.iter().copied());
+        assert_eq!(heap.allocated, 2 * MIN_THRESHOLD);
+        assert_eq!(heap.threshold, 4 * MIN_THRESHOLD);
+        assert!(!heap.needs_collection(0));
+
+        heap.collect([]);
+        assert_eq!(heap.threshold, MIN_THRESHOLD);
+        assert_eq!(heap.free.len(), MIN_THRESHOLD);
+    }
+
+    #[test]
+    fn safepoint_registers_threads_once() {
+        let safepoint = GcSafepoint::default();
+        assert!(safepoint.enter());
+        assert!(!safepoint.enter());
+        assert_eq!(safepoint.state.lock().unwrap().mutators, 1);
+        safepoint.leave();
+        assert_eq!(safepoint.state.lock().unwrap().mutators, 0);
+        assert!(!safepoint.is_mutator());
+    }
+
+    #[test]
+    fn collection_is_postponed_while_other_threads_run() {
+        let (_, module) = heap();
+        let cluster = Cluster::default();
+        let safepoint = cluster.gc_safepoint();
+        let objects = fill_heap(&cluster, &module);
+        let (entered, done) = (std::sync::Barrier::new(2), std::sync::Barrier::new(2));
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                safepoint.enter();
+                entered.wait();
+                done.wait();
+                safepoint.leave();
+            });
+            entered.wait();
+            assert!(safepoint.enter());
+            safepoint.allocate(&cluster, &[]);
+            safepoint.leave();
+            done.wait();
+        });
+
+        // the other thread may hold references to the objects
+        let heap = cluster.gc_heap();
+        assert!(objects.iter().all(|object| heap.get(*object).is_ok()));
+        assert!(!heap.needs_collection(0));
+    }
+
+    #[test]
+    fn blocked_threads_do_not_delay_collections() {
+        let (_, module) = heap();
+        let cluster = Cluster::default();
+        let safepoint = cluster.gc_safepoint();
+        let objects = fill_heap(&cluster, &module);
+        let (blocked, done) = (std::sync::Barrier::new(2), std::sync::Barrier::new(2));
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                safepoint.enter();
+                safepoint.blocking(|| {
+                    blocked.wait();
+                    done.wait();
+                });
+                safepoint.leave();
+            });
+            blocked.wait();
+            assert!(safepoint.enter());
+            safepoint.allocate(&cluster, &[]);
+            safepoint.leave();
+            done.wait();
+        });
+
+        let heap = cluster.gc_heap();
+        assert!(objects.iter().all(|object| heap.get(*object).is_err()));
+    }
+}
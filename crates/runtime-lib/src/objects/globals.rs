@@ -1,12 +1,14 @@
-use super::instance_handle::InstantiationError;
+use super::{gc::ConstantAllocator, instance_handle::InstantiationError};
 use crate::{linker::RTGlobalImport, Cluster, Engine};
 use module::objects::{
+    expression::eval_allocating_constant_ops,
     global::Global,
+    module::Module as WasmModule,
     value::{ConstantValue, ValueRaw},
 };
 use nix::errno::Errno;
 use runtime_interface::GlobalInstance;
-use std::ptr::NonNull;
+use std::{ptr::NonNull, rc::Rc};
 use wasm_types::GlobalIdx;
 
 pub struct GlobalsObject {
@@ -16,11 +18,12 @@ pub struct GlobalsObject {
 impl GlobalsObject {
     pub(crate) fn init_on_cluster<'a>(
         cluster: &'a Cluster,
+        wasm_module: &Rc<WasmModule>,
         globals_meta: &[Global],
         imports: &[RTGlobalImport],
         engine: &mut Engine,
     ) -> Result<&'a mut GlobalsObject, InstantiationError> {
-        let storage_size = globals_meta.len() * 8;
+        let storage_size = globals_meta.len() * std::mem::size_of::<ValueRaw>();
         let storage = if storage_size > 0 {
             unsafe {
                 libc::mmap(
@@ -56,6 +59,7 @@ impl GlobalsObject {
                 engine.set_global_addr(idx as GlobalIdx, addr.cast());
             }
         }
+        let mut allocator = ConstantAllocator::new(cluster, wasm_module.clone());
         for (idx, global) in globals_meta.iter().enumerate() {
             if !global.import {
                 unsafe {
@@ -65,6 +69,16 @@ impl GlobalsObject {
                             *globals[glob_idx as usize].addr.as_ptr()
                         }
                         ConstantValue::FuncPtr(func_idx) => ValueRaw::funcref(func_idx),
+                        ConstantValue::Expr(ops) => eval_allocating_constant_ops(
+                            &ops,
+                            |glob_idx| {
+                                Ok::<_, InstantiationError>(
+                                    *globals[glob_idx as usize].addr.as_ptr(),
+                                )
+                            },
+                            |allocation, operands| Ok(allocator.alloc(allocation, operands)?),
+                        )?
+                        .into(),
                     }
                 }
             }
@@ -88,7 +102,7 @@ impl Drop for GlobalsObject {
         unsafe {
             libc::munmap(
                 self.inner.storage as *mut libc::c_void,
-                self.inner.globals.len() * 8,
+                self.inner.globals.len() * std::mem::size_of::<ValueRaw>(),
             );
         }
     }
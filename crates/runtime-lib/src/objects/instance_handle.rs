@@ -5,6 +5,7 @@ use crate::{
     },
     linker::{rt_func_imports, DependencyStore},
     objects::{
+        exceptions::TagInstance,
         functions::{Function, FunctionKind},
         globals::GlobalsObject,
         memory::{MemoryError, MemoryObject, MemoryStorage},
@@ -22,9 +23,9 @@ use std::{
     sync::Mutex,
 };
 use wasi::{WasiContext, WasiError};
-use wasm_types::{FuncIdx, FuncType, GlobalIdx, MemIdx, TableIdx};
+use wasm_types::{FuncIdx, FuncType, GlobalIdx, MemIdx, TableIdx, TagIdx};
 
-use super::{engine::EngineError, functions::HostFuncRawContainer};
+use super::{engine::EngineError, functions::HostFuncRawContainer, gc::GcError};
 
 #[derive(thiserror::Error, Debug)]
 pub enum InstantiationError {
@@ -38,6 +39,8 @@ pub enum InstantiationError {
     FunctionNotFound(String),
     #[error("Error during WASI initialization: {0}")]
     WasiError(#[from] WasiError),
+    #[error("Error during allocation of a constant expression: {0}")]
+    AllocationError(#[from] GcError),
 }
 
 pub struct InstanceHandle<'a> {
@@ -50,6 +53,7 @@ pub struct InstanceHandle<'a> {
     globals: &'a mut GlobalsObject,
     tables: Vec<TableInstance<'a>>,
     memories: &'a mut [MemoryObject],
+    tags: &'a mut [TagInstance],
 
     exported_functions: Mutex<HashMap<String, Either<&'a Function, FuncIdx>>>,
     wasi_context: Option<&'a mut WasiContext>,
@@ -71,8 +75,11 @@ impl<'a> InstanceHandle<'a> {
             globals_len: 0,
             memories_ptr: null_mut(),
             memories_len: 0,
+            tags_ptr: null_mut(),
+            tags_len: 0,
             wasm_module: m.clone(),
             engine: engine as *mut Engine as *mut ffi::c_void,
+            cluster: cluster as *const Cluster as *const ffi::c_void,
             trap_msg: None,
             recursion_size: 0,
             id: 0,
@@ -121,13 +128,13 @@ impl<'a> InstanceHandle<'a> {
 
         // initialize globals
         let globals =
-            GlobalsObject::init_on_cluster(cluster, &m.meta.globals, &imports.globals, engine)?;
+            GlobalsObject::init_on_cluster(cluster, &m, &m.meta.globals, &imports.globals, engine)?;
         execution_context.globals_ptr = &mut globals.inner as *mut runtime_interface::GlobalStorage;
         execution_context.globals_len = 1;
 
         // initialize tables
         let tables = Self::init_tables_on_cluster(
-            m.as_ref(),
+            &m,
             engine,
             cluster,
             &m.meta.tables,
@@ -150,6 +157,21 @@ impl<'a> InstanceHandle<'a> {
             memories.as_mut_ptr() as *mut runtime_interface::MemoryInstance;
         execution_context.memories_len = memories.len();
 
+        // initialize tags, imported tags keep the identity of the exporting module's tag
+        let mut imported_tags = imports.tags.iter();
+        let tags = cluster.alloc_tags(
+            m.meta
+                .tags
+                .iter()
+                .map(|tag| match tag.import {
+                    true => *imported_tags.next().unwrap(),
+                    false => TagInstance::new(m.meta.function_types[tag.type_idx as usize]),
+                })
+                .collect(),
+        );
+        execution_context.tags_ptr = tags.as_mut_ptr() as *mut ffi::c_void;
+        execution_context.tags_len = tags.len();
+
         let exported_functions = Mutex::new(
             m.meta
                 .exports
@@ -166,6 +188,7 @@ impl<'a> InstanceHandle<'a> {
             globals,
             tables,
             memories,
+            tags,
             cluster,
             exported_functions,
             wasi_context,
@@ -292,6 +315,10 @@ impl<'a> InstanceHandle<'a> {
         &self.tables[table_idx as usize]
     }
 
+    pub(crate) fn tags(&self, tag_idx: TagIdx) -> &TagInstance {
+        &self.tags[tag_idx as usize]
+    }
+
     pub(crate) fn execution_context_ref(&self) -> &ExecutionContext {
         self.execution_context
     }
@@ -331,6 +358,7 @@ impl<'a> Clone for InstanceHandle<'a> {
                     self.memories.as_ptr() as *mut _,
                     self.memories.len(),
                 ),
+                tags: slice::from_raw_parts_mut(self.tags.as_ptr() as *mut _, self.tags.len()),
 
                 exported_functions: Mutex::new(self.exported_functions.lock().unwrap().clone()),
                 wasi_context: match &self.wasi_context {
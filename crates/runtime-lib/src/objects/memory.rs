@@ -1,9 +1,10 @@
 use crate::{
     error::RuntimeError, linker::RTMemoryImport, objects::execution_context::trap_on_err, Cluster,
-    WASM_PAGE_LIMIT, WASM_PAGE_SIZE, WASM_RESERVED_MEMORY_SIZE,
+    WASM64_PAGE_LIMIT, WASM_PAGE_LIMIT, WASM_PAGE_SIZE, WASM_RESERVED_MEMORY_SIZE,
 };
 use core::slice;
 use module::objects::data::{Data, DataMode};
+use module::objects::expression::eval_constant_ops;
 use module::objects::memory::Memory;
 use module::objects::module::Module as WasmModule;
 use module::objects::value::{ConstantValue, Number, Value};
@@ -13,12 +14,12 @@ use nix::{errno, libc};
 use runtime_interface::GlobalStorage;
 use std::ops::Index;
 use std::rc::Rc;
-use wasm_types::{DataIdx, MemIdx, ValType};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::time::Duration;
+use wasm_types::{DataIdx, IndexType, MemIdx, ValType};
 
 #[derive(Debug, thiserror::Error)]
 pub enum MemoryError {
-    #[error("Encountered reference to memory with index != 0. A maximum of one memory is allowed per wasm module.")]
-    MemoryIdxNotZero,
     #[error("Offset into segment was of invalid type '{0:}'")]
     InvalidOffsetType(ValType),
     #[error("Supplied datasource too small.")]
@@ -31,6 +32,14 @@ pub enum MemoryError {
     MemoryCopyOOB,
     #[error("Data segment index out of bounds.")]
     DataIdxOOB,
+    #[error("out of bounds memory access")]
+    AtomicAccessOOB,
+    #[error("unaligned atomic")]
+    UnalignedAtomic,
+    #[error("expected shared memory")]
+    WaitOnUnsharedMemory,
+    #[error("Memory minimum of {0} pages exceeds the supported maximum.")]
+    MemoryTooLarge(u64),
     #[error("Allocation Failure ({0})")]
     AllocationFailure(Errno),
 }
@@ -39,7 +48,7 @@ pub enum MemoryError {
 pub(crate) struct MemoryObject(pub(crate) runtime_interface::MemoryInstance);
 
 impl MemoryObject {
-    pub(crate) fn new(data: *mut u8, size: u32, max_size: u32) -> Self {
+    pub(crate) fn new(data: *mut u8, size: u64, max_size: u64) -> Self {
         Self(runtime_interface::MemoryInstance {
             data,
             size,
@@ -47,27 +56,43 @@ impl MemoryObject {
         })
     }
 
-    pub(crate) fn grow(&mut self, grow_by: u32) -> i32 {
+    /// Amount of address space reserved for a memory of at most `max_size` pages.
+    /// 32-bit memories reserve everything reachable from wasm code, so that out of bounds accesses hit
+    /// guard pages. This doesn't scale to 64-bit memories, which are bounds checked explicitly instead.
+    fn reserved_size(max_size: u64) -> u64 {
+        WASM_RESERVED_MEMORY_SIZE.max(max_size * WASM_PAGE_SIZE as u64)
+    }
+
+    fn byte_size(&self) -> u64 {
+        self.0.size * WASM_PAGE_SIZE as u64
+    }
+
+    /// Whether `[offset, offset + size)` lies within the memory.
+    fn in_bounds(&self, offset: u64, size: u64) -> bool {
+        offset
+            .checked_add(size)
+            .is_some_and(|end| end <= self.byte_size())
+    }
+
+    pub(crate) fn grow(&mut self, grow_by: u64) -> i64 {
         if grow_by == 0 {
-            return self.0.size as i32;
+            return self.0.size as i64;
         }
 
-        // larger than 2**32 = 4GiB?
-        if self.0.size + grow_by > 2_u32.pow(16) {
-            log::debug!("Memory grow failed: larger than 4GiB");
-            return -1;
-        }
-        // larger than memory::limits::max_size?
-        if self.0.size + grow_by > self.0.max_size {
-            return -1;
-        }
+        // larger than memory::limits::max_size? (which is capped at the page limit of the index type)
+        let new_size = match self.0.size.checked_add(grow_by) {
+            Some(new_size) if new_size <= self.0.max_size => new_size,
+            _ => {
+                log::debug!("Memory grow failed: larger than {} pages", self.0.max_size);
+                return -1;
+            }
+        };
 
         // increase size!
-        let new_size = (self.0.size + grow_by) * WASM_PAGE_SIZE;
         let res = unsafe {
             mprotect(
                 self.0.data as *mut libc::c_void,
-                new_size as usize,
+                (new_size * WASM_PAGE_SIZE as u64) as usize,
                 libc::PROT_READ | libc::PROT_WRITE,
             )
         };
@@ -76,12 +101,12 @@ impl MemoryObject {
             return -1;
         }
         let old_size = self.0.size;
-        self.0.size += grow_by;
-        old_size as i32
+        self.0.size = new_size;
+        old_size as i64
     }
 
-    pub(crate) fn fill(&self, offset: u32, size: u32, value: u8) -> Result<(), MemoryError> {
-        if offset + size > self.0.size * WASM_PAGE_SIZE {
+    pub(crate) fn fill(&self, offset: u64, size: u64, value: u8) -> Result<(), MemoryError> {
+        if !self.in_bounds(offset, size) {
             return Err(MemoryError::MemoryFillOOB);
         }
         if size == 0 {
@@ -96,16 +121,18 @@ impl MemoryObject {
         Ok(())
     }
 
+    /// Copies `size` bytes from `src` into this memory, `src` may be this memory itself.
     pub(crate) fn copy(
         &self,
-        src_offset: u32,
-        dst_offset: u32,
-        size: u32,
+        src: &MemoryObject,
+        src_offset: u64,
+        dst_offset: u64,
+        size: u64,
     ) -> Result<(), MemoryError> {
-        if src_offset + size > self.0.size * WASM_PAGE_SIZE {
+        if !src.in_bounds(src_offset, size) {
             return Err(MemoryError::MemoryCopyOOB);
         }
-        if dst_offset + size > self.0.size * WASM_PAGE_SIZE {
+        if !self.in_bounds(dst_offset, size) {
             return Err(MemoryError::MemoryCopyOOB);
         }
         if size == 0 {
@@ -113,7 +140,7 @@ impl MemoryObject {
         }
         unsafe {
             std::ptr::copy(
-                self.0.data.add(src_offset as usize),
+                src.0.data.add(src_offset as usize),
                 self.0.data.add(dst_offset as usize),
                 size as usize,
             )
@@ -125,7 +152,7 @@ impl MemoryObject {
         &self,
         data_source: &[u8],
         src_offset: u32,
-        dst_offset: u32,
+        dst_offset: u64,
         size: Option<u32>,
     ) -> Result<(), MemoryError> {
         unsafe {
@@ -135,7 +162,7 @@ impl MemoryObject {
             if src_offset as u64 + size > data_source.len() as u64 {
                 return Err(MemoryError::DataSourceOOB);
             }
-            if dst_offset as u64 + size > self.0.size as u64 * WASM_PAGE_SIZE as u64 {
+            if !self.in_bounds(dst_offset, size) {
                 return Err(MemoryError::MemoryInitOOB);
             }
             if size == 0 {
@@ -155,7 +182,7 @@ impl MemoryObject {
         wasm_module: Rc<WasmModule>,
         data_idx: DataIdx,
         src_offset: u32,
-        dst_offset: u32,
+        dst_offset: u64,
         size: u32,
     ) -> Result<(), RuntimeError> {
         let data_instance = match wasm_module.meta.datas.get(data_idx as usize) {
@@ -180,7 +207,7 @@ impl Drop for MemoryObject {
         if unsafe {
             libc::munmap(
                 self.0.data as *mut libc::c_void,
-                WASM_RESERVED_MEMORY_SIZE as usize,
+                Self::reserved_size(self.0.max_size) as usize,
             )
         } != 0
         {
@@ -207,17 +234,27 @@ impl<'a> MemoryStorage<'a> {
 
         let mut imports_iter = imports.iter();
         let memories_meta_iter = memories_meta.iter().map(|m| {
-            if m.import {
+            let limits = if m.import {
                 imports_iter.next().unwrap().limits
             } else {
-                m.limits
-            }
+                m.r#type.lim
+            };
+            (limits, m.r#type.index_type)
         });
-        for limits in memories_meta_iter {
+        for (limits, index_type) in memories_meta_iter {
+            let page_limit = match index_type {
+                IndexType::I32 => WASM_PAGE_LIMIT as u64,
+                IndexType::I64 => WASM64_PAGE_LIMIT,
+            };
+            let max_size = limits.max.unwrap_or(page_limit).min(page_limit);
+            if limits.min > max_size {
+                return Err(MemoryError::MemoryTooLarge(limits.min));
+            }
+
             let memory_ptr = unsafe {
                 libc::mmap(
                     core::ptr::null_mut::<libc::c_void>(),
-                    WASM_RESERVED_MEMORY_SIZE as usize,
+                    MemoryObject::reserved_size(max_size) as usize,
                     libc::PROT_NONE,
                     libc::MAP_ANONYMOUS | libc::MAP_PRIVATE | libc::MAP_NORESERVE,
                     -1,
@@ -231,7 +268,7 @@ impl<'a> MemoryStorage<'a> {
             if 0 != unsafe {
                 libc::mprotect(
                     memory_ptr,
-                    (limits.min * WASM_PAGE_SIZE) as usize,
+                    (limits.min * WASM_PAGE_SIZE as u64) as usize,
                     libc::PROT_READ | libc::PROT_WRITE,
                 )
             } {
@@ -240,25 +277,46 @@ impl<'a> MemoryStorage<'a> {
             memories.push(MemoryObject::new(
                 memory_ptr as *mut u8,
                 limits.min,
-                limits.max.unwrap_or(WASM_PAGE_LIMIT),
+                max_size,
             ))
         }
 
         for data in data_meta {
             if let DataMode::Active { memory, offset } = &data.mode {
-                if *memory != 0 {
-                    return Err(MemoryError::MemoryIdxNotZero);
-                }
+                let index_type = memories_meta[*memory as usize].r#type.index_type;
                 let memory = &memories[*memory as usize];
-                let offset = match offset {
-                    ConstantValue::V(Value::Number(Number::I32(offset))) => *offset,
-                    ConstantValue::V(v) => return Err(MemoryError::InvalidOffsetType(v.r#type())),
-                    ConstantValue::Global(idx) => {
-                        unsafe { globals.globals[*idx as usize].addr.as_ref() }.as_u32()
+                let offset = match (offset, index_type) {
+                    (ConstantValue::V(Value::Number(Number::I32(offset))), IndexType::I32) => {
+                        *offset as u64
+                    }
+                    (ConstantValue::V(Value::Number(Number::I64(offset))), IndexType::I64) => {
+                        *offset
+                    }
+                    (ConstantValue::V(v), _) => {
+                        return Err(MemoryError::InvalidOffsetType(v.r#type()))
                     }
-                    ConstantValue::FuncPtr(_) => {
+                    (ConstantValue::Global(idx), _) => {
+                        let value = unsafe { globals.globals[*idx as usize].addr.as_ref() };
+                        match index_type {
+                            IndexType::I32 => value.as_u32() as u64,
+                            IndexType::I64 => value.as_u64(),
+                        }
+                    }
+                    (ConstantValue::FuncPtr(_), _) => {
                         unimplemented!()
                     }
+                    (ConstantValue::Expr(ops), _) => {
+                        let value = eval_constant_ops(ops, |idx| {
+                            Ok::<_, MemoryError>(unsafe {
+                                *globals.globals[idx as usize].addr.as_ref()
+                            })
+                        })?;
+                        match (value, index_type) {
+                            (Value::Number(Number::I32(offset)), IndexType::I32) => offset as u64,
+                            (Value::Number(Number::I64(offset)), IndexType::I64) => offset,
+                            (v, _) => return Err(MemoryError::InvalidOffsetType(v.r#type())),
+                        }
+                    }
                 };
                 memory.init(data.init.as_slice(), 0, offset, None)?;
                 // TODO: drop data
@@ -281,8 +339,8 @@ impl Index<usize> for MemoryStorage<'_> {
 extern "C" fn memory_grow(
     ctxt: &runtime_interface::ExecutionContext,
     memory_idx: usize,
-    grow_by: u32,
-) -> i32 {
+    grow_by: u64,
+) -> i64 {
     let memories = MemoryStorage(unsafe {
         slice::from_raw_parts_mut(ctxt.memories_ptr as *mut MemoryObject, ctxt.memories_len)
     });
@@ -292,14 +350,15 @@ extern "C" fn memory_grow(
 
 fn memory_fill_impl(
     ctxt: &mut runtime_interface::ExecutionContext,
-    offset: u32,
-    size: u32,
+    memory_idx: MemIdx,
+    offset: u64,
+    size: u64,
     value: u8,
 ) -> Result<(), MemoryError> {
     let memories = MemoryStorage(unsafe {
         slice::from_raw_parts_mut(ctxt.memories_ptr as *mut MemoryObject, ctxt.memories_len)
     });
-    let memory = &memories.0[0];
+    let memory = &memories.0[memory_idx as usize];
     memory.fill(offset, size, value)
 }
 
@@ -307,36 +366,47 @@ fn memory_fill_impl(
 extern "C" fn memory_fill(
     ctxt: &mut runtime_interface::ExecutionContext,
     memory_idx: MemIdx,
-    offset: u32,
-    size: u32,
+    offset: u64,
+    size: u64,
     value: u8,
 ) {
-    let res = memory_fill_impl(ctxt, offset, size, value);
+    let res = memory_fill_impl(ctxt, memory_idx, offset, size, value);
     trap_on_err(ctxt, res)
 }
 
 fn memory_copy_impl(
     ctxt: &mut runtime_interface::ExecutionContext,
-    src_offset: u32,
-    dst_offset: u32,
-    size: u32,
+    dst_memory_idx: MemIdx,
+    src_memory_idx: MemIdx,
+    src_offset: u64,
+    dst_offset: u64,
+    size: u64,
 ) -> Result<(), MemoryError> {
     let memories = MemoryStorage(unsafe {
         slice::from_raw_parts_mut(ctxt.memories_ptr as *mut MemoryObject, ctxt.memories_len)
     });
-    let memory = &memories.0[0];
-    memory.copy(src_offset, dst_offset, size)
+    let dst = &memories.0[dst_memory_idx as usize];
+    let src = &memories.0[src_memory_idx as usize];
+    dst.copy(src, src_offset, dst_offset, size)
 }
 
 #[no_mangle]
 extern "C" fn memory_copy(
     ctxt: &mut runtime_interface::ExecutionContext,
-    memory_idx: MemIdx,
-    src_offset: u32,
-    dst_offset: u32,
-    size: u32,
+    dst_memory_idx: MemIdx,
+    src_memory_idx: MemIdx,
+    src_offset: u64,
+    dst_offset: u64,
+    size: u64,
 ) {
-    let res = memory_copy_impl(ctxt, src_offset, dst_offset, size);
+    let res = memory_copy_impl(
+        ctxt,
+        dst_memory_idx,
+        src_memory_idx,
+        src_offset,
+        dst_offset,
+        size,
+    );
     trap_on_err(ctxt, res)
 }
 
@@ -345,7 +415,7 @@ fn memory_init_impl(
     memory_idx: MemIdx,
     data_idx: DataIdx,
     src_offset: u32,
-    dst_offset: u32,
+    dst_offset: u64,
     size: u32,
 ) -> Result<(), RuntimeError> {
     let memories = MemoryStorage(unsafe {
@@ -367,7 +437,7 @@ extern "C" fn memory_init(
     memory_idx: MemIdx,
     data_idx: DataIdx,
     src_offset: u32,
-    dst_offset: u32,
+    dst_offset: u64,
     size: u32,
 ) {
     let res = memory_init_impl(ctxt, memory_idx, data_idx, src_offset, dst_offset, size);
@@ -387,3 +457,111 @@ fn data_drop_impl(
 
 #[no_mangle]
 extern "C" fn data_drop(ctxt: &mut runtime_interface::ExecutionContext, data_idx: DataIdx) {}
+
+/// Resolves the host address of an atomic access of `width` bytes, checking alignment and bounds.
+fn atomic_access_ptr(
+    ctxt: &runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    width: u64,
+) -> Result<*mut u8, MemoryError> {
+    let memory = unsafe { &*ctxt.memories_ptr.add(memory_idx as usize) };
+    if address % width != 0 {
+        return Err(MemoryError::UnalignedAtomic);
+    }
+    if !address
+        .checked_add(width)
+        .is_some_and(|end| end <= memory.size * WASM_PAGE_SIZE as u64)
+    {
+        return Err(MemoryError::AtomicAccessOOB);
+    }
+    Ok(unsafe { memory.data.add(address as usize) })
+}
+
+fn memory_atomic_wait_impl(
+    ctxt: &runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    width: u64,
+    value_matches: impl FnOnce(*mut u8) -> bool,
+    timeout: i64,
+) -> Result<u32, MemoryError> {
+    let ptr = atomic_access_ptr(ctxt, memory_idx, address, width)?;
+    if !ctxt.wasm_module.meta.memories[memory_idx as usize]
+        .r#type
+        .shared
+    {
+        return Err(MemoryError::WaitOnUnsharedMemory);
+    }
+    // negative timeouts never expire
+    let timeout = u64::try_from(timeout).ok().map(Duration::from_nanos);
+    let cluster = unsafe { &*(ctxt.cluster as *const Cluster) };
+    Ok(cluster.wait(ptr as usize, || value_matches(ptr), timeout) as u32)
+}
+
+#[no_mangle]
+extern "C" fn memory_atomic_wait32(
+    ctxt: &mut runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    expected: u32,
+    timeout: i64,
+) -> u32 {
+    let res = memory_atomic_wait_impl(
+        ctxt,
+        memory_idx,
+        address,
+        4,
+        |ptr| unsafe { AtomicU32::from_ptr(ptr as *mut u32) }.load(Ordering::SeqCst) == expected,
+        timeout,
+    );
+    trap_on_err(ctxt, res)
+}
+
+#[no_mangle]
+extern "C" fn memory_atomic_wait64(
+    ctxt: &mut runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    expected: u64,
+    timeout: i64,
+) -> u32 {
+    let res = memory_atomic_wait_impl(
+        ctxt,
+        memory_idx,
+        address,
+        8,
+        |ptr| unsafe { AtomicU64::from_ptr(ptr as *mut u64) }.load(Ordering::SeqCst) == expected,
+        timeout,
+    );
+    trap_on_err(ctxt, res)
+}
+
+fn memory_atomic_notify_impl(
+    ctxt: &runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    count: u32,
+) -> Result<u32, MemoryError> {
+    let ptr = atomic_access_ptr(ctxt, memory_idx, address, 4)?;
+    // unshared memories can't have waiters
+    if !ctxt.wasm_module.meta.memories[memory_idx as usize]
+        .r#type
+        .shared
+    {
+        return Ok(0);
+    }
+    let cluster = unsafe { &*(ctxt.cluster as *const Cluster) };
+    Ok(cluster.notify(ptr as usize, count))
+}
+
+#[no_mangle]
+extern "C" fn memory_atomic_notify(
+    ctxt: &mut runtime_interface::ExecutionContext,
+    memory_idx: MemIdx,
+    address: u64,
+    count: u32,
+) -> u32 {
+    let res = memory_atomic_notify_impl(ctxt, memory_idx, address, count);
+    trap_on_err(ctxt, res)
+}
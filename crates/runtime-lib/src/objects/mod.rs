@@ -1,6 +1,8 @@
 pub(crate) mod engine;
+pub(crate) mod exceptions;
 pub(crate) mod execution_context;
 pub(crate) mod functions;
+pub(crate) mod gc;
 pub(crate) mod globals;
 pub(crate) mod instance_handle;
 pub(crate) mod memory;
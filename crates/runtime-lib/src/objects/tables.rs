@@ -3,6 +3,7 @@ use crate::{
     objects::{
         engine::EngineError,
         execution_context::{trap_on_err, ExecutionContextWrapper},
+        gc::ConstantAllocator,
         instance_handle::InstantiationError,
     },
     Cluster, Engine, InstanceHandle, RuntimeError,
@@ -11,6 +12,7 @@ use core::slice;
 use module::{
     objects::{
         element::{ElemMode, Element, ElementInit},
+        expression::{eval_allocating_constant_ops, eval_constant_ops},
         module::Module as WasmModule,
         table::Table,
         value::{ConstantValue, Number, Reference, Value, ValueRaw},
@@ -18,7 +20,10 @@ use module::{
     utils::numeric_transmutes::Bit32,
 };
 use runtime_interface::{ExecutionContext, GlobalStorage, RawPointer};
-use wasm_types::{ElemIdx, FuncIdx, FuncType, RefType, TableIdx, TableType, TypeIdx, ValType};
+use std::rc::Rc;
+use wasm_types::{
+    ElemIdx, FuncIdx, FuncType, HeapType, RefType, TableIdx, TableType, TypeIdx, ValType,
+};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TableError {
@@ -57,9 +62,66 @@ pub(crate) enum TableItem {
     ExternReference {
         func_ptr: Option<RawPointer>,
     },
+    /// `exnref` to an exception on the heap of the cluster
+    ExceptionReference(u64),
+    /// reference of the `any` hierarchy, see [`module::objects::value::gc`]
+    GcReference(u64),
     Null,
 }
 
+impl TableItem {
+    /// Converts the non-null raw `value` of a reference of `ref_type` into a table item.
+    fn from_raw(
+        engine: &Engine,
+        wasm_module: &WasmModule,
+        ref_type: RefType,
+        value: ValueRaw,
+    ) -> Result<Self, TableError> {
+        Ok(match ref_type.heap_type() {
+            HeapType::Func | HeapType::NoFunc => {
+                Self::function_reference(engine, wasm_module, value)?
+            }
+            HeapType::Concrete(type_idx)
+                if wasm_module
+                    .meta
+                    .types
+                    .get(type_idx as usize)
+                    .is_some_and(|t| t.as_func().is_some()) =>
+            {
+                Self::function_reference(engine, wasm_module, value)?
+            }
+            HeapType::Extern | HeapType::NoExtern => TableItem::ExternReference {
+                func_ptr: RawPointer::new(value.as_externref() as _),
+            },
+            HeapType::Exn | HeapType::NoExn => TableItem::ExceptionReference(value.as_u64()),
+            HeapType::Any
+            | HeapType::Eq
+            | HeapType::I31
+            | HeapType::Struct
+            | HeapType::Array
+            | HeapType::None
+            | HeapType::Concrete(_) => TableItem::GcReference(value.as_u64()),
+        })
+    }
+
+    fn function_reference(
+        engine: &Engine,
+        wasm_module: &WasmModule,
+        value: ValueRaw,
+    ) -> Result<Self, TableError> {
+        let func_idx = value.as_funcref();
+        debug_assert!(func_idx < wasm_module.meta.functions.len() as u32);
+        Ok(TableItem::FunctionReference {
+            func_idx,
+            #[cfg(feature = "lazy-tables")]
+            func_ptr: None,
+            #[cfg(not(feature = "lazy-tables"))]
+            func_ptr: engine.get_internal_function_ptr(func_idx)?,
+            func_type: wasm_module.meta.functions[func_idx as usize].type_idx,
+        })
+    }
+}
+
 #[repr(transparent)]
 #[derive(Debug)]
 pub struct TableObject(pub Vec<TableItem>);
@@ -74,7 +136,7 @@ pub struct TableInstance<'a> {
 
 impl InstanceHandle<'_> {
     pub(crate) fn init_tables_on_cluster<'a>(
-        wasm_module: &WasmModule,
+        wasm_module: &Rc<WasmModule>,
         engine: &Engine,
         cluster: &'a Cluster,
         tables_meta: &[Table],
@@ -107,6 +169,33 @@ impl InstanceHandle<'_> {
             })
             .collect::<Vec<_>>();
 
+        let mut allocator = ConstantAllocator::new(cluster, wasm_module.clone());
+        for (table, table_meta) in tables.iter_mut().zip(tables_meta.iter()) {
+            let Some(init) = table_meta.init.clone() else {
+                continue;
+            };
+            let value: ValueRaw = match init {
+                ConstantValue::V(value) => value.into(),
+                ConstantValue::Global(idx) => unsafe {
+                    *globals.globals[idx as usize].addr.as_ref()
+                },
+                ConstantValue::FuncPtr(func_idx) => ValueRaw::funcref(func_idx),
+                ConstantValue::Expr(ops) => eval_allocating_constant_ops(
+                    &ops,
+                    |idx| {
+                        Ok::<_, InstantiationError>(unsafe {
+                            *globals.globals[idx as usize].addr.as_ref()
+                        })
+                    },
+                    |allocation, operands| Ok(allocator.alloc(allocation, operands)?),
+                )?
+                .into(),
+            };
+            for idx in 0..table.values.0.len() as u32 {
+                table.set(engine, wasm_module, value.as_u64(), idx)?;
+            }
+        }
+
         for elem in elems_meta.iter() {
             if let ElemMode::Active { table, offset } = &elem.mode {
                 if *table as usize >= tables.len() {
@@ -126,6 +215,16 @@ impl InstanceHandle<'_> {
                         globals.globals[*idx as usize].addr.as_ref().as_u32()
                     },
                     ConstantValue::FuncPtr(_) => unimplemented!(),
+                    ConstantValue::Expr(ops) => {
+                        match eval_constant_ops(ops, |idx| {
+                            Ok::<_, TableError>(unsafe {
+                                *globals.globals[idx as usize].addr.as_ref()
+                            })
+                        })? {
+                            Value::Number(Number::I32(offset)) => offset,
+                            v => return Err(TableError::InvalidOffsetType(v.r#type()).into()),
+                        }
+                    }
                 };
                 tables[*table as usize].init(wasm_module, engine, elem, 0, offset, n as u32)?
                 // TODO: drop elem
@@ -152,33 +251,8 @@ impl TableInstance<'_> {
             self.values.0[idx as usize] = TableItem::Null;
             return Ok(());
         }
-        match self.ty.ref_type {
-            RefType::FunctionReference => {
-                let func_idx = value.as_funcref();
-                debug_assert!(func_idx < wasm_module.meta.functions.len() as u32);
-                #[cfg(feature = "lazy-tables")]
-                {
-                    self.values.0[idx as usize] = TableItem::FunctionReference {
-                        func_idx,
-                        func_ptr: None,
-                        func_type: wasm_module.meta.functions[func_idx as usize].type_idx,
-                    }
-                }
-                #[cfg(not(feature = "lazy-tables"))]
-                {
-                    self.values.0[idx as usize] = TableItem::FunctionReference {
-                        func_idx,
-                        func_ptr: engine.get_internal_function_ptr(func_idx)?,
-                        func_type: wasm_module.meta.functions[func_idx as usize].type_idx,
-                    }
-                }
-            }
-            RefType::ExternReference => {
-                self.values.0[idx as usize] = TableItem::ExternReference {
-                    func_ptr: RawPointer::new(value.as_externref() as _),
-                }
-            }
-        }
+        self.values.0[idx as usize] =
+            TableItem::from_raw(engine, wasm_module, self.ty.ref_type, value)?;
         Ok(())
     }
 
@@ -191,6 +265,8 @@ impl TableInstance<'_> {
             TableItem::ExternReference { func_ptr } => Ok(Value::externref(
                 func_ptr.map(|ptr| ptr.as_ptr() as u64).unwrap_or(0),
             )),
+            TableItem::ExceptionReference(exn) => Ok(Value::Reference(Reference::Exception(exn))),
+            TableItem::GcReference(raw) => Ok(Value::Reference(Reference::Gc(raw))),
             TableItem::Null => Ok(Value::Reference(Reference::Null)),
         }
     }
@@ -213,7 +289,7 @@ impl TableInstance<'_> {
         }
 
         let new_len = old_len + size as usize;
-        if new_len > self.ty.lim.max.unwrap_or(u32::MAX) as usize {
+        if new_len > self.ty.lim.max.unwrap_or(u32::MAX as u64) as usize {
             log::debug!("Called table.grow with size > max size. Ignoring.");
             return Ok(err);
         }
@@ -229,34 +305,8 @@ impl TableInstance<'_> {
             self.values.0.resize(new_len, TableItem::Null);
             return Ok(old_len as u32);
         } else {
-            let table_value_to_fill = match self.ty.ref_type {
-                RefType::FunctionReference => {
-                    #[cfg(feature = "lazy-tables")]
-                    {
-                        TableItem::FunctionReference {
-                            func_idx: value_to_fill.as_funcref(),
-                            func_ptr: None,
-                            func_type: wasm_module.meta.functions
-                                [value_to_fill.as_funcref() as usize]
-                                .type_idx,
-                        }
-                    }
-                    #[cfg(not(feature = "lazy-tables"))]
-                    {
-                        TableItem::FunctionReference {
-                            func_idx: value_to_fill.as_funcref(),
-                            func_ptr: engine
-                                .get_internal_function_ptr(value_to_fill.as_funcref())?,
-                            func_type: wasm_module.meta.functions
-                                [value_to_fill.as_funcref() as usize]
-                                .type_idx,
-                        }
-                    }
-                }
-                RefType::ExternReference => TableItem::ExternReference {
-                    func_ptr: RawPointer::new(value_to_fill.as_externref() as _),
-                },
-            };
+            let table_value_to_fill =
+                TableItem::from_raw(engine, wasm_module, self.ty.ref_type, value_to_fill)?;
             self.values.0.resize(new_len, table_value_to_fill);
         }
         Ok(old_len as u32)
@@ -283,23 +333,7 @@ impl TableInstance<'_> {
         {
             TableItem::Null
         } else {
-            match self.ty.ref_type {
-                #[cfg(feature = "lazy-tables")]
-                RefType::FunctionReference => TableItem::FunctionReference {
-                    func_idx: value.as_funcref(),
-                    func_ptr: None,
-                    func_type: wasm_module.meta.functions[value.as_funcref() as usize].type_idx,
-                },
-                #[cfg(not(feature = "lazy-tables"))]
-                RefType::FunctionReference => TableItem::FunctionReference {
-                    func_idx: value.as_funcref(),
-                    func_ptr: engine.get_internal_function_ptr(value.as_funcref())?,
-                    func_type: wasm_module.meta.functions[value.as_funcref() as usize].type_idx,
-                },
-                RefType::ExternReference => TableItem::ExternReference {
-                    func_ptr: RawPointer::new(value.as_externref() as _),
-                },
-            }
+            TableItem::from_raw(engine, wasm_module, self.ty.ref_type, value)?
         };
         self.values.0[start as usize..(start + len) as usize].fill(value_to_fill);
         Ok(())
@@ -369,6 +403,9 @@ impl TableInstance<'_> {
                             wasm_module.meta.globals[idx as usize].val_type(),
                         ),
                         ConstantValue::FuncPtr(func_idx) => Value::funcref(func_idx),
+                        ConstantValue::Expr(ops) => {
+                            eval_constant_ops(&ops, |idx| engine.get_global_value(idx))?
+                        }
                     };
                     self.values.0[(dst_offset as usize) + i] = match val {
                         #[cfg(feature = "lazy-tables")]
@@ -394,6 +431,10 @@ impl TableInstance<'_> {
                                 func_ptr: RawPointer::new(func_ptr as _),
                             }
                         }
+                        Value::Reference(Reference::Exception(exn)) => {
+                            TableItem::ExceptionReference(exn)
+                        }
+                        Value::Reference(Reference::Gc(raw)) => TableItem::GcReference(raw),
                         Value::Reference(Reference::Null) => TableItem::Null,
                         _ => unreachable!(),
                     }
@@ -438,6 +479,11 @@ fn indirect_call_impl(
     ctxt.indirect_call(table_idx, entry_idx, ty_idx)
 }
 
+fn ref_call_impl(ctxt: &mut ExecutionContext, func_ref: u64) -> Result<RawPointer, RuntimeError> {
+    let mut ctxt = ExecutionContextWrapper(ctxt);
+    ctxt.ref_call(func_ref)
+}
+
 fn table_set_impl(
     ctxt: &mut ExecutionContext,
     table_idx: usize,
@@ -574,6 +620,11 @@ pub extern "C" fn indirect_call(
     trap_on_err(ctxt, res)
 }
 #[no_mangle]
+pub extern "C" fn ref_call(ctxt: &mut ExecutionContext, func_ref: u64) -> RawPointer {
+    let res = ref_call_impl(ctxt, func_ref);
+    trap_on_err(ctxt, res)
+}
+#[no_mangle]
 pub extern "C" fn table_set(ctxt: &mut ExecutionContext, table_idx: usize, value: u64, idx: u32) {
     let res = table_set_impl(ctxt, table_idx, value, idx);
     trap_on_err(ctxt, res)
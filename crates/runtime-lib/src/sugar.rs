@@ -3,15 +3,24 @@ use resource_buffer::{ResourceBuffer, SourceFormat};
 use std::path::Path;
 
 pub fn module_from_file(file: &Path) -> Result<Module, ModuleError> {
+    module_from_file_with_key(file, None)
+}
+
+/// Like [`module_from_file`], but cwasm files have to be authenticated with `cwasm_key`, if one is given.
+/// The key doesn't apply to wasm and wat files.
+#[cfg_attr(not(feature = "llvm"), allow(unused_variables))]
+pub fn module_from_file_with_key(
+    file: &Path,
+    cwasm_key: Option<&[u8]>,
+) -> Result<Module, ModuleError> {
     let buf = ResourceBuffer::from_file(file)?;
     match buf.kind() {
-        SourceFormat::Wasm => {
+        SourceFormat::Wasm | SourceFormat::Wat => {
             parser::Parser::parse(buf).map_err(|e| ModuleError::Msg(e.to_string()))
         }
         #[cfg(feature = "llvm")]
-        SourceFormat::Cwasm => {
-            llvm_gen::aot::parse_aot_meta(buf).map_err(|e| ModuleError::Msg(e.to_string()))
-        }
+        SourceFormat::Cwasm => llvm_gen::aot::parse_aot_meta_with_key(buf, cwasm_key)
+            .map_err(|e| ModuleError::Msg(e.to_string())),
         #[cfg(not(feature = "llvm"))]
         SourceFormat::Cwasm => Err(ModuleError::Msg(
             "Cwasm files are not supported without the `llvm` feature.".to_string(),
@@ -19,20 +28,79 @@ pub fn module_from_file(file: &Path) -> Result<Module, ModuleError> {
     }
 }
 
+/// Load a module from a binary or text buffer. Buffers are assembled as text if their first non-whitespace byte
+/// starts a module or a comment, i.e. is `(` or `;`. Everything else, including empty buffers, is parsed as a
+/// binary, so truncated or corrupted binaries fail with a binary format error. Use [`module_from_wat_buf`] if the
+/// format is known to be text.
 pub fn module_from_buf(buf: Vec<u8>) -> Result<Module, ModuleError> {
-    let buf = ResourceBuffer::from_wasm_buf(buf);
-    debug_assert_eq!(buf.kind(), SourceFormat::Wasm);
-    match buf.kind() {
-        SourceFormat::Wasm => {
-            parser::Parser::parse(buf).map_err(|e| ModuleError::Msg(e.to_string()))
+    let is_text = buf
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|byte| matches!(byte, b'(' | b';'));
+    if is_text {
+        return module_from_wat_buf(&buf);
+    }
+    parse_module(ResourceBuffer::from_wasm_buf(buf))
+}
+
+/// Load a module from WebAssembly text.
+pub fn module_from_wat_buf(text: &[u8]) -> Result<Module, ModuleError> {
+    parse_module(ResourceBuffer::from_wat_buf(text)?)
+}
+
+fn parse_module(buf: ResourceBuffer) -> Result<Module, ModuleError> {
+    debug_assert_ne!(buf.kind(), SourceFormat::Cwasm);
+    parser::Parser::parse(buf).map_err(|e| ModuleError::Msg(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_of(buf: &[u8]) -> String {
+        match module_from_buf(buf.to_vec()) {
+            Ok(_) => panic!("{buf:?} is not a valid module"),
+            Err(e) => e.to_string(),
         }
-        #[cfg(feature = "llvm")]
-        SourceFormat::Cwasm => {
-            llvm_gen::aot::parse_aot_meta(buf).map_err(|e| ModuleError::Msg(e.to_string()))
+    }
+
+    #[test]
+    fn text_errors_point_to_line_and_column() {
+        let text = "(module\n  (func (result i32)\n    (i32.const 1)\n    (i32.bogus)))\n";
+        let err = error_of(text.as_bytes());
+        assert!(err.contains("Invalid WebAssembly text"), "{err}");
+        assert!(err.contains(":4:6"), "{err}");
+    }
+
+    #[test]
+    fn binaries_are_not_assembled_as_text() {
+        let text = "(module (func))";
+        assert!(module_from_buf(text.as_bytes().to_vec()).is_ok());
+
+        // truncated preamble, a binary that happens to be valid utf-8, an empty buffer and text that isn't a module
+        for binary in [&b"\0as"[..], b"\0asm\x02\0\0\0", b"", b"  \n", b"module"] {
+            let err = error_of(binary);
+            assert!(!err.contains("Invalid WebAssembly text"), "{err}");
         }
-        #[cfg(not(feature = "llvm"))]
-        SourceFormat::Cwasm => Err(ModuleError::Msg(
-            "Cwasm files are not supported without the `llvm` feature.".to_string(),
-        )),
+    }
+
+    #[test]
+    fn text_starts_with_a_module_or_comment() {
+        for text in [
+            "  \n(module)",
+            ";; comment\n(module)",
+            "(; block comment ;) (module)",
+        ] {
+            assert!(
+                module_from_buf(text.as_bytes().to_vec()).is_ok(),
+                "{text:?}"
+            );
+        }
+        // explicitly text, so any content is assembled as text
+        let err = match module_from_wat_buf(b"module") {
+            Ok(_) => panic!("`module` is not a valid module"),
+            Err(e) => e.to_string(),
+        };
+        assert!(err.contains("Invalid WebAssembly text"), "{err}");
     }
 }
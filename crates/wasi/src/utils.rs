@@ -10,7 +10,7 @@ impl WasiContext {
     pub(super) fn get_memory_slice<T>(&self, ptr: Ptr<T>, len: usize) -> Result<&mut [T], Errno> {
         let memory = unsafe { &*(*self.execution_context).memories_ptr.add(0) };
         if std::mem::size_of::<T>() * len + ptr.get() as usize
-            > (memory.size * WASM_PAGE_SIZE) as usize
+            > (memory.size * WASM_PAGE_SIZE as u64) as usize
         {
             return Err(Errno::Inval);
         }
@@ -7,6 +7,7 @@ use std::{collections::HashMap, sync::atomic::AtomicU64};
 
 // https://webassembly.github.io/spec/core/syntax/types.html#result-types
 #[derive(Debug, Default, Clone, Copy, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct FuncType(u64);
 
 #[derive(Default)]
@@ -266,7 +267,9 @@ impl FuncTypeBuilder {
     }
 
     pub fn add_param(mut self, val_type: ValType) -> Self {
-        if self.overflow.is_none() && self.cnt_in >= MAX_NUM_PARAMS {
+        if self.overflow.is_none()
+            && (self.cnt_in >= MAX_NUM_PARAMS || idx_of_valtype(val_type).is_none())
+        {
             let partial_type = FuncType(self.tmp_func_type).r#type();
             self.overflow = Some(partial_type);
         }
@@ -276,7 +279,7 @@ impl FuncTypeBuilder {
         } else {
             let old_idx = (self.tmp_func_type >> PARAM_OFFSET_LOG) & PARAM_INDEX_BITS;
             let new_idx = old_idx
-                + idx_of_valtype(val_type)
+                + idx_of_valtype(val_type).unwrap()
                     * GEOMETRIC_PARTIAL_SUM_LOOKUP[MAX_NUM_PARAMS - self.cnt_in]
                 + 1;
             // set param idx bits zero
@@ -296,7 +299,9 @@ impl FuncTypeBuilder {
     }
 
     pub fn add_result(mut self, val_type: ValType) -> Self {
-        if self.overflow.is_none() && self.cnt_out >= MAX_NUM_RESULTS {
+        if self.overflow.is_none()
+            && (self.cnt_out >= MAX_NUM_RESULTS || idx_of_valtype(val_type).is_none())
+        {
             let partial_type = FuncType(self.tmp_func_type).r#type();
             self.overflow = Some(partial_type);
         }
@@ -306,7 +311,7 @@ impl FuncTypeBuilder {
         } else {
             let old_idx = (self.tmp_func_type >> RESULT_OFFSET_LOG) & RESULT_INDEX_BITS;
             let new_idx = old_idx
-                + idx_of_valtype(val_type)
+                + idx_of_valtype(val_type).unwrap()
                     * GEOMETRIC_PARTIAL_SUM_LOOKUP[MAX_NUM_RESULTS - self.cnt_out]
                 + 1;
             // set param idx bits zero
@@ -327,15 +332,17 @@ impl FuncTypeBuilder {
     }
 }
 
-const fn idx_of_valtype(ty: ValType) -> u64 {
+/// Types without a packed index are always kept in the overflow storage.
+const fn idx_of_valtype(ty: ValType) -> Option<u64> {
     match ty {
-        ValType::Number(NumType::I32) => 0,
-        ValType::Number(NumType::I64) => 1,
-        ValType::Number(NumType::F32) => 2,
-        ValType::Number(NumType::F64) => 3,
-        ValType::Reference(RefType::FunctionReference) => 4,
-        ValType::Reference(RefType::ExternReference) => 5,
-        ValType::VecType => 6,
+        ValType::Number(NumType::I32) => Some(0),
+        ValType::Number(NumType::I64) => Some(1),
+        ValType::Number(NumType::F32) => Some(2),
+        ValType::Number(NumType::F64) => Some(3),
+        ValType::Reference(RefType::FunctionReference) => Some(4),
+        ValType::Reference(RefType::ExternReference) => Some(5),
+        ValType::VecType => Some(6),
+        ValType::Reference(RefType::ExceptionReference | RefType::Typed { .. }) => None,
     }
 }
 
@@ -456,13 +463,14 @@ mod tests {
             ValType::f64(),
             ValType::i32(),
             ValType::externref(),
+            ValType::exnref(),
             ValType::i64(),
         ];
         for param in params.iter() {
             builder = builder.add_param(*param);
         }
         let t = builder.finish();
-        assert_eq!(t.num_params(), 11);
+        assert_eq!(t.num_params(), 12);
         assert_eq!(t.num_results(), 0);
         assert_eq!(t.params(), params);
         assert_eq!(t.params_iter().collect::<Vec<_>>(), params);
@@ -0,0 +1,149 @@
+//! https://webassembly.github.io/gc/core/syntax/types.html#composite-types
+
+use crate::{FuncType, NumType, TypeIdx, ValType};
+use rkyv::{Archive, Deserialize, Serialize};
+use std::fmt::{self, Display, Formatter};
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#storage-types
+///
+/// Packed types only occur in fields of structs and arrays, they are read as `i32`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum StorageType {
+    Val(ValType),
+    I8,
+    I16,
+}
+
+impl StorageType {
+    /// Type of values stored in or loaded from a field of this storage type.
+    pub const fn unpacked(&self) -> ValType {
+        match *self {
+            StorageType::Val(val_type) => val_type,
+            StorageType::I8 | StorageType::I16 => ValType::Number(NumType::I32),
+        }
+    }
+
+    pub const fn is_packed(&self) -> bool {
+        !matches!(self, StorageType::Val(_))
+    }
+
+    /// Width in bits of packed types, packed values are truncated to this width when stored.
+    pub const fn packed_bits(&self) -> Option<u32> {
+        match self {
+            StorageType::I8 => Some(8),
+            StorageType::I16 => Some(16),
+            StorageType::Val(_) => None,
+        }
+    }
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#field-types
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct FieldType {
+    pub storage_type: StorageType,
+    pub mutable: bool,
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#composite-types
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum CompositeType {
+    Func(FuncType),
+    Struct(Vec<FieldType>),
+    Array(FieldType),
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#recursive-types
+///
+/// Type definitions of a module. Recursion groups are flattened, type indices within a group refer to
+/// the definitions of the same module.
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct SubType {
+    pub is_final: bool,
+    /// the proposal currently allows at most one declared supertype
+    pub supertype: Option<TypeIdx>,
+    pub composite_type: CompositeType,
+}
+
+impl SubType {
+    /// Final function type without supertype, the type definitions of the MVP.
+    pub fn func(func_type: FuncType) -> Self {
+        SubType {
+            is_final: true,
+            supertype: None,
+            composite_type: CompositeType::Func(func_type),
+        }
+    }
+
+    pub fn as_func(&self) -> Option<FuncType> {
+        match self.composite_type {
+            CompositeType::Func(func_type) => Some(func_type),
+            _ => None,
+        }
+    }
+
+    pub fn as_struct(&self) -> Option<&[FieldType]> {
+        match &self.composite_type {
+            CompositeType::Struct(fields) => Some(fields),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<FieldType> {
+        match self.composite_type {
+            CompositeType::Array(field) => Some(field),
+            _ => None,
+        }
+    }
+}
+
+impl Display for StorageType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageType::Val(val_type) => write!(f, "{val_type}"),
+            StorageType::I8 => write!(f, "i8"),
+            StorageType::I16 => write!(f, "i16"),
+        }
+    }
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.mutable {
+            true => write!(f, "(mut {})", self.storage_type),
+            false => write!(f, "{}", self.storage_type),
+        }
+    }
+}
+
+impl Display for CompositeType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            CompositeType::Func(func_type) => write!(f, "func {func_type}"),
+            CompositeType::Struct(fields) => write!(
+                f,
+                "struct {{{}}}",
+                fields
+                    .iter()
+                    .map(|field| field.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            CompositeType::Array(field) => write!(f, "array {field}"),
+        }
+    }
+}
+
+impl Display for SubType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match (self.is_final, self.supertype) {
+            (true, None) => write!(f, "{}", self.composite_type),
+            (true, Some(supertype)) => write!(f, "sub final {supertype} {}", self.composite_type),
+            (false, Some(supertype)) => write!(f, "sub {supertype} {}", self.composite_type),
+            (false, None) => write!(f, "sub {}", self.composite_type),
+        }
+    }
+}
@@ -1,21 +1,23 @@
-#![allow(unreachable_code)] // rustc otherwise reports unreachable code for the InstructionType::Vector variant
-
 use crate::*;
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum InstructionType {
     Numeric(NumericInstructionCategory),
     Reference(ReferenceInstructionType),
-    Vector,
+    Vector(VectorInstructionCategory),
     Parametric(ParametricInstructionType),
     Variable(VariableInstructionType),
     Table(TableInstructionCategory),
     Memory(MemoryInstructionCategory),
+    /// https://webassembly.github.io/gc/core/syntax/instructions.html#aggregate-instructions
+    Aggregate(AggregateInstructionCategory),
     Control(ControlInstructionType),
     Meta(MetaInstructionType),
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum NumericInstructionCategory {
     /// Constants: return a static constant.
     Constant,
@@ -36,6 +38,7 @@ pub enum NumericInstructionCategory {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum IUnaryOp {
     Clz,
     Ctz,
@@ -43,6 +46,7 @@ pub enum IUnaryOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum FUnaryOp {
     Abs,
     Neg,
@@ -54,6 +58,7 @@ pub enum FUnaryOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum IBinaryOp {
     Add,
     Sub,
@@ -73,6 +78,7 @@ pub enum IBinaryOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum FBinaryOp {
     Add,
     Sub,
@@ -84,11 +90,13 @@ pub enum FBinaryOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ITestOp {
     Eqz,
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum IRelationalOp {
     Eq,
     Ne,
@@ -103,6 +111,7 @@ pub enum IRelationalOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum FRelationalOp {
     Eq,
     Ne,
@@ -113,6 +122,7 @@ pub enum FRelationalOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ConversionOp {
     Wrap,
     ExtendBits,
@@ -125,13 +135,214 @@ pub enum ConversionOp {
     Reinterpret,
 }
 
+/// Lane interpretation of a 128 bit vector value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VecShape {
+    I8x16,
+    I16x8,
+    I32x4,
+    I64x2,
+    F32x4,
+    F64x2,
+}
+
+impl VecShape {
+    pub fn lane_count(&self) -> u8 {
+        match self {
+            VecShape::I8x16 => 16,
+            VecShape::I16x8 => 8,
+            VecShape::I32x4 | VecShape::F32x4 => 4,
+            VecShape::I64x2 | VecShape::F64x2 => 2,
+        }
+    }
+
+    pub fn lane_bytes(&self) -> usize {
+        16 / self.lane_count() as usize
+    }
+
+    /// Scalar type a single lane is unpacked to (i8 and i16 lanes are unpacked to i32).
+    pub fn unpacked_type(&self) -> NumType {
+        match self {
+            VecShape::I8x16 | VecShape::I16x8 | VecShape::I32x4 => NumType::I32,
+            VecShape::I64x2 => NumType::I64,
+            VecShape::F32x4 => NumType::F32,
+            VecShape::F64x2 => NumType::F64,
+        }
+    }
+
+    pub fn is_float(&self) -> bool {
+        matches!(self, VecShape::F32x4 | VecShape::F64x2)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VectorInstructionCategory {
+    /// v128.const
+    Constant,
+    Load(VecLoadOp),
+    Store,
+    LoadLane(VecShape),
+    StoreLane(VecShape),
+    /// i8x16.shuffle
+    Shuffle,
+    Splat(VecShape),
+    ExtractLane(VecShape),
+    ExtractLaneS(VecShape),
+    ExtractLaneU(VecShape),
+    ReplaceLane(VecShape),
+    /// Unary Operators: consume one vector and produce one vector. The shape is the shape of the result.
+    Unary(VecShape, VUnaryOp),
+    /// Binary Operators: consume two vectors and produce one vector. The shape is the shape of the result.
+    Binary(VecShape, VBinaryOp),
+    /// v128.bitselect
+    Bitselect,
+    /// Comparisons: consume two vectors and produce a lane-wise mask.
+    Relational(VecShape, VRelationalOp),
+    /// Shifts: consume a vector and an i32 shift amount.
+    Shift(VecShape, VShiftOp),
+    /// Tests: consume one vector and produce an i32.
+    Test(VecShape, VTestOp),
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VecLoadOp {
+    Load128,
+    Load8x8S,
+    Load8x8U,
+    Load16x4S,
+    Load16x4U,
+    Load32x2S,
+    Load32x2U,
+    Load8Splat,
+    Load16Splat,
+    Load32Splat,
+    Load64Splat,
+    Load32Zero,
+    Load64Zero,
+}
+
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VUnaryOp {
+    Not,
+    Abs,
+    Neg,
+    Popcnt,
+    Sqrt,
+    Ceil,
+    Floor,
+    Trunc,
+    Nearest,
+    ExtendLowS,
+    ExtendLowU,
+    ExtendHighS,
+    ExtendHighU,
+    ExtAddPairwiseS,
+    ExtAddPairwiseU,
+    /// i32x4.trunc_sat_f32x4_s
+    TruncSatS,
+    /// i32x4.trunc_sat_f32x4_u
+    TruncSatU,
+    /// i32x4.trunc_sat_f64x2_s_zero
+    TruncSatZeroS,
+    /// i32x4.trunc_sat_f64x2_u_zero
+    TruncSatZeroU,
+    /// f32x4.convert_i32x4_s
+    ConvertS,
+    /// f32x4.convert_i32x4_u
+    ConvertU,
+    /// f64x2.convert_low_i32x4_s
+    ConvertLowS,
+    /// f64x2.convert_low_i32x4_u
+    ConvertLowU,
+    /// f32x4.demote_f64x2_zero
+    DemoteZero,
+    /// f64x2.promote_low_f32x4
+    PromoteLow,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VBinaryOp {
+    And,
+    AndNot,
+    Or,
+    Xor,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    AddSatS,
+    AddSatU,
+    SubSatS,
+    SubSatU,
+    MinS,
+    MinU,
+    MaxS,
+    MaxU,
+    Min,
+    Max,
+    PMin,
+    PMax,
+    AvgrU,
+    Q15MulrSatS,
+    DotS,
+    ExtMulLowS,
+    ExtMulLowU,
+    ExtMulHighS,
+    ExtMulHighU,
+    NarrowS,
+    NarrowU,
+    Swizzle,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VRelationalOp {
+    Eq,
+    Ne,
+    LtS,
+    LtU,
+    GtS,
+    GtU,
+    LeS,
+    LeU,
+    GeS,
+    GeU,
+    Lt,
+    Gt,
+    Le,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VShiftOp {
+    Shl,
+    ShrS,
+    ShrU,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum VTestOp {
+    AnyTrue,
+    AllTrue,
+    Bitmask,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ParametricInstructionType {
     Drop,
     Select,
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum VariableInstructionType {
     LocalGet,
     LocalSet,
@@ -141,6 +352,7 @@ pub enum VariableInstructionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum TableInstructionCategory {
     Get,
     Set,
@@ -153,13 +365,36 @@ pub enum TableInstructionCategory {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum AggregateInstructionCategory {
+    StructNew,
+    StructGet,
+    StructSet,
+    ArrayNew,
+    ArrayNewFixed,
+    ArrayNewData,
+    ArrayNewElem,
+    ArrayGet,
+    ArraySet,
+    ArrayLen,
+    ArrayFill,
+    ArrayCopy,
+    ArrayInitData,
+    ArrayInitElem,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum MemoryInstructionCategory {
     Load(LoadOp),
     Store(StoreOp),
     Memory(MemoryOp),
+    /// Threads proposal: atomic memory accesses and wait / notify.
+    Atomic(AtomicOp),
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum LoadOp {
     INNLoad,
     FNNLoad,
@@ -173,6 +408,7 @@ pub enum LoadOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum StoreOp {
     INNStore,
     FNNStore,
@@ -183,6 +419,7 @@ pub enum StoreOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum MemoryOp {
     Size,
     Grow,
@@ -193,6 +430,34 @@ pub enum MemoryOp {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum AtomicOp {
+    /// memory.atomic.notify
+    Notify,
+    /// memory.atomic.wait32 / memory.atomic.wait64
+    Wait,
+    /// atomic.fence
+    Fence,
+    Load,
+    Store,
+    /// Read-modify-write operators: store the combination of the operand and the old value, return the old value.
+    Rmw(AtomicRmwOp),
+    Cmpxchg,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum AtomicRmwOp {
+    Add,
+    Sub,
+    And,
+    Or,
+    Xor,
+    Xchg,
+}
+
+#[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ControlInstructionType {
     Nop,
     Unreachable,
@@ -212,19 +477,28 @@ pub enum ControlInstructionType {
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ReferenceInstructionType {
     RefNull,
     RefIsNull,
     RefFunc,
+    RefAsNonNull,
+    RefEq,
+    RefTest,
+    RefCast,
+    RefI31,
+    I31Get,
 }
 
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum MetaInstructionType {
     PhiNode,
 }
 
 // https://webassembly.github.io/spec/core/bikeshed/#syntax-blocktype
 #[derive(Debug, Clone, PartialEq, Default, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum BlockType {
     FunctionSig(TypeIdx),
     ShorthandFunc(ValType),
@@ -367,3 +641,191 @@ impl Display for ConversionOp {
         )
     }
 }
+
+impl Display for AtomicRmwOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AtomicRmwOp::Add => "add",
+                AtomicRmwOp::Sub => "sub",
+                AtomicRmwOp::And => "and",
+                AtomicRmwOp::Or => "or",
+                AtomicRmwOp::Xor => "xor",
+                AtomicRmwOp::Xchg => "xchg",
+            }
+        )
+    }
+}
+
+impl Display for VecShape {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VecShape::I8x16 => "i8x16",
+                VecShape::I16x8 => "i16x8",
+                VecShape::I32x4 => "i32x4",
+                VecShape::I64x2 => "i64x2",
+                VecShape::F32x4 => "f32x4",
+                VecShape::F64x2 => "f64x2",
+            }
+        )
+    }
+}
+
+impl Display for VecLoadOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VecLoadOp::Load128 => "load",
+                VecLoadOp::Load8x8S => "load8x8_s",
+                VecLoadOp::Load8x8U => "load8x8_u",
+                VecLoadOp::Load16x4S => "load16x4_s",
+                VecLoadOp::Load16x4U => "load16x4_u",
+                VecLoadOp::Load32x2S => "load32x2_s",
+                VecLoadOp::Load32x2U => "load32x2_u",
+                VecLoadOp::Load8Splat => "load8_splat",
+                VecLoadOp::Load16Splat => "load16_splat",
+                VecLoadOp::Load32Splat => "load32_splat",
+                VecLoadOp::Load64Splat => "load64_splat",
+                VecLoadOp::Load32Zero => "load32_zero",
+                VecLoadOp::Load64Zero => "load64_zero",
+            }
+        )
+    }
+}
+
+impl Display for VUnaryOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VUnaryOp::Not => "not",
+                VUnaryOp::Abs => "abs",
+                VUnaryOp::Neg => "neg",
+                VUnaryOp::Popcnt => "popcnt",
+                VUnaryOp::Sqrt => "sqrt",
+                VUnaryOp::Ceil => "ceil",
+                VUnaryOp::Floor => "floor",
+                VUnaryOp::Trunc => "trunc",
+                VUnaryOp::Nearest => "nearest",
+                VUnaryOp::ExtendLowS => "extend_low_s",
+                VUnaryOp::ExtendLowU => "extend_low_u",
+                VUnaryOp::ExtendHighS => "extend_high_s",
+                VUnaryOp::ExtendHighU => "extend_high_u",
+                VUnaryOp::ExtAddPairwiseS => "extadd_pairwise_s",
+                VUnaryOp::ExtAddPairwiseU => "extadd_pairwise_u",
+                VUnaryOp::TruncSatS => "trunc_sat_f32x4_s",
+                VUnaryOp::TruncSatU => "trunc_sat_f32x4_u",
+                VUnaryOp::TruncSatZeroS => "trunc_sat_f64x2_s_zero",
+                VUnaryOp::TruncSatZeroU => "trunc_sat_f64x2_u_zero",
+                VUnaryOp::ConvertS => "convert_i32x4_s",
+                VUnaryOp::ConvertU => "convert_i32x4_u",
+                VUnaryOp::ConvertLowS => "convert_low_i32x4_s",
+                VUnaryOp::ConvertLowU => "convert_low_i32x4_u",
+                VUnaryOp::DemoteZero => "demote_f64x2_zero",
+                VUnaryOp::PromoteLow => "promote_low_f32x4",
+            }
+        )
+    }
+}
+
+impl Display for VBinaryOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VBinaryOp::And => "and",
+                VBinaryOp::AndNot => "andnot",
+                VBinaryOp::Or => "or",
+                VBinaryOp::Xor => "xor",
+                VBinaryOp::Add => "add",
+                VBinaryOp::Sub => "sub",
+                VBinaryOp::Mul => "mul",
+                VBinaryOp::Div => "div",
+                VBinaryOp::AddSatS => "add_sat_s",
+                VBinaryOp::AddSatU => "add_sat_u",
+                VBinaryOp::SubSatS => "sub_sat_s",
+                VBinaryOp::SubSatU => "sub_sat_u",
+                VBinaryOp::MinS => "min_s",
+                VBinaryOp::MinU => "min_u",
+                VBinaryOp::MaxS => "max_s",
+                VBinaryOp::MaxU => "max_u",
+                VBinaryOp::Min => "min",
+                VBinaryOp::Max => "max",
+                VBinaryOp::PMin => "pmin",
+                VBinaryOp::PMax => "pmax",
+                VBinaryOp::AvgrU => "avgr_u",
+                VBinaryOp::Q15MulrSatS => "q15mulr_sat_s",
+                VBinaryOp::DotS => "dot_s",
+                VBinaryOp::ExtMulLowS => "extmul_low_s",
+                VBinaryOp::ExtMulLowU => "extmul_low_u",
+                VBinaryOp::ExtMulHighS => "extmul_high_s",
+                VBinaryOp::ExtMulHighU => "extmul_high_u",
+                VBinaryOp::NarrowS => "narrow_s",
+                VBinaryOp::NarrowU => "narrow_u",
+                VBinaryOp::Swizzle => "swizzle",
+            }
+        )
+    }
+}
+
+impl Display for VRelationalOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VRelationalOp::Eq => "eq",
+                VRelationalOp::Ne => "ne",
+                VRelationalOp::LtS => "lt_s",
+                VRelationalOp::LtU => "lt_u",
+                VRelationalOp::GtS => "gt_s",
+                VRelationalOp::GtU => "gt_u",
+                VRelationalOp::LeS => "le_s",
+                VRelationalOp::LeU => "le_u",
+                VRelationalOp::GeS => "ge_s",
+                VRelationalOp::GeU => "ge_u",
+                VRelationalOp::Lt => "lt",
+                VRelationalOp::Gt => "gt",
+                VRelationalOp::Le => "le",
+                VRelationalOp::Ge => "ge",
+            }
+        )
+    }
+}
+
+impl Display for VShiftOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VShiftOp::Shl => "shl",
+                VShiftOp::ShrS => "shr_s",
+                VShiftOp::ShrU => "shr_u",
+            }
+        )
+    }
+}
+
+impl Display for VTestOp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                VTestOp::AnyTrue => "any_true",
+                VTestOp::AllTrue => "all_true",
+                VTestOp::Bitmask => "bitmask",
+            }
+        )
+    }
+}
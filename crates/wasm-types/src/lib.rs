@@ -1,16 +1,20 @@
 mod functypes;
+mod gctypes;
 mod instruction;
 mod module;
+mod subtyping;
 
 use rkyv::{Archive, Deserialize, Serialize};
 use std::fmt::{self, Display, Formatter};
 
 pub use functypes::{FuncType, FuncTypeBuilder};
+pub use gctypes::*;
 pub use instruction::*;
 pub use module::*;
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#number-types
 #[derive(Debug, Clone, PartialEq, Copy, Default, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum NumType {
     #[default]
     I32,
@@ -19,16 +23,143 @@ pub enum NumType {
     F64,
 }
 
+/// https://webassembly.github.io/gc/core/syntax/types.html#heap-types
+#[derive(Debug, Clone, PartialEq, Copy, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub enum HeapType {
+    Func,
+    Extern,
+    Exn,
+    Any,
+    Eq,
+    I31,
+    Struct,
+    Array,
+    /// bottom type of the `any` hierarchy
+    None,
+    NoFunc,
+    NoExtern,
+    NoExn,
+    /// type definition of the module
+    Concrete(TypeIdx),
+}
+
+impl HeapType {
+    /// Binary encoding as signed 33-bit integer, abstract heap types share their (negative) encoding with
+    /// the reference type shorthands, concrete heap types are type indices.
+    pub const fn to_s33(&self) -> i64 {
+        match *self {
+            HeapType::Func => -0x10,
+            HeapType::Extern => -0x11,
+            HeapType::Any => -0x12,
+            HeapType::Eq => -0x13,
+            HeapType::I31 => -0x14,
+            HeapType::Struct => -0x15,
+            HeapType::Array => -0x16,
+            HeapType::Exn => -0x17,
+            HeapType::None => -0x0F,
+            HeapType::NoExtern => -0x0E,
+            HeapType::NoFunc => -0x0D,
+            HeapType::NoExn => -0x0C,
+            HeapType::Concrete(type_idx) => type_idx as i64,
+        }
+    }
+
+    pub const fn from_s33(value: i64) -> Option<Self> {
+        Some(match value {
+            -0x10 => HeapType::Func,
+            -0x11 => HeapType::Extern,
+            -0x12 => HeapType::Any,
+            -0x13 => HeapType::Eq,
+            -0x14 => HeapType::I31,
+            -0x15 => HeapType::Struct,
+            -0x16 => HeapType::Array,
+            -0x17 => HeapType::Exn,
+            -0x0F => HeapType::None,
+            -0x0E => HeapType::NoExtern,
+            -0x0D => HeapType::NoFunc,
+            -0x0C => HeapType::NoExn,
+            0..=0xFFFF_FFFF => HeapType::Concrete(value as TypeIdx),
+            _ => return None,
+        })
+    }
+}
+
 /// https://webassembly.github.io/spec/core/syntax/types.html#reference-types
 #[derive(Debug, Clone, PartialEq, Copy, Default, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum RefType {
     #[default]
     FunctionReference,
     ExternReference,
+    /// https://webassembly.github.io/exception-handling/core/syntax/types.html#reference-types
+    ExceptionReference,
+    /// https://webassembly.github.io/function-references/core/syntax/types.html#reference-types
+    ///
+    /// Nullable references to abstract heap types are always represented by the variants above, use
+    /// [`RefType::new`] to construct reference types.
+    Typed {
+        heap_type: HeapType,
+        nullable: bool,
+    },
+}
+
+impl RefType {
+    pub const fn new(heap_type: HeapType, nullable: bool) -> Self {
+        match (heap_type, nullable) {
+            (HeapType::Func, true) => RefType::FunctionReference,
+            (HeapType::Extern, true) => RefType::ExternReference,
+            (HeapType::Exn, true) => RefType::ExceptionReference,
+            (heap_type, nullable) => RefType::Typed {
+                heap_type,
+                nullable,
+            },
+        }
+    }
+
+    pub const fn heap_type(&self) -> HeapType {
+        match *self {
+            RefType::FunctionReference => HeapType::Func,
+            RefType::ExternReference => HeapType::Extern,
+            RefType::ExceptionReference => HeapType::Exn,
+            RefType::Typed { heap_type, .. } => heap_type,
+        }
+    }
+
+    pub const fn is_nullable(&self) -> bool {
+        match *self {
+            RefType::Typed { nullable, .. } => nullable,
+            _ => true,
+        }
+    }
+
+    pub const fn as_non_null(&self) -> Self {
+        RefType::new(self.heap_type(), false)
+    }
+
+    /// The MVP reference type sharing the runtime representation of this type.
+    ///
+    /// References of the `any` hierarchy are opaque 64-bit handles like extern references. Concrete heap
+    /// types are assumed to be function types, struct and array references carry a tag instead.
+    pub const fn untyped(&self) -> Self {
+        match self.heap_type() {
+            HeapType::Func | HeapType::NoFunc | HeapType::Concrete(_) => RefType::FunctionReference,
+            HeapType::Extern
+            | HeapType::NoExtern
+            | HeapType::Any
+            | HeapType::Eq
+            | HeapType::I31
+            | HeapType::Struct
+            | HeapType::Array
+            | HeapType::None => RefType::ExternReference,
+            HeapType::Exn | HeapType::NoExn => RefType::ExceptionReference,
+        }
+    }
 }
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#value-types
 #[derive(Debug, Clone, PartialEq, Default, Copy, Eq, Hash, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ValType {
     Number(NumType),
     Reference(RefType),
@@ -68,6 +199,11 @@ impl ValType {
         ValType::Reference(RefType::ExternReference)
     }
 
+    #[inline]
+    pub const fn exnref() -> Self {
+        ValType::Reference(RefType::ExceptionReference)
+    }
+
     #[inline]
     pub const fn vec() -> Self {
         ValType::VecType
@@ -77,10 +213,15 @@ impl ValType {
 /// https://webassembly.github.io/spec/core/syntax/types.html#result-types
 pub type ResType = Vec<ValType>;
 
+/// https://webassembly.github.io/memory64/core/syntax/types.html#limits
+///
+/// Limits are 64-bit wide to accommodate 64-bit memories, the parser keeps table limits and limits of
+/// 32-bit memories within `u32`.
 #[derive(Debug, Clone, Copy, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct Limits {
-    pub min: u32,
-    pub max: Option<u32>,
+    pub min: u64,
+    pub max: Option<u64>,
 }
 
 pub type TypeIdx = u32;
@@ -92,10 +233,31 @@ pub type ElemIdx = u32;
 pub type DataIdx = u32;
 pub type LocalIdx = u32;
 pub type LabelIdx = u32;
+pub type TagIdx = u32;
 
 impl ValType {
     pub fn is_valtype_byte(byte: u8) -> bool {
-        matches!(byte, 0x7F | 0x7E | 0x7D | 0x7C | 0x7B | 0x70 | 0x6F)
+        matches!(
+            byte,
+            0x7F | 0x7E
+                | 0x7D
+                | 0x7C
+                | 0x7B
+                | 0x70
+                | 0x6F
+                | 0x69
+                | 0x64
+                | 0x63
+                | 0x6E
+                | 0x6D
+                | 0x6C
+                | 0x6B
+                | 0x6A
+                | 0x71
+                | 0x72
+                | 0x73
+                | 0x74
+        )
     }
 }
 
@@ -125,6 +287,32 @@ impl Display for RefType {
         match self {
             RefType::FunctionReference => write!(f, "funcref"),
             RefType::ExternReference => write!(f, "externref"),
+            RefType::ExceptionReference => write!(f, "exnref"),
+            RefType::Typed {
+                heap_type,
+                nullable: true,
+            } => write!(f, "(ref null {heap_type})"),
+            RefType::Typed { heap_type, .. } => write!(f, "(ref {heap_type})"),
+        }
+    }
+}
+
+impl Display for HeapType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            HeapType::Func => write!(f, "func"),
+            HeapType::Extern => write!(f, "extern"),
+            HeapType::Exn => write!(f, "exn"),
+            HeapType::Any => write!(f, "any"),
+            HeapType::Eq => write!(f, "eq"),
+            HeapType::I31 => write!(f, "i31"),
+            HeapType::Struct => write!(f, "struct"),
+            HeapType::Array => write!(f, "array"),
+            HeapType::None => write!(f, "none"),
+            HeapType::NoFunc => write!(f, "nofunc"),
+            HeapType::NoExtern => write!(f, "noextern"),
+            HeapType::NoExn => write!(f, "noexn"),
+            HeapType::Concrete(type_idx) => write!(f, "{type_idx}"),
         }
     }
 }
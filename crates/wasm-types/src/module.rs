@@ -1,31 +1,71 @@
 use crate::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub struct TableType {
     pub ref_type: RefType,
     pub lim: Limits,
 }
 
+/// https://webassembly.github.io/memory64/core/syntax/types.html#memory-types
+/// Ordered by width, the minimum of two index types is the narrower one.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Archive, Deserialize, Serialize,
+)]
+#[archive(check_bytes)]
+pub enum IndexType {
+    #[default]
+    I32,
+    I64,
+}
+
+impl IndexType {
+    /// Type of addresses, sizes and page counts of a memory with this index type.
+    pub const fn val_type(&self) -> ValType {
+        match self {
+            IndexType::I32 => ValType::i32(),
+            IndexType::I64 => ValType::i64(),
+        }
+    }
+}
+
+/// https://webassembly.github.io/threads/core/syntax/types.html#memory-types
+#[derive(Debug, Clone, Copy, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
+pub struct MemType {
+    pub lim: Limits,
+    /// shared memories may be accessed concurrently by multiple agents (threads proposal)
+    pub shared: bool,
+    /// 64-bit memories are addressed with i64 values (memory64 proposal)
+    pub index_type: IndexType,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum GlobalType {
     Mut(ValType),
     Const(ValType),
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ExternType {
     Func(FuncType),
     Table(TableType),
     Mem(MemType),
     Global(GlobalType),
+    Tag(FuncType),
 }
 
 #[derive(Debug, Clone, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum ImportDesc {
     Func(TypeIdx),
     Table(TableType),
-    Mem(Limits),
+    Mem(MemType),
     Global((GlobalType, GlobalIdx)),
+    /// tags are typed by a function type without results
+    Tag(TypeIdx),
 }
 
 pub type Name = String;
@@ -33,6 +73,7 @@ pub type Name = String;
 // non-custom sections must appear in module at least once in a certain order defined by the spec.
 // This enum contains all sections with numbers assigned in the order they must appear in the module.
 #[derive(Debug, Clone, PartialEq, Archive, Deserialize, Serialize)]
+#[archive(check_bytes)]
 pub enum Section {
     Custom = 99,
     Type = 0,
@@ -40,13 +81,34 @@ pub enum Section {
     Function = 2,
     Table = 3,
     Memory = 4,
-    Global = 5,
-    Export = 6,
-    Start = 7,
-    Element = 8,
-    DataCount = 9,
-    Code = 10,
-    Data = 11,
+    Tag = 5,
+    Global = 6,
+    Export = 7,
+    Start = 8,
+    Element = 9,
+    DataCount = 10,
+    Code = 11,
+    Data = 12,
 }
 
-pub type MemType = Limits;
+impl Section {
+    /// Section id of the binary format, which differs from the section order.
+    pub const fn id(&self) -> u8 {
+        match self {
+            Section::Custom => 0,
+            Section::Type => 1,
+            Section::Import => 2,
+            Section::Function => 3,
+            Section::Table => 4,
+            Section::Memory => 5,
+            Section::Global => 6,
+            Section::Export => 7,
+            Section::Start => 8,
+            Section::Element => 9,
+            Section::Code => 10,
+            Section::Data => 11,
+            Section::DataCount => 12,
+            Section::Tag => 13,
+        }
+    }
+}
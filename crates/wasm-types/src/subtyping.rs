@@ -0,0 +1,247 @@
+//! https://webassembly.github.io/gc/core/valid/matching.html
+//!
+//! Concrete heap types are indices into the type definitions of a module, so every type is matched together with the
+//! type definitions its indices refer to. Types of different modules can be matched against each other this way.
+//!
+//! Type definitions are compared structurally. Recursive definitions are assumed to be equivalent while they are
+//! being compared, which approximates the equivalence of iso-recursive types.
+
+use crate::{
+    CompositeType, FieldType, FuncType, HeapType, RefType, StorageType, SubType, TypeIdx, ValType,
+};
+
+impl HeapType {
+    pub fn matches(&self, types: &[SubType], other: &HeapType, other_types: &[SubType]) -> bool {
+        match (*self, *other) {
+            (HeapType::Concrete(a), HeapType::Concrete(b)) => {
+                // walk the declared supertypes, they always precede their subtypes
+                let mut current = Some(a);
+                for _ in 0..=types.len() {
+                    let Some(idx) = current else {
+                        return false;
+                    };
+                    if equivalent_defs(types, idx, other_types, b, &mut Vec::new()) {
+                        return true;
+                    }
+                    current = types.get(idx as usize).and_then(|t| t.supertype);
+                }
+                false
+            }
+            (HeapType::Concrete(a), abstract_type) => match types.get(a as usize) {
+                Some(def) => match def.composite_type {
+                    CompositeType::Func(_) => abstract_type == HeapType::Func,
+                    CompositeType::Struct(_) => matches!(
+                        abstract_type,
+                        HeapType::Struct | HeapType::Eq | HeapType::Any
+                    ),
+                    CompositeType::Array(_) => matches!(
+                        abstract_type,
+                        HeapType::Array | HeapType::Eq | HeapType::Any
+                    ),
+                },
+                None => false,
+            },
+            (abstract_type, HeapType::Concrete(b)) => match other_types.get(b as usize) {
+                Some(def) => match def.composite_type {
+                    CompositeType::Func(_) => abstract_type == HeapType::NoFunc,
+                    CompositeType::Struct(_) | CompositeType::Array(_) => {
+                        abstract_type == HeapType::None
+                    }
+                },
+                None => false,
+            },
+            (a, b) if a == b => true,
+            (HeapType::I31 | HeapType::Struct | HeapType::Array, HeapType::Eq | HeapType::Any) => {
+                true
+            }
+            (HeapType::Eq, HeapType::Any) => true,
+            (
+                HeapType::None,
+                HeapType::Any | HeapType::Eq | HeapType::I31 | HeapType::Struct | HeapType::Array,
+            ) => true,
+            (HeapType::NoFunc, HeapType::Func) => true,
+            (HeapType::NoExtern, HeapType::Extern) => true,
+            (HeapType::NoExn, HeapType::Exn) => true,
+            _ => false,
+        }
+    }
+}
+
+impl RefType {
+    pub fn matches(&self, types: &[SubType], other: &RefType, other_types: &[SubType]) -> bool {
+        (!self.is_nullable() || other.is_nullable())
+            && self
+                .heap_type()
+                .matches(types, &other.heap_type(), other_types)
+    }
+}
+
+impl ValType {
+    pub fn matches(&self, types: &[SubType], other: &ValType, other_types: &[SubType]) -> bool {
+        match (self, other) {
+            (ValType::Reference(a), ValType::Reference(b)) => a.matches(types, b, other_types),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl StorageType {
+    pub fn matches(&self, types: &[SubType], other: &StorageType, other_types: &[SubType]) -> bool {
+        match (self, other) {
+            (StorageType::Val(a), StorageType::Val(b)) => a.matches(types, b, other_types),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl FieldType {
+    /// Immutable fields are covariant, mutable ones invariant.
+    pub fn matches(&self, types: &[SubType], other: &FieldType, other_types: &[SubType]) -> bool {
+        match (self.mutable, other.mutable) {
+            (false, false) => self
+                .storage_type
+                .matches(types, &other.storage_type, other_types),
+            (true, true) => {
+                self.storage_type
+                    .matches(types, &other.storage_type, other_types)
+                    && other
+                        .storage_type
+                        .matches(other_types, &self.storage_type, types)
+            }
+            _ => false,
+        }
+    }
+}
+
+impl CompositeType {
+    /// Structs match structs with a prefix of their fields (width and depth subtyping).
+    pub fn matches(
+        &self,
+        types: &[SubType],
+        other: &CompositeType,
+        other_types: &[SubType],
+    ) -> bool {
+        match (self, other) {
+            (CompositeType::Func(a), CompositeType::Func(b)) => a.matches(types, b, other_types),
+            (CompositeType::Struct(a), CompositeType::Struct(b)) => {
+                a.len() >= b.len()
+                    && a.iter()
+                        .zip(b.iter())
+                        .all(|(a, b)| a.matches(types, b, other_types))
+            }
+            (CompositeType::Array(a), CompositeType::Array(b)) => a.matches(types, b, other_types),
+            _ => false,
+        }
+    }
+}
+
+impl FuncType {
+    /// Parameters are contravariant, results covariant.
+    pub fn matches(&self, types: &[SubType], other: &FuncType, other_types: &[SubType]) -> bool {
+        if self == other && std::ptr::eq(types, other_types) {
+            return true;
+        }
+        let (params, results) = self.r#type();
+        let (other_params, other_results) = other.r#type();
+        params.len() == other_params.len()
+            && results.len() == other_results.len()
+            && params
+                .iter()
+                .zip(other_params.iter())
+                .all(|(a, b)| b.matches(other_types, a, types))
+            && results
+                .iter()
+                .zip(other_results.iter())
+                .all(|(a, b)| a.matches(types, b, other_types))
+    }
+
+    pub fn equivalent(&self, types: &[SubType], other: &FuncType, other_types: &[SubType]) -> bool {
+        self.matches(types, other, other_types) && other.matches(other_types, self, types)
+    }
+}
+
+/// Structural equivalence of two type definitions, `assumed` holds the pairs currently being compared.
+fn equivalent_defs(
+    types: &[SubType],
+    a: TypeIdx,
+    other_types: &[SubType],
+    b: TypeIdx,
+    assumed: &mut Vec<(TypeIdx, TypeIdx)>,
+) -> bool {
+    if a == b && std::ptr::eq(types, other_types) || assumed.contains(&(a, b)) {
+        return true;
+    }
+    let (Some(def_a), Some(def_b)) = (types.get(a as usize), other_types.get(b as usize)) else {
+        return false;
+    };
+    assumed.push((a, b));
+    let equivalent = def_a.is_final == def_b.is_final
+        && match (def_a.supertype, def_b.supertype) {
+            (Some(sa), Some(sb)) => equivalent_defs(types, sa, other_types, sb, assumed),
+            (None, None) => true,
+            _ => false,
+        }
+        && match (&def_a.composite_type, &def_b.composite_type) {
+            (CompositeType::Func(fa), CompositeType::Func(fb)) => {
+                let (params_a, results_a) = fa.r#type();
+                let (params_b, results_b) = fb.r#type();
+                params_a.len() == params_b.len()
+                    && results_a.len() == results_b.len()
+                    && params_a
+                        .iter()
+                        .chain(results_a.iter())
+                        .zip(params_b.iter().chain(results_b.iter()))
+                        .all(|(a, b)| equivalent_vals(types, a, other_types, b, assumed))
+            }
+            (CompositeType::Struct(fa), CompositeType::Struct(fb)) => {
+                fa.len() == fb.len()
+                    && fa
+                        .iter()
+                        .zip(fb.iter())
+                        .all(|(a, b)| equivalent_fields(types, a, other_types, b, assumed))
+            }
+            (CompositeType::Array(a), CompositeType::Array(b)) => {
+                equivalent_fields(types, a, other_types, b, assumed)
+            }
+            _ => false,
+        };
+    assumed.pop();
+    equivalent
+}
+
+fn equivalent_fields(
+    types: &[SubType],
+    a: &FieldType,
+    other_types: &[SubType],
+    b: &FieldType,
+    assumed: &mut Vec<(TypeIdx, TypeIdx)>,
+) -> bool {
+    a.mutable == b.mutable
+        && match (a.storage_type, b.storage_type) {
+            (StorageType::Val(a), StorageType::Val(b)) => {
+                equivalent_vals(types, &a, other_types, &b, assumed)
+            }
+            (a, b) => a == b,
+        }
+}
+
+fn equivalent_vals(
+    types: &[SubType],
+    a: &ValType,
+    other_types: &[SubType],
+    b: &ValType,
+    assumed: &mut Vec<(TypeIdx, TypeIdx)>,
+) -> bool {
+    match (a, b) {
+        (ValType::Reference(a), ValType::Reference(b)) => {
+            a.is_nullable() == b.is_nullable()
+                && match (a.heap_type(), b.heap_type()) {
+                    (HeapType::Concrete(a), HeapType::Concrete(b)) => {
+                        equivalent_defs(types, a, other_types, b, assumed)
+                    }
+                    (a, b) => a == b,
+                }
+        }
+        (a, b) => a == b,
+    }
+}
@@ -12,7 +12,7 @@ fuzz_target!(|module: Module| {
     }
     let module = module.unwrap();
 
-    if let Err(e) = FunctionLoader.parse_all_functions(&module) {
+    if let Err(e) = FunctionLoader::default().parse_all_functions(&module) {
         panic!("Failed to parse IR functions: {e}")
     }
 
@@ -20,7 +20,7 @@ fuzz_target!(|module: Module| {
         panic!("Failed to translate module meta in: {e}")
     }
 
-    if let Err(e) = llvm_gen::FunctionLoader.parse_all_functions(&module) {
+    if let Err(e) = llvm_gen::FunctionLoader::default().parse_all_functions(&module) {
         panic!("Failed to parse llvm functions from: {e}")
     }
 });
@@ -1,8 +1,9 @@
+use crate::inspect::InspectFormat;
 use clap::{Parser, Subcommand, ValueEnum};
 use log::LevelFilter;
-use runtime_lib::ConfigBuilder;
+use runtime_lib::{CompilationMode, ConfigBuilder, EngineConfig};
 use simplelog::{ColorChoice, CombinedLogger, TermLogger, TerminalMode};
-use std::{path::PathBuf, process::ExitCode};
+use std::{num::NonZeroUsize, path::PathBuf, process::ExitCode};
 
 #[derive(Parser, Debug)]
 #[command(name = "WASMine", version = "dev")]
@@ -15,6 +16,48 @@ struct Args {
     #[arg(short, long, default_value = "llvm")]
     backend: Backend,
 
+    /// parse and compile functions on their first call instead of ahead of time, LLVM skips `--cache-dir` then
+    #[arg(long)]
+    lazy: bool,
+
+    /// optimize the IR of functions before executing or compiling them, defaults to the backend's default
+    #[arg(long)]
+    optimize_ir: Option<bool>,
+
+    /// check the IR of functions after parsing and optimizing them, which debug builds always do
+    #[arg(long)]
+    verify_ir: bool,
+
+    /// worker threads decoding and validating function bodies, defaults to the available parallelism
+    #[arg(long, value_name = "N")]
+    threads: Option<NonZeroUsize>,
+
+    /// LLVM optimization level: basic, 0, 1, 2, 3 or s. `basic` is the short pipeline the JIT always used, the
+    /// numbered levels run LLVM's `default<O..>` pipelines
+    #[cfg(feature = "llvm")]
+    #[arg(short = 'O', long, default_value = "basic")]
+    opt_level: runtime_lib::OptLevel,
+
+    /// custom LLVM pass pipeline in the syntax of `opt -passes`, replaces the passes of `--opt-level`
+    #[cfg(feature = "llvm")]
+    #[arg(long, value_name = "PIPELINE")]
+    llvm_passes: Option<String>,
+
+    /// file with an HMAC-SHA256 key, `compile` authenticates cwasm files with it and running them requires it
+    #[cfg(feature = "llvm")]
+    #[arg(long, value_name = "FILE")]
+    cwasm_key: Option<PathBuf>,
+
+    /// reuse modules compiled by LLVM from this directory and store newly compiled ones there
+    #[cfg(feature = "llvm")]
+    #[arg(long, value_name = "DIR")]
+    cache_dir: Option<PathBuf>,
+
+    /// size limit of `--cache-dir` in MiB, the least recently used modules are evicted beyond it
+    #[cfg(feature = "llvm")]
+    #[arg(long, value_name = "MIB", default_value_t = 1024)]
+    cache_max_size: u64,
+
     #[command(subcommand)]
     action: Action,
 }
@@ -23,7 +66,7 @@ struct Args {
 enum Action {
     /// execute fully sandboxed
     Run {
-        /// ".wasm" / ".cwasm" executable path
+        /// ".wasm" / ".wat" / ".cwasm" executable path
         path: PathBuf,
 
         /// exported Wasm function name (defaults to the set start function)
@@ -36,7 +79,7 @@ enum Action {
     },
     /// execute with WASI API support (CAUTION: weakens WebAssembly sandboxing)
     RunWasi {
-        /// ".wasm" / ".cwasm" executable path
+        /// ".wasm" / ".wat" / ".cwasm" executable path
         path: PathBuf,
 
         /// exported Wasm function name (defaults to the set start function)
@@ -51,15 +94,51 @@ enum Action {
         #[arg(last = true)]
         wasi_args: Vec<String>,
     },
+    /// validate a module including all function bodies without executing it
+    Validate {
+        /// ".wasm" / ".wat" module path
+        path: PathBuf,
+    },
+    /// print the sections of a module and optionally the IR of its functions
+    Inspect {
+        /// ".wasm" / ".wat" / ".cwasm" module path
+        path: PathBuf,
+
+        /// print the IR of the listed functions (index, name or export name), all functions if none are listed
+        #[arg(long, value_delimiter = ',', num_args = 0.., value_name = "FUNCTION")]
+        ir: Option<Vec<String>>,
+
+        /// output format
+        #[arg(short, long, default_value = "text")]
+        format: InspectFormat,
+    },
     /// create precompiled cwasm executable
     #[cfg(feature = "llvm")]
     Compile {
-        /// `.wasm` file path
+        /// `.wasm` / `.wat` file path
         path: PathBuf,
 
         /// `.cwasm` output path for the compiled executable
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// target triple to compile for, e.g. `aarch64-unknown-linux-gnu` (defaults to the host)
+        #[arg(long, value_name = "TRIPLE")]
+        target: Option<String>,
+
+        /// CPU to compile for, e.g. `x86-64-v2` or `generic` (defaults to the host CPU when compiling for the host)
+        #[arg(long, value_name = "NAME")]
+        cpu: Option<String>,
+
+        /// CPU features to enable or disable on top of those of `--cpu`, e.g. `+avx2,-avx512f`
+        #[arg(long, value_name = "LIST")]
+        features: Option<String>,
+    },
+    /// print the header of a precompiled cwasm executable and whether it can be run on this host
+    #[cfg(feature = "llvm")]
+    CwasmInfo {
+        /// `.cwasm` file path
+        path: PathBuf,
     },
 }
 
@@ -77,8 +156,12 @@ impl Action {
         match self {
             Action::Run { path, .. } => path.clone(),
             Action::RunWasi { path, .. } => path.clone(),
+            Action::Validate { path } => path.clone(),
+            Action::Inspect { path, .. } => path.clone(),
             #[cfg(feature = "llvm")]
             Action::Compile { path, .. } => path.clone(),
+            #[cfg(feature = "llvm")]
+            Action::CwasmInfo { path } => path.clone(),
         }
     }
 }
@@ -123,14 +206,54 @@ pub fn main() -> ExitCode {
     .unwrap();
 
     let path = args.action.path();
+    let mut cb = ConfigBuilder::new();
+    cb.set_engine_config(EngineConfig {
+        compilation_mode: if args.lazy {
+            CompilationMode::Lazy
+        } else {
+            CompilationMode::Eager
+        },
+        optimize_ir: args.optimize_ir,
+        verify_ir: args.verify_ir,
+        parser_threads: args.threads,
+        ..Default::default()
+    });
+    #[cfg(feature = "llvm")]
+    cb.set_opt_level(match args.llvm_passes {
+        Some(passes) => runtime_lib::OptLevel::Custom(passes),
+        None => args.opt_level,
+    });
+    #[cfg(feature = "llvm")]
+    if let Some(key_path) = &args.cwasm_key {
+        match std::fs::read(key_path) {
+            Ok(key) => {
+                cb.set_cwasm_key(key);
+            }
+            Err(e) => {
+                log::error!("Could not read cwasm key {key_path:?}: {e}");
+                return ExitCode::from(1);
+            }
+        }
+    }
+    #[cfg(feature = "llvm")]
+    if let Some(cache_dir) = args.cache_dir {
+        let mut cache = runtime_lib::CompilationCache::new(cache_dir)
+            .with_max_size(args.cache_max_size.saturating_mul(1024 * 1024));
+        // entries are authenticated like cwasm files
+        if let Some(key) = cb.cwasm_key() {
+            cache = cache.with_key(key.to_vec());
+        }
+        cb.set_compilation_cache(cache);
+    }
     let engine = match args.backend {
         #[cfg(feature = "llvm")]
-        Backend::LLVM => runtime_lib::Engine::llvm().unwrap(),
+        Backend::LLVM => runtime_lib::Engine::llvm_with_config(cb.engine_config().clone()).unwrap(),
         #[cfg(feature = "interp")]
-        Backend::Interpreter => runtime_lib::Engine::interpreter().unwrap(),
+        Backend::Interpreter => {
+            runtime_lib::Engine::interpreter_with_config(cb.engine_config().clone()).unwrap()
+        }
     };
 
-    let mut cb = ConfigBuilder::new();
     let ret = match args.action {
         Action::Run {
             invoke,
@@ -157,15 +280,33 @@ pub fn main() -> ExitCode {
             cb.set_wasi_args(wasi_args);
             crate::run(&path, cb.finish(), engine, vec![])
         }
+        Action::Validate { .. } => crate::validate(&path),
+        Action::Inspect { ir, format, .. } => crate::inspect::inspect(&path, ir, format),
         #[cfg(feature = "llvm")]
-        Action::Compile { output, .. } => crate::c_wasm_compilation::compile(
+        Action::Compile {
+            output,
+            target,
+            cpu,
+            features,
+            ..
+        } => crate::c_wasm_compilation::compile(
             &path,
             &output.unwrap_or_else(|| {
                 PathBuf::new()
                     .join(path.file_name().unwrap_or_default())
                     .with_extension("cwasm")
             }),
+            cb.engine_config().function_loader(),
+            &cb.engine_config().llvm_opt_level,
+            &llvm_gen::CompileTarget {
+                triple: target,
+                cpu,
+                features,
+            },
+            cb.cwasm_key(),
         ),
+        #[cfg(feature = "llvm")]
+        Action::CwasmInfo { .. } => crate::c_wasm_compilation::info(&path, cb.cwasm_key()),
     };
     ExitCode::from(ret)
 }
@@ -0,0 +1,564 @@
+use clap::ValueEnum;
+use module::{
+    fmt::FunctionIRDisplayContext,
+    instructions::FunctionIR,
+    objects::{
+        data::DataMode,
+        element::{ElemMode, ElementInit},
+        function::{Function, FunctionSource},
+        value::{ConstantAllocation, ConstantOp, ConstantValue},
+    },
+    FunctionLoaderInterface, Module,
+};
+use resource_buffer::SourceFormat;
+use runtime_lib::RuntimeError;
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+use wasm_types::{FuncIdx, GlobalType, ImportDesc, Limits, MemType};
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InspectFormat {
+    Text,
+    Json,
+}
+
+/// Prints the module's sections and the IR of the `ir_functions`, `Some` empty selection prints all functions.
+pub fn inspect(path: &Path, ir_functions: Option<Vec<String>>, format: InspectFormat) -> u8 {
+    match inspect_internal(path, ir_functions, format) {
+        Ok(output) => {
+            println!("{output}");
+            0
+        }
+        Err(e) => {
+            log::error!("Error: {}", e);
+            1
+        }
+    }
+}
+
+fn inspect_internal(
+    path: &Path,
+    ir_functions: Option<Vec<String>>,
+    format: InspectFormat,
+) -> Result<String, RuntimeError> {
+    let module = runtime_lib::sugar::module_from_file(path)?;
+    inspect_module(&module, ir_functions, format)
+}
+
+fn inspect_module(
+    module: &Module,
+    ir_functions: Option<Vec<String>>,
+    format: InspectFormat,
+) -> Result<String, RuntimeError> {
+    let mut sections = module_sections(module);
+
+    let selected = match ir_functions {
+        None => Vec::new(),
+        Some(selection) if selection.is_empty() => (0..module.meta.functions.len())
+            .map(|func_idx| func_idx as FuncIdx)
+            .filter(|func_idx| {
+                matches!(
+                    module.meta.functions[*func_idx as usize].source,
+                    FunctionSource::Wasm(_)
+                )
+            })
+            .collect(),
+        Some(selection) => selection
+            .iter()
+            .map(|function| resolve_function(module, function))
+            .collect::<Result<_, _>>()?,
+    };
+    if selected.is_empty() {
+        return Ok(match format {
+            InspectFormat::Text => render_text(&sections),
+            InspectFormat::Json => Json::Obj(sections).to_string(),
+        });
+    }
+
+    if module.source.kind() == SourceFormat::Cwasm {
+        return Err(RuntimeError::Msg(
+            "The IR of precompiled cwasm modules is not available.".into(),
+        ));
+    }
+    parser::FunctionLoader::default().parse_all_functions(module)?;
+    let artifacts = module.artifact_registry.read().unwrap();
+    let ir = artifacts
+        .get("ir")
+        .ok_or_else(|| RuntimeError::Msg("Module IR is missing.".into()))?
+        .read()
+        .unwrap();
+    let ir = ir
+        .downcast_ref::<Vec<FunctionIR>>()
+        .ok_or_else(|| RuntimeError::Msg("Module IR is missing.".into()))?;
+    let functions = selected
+        .into_iter()
+        .map(|func_idx| FunctionIRDisplayContext {
+            module: &module.meta,
+            func_idx,
+            ir: &ir[func_idx as usize],
+        });
+    Ok(match format {
+        InspectFormat::Text => {
+            let mut output = render_text(&sections);
+            for function in functions {
+                output.push_str(&format!("\n{function}\n"));
+            }
+            output
+        }
+        InspectFormat::Json => {
+            sections.push(("ir", Json::Arr(functions.map(function_ir_json).collect())));
+            Json::Obj(sections).to_string()
+        }
+    })
+}
+
+/// Function index of an index, a name section name or an export name.
+fn resolve_function(module: &Module, function: &str) -> Result<FuncIdx, RuntimeError> {
+    let meta = &module.meta;
+    let func_idx = function
+        .parse::<FuncIdx>()
+        .ok()
+        .or_else(|| {
+            meta.names
+                .functions
+                .iter()
+                .find(|(_, name)| *name == function)
+                .map(|(func_idx, _)| *func_idx)
+        })
+        .or_else(|| meta.exports.find_function_idx(function))
+        .filter(|func_idx| (*func_idx as usize) < meta.functions.len())
+        .ok_or_else(|| RuntimeError::FunctionNotFound(function.to_owned()))?;
+    match meta.functions[func_idx as usize].source {
+        FunctionSource::Wasm(_) => Ok(func_idx),
+        FunctionSource::Import(_) => Err(RuntimeError::Msg(format!(
+            "Function '{function}' is imported and has no IR."
+        ))),
+    }
+}
+
+fn module_sections(module: &Module) -> Vec<(&'static str, Json)> {
+    let meta = &module.meta;
+    let mut sections = Vec::new();
+    if let Some(name) = &meta.names.module {
+        sections.push(("name", Json::Str(name.clone())));
+    }
+
+    let types = meta.types.iter().enumerate().map(|(type_idx, sub_type)| {
+        Json::Obj(vec![
+            ("index", Json::Num(type_idx as u64)),
+            ("type", Json::Str(sub_type.to_string())),
+        ])
+    });
+    sections.push(("types", Json::Arr(types.collect())));
+
+    let imports = meta.imports.iter().enumerate().map(|(import_idx, import)| {
+        let (kind, desc) = match &import.desc {
+            ImportDesc::Func(type_idx) => ("func", format!("type {type_idx}")),
+            ImportDesc::Table(table_type) => (
+                "table",
+                format!("{} {}", table_type.ref_type, limits(&table_type.lim)),
+            ),
+            ImportDesc::Mem(mem_type) => ("memory", memory_type(mem_type)),
+            ImportDesc::Global((global_type, _)) => ("global", global_type_str(global_type)),
+            ImportDesc::Tag(type_idx) => ("tag", format!("type {type_idx}")),
+        };
+        Json::Obj(vec![
+            ("index", Json::Num(import_idx as u64)),
+            ("module", Json::Str(import.module.clone())),
+            ("name", Json::Str(import.name.clone())),
+            ("kind", Json::Str(kind.into())),
+            ("type", Json::Str(desc)),
+        ])
+    });
+    sections.push(("imports", Json::Arr(imports.collect())));
+
+    let exports = &meta.exports;
+    let mut sorted_exports = Vec::new();
+    for (kind, exports) in [
+        ("func", &exports.functions),
+        ("table", &exports.tables),
+        ("memory", &exports.memories),
+        ("global", &exports.globals),
+        ("tag", &exports.tags),
+    ] {
+        let mut exports = exports.iter().collect::<Vec<_>>();
+        exports.sort_by_key(|(name, idx)| (**idx, *name));
+        sorted_exports.extend(exports.into_iter().map(|(name, idx)| {
+            Json::Obj(vec![
+                ("name", Json::Str(name.clone())),
+                ("kind", Json::Str(kind.into())),
+                ("index", Json::Num(*idx as u64)),
+            ])
+        }));
+    }
+    sections.push(("exports", Json::Arr(sorted_exports)));
+
+    let functions = meta
+        .functions
+        .iter()
+        .enumerate()
+        .map(|(func_idx, function)| {
+            let mut fields = vec![
+                ("index", Json::Num(func_idx as u64)),
+                (
+                    "name",
+                    Json::Str(Function::debug_function_name(func_idx as FuncIdx, meta)),
+                ),
+                ("type", Json::Num(function.type_idx as u64)),
+            ];
+            match &function.source {
+                FunctionSource::Import(import) => {
+                    fields.push(("import", Json::Num(import.import_idx as u64)))
+                }
+                FunctionSource::Wasm(unparsed) => {
+                    fields.push(("size", Json::Num(unparsed.size as u64)))
+                }
+            }
+            Json::Obj(fields)
+        });
+    sections.push(("functions", Json::Arr(functions.collect())));
+
+    let tables = meta.tables.iter().enumerate().map(|(table_idx, table)| {
+        let mut fields = vec![
+            ("index", Json::Num(table_idx as u64)),
+            ("type", Json::Str(table.r#type.ref_type.to_string())),
+            ("limits", Json::Str(limits(&table.r#type.lim))),
+            ("imported", Json::Bool(table.import)),
+        ];
+        if let Some(init) = &table.init {
+            fields.push(("init", Json::Str(constant(init))));
+        }
+        Json::Obj(fields)
+    });
+    sections.push(("tables", Json::Arr(tables.collect())));
+
+    let memories = meta.memories.iter().enumerate().map(|(mem_idx, memory)| {
+        Json::Obj(vec![
+            ("index", Json::Num(mem_idx as u64)),
+            ("type", Json::Str(memory_type(&memory.r#type))),
+            ("imported", Json::Bool(memory.import)),
+        ])
+    });
+    sections.push(("memories", Json::Arr(memories.collect())));
+
+    let globals = meta.globals.iter().enumerate().map(|(global_idx, global)| {
+        let mut fields = vec![("index", Json::Num(global_idx as u64))];
+        if let Some(name) = meta.names.global_name(global_idx as u32) {
+            fields.push(("name", Json::Str(name.into())));
+        }
+        fields.push(("type", Json::Str(global_type_str(&global.r#type))));
+        fields.push(("imported", Json::Bool(global.import)));
+        if !global.import {
+            fields.push(("init", Json::Str(constant(&global.init))));
+        }
+        Json::Obj(fields)
+    });
+    sections.push(("globals", Json::Arr(globals.collect())));
+
+    let tags = meta.tags.iter().enumerate().map(|(tag_idx, tag)| {
+        Json::Obj(vec![
+            ("index", Json::Num(tag_idx as u64)),
+            ("type", Json::Num(tag.type_idx as u64)),
+            ("imported", Json::Bool(tag.import)),
+        ])
+    });
+    sections.push(("tags", Json::Arr(tags.collect())));
+
+    let elements = meta.elements.iter().enumerate().map(|(elem_idx, element)| {
+        let mut fields = vec![("index", Json::Num(elem_idx as u64))];
+        match &element.mode {
+            ElemMode::Active { table, offset } => {
+                fields.push(("mode", Json::Str("active".into())));
+                fields.push(("table", Json::Num(*table as u64)));
+                fields.push(("offset", Json::Str(constant(offset))));
+            }
+            ElemMode::Passive => fields.push(("mode", Json::Str("passive".into()))),
+            ElemMode::Declarative => fields.push(("mode", Json::Str("declarative".into()))),
+        }
+        fields.push(("type", Json::Str(element.type_.to_string())));
+        let items = match &element.init {
+            ElementInit::Unresolved(func_idxs) => func_idxs
+                .iter()
+                .map(|func_idx| Json::Str(format!("ref.func {func_idx}")))
+                .collect(),
+            ElementInit::Final(values) => values.iter().map(|v| Json::Str(constant(v))).collect(),
+        };
+        fields.push(("items", Json::Arr(items)));
+        Json::Obj(fields)
+    });
+    sections.push(("elements", Json::Arr(elements.collect())));
+
+    let datas = meta.datas.iter().enumerate().map(|(data_idx, data)| {
+        let mut fields = vec![("index", Json::Num(data_idx as u64))];
+        match &data.mode {
+            DataMode::Active { memory, offset } => {
+                fields.push(("mode", Json::Str("active".into())));
+                fields.push(("memory", Json::Num(*memory as u64)));
+                fields.push(("offset", Json::Str(constant(offset))));
+            }
+            DataMode::Passive => fields.push(("mode", Json::Str("passive".into()))),
+        }
+        fields.push(("size", Json::Num(data.init.len() as u64)));
+        Json::Obj(fields)
+    });
+    sections.push(("data", Json::Arr(datas.collect())));
+
+    let custom_sections = meta.custom_sections.iter().map(|section| {
+        Json::Obj(vec![
+            ("name", Json::Str(section.name.clone())),
+            ("size", Json::Num(section.size as u64)),
+        ])
+    });
+    sections.push(("custom_sections", Json::Arr(custom_sections.collect())));
+
+    if let Some(func_idx) = meta.entry_point {
+        sections.push(("start", Json::Num(func_idx as u64)));
+    }
+    sections
+}
+
+fn function_ir_json(function: FunctionIRDisplayContext) -> Json {
+    let basic_blocks = function.basic_blocks().map(|bb| {
+        Json::Obj(vec![
+            ("id", Json::Num(bb.bb.id as u64)),
+            (
+                "inputs",
+                Json::Arr(
+                    bb.bb
+                        .inputs
+                        .iter()
+                        .map(|phi| Json::Str(phi.to_string()))
+                        .collect(),
+                ),
+            ),
+            (
+                "instructions",
+                Json::Arr(bb.instructions().into_iter().map(Json::Str).collect()),
+            ),
+            ("terminator", Json::Str(bb.terminator())),
+        ])
+    });
+    Json::Obj(vec![
+        ("index", Json::Num(function.func_idx as u64)),
+        (
+            "name",
+            Json::Str(Function::debug_function_name(
+                function.func_idx,
+                function.module,
+            )),
+        ),
+        (
+            "signature",
+            Json::Str(
+                function
+                    .module
+                    .functions
+                    .get(function.func_idx as usize)
+                    .and_then(|f| function.module.func_type(f.type_idx))
+                    .map(|func_type| func_type.to_string())
+                    .unwrap_or_default(),
+            ),
+        ),
+        (
+            "locals",
+            Json::Arr(function.locals().into_iter().map(Json::Str).collect()),
+        ),
+        ("basic_blocks", Json::Arr(basic_blocks.collect())),
+    ])
+}
+
+fn limits(lim: &Limits) -> String {
+    match lim.max {
+        Some(max) => format!("{}..{}", lim.min, max),
+        None => format!("{}..", lim.min),
+    }
+}
+
+fn memory_type(mem_type: &MemType) -> String {
+    let mut s = format!("{} pages", limits(&mem_type.lim));
+    if mem_type.index_type == wasm_types::IndexType::I64 {
+        s.push_str(" i64");
+    }
+    if mem_type.shared {
+        s.push_str(" shared");
+    }
+    s
+}
+
+fn global_type_str(global_type: &GlobalType) -> String {
+    match global_type {
+        GlobalType::Mut(t) => format!("mut {t}"),
+        GlobalType::Const(t) => format!("const {t}"),
+    }
+}
+
+fn constant(value: &ConstantValue) -> String {
+    match value {
+        ConstantValue::V(v) => format!("{} {v}", v.r#type()),
+        ConstantValue::Global(global_idx) => format!("global.get {global_idx}"),
+        ConstantValue::FuncPtr(func_idx) => format!("ref.func {func_idx}"),
+        ConstantValue::Expr(ops) => ops
+            .iter()
+            .map(|op| match op {
+                ConstantOp::Const(v) => format!("{} {v}", v.r#type()),
+                ConstantOp::Global(global_idx, _) => format!("global.get {global_idx}"),
+                ConstantOp::IBinary(t, op) => format!("{t}.{op:?}").to_lowercase(),
+                ConstantOp::RefNull(heap_type) => format!("ref.null {heap_type}"),
+                ConstantOp::Alloc(allocation) => match allocation {
+                    ConstantAllocation::ArrayNewFixed { type_idx, len } => {
+                        format!("array.new_fixed {type_idx} {len}")
+                    }
+                    _ => format!("{} {}", allocation.name(), allocation.type_idx()),
+                },
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    }
+}
+
+/// Text rendering: one line per entry listing its fields
+fn render_text(sections: &[(&'static str, Json)]) -> String {
+    let mut output = String::new();
+    for (section, content) in sections {
+        match content {
+            Json::Arr(entries) => {
+                output.push_str(&format!("{section} ({}):\n", entries.len()));
+                for entry in entries {
+                    let Json::Obj(fields) = entry else {
+                        continue;
+                    };
+                    let fields = fields
+                        .iter()
+                        .map(|(key, value)| match value {
+                            Json::Str(s) => format!("{key}={s:?}"),
+                            value => format!("{key}={value}"),
+                        })
+                        .collect::<Vec<_>>();
+                    output.push_str(&format!("  {}\n", fields.join(" ")));
+                }
+            }
+            Json::Str(s) => output.push_str(&format!("{section}: {s}\n")),
+            value => output.push_str(&format!("{section}: {value}\n")),
+        }
+    }
+    output
+}
+
+/// Minimal JSON document, objects keep the order of their fields
+enum Json {
+    Bool(bool),
+    Num(u64),
+    Str(String),
+    Arr(Vec<Json>),
+    Obj(Vec<(&'static str, Json)>),
+}
+
+fn write_json_str(f: &mut Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{c}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Bool(b) => write!(f, "{b}"),
+            Json::Num(n) => write!(f, "{n}"),
+            Json::Str(s) => write_json_str(f, s),
+            Json::Arr(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Obj(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_json_str(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use runtime_lib::sugar::module_from_buf;
+
+    const MODULE: &str = r#"
+        (module $m
+          (import "env" "log" (func $log (param i32)))
+          (memory (export "mem") 1 2)
+          (global $counter (mut i32) (i32.const 7))
+          (func $add (export "add") (param i32 i32) (result i32)
+            (i32.add (local.get 0) (local.get 1)))
+          (data (i32.const 16) "hi"))
+    "#;
+
+    fn inspect(ir_functions: Option<Vec<String>>, format: InspectFormat) -> String {
+        let module = module_from_buf(MODULE.as_bytes().to_vec()).unwrap();
+        inspect_module(&module, ir_functions, format).unwrap()
+    }
+
+    #[test]
+    fn sections_are_listed() {
+        let text = inspect(None, InspectFormat::Text);
+        assert!(text.contains("name: m\n"), "{text}");
+        assert!(
+            text.contains(r#"module="env" name="log" kind="func""#),
+            "{text}"
+        );
+        assert!(text.contains(r#"name="add" kind="func" index=1"#), "{text}");
+        assert!(text.contains(r#"type="1..2 pages""#), "{text}");
+        assert!(
+            text.contains(r#"name="counter" type="mut i32" imported=false init="i32 7""#),
+            "{text}"
+        );
+        assert!(
+            text.contains(r#"mode="active" memory=0 offset="i32 16" size=2"#),
+            "{text}"
+        );
+        // no IR is printed unless functions are selected
+        assert!(!text.contains("i32.add"), "{text}");
+    }
+
+    #[test]
+    fn ir_of_selected_functions() {
+        let json = inspect(Some(vec!["add".into()]), InspectFormat::Json);
+        assert!(json.starts_with('{') && json.ends_with('}'), "{json}");
+        assert!(json.contains(r#""ir":[{"index":1,"name":"add""#), "{json}");
+
+        // all functions that have a body, by name section name, index or export name
+        let all = inspect(Some(vec![]), InspectFormat::Json);
+        assert_eq!(all, json);
+        assert_eq!(inspect(Some(vec!["1".into()]), InspectFormat::Json), json);
+
+        let module = module_from_buf(MODULE.as_bytes().to_vec()).unwrap();
+        for function in ["log", "missing"] {
+            let selection = Some(vec![function.to_string()]);
+            assert!(inspect_module(&module, selection, InspectFormat::Text).is_err());
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use module::objects::value::Value;
+use parser::{Parser, ParserError};
+use resource_buffer::ResourceBuffer;
 use runtime_lib::{Cluster, Config, Engine, Linker, RuntimeError};
 use std::{path::Path, rc::Rc};
 use utils::parse_input_params_for_function;
 use wasi::{PreopenDirInheritPerms, PreopenDirPerms, WasiContextBuilder};
 
 mod cli;
+mod inspect;
 mod utils;
 
 pub use cli::main;
@@ -17,7 +20,7 @@ fn run_internal(
 ) -> Result<Vec<Value>, RuntimeError> {
     log::debug!("run_internal: {:?}", config);
 
-    let module = runtime_lib::sugar::module_from_file(path)?;
+    let module = runtime_lib::sugar::module_from_file_with_key(path, config.cwasm_key.as_deref())?;
     let module = Rc::new(module);
     engine.init(module.clone())?;
 
@@ -94,30 +97,121 @@ pub fn run(path: &Path, config: Config, engine: Engine, function_args: Vec<Strin
     }
 }
 
+pub fn validate(path: &Path) -> u8 {
+    match ResourceBuffer::from_file(path)
+        .map_err(ParserError::from)
+        .and_then(Parser::validate)
+    {
+        Ok(()) => {
+            log::info!("Module {:?} is valid", path);
+            0
+        }
+        Err(e) => {
+            log::error!("Error: {}", e);
+            1
+        }
+    }
+}
+
 #[cfg(feature = "llvm")]
 mod c_wasm_compilation {
     use super::*;
+    use llvm_gen::{
+        aot::{AOTError, CwasmHeader},
+        CompileTarget,
+    };
     use resource_buffer::SourceFormat;
-    use runtime_lib::FunctionLoaderInterface;
+    use runtime_lib::{FunctionLoader, FunctionLoaderInterface, OptLevel};
     use std::rc::Rc;
 
-    pub fn compile_internal(in_path: &Path, out_path: &Path) -> Result<(), RuntimeError> {
+    pub fn compile_internal(
+        in_path: &Path,
+        out_path: &Path,
+        loader: FunctionLoader,
+        opt_level: &OptLevel,
+        target: &CompileTarget,
+        key: Option<&[u8]>,
+    ) -> Result<(), RuntimeError> {
         if SourceFormat::from_path(in_path)? == SourceFormat::Cwasm {
             return Err(RuntimeError::Msg(
-                "Cwasm files can't be compiled AGAIN... Please provide a wasm file.".to_owned(),
+                "Cwasm files can't be compiled AGAIN... Please provide a wasm or wat file."
+                    .to_owned(),
             ));
         }
         let module = runtime_lib::Parser::parse_from_file(in_path)?;
         let module = Rc::new(module);
 
         llvm_gen::Translator::translate_module_meta(&module)?;
-        llvm_gen::FunctionLoader::default().parse_all_functions(&module)?;
-        llvm_gen::aot::store_aot_module(module, out_path)?;
+        llvm_gen::FunctionLoader::with_parser(loader).parse_all_functions(&module)?;
+        match key {
+            Some(key) => {
+                llvm_gen::aot::store_signed_aot_module(module, out_path, opt_level, target, key)?
+            }
+            None => llvm_gen::aot::store_aot_module(module, out_path, opt_level, target)?,
+        }
+        Ok(())
+    }
+
+    pub fn compile(
+        in_path: &Path,
+        out_path: &Path,
+        loader: FunctionLoader,
+        opt_level: &OptLevel,
+        target: &CompileTarget,
+        key: Option<&[u8]>,
+    ) -> u8 {
+        log::debug!("compiling {in_path:?} with LLVM optimization level {opt_level}");
+        match compile_internal(in_path, out_path, loader, opt_level, target, key) {
+            Ok(_) => 0,
+            Err(e) => {
+                log::error!("Error: {}", e);
+                1
+            }
+        }
+    }
+
+    pub fn info_internal(path: &Path, key: Option<&[u8]>) -> Result<(), RuntimeError> {
+        let buffer = ResourceBuffer::from_file(path)?;
+        let input = buffer.get();
+        let header = CwasmHeader::parse(input)?;
+        let section = |range: &std::ops::Range<usize>| format!("{range:?} ({} bytes)", range.len());
+        let status = |result: Result<(), AOTError>| match result {
+            Ok(()) => "ok".to_owned(),
+            Err(e) => e.to_string(),
+        };
+        println!("format version:   {}", header.format_version);
+        println!("runtime version:  {}", header.runtime_version);
+        println!("target triple:    {}", header.target.triple);
+        println!("cpu:              {}", header.target.cpu);
+        println!("cpu features:     {}", header.target.features);
+        println!("optimization:     {}", header.opt_level);
+        println!("metadata:         {}", section(&header.metadata));
+        println!("custom sections:  {}", section(&header.custom_sections));
+        println!("object code:      {}", section(&header.object));
+        println!(
+            "checksum:         {} ({})",
+            header
+                .checksum
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<String>(),
+            status(header.verify_checksum(input))
+        );
+        println!(
+            "authentication:   {}",
+            match (header.mac, key) {
+                (None, _) => "none".to_owned(),
+                (Some(_), None) => "HMAC-SHA256 (not verified without a key)".to_owned(),
+                (Some(_), Some(key)) =>
+                    format!("HMAC-SHA256 ({})", status(header.verify_mac(input, key))),
+            }
+        );
+        println!("compatibility:    {}", status(header.check_compatibility()));
         Ok(())
     }
 
-    pub fn compile(in_path: &Path, out_path: &Path) -> u8 {
-        match compile_internal(in_path, out_path) {
+    pub fn info(path: &Path, key: Option<&[u8]>) -> u8 {
+        match info_internal(path, key) {
             Ok(_) => 0,
             Err(e) => {
                 log::error!("Error: {}", e);
@@ -0,0 +1,65 @@
+;; Threads: atomic accesses to shared memories, wait and notify
+
+(module
+  (memory 1 1 shared)
+  (func (export "rmw") (result i32 i32 i32)
+    (i32.atomic.store (i32.const 0) (i32.const 5))
+    (i32.atomic.rmw.add (i32.const 0) (i32.const 3))
+    (i32.atomic.rmw.cmpxchg (i32.const 0) (i32.const 8) (i32.const 1))
+    (i32.atomic.load (i32.const 0)))
+  (func (export "rmw8") (result i64 i64)
+    (i64.atomic.store8 (i32.const 16) (i64.const 0x1ff))
+    (i64.atomic.rmw8.sub_u (i32.const 16) (i64.const 2))
+    (i64.atomic.load8_u (i32.const 16)))
+  (func (export "wait32") (param i32 i64) (result i32)
+    (memory.atomic.wait32 (i32.const 0) (local.get 0) (local.get 1)))
+  (func (export "wait64") (param i64 i64) (result i32)
+    (memory.atomic.wait64 (i32.const 8) (local.get 0) (local.get 1)))
+  (func (export "notify") (param i32) (result i32)
+    (memory.atomic.notify (i32.const 0) (local.get 0)))
+  (func (export "load32") (param i32) (result i32) (i32.atomic.load (local.get 0)))
+  (func (export "store64") (param i32) (i64.atomic.store (local.get 0) (i64.const 1)))
+  (func (export "rmw16") (param i32) (result i32)
+    (i32.atomic.rmw16.xchg_u (local.get 0) (i32.const 1)))
+  (func (export "wait_at") (param i32) (result i32)
+    (memory.atomic.wait32 (local.get 0) (i32.const 0) (i64.const 0)))
+  (func (export "notify_at") (param i32) (result i32)
+    (memory.atomic.notify (local.get 0) (i32.const 1)))
+  (func (export "fence") (atomic.fence))
+)
+(assert_return (invoke "rmw") (i32.const 5) (i32.const 8) (i32.const 1))
+(assert_return (invoke "rmw8") (i64.const 0xff) (i64.const 0xfd))
+(invoke "fence")
+
+;; "not-equal" without waiting if the value differs, "timed-out" once the timeout in nanoseconds has passed
+(assert_return (invoke "wait32" (i32.const 0) (i64.const 0)) (i32.const 1))
+(assert_return (invoke "wait32" (i32.const 1) (i64.const 0)) (i32.const 2))
+(assert_return (invoke "wait32" (i32.const 1) (i64.const 1000000)) (i32.const 2))
+(assert_return (invoke "wait64" (i64.const 1) (i64.const 0)) (i32.const 1))
+(assert_return (invoke "wait64" (i64.const 0) (i64.const 1000)) (i32.const 2))
+;; nobody is waiting
+(assert_return (invoke "notify" (i32.const 1)) (i32.const 0))
+
+;; atomic accesses trap if they are misaligned or out of bounds
+(assert_trap (invoke "load32" (i32.const 2)) "unaligned atomic")
+(assert_trap (invoke "store64" (i32.const 4)) "unaligned atomic")
+(assert_trap (invoke "rmw16" (i32.const 1)) "unaligned atomic")
+(assert_trap (invoke "wait_at" (i32.const 6)) "unaligned atomic")
+(assert_trap (invoke "notify_at" (i32.const 2)) "unaligned atomic")
+(assert_trap (invoke "load32" (i32.const 65536)) "out of bounds memory access")
+(assert_trap (invoke "store64" (i32.const 65536)) "out of bounds memory access")
+(assert_trap (invoke "wait_at" (i32.const 65536)) "out of bounds memory access")
+(assert_return (invoke "load32" (i32.const 65532)) (i32.const 0))
+
+;; waiting on unshared memories traps
+(module
+  (memory 1)
+  (func (export "wait") (result i32)
+    (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i64.const 0)))
+  (func (export "notify") (result i32) (memory.atomic.notify (i32.const 0) (i32.const 1)))
+)
+(assert_trap (invoke "wait") "expected shared memory")
+(assert_return (invoke "notify") (i32.const 0))
+
+;; shared memories need a maximum
+(assert_invalid (module (memory 1 shared)) "shared memory must have maximum")
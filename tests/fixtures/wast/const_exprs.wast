@@ -0,0 +1,49 @@
+;; Constant expressions reading globals: imported ones and, since wasm 3.0, immutable ones defined before them
+
+(module $provider
+  (global (export "g") i32 (i32.const 10))
+)
+(register "provider" $provider)
+
+(module
+  (global $imp (import "provider" "g") i32)
+  (global $a i32 (i32.const 1))
+  (global $b i32 (global.get $a))
+  (global $c i32 (i32.add (global.get $a) (i32.const 1)))
+  (global $d i64 (i64.mul (i64.const 3) (i64.const 4)))
+  (global $e i32 (i32.sub (global.get $c) (global.get $imp)))
+  (global $f (mut i32) (global.get $e))
+
+  (table 4 funcref)
+  (elem (offset (global.get $c)) $one $two)
+  (func $one (result i32) (i32.const 1))
+  (func $two (result i32) (i32.const 2))
+
+  (func (export "b") (result i32) (global.get $b))
+  (func (export "c") (result i32) (global.get $c))
+  (func (export "d") (result i64) (global.get $d))
+  (func (export "e") (result i32) (global.get $e))
+  (func (export "f") (result i32) (global.get $f))
+  (func (export "call") (param i32) (result i32) (call_indirect (result i32) (local.get 0)))
+)
+
+(assert_return (invoke "b") (i32.const 1))
+(assert_return (invoke "c") (i32.const 2))
+(assert_return (invoke "d") (i64.const 12))
+(assert_return (invoke "e") (i32.const -8))
+(assert_return (invoke "f") (i32.const -8))
+(assert_return (invoke "call" (i32.const 2)) (i32.const 1))
+(assert_return (invoke "call" (i32.const 3)) (i32.const 2))
+
+(assert_invalid
+  (module (global $a i32 (global.get $b)) (global $b i32 (i32.const 1)))
+  "unknown global")
+(assert_invalid
+  (module (global $a i32 (i32.add (global.get $a) (i32.const 1))))
+  "unknown global")
+(assert_invalid
+  (module (global $a (mut i32) (i32.const 1)) (global $b i32 (global.get $a)))
+  "constant expression required")
+(assert_invalid
+  (module (global $a i32 (i32.const 1)) (global $b i64 (global.get $a)))
+  "type mismatch")
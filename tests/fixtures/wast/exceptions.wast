@@ -0,0 +1,139 @@
+;; Exception handling: tags, try_table, throw and throw_ref
+
+(module
+  (tag $e0)
+  (tag $e1 (param i32))
+  (tag $e2 (param i32 i64))
+
+  (func $throw-e1 (param i32) (throw $e1 (local.get 0)))
+
+  (func (export "throw-e0") (throw $e0))
+  (func (export "throw-e1") (param i32) (call $throw-e1 (local.get 0)))
+  (func (export "throw-if") (param i32) (result i32)
+    (if (local.get 0) (then (throw $e0)))
+    (i32.const 0))
+
+  (func (export "catch") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (catch $e1 $h) (call $throw-e1 (local.get 0)))
+      (i32.const -1)))
+
+  (func (export "catch-multi") (result i64)
+    (local $a i32) (local $b i64)
+    (block $h (result i32 i64)
+      (try_table (catch $e2 $h) (throw $e2 (i32.const 3) (i64.const 4)))
+      (unreachable))
+    (local.set $b)
+    (local.set $a)
+    (i64.add (i64.extend_i32_u (local.get $a)) (local.get $b)))
+
+  (func (export "catch-all") (result i32)
+    (block $h
+      (try_table (catch_all $h) (throw $e0)))
+    (i32.const 42))
+
+  (func (export "catch-order") (result i32)
+    (block $all
+      (block $h (result i32)
+        (try_table (catch $e1 $h) (catch_all $all) (throw $e1 (i32.const 7)))
+        (i32.const -1))
+      (return))
+    (i32.const -2))
+
+  (func (export "nested-miss") (result i32)
+    (block $h (result i32)
+      (try_table (catch $e1 $h)
+        (block $h2 (try_table (catch $e0 $h2) (throw $e1 (i32.const 5)))))
+      (i32.const -3)))
+
+  (func (export "rethrow") (param i32) (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e1 $outer)
+        (block $h (result exnref)
+          (try_table (catch_all_ref $h) (call $throw-e1 (local.get 0)))
+          (unreachable))
+        (throw_ref))
+      (i32.const -2)))
+
+  (func (export "catch-ref") (param i32) (result i32)
+    (block $outer (result i32)
+      (try_table (catch $e1 $outer)
+        (block $h (result i32 exnref)
+          (try_table (catch_ref $e1 $h) (call $throw-e1 (local.get 0)))
+          (unreachable))
+        (throw_ref))
+      (i32.const -4)))
+
+  (func (export "rethrow-uncaught") (param i32)
+    (block $h (result exnref)
+      (try_table (catch_all_ref $h) (call $throw-e1 (local.get 0)))
+      (unreachable))
+    (throw_ref))
+
+  (func $deep (param i32)
+    (if (i32.eqz (local.get 0))
+      (then (throw $e1 (i32.const 99)))
+      (else (call $deep (i32.sub (local.get 0) (i32.const 1))))))
+  (func (export "deep") (result i32)
+    (block $h (result i32) (try_table (catch $e1 $h) (call $deep (i32.const 100))) (i32.const 0)))
+
+  (func (export "after-catch") (result i32)
+    (local i32)
+    (block $h (result i32) (try_table (catch $e1 $h) (call $deep (i32.const 3))) (i32.const 0))
+    (local.set 0)
+    (block $h (result i32) (try_table (catch $e1 $h) (call $throw-e1 (i32.const 7))) (i32.const 0))
+    (local.get 0)
+    (i32.add))
+
+  (func (export "null-ref") (throw_ref (ref.null exn)))
+)
+
+(assert_exception (invoke "throw-e0"))
+(assert_exception (invoke "throw-e1" (i32.const 1)))
+(assert_exception (invoke "throw-if" (i32.const 1)))
+(assert_return (invoke "throw-if" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "catch" (i32.const 5)) (i32.const 5))
+(assert_return (invoke "catch-multi") (i64.const 7))
+(assert_return (invoke "catch-all") (i32.const 42))
+(assert_return (invoke "catch-order") (i32.const 7))
+(assert_return (invoke "nested-miss") (i32.const 5))
+(assert_return (invoke "rethrow" (i32.const 9)) (i32.const 9))
+(assert_return (invoke "catch-ref" (i32.const 11)) (i32.const 11))
+(assert_exception (invoke "rethrow-uncaught" (i32.const 1)))
+(assert_return (invoke "deep") (i32.const 99))
+(assert_return (invoke "after-catch") (i32.const 106))
+(assert_trap (invoke "null-ref") "null exception reference")
+
+;; tags are shared between modules through imports and exports
+
+(module $thrower
+  (tag $e (export "e") (param i32))
+  (func (export "throw") (param i32) (throw $e (local.get 0)))
+)
+(register "thrower" $thrower)
+
+(module
+  (tag $e (import "thrower" "e") (param i32))
+  (tag $other (param i32))
+  (func $throw (import "thrower" "throw") (param i32))
+
+  (func (export "catch-imported") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (catch $e $h) (call $throw (local.get 0)))
+      (i32.const -1)))
+
+  (func (export "miss-local") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (catch $other $h) (call $throw (local.get 0)))
+      (i32.const -1)))
+)
+
+(assert_return (invoke "catch-imported" (i32.const 3)) (i32.const 3))
+(assert_exception (invoke "miss-local" (i32.const 3)))
+
+(assert_invalid
+  (module (func (throw 0)))
+  "unknown tag")
+(assert_invalid
+  (module (tag $e (param i32)) (func (throw $e)))
+  "type mismatch")
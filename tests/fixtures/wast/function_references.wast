@@ -0,0 +1,73 @@
+;; Typed function references: call_ref, ref.as_non_null, br_on_null and br_on_non_null
+
+(module
+  (type $unop (func (param i32) (result i32)))
+  (func $inc (param i32) (result i32) (i32.add (local.get 0) (i32.const 1)))
+  (func $double (param i32) (result i32) (i32.mul (local.get 0) (i32.const 2)))
+  (elem declare func $inc $double)
+  (global $nullable (mut (ref null $unop)) (ref.null $unop))
+  (table $ops 2 (ref null $unop))
+  (elem (table $ops) (i32.const 0) (ref null $unop) (ref.func $inc) (ref.func $double))
+
+  (func $pick (param i32) (result (ref $unop))
+    (if (result (ref $unop)) (local.get 0)
+      (then (ref.func $double))
+      (else (ref.func $inc))))
+  (func (export "call_ref") (param i32 i32) (result i32)
+    (call_ref $unop (local.get 1) (call $pick (local.get 0))))
+  (func (export "call_table") (param i32 i32) (result i32)
+    (call_ref $unop (local.get 1) (table.get $ops (local.get 0))))
+  (func (export "call_null") (result i32)
+    (call_ref $unop (i32.const 0) (global.get $nullable)))
+  (func (export "return_call_ref") (param i32) (result i32)
+    (return_call_ref $unop (local.get 0) (ref.func $double)))
+
+  (func (export "as_non_null") (result i32)
+    (call_ref $unop (i32.const 1) (ref.as_non_null (global.get $nullable))))
+  (func (export "set_nullable")
+    (global.set $nullable (ref.func $inc)))
+
+  ;; returns -1 for null, otherwise applies the function to 10
+  (func (export "br_on_null") (param i32) (result i32)
+    (block $is_null
+      (return (call_ref $unop (i32.const 10)
+        (br_on_null $is_null (table.get $ops (local.get 0))))))
+    (i32.const -1))
+  (func (export "br_on_non_null") (param i32) (result i32) (local $f (ref null $unop))
+    (block $non_null (result (ref $unop))
+      (br_on_non_null $non_null (table.get $ops (local.get 0)))
+      (return (i32.const -1)))
+    (local.set $f)
+    (call_ref $unop (i32.const 10) (local.get $f)))
+  (func (export "clear") (param i32)
+    (table.set $ops (local.get 0) (ref.null $unop)))
+)
+(assert_return (invoke "call_ref" (i32.const 0) (i32.const 5)) (i32.const 6))
+(assert_return (invoke "call_ref" (i32.const 1) (i32.const 5)) (i32.const 10))
+(assert_return (invoke "call_table" (i32.const 1) (i32.const 4)) (i32.const 8))
+(assert_return (invoke "return_call_ref" (i32.const 21)) (i32.const 42))
+(assert_trap (invoke "call_null") "null function reference")
+(assert_trap (invoke "as_non_null") "null reference")
+(invoke "set_nullable")
+(assert_return (invoke "as_non_null") (i32.const 2))
+(assert_return (invoke "call_null") (i32.const 1))
+
+(assert_return (invoke "br_on_null" (i32.const 0)) (i32.const 11))
+(assert_return (invoke "br_on_non_null" (i32.const 1)) (i32.const 20))
+(invoke "clear" (i32.const 0))
+(assert_return (invoke "br_on_null" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "br_on_non_null" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "br_on_non_null" (i32.const 1)) (i32.const 20))
+
+;; non-nullable references can't be null, and call_ref checks the function type
+(assert_invalid
+  (module (type $t (func)) (func (param (ref $t)) (local.set 0 (ref.null $t))))
+  "type mismatch")
+(assert_invalid
+  (module
+    (type $t (func (result i32)))
+    (type $u (func (result i64)))
+    (func $f (result i64) (i64.const 0))
+    (elem declare func $f)
+    (func (result i32) (call_ref $t (ref.func $f))))
+  "type mismatch")
@@ -0,0 +1,169 @@
+;; Objects referenced by globals, tables and locals survive garbage collections, which are triggered by
+;; allocating far more fields than the initial collection threshold of 2^16 fields
+
+(module
+  (type $node (struct (field $val i32) (field $next (ref null $node))))
+  (type $bytes (array (mut i8)))
+
+  (global $g (mut (ref null $node)) (ref.null $node))
+  (table $t 2 (ref null $node))
+
+  (func $node (param i32) (param (ref null $node)) (result (ref $node))
+    (struct.new $node (local.get 0) (local.get 1)))
+
+  ;; allocates `n` unreachable arrays of 1024 fields
+  (func $churn (param $n i32)
+    (loop $l
+      (drop (array.new_default $bytes (i32.const 1024)))
+      (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1))))))
+
+  (func $sum (param $list (ref null $node)) (result i32)
+    (local $sum i32)
+    (block $done
+      (loop $l
+        (br_if $done (ref.is_null (local.get $list)))
+        (local.set $sum (i32.add (local.get $sum) (struct.get $node $val (local.get $list))))
+        (local.set $list (struct.get $node $next (local.get $list)))
+        (br $l)))
+    (local.get $sum))
+
+  ;; a list of the values 1 to `n`, interleaved with garbage
+  (func $list (param $n i32) (result (ref null $node))
+    (local $list (ref null $node))
+    (loop $l
+      (local.set $list (call $node (local.get $n) (local.get $list)))
+      (call $churn (i32.const 4))
+      (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+    (local.get $list))
+
+  (func (export "run") (result i32)
+    (local $local (ref null $node))
+    (local $list (ref null $node))
+    (global.set $g (call $node (i32.const 1) (ref.null $node)))
+    (table.set $t (i32.const 0) (call $node (i32.const 2) (ref.null $node)))
+    (table.set $t (i32.const 1) (call $list (i32.const 100)))
+    (local.set $local (call $node (i32.const 3) (ref.null $node)))
+    (local.set $list (call $list (i32.const 1000)))
+    (call $churn (i32.const 512))
+    (i32.add
+      (i32.add
+        (struct.get $node $val (global.get $g))
+        (struct.get $node $val (table.get $t (i32.const 0))))
+      (i32.add
+        (struct.get $node $val (local.get $local))
+        (call $sum (local.get $list)))))
+
+  ;; reference locals start out as null
+  (func (export "null-local") (result i32)
+    (local (ref null $node))
+    (ref.is_null (local.get 0)))
+
+  ;; the roots of "run" are gone, but globals and tables keep their objects alive
+  (func (export "check") (result i32)
+    (call $churn (i32.const 512))
+    (i32.add
+      (i32.add
+        (struct.get $node $val (global.get $g))
+        (struct.get $node $val (table.get $t (i32.const 0))))
+      (call $sum (table.get $t (i32.const 1)))))
+)
+
+(assert_return (invoke "null-local") (i32.const 1))
+(assert_return (invoke "run") (i32.const 500506))
+(assert_return (invoke "check") (i32.const 5053))
+(assert_return (invoke "run") (i32.const 500506))
+
+;; thrown exceptions are collected like objects, exnrefs keep them and their payloads alive
+(module
+  (type $node (struct (field $val i32)))
+  (type $bytes (array (mut i8)))
+  (tag $e (param (ref $node)))
+
+  (global $first (mut exnref) (ref.null exn))
+
+  (func $churn (param $n i32)
+    (loop $l
+      (drop (array.new_default $bytes (i32.const 1024)))
+      (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1))))))
+
+  ;; throws and catches `n` exceptions, keeping the first one, and returns the payload of the first one
+  (func (export "throw-loop") (param $n i32) (result i32)
+    (local $exn exnref)
+    (loop $l
+      (block $h (result (ref $node) exnref)
+        (try_table (catch_ref $e $h) (throw $e (struct.new $node (local.get $n))))
+        (unreachable))
+      (local.set $exn)
+      (drop)
+      (if (ref.is_null (global.get $first))
+        (then (global.set $first (local.get $exn))))
+      (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1)))))
+    (call $churn (i32.const 512))
+    (block $h (result (ref $node))
+      (try_table (catch $e $h) (throw_ref (global.get $first)))
+      (unreachable))
+    (struct.get $node $val))
+)
+
+(assert_return (invoke "throw-loop" (i32.const 200000)) (i32.const 200000))
+
+;; initializers of globals and tables allocate their objects during instantiation, the globals and tables keep
+;; them alive
+(module
+  (type $node (struct (field $val i32) (field $next (ref null $node))))
+  (type $bytes (array (mut i8)))
+  (type $nodes (array (ref null $node)))
+
+  (global $leaf (ref $node) (struct.new $node (i32.const 1) (ref.null $node)))
+  (global $list (ref $node)
+    (struct.new $node (i32.const 2) (struct.new $node (i32.const 3) (global.get $leaf))))
+  (global $default (ref $node) (struct.new_default $node))
+  (global $filled (ref $bytes) (array.new $bytes (i32.const 300) (i32.const 4)))
+  (global $zeros (ref $bytes) (array.new_default $bytes (i32.const 5)))
+  (global $fixed (ref $nodes) (array.new_fixed $nodes 2 (global.get $leaf) (ref.null $node)))
+  (table $t 2 (ref null $node) (struct.new $node (i32.const 7) (ref.null $node)))
+
+  (func $churn (param $n i32)
+    (loop $l
+      (drop (array.new_default $bytes (i32.const 1024)))
+      (br_if $l (local.tee $n (i32.sub (local.get $n) (i32.const 1))))))
+
+  (func $sum (param $list (ref null $node)) (result i32)
+    (local $sum i32)
+    (block $done
+      (loop $l
+        (br_if $done (ref.is_null (local.get $list)))
+        (local.set $sum (i32.add (local.get $sum) (struct.get $node $val (local.get $list))))
+        (local.set $list (struct.get $node $next (local.get $list)))
+        (br $l)))
+    (local.get $sum))
+
+  (func (export "check") (result i32)
+    (call $churn (i32.const 512))
+    (i32.add
+      (i32.add
+        (i32.add
+          (call $sum (global.get $list))
+          (struct.get $node $val (global.get $default)))
+        (i32.add
+          ;; packed elements are truncated
+          (array.get_u $bytes (global.get $filled) (i32.const 3))
+          (i32.add
+            (array.len (global.get $filled))
+            (array.len (global.get $zeros)))))
+      (i32.add
+        (struct.get $node $val (array.get $nodes (global.get $fixed) (i32.const 0)))
+        (struct.get $node $val (table.get $t (i32.const 1))))))
+
+  ;; the initializer of a table is evaluated once, all elements refer to the same object
+  (func (export "shared") (result i32)
+    (ref.eq (table.get $t (i32.const 0)) (table.get $t (i32.const 1))))
+)
+
+(assert_return (invoke "check") (i32.const 67))
+(assert_return (invoke "check") (i32.const 67))
+(assert_return (invoke "shared") (i32.const 1))
+
+(assert_invalid
+  (module (type $s (struct (field i32))) (elem (ref $s) (struct.new $s (i32.const 1))))
+  "isn't supported in this constant expression")
@@ -0,0 +1,46 @@
+;; Memory64: 64-bit addresses, offsets and sizes
+
+(module
+  (memory i64 1 3)
+  (data (i64.const 0xfffc) "\01\02\03\04")
+  (func (export "load") (param i64) (result i32) (i32.load (local.get 0)))
+  (func (export "load_offset") (param i64) (result i32) (i32.load offset=0x100000000 (local.get 0)))
+  (func (export "load8") (param i64) (result i32) (i32.load8_u (local.get 0)))
+  (func (export "store") (param i64 i64) (i64.store (local.get 0) (local.get 1)))
+  (func (export "size") (result i64) (memory.size))
+  (func (export "grow") (param i64) (result i64) (memory.grow (local.get 0)))
+  (func (export "fill") (param i64 i64) (memory.fill (local.get 0) (i32.const 0xaa) (local.get 1)))
+  (func (export "copy") (param i64 i64 i64) (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+)
+(assert_return (invoke "load" (i64.const 0xfffc)) (i32.const 0x04030201))
+(assert_return (invoke "size") (i64.const 1))
+
+;; addresses at and above 4 GiB don't wrap around to the start of the memory
+(assert_trap (invoke "load" (i64.const 0x100000000)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const 0x10000fffc)) "out of bounds memory access")
+(assert_trap (invoke "load8" (i64.const 0xffffffffffffffff)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const 0xfffffffffffffffe)) "out of bounds memory access")
+(assert_trap (invoke "load_offset" (i64.const 0)) "out of bounds memory access")
+(assert_trap (invoke "load_offset" (i64.const 0xffffffff00000000)) "out of bounds memory access")
+(assert_trap (invoke "store" (i64.const 0x100000000) (i64.const 1)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const 0xfffd)) "out of bounds memory access")
+(assert_trap (invoke "fill" (i64.const 0x100000000) (i64.const 0)) "out of bounds memory access")
+(assert_trap (invoke "fill" (i64.const 0) (i64.const 0x100000000)) "out of bounds memory access")
+(assert_trap (invoke "copy" (i64.const 0) (i64.const 0x100000000) (i64.const 1)) "out of bounds memory access")
+
+;; sizes are 64-bit as well, failed growth returns -1
+(assert_return (invoke "grow" (i64.const 1)) (i64.const 1))
+(assert_return (invoke "size") (i64.const 2))
+(assert_return (invoke "grow" (i64.const 0x100000001)) (i64.const -1))
+(assert_return (invoke "grow" (i64.const 2)) (i64.const -1))
+(invoke "store" (i64.const 0x1fff8) (i64.const 7))
+(assert_return (invoke "load" (i64.const 0x1fff8)) (i32.const 7))
+(assert_trap (invoke "load" (i64.const 0x1fffd)) "out of bounds memory access")
+
+;; 64-bit memories are indexed with i64
+(assert_invalid
+  (module (memory i64 1) (func (drop (i32.load (i32.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 1) (func (result i32) (memory.size)))
+  "type mismatch")
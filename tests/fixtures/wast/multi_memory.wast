@@ -0,0 +1,57 @@
+;; Multi-memory: memory index immediates and bulk operations between memories
+
+(module
+  (memory $m0 1)
+  (memory $m1 2 3)
+  (data (memory $m1) (i32.const 8) "\01\02\03\04")
+  (data $passive "\aa\bb")
+  (func (export "load0") (param i32) (result i32) (i32.load $m0 (local.get 0)))
+  (func (export "load1") (param i32) (result i32) (i32.load $m1 (local.get 0)))
+  (func (export "store1") (param i32 i32) (i32.store $m1 (local.get 0) (local.get 1)))
+  (func (export "load8_1") (param i32) (result i32) (i32.load8_u $m1 (local.get 0)))
+  (func (export "size0") (result i32) (memory.size $m0))
+  (func (export "size1") (result i32) (memory.size $m1))
+  (func (export "grow1") (param i32) (result i32) (memory.grow $m1 (local.get 0)))
+  (func (export "copy_1_to_0") (param i32 i32 i32) (memory.copy $m0 $m1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy_0_to_1") (param i32 i32 i32) (memory.copy $m1 $m0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "fill1") (param i32 i32 i32) (memory.fill $m1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1") (param i32) (memory.init $m1 $passive (local.get 0) (i32.const 0) (i32.const 2)))
+)
+;; active data segments and accesses use their memory's index
+(assert_return (invoke "load1" (i32.const 8)) (i32.const 0x04030201))
+(assert_return (invoke "load0" (i32.const 8)) (i32.const 0))
+(invoke "store1" (i32.const 0) (i32.const 42))
+(assert_return (invoke "load1" (i32.const 0)) (i32.const 42))
+(assert_return (invoke "load0" (i32.const 0)) (i32.const 0))
+
+;; memories have their own sizes and bounds
+(assert_return (invoke "size0") (i32.const 1))
+(assert_return (invoke "size1") (i32.const 2))
+(assert_return (invoke "load1" (i32.const 0x10000)) (i32.const 0))
+(assert_trap (invoke "load0" (i32.const 0x10000)) "out of bounds memory access")
+(assert_return (invoke "grow1" (i32.const 1)) (i32.const 2))
+(assert_return (invoke "grow1" (i32.const 1)) (i32.const -1))
+(assert_return (invoke "size0") (i32.const 1))
+
+;; copies between the memories
+(invoke "copy_1_to_0" (i32.const 100) (i32.const 8) (i32.const 4))
+(assert_return (invoke "load0" (i32.const 100)) (i32.const 0x04030201))
+(invoke "copy_0_to_1" (i32.const 0x20000) (i32.const 100) (i32.const 2))
+(assert_return (invoke "load1" (i32.const 0x20000)) (i32.const 0x0201))
+(assert_trap (invoke "copy_1_to_0" (i32.const 0) (i32.const 0x2fffe) (i32.const 4)) "out of bounds memory access")
+(assert_trap (invoke "copy_0_to_1" (i32.const 0) (i32.const 0xfffe) (i32.const 4)) "out of bounds memory access")
+(assert_return (invoke "load0" (i32.const 0)) (i32.const 0))
+
+(invoke "fill1" (i32.const 200) (i32.const 0xff) (i32.const 2))
+(assert_return (invoke "load1" (i32.const 200)) (i32.const 0xffff))
+(assert_return (invoke "load0" (i32.const 200)) (i32.const 0))
+(invoke "init1" (i32.const 300))
+(assert_return (invoke "load8_1" (i32.const 301)) (i32.const 0xbb))
+(assert_return (invoke "load0" (i32.const 300)) (i32.const 0))
+
+(assert_invalid
+  (module (memory 1) (func (drop (i32.load 1 (i32.const 0)))))
+  "unknown memory")
+(assert_invalid
+  (module (memory 1) (func (memory.copy 0 1 (i32.const 0) (i32.const 0) (i32.const 0))))
+  "unknown memory")
@@ -0,0 +1,104 @@
+;; Fixed-width SIMD: lane operations, shuffles, saturating arithmetic, conversions and v128 loads and stores
+
+(module
+  (func (export "add") (param v128 v128) (result v128) (i32x4.add (local.get 0) (local.get 1)))
+  (func (export "satu") (param v128 v128) (result v128) (i8x16.add_sat_u (local.get 0) (local.get 1)))
+  (func (export "sats") (param v128 v128) (result v128) (i16x8.sub_sat_s (local.get 0) (local.get 1)))
+  (func (export "shuf") (param v128 v128) (result v128) (i8x16.shuffle 0 17 2 19 4 21 6 23 8 25 10 27 12 29 14 31 (local.get 0) (local.get 1)))
+  (func (export "swz") (param v128 v128) (result v128) (i8x16.swizzle (local.get 0) (local.get 1)))
+  (func (export "shl") (param v128 i32) (result v128) (i32x4.shl (local.get 0) (local.get 1)))
+  (func (export "shrs") (param v128 i32) (result v128) (i8x16.shr_s (local.get 0) (local.get 1)))
+  (func (export "fmin") (param v128 v128) (result v128) (f32x4.min (local.get 0) (local.get 1)))
+  (func (export "pmin") (param v128 v128) (result v128) (f32x4.pmin (local.get 0) (local.get 1)))
+  (func (export "trunc") (param v128) (result v128) (i32x4.trunc_sat_f32x4_s (local.get 0)))
+  (func (export "truncu") (param v128) (result v128) (i32x4.trunc_sat_f64x2_u_zero (local.get 0)))
+  (func (export "narrow") (param v128 v128) (result v128) (i8x16.narrow_i16x8_u (local.get 0) (local.get 1)))
+  (func (export "extmul") (param v128 v128) (result v128) (i32x4.extmul_high_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "q15") (param v128 v128) (result v128) (i16x8.q15mulr_sat_s (local.get 0) (local.get 1)))
+  (func (export "dot") (param v128 v128) (result v128) (i32x4.dot_i16x8_s (local.get 0) (local.get 1)))
+  (func (export "popcnt") (param v128) (result v128) (i8x16.popcnt (local.get 0)))
+  (func (export "avgr") (param v128 v128) (result v128) (i8x16.avgr_u (local.get 0) (local.get 1)))
+  (func (export "bitmask") (param v128) (result i32) (i16x8.bitmask (local.get 0)))
+  (func (export "alltrue") (param v128) (result i32) (i64x2.all_true (local.get 0)))
+  (func (export "extract") (param v128) (result i32) (i8x16.extract_lane_s 15 (local.get 0)))
+  (func (export "replace") (param v128 f64) (result v128) (f64x2.replace_lane 1 (local.get 0) (local.get 1)))
+  (func (export "nearest") (param v128) (result v128) (f32x4.nearest (local.get 0)))
+  (func (export "convert") (param v128) (result v128) (f64x2.convert_low_i32x4_u (local.get 0)))
+  (func (export "demote") (param v128) (result v128) (f32x4.demote_f64x2_zero (local.get 0)))
+  (func (export "i64gt") (param v128 v128) (result v128) (i64x2.gt_s (local.get 0) (local.get 1)))
+  (func (export "abs64") (param v128) (result v128) (i64x2.abs (local.get 0)))
+  (func (export "bitsel") (param v128 v128 v128) (result v128) (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "extadd") (param v128) (result v128) (i16x8.extadd_pairwise_i8x16_s (local.get 0)))
+  (memory 1)
+  (data (i32.const 0) "\01\02\03\04\05\06\07\08\09\0a\0b\0c\0d\0e\0f\10\ff\fe")
+  (func (export "load8x8s") (result v128) (v128.load8x8_s offset=10 (i32.const 6)))
+  (func (export "loadlane") (param v128) (result v128) (v128.load16_lane 3 (i32.const 15) (local.get 0)))
+  (func (export "load32zero") (result v128) (v128.load32_zero (i32.const 14)))
+  (func (export "oob") (result v128) (v128.load (i32.const 65521)))
+  (func (export "storelane") (param v128) (result i64) (v128.store64_lane 1 (i32.const 100) (local.get 0)) (i64.load (i32.const 100)))
+  (global $g (mut v128) (v128.const i32x4 1 2 3 4))
+  (func (export "g") (result v128) (global.set $g (i32x4.mul (global.get $g) (global.get $g))) (global.get $g))
+  (func (export "loc") (result v128) (local v128) (local.get 0))
+)
+(assert_return (invoke "add" (v128.const i32x4 1 2 3 0xffffffff) (v128.const i32x4 1 2 3 1)) (v128.const i32x4 2 4 6 0))
+(assert_return (invoke "satu" (v128.const i8x16 250 1 0 0 0 0 0 0 0 0 0 0 0 0 0 255) (v128.const i8x16 10 1 0 0 0 0 0 0 0 0 0 0 0 0 0 1)) (v128.const i8x16 255 2 0 0 0 0 0 0 0 0 0 0 0 0 0 255))
+(assert_return (invoke "sats" (v128.const i16x8 -32768 32767 0 0 0 0 0 0) (v128.const i16x8 1 -1 0 0 0 0 0 0)) (v128.const i16x8 -32768 32767 0 0 0 0 0 0))
+(assert_return (invoke "shuf" (v128.const i8x16 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15) (v128.const i8x16 16 17 18 19 20 21 22 23 24 25 26 27 28 29 30 31)) (v128.const i8x16 0 17 2 19 4 21 6 23 8 25 10 27 12 29 14 31))
+(assert_return (invoke "swz" (v128.const i8x16 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25) (v128.const i8x16 15 0 16 255 1 2 3 4 5 6 7 8 9 10 11 12)) (v128.const i8x16 25 10 0 0 11 12 13 14 15 16 17 18 19 20 21 22))
+(assert_return (invoke "shl" (v128.const i32x4 1 1 1 1) (i32.const 33)) (v128.const i32x4 2 2 2 2))
+(assert_return (invoke "shrs" (v128.const i8x16 -128 64 0 0 0 0 0 0 0 0 0 0 0 0 0 0) (i32.const 9)) (v128.const i8x16 -64 32 0 0 0 0 0 0 0 0 0 0 0 0 0 0))
+(assert_return (invoke "fmin" (v128.const f32x4 0 -0 nan 1) (v128.const f32x4 -0 0 1 nan)) (v128.const f32x4 -0 -0 nan:canonical nan:canonical))
+(assert_return (invoke "pmin" (v128.const f32x4 0 -0 nan 1) (v128.const f32x4 -0 0 1 nan)) (v128.const f32x4 0 -0 nan 1))
+(assert_return (invoke "trunc" (v128.const f32x4 nan 3e9 -3e9 -1.7)) (v128.const i32x4 0 2147483647 -2147483648 -1))
+(assert_return (invoke "truncu" (v128.const f64x2 -1 5e9)) (v128.const i32x4 0 4294967295 0 0))
+(assert_return (invoke "narrow" (v128.const i16x8 -1 256 255 3 0 0 0 0) (v128.const i16x8 0 0 0 0 0 0 0 300)) (v128.const i8x16 0 255 255 3 0 0 0 0 0 0 0 0 0 0 0 255))
+(assert_return (invoke "extmul" (v128.const i16x8 0 0 0 0 -2 3 32767 -32768) (v128.const i16x8 0 0 0 0 5 6 32767 -32768)) (v128.const i32x4 -10 18 1073676289 1073741824))
+(assert_return (invoke "q15" (v128.const i16x8 -32768 16384 0 0 0 0 0 0) (v128.const i16x8 -32768 16384 0 0 0 0 0 0)) (v128.const i16x8 32767 8192 0 0 0 0 0 0))
+(assert_return (invoke "dot" (v128.const i16x8 -32768 -32768 1 2 0 0 0 0) (v128.const i16x8 -32768 -32768 3 4 0 0 0 0)) (v128.const i32x4 -2147483648 11 0 0))
+(assert_return (invoke "popcnt" (v128.const i8x16 255 1 3 0 0 0 0 0 0 0 0 0 0 0 0 0)) (v128.const i8x16 8 1 2 0 0 0 0 0 0 0 0 0 0 0 0 0))
+(assert_return (invoke "avgr" (v128.const i8x16 255 0 1 0 0 0 0 0 0 0 0 0 0 0 0 0) (v128.const i8x16 255 1 2 0 0 0 0 0 0 0 0 0 0 0 0 0)) (v128.const i8x16 255 1 2 0 0 0 0 0 0 0 0 0 0 0 0 0))
+(assert_return (invoke "bitmask" (v128.const i16x8 -1 0 -1 0 0 0 0 -5)) (i32.const 133))
+(assert_return (invoke "alltrue" (v128.const i64x2 1 0x100000000)) (i32.const 1))
+(assert_return (invoke "extract" (v128.const i8x16 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 -3)) (i32.const -3))
+(assert_return (invoke "replace" (v128.const f64x2 1 2) (f64.const 9)) (v128.const f64x2 1 9))
+(assert_return (invoke "nearest" (v128.const f32x4 0.5 1.5 2.5 -0.5)) (v128.const f32x4 0 2 2 -0))
+(assert_return (invoke "convert" (v128.const i32x4 -1 2 0 0)) (v128.const f64x2 4294967295 2))
+(assert_return (invoke "demote" (v128.const f64x2 1.5 1e300)) (v128.const f32x4 1.5 inf 0 0))
+(assert_return (invoke "i64gt" (v128.const i64x2 -1 5) (v128.const i64x2 1 4)) (v128.const i64x2 0 -1))
+(assert_return (invoke "abs64" (v128.const i64x2 -9223372036854775808 -5)) (v128.const i64x2 -9223372036854775808 5))
+(assert_return (invoke "bitsel" (v128.const i32x4 0xffffffff 0 0 0) (v128.const i32x4 0 0xffffffff 0 0) (v128.const i32x4 0xffff0000 0 0 0)) (v128.const i32x4 0xffff0000 0xffffffff 0 0))
+(assert_return (invoke "extadd" (v128.const i8x16 -128 -128 127 127 1 2 0 0 0 0 0 0 0 0 0 0)) (v128.const i16x8 -256 254 3 0 0 0 0 0))
+(assert_return (invoke "load8x8s") (v128.const i16x8 -1 -2 0 0 0 0 0 0))
+(assert_return (invoke "loadlane" (v128.const i16x8 0 0 0 0 0 0 0 0)) (v128.const i16x8 0 0 0 0xff10 0 0 0 0))
+(assert_return (invoke "load32zero") (v128.const i32x4 0xfeff100f 0 0 0))
+(assert_trap (invoke "oob") "out of bounds memory access")
+(assert_return (invoke "storelane" (v128.const i64x2 1 0x123456789)) (i64.const 0x123456789))
+(assert_return (invoke "g") (v128.const i32x4 1 4 9 16))
+(assert_return (invoke "g") (v128.const i32x4 1 16 81 256))
+(assert_return (invoke "loc") (v128.const i64x2 0 0))
+
+;; splats and full-width stores
+(module
+  (memory 1)
+  (func (export "splat") (param i32) (result v128) (i16x8.splat (local.get 0)))
+  (func (export "load_splat") (result v128)
+    (i32.store (i32.const 8) (i32.const 0xdeadbeef))
+    (v128.load32_splat (i32.const 8)))
+  (func (export "store") (param v128) (result i64)
+    (v128.store offset=16 (i32.const 0) (local.get 0))
+    (i64.load (i32.const 24)))
+  (func (export "store_oob") (param v128) (v128.store (i32.const 65530) (local.get 0)))
+)
+(assert_return (invoke "splat" (i32.const 0x12345)) (v128.const i16x8 0x2345 0x2345 0x2345 0x2345 0x2345 0x2345 0x2345 0x2345))
+(assert_return (invoke "load_splat") (v128.const i32x4 0xdeadbeef 0xdeadbeef 0xdeadbeef 0xdeadbeef))
+(assert_return (invoke "store" (v128.const i64x2 1 -2)) (i64.const -2))
+(assert_trap (invoke "store_oob" (v128.const i64x2 0 0)) "out of bounds memory access")
+
+;; lane indices are immediates checked by validation
+(assert_invalid
+  (module (func (result i32) (i32x4.extract_lane 4 (v128.const i64x2 0 0))))
+  "invalid lane index")
+(assert_invalid
+  (module (func (result v128)
+    (i8x16.shuffle 0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 32 (v128.const i64x2 0 0) (v128.const i64x2 0 0))))
+  "invalid lane index")
@@ -0,0 +1,80 @@
+;; Tail calls: return_call and return_call_indirect reuse the caller's frame
+
+(module
+  (type $binop (func (param i64 i64) (result i64)))
+  (type $unop (func (param i32) (result i32)))
+  (table $t 3 funcref)
+  (elem (table $t) (i32.const 0) func $sum_indirect $even_i)
+
+  (func $sum (export "sum") (param i64 i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else (return_call $sum (i64.sub (local.get 0) (i64.const 1)) (i64.add (local.get 1) (local.get 0))))))
+  (func $sum_indirect (export "sum_indirect") (param i64 i64) (result i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else (return_call_indirect $t (type $binop)
+        (i64.sub (local.get 0) (i64.const 1)) (i64.add (local.get 1) (local.get 0)) (i32.const 0)))))
+
+  ;; mutual recursion a million frames deep
+  (func $even (export "even") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else (return_call $odd (i32.sub (local.get 0) (i32.const 1))))))
+  (func $odd (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else (return_call $even (i32.sub (local.get 0) (i32.const 1))))))
+  (func $even_i (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else (return_call $odd_i (i32.sub (local.get 0) (i32.const 1))))))
+  (func $odd_i (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else (return_call_indirect $t (type $unop) (i32.sub (local.get 0) (i32.const 1)) (i32.const 1)))))
+  (func (export "even_indirect") (param i32) (result i32) (call $even_i (local.get 0)))
+
+  ;; the same recursion without tail calls exhausts the stack
+  (func $even_call (export "even_call") (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 1))
+      (else (call $odd_call (i32.sub (local.get 0) (i32.const 1))))))
+  (func $odd_call (param i32) (result i32)
+    (if (result i32) (i32.eqz (local.get 0))
+      (then (i32.const 0))
+      (else (call $even_call (i32.sub (local.get 0) (i32.const 1))))))
+
+  ;; the callee's type is checked at runtime
+  (func (export "wrong_type") (result i32)
+    (return_call_indirect $t (type $unop) (i32.const 0) (i32.const 0)))
+  (func (export "null_entry") (result i32)
+    (return_call_indirect $t (type $unop) (i32.const 0) (i32.const 2)))
+  (func (export "out_of_bounds") (result i32)
+    (return_call_indirect $t (type $unop) (i32.const 0) (i32.const 3)))
+
+  (func $pair (param i32) (result i32 i32) (local.get 0) (i32.const 7))
+  (func (export "multi_value") (param i32) (result i32 i32) (return_call $pair (local.get 0)))
+)
+(assert_return (invoke "sum" (i64.const 1000000) (i64.const 0)) (i64.const 500000500000))
+(assert_return (invoke "sum_indirect" (i64.const 1000000) (i64.const 0)) (i64.const 500000500000))
+(assert_return (invoke "even" (i32.const 1000001)) (i32.const 0))
+(assert_return (invoke "even_indirect" (i32.const 1000000)) (i32.const 1))
+(assert_exhaustion (invoke "even_call" (i32.const 1000000)) "call stack exhausted")
+(assert_return (invoke "multi_value" (i32.const 3)) (i32.const 3) (i32.const 7))
+(assert_trap (invoke "wrong_type") "indirect call type mismatch")
+(assert_trap (invoke "null_entry") "uninitialized element")
+(assert_trap (invoke "out_of_bounds") "undefined element")
+
+;; tail calls need the caller to return the callee's results
+(assert_invalid
+  (module
+    (func $f (result i64) (i64.const 0))
+    (func (result i32) (return_call $f)))
+  "type mismatch")
+(assert_invalid
+  (module
+    (type $t (func (result i64)))
+    (table 1 funcref)
+    (func (result i32) (return_call_indirect (type $t) (i32.const 0))))
+  "type mismatch")
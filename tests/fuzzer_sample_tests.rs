@@ -25,7 +25,7 @@ fn test_fuzzer_samples() {
         }
         let module = module.unwrap();
 
-        if let Err(e) = parser::FunctionLoader.parse_all_functions(&module) {
+        if let Err(e) = parser::FunctionLoader::default().parse_all_functions(&module) {
             panic!("Failed to parse IR functions {file_path:?}: {e}")
         }
 
@@ -33,7 +33,7 @@ fn test_fuzzer_samples() {
             panic!("Failed to translate module meta in {file_path:?}: {e}")
         }
 
-        if let Err(e) = llvm_gen::FunctionLoader.parse_all_functions(&module) {
+        if let Err(e) = llvm_gen::FunctionLoader::default().parse_all_functions(&module) {
             panic!("Failed to parse llvm functions from {file_path:?}: {e}")
         }
     });
@@ -19,7 +19,7 @@ use std::{collections::HashMap, path::PathBuf, rc::Rc};
 use test_log::test;
 use wasm_types::ValType;
 use wast::{
-    core::{NanPattern, WastArgCore, WastRetCore},
+    core::{NanPattern, V128Pattern, WastArgCore, WastRetCore},
     QuoteWat, Wast, WastExecute, Wat,
 };
 
@@ -116,7 +116,9 @@ fn execute_via_interpreter_backend(
             &wast::WastArg::Core(WastArgCore::F64(f)) if input_type == ValType::f64() => {
                 input_params.push(Value::f64(f64::from_bits(f.bits)))
             }
-            wast::WastArg::Core(WastArgCore::V128(_)) => unimplemented!(),
+            wast::WastArg::Core(WastArgCore::V128(v)) if input_type == ValType::VecType => {
+                input_params.push(Value::Vector(v.to_le_bytes()))
+            }
             wast::WastArg::Core(WastArgCore::RefExtern(r)) => {
                 input_params.push(Value::externref(r.trans_u64()))
             }
@@ -397,8 +399,25 @@ pub fn test_interpreter(file_path: &str) {
                     )
                 }
             }
-            wast::WastDirective::AssertException { .. } => {
-                unimplemented!("AssertException");
+            wast::WastDirective::AssertException { exec, .. } => {
+                let WastExecute::Invoke(invoke) = exec else {
+                    todo!("{:?}", exec)
+                };
+                match execute_via_interpreter_backend(
+                    file_path,
+                    line,
+                    col,
+                    &invoke,
+                    &mut declared_modules,
+                ) {
+                    Err(RuntimeError::UncaughtException(_)) => {}
+                    Err(e) => panic!(
+                        "{file_path:?}:{line}:{col}\nExpected uncaught exception, Got {e:?}"
+                    ),
+                    Ok(v) => panic!(
+                        "{file_path:?}:{line}:{col}\nExpected uncaught exception, Got success: {v:?}"
+                    ),
+                }
             }
             wast::WastDirective::AssertExhaustion { call, .. } => {
                 match execute_via_interpreter_backend(
@@ -522,7 +541,19 @@ pub fn test_interpreter(file_path: &str) {
                     }
                 }
             }
-            wast::WastRet::Core(WastRetCore::V128(_)) => unimplemented!(),
+            wast::WastRet::Core(WastRetCore::V128(pattern)) => {
+                assert_eq!(actual_type, ValType::VecType, "{prefix}");
+                match actual_result {
+                    Value::Vector(v) => assert!(
+                        v128_matches(pattern, v),
+                        "{}Expected {:?}, Got {:?}",
+                        prefix,
+                        pattern,
+                        v
+                    ),
+                    _ => panic!("{}Expected V128, Got {:?}", prefix, actual_result),
+                }
+            }
             wast::WastRet::Core(WastRetCore::RefExtern(r)) => {
                 assert_eq!(
                     actual_type,
@@ -559,3 +590,39 @@ pub fn test_interpreter(file_path: &str) {
         };
     }
 }
+
+fn v128_matches(pattern: &V128Pattern, actual: &[u8; 16]) -> bool {
+    fn lanes<const N: usize, const B: usize>(actual: &[u8; 16]) -> [[u8; B]; N] {
+        std::array::from_fn(|i| actual[i * B..(i + 1) * B].try_into().unwrap())
+    }
+    fn nan_matches<T: PartialEq>(pattern: &NanPattern<T>, actual: T, is_nan: bool) -> bool {
+        match pattern {
+            NanPattern::CanonicalNan | NanPattern::ArithmeticNan => is_nan,
+            NanPattern::Value(v) => *v == actual,
+        }
+    }
+    match pattern {
+        V128Pattern::I8x16(l) => l.map(|l| l as u8) == *actual,
+        V128Pattern::I16x8(l) => l.map(i16::to_le_bytes) == lanes::<8, 2>(actual),
+        V128Pattern::I32x4(l) => l.map(i32::to_le_bytes) == lanes::<4, 4>(actual),
+        V128Pattern::I64x2(l) => l.map(i64::to_le_bytes) == lanes::<2, 8>(actual),
+        V128Pattern::F32x4(l) => l.iter().zip(lanes::<4, 4>(actual)).all(|(p, a)| {
+            let a = u32::from_le_bytes(a);
+            let pattern = match p {
+                NanPattern::Value(v) => NanPattern::Value(v.bits),
+                NanPattern::CanonicalNan => NanPattern::CanonicalNan,
+                NanPattern::ArithmeticNan => NanPattern::ArithmeticNan,
+            };
+            nan_matches(&pattern, a, f32::from_bits(a).is_nan())
+        }),
+        V128Pattern::F64x2(l) => l.iter().zip(lanes::<2, 8>(actual)).all(|(p, a)| {
+            let a = u64::from_le_bytes(a);
+            let pattern = match p {
+                NanPattern::Value(v) => NanPattern::Value(v.bits),
+                NanPattern::CanonicalNan => NanPattern::CanonicalNan,
+                NanPattern::ArithmeticNan => NanPattern::ArithmeticNan,
+            };
+            nan_matches(&pattern, a, f64::from_bits(a).is_nan())
+        }),
+    }
+}
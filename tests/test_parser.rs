@@ -1,8 +1,103 @@
-use module::ModuleError;
+use module::{
+    instructions::FunctionIR,
+    objects::function::FunctionSource,
+    passes::{DecodedFunction, PassManager},
+    Module, ModuleError, ModuleWriter,
+};
 use parser::Parser;
 use runtime_lib::FunctionLoaderInterface;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    num::NonZeroUsize,
+};
 use wast::Wast;
 
+fn ir_of(module: &Module) -> Vec<FunctionIR> {
+    let artifacts = module.artifact_registry.read().unwrap();
+    let ir = artifacts.get("ir").unwrap().read().unwrap();
+    ir.downcast_ref::<Vec<FunctionIR>>().unwrap().clone()
+}
+
+fn ir_dump(module: &Module) -> String {
+    format!("{:?}", ir_of(module))
+}
+
+fn sorted<K: Ord + Debug, V: Debug>(map: &HashMap<K, V>) -> String {
+    format!("{:?}", map.iter().collect::<BTreeMap<_, _>>())
+}
+
+/// Everything the writer has to keep, the offsets into the sources differ between the modules
+fn module_summary(module: &Module) -> Vec<(&'static str, String)> {
+    let meta = &module.meta;
+    let source = module.source.get();
+    let functions = meta
+        .functions
+        .iter()
+        .map(|function| match &function.source {
+            FunctionSource::Import(import) => format!("{} {import:?}", function.type_idx),
+            FunctionSource::Wasm(code) => format!(
+                "{} {:?}",
+                function.type_idx,
+                &source[code.offset..code.offset + code.size]
+            ),
+        })
+        .collect::<Vec<_>>();
+    let custom_sections = meta
+        .custom_sections
+        .iter()
+        .map(|section| {
+            format!(
+                "{} {:?}",
+                section.name,
+                &source[section.offset..section.offset + section.size]
+            )
+        })
+        .collect::<Vec<_>>();
+    let locals = meta
+        .names
+        .locals
+        .iter()
+        .map(|(func_idx, locals)| (func_idx, sorted(locals)))
+        .collect::<BTreeMap<_, _>>();
+    vec![
+        ("types", format!("{:?}", meta.types)),
+        ("function types", format!("{:?}", meta.function_types)),
+        ("imports", format!("{:?}", meta.imports)),
+        ("functions", format!("{functions:?}")),
+        ("tables", format!("{:?}", meta.tables)),
+        ("memories", format!("{:?}", meta.memories)),
+        ("tags", format!("{:?}", meta.tags)),
+        ("globals", format!("{:?}", meta.globals)),
+        ("function exports", sorted(&meta.exports.functions)),
+        ("table exports", sorted(&meta.exports.tables)),
+        ("memory exports", sorted(&meta.exports.memories)),
+        ("global exports", sorted(&meta.exports.globals)),
+        ("tag exports", sorted(&meta.exports.tags)),
+        ("start function", format!("{:?}", meta.entry_point)),
+        ("elements", format!("{:?}", meta.elements)),
+        ("data count", format!("{:?}", meta.datacount)),
+        ("data", format!("{:?}", meta.datas)),
+        ("module name", format!("{:?}", meta.names.module)),
+        ("function names", sorted(&meta.names.functions)),
+        ("local names", format!("{locals:?}")),
+        ("global names", sorted(&meta.names.globals)),
+        ("custom sections", format!("{custom_sections:?}")),
+    ]
+}
+
+fn assert_same_module(original: &Module, written: &Module, location: &str) {
+    for ((part, expected), (_, actual)) in module_summary(original)
+        .into_iter()
+        .zip(module_summary(written))
+    {
+        assert_eq!(
+            expected, actual,
+            "The {part} of the written module of spec test file {location} differ"
+        );
+    }
+}
+
 pub fn test_parser(file_path: &str) {
     let content = std::fs::read_to_string(file_path).unwrap();
     let parse_buf = wast::parser::ParseBuffer::new(&content).unwrap();
@@ -22,15 +117,58 @@ pub fn test_parser(file_path: &str) {
                 let binary_mod = module.encode().unwrap();
                 let module = Parser::parse_from_buf(binary_mod.clone());
                 if module.is_err() {
-                    std::fs::write("test_module_dump.wasm", binary_mod).unwrap();
+                    std::fs::write("test_module_dump.wasm", &binary_mod).unwrap();
                     eprintln!(
                         "Parsing failed of spec test file: {file_path:?}:{line}:{col}\nWriting binary module to ./test_module_dump.wasm"
                     );
                 }
                 let module = module.unwrap();
-                if parser::FunctionLoader.parse_all_functions(&module).is_err() {
+                if parser::FunctionLoader::default()
+                    .parse_all_functions(&module)
+                    .is_err()
+                {
                     eprintln!("Failed to parse IR functions {file_path:?}:{line}:{col}");
+                } else {
+                    // the IR must not depend on the number of parser threads
+                    let sequential = Parser::parse_from_buf(binary_mod.clone()).unwrap();
+                    parser::FunctionLoader::default()
+                        .with_threads(NonZeroUsize::MIN)
+                        .parse_all_functions(&sequential)
+                        .unwrap();
+                    assert_eq!(
+                        ir_dump(&module),
+                        ir_dump(&sequential),
+                        "IR differs between parallel and sequential parsing of spec test file {file_path:?}:{line}:{col}"
+                    );
+                    // the optimization passes work on decoded functions, which must encode to the same IR
+                    let mut ir = ir_of(&module);
+                    for function in ir.iter().filter(|function| !function.bbs.is_empty()) {
+                        let decoded = DecodedFunction::decode(function).unwrap();
+                        assert_eq!(
+                            format!("{function:?}"),
+                            format!("{:?}", decoded.encode()),
+                            "IR changes when decoded and encoded again in spec test file {file_path:?}:{line}:{col}"
+                        );
+                    }
+                    PassManager::default().run_all(&mut ir).unwrap();
                 }
+                let written = ModuleWriter::new(&module).finish().unwrap();
+                let reparsed = match Parser::parse_from_buf(written.clone()) {
+                    Ok(reparsed) => reparsed,
+                    Err(e) => {
+                        std::fs::write("test_module_dump.wasm", written).unwrap();
+                        panic!(
+                            "Parsing the written module of spec test file {file_path:?}:{line}:{col} failed: {e}\nWriting binary module to ./test_module_dump.wasm"
+                        );
+                    }
+                };
+                let location = format!("{file_path:?}:{line}:{col}");
+                assert_same_module(&module, &reparsed, &location);
+                // the written module is canonical, writing it again must not change a byte
+                assert!(
+                    ModuleWriter::new(&reparsed).finish().unwrap() == written,
+                    "Writing the written module of spec test file {location} again changes it"
+                );
             }
             wast::WastDirective::AssertMalformed {
                 span: _,
@@ -40,7 +178,7 @@ pub fn test_parser(file_path: &str) {
                 let binary_mod = module.encode().unwrap();
                 let module = Parser::parse_from_buf(binary_mod.clone())
                     .map_err(|e| ModuleError::Msg(e.to_string()))
-                    .and_then(|m| parser::FunctionLoader.parse_all_functions(&m));
+                    .and_then(|m| parser::FunctionLoader::default().parse_all_functions(&m));
                 if module.is_ok() {
                     std::fs::write("test_module_dump.wasm", binary_mod).unwrap();
                     panic!(
@@ -52,3 +190,24 @@ pub fn test_parser(file_path: &str) {
         }
     }
 }
+
+#[test]
+fn validate_reports_the_location_of_errors() {
+    // `table.size 0` at offset 0x17 of a module without tables
+    let wasm = b"\0asm\x01\0\0\0\x01\x04\x01\x60\x00\x00\x03\x02\x01\x00\x0a\x08\x01\x06\x00\xfc\x10\x00\x1a\x0b";
+    let path = std::env::temp_dir().join(format!("wasm_rt_validate_{}.wasm", std::process::id()));
+    std::fs::write(&path, wasm).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_wasm_rt"))
+        .arg("validate")
+        .arg(&path)
+        .output()
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("unknown table 0 (Code section, function 0, offset 0x17)"),
+        "unexpected output: {stderr}"
+    );
+}